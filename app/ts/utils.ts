@@ -250,6 +250,59 @@ export class LogUtils {
   }
 }
 
+// Event decoding utilities
+export class EventUtils {
+  /**
+   * Prefix of the instruction data of an Anchor event self-CPI (emit_cpi!), sha256("anchor:event")[..8]
+   */
+  static readonly EVENT_IX_TAG = Buffer.from("e445a52e51cb9a1d", "hex");
+
+  /**
+   * Decode the hook program's events from a fetched transaction. Governance events are emitted through
+   * a self-CPI and read from the inner instructions, so they survive truncated logs; the transfer hook's
+   * own events are plain log entries. Inner instruction events come first, each group in program order.
+   */
+  static decodeTransactionEvents(
+    transaction: anchor.web3.VersionedTransactionResponse,
+    programId: PublicKey,
+    coder: anchor.BorshCoder
+  ): anchor.Event[] {
+    const events: anchor.Event[] = [];
+    const meta = transaction.meta;
+    if (!meta) {
+      return events;
+    }
+
+    const message = transaction.transaction.message;
+    const accountKeys = [
+      ...message.staticAccountKeys,
+      ...(meta.loadedAddresses?.writable ?? []),
+      ...(meta.loadedAddresses?.readonly ?? []),
+    ];
+    for (const inner of meta.innerInstructions ?? []) {
+      for (const instruction of inner.instructions) {
+        if (!accountKeys[instruction.programIdIndex]?.equals(programId)) {
+          continue;
+        }
+        const data = Buffer.from(bs58.decode(instruction.data));
+        if (data.length < 8 || !data.subarray(0, 8).equals(this.EVENT_IX_TAG)) {
+          continue;
+        }
+        const event = coder.events.decode(data.subarray(8).toString("base64"));
+        if (event) {
+          events.push(event);
+        }
+      }
+    }
+
+    const parser = new anchor.EventParser(programId, coder);
+    for (const event of parser.parseLogs(meta.logMessages ?? [])) {
+      events.push(event);
+    }
+    return events;
+  }
+}
+
 // Rate limiting utilities
 export class RateLimitUtils {
   private static lastCall = 0;
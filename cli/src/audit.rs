@@ -0,0 +1,212 @@
+// Finding holders over the cap that the hook never saw arrive.
+//
+// The hook caps each destination token account at transfer time, so balances from before the hook, from
+// `mint_to` (which skips hooks) or spread over several token accounts of one owner can all exceed the
+// cap. The audit sums every token account of the mint per owner and reports each owner whose total is
+// over the config's wallet cap, in the cap's own units. Owners the hook flagged for buying in the snipe
+// window are listed after them, as the mint's sniper log has them.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use one_kx_hook::{logic, CapUnit, ExemptRegistry, HookConfig, SniperLog};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{Result, TokenHolding};
+
+/// Why an owner over the cap may be allowed to hold it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exemption {
+    None,
+    /// A dev wallet whose exemption hasn't sunset
+    DevWallet,
+    /// Every one of the owner's token accounts is in the exempt registry
+    Registry,
+    /// Some of the owner's token accounts are in the exempt registry
+    PartialRegistry,
+    /// Every one of the owner's token accounts is a registered bridge custody account: supply bridged to
+    /// other chains rather than held
+    BridgeCustody,
+}
+
+impl Exemption {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::DevWallet => "dev-wallet",
+            Self::Registry => "registry",
+            Self::PartialRegistry => "partial-registry",
+            Self::BridgeCustody => "bridge-custody",
+        }
+    }
+}
+
+/// An owner whose token accounts together hold more than the cap
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub owner: Pubkey,
+    /// Summed over the owner's token accounts, in base units
+    pub balance: u64,
+    /// `balance` in the units the cap counts
+    pub cap_balance: u64,
+    pub token_accounts: usize,
+    pub exemption: Exemption,
+    /// Every token account is within the cap on its own: only the sum exceeds it
+    pub split: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Audit {
+    pub mint: Pubkey,
+    pub cap: u64,
+    pub cap_unit: CapUnit,
+    /// Owners with a non-zero balance
+    pub holders: usize,
+    /// Largest balance first
+    pub violations: Vec<Violation>,
+    /// Owners in the sniper log, in the order they were flagged
+    pub snipers: Vec<Pubkey>,
+}
+
+/// Audit `holdings`, every token account of `mint`, against `config` as of `now`
+pub fn audit(
+    mint: &Pubkey,
+    config: &HookConfig,
+    registry: Option<&ExemptRegistry>,
+    sniper_log: Option<&SniperLog>,
+    mint_data: &[u8],
+    holdings: &[TokenHolding],
+    now: i64,
+) -> Result<Audit> {
+    let mut owners: BTreeMap<Pubkey, Vec<&TokenHolding>> = BTreeMap::new();
+    for holding in holdings.iter().filter(|holding| holding.amount > 0) {
+        owners.entry(holding.owner).or_default().push(holding);
+    }
+
+    let cap = config.wallet_cap_raw;
+    let in_cap_units = |raw: u64| logic::balance_in_cap_units(config.cap_unit, mint_data, raw, now);
+    let mut violations = Vec::new();
+    for (owner, accounts) in &owners {
+        // Token-2022 supply fits a u64, so no owner's sum can overflow one
+        let balance = accounts.iter().map(|account| account.amount).sum();
+        let cap_balance = in_cap_units(balance)?;
+        if cap_balance <= cap {
+            continue;
+        }
+        let mut split = true;
+        for account in accounts {
+            split &= in_cap_units(account.amount)? <= cap;
+        }
+        let registered = accounts
+            .iter()
+            .filter(|account| registry.is_some_and(|registry| registry.is_exempt(&account.address, now)))
+            .count();
+        let bridged = accounts
+            .iter()
+            .filter(|account| registry.is_some_and(|registry| registry.is_bridge_custody(&account.address, now)))
+            .count();
+        let exemption = if logic::is_exempt(config, owner, now) {
+            Exemption::DevWallet
+        } else if bridged == accounts.len() {
+            Exemption::BridgeCustody
+        } else if registered == accounts.len() {
+            Exemption::Registry
+        } else if registered > 0 {
+            Exemption::PartialRegistry
+        } else {
+            Exemption::None
+        };
+        violations.push(Violation {
+            owner: *owner,
+            balance,
+            cap_balance,
+            token_accounts: accounts.len(),
+            exemption,
+            split,
+        });
+    }
+    violations.sort_by(|a, b| b.cap_balance.cmp(&a.cap_balance).then(a.owner.cmp(&b.owner)));
+
+    let snipers = sniper_log.map(|log| log.sniper_list.clone()).unwrap_or_default();
+    Ok(Audit { mint: *mint, cap, cap_unit: config.cap_unit, holders: owners.len(), violations, snipers })
+}
+
+impl Audit {
+    pub fn write_table(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "Mint {}: wallet cap {} ({:?}), {} holders, {} over the cap",
+            self.mint,
+            self.cap,
+            self.cap_unit,
+            self.holders,
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            let split = if violation.split { ", each account under the cap" } else { "" };
+            writeln!(
+                out,
+                "  {}: {} in {} token account(s), exemption {}{}",
+                violation.owner,
+                violation.cap_balance,
+                violation.token_accounts,
+                violation.exemption.label(),
+                split
+            )?;
+        }
+        if !self.snipers.is_empty() {
+            writeln!(out, "{} flagged as snipers", self.snipers.len())?;
+            for sniper in &self.snipers {
+                writeln!(out, "  {}", sniper)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, out: &mut dyn Write) -> io::Result<()> {
+        // Keys, numbers and fixed labels only, so nothing needs escaping
+        let violations: Vec<_> = self
+            .violations
+            .iter()
+            .map(|violation| {
+                format!(
+                    r#"{{"owner":"{}","balance":{},"cap_balance":{},"token_accounts":{},"exemption":"{}","split":{}}}"#,
+                    violation.owner,
+                    violation.balance,
+                    violation.cap_balance,
+                    violation.token_accounts,
+                    violation.exemption.label(),
+                    violation.split
+                )
+            })
+            .collect();
+        let snipers: Vec<_> = self.snipers.iter().map(|sniper| format!(r#""{}""#, sniper)).collect();
+        writeln!(
+            out,
+            r#"{{"mint":"{}","cap":{},"cap_unit":"{:?}","holders":{},"violations":[{}],"snipers":[{}]}}"#,
+            self.mint,
+            self.cap,
+            self.cap_unit,
+            self.holders,
+            violations.join(","),
+            snipers.join(",")
+        )
+    }
+
+    pub fn write_csv(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "owner,balance,cap_balance,token_accounts,exemption,split")?;
+        for violation in &self.violations {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                violation.owner,
+                violation.balance,
+                violation.cap_balance,
+                violation.token_accounts,
+                violation.exemption.label(),
+                violation.split
+            )?;
+        }
+        Ok(())
+    }
+}
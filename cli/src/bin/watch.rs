@@ -0,0 +1,195 @@
+// The proposal watcher: follows a mint's proposals over a websocket log subscription, alerting a webhook
+// as they are created, canceled and executed, and polls the config between notifications to catch up and
+// to execute matured proposals.
+
+use std::path::PathBuf;
+use std::process::exit;
+use std::str::FromStr;
+use std::thread::sleep;
+use std::time::Duration;
+
+use clap::Parser;
+use onekx_hook_cli::{rpc_url, HttpWebhook, Result, RpcCluster, Watcher};
+use onekx_hook_client::events::{parse_event_cpi, parse_logs, HookEvent};
+use solana_cli_config::{Config, CONFIG_FILE};
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_client::RpcClient,
+    rpc_config::{RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
+};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiInstruction, UiTransactionEncoding};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Times to look a notified transaction up before giving it to the next poll
+const FETCH_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Parser)]
+#[command(name = "onekx-hook-watch", version, about = "Alert on a 1000x mint's cap proposals and execute them")]
+struct Args {
+    /// The hooked mint
+    #[arg(long)]
+    mint: Pubkey,
+
+    /// RPC URL or moniker (mainnet-beta, devnet, testnet, localhost); defaults to the Solana CLI config's
+    #[arg(long, short = 'u')]
+    url: Option<String>,
+
+    /// Websocket URL to subscribe at; derived from the RPC URL by default
+    #[arg(long)]
+    ws_url: Option<String>,
+
+    /// Alerts are POSTed here as JSON
+    #[arg(long)]
+    webhook: String,
+
+    /// Where sent alerts are remembered, so a restart doesn't send them again
+    #[arg(long, default_value = "onekx-hook-watch.state")]
+    state_file: PathBuf,
+
+    /// Execute matured proposals, signed and paid for by this keypair
+    #[arg(long)]
+    execute_keypair: Option<PathBuf>,
+
+    /// Seconds between config polls while no notification arrives
+    #[arg(long, default_value_t = 30)]
+    poll_seconds: u64,
+}
+
+/// The hook events of a confirmed transaction: `emit!` events from its log and `emit_cpi!` events from
+/// its inner instructions to the program
+fn transaction_events(rpc: &RpcClient, signature: &Signature) -> Result<Vec<HookEvent>> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    // A notification can arrive before the node serves the transaction
+    let mut attempt = 1;
+    let transaction = loop {
+        match rpc.get_transaction_with_config(signature, config) {
+            Ok(transaction) => break transaction.transaction,
+            Err(_) if attempt < FETCH_ATTEMPTS => {
+                attempt += 1;
+                sleep(INITIAL_BACKOFF);
+            }
+            Err(error) => return Err(error.into()),
+        }
+    };
+    let meta = transaction.meta.ok_or("the transaction has no status meta")?;
+    let message = transaction.transaction.decode().ok_or("the transaction doesn't decode")?.message;
+
+    // Inner instructions index the static keys, then the writable and readonly keys of lookup tables
+    let mut keys = message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            keys.push(Pubkey::from_str(address)?);
+        }
+    }
+    let mut events = match &meta.log_messages {
+        OptionSerializer::Some(logs) => parse_logs(logs),
+        _ => Vec::new(),
+    };
+    if let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions {
+        for instruction in inner_instructions.iter().flat_map(|inner| &inner.instructions) {
+            let UiInstruction::Compiled(instruction) = instruction else { continue };
+            if keys.get(usize::from(instruction.program_id_index)) == Some(&onekx_hook_client::ID) {
+                events.extend(parse_event_cpi(&bs58::decode(&instruction.data).into_vec()?));
+            }
+        }
+    }
+    Ok(events)
+}
+
+struct Daemon {
+    watcher: Watcher,
+    webhook: HttpWebhook,
+    cluster: RpcCluster,
+    rpc: RpcClient,
+    executor: Option<Keypair>,
+}
+
+impl Daemon {
+    fn poll(&mut self) {
+        let executor = self.executor.as_ref().map(|executor| executor as &dyn Signer);
+        match self.watcher.poll(&mut self.cluster, executor, &mut self.webhook) {
+            Ok(Some(signature)) => println!("Executed the matured proposal: {}", signature),
+            Ok(None) => {}
+            Err(error) => eprintln!("Polling the config failed: {}", error),
+        }
+    }
+
+    fn notified(&mut self, signature: &str) {
+        let events = Signature::from_str(signature)
+            .map_err(Into::into)
+            .and_then(|signature| transaction_events(&self.rpc, &signature));
+        match events.and_then(|events| self.watcher.observe(&events, &mut self.webhook)) {
+            Ok(alerts) => alerts.iter().for_each(|alert| println!("Alerted {} {}", alert.name(), alert.nonce())),
+            Err(error) => eprintln!("Handling {} failed: {}", signature, error),
+        }
+    }
+}
+
+fn watch(args: Args) -> Result<()> {
+    let config = CONFIG_FILE.as_ref().and_then(|path| Config::load(path).ok()).unwrap_or_default();
+    let url = rpc_url(args.url.as_deref().unwrap_or(&config.json_rpc_url));
+    let ws_url = args.ws_url.unwrap_or_else(|| Config::compute_websocket_url(&url));
+    let executor = match &args.execute_keypair {
+        Some(path) => Some(read_keypair_file(path).map_err(|error| format!("reading {}: {}", path.display(), error))?),
+        None => None,
+    };
+    let mut daemon = Daemon {
+        watcher: Watcher::open(args.mint, &args.state_file)?,
+        webhook: HttpWebhook::new(args.webhook),
+        cluster: RpcCluster::new(url.clone()),
+        rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+        executor,
+    };
+    let poll_interval = Duration::from_secs(args.poll_seconds);
+
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        // Every instruction that proposes, cancels, executes or sweeps a proposal writes the governance
+        // log, which transfers never touch
+        let governance_log = onekx_hook_client::governance_log_pda(&args.mint);
+        let filter = RpcTransactionLogsFilter::Mentions(vec![governance_log.to_string()]);
+        let logs_config = RpcTransactionLogsConfig { commitment: Some(CommitmentConfig::confirmed()) };
+        let (mut subscription, notifications) = match PubsubClient::logs_subscribe(&ws_url, filter, logs_config) {
+            Ok(subscribed) => subscribed,
+            Err(error) => {
+                eprintln!("Subscribing at {} failed, retrying in {:?}: {}", ws_url, backoff, error);
+                sleep(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                continue;
+            }
+        };
+        println!("Watching {} at {}", args.mint, ws_url);
+        backoff = INITIAL_BACKOFF;
+
+        // Catch up on whatever happened while disconnected
+        daemon.poll();
+        loop {
+            match notifications.recv_timeout(poll_interval) {
+                Ok(notification) if notification.value.err.is_none() => daemon.notified(&notification.value.signature),
+                Ok(_) => {}
+                Err(error) if error.is_timeout() => daemon.poll(),
+                Err(_) => break,
+            }
+        }
+        eprintln!("The subscription closed, reconnecting");
+        // The socket is already gone, so there is nothing to unsubscribe from cleanly
+        let _ = subscription.shutdown();
+    }
+}
+
+fn main() {
+    if let Err(error) = watch(Args::parse()) {
+        eprintln!("Error: {}", error);
+        exit(1);
+    }
+}
@@ -0,0 +1,149 @@
+// What the commands need of a cluster, and the RPC node the binary talks to.
+
+use std::str::FromStr;
+
+use serde_json::json;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::RpcRequest,
+    rpc_response::{OptionalContext, RpcKeyedAccount},
+};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+    sysvar,
+    transaction::Transaction,
+};
+use spl_token_2022::{extension::AccountType, state::Account as TokenAccount};
+
+use crate::Result;
+
+/// Where a token account's owner and amount sit in its data, right after the mint
+const OWNER_OFFSET: usize = 32;
+const AMOUNT_OFFSET: usize = 64;
+
+/// A token account of a mint, as far as the audit reads it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenHolding {
+    pub address: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+/// Every token account of a mint, as one read of the cluster saw them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenAccounts {
+    /// The slot the accounts were read at; of a read in several requests, the latest any request saw
+    pub slot: u64,
+    pub holdings: Vec<TokenHolding>,
+}
+
+pub trait Cluster {
+    /// The account at `address`, or None if there is none
+    fn account(&mut self, address: &Pubkey) -> Result<Option<Account>>;
+
+    fn clock(&mut self) -> Result<Clock>;
+
+    /// Send `instructions` in one transaction signed and paid for by `signer`, once it has landed
+    fn send(&mut self, instructions: &[Instruction], signer: &dyn Signer) -> Result<Signature>;
+
+    /// Every Token-2022 account of `mint`, read at `min_context_slot` or later
+    fn token_accounts(&mut self, mint: &Pubkey, min_context_slot: Option<u64>) -> Result<TokenAccounts>;
+}
+
+/// A cluster behind a JSON RPC node, read and confirmed at the confirmed commitment
+pub struct RpcCluster {
+    rpc: RpcClient,
+}
+
+impl RpcCluster {
+    pub fn new(url: String) -> Self {
+        Self { rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()) }
+    }
+}
+
+impl Cluster for RpcCluster {
+    fn account(&mut self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.rpc.get_account_with_commitment(address, self.rpc.commitment())?.value)
+    }
+
+    fn clock(&mut self) -> Result<Clock> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+        Ok(bincode::deserialize(&account.data)?)
+    }
+
+    fn send(&mut self, instructions: &[Instruction], signer: &dyn Signer) -> Result<Signature> {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&signer.pubkey()));
+        // Signing may fail on a hardware wallet, which `new_signed_with_payer` would panic on
+        let signers: Vec<&dyn Signer> = vec![signer];
+        transaction.try_sign(&signers, self.rpc.get_latest_blockhash()?)?;
+        Ok(self.rpc.send_and_confirm_transaction_with_spinner(&transaction)?)
+    }
+
+    fn token_accounts(&mut self, mint: &Pubkey, min_context_slot: Option<u64>) -> Result<TokenAccounts> {
+        // Accounts without extensions are exactly the base layout; with extensions the account type byte
+        // follows it, which also keeps multisigs and mints out
+        let layouts = [
+            RpcFilterType::DataSize(TokenAccount::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(TokenAccount::LEN, vec![AccountType::Account as u8])),
+        ];
+        let mut holdings = Vec::new();
+        // Later pages are read no earlier than the slots before them, so the pages only ever move forward
+        let mut slot = min_context_slot.unwrap_or_default();
+        for layout in layouts {
+            // One page per leading byte of the owner, so no single response has to carry every account of
+            // a widely held mint; only the owner and amount are fetched
+            for page in 0..=u8::MAX {
+                let filters = vec![
+                    layout.clone(),
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, mint.to_bytes().to_vec())),
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(OWNER_OFFSET, vec![page])),
+                ];
+                let account_config = RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: OWNER_OFFSET,
+                        length: AMOUNT_OFFSET + 8 - OWNER_OFFSET,
+                    }),
+                    commitment: Some(self.rpc.commitment()),
+                    min_context_slot: Some(slot),
+                    ..RpcAccountInfoConfig::default()
+                };
+                let config = RpcProgramAccountsConfig {
+                    filters: Some(filters),
+                    account_config,
+                    with_context: Some(true),
+                    ..Default::default()
+                };
+                // Sent raw, as the client's own call leaves out the slot the response was read at
+                let params = json!([spl_token_2022::id().to_string(), config]);
+                let response =
+                    self.rpc.send::<OptionalContext<Vec<RpcKeyedAccount>>>(RpcRequest::GetProgramAccounts, params)?;
+                let accounts = match response {
+                    OptionalContext::Context(response) => {
+                        slot = slot.max(response.context.slot);
+                        response.value
+                    }
+                    OptionalContext::NoContext(accounts) => accounts,
+                };
+                for keyed in accounts {
+                    let address = Pubkey::from_str(&keyed.pubkey)?;
+                    let data = keyed.account.data.decode().ok_or("undecodable token account data")?;
+                    let (owner, amount) = data.split_at(AMOUNT_OFFSET - OWNER_OFFSET);
+                    let owner = Pubkey::try_from(owner)?;
+                    let amount = u64::from_le_bytes(amount.try_into()?);
+                    holdings.push(TokenHolding { address, owner, amount });
+                }
+            }
+        }
+        Ok(TokenAccounts { slot, holdings })
+    }
+}
@@ -0,0 +1,425 @@
+// The governance command line behind the `onekx-hook-cli` binary.
+//
+// Every command builds its instructions with `onekx-hook-client`, sends them in one transaction that
+// the selected key signs and pays for, and prints the signature with what changed in the mint's config;
+// `show-config`, `audit`, `simulate-cap`, `snapshot` and `verify` only read. All chain access goes
+// through `Cluster`: an RPC node for the binary, the program-test harness for the suites, which run the
+// same `run` the binary does.
+
+mod audit;
+mod cluster;
+mod render;
+mod simulate;
+mod snapshot;
+mod verify;
+mod watch;
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anchor_lang::AccountDeserialize;
+use clap::{Parser, Subcommand, ValueEnum};
+use one_kx_hook::{ExemptRegistry, HookConfig, SniperLog};
+use onekx_hook_client::{self as client, args, CapUnit, ExemptKind, Governance};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+pub use audit::{Audit, Exemption, Violation};
+pub use cluster::{Cluster, RpcCluster, TokenAccounts, TokenHolding};
+pub use simulate::Simulation;
+pub use snapshot::{Holder, Snapshot};
+pub use verify::{Check, Manifest};
+pub use watch::{Alert, HttpWebhook, Watcher, Webhook};
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// The default timelock `init` sets, the program's own 48 hours
+const DEFAULT_TIMELOCK_SECONDS: u32 = 48 * 60 * 60;
+
+#[derive(Debug, Parser)]
+#[command(name = "onekx-hook-cli", version, about = "Govern a 1000x hooked mint")]
+pub struct Cli {
+    /// The hooked mint; `verify` takes it from the manifest
+    #[arg(long, global = true)]
+    pub mint: Option<Pubkey>,
+
+    /// RPC URL or moniker (mainnet-beta, devnet, testnet, localhost); defaults to the Solana CLI config's
+    #[arg(long, short = 'u', global = true)]
+    pub url: Option<String>,
+
+    /// Keypair file that signs and pays; defaults to the Solana CLI config's
+    #[arg(long, short = 'k', global = true, conflicts_with = "ledger")]
+    pub keypair: Option<PathBuf>,
+
+    /// Sign and pay with a hardware wallet instead, at its default derivation path
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "usb://ledger", value_name = "LOCATOR")]
+    pub ledger: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Create the mint's config, governance log, config extension, sniper log and extra account meta list,
+    /// signed by its mint authority, and finalize the setup
+    Init {
+        #[arg(long)]
+        dev_wallet: Pubkey,
+        /// Defaults to the signer
+        #[arg(long)]
+        governance_authority: Option<Pubkey>,
+        #[arg(long, value_enum, default_value_t = Unit::Raw)]
+        cap_unit: Unit,
+        #[arg(long, default_value_t = DEFAULT_TIMELOCK_SECONDS)]
+        timelock_seconds: u32,
+        /// Lowest wallet cap governance may ever set
+        #[arg(long, default_value_t = 0)]
+        min_cap_floor: u64,
+        /// Largest wallet cap change per proposal, in bps of the current cap; 0 for no limit
+        #[arg(long, default_value_t = 0)]
+        max_cap_change_bps: u16,
+        #[arg(long)]
+        strict_authorities: bool,
+        /// Accept a mint with confidential transfers, whose hidden amounts leave only public balances capped
+        #[arg(long)]
+        allow_confidential: bool,
+    },
+    /// Print the config, with the countdown to a pending cap update
+    ShowConfig,
+    /// Propose a new wallet cap, executable once the timelock has passed
+    ProposeCap {
+        /// The new cap, in the config's cap unit
+        #[arg(long)]
+        cap: u64,
+        /// Replace a pending proposal instead of failing
+        #[arg(long)]
+        overwrite: bool,
+        /// Hex encoded hash of the off-chain rationale
+        #[arg(long, value_parser = parse_memo_hash)]
+        memo_hash: Option<[u8; 32]>,
+    },
+    /// Execute the matured wallet cap proposal
+    ExecuteCap,
+    /// Cancel the pending wallet cap proposal
+    CancelCap,
+    /// Propose a new governance authority, which takes over once it accepts
+    SetGovernance {
+        #[arg(long)]
+        new_authority: Pubkey,
+    },
+    /// Pause transfers to non-exempt destinations
+    Pause {
+        /// Lift the pause, including a tripped circuit breaker
+        #[arg(long)]
+        resume: bool,
+    },
+    /// Manage the exempt registry
+    #[command(subcommand)]
+    Exempt(ExemptCommand),
+    /// Report every owner whose token accounts together hold more than the wallet cap, and the flagged snipers
+    Audit {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+        /// Write the report here instead of to stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Report who a new wallet cap would leave over it, and whether the config lets it be proposed
+    SimulateCap {
+        /// The cap to simulate, in the config's cap unit
+        #[arg(long)]
+        new_cap: u64,
+        /// How many of the largest affected owners to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Write every holder with their balance, share of the supply and exempt and over-cap flags
+    Snapshot {
+        /// Read the token accounts at this slot or later; the slot read at is reported either way
+        #[arg(long)]
+        slot: Option<u64>,
+        #[arg(long, value_enum, default_value_t = SnapshotFormat::Csv)]
+        format: SnapshotFormat,
+        /// Write the snapshot here instead of to stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Check the deployment against a TOML manifest of what it should be, failing on any mismatch
+    Verify {
+        #[arg(long)]
+        manifest: PathBuf,
+    },
+}
+
+impl Command {
+    /// Whether the command sends a transaction, and so needs a signer
+    pub fn sends(&self) -> bool {
+        !matches!(
+            self,
+            Self::ShowConfig
+                | Self::Audit { .. }
+                | Self::SimulateCap { .. }
+                | Self::Snapshot { .. }
+                | Self::Verify { .. }
+        )
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExemptCommand {
+    /// Exempt a token account from the cap, creating the registry first if the mint has none
+    Add {
+        token_account: Pubkey,
+        #[arg(long, value_enum, default_value_t = Kind::Generic)]
+        kind: Kind,
+        /// Unix timestamp the exemption lapses at
+        #[arg(long)]
+        expires_at: Option<i64>,
+    },
+    /// Remove a token account's exemption
+    Remove { token_account: Pubkey },
+}
+
+/// `CapUnit` on the command line
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Unit {
+    Raw,
+    Ui,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SnapshotFormat {
+    Csv,
+    Json,
+}
+
+/// `ExemptKind` on the command line
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Kind {
+    Generic,
+    PoolVault,
+}
+
+fn parse_memo_hash(hex: &str) -> std::result::Result<[u8; 32], String> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    if digits.len() != 64 || !digits.is_ascii() {
+        return Err("expected 32 bytes as 64 hex digits".to_string());
+    }
+    let mut hash = [0; 32];
+    for (byte, pair) in hash.iter_mut().zip(digits.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|error| error.to_string())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|error| error.to_string())?;
+    }
+    Ok(hash)
+}
+
+/// The URL a cluster moniker stands for, as the Solana CLI reads them; anything else is taken as a URL
+pub fn rpc_url(url: &str) -> String {
+    match url {
+        "m" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        "d" | "devnet" => "https://api.devnet.solana.com",
+        "t" | "testnet" => "https://api.testnet.solana.com",
+        "l" | "localhost" => "http://localhost:8899",
+        url => url,
+    }
+    .to_string()
+}
+
+fn config(cluster: &mut dyn Cluster, mint: &Pubkey) -> Result<Option<HookConfig>> {
+    let account = cluster.account(&client::config_pda(mint))?;
+    Ok(account.map(|account| HookConfig::try_deserialize(&mut account.data.as_slice())).transpose()?)
+}
+
+fn exempt_registry(cluster: &mut dyn Cluster, mint: &Pubkey) -> Result<Option<ExemptRegistry>> {
+    let account = cluster.account(&client::exempt_registry_pda(mint))?;
+    Ok(account.map(|account| ExemptRegistry::try_deserialize(&mut account.data.as_slice())).transpose()?)
+}
+
+fn sniper_log(cluster: &mut dyn Cluster, mint: &Pubkey) -> Result<Option<SniperLog>> {
+    let account = cluster.account(&client::sniper_log_pda(mint))?;
+    Ok(account.map(|account| SniperLog::try_deserialize(&mut account.data.as_slice())).transpose()?)
+}
+
+/// Run `cli`'s command against `cluster`, signed and paid for by `signer`, writing what it did to `out`
+pub fn run(cli: &Cli, cluster: &mut dyn Cluster, signer: &dyn Signer, out: &mut dyn Write) -> Result<()> {
+    if let Command::Verify { manifest } = &cli.command {
+        let manifest = Manifest::read(manifest)?;
+        if cli.mint.is_some_and(|mint| mint != manifest.mint) {
+            return Err(format!("--mint differs from the manifest's mint {}", manifest.mint).into());
+        }
+        let checks = verify::verify(cluster, &manifest)?;
+        verify::write_checks(out, &checks)?;
+        let failed = checks.iter().filter(|check| !check.passed()).count();
+        return if failed == 0 { Ok(()) } else { Err(format!("{} checks failed", failed).into()) };
+    }
+    let mint = cli.mint.as_ref().ok_or("--mint is required")?;
+    let key = signer.pubkey();
+    let governance = Governance::authority(key);
+
+    let instructions = match &cli.command {
+        Command::ShowConfig => {
+            let config = config(cluster, mint)?.ok_or_else(|| format!("{} has no config", mint))?;
+            let now = cluster.clock()?.unix_timestamp;
+            render::write_config(out, &client::config_pda(mint), &config, now)?;
+            return Ok(());
+        }
+        Command::Audit { format, output } => {
+            let config = config(cluster, mint)?.ok_or_else(|| format!("{} has no config", mint))?;
+            let mint_account = cluster.account(mint)?.ok_or_else(|| format!("{} not found", mint))?;
+            let registry = exempt_registry(cluster, mint)?;
+            let sniper_log = sniper_log(cluster, mint)?;
+            let now = cluster.clock()?.unix_timestamp;
+            let holdings = cluster.token_accounts(mint, None)?.holdings;
+            let audit = audit::audit(
+                mint,
+                &config,
+                registry.as_ref(),
+                sniper_log.as_ref(),
+                &mint_account.data,
+                &holdings,
+                now,
+            )?;
+
+            let mut file = output.as_ref().map(File::create).transpose()?;
+            let report: &mut dyn Write = match &mut file {
+                Some(file) => file,
+                None => &mut *out,
+            };
+            match format {
+                Format::Table => audit.write_table(report)?,
+                Format::Json => audit.write_json(report)?,
+                Format::Csv => audit.write_csv(report)?,
+            }
+            if let Some(path) = output {
+                let (violations, holders) = (audit.violations.len(), audit.holders);
+                writeln!(out, "{} of {} holders over the cap, written to {}", violations, holders, path.display())?;
+            }
+            return Ok(());
+        }
+        Command::SimulateCap { new_cap, top, format } => {
+            let config = config(cluster, mint)?.ok_or_else(|| format!("{} has no config", mint))?;
+            let mint_account = cluster.account(mint)?.ok_or_else(|| format!("{} not found", mint))?;
+            let registry = exempt_registry(cluster, mint)?;
+            let now = cluster.clock()?.unix_timestamp;
+            let holdings = cluster.token_accounts(mint, None)?.holdings;
+            let simulation =
+                simulate::simulate(mint, &config, registry.as_ref(), &mint_account.data, &holdings, *new_cap, now)?;
+            match format {
+                Format::Table => simulation.write_table(out, *top)?,
+                Format::Json => simulation.write_json(out, *top)?,
+                Format::Csv => simulation.write_csv(out, *top)?,
+            }
+            return Ok(());
+        }
+        Command::Snapshot { slot, format, out: path } => {
+            let holdings = cluster.token_accounts(mint, *slot)?;
+            let config = config(cluster, mint)?.ok_or_else(|| format!("{} has no config", mint))?;
+            let mint_account = cluster.account(mint)?.ok_or_else(|| format!("{} not found", mint))?;
+            let registry = exempt_registry(cluster, mint)?;
+            let now = cluster.clock()?.unix_timestamp;
+            let snapshot = snapshot::snapshot(mint, &config, registry.as_ref(), &mint_account.data, &holdings, now)?;
+
+            let mut file = path.as_ref().map(File::create).transpose()?;
+            let report: &mut dyn Write = match &mut file {
+                Some(file) => file,
+                None => &mut *out,
+            };
+            match format {
+                SnapshotFormat::Csv => snapshot.write_csv(report)?,
+                SnapshotFormat::Json => snapshot.write_json(report)?,
+            }
+            if let Some(path) = path {
+                let (holders, slot) = (snapshot.holders.len(), snapshot.slot);
+                writeln!(out, "{} holders at slot {}, written to {}", holders, slot, path.display())?;
+            }
+            return Ok(());
+        }
+        Command::Init {
+            dev_wallet,
+            governance_authority,
+            cap_unit,
+            timelock_seconds,
+            min_cap_floor,
+            max_cap_change_bps,
+            strict_authorities,
+            allow_confidential,
+        } => {
+            let initialize = args::Initialize {
+                dev_wallet: *dev_wallet,
+                governance_authority: governance_authority.unwrap_or(key),
+                cap_unit: match cap_unit {
+                    Unit::Raw => CapUnit::Raw,
+                    Unit::Ui => CapUnit::Ui,
+                },
+                timelock_seconds: *timelock_seconds,
+                min_cap_floor: *min_cap_floor,
+                max_cap_change_bps: *max_cap_change_bps,
+                strict_authorities: *strict_authorities,
+                allow_confidential: *allow_confidential,
+            };
+            vec![
+                client::initialize(mint, &key, &key, initialize),
+                client::init_governance_log(mint, &key),
+                client::init_config_extension(mint, &key),
+                client::init_sniper_log(mint, &key),
+                client::init_extra_account_meta_list(mint, &key),
+                client::finalize_setup(mint),
+            ]
+        }
+        Command::ProposeCap { cap, overwrite, memo_hash } => {
+            let propose = args::ProposeWalletCapUpdate {
+                new_cap: *cap,
+                overwrite: *overwrite,
+                memo_hash: memo_hash.unwrap_or_default(),
+            };
+            vec![client::propose_wallet_cap_update(mint, &governance, propose)]
+        }
+        Command::ExecuteCap => vec![client::execute_wallet_cap_update(mint, &key)],
+        Command::CancelCap => vec![client::cancel_wallet_cap_update(mint, &governance)],
+        Command::SetGovernance { new_authority } => {
+            let propose = args::ProposeGovernanceTransfer { new_authority: *new_authority };
+            vec![client::propose_governance_transfer(mint, &governance, propose)]
+        }
+        Command::Pause { resume } => vec![client::set_pause(mint, &governance, args::SetPause { paused: !resume })],
+        Command::Exempt(ExemptCommand::Add { token_account, kind, expires_at }) => {
+            let mut instructions = Vec::with_capacity(2);
+            if cluster.account(&client::exempt_registry_pda(mint))?.is_none() {
+                let init = args::InitExemptRegistry { rent_recipient: key };
+                instructions.push(client::init_exempt_registry(mint, &governance, &key, init));
+            }
+            let kind = match kind {
+                Kind::Generic => ExemptKind::Generic,
+                Kind::PoolVault => ExemptKind::PoolVault,
+            };
+            let add = args::AddExemptEntry { token_account: *token_account, kind, expires_at: *expires_at };
+            instructions.push(client::add_exempt_entry(mint, &governance, &key, add));
+            instructions
+        }
+        Command::Exempt(ExemptCommand::Remove { token_account }) => {
+            let registry = cluster
+                .account(&client::exempt_registry_pda(mint))?
+                .ok_or_else(|| format!("{} has no exempt registry", mint))?;
+            let registry = ExemptRegistry::try_deserialize(&mut registry.data.as_slice())?;
+            let remove = args::RemoveExemptEntry { token_account: *token_account };
+            vec![client::remove_exempt_entry(mint, &governance, &registry.rent_recipient, remove)]
+        }
+        Command::Verify { .. } => unreachable!("verify reads its mint from the manifest"),
+    };
+
+    let before = config(cluster, mint)?;
+    let signature = cluster.send(&instructions, signer)?;
+    let after = config(cluster, mint)?;
+    writeln!(out, "Signature: {}", signature)?;
+    render::write_diff(out, before.as_ref(), after.as_ref())?;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::io;
+use std::process::exit;
+
+use clap::Parser;
+use onekx_hook_cli::{rpc_url, Cli, Result, RpcCluster};
+use solana_cli_config::{Config, CONFIG_FILE};
+use solana_remote_wallet::{
+    locator::Locator, remote_keypair::generate_remote_keypair, remote_wallet::maybe_wallet_manager,
+};
+use solana_sdk::{
+    derivation_path::DerivationPath,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, NullSigner, Signer},
+};
+
+fn signer(cli: &Cli, config: &Config) -> Result<Box<dyn Signer>> {
+    if let Some(locator) = &cli.ledger {
+        let wallet_manager = maybe_wallet_manager()?.ok_or("no hardware wallet found")?;
+        let locator = Locator::new_from_path(locator)?;
+        let keypair = generate_remote_keypair(locator, DerivationPath::default(), &wallet_manager, false, "ledger")?;
+        return Ok(Box::new(keypair));
+    }
+    let path = cli.keypair.clone().unwrap_or_else(|| config.keypair_path.clone().into());
+    let keypair = read_keypair_file(&path).map_err(|error| format!("reading {}: {}", path.display(), error))?;
+    Ok(Box::new(keypair))
+}
+
+fn main() {
+    let cli = Cli::parse();
+    // The Solana CLI's own config supplies the defaults, as for every other Solana tool
+    let config = CONFIG_FILE.as_ref().and_then(|path| Config::load(path).ok()).unwrap_or_default();
+    // Read-only commands run without a key to sign with
+    let signer: Result<Box<dyn Signer>> =
+        if cli.command.sends() { signer(&cli, &config) } else { Ok(Box::new(NullSigner::new(&Pubkey::default()))) };
+    let result = signer.and_then(|signer| {
+        let mut cluster = RpcCluster::new(rpc_url(cli.url.as_deref().unwrap_or(&config.json_rpc_url)));
+        onekx_hook_cli::run(&cli, &mut cluster, signer.as_ref(), &mut io::stdout())
+    });
+    if let Err(error) = result {
+        eprintln!("Error: {}", error);
+        exit(1);
+    }
+}
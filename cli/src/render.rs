@@ -0,0 +1,146 @@
+// The config as people read it: one labelled line per setting, diffed across a command.
+
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use one_kx_hook::{HookConfig, PendingCapUpdate};
+use solana_sdk::pubkey::Pubkey;
+
+fn key(key: &Pubkey) -> String {
+    if *key == Pubkey::default() {
+        "none".to_string()
+    } else {
+        key.to_string()
+    }
+}
+
+fn optional(value: Option<impl Display>) -> String {
+    value.map_or_else(|| "none".to_string(), |value| value.to_string())
+}
+
+fn keys(keys: &[Pubkey]) -> String {
+    let set: Vec<_> = keys.iter().filter(|key| **key != Pubkey::default()).map(Pubkey::to_string).collect();
+    if set.is_empty() {
+        "none".to_string()
+    } else {
+        set.join(", ")
+    }
+}
+
+fn cap_update(pending: &PendingCapUpdate) -> String {
+    format!(
+        "{:?} cap {} (proposal {} by {}, executable at {}, expires at {})",
+        pending.kind, pending.new_cap, pending.nonce, pending.proposed_by, pending.execution_time, pending.expires_at
+    )
+}
+
+/// `seconds` as days, hours, minutes and seconds, leaving out zero units
+fn duration(seconds: i64) -> String {
+    let units = [(seconds / 86_400, "d"), (seconds / 3_600 % 24, "h"), (seconds / 60 % 60, "m"), (seconds % 60, "s")];
+    let parts: Vec<_> =
+        units.iter().filter(|(value, _)| *value != 0).map(|(value, unit)| format!("{}{}", value, unit)).collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Every setting of the config, labelled, in a fixed order
+fn fields(config: &HookConfig) -> Vec<(&'static str, String)> {
+    let paused_until = match config.paused_until {
+        0 => "not paused".to_string(),
+        i64::MAX => "until further notice".to_string(),
+        timestamp => timestamp.to_string(),
+    };
+    let threshold = match config.governance_threshold {
+        0 => "single authority".to_string(),
+        threshold => format!("{} of the members", threshold),
+    };
+    vec![
+        ("version", config.version.to_string()),
+        ("wallet cap", config.wallet_cap_raw.to_string()),
+        ("buy cap", config.buy_cap_raw.to_string()),
+        ("max sell", config.max_sell_raw.to_string()),
+        ("cap unit", format!("{:?}", config.cap_unit)),
+        ("min cap floor", config.min_cap_floor.to_string()),
+        ("max cap change bps", config.max_cap_change_bps.to_string()),
+        ("pending cap update", optional(config.pending_cap_update.as_ref().map(cap_update))),
+        ("governance authority", key(&config.governance_authority)),
+        ("pending governance authority", optional(config.pending_governance_authority)),
+        ("governance members", keys(&config.governance_members)),
+        ("governance threshold", threshold),
+        ("governance renounced", config.governance_renounced.to_string()),
+        ("proposer authority", key(&config.proposer_authority)),
+        ("executor authority", key(&config.executor_authority)),
+        ("veto authority", optional(config.veto_authority)),
+        ("guardian", optional(config.guardian)),
+        ("timelock seconds", config.timelock_seconds.to_string()),
+        (
+            "pending timelock update",
+            optional(config.pending_timelock_update.as_ref().map(|pending| {
+                format!("{} seconds, executable at {}", pending.timelock_seconds, pending.execution_time)
+            })),
+        ),
+        ("proposal expiry seconds", config.proposal_expiry_seconds.to_string()),
+        ("dev wallets", keys(&config.dev_wallets)),
+        ("dev exemption expires at", optional(config.dev_exemption_expires_at)),
+        ("trusted programs", keys(&config.trusted_programs)),
+        ("paused until", paused_until),
+        ("circuit breaker threshold", config.circuit_breaker_threshold_raw.to_string()),
+        ("max holders", config.max_holders.to_string()),
+        ("holder count", config.holder_count.to_string()),
+        ("transfer events", config.emit_transfer_events.to_string()),
+        ("frozen", config.frozen.to_string()),
+        ("hook disabled", config.hook_disabled.to_string()),
+        ("confidential transfers allowed", config.allow_confidential.to_string()),
+        ("wallet owners required", config.require_wallet_owners.to_string()),
+        ("setup finalized", config.finalized.to_string()),
+    ]
+}
+
+/// The config at `address`, and when its pending cap update can be executed as of `now`
+pub fn write_config(out: &mut dyn Write, address: &Pubkey, config: &HookConfig, now: i64) -> io::Result<()> {
+    writeln!(out, "Config {}", address)?;
+    for (label, value) in fields(config) {
+        writeln!(out, "  {}: {}", label, value)?;
+    }
+    if let Some(pending) = &config.pending_cap_update {
+        let countdown = if pending.is_expired(now) {
+            "expired, sweep it with sweep_expired_proposal".to_string()
+        } else if now < pending.execution_time {
+            format!("executable in {}", duration(pending.execution_time - now))
+        } else {
+            format!("executable now, for another {}", duration(pending.expires_at - now))
+        };
+        writeln!(out, "Pending cap update {}", countdown)?;
+    }
+    Ok(())
+}
+
+/// The settings a command changed, old and new
+pub fn write_diff(out: &mut dyn Write, before: Option<&HookConfig>, after: Option<&HookConfig>) -> io::Result<()> {
+    match (before, after) {
+        (None, Some(after)) => {
+            writeln!(out, "Config created:")?;
+            for (label, value) in fields(after) {
+                writeln!(out, "  {}: {}", label, value)?;
+            }
+        }
+        (Some(before), Some(after)) => {
+            let changes: Vec<_> =
+                fields(before).into_iter().zip(fields(after)).filter(|(before, after)| before != after).collect();
+            if changes.is_empty() {
+                writeln!(out, "Config unchanged")?;
+            } else {
+                writeln!(out, "Config changes:")?;
+            }
+            for ((label, before), (_, after)) in changes {
+                writeln!(out, "  {}: {} -> {}", label, before, after)?;
+            }
+        }
+        (Some(_), None) => writeln!(out, "Config closed")?,
+        (None, None) => writeln!(out, "No config")?,
+    }
+    Ok(())
+}
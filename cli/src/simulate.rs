@@ -0,0 +1,176 @@
+// What a wallet cap proposal would do to the holders there are now.
+//
+// A lower cap takes nothing back: owners over it keep their balance, and the hook only refuses them more
+// until they're back under. The simulation runs the audit against the proposed cap to find them, and the
+// program's own proposal checks against the config, so a proposal that would bounce on the floor or the
+// largest allowed change is caught before it's sent.
+
+use std::io::{self, Write};
+
+use one_kx_hook::{logic, CapKind, CapUnit, ExemptRegistry, HookConfig};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::audit::{self, Exemption, Violation};
+use crate::{Result, TokenHolding};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simulation {
+    pub mint: Pubkey,
+    pub current_cap: u64,
+    pub new_cap: u64,
+    pub cap_unit: CapUnit,
+    /// Owners with a non-zero balance
+    pub holders: usize,
+    /// Owners over the new cap, largest balance first
+    pub over: Vec<Violation>,
+    /// How many of `over` are within the current cap, and so only over because of the change
+    pub newly_over: usize,
+    /// How many of `over` are exempt from the cap either way
+    pub exempt: usize,
+    /// What `over` holds above the new cap, in cap units
+    pub excess: u128,
+    /// Why the program would refuse to take the proposal, if it would
+    pub rejection: Option<String>,
+}
+
+/// Run `holdings`, every token account of `mint`, against `new_cap` in place of the cap `config` has
+pub fn simulate(
+    mint: &Pubkey,
+    config: &HookConfig,
+    registry: Option<&ExemptRegistry>,
+    mint_data: &[u8],
+    holdings: &[TokenHolding],
+    new_cap: u64,
+    now: i64,
+) -> Result<Simulation> {
+    let mut proposed = config.clone();
+    proposed.wallet_cap_raw = new_cap;
+    let audit = audit::audit(mint, &proposed, registry, None, mint_data, holdings, now)?;
+
+    let over = audit.violations;
+    let newly_over = over.iter().filter(|violation| violation.cap_balance <= config.wallet_cap_raw).count();
+    let exempt = over
+        .iter()
+        .filter(|violation| {
+            matches!(violation.exemption, Exemption::DevWallet | Exemption::Registry | Exemption::BridgeCustody)
+        })
+        .count();
+    let excess = over.iter().map(|violation| u128::from(violation.cap_balance - new_cap)).sum();
+
+    // In the order `propose_wallet_cap_update` checks them, so the reason is the one it would fail with
+    let rejection = logic::validate_cap_proposal(CapKind::Wallet, new_cap)
+        .and_then(|()| config.check_cap_floor(CapKind::Wallet, new_cap))
+        .and_then(|()| config.check_cap_change(CapKind::Wallet, new_cap))
+        .err()
+        .map(|error| match error {
+            anchor_lang::error::Error::AnchorError(error) => error.error_msg.clone(),
+            anchor_lang::error::Error::ProgramError(error) => error.program_error.to_string(),
+        });
+
+    Ok(Simulation {
+        mint: *mint,
+        current_cap: config.wallet_cap_raw,
+        new_cap,
+        cap_unit: config.cap_unit,
+        holders: audit.holders,
+        over,
+        newly_over,
+        exempt,
+        excess,
+        rejection,
+    })
+}
+
+impl Simulation {
+    pub fn write_table(&self, out: &mut dyn Write, top: usize) -> io::Result<()> {
+        writeln!(
+            out,
+            "Mint {}: wallet cap {} -> {} ({:?}), {} holders",
+            self.mint, self.current_cap, self.new_cap, self.cap_unit, self.holders
+        )?;
+        writeln!(
+            out,
+            "{} over the new cap ({} newly, {} exempt), {} above it in total",
+            self.over.len(),
+            self.newly_over,
+            self.exempt,
+            self.excess
+        )?;
+        if !self.over.is_empty() {
+            writeln!(out, "Largest {} affected:", top.min(self.over.len()))?;
+        }
+        for violation in self.over.iter().take(top) {
+            writeln!(
+                out,
+                "  {}: {} ({} over) in {} token account(s), exemption {}",
+                violation.owner,
+                violation.cap_balance,
+                violation.cap_balance - self.new_cap,
+                violation.token_accounts,
+                violation.exemption.label()
+            )?;
+        }
+        match &self.rejection {
+            None => writeln!(out, "The proposal passes the config's floor and change limits"),
+            Some(reason) => writeln!(out, "The proposal would be rejected: {}", reason),
+        }
+    }
+
+    pub fn write_json(&self, out: &mut dyn Write, top: usize) -> io::Result<()> {
+        // Keys, numbers and fixed labels only, so nothing needs escaping but the program's error message
+        let affected: Vec<_> = self
+            .over
+            .iter()
+            .take(top)
+            .map(|violation| {
+                format!(
+                    r#"{{"owner":"{}","cap_balance":{},"excess":{},"token_accounts":{},"exemption":"{}"}}"#,
+                    violation.owner,
+                    violation.cap_balance,
+                    violation.cap_balance - self.new_cap,
+                    violation.token_accounts,
+                    violation.exemption.label()
+                )
+            })
+            .collect();
+        let rejection = match &self.rejection {
+            None => "null".to_string(),
+            Some(reason) => format!("\"{}\"", reason.replace('\\', "\\\\").replace('"', "\\\"")),
+        };
+        writeln!(
+            out,
+            concat!(
+                r#"{{"mint":"{}","current_cap":{},"new_cap":{},"cap_unit":"{:?}","holders":{},"over":{},"#,
+                r#""newly_over":{},"exempt":{},"excess":{},"largest":[{}],"rejection":{}}}"#
+            ),
+            self.mint,
+            self.current_cap,
+            self.new_cap,
+            self.cap_unit,
+            self.holders,
+            self.over.len(),
+            self.newly_over,
+            self.exempt,
+            self.excess,
+            affected.join(","),
+            rejection
+        )
+    }
+
+    /// The `top` largest affected owners only; the totals are left to the table and JSON
+    pub fn write_csv(&self, out: &mut dyn Write, top: usize) -> io::Result<()> {
+        writeln!(out, "owner,cap_balance,excess,token_accounts,exemption")?;
+        for violation in self.over.iter().take(top) {
+            writeln!(
+                out,
+                "{},{},{},{},{}",
+                violation.owner,
+                violation.cap_balance,
+                violation.cap_balance - self.new_cap,
+                violation.token_accounts,
+                violation.exemption.label()
+            )?;
+        }
+        Ok(())
+    }
+}
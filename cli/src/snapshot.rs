@@ -0,0 +1,236 @@
+// Every holder of a mint at one slot, for reports made outside the chain.
+//
+// Token accounts are summed per owner, the way the audit sums them, so an owner spreading a balance over
+// several accounts is one holder. The exempt and over-cap flags are those of the config the snapshot
+// was taken with; an exempt owner over the cap is flagged as both.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use one_kx_hook::{logic, ExemptRegistry, HookConfig};
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::{extension::StateWithExtensions, state::Mint};
+
+use crate::{Result, TokenAccounts};
+
+/// One owner's token accounts, together
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Holder {
+    pub owner: Pubkey,
+    /// Summed over the owner's token accounts, in base units
+    pub balance: u64,
+    pub token_accounts: usize,
+    /// A dev wallet whose exemption hasn't sunset, or an owner whose every token account is registered
+    pub exempt: bool,
+    /// `balance` in cap units exceeds the wallet cap
+    pub over_cap: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub mint: Pubkey,
+    /// The slot the token accounts were read at
+    pub slot: u64,
+    pub supply: u64,
+    /// Largest balance first
+    pub holders: Vec<Holder>,
+}
+
+/// `holdings`, every token account of `mint`, per owner with their flags under `config` as of `now`
+pub fn snapshot(
+    mint: &Pubkey,
+    config: &HookConfig,
+    registry: Option<&ExemptRegistry>,
+    mint_data: &[u8],
+    holdings: &TokenAccounts,
+    now: i64,
+) -> Result<Snapshot> {
+    // Both account layouts are paged for separately, so an account seen twice is only counted once
+    let by_address: BTreeMap<_, _> = holdings.holdings.iter().map(|holding| (holding.address, holding)).collect();
+    let mut owners: BTreeMap<Pubkey, Vec<Pubkey>> = BTreeMap::new();
+    let mut balances: BTreeMap<Pubkey, u64> = BTreeMap::new();
+    for holding in by_address.values().filter(|holding| holding.amount > 0) {
+        owners.entry(holding.owner).or_default().push(holding.address);
+        // Token-2022 supply fits a u64, so no owner's sum can overflow one
+        *balances.entry(holding.owner).or_default() += holding.amount;
+    }
+
+    let mut holders = Vec::with_capacity(owners.len());
+    for (owner, accounts) in owners {
+        let balance = balances[&owner];
+        let registered =
+            accounts.iter().all(|account| registry.is_some_and(|registry| registry.is_exempt(account, now)));
+        let cap_balance = logic::balance_in_cap_units(config.cap_unit, mint_data, balance, now)?;
+        holders.push(Holder {
+            owner,
+            balance,
+            token_accounts: accounts.len(),
+            exempt: logic::is_exempt(config, &owner, now) || registered,
+            over_cap: cap_balance > config.wallet_cap_raw,
+        });
+    }
+    holders.sort_by(|a, b| b.balance.cmp(&a.balance).then(a.owner.cmp(&b.owner)));
+
+    let supply = StateWithExtensions::<Mint>::unpack(mint_data)?.base.supply;
+    Ok(Snapshot { mint: *mint, slot: holdings.slot, supply, holders })
+}
+
+impl Snapshot {
+    /// `balance` as a percentage of the supply with four decimals, computed without floats so the same
+    /// snapshot always prints the same
+    fn percent_of_supply(&self, balance: u64) -> String {
+        if self.supply == 0 {
+            return "0.0000".to_string();
+        }
+        let scaled = u128::from(balance) * 1_000_000 / u128::from(self.supply);
+        format!("{}.{:04}", scaled / 10_000, scaled % 10_000)
+    }
+
+    pub fn write_csv(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "owner,balance,token_accounts,percent_of_supply,exempt,over_cap")?;
+        for holder in &self.holders {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                holder.owner,
+                holder.balance,
+                holder.token_accounts,
+                self.percent_of_supply(holder.balance),
+                holder.exempt,
+                holder.over_cap
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, out: &mut dyn Write) -> io::Result<()> {
+        // Keys, numbers and booleans only, so nothing needs escaping
+        let holders: Vec<_> = self
+            .holders
+            .iter()
+            .map(|holder| {
+                format!(
+                    concat!(
+                        r#"{{"owner":"{}","balance":{},"token_accounts":{},"#,
+                        r#""percent_of_supply":{},"exempt":{},"over_cap":{}}}"#
+                    ),
+                    holder.owner,
+                    holder.balance,
+                    holder.token_accounts,
+                    self.percent_of_supply(holder.balance),
+                    holder.exempt,
+                    holder.over_cap
+                )
+            })
+            .collect();
+        writeln!(
+            out,
+            r#"{{"mint":"{}","slot":{},"supply":{},"holders":[{}]}}"#,
+            self.mint,
+            self.slot,
+            self.supply,
+            holders.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorDeserialize;
+    use one_kx_hook::{ExemptEntry, ExemptKind};
+    use solana_sdk::program_pack::Pack;
+
+    use crate::TokenHolding;
+
+    const CAP: u64 = 5_000_000_000;
+    const SUPPLY: u64 = 1_000_000_000_000;
+
+    fn config(dev_wallet: Pubkey) -> HookConfig {
+        // An all-zero config is a valid one: no options set, raw units, every key unset
+        let mut config = HookConfig::deserialize(&mut &[0; HookConfig::SPACE - 8][..]).unwrap();
+        config.wallet_cap_raw = CAP;
+        config.dev_wallets[0] = dev_wallet;
+        config
+    }
+
+    fn mint_data() -> Vec<u8> {
+        let mint = Mint { supply: SUPPLY, decimals: 9, is_initialized: true, ..Mint::default() };
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(mint, &mut data).unwrap();
+        data
+    }
+
+    fn holding(owner: Pubkey, amount: u64) -> TokenHolding {
+        TokenHolding { address: Pubkey::new_unique(), owner, amount }
+    }
+
+    #[test]
+    fn sums_owners_once_per_token_account() {
+        let (whale, dev_wallet, pool, small) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let split = [holding(whale, CAP), holding(whale, 1)];
+        let vault = holding(pool, 2 * CAP);
+        let holdings = vec![
+            split[0],
+            split[1],
+            // The same account from both layout filters
+            split[0],
+            holding(dev_wallet, 3 * CAP),
+            vault,
+            holding(small, 10_000_000),
+            // Empty accounts don't make holders
+            holding(Pubkey::new_unique(), 0),
+        ];
+        let entry = ExemptEntry { token_account: vault.address, kind: ExemptKind::PoolVault, expires_at: None };
+        let registry = ExemptRegistry { rent_recipient: Pubkey::new_unique(), entries: vec![entry] };
+        let holdings = TokenAccounts { slot: 42, holdings };
+
+        let snapshot =
+            snapshot(&Pubkey::new_unique(), &config(dev_wallet), Some(&registry), &mint_data(), &holdings, 0).unwrap();
+        let holders: Vec<_> = snapshot
+            .holders
+            .iter()
+            .map(|holder| (holder.owner, holder.balance, holder.token_accounts, holder.exempt, holder.over_cap))
+            .collect();
+        assert_eq!(
+            holders,
+            [
+                (dev_wallet, 3 * CAP, 1, true, true),
+                (pool, 2 * CAP, 1, true, true),
+                (whale, CAP + 1, 2, false, true),
+                (small, 10_000_000, 1, false, false),
+            ]
+        );
+        assert_eq!((snapshot.slot, snapshot.supply), (42, SUPPLY));
+
+        let mut csv = Vec::new();
+        snapshot.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "owner,balance,token_accounts,percent_of_supply,exempt,over_cap");
+        assert_eq!(lines[1], format!("{},15000000000,1,1.5000,true,true", dev_wallet));
+        assert_eq!(lines[4], format!("{},10000000,1,0.0010,false,false", small));
+
+        let mut json = Vec::new();
+        snapshot.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains(r#""slot":42,"supply":1000000000000,"holders":[{"owner":"#), "{}", json);
+        assert!(json.contains(r#""balance":5000000001,"token_accounts":2,"percent_of_supply":0.5000"#), "{}", json);
+    }
+
+    #[test]
+    fn a_partly_registered_owner_is_not_exempt() {
+        let owner = Pubkey::new_unique();
+        let (registered, unregistered) = (holding(owner, CAP), holding(owner, CAP));
+        let entry = ExemptEntry { token_account: registered.address, kind: ExemptKind::Generic, expires_at: None };
+        let registry = ExemptRegistry { rent_recipient: Pubkey::new_unique(), entries: vec![entry] };
+        let holdings = TokenAccounts { slot: 1, holdings: vec![registered, unregistered] };
+
+        let snapshot =
+            snapshot(&Pubkey::new_unique(), &config(Pubkey::new_unique()), Some(&registry), &mint_data(), &holdings, 0)
+                .unwrap();
+        assert_eq!(snapshot.holders.len(), 1);
+        assert!(!snapshot.holders[0].exempt && snapshot.holders[0].over_cap);
+    }
+}
@@ -0,0 +1,223 @@
+// Checking a deployment against what it was meant to be.
+//
+// The manifest states the program id, mint and settings a deployment was planned with; every check
+// reads them back from the chain on its own terms, so a mint whose hook points at another build of the
+// program fails on the program id rather than passing on a config nobody looked for. The config, meta
+// list and registry are derived from the manifest's program id, not the one this CLI was built with.
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anchor_lang::AccountDeserialize;
+use one_kx_hook::{ExemptRegistry, HookConfig};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use solana_sdk::{
+    account::Account, bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable,
+    bpf_loader_upgradeable::UpgradeableLoaderState, pubkey::Pubkey,
+};
+use spl_token_2022::extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Mint;
+
+use crate::{Cluster, Result};
+
+/// How the manifest spells an upgrade authority that was given up
+const IMMUTABLE: &str = "none";
+
+/// A deployment as it was planned, read from TOML
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(deserialize_with = "pubkey")]
+    pub program_id: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub mint: Pubkey,
+    /// The wallet cap, in the config's cap unit
+    pub cap: u64,
+    #[serde(deserialize_with = "pubkey")]
+    pub dev_wallet: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub governance_authority: Pubkey,
+    pub timelock_seconds: u32,
+    /// The program's upgrade authority, or "none" for an immutable program
+    #[serde(deserialize_with = "upgrade_authority")]
+    pub upgrade_authority: Option<Pubkey>,
+    /// Token accounts the exempt registry is to hold
+    #[serde(default, deserialize_with = "pubkeys")]
+    pub exemptions: Vec<Pubkey>,
+}
+
+fn pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Pubkey, D::Error> {
+    String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+}
+
+fn pubkeys<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Vec<Pubkey>, D::Error> {
+    let keys = Vec::<String>::deserialize(deserializer)?;
+    keys.iter().map(|key| key.parse().map_err(D::Error::custom)).collect()
+}
+
+fn upgrade_authority<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<Pubkey>, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+        IMMUTABLE => Ok(None),
+        key => key.parse().map(Some).map_err(D::Error::custom),
+    }
+}
+
+impl Manifest {
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|error| format!("reading {}: {}", path.display(), error))?;
+        Ok(toml::from_str(&text).map_err(|error| format!("{}: {}", path.display(), error))?)
+    }
+
+    fn pda(&self, seed: &[u8]) -> Pubkey {
+        Pubkey::find_program_address(&[seed, self.mint.as_ref()], &self.program_id).0
+    }
+}
+
+/// One field of the manifest against what the chain holds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub label: &'static str,
+    pub expected: String,
+    pub found: String,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        self.expected == self.found
+    }
+}
+
+fn key(key: Option<Pubkey>) -> String {
+    key.map_or_else(|| IMMUTABLE.to_string(), |key| key.to_string())
+}
+
+fn keys(keys: impl IntoIterator<Item = Pubkey>) -> String {
+    let keys: BTreeSet<_> = keys.into_iter().map(|key| key.to_string()).collect();
+    if keys.is_empty() {
+        "none".to_string()
+    } else {
+        keys.into_iter().collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// What a program-owned account holds, or why it can't be read
+fn owned_by(account: &Option<Account>, program_id: &Pubkey) -> std::result::Result<(), String> {
+    match account {
+        None => Err("missing".to_string()),
+        Some(account) if account.owner != *program_id => Err(format!("owned by {}", account.owner)),
+        Some(_) => Ok(()),
+    }
+}
+
+/// The upgrade authority of the program at `program_id`, read through the BPF loaders
+fn upgrade_authority_of(cluster: &mut dyn Cluster, program_id: &Pubkey) -> Result<String> {
+    let Some(program) = cluster.account(program_id)? else { return Ok("no program deployed".to_string()) };
+    if !program.executable {
+        return Ok("not executable".to_string());
+    }
+    // Programs of the older loaders can't be upgraded at all
+    if program.owner == bpf_loader::id() || program.owner == bpf_loader_deprecated::id() {
+        return Ok(key(None));
+    }
+    if program.owner != bpf_loader_upgradeable::id() {
+        return Ok(format!("owned by {}", program.owner));
+    }
+    let Ok(UpgradeableLoaderState::Program { programdata_address }) = bincode::deserialize(&program.data) else {
+        return Ok("not an upgradeable program".to_string());
+    };
+    let Some(program_data) = cluster.account(&programdata_address)? else {
+        return Ok(format!("program data {} missing", programdata_address));
+    };
+    let metadata = program_data.data.get(..UpgradeableLoaderState::size_of_programdata_metadata());
+    match metadata.and_then(|metadata| bincode::deserialize(metadata).ok()) {
+        Some(UpgradeableLoaderState::ProgramData { upgrade_authority_address, .. }) => {
+            Ok(key(upgrade_authority_address))
+        }
+        _ => Ok(format!("program data {} unreadable", programdata_address)),
+    }
+}
+
+/// Read the deployment `manifest` describes back from `cluster`, one check per field
+pub fn verify(cluster: &mut dyn Cluster, manifest: &Manifest) -> Result<Vec<Check>> {
+    let mut checks = Vec::new();
+    let mut check = |label, expected: String, found: String| checks.push(Check { label, expected, found });
+
+    // The hook program the mint's transfers actually run
+    let mint = cluster.account(&manifest.mint)?;
+    let hook_program = match &mint {
+        None => "no mint".to_string(),
+        Some(account) if account.owner != spl_token_2022::id() => format!("mint owned by {}", account.owner),
+        Some(account) => match StateWithExtensions::<Mint>::unpack(&account.data) {
+            Err(error) => format!("mint unreadable: {}", error),
+            Ok(state) => match state.get_extension::<TransferHook>() {
+                Ok(hook) => key(Option::<Pubkey>::from(hook.program_id)),
+                Err(_) => "no transfer hook".to_string(),
+            },
+        },
+    };
+    check("program id", manifest.program_id.to_string(), hook_program);
+    let upgrade_authority = upgrade_authority_of(cluster, &manifest.program_id)?;
+    check("upgrade authority", key(manifest.upgrade_authority), upgrade_authority);
+
+    let account = cluster.account(&manifest.pda(b"config"))?;
+    let config = owned_by(&account, &manifest.program_id).and_then(|()| {
+        let data = &account.as_ref().expect("an owned account exists").data;
+        HookConfig::try_deserialize(&mut data.as_slice()).map_err(|error| format!("config unreadable: {}", error))
+    });
+    let found = |read: fn(&HookConfig) -> String| match &config {
+        Ok(config) => read(config),
+        Err(error) => format!("config {}", error),
+    };
+    check("cap", manifest.cap.to_string(), found(|config| config.wallet_cap_raw.to_string()));
+    check("dev wallet", manifest.dev_wallet.to_string(), found(|config| config.dev_wallets[0].to_string()));
+    let governance_authority = found(|config| config.governance_authority.to_string());
+    check("governance authority", manifest.governance_authority.to_string(), governance_authority);
+    check(
+        "timelock seconds",
+        manifest.timelock_seconds.to_string(),
+        found(|config| config.timelock_seconds.to_string()),
+    );
+
+    let extra_metas = cluster.account(&manifest.pda(b"extra-account-metas"))?;
+    let extra_metas = match owned_by(&extra_metas, &manifest.program_id) {
+        Ok(()) => "initialized".to_string(),
+        Err(error) => error,
+    };
+    check("extra account metas", "initialized".to_string(), extra_metas);
+
+    // Only exemptions still in force count, as the hook only honours those
+    let now = cluster.clock()?.unix_timestamp;
+    let registry = cluster.account(&manifest.pda(b"exempt-registry"))?;
+    let exemptions = match (&registry, owned_by(&registry, &manifest.program_id)) {
+        (None, _) => keys([]),
+        (Some(_), Err(error)) => format!("registry {}", error),
+        (Some(account), Ok(())) => match ExemptRegistry::try_deserialize(&mut account.data.as_slice()) {
+            Ok(registry) => keys(
+                registry
+                    .entries
+                    .iter()
+                    .filter(|entry| registry.is_exempt(&entry.token_account, now))
+                    .map(|entry| entry.token_account),
+            ),
+            Err(error) => format!("registry unreadable: {}", error),
+        },
+    };
+    check("exemptions", keys(manifest.exemptions.iter().copied()), exemptions);
+    Ok(checks)
+}
+
+/// One PASS or FAIL line per check, then the tally
+pub fn write_checks(out: &mut dyn Write, checks: &[Check]) -> io::Result<()> {
+    for check in checks {
+        if check.passed() {
+            writeln!(out, "PASS {}: {}", check.label, check.found)?;
+        } else {
+            writeln!(out, "FAIL {}: expected {}, found {}", check.label, check.expected, check.found)?;
+        }
+    }
+    match checks.iter().filter(|check| !check.passed()).count() {
+        0 => writeln!(out, "All {} checks passed", checks.len()),
+        failed => writeln!(out, "{} of {} checks failed", failed, checks.len()),
+    }
+}
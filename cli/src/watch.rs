@@ -0,0 +1,445 @@
+// Watching a mint's cap proposals, for the `onekx-hook-watch` daemon.
+//
+// A timelock only protects holders if someone notices the proposal, so every proposal the program
+// announces becomes a webhook alert: created, canceled or vetoed, executed, expired. Alerts are keyed by
+// proposal nonce and the keys persisted to a state file, which is how a restarted watcher knows what it
+// already sent. Events only arrive while connected, so `Watcher::poll` also reads the config itself:
+// that catches proposals made while the watcher was down and executes matured ones.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anchor_lang::AccountDeserialize;
+use one_kx_hook::{CapKind, HookConfig, PendingCapUpdate};
+use onekx_hook_client::{self as client, events::HookEvent};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+};
+
+use crate::{Cluster, Result};
+
+/// Something worth telling the people watching a mint about one of its cap proposals
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Alert {
+    ProposalCreated {
+        kind: CapKind,
+        nonce: u64,
+        new_cap: u64,
+        current_cap: u64,
+        execution_time: i64,
+        proposed_by: Pubkey,
+    },
+    /// Canceled by governance or vetoed by the veto authority
+    ProposalCanceled {
+        kind: CapKind,
+        nonce: u64,
+        canceled_cap: u64,
+        canceled_by: Pubkey,
+        vetoed: bool,
+    },
+    ProposalExecuted {
+        kind: CapKind,
+        nonce: u64,
+        old_cap: u64,
+        new_cap: u64,
+        executed_by: Pubkey,
+    },
+    /// Swept after its execution window closed without anyone executing it
+    ProposalExpired {
+        kind: CapKind,
+        nonce: u64,
+        expired_cap: u64,
+    },
+}
+
+impl Alert {
+    /// The alert for `event` of `mint`, if it is one about a cap proposal
+    pub fn for_event(mint: &Pubkey, event: &HookEvent) -> Option<Self> {
+        let alert = match event {
+            HookEvent::WalletCapUpdateProposed(event) if event.mint == *mint => Self::ProposalCreated {
+                kind: event.kind,
+                nonce: event.proposal_nonce,
+                new_cap: event.new_cap,
+                current_cap: event.current_cap,
+                execution_time: event.execution_time,
+                proposed_by: event.proposed_by,
+            },
+            HookEvent::WalletCapUpdateCanceled(event) if event.mint == *mint => Self::ProposalCanceled {
+                kind: event.kind,
+                nonce: event.proposal_nonce,
+                canceled_cap: event.canceled_cap,
+                canceled_by: event.governance_authority,
+                vetoed: false,
+            },
+            HookEvent::WalletCapUpdateVetoed(event) if event.mint == *mint => Self::ProposalCanceled {
+                kind: event.kind,
+                nonce: event.proposal_nonce,
+                canceled_cap: event.vetoed_cap,
+                canceled_by: event.veto_authority,
+                vetoed: true,
+            },
+            // Immediate increases never had a proposal to watch
+            HookEvent::WalletCapUpdated(event) if event.mint == *mint && !event.immediate => Self::ProposalExecuted {
+                kind: event.kind,
+                nonce: event.proposal_nonce,
+                old_cap: event.old_cap,
+                new_cap: event.new_cap,
+                executed_by: event.executed_by,
+            },
+            HookEvent::WalletCapUpdateExpired(event) if event.mint == *mint => {
+                Self::ProposalExpired { kind: event.kind, nonce: event.proposal_nonce, expired_cap: event.expired_cap }
+            }
+            _ => return None,
+        };
+        Some(alert)
+    }
+
+    /// The alert for `pending`, the proposal a config holds
+    pub fn for_pending(config: &HookConfig, pending: &PendingCapUpdate) -> Self {
+        Self::ProposalCreated {
+            kind: pending.kind,
+            nonce: pending.nonce,
+            new_cap: pending.new_cap,
+            current_cap: config.cap(pending.kind),
+            execution_time: pending.execution_time,
+            proposed_by: pending.proposed_by,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ProposalCreated { .. } => "proposal_created",
+            Self::ProposalCanceled { .. } => "proposal_canceled",
+            Self::ProposalExecuted { .. } => "proposal_executed",
+            Self::ProposalExpired { .. } => "proposal_expired",
+        }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        match self {
+            Self::ProposalCreated { nonce, .. }
+            | Self::ProposalCanceled { nonce, .. }
+            | Self::ProposalExecuted { nonce, .. }
+            | Self::ProposalExpired { nonce, .. } => *nonce,
+        }
+    }
+
+    /// What the state file records once the alert has been sent: each alert goes out once per proposal
+    fn key(&self) -> String {
+        format!("{} {}", self.name(), self.nonce())
+    }
+
+    /// The webhook body, a flat JSON object of `mint`, the alert's name and its fields
+    pub fn to_json(&self, mint: &Pubkey) -> String {
+        // Keys, numbers and fixed names only, so nothing needs escaping
+        let fields = match self {
+            Self::ProposalCreated { kind, nonce, new_cap, current_cap, execution_time, proposed_by } => format!(
+                r#""kind":"{:?}","nonce":{},"new_cap":{},"current_cap":{},"execution_time":{},"proposed_by":"{}""#,
+                kind, nonce, new_cap, current_cap, execution_time, proposed_by
+            ),
+            Self::ProposalCanceled { kind, nonce, canceled_cap, canceled_by, vetoed } => format!(
+                r#""kind":"{:?}","nonce":{},"canceled_cap":{},"canceled_by":"{}","vetoed":{}"#,
+                kind, nonce, canceled_cap, canceled_by, vetoed
+            ),
+            Self::ProposalExecuted { kind, nonce, old_cap, new_cap, executed_by } => format!(
+                r#""kind":"{:?}","nonce":{},"old_cap":{},"new_cap":{},"executed_by":"{}""#,
+                kind, nonce, old_cap, new_cap, executed_by
+            ),
+            Self::ProposalExpired { kind, nonce, expired_cap } => {
+                format!(r#""kind":"{:?}","nonce":{},"expired_cap":{}"#, kind, nonce, expired_cap)
+            }
+        };
+        format!(r#"{{"mint":"{}","alert":"{}",{}}}"#, mint, self.name(), fields)
+    }
+}
+
+/// Where alerts go
+pub trait Webhook {
+    /// Deliver `body`, a JSON object; an error leaves the alert unsent, to be retried
+    fn post(&mut self, body: &str) -> Result<()>;
+}
+
+/// A generic webhook: each alert is POSTed to the URL as JSON
+pub struct HttpWebhook {
+    url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpWebhook {
+    pub fn new(url: String) -> Self {
+        Self { url, client: reqwest::blocking::Client::new() }
+    }
+}
+
+impl Webhook for HttpWebhook {
+    fn post(&mut self, body: &str) -> Result<()> {
+        let request = self.client.post(&self.url).header(reqwest::header::CONTENT_TYPE, "application/json");
+        request.body(body.to_string()).send()?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// One mint's proposals, with the alerts already sent for them
+pub struct Watcher {
+    mint: Pubkey,
+    state_path: PathBuf,
+    sent: BTreeSet<String>,
+    /// Alerts whose webhook failed, retried on the next `poll`
+    unsent: Vec<Alert>,
+}
+
+impl Watcher {
+    /// Watch `mint`, remembering sent alerts in `state_path`, which needn't exist yet
+    pub fn open(mint: Pubkey, state_path: &Path) -> Result<Self> {
+        let sent = match fs::read_to_string(state_path) {
+            Ok(state) => state.lines().map(str::to_string).collect(),
+            Err(error) if error.kind() == ErrorKind::NotFound => BTreeSet::new(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self { mint, state_path: state_path.to_path_buf(), sent, unsent: Vec::new() })
+    }
+
+    fn save(&self) -> Result<()> {
+        // Replace the file in one rename, so a crash mid-write can't lose what was sent before
+        let state: String = self.sent.iter().map(|key| format!("{}\n", key)).collect();
+        let temporary = self.state_path.with_extension("tmp");
+        fs::write(&temporary, state)?;
+        fs::rename(&temporary, &self.state_path)?;
+        Ok(())
+    }
+
+    /// Send `alert` unless it has been sent before, returning whether it was sent now
+    fn alert(&mut self, alert: Alert, webhook: &mut dyn Webhook) -> Result<bool> {
+        if self.sent.contains(&alert.key()) {
+            return Ok(false);
+        }
+        if let Err(error) = webhook.post(&alert.to_json(&self.mint)) {
+            if !self.unsent.contains(&alert) {
+                self.unsent.push(alert);
+            }
+            return Err(error);
+        }
+        self.unsent.retain(|unsent| *unsent != alert);
+        self.sent.insert(alert.key());
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Alert on each of `events` that is about one of the mint's proposals, once; returns the alerts sent.
+    /// Alerts whose webhook fails are kept for the next `poll`, and the first failure returned.
+    pub fn observe(&mut self, events: &[HookEvent], webhook: &mut dyn Webhook) -> Result<Vec<Alert>> {
+        let mut sent = Vec::new();
+        let mut failure = None;
+        for alert in events.iter().filter_map(|event| Alert::for_event(&self.mint, event)) {
+            match self.alert(alert.clone(), webhook) {
+                Ok(true) => sent.push(alert),
+                Ok(false) => {}
+                Err(error) => failure = failure.or(Some(error)),
+            }
+        }
+        match failure {
+            Some(error) => Err(error),
+            None => Ok(sent),
+        }
+    }
+
+    /// Catch up from the config: retry unsent alerts, alert on a pending proposal that was never alerted
+    /// on, and with an `executor` execute the proposal once its timelock has passed. Returns the
+    /// execution's signature. A failing webhook holds back the execution too: nothing is executed that
+    /// nobody was alerted to.
+    pub fn poll(
+        &mut self,
+        cluster: &mut dyn Cluster,
+        executor: Option<&dyn Signer>,
+        webhook: &mut dyn Webhook,
+    ) -> Result<Option<Signature>> {
+        for alert in std::mem::take(&mut self.unsent) {
+            self.alert(alert, webhook)?;
+        }
+
+        let account = cluster.account(&client::config_pda(&self.mint))?;
+        let account = account.ok_or_else(|| format!("{} has no config", self.mint))?;
+        let config = HookConfig::try_deserialize(&mut account.data.as_slice())?;
+        let Some(pending) = &config.pending_cap_update else { return Ok(None) };
+        self.alert(Alert::for_pending(&config, pending), webhook)?;
+
+        let Some(executor) = executor else { return Ok(None) };
+        let now = cluster.clock()?.unix_timestamp;
+        let authorized =
+            config.executor_authority == Pubkey::default() || config.executor_authority == executor.pubkey();
+        if now < pending.execution_time || pending.is_expired(now) || !authorized {
+            return Ok(None);
+        }
+        let execute = client::execute_wallet_cap_update(&self.mint, &executor.pubkey());
+        Ok(Some(cluster.send(&[execute], executor)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use one_kx_hook::events::*;
+
+    const CAP: u64 = 5_000_000_000;
+
+    fn proposed(mint: Pubkey) -> WalletCapUpdateProposed {
+        WalletCapUpdateProposed {
+            mint,
+            slot: 10,
+            kind: CapKind::Wallet,
+            new_cap: 2 * CAP,
+            current_cap: CAP,
+            proposed_at: 1_000,
+            execution_time: 4_600,
+            governance_authority: Pubkey::new_unique(),
+            proposal_nonce: 3,
+            proposed_by: Pubkey::new_unique(),
+            memo_hash: [7; 32],
+        }
+    }
+
+    fn updated(mint: Pubkey, immediate: bool) -> WalletCapUpdated {
+        WalletCapUpdated {
+            mint,
+            slot: 11,
+            kind: CapKind::Buy,
+            old_cap: CAP,
+            new_cap: 2 * CAP,
+            updated_at: 4_600,
+            executed_by: Pubkey::new_unique(),
+            proposal_nonce: if immediate { 0 } else { 3 },
+            immediate,
+            memo_hash: [0; 32],
+        }
+    }
+
+    #[test]
+    fn proposal_events_map_to_alerts() {
+        let mint = Pubkey::new_unique();
+        let event = proposed(mint);
+        assert_eq!(
+            Alert::for_event(&mint, &HookEvent::WalletCapUpdateProposed(event.clone())),
+            Some(Alert::ProposalCreated {
+                kind: CapKind::Wallet,
+                nonce: 3,
+                new_cap: 2 * CAP,
+                current_cap: CAP,
+                execution_time: 4_600,
+                proposed_by: event.proposed_by,
+            })
+        );
+
+        let canceled = WalletCapUpdateCanceled {
+            mint,
+            slot: 11,
+            kind: CapKind::Wallet,
+            canceled_cap: 2 * CAP,
+            current_cap: CAP,
+            canceled_at: 2_000,
+            governance_authority: Pubkey::new_unique(),
+            proposal_nonce: 3,
+            memo_hash: [7; 32],
+        };
+        let vetoed = WalletCapUpdateVetoed {
+            mint,
+            slot: 11,
+            kind: CapKind::Wallet,
+            vetoed_cap: 2 * CAP,
+            current_cap: CAP,
+            vetoed_at: 2_000,
+            veto_authority: Pubkey::new_unique(),
+            proposal_nonce: 3,
+            memo_hash: [7; 32],
+        };
+        let canceled_alert = |canceled_by, vetoed| Alert::ProposalCanceled {
+            kind: CapKind::Wallet,
+            nonce: 3,
+            canceled_cap: 2 * CAP,
+            canceled_by,
+            vetoed,
+        };
+        let authority = canceled.governance_authority;
+        assert_eq!(
+            Alert::for_event(&mint, &HookEvent::WalletCapUpdateCanceled(canceled)),
+            Some(canceled_alert(authority, false))
+        );
+        let veto_authority = vetoed.veto_authority;
+        assert_eq!(
+            Alert::for_event(&mint, &HookEvent::WalletCapUpdateVetoed(vetoed)),
+            Some(canceled_alert(veto_authority, true))
+        );
+
+        let executed = updated(mint, false);
+        assert_eq!(
+            Alert::for_event(&mint, &HookEvent::WalletCapUpdated(executed.clone())),
+            Some(Alert::ProposalExecuted {
+                kind: CapKind::Buy,
+                nonce: 3,
+                old_cap: CAP,
+                new_cap: 2 * CAP,
+                executed_by: executed.executed_by,
+            })
+        );
+        let expired = WalletCapUpdateExpired {
+            mint,
+            slot: 12,
+            kind: CapKind::Sell,
+            expired_cap: 10,
+            current_cap: 0,
+            expires_at: 9_000,
+            swept_at: 9_001,
+            proposal_nonce: 4,
+            memo_hash: [0; 32],
+        };
+        assert_eq!(
+            Alert::for_event(&mint, &HookEvent::WalletCapUpdateExpired(expired)),
+            Some(Alert::ProposalExpired { kind: CapKind::Sell, nonce: 4, expired_cap: 10 })
+        );
+    }
+
+    #[test]
+    fn other_mints_immediate_increases_and_other_events_raise_no_alert() {
+        let mint = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        assert_eq!(Alert::for_event(&mint, &HookEvent::WalletCapUpdateProposed(proposed(other))), None);
+        assert_eq!(Alert::for_event(&mint, &HookEvent::WalletCapUpdated(updated(other, false))), None);
+        assert_eq!(Alert::for_event(&mint, &HookEvent::WalletCapUpdated(updated(mint, true))), None);
+        let paused = PauseUpdated { mint, slot: 1, paused: true, updated_at: 1, authority: Pubkey::new_unique() };
+        assert_eq!(Alert::for_event(&mint, &HookEvent::PauseUpdated(paused)), None);
+    }
+
+    #[test]
+    fn alerts_serialize_to_flat_json() {
+        let mint = Pubkey::new_unique();
+        let proposed_by = Pubkey::new_unique();
+        let alert = Alert::ProposalCreated {
+            kind: CapKind::Wallet,
+            nonce: 3,
+            new_cap: 2 * CAP,
+            current_cap: CAP,
+            execution_time: 4_600,
+            proposed_by,
+        };
+        assert_eq!(
+            alert.to_json(&mint),
+            format!(
+                concat!(
+                    r#"{{"mint":"{}","alert":"proposal_created","kind":"Wallet","nonce":3,"#,
+                    r#""new_cap":{},"current_cap":{},"execution_time":4600,"proposed_by":"{}"}}"#
+                ),
+                mint,
+                2 * CAP,
+                CAP,
+                proposed_by
+            )
+        );
+        let alert = Alert::ProposalExpired { kind: CapKind::Sell, nonce: 4, expired_cap: 10 };
+        assert_eq!(
+            alert.to_json(&mint),
+            format!(r#"{{"mint":"{}","alert":"proposal_expired","kind":"Sell","nonce":4,"expired_cap":10}}"#, mint)
+        );
+    }
+}
@@ -0,0 +1,108 @@
+// `audit` over a mint seeded with balances minted past the hook, which is how holders end up over the cap,
+// and one whose first buyers the hook flagged as snipers.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+mod cluster;
+
+use cluster::ProgramTestCluster;
+use onekx_hook_client::{self as client, args, Governance};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::WALLET_CAP_RAW;
+
+#[test]
+fn reports_every_owner_over_the_cap() {
+    let mut cluster = ProgramTestCluster::new();
+    let mint = cluster.runtime.block_on(cluster.harness.create_hooked_mint());
+    let governance = mint.governance.insecure_clone();
+    cluster.fund(&governance.pubkey());
+
+    let (whale, splitter, at_cap, vault_owner, empty) =
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    cluster.create_token_account(&mint, &whale, WALLET_CAP_RAW + 1);
+    // Each account is within the cap, the two together aren't
+    cluster.create_token_account(&mint, &splitter, WALLET_CAP_RAW / 2 + 1);
+    cluster.create_token_account(&mint, &splitter, WALLET_CAP_RAW / 2 + 1);
+    cluster.create_token_account(&mint, &at_cap, WALLET_CAP_RAW);
+    cluster.create_token_account(&mint, &mint.dev_wallet, 2 * WALLET_CAP_RAW);
+    let vault = cluster.create_token_account(&mint, &vault_owner, WALLET_CAP_RAW + 5);
+    cluster.create_token_account(&mint, &empty, 0);
+    cluster.run(&mint, &governance, &["exempt", "add", &vault.to_string(), "--kind", "pool-vault"]).unwrap();
+
+    let out = cluster.run(&mint, &governance, &["audit"]).unwrap();
+    let expected = [
+        format!("Mint {}: wallet cap {} (Raw), 5 holders, 4 over the cap", mint.address, WALLET_CAP_RAW),
+        format!("  {}: {} in 1 token account(s), exemption dev-wallet", mint.dev_wallet, 2 * WALLET_CAP_RAW),
+        format!("  {}: {} in 1 token account(s), exemption registry", vault_owner, WALLET_CAP_RAW + 5),
+        format!(
+            "  {}: {} in 2 token account(s), exemption none, each account under the cap",
+            splitter,
+            WALLET_CAP_RAW + 2
+        ),
+        format!("  {}: {} in 1 token account(s), exemption none", whale, WALLET_CAP_RAW + 1),
+    ];
+    assert_eq!(out.lines().collect::<Vec<_>>(), expected, "{}", out);
+
+    let out = cluster.run(&mint, &governance, &["audit", "--format", "csv"]).unwrap();
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines[0], "owner,balance,cap_balance,token_accounts,exemption,split");
+    assert_eq!(lines[3], format!("{},{},{},2,none,true", splitter, WALLET_CAP_RAW + 2, WALLET_CAP_RAW + 2));
+    assert_eq!(lines.len(), 5);
+
+    // JSON goes to the file, a summary to stdout
+    let path = std::env::temp_dir().join(format!("audit-{}.json", mint.address));
+    let out =
+        cluster.run(&mint, &governance, &["audit", "--format", "json", "--output", path.to_str().unwrap()]).unwrap();
+    assert_eq!(out, format!("4 of 5 holders over the cap, written to {}\n", path.display()));
+    let json = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let prefix = format!(
+        r#"{{"mint":"{}","cap":{},"cap_unit":"Raw","holders":5,"violations":[{{"owner":"{}""#,
+        mint.address, WALLET_CAP_RAW, mint.dev_wallet
+    );
+    assert!(json.starts_with(&prefix), "{}", json);
+    let whale_entry = format!(
+        r#"{{"owner":"{}","balance":{},"cap_balance":{},"token_accounts":1,"exemption":"none","split":false}}"#,
+        whale,
+        WALLET_CAP_RAW + 1,
+        WALLET_CAP_RAW + 1
+    );
+    // Nobody bought in a snipe window, so nobody is listed after the violations
+    assert!(json.trim_end().ends_with(&format!(r#"{}],"snipers":[]}}"#, whale_entry)), "{}", json);
+}
+
+#[test]
+fn lists_the_flagged_snipers() {
+    let mut cluster = ProgramTestCluster::new();
+    let mint = cluster.runtime.block_on(cluster.harness.create_hooked_mint());
+    let governance = mint.governance.insecure_clone();
+    cluster.fund(&governance.pubkey());
+    let pool = Keypair::new();
+    let vault = cluster.create_token_account(&mint, &pool.pubkey(), 1_000);
+    cluster.run(&mint, &governance, &["exempt", "add", &vault.to_string(), "--kind", "pool-vault"]).unwrap();
+
+    // Nobody is flagged until there is a window to be flagged in
+    let out = cluster.run(&mint, &governance, &["audit"]).unwrap();
+    assert_eq!(out.lines().count(), 1, "{}", out);
+
+    let window = args::SetSnipeWindow { window_seconds: 600 };
+    let set_window = client::set_snipe_window(&mint.address, &Governance::authority(governance.pubkey()), window);
+    cluster.runtime.block_on(cluster.harness.process(&[set_window], &[&governance])).0.unwrap();
+    let snipers = [Pubkey::new_unique(), Pubkey::new_unique()];
+    for sniper in &snipers {
+        let destination = cluster.create_token_account(&mint, sniper, 0);
+        let buy = cluster.harness.transfer(&mint, &vault, &destination, &pool, 100);
+        cluster.runtime.block_on(buy).0.unwrap();
+    }
+
+    let out = cluster.run(&mint, &governance, &["audit"]).unwrap();
+    let expected = ["2 flagged as snipers".to_string(), format!("  {}", snipers[0]), format!("  {}", snipers[1])];
+    assert_eq!(out.lines().skip(1).collect::<Vec<_>>(), expected, "{}", out);
+    let out = cluster.run(&mint, &governance, &["audit", "--format", "json"]).unwrap();
+    let listed = format!(r#"],"snipers":["{}","{}"]}}"#, snipers[0], snipers[1]);
+    assert!(out.trim_end().ends_with(&listed), "{}", out);
+}
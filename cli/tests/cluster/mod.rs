@@ -0,0 +1,106 @@
+// `Cluster` over the program-test harness, for the suites that run commands the way the binary does.
+
+// Each suite that declares this module uses a different part of it
+#![allow(dead_code)]
+
+use one_kx_hook::HookConfig;
+use onekx_hook_cli::{Cli, Cluster, Result, TokenAccounts, TokenHolding};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccount;
+use tokio::runtime::Runtime;
+
+use crate::test_utils::{Harness, HookedMint};
+
+/// The harness behind `Cluster`, on a runtime of its own since the commands are synchronous
+pub struct ProgramTestCluster {
+    pub runtime: Runtime,
+    pub harness: Harness,
+    /// Every token account created through `create_token_account`, which is all `token_accounts` knows of
+    created_token_accounts: Vec<Pubkey>,
+}
+
+impl Cluster for ProgramTestCluster {
+    fn account(&mut self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.runtime.block_on(self.harness.context.banks_client.get_account(*address))?)
+    }
+
+    fn clock(&mut self) -> Result<Clock> {
+        Ok(self.runtime.block_on(self.harness.context.banks_client.get_sysvar())?)
+    }
+
+    fn send(&mut self, instructions: &[Instruction], signer: &dyn Signer) -> Result<Signature> {
+        let blockhash = self.runtime.block_on(self.harness.context.get_new_latest_blockhash())?;
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&signer.pubkey()));
+        let signers: Vec<&dyn Signer> = vec![signer];
+        transaction.try_sign(&signers, blockhash)?;
+        let signature = transaction.signatures[0];
+        self.runtime.block_on(self.harness.context.banks_client.process_transaction(transaction))?;
+        Ok(signature)
+    }
+
+    fn token_accounts(&mut self, mint: &Pubkey, min_context_slot: Option<u64>) -> Result<TokenAccounts> {
+        let slot = self.clock()?.slot;
+        // As an RPC node answers a minimum context slot it hasn't reached
+        if let Some(min_context_slot) = min_context_slot.filter(|min_context_slot| slot < *min_context_slot) {
+            return Err(format!("minimum context slot {} not reached", min_context_slot).into());
+        }
+        let mut holdings = Vec::new();
+        for address in &self.created_token_accounts {
+            let account = self.runtime.block_on(self.harness.context.banks_client.get_account(*address))?;
+            let data = account.ok_or("token account closed")?.data;
+            let account = StateWithExtensions::<TokenAccount>::unpack(&data)?.base;
+            if account.mint == *mint {
+                holdings.push(TokenHolding { address: *address, owner: account.owner, amount: account.amount });
+            }
+        }
+        Ok(TokenAccounts { slot, holdings })
+    }
+}
+
+impl ProgramTestCluster {
+    pub fn new() -> Self {
+        let runtime = Runtime::new().unwrap();
+        let harness = runtime.block_on(Harness::new());
+        Self { runtime, harness, created_token_accounts: Vec::new() }
+    }
+
+    /// Give `key` the lamports to pay for transactions and the accounts they create
+    pub fn fund(&mut self, key: &Pubkey) {
+        let transfer = system_instruction::transfer(&self.harness.context.payer.pubkey(), key, 1_000_000_000);
+        self.runtime.block_on(self.harness.process(&[transfer], &[])).0.unwrap();
+    }
+
+    /// A token account of `mint` owned by `owner`, with `amount` minted into it past the hook
+    pub fn create_token_account(&mut self, mint: &HookedMint, owner: &Pubkey, amount: u64) -> Pubkey {
+        let address = self.runtime.block_on(self.harness.create_token_account(mint, owner, amount));
+        self.created_token_accounts.push(address);
+        address
+    }
+
+    pub fn config(&mut self, mint: &HookedMint) -> HookConfig {
+        self.runtime.block_on(self.harness.config(mint))
+    }
+
+    pub fn warp_to(&mut self, unix_timestamp: i64) {
+        self.runtime.block_on(self.harness.warp_to_timestamp(unix_timestamp));
+    }
+
+    /// Run the command line `args` for `mint`, signed by `signer`, returning what it printed
+    pub fn run(&mut self, mint: &HookedMint, signer: &Keypair, args: &[&str]) -> Result<String> {
+        let mint = mint.address.to_string();
+        let argv = ["onekx-hook-cli", "--mint", mint.as_str()].into_iter().chain(args.iter().copied());
+        let cli: Cli = clap::Parser::try_parse_from(argv)?;
+        let mut out = Vec::new();
+        onekx_hook_cli::run(&cli, self, signer, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+}
@@ -0,0 +1,91 @@
+// The commands as the binary runs them, parsed from the command line and sent through `Cluster`,
+// against the program in the Token-2022 harness its end-to-end suites use.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+mod cluster;
+
+use cluster::ProgramTestCluster;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{TIMELOCK_SECONDS, WALLET_CAP_RAW};
+
+#[test]
+fn init_then_propose_and_execute_a_cap_update() {
+    let mut cluster = ProgramTestCluster::new();
+    let mint = cluster.runtime.block_on(cluster.harness.create_mint());
+    let (authority, governance) = (mint.mint_authority.insecure_clone(), mint.governance.insecure_clone());
+    cluster.fund(&authority.pubkey());
+    cluster.fund(&governance.pubkey());
+
+    let dev_wallet = mint.dev_wallet.to_string();
+    let governance_key = governance.pubkey().to_string();
+    let init = ["init", "--dev-wallet", &dev_wallet, "--governance-authority", &governance_key];
+    let out = cluster.run(&mint, &authority, &init).unwrap();
+    assert!(out.starts_with("Signature: "), "{}", out);
+    assert!(out.contains("Config created:"), "{}", out);
+    assert!(out.contains(&format!("  wallet cap: {}\n", WALLET_CAP_RAW)), "{}", out);
+    assert_eq!(cluster.config(&mint).governance_authority, governance.pubkey());
+
+    let out = cluster.run(&mint, &governance, &["propose-cap", "--cap", "2000000000"]).unwrap();
+    assert!(out.contains("  pending cap update: none -> Wallet cap 2000000000 (proposal 1"), "{}", out);
+    let pending = cluster.config(&mint).pending_cap_update.unwrap();
+    assert_eq!(pending.execution_time - pending.proposed_at, i64::from(TIMELOCK_SECONDS));
+    cluster.warp_to(pending.proposed_at);
+    let out = cluster.run(&mint, &governance, &["show-config"]).unwrap();
+    assert!(out.contains("Pending cap update executable in 2d\n"), "{}", out);
+
+    // The timelock holds until it has passed, whoever executes
+    let executor = Keypair::new();
+    cluster.fund(&executor.pubkey());
+    assert!(cluster.run(&mint, &executor, &["execute-cap"]).is_err());
+    cluster.warp_to(pending.execution_time - 90);
+    let out = cluster.run(&mint, &governance, &["show-config"]).unwrap();
+    assert!(out.contains("Pending cap update executable in 1m 30s\n"), "{}", out);
+    cluster.warp_to(pending.execution_time);
+    let out = cluster.run(&mint, &governance, &["show-config"]).unwrap();
+    assert!(out.contains("Pending cap update executable now"), "{}", out);
+
+    let out = cluster.run(&mint, &executor, &["execute-cap"]).unwrap();
+    assert!(out.contains(&format!("  wallet cap: {} -> 2000000000\n", WALLET_CAP_RAW)), "{}", out);
+    assert!(out.contains("  pending cap update: Wallet cap 2000000000"), "{}", out);
+    assert_eq!(cluster.config(&mint).wallet_cap_raw, 2_000_000_000);
+    assert!(cluster.config(&mint).pending_cap_update.is_none());
+}
+
+#[test]
+fn pause_cancel_exempt_and_governance_commands() {
+    let mut cluster = ProgramTestCluster::new();
+    let mint = cluster.runtime.block_on(cluster.harness.create_hooked_mint());
+    let governance = mint.governance.insecure_clone();
+    cluster.fund(&governance.pubkey());
+
+    cluster.run(&mint, &governance, &["propose-cap", "--cap", "2000000000"]).unwrap();
+    let out = cluster.run(&mint, &governance, &["cancel-cap"]).unwrap();
+    assert!(out.contains("-> none\n"), "{}", out);
+    assert!(cluster.config(&mint).pending_cap_update.is_none());
+
+    let out = cluster.run(&mint, &governance, &["pause"]).unwrap();
+    assert!(out.contains("  paused until: not paused -> until further notice\n"), "{}", out);
+    cluster.run(&mint, &governance, &["pause", "--resume"]).unwrap();
+    assert_eq!(cluster.config(&mint).paused_until, 0);
+
+    // The first exemption creates the registry; the config itself is untouched
+    let token_account = Pubkey::new_unique().to_string();
+    let out = cluster.run(&mint, &governance, &["exempt", "add", &token_account, "--kind", "pool-vault"]).unwrap();
+    assert!(out.contains("Config unchanged"), "{}", out);
+    cluster.run(&mint, &governance, &["exempt", "remove", &token_account]).unwrap();
+
+    let new_authority = Keypair::new().pubkey();
+    let out =
+        cluster.run(&mint, &governance, &["set-governance", "--new-authority", &new_authority.to_string()]).unwrap();
+    assert!(out.contains(&format!("  pending governance authority: none -> {}\n", new_authority)), "{}", out);
+
+    // Anyone else is refused by the program
+    let impostor = Keypair::new();
+    cluster.fund(&impostor.pubkey());
+    assert!(cluster.run(&mint, &impostor, &["pause"]).is_err());
+}
@@ -0,0 +1,83 @@
+// `simulate-cap` over a mint seeded with balances past the hook, under a config with a cap floor and a
+// largest allowed change, so both the holders a lower cap leaves over it and the proposals the program
+// would refuse show up.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+mod cluster;
+
+use cluster::ProgramTestCluster;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+use test_utils::WALLET_CAP_RAW;
+
+#[test]
+fn reports_holders_over_the_new_cap_and_proposals_the_config_refuses() {
+    let mut cluster = ProgramTestCluster::new();
+    let mint = cluster.runtime.block_on(cluster.harness.create_mint());
+    let (authority, governance) = (mint.mint_authority.insecure_clone(), mint.governance.insecure_clone());
+    cluster.fund(&authority.pubkey());
+    cluster.fund(&governance.pubkey());
+    let (dev_wallet, governance_key) = (mint.dev_wallet.to_string(), governance.pubkey().to_string());
+    let init = [
+        "init",
+        "--dev-wallet",
+        &dev_wallet,
+        "--governance-authority",
+        &governance_key,
+        "--min-cap-floor",
+        "1000000000",
+        "--max-cap-change-bps",
+        "5000",
+    ];
+    cluster.run(&mint, &authority, &init).unwrap();
+
+    let (whale, mid, small) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    cluster.create_token_account(&mint, &whale, 6_000_000_000);
+    cluster.create_token_account(&mint, &mid, 2_000_000_000);
+    cluster.create_token_account(&mint, &mid, 2_000_000_000);
+    cluster.create_token_account(&mint, &small, 2_000_000_000);
+    cluster.create_token_account(&mint, &mint.dev_wallet, 4_500_000_000);
+
+    let out = cluster.run(&mint, &governance, &["simulate-cap", "--new-cap", "3000000000", "--top", "2"]).unwrap();
+    let expected = [
+        format!("Mint {}: wallet cap {} -> 3000000000 (Raw), 4 holders", mint.address, WALLET_CAP_RAW),
+        "3 over the new cap (2 newly, 1 exempt), 5500000000 above it in total".to_string(),
+        "Largest 2 affected:".to_string(),
+        format!("  {}: 6000000000 (3000000000 over) in 1 token account(s), exemption none", whale),
+        format!("  {}: 4500000000 (1500000000 over) in 1 token account(s), exemption dev-wallet", mint.dev_wallet),
+        "The proposal passes the config's floor and change limits".to_string(),
+    ];
+    assert_eq!(out.lines().collect::<Vec<_>>(), expected, "{}", out);
+
+    let out =
+        cluster.run(&mint, &governance, &["simulate-cap", "--new-cap", "3000000000", "--format", "json"]).unwrap();
+    let prefix = format!(
+        concat!(
+            r#"{{"mint":"{}","current_cap":{},"new_cap":3000000000,"cap_unit":"Raw","holders":4,"over":3,"#,
+            r#""newly_over":2,"exempt":1,"excess":5500000000,"largest":[{{"owner":"{}""#
+        ),
+        mint.address, WALLET_CAP_RAW, whale
+    );
+    assert!(out.starts_with(&prefix), "{}", out);
+    let mid_entry = format!(
+        r#"{{"owner":"{}","cap_balance":4000000000,"excess":1000000000,"token_accounts":2,"exemption":"none"}}"#,
+        mid
+    );
+    assert!(out.contains(&mid_entry), "{}", out);
+    assert!(out.trim_end().ends_with(r#"],"rejection":null}"#), "{}", out);
+
+    // A 60% cut is more than the 50% allowed; below the floor fails on the floor first
+    let out = cluster.run(&mint, &governance, &["simulate-cap", "--new-cap", "2000000000"]).unwrap();
+    assert!(out.starts_with(&format!("Mint {}: wallet cap {} -> 2000000000", mint.address, WALLET_CAP_RAW)));
+    assert!(out.contains("\n3 over the new cap (2 newly, 1 exempt), 8500000000 above it in total\n"), "{}", out);
+    assert!(out.ends_with("rejected: Wallet cap change exceeds the maximum swing per proposal\n"), "{}", out);
+    let out = cluster.run(&mint, &governance, &["simulate-cap", "--new-cap", "900000000", "--format", "json"]).unwrap();
+    assert!(out.contains(r#""over":4,"#), "{}", out);
+    assert!(out.trim_end().ends_with(r#""rejection":"Wallet cap would be below the minimum cap floor"}"#), "{}", out);
+
+    // Nothing was proposed
+    let config = cluster.config(&mint);
+    assert_eq!(config.wallet_cap_raw, WALLET_CAP_RAW);
+    assert!(config.pending_cap_update.is_none());
+}
@@ -0,0 +1,182 @@
+// `verify` over mocked RPC responses: the accounts of a mint set up on the harness, plus the upgradeable
+// loader accounts program-test doesn't have, against a manifest that is wrong in one field at a time.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+mod cluster;
+
+use std::collections::{BTreeMap, HashMap};
+
+use cluster::ProgramTestCluster;
+use onekx_hook_cli::{Cli, Cluster, Result, TokenAccounts};
+use onekx_hook_client::exempt_registry_pda;
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{NullSigner, Signature, Signer},
+};
+use test_utils::{HookedMint, TIMELOCK_SECONDS, WALLET_CAP_RAW};
+
+/// Accounts as an RPC node would return them, and nothing else
+struct MockCluster {
+    accounts: HashMap<Pubkey, Account>,
+}
+
+impl Cluster for MockCluster {
+    fn account(&mut self, address: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.accounts.get(address).cloned())
+    }
+
+    fn clock(&mut self) -> Result<Clock> {
+        Ok(Clock::default())
+    }
+
+    fn send(&mut self, _: &[Instruction], _: &dyn Signer) -> Result<Signature> {
+        Err("verify never sends".into())
+    }
+
+    fn token_accounts(&mut self, _: &Pubkey, _: Option<u64>) -> Result<TokenAccounts> {
+        Ok(TokenAccounts { slot: 0, holdings: Vec::new() })
+    }
+}
+
+struct Fixture {
+    cluster: MockCluster,
+    mint: HookedMint,
+    upgrade_authority: Pubkey,
+    exempt: Pubkey,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut harness = ProgramTestCluster::new();
+        let mint = harness.runtime.block_on(harness.harness.create_hooked_mint());
+        let governance = mint.governance.insecure_clone();
+        harness.fund(&governance.pubkey());
+        let exempt = harness.create_token_account(&mint, &Pubkey::new_unique(), 0);
+        harness.run(&mint, &governance, &["exempt", "add", &exempt.to_string()]).unwrap();
+
+        let mut accounts = HashMap::new();
+        for address in [mint.address, mint.config(), mint.extra_account_meta_list(), exempt_registry_pda(&mint.address)]
+        {
+            accounts.insert(address, harness.account(&address).unwrap().unwrap());
+        }
+        // The program as the upgradeable loader deploys it
+        let upgrade_authority = Pubkey::new_unique();
+        let program_data = Pubkey::find_program_address(&[one_kx_hook::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
+        let program = UpgradeableLoaderState::Program { programdata_address: program_data };
+        let data = UpgradeableLoaderState::ProgramData { slot: 1, upgrade_authority_address: Some(upgrade_authority) };
+        let mut data = bincode::serialize(&data).unwrap();
+        data.extend([0; 64]);
+        accounts.insert(one_kx_hook::ID, loader_account(bincode::serialize(&program).unwrap(), true));
+        accounts.insert(program_data, loader_account(data, false));
+
+        Self { cluster: MockCluster { accounts }, mint, upgrade_authority, exempt }
+    }
+
+    /// The manifest of the deployment as it is, as TOML values by key
+    fn manifest(&self) -> BTreeMap<&'static str, String> {
+        let quoted = |key: Pubkey| format!("\"{}\"", key);
+        BTreeMap::from([
+            ("program_id", quoted(one_kx_hook::ID)),
+            ("mint", quoted(self.mint.address)),
+            ("cap", WALLET_CAP_RAW.to_string()),
+            ("dev_wallet", quoted(self.mint.dev_wallet)),
+            ("governance_authority", quoted(self.mint.governance.pubkey())),
+            ("timelock_seconds", TIMELOCK_SECONDS.to_string()),
+            ("upgrade_authority", quoted(self.upgrade_authority)),
+            ("exemptions", format!("[{}]", quoted(self.exempt))),
+        ])
+    }
+
+    /// Run `verify` against `manifest`, returning whether it passed and what it printed
+    fn verify(&mut self, manifest: &BTreeMap<&str, String>) -> (bool, String) {
+        let toml: String = manifest.iter().map(|(key, value)| format!("{} = {}\n", key, value)).collect();
+        let path = std::env::temp_dir().join(format!("onekx-hook-verify-{}.toml", Pubkey::new_unique()));
+        std::fs::write(&path, toml).unwrap();
+        let argv = ["onekx-hook-cli", "verify", "--manifest", path.to_str().unwrap()];
+        let cli: Cli = clap::Parser::try_parse_from(argv).unwrap();
+        let mut out = Vec::new();
+        let signer = NullSigner::new(&Pubkey::default());
+        let result = onekx_hook_cli::run(&cli, &mut self.cluster, &signer, &mut out);
+        std::fs::remove_file(&path).unwrap();
+        (result.is_ok(), String::from_utf8(out).unwrap())
+    }
+
+    /// The labels of the checks `manifest` fails
+    fn failures(&mut self, manifest: &BTreeMap<&str, String>) -> Vec<String> {
+        let (passed, out) = self.verify(manifest);
+        let failures: Vec<_> = out
+            .lines()
+            .filter_map(|line| line.strip_prefix("FAIL "))
+            .map(|line| line.split(':').next().unwrap().to_string())
+            .collect();
+        assert_eq!(passed, failures.is_empty(), "{}", out);
+        failures
+    }
+}
+
+fn loader_account(data: Vec<u8>, executable: bool) -> Account {
+    Account { lamports: 1, data, owner: bpf_loader_upgradeable::id(), executable, rent_epoch: 0 }
+}
+
+#[test]
+fn passes_the_deployment_as_planned() {
+    let mut fixture = Fixture::new();
+    let manifest = fixture.manifest();
+    let (passed, out) = fixture.verify(&manifest);
+    assert!(passed, "{}", out);
+    assert!(out.contains(&format!("PASS program id: {}", one_kx_hook::ID)), "{}", out);
+    assert!(out.ends_with("All 8 checks passed\n"), "{}", out);
+}
+
+#[test]
+fn fails_one_check_per_mismatched_field() {
+    let mut fixture = Fixture::new();
+    let other = format!("\"{}\"", Pubkey::new_unique());
+    let cases = [
+        ("cap", (WALLET_CAP_RAW + 1).to_string(), "cap"),
+        ("dev_wallet", other.clone(), "dev wallet"),
+        ("governance_authority", other.clone(), "governance authority"),
+        ("timelock_seconds", (TIMELOCK_SECONDS + 1).to_string(), "timelock seconds"),
+        ("upgrade_authority", other.clone(), "upgrade authority"),
+        ("upgrade_authority", "\"none\"".to_string(), "upgrade authority"),
+        ("exemptions", "[]".to_string(), "exemptions"),
+        ("exemptions", format!("[\"{}\", {}]", fixture.exempt, other), "exemptions"),
+    ];
+    for (field, value, label) in cases {
+        let mut manifest = fixture.manifest();
+        manifest.insert(field, value.clone());
+        assert_eq!(fixture.failures(&manifest), [label], "{} = {}", field, value);
+    }
+
+    // A missing meta list, which transfers can't run without
+    let manifest = fixture.manifest();
+    fixture.cluster.accounts.remove(&fixture.mint.extra_account_meta_list()).unwrap();
+    assert_eq!(fixture.failures(&manifest), ["extra account metas"]);
+}
+
+#[test]
+fn a_mint_hooked_to_another_program_fails_every_program_check() {
+    let mut fixture = Fixture::new();
+
+    // As if the manifest named the mainnet id while the mint was set up against a devnet build
+    let mut manifest = fixture.manifest();
+    manifest.insert("program_id", format!("\"{}\"", Pubkey::new_unique()));
+    let (passed, out) = fixture.verify(&manifest);
+    assert!(!passed);
+    assert!(out.contains(&format!("found {}", one_kx_hook::ID)), "{}", out);
+    assert!(out.contains("FAIL upgrade authority: expected"), "{}", out);
+    assert!(out.ends_with("8 of 8 checks failed\n"), "{}", out);
+
+    // And a manifest for another mint finds nothing of this one
+    let mut manifest = fixture.manifest();
+    manifest.insert("mint", format!("\"{}\"", Pubkey::new_unique()));
+    let failures = fixture.failures(&manifest);
+    assert_eq!(failures.len(), 7, "{:?}", failures);
+    assert!(!failures.contains(&"upgrade authority".to_string()));
+}
@@ -0,0 +1,164 @@
+// The watcher over a mint with the shortest timelock the program allows: alerts from the events of real
+// transactions, deduplicated across restarts through the state file, and execution once matured.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+mod cluster;
+
+use std::path::PathBuf;
+
+use cluster::ProgramTestCluster;
+use onekx_hook_cli::{Alert, Result, Watcher, Webhook};
+use onekx_hook_client::{self as client, args, events::parse_logs, CapKind, Governance};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+use test_utils::{HookedMint, WALLET_CAP_RAW};
+
+const TIMELOCK_SECONDS: i64 = 60 * 60;
+
+#[derive(Default)]
+struct RecordingWebhook {
+    bodies: Vec<String>,
+    down: bool,
+}
+
+impl Webhook for RecordingWebhook {
+    fn post(&mut self, body: &str) -> Result<()> {
+        if self.down {
+            return Err("webhook down".into());
+        }
+        self.bodies.push(body.to_string());
+        Ok(())
+    }
+}
+
+struct Fixture {
+    cluster: ProgramTestCluster,
+    mint: HookedMint,
+    governance: Keypair,
+    state_file: PathBuf,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let mut cluster = ProgramTestCluster::new();
+        let mint = cluster.runtime.block_on(cluster.harness.create_mint());
+        let (authority, governance) = (mint.mint_authority.insecure_clone(), mint.governance.insecure_clone());
+        cluster.fund(&authority.pubkey());
+        cluster.fund(&governance.pubkey());
+        let (dev_wallet, governance_key) = (mint.dev_wallet.to_string(), governance.pubkey().to_string());
+        let init = [
+            "init",
+            "--dev-wallet",
+            &dev_wallet,
+            "--governance-authority",
+            &governance_key,
+            "--timelock-seconds",
+            &TIMELOCK_SECONDS.to_string(),
+        ];
+        cluster.run(&mint, &authority, &init).unwrap();
+        let state_file = std::env::temp_dir().join(format!("onekx-hook-watch-{}.state", mint.address));
+        Self { cluster, mint, governance, state_file }
+    }
+
+    fn watcher(&self) -> Watcher {
+        Watcher::open(self.mint.address, &self.state_file).unwrap()
+    }
+
+    /// Send `instruction` signed by governance, returning the hook events of its log
+    fn govern(&mut self, instruction: Instruction) -> Vec<client::events::HookEvent> {
+        let logs =
+            self.cluster.runtime.block_on(self.cluster.harness.process_for_logs(&[instruction], &[&self.governance]));
+        parse_logs(&logs)
+    }
+
+    fn propose(&mut self, new_cap: u64) -> Instruction {
+        let args = args::ProposeWalletCapUpdate { new_cap, overwrite: false, memo_hash: [0; 32] };
+        client::propose_wallet_cap_update(&self.mint.address, &Governance::authority(self.governance.pubkey()), args)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.state_file);
+    }
+}
+
+#[test]
+fn alerts_once_across_restarts_and_executes_when_matured() {
+    let mut fixture = Fixture::new();
+    let mut webhook = RecordingWebhook::default();
+    let mut watcher = fixture.watcher();
+
+    let propose = fixture.propose(2_000_000_000);
+    let events = fixture.govern(propose);
+    let alerts = watcher.observe(&events, &mut webhook).unwrap();
+    let pending = fixture.cluster.config(&fixture.mint).pending_cap_update.unwrap();
+    assert_eq!(
+        alerts,
+        [Alert::ProposalCreated {
+            kind: CapKind::Wallet,
+            nonce: 1,
+            new_cap: 2_000_000_000,
+            current_cap: WALLET_CAP_RAW,
+            execution_time: pending.execution_time,
+            proposed_by: fixture.governance.pubkey(),
+        }]
+    );
+    assert_eq!(webhook.bodies, [alerts[0].to_json(&fixture.mint.address)]);
+
+    // Neither the same events again, a restart, nor the proposal showing in the config alert twice
+    assert!(watcher.observe(&events, &mut webhook).unwrap().is_empty());
+    let mut watcher = fixture.watcher();
+    assert!(watcher.observe(&events, &mut webhook).unwrap().is_empty());
+    assert_eq!(watcher.poll(&mut fixture.cluster, None, &mut webhook).unwrap(), None);
+    assert_eq!(webhook.bodies.len(), 1);
+
+    // The executor waits out the timelock
+    let executor = Keypair::new();
+    fixture.cluster.fund(&executor.pubkey());
+    fixture.cluster.warp_to(pending.execution_time - 1);
+    assert_eq!(watcher.poll(&mut fixture.cluster, Some(&executor), &mut webhook).unwrap(), None);
+    assert_eq!(fixture.cluster.config(&fixture.mint).wallet_cap_raw, WALLET_CAP_RAW);
+    fixture.cluster.warp_to(pending.execution_time);
+    assert!(watcher.poll(&mut fixture.cluster, Some(&executor), &mut webhook).unwrap().is_some());
+    let config = fixture.cluster.config(&fixture.mint);
+    assert_eq!((config.wallet_cap_raw, config.pending_cap_update.is_none()), (2_000_000_000, true));
+    assert_eq!(watcher.poll(&mut fixture.cluster, Some(&executor), &mut webhook).unwrap(), None);
+}
+
+#[test]
+fn catches_up_on_missed_proposals_and_retries_failed_webhooks() {
+    let mut fixture = Fixture::new();
+    let mut webhook = RecordingWebhook::default();
+    let mut watcher = fixture.watcher();
+
+    // Proposed while nobody was watching: the next poll finds it in the config
+    let propose = fixture.propose(3_000_000_000);
+    fixture.govern(propose);
+    watcher.poll(&mut fixture.cluster, None, &mut webhook).unwrap();
+    assert_eq!(webhook.bodies.len(), 1);
+    assert!(webhook.bodies[0].contains(r#""alert":"proposal_created","kind":"Wallet","nonce":1"#));
+
+    // A cancellation whose webhook fails is sent by the next poll instead
+    let cancel =
+        client::cancel_wallet_cap_update(&fixture.mint.address, &Governance::authority(fixture.governance.pubkey()));
+    let events = fixture.govern(cancel);
+    webhook.down = true;
+    assert!(watcher.observe(&events, &mut webhook).is_err());
+    webhook.down = false;
+    watcher.poll(&mut fixture.cluster, None, &mut webhook).unwrap();
+    assert_eq!(webhook.bodies.len(), 2);
+    let canceled = format!(
+        concat!(
+            r#""alert":"proposal_canceled","kind":"Wallet","nonce":1,"#,
+            r#""canceled_cap":3000000000,"canceled_by":"{}","vetoed":false"#
+        ),
+        fixture.governance.pubkey()
+    );
+    assert!(webhook.bodies[1].contains(&canceled), "{}", webhook.bodies[1]);
+    assert!(watcher.observe(&events, &mut webhook).unwrap().is_empty());
+}
@@ -0,0 +1,18 @@
+// Derive a mint's config PDA in the browser, from the same `config_pda` the native client uses. Built
+// without the `rpc` feature, so nothing in it reaches for a socket:
+//
+//     cargo build --example config_pda --target wasm32-unknown-unknown --release
+//     wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/examples/config_pda.wasm
+//
+// then from JavaScript, after `await init()`: `config_pda("<MINT>")`.
+
+use std::str::FromStr;
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+
+/// The config PDA of `mint`, both base58
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
+pub fn config_pda(mint: &str) -> Result<String, String> {
+    let mint = Pubkey::from_str(mint).map_err(|error| format!("{}: {}", mint, error))?;
+    Ok(onekx_hook_client::config_pda(&mint).to_string())
+}
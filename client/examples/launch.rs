@@ -0,0 +1,45 @@
+// Launch a hooked mint in one transaction, paid for by the keypair file given, or with `--dry-run` only
+// print the plan.
+//
+//     cargo run --example launch --features rpc -- <KEYPAIR> <DEV_WALLET> <CAP> [--dry-run] [RPC_URL]
+
+use std::str::FromStr;
+
+use onekx_hook_client::launch::{self, LaunchParams};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+const USAGE: &str = "usage: launch <KEYPAIR> <DEV_WALLET> <CAP> [--dry-run] [RPC_URL]";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut positional, flags): (Vec<String>, Vec<String>) =
+        std::env::args().skip(1).partition(|arg| !arg.starts_with("--"));
+    let dry_run = flags.iter().any(|flag| flag == "--dry-run");
+    if positional.len() < 3 {
+        return Err(USAGE.into());
+    }
+    let url = if positional.len() > 3 { positional.remove(3) } else { DEFAULT_RPC_URL.to_string() };
+    let payer = read_keypair_file(&positional[0])?;
+    let params = LaunchParams {
+        decimals: 9,
+        cap: positional[2].parse()?,
+        dev_wallet: Pubkey::from_str(&positional[1])?,
+        governance_authority: payer.pubkey(),
+        metadata_pointer: None,
+    };
+    let rpc = RpcClient::new(url);
+
+    if dry_run {
+        let mint = Keypair::new();
+        print!("{}", launch::plan(&rpc, &mint.pubkey(), &payer.pubkey(), &params).await?);
+        return Ok(());
+    }
+    let (mint, signature) = launch::create_hooked_mint(&rpc, &payer, &params).await?;
+    println!("Launched {}: {}", mint, signature);
+    Ok(())
+}
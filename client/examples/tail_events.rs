@@ -0,0 +1,71 @@
+// Print the hook events of a transaction, waiting for it to confirm: the `emit!` events from its log and
+// the `emit_cpi!` events from its inner instructions to the program.
+//
+//     cargo run --example tail_events --features rpc -- <SIGNATURE> [RPC_URL]
+
+use std::{str::FromStr, time::Duration};
+
+use onekx_hook_client::events::{parse_event_cpi, parse_logs};
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiInstruction, UiTransactionEncoding};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+/// Seconds to wait for the transaction before giving up
+const WAIT_SECONDS: u32 = 60;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let signature = args.next().ok_or("usage: tail_events <SIGNATURE> [RPC_URL]")?;
+    let signature = Signature::from_str(&signature)?;
+    let rpc = RpcClient::new(args.next().unwrap_or_else(|| DEFAULT_RPC_URL.to_string()));
+
+    let mut waited = 0;
+    let transaction = loop {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        match rpc.get_transaction_with_config(&signature, config).await {
+            Ok(transaction) => break transaction.transaction,
+            Err(_) if waited < WAIT_SECONDS => {
+                waited += 1;
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            Err(error) => return Err(error.into()),
+        }
+    };
+    let meta = transaction.meta.ok_or("the transaction has no status meta")?;
+    let message = transaction.transaction.decode().ok_or("the transaction doesn't decode")?.message;
+
+    // Inner instructions index the static keys, then the writable and readonly keys of lookup tables
+    let mut keys = message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            keys.push(Pubkey::from_str(address)?);
+        }
+    }
+
+    let mut events = match &meta.log_messages {
+        OptionSerializer::Some(logs) => parse_logs(logs),
+        _ => Vec::new(),
+    };
+    if let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions {
+        for instruction in inner_instructions.iter().flat_map(|inner| &inner.instructions) {
+            let UiInstruction::Compiled(instruction) = instruction else { continue };
+            if keys.get(usize::from(instruction.program_id_index)) == Some(&onekx_hook_client::ID) {
+                events.extend(parse_event_cpi(&bs58::decode(&instruction.data).into_vec()?));
+            }
+        }
+    }
+
+    if events.is_empty() {
+        println!("no hook events in {}", signature);
+    }
+    for event in events {
+        println!("{:#?}", event);
+    }
+    Ok(())
+}
@@ -0,0 +1,402 @@
+// Handing a mint out to many wallets without transfers bouncing off the cap.
+//
+// `plan` decides every recipient up front with the program's own receive check, the one `preflight`
+// runs, and applies the policy to those it would reject, so the transactions `execute` sends only carry
+// transfers the hook lets through. Each recipient gets its Token-2022 associated token account, created
+// in the same transaction if it doesn't exist yet, and the recipients are packed into transactions as
+// tightly as the packet size and account lock limits allow. Only the receiving side is planned for:
+// sell limits and lockups of the source are the distributor's own business.
+
+use std::collections::HashMap;
+#[cfg(feature = "rpc")]
+use std::collections::HashSet;
+#[cfg(feature = "rpc")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "rpc")]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(feature = "rpc")]
+use std::path::Path;
+
+#[cfg(feature = "rpc")]
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::solana_program::{
+    clock::Clock, instruction::Instruction, message::Message, program_error::ProgramError, program_pack::Pack,
+    pubkey::Pubkey,
+};
+use one_kx_hook::HookError;
+#[cfg(feature = "rpc")]
+use solana_client::nonblocking::rpc_client::RpcClient;
+#[cfg(feature = "rpc")]
+use solana_sdk::{
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::{Account, AccountState, Mint};
+
+use crate::{
+    add_extra_accounts_for_transfer_offline, check_transfer_offline, Error, PreflightAccounts, TransferVerdict,
+};
+#[cfg(feature = "rpc")]
+use crate::{config_pda, exempt_registry_pda, extra_metas_pda, ID};
+
+/// Accounts per `getMultipleAccounts` call, the RPC's limit
+#[cfg(feature = "rpc")]
+const FETCH_CHUNK: usize = 100;
+/// Times a transaction is sent before the airdrop stops
+#[cfg(feature = "rpc")]
+const SEND_ATTEMPTS: u32 = 3;
+/// The largest serialized transaction, solana-sdk's `PACKET_DATA_SIZE`: an IPv6 packet less its headers
+const PACKET_DATA_SIZE: usize = 1280 - 40 - 8;
+/// The most accounts one transaction may lock, solana-sdk's `MAX_TX_ACCOUNT_LOCKS`
+const MAX_TX_ACCOUNT_LOCKS: usize = 128;
+/// A transaction signature's serialized size
+const SIGNATURE_SIZE: usize = 64;
+
+/// What to do with a recipient the transfer would take over the cap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AirdropPolicy {
+    /// Leave the recipient out
+    Skip,
+    /// Send what fits under the cap, leaving the recipient out if nothing does
+    ClampToCap,
+    /// Refuse to plan the airdrop at all
+    Fail,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AirdropConfig {
+    /// The token account everything is sent from
+    pub source: Pubkey,
+    /// The source's owner or delegate, which signs every transfer
+    pub authority: Pubkey,
+    /// Pays the fees and the rent of the recipients' new token accounts
+    pub payer: Pubkey,
+    pub policy: AirdropPolicy,
+}
+
+/// One recipient's transfer, with the instructions that make it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    pub owner: Pubkey,
+    /// The owner's associated token account
+    pub destination: Pubkey,
+    pub requested: u64,
+    /// What is sent, less than `requested` if clamped to the cap
+    pub amount: u64,
+    /// Exempt from the cap, so sent in full whatever the policy
+    pub exempt: bool,
+    pub instructions: Vec<Instruction>,
+}
+
+/// A recipient left out of the airdrop, with what the hook would have rejected its transfer with
+#[derive(Clone, Copy, Debug)]
+pub struct Skipped {
+    pub owner: Pubkey,
+    pub requested: u64,
+    pub reason: HookError,
+}
+
+#[derive(Clone, Debug)]
+pub struct AirdropPlan {
+    pub mint: Pubkey,
+    pub config: AirdropConfig,
+    /// The deliveries to send, one transaction per entry
+    pub transactions: Vec<Vec<Delivery>>,
+    pub skipped: Vec<Skipped>,
+}
+
+impl AirdropPlan {
+    pub fn deliveries(&self) -> impl Iterator<Item = &Delivery> {
+        self.transactions.iter().flatten()
+    }
+
+    /// Raw amount the airdrop sends
+    pub fn total(&self) -> u64 {
+        self.deliveries().map(|delivery| delivery.amount).sum()
+    }
+}
+
+/// The accounts an airdrop plan reads, fetched beforehand
+pub struct AirdropAccounts<'a> {
+    pub config: &'a [u8],
+    pub mint: &'a [u8],
+    pub meta_list: &'a [u8],
+    pub source: &'a [u8],
+    /// The exempt registry, if it has been created
+    pub exempt_registry: Option<&'a [u8]>,
+    /// The recipients' associated token accounts that exist; any other is planned as created empty
+    pub destinations: &'a [(Pubkey, &'a [u8])],
+}
+
+/// The associated token account `owner` receives `mint` at
+pub fn destination(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::id())
+}
+
+/// Fetch the config, mint, meta list, source, exempt registry, clock and the recipients' token accounts,
+/// and plan the airdrop of `recipients`, owners with the raw amounts they are sent
+#[cfg(feature = "rpc")]
+pub async fn plan(
+    rpc: &RpcClient,
+    mint: Pubkey,
+    recipients: Vec<(Pubkey, u64)>,
+    config: AirdropConfig,
+) -> Result<AirdropPlan, Error> {
+    let addresses =
+        [config_pda(&mint), mint, extra_metas_pda(&mint), config.source, exempt_registry_pda(&mint), sysvar::clock::ID];
+    let accounts = rpc.get_multiple_accounts(&addresses).await?;
+    let fetched = |index: usize| accounts[index].as_ref().ok_or(Error::AccountNotFound(addresses[index]));
+    let clock: Clock = bincode::deserialize(&fetched(5)?.data).map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let destinations: Vec<_> = recipients.iter().map(|(owner, _)| destination(&mint, owner)).collect();
+    let mut fetched_destinations = Vec::new();
+    for chunk in destinations.chunks(FETCH_CHUNK) {
+        let found = rpc.get_multiple_accounts(chunk).await?;
+        fetched_destinations
+            .extend(chunk.iter().zip(found).filter_map(|(address, account)| Some((*address, account?))));
+    }
+    let destinations: Vec<_> =
+        fetched_destinations.iter().map(|(address, account)| (*address, account.data.as_slice())).collect();
+
+    let accounts = AirdropAccounts {
+        config: &fetched(0)?.data,
+        mint: &fetched(1)?.data,
+        meta_list: &fetched(2)?.data,
+        source: &fetched(3)?.data,
+        // An uninitialized PDA means no registry exemptions, as on-chain
+        exempt_registry: accounts[4]
+            .as_ref()
+            .filter(|registry| registry.owner == ID)
+            .map(|registry| &registry.data[..]),
+        destinations: &destinations,
+    };
+    plan_offline(&mint, recipients, config, &accounts, &clock)
+}
+
+/// `plan` over account data fetched beforehand, at `clock`
+pub fn plan_offline(
+    mint: &Pubkey,
+    recipients: Vec<(Pubkey, u64)>,
+    config: AirdropConfig,
+    accounts: &AirdropAccounts,
+    clock: &Clock,
+) -> Result<AirdropPlan, Error> {
+    let decimals = StateWithExtensions::<Mint>::unpack(accounts.mint)?.base.decimals;
+
+    // A recipient listed twice receives the sum, decided as one transfer
+    let mut merged: Vec<(Pubkey, u64)> = Vec::with_capacity(recipients.len());
+    let mut positions = HashMap::with_capacity(recipients.len());
+    for (owner, amount) in recipients {
+        match positions.get(&owner) {
+            Some(&position) => {
+                let total: &mut u64 = &mut merged[position].1;
+                *total = total.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+            }
+            None => {
+                positions.insert(owner, merged.len());
+                merged.push((owner, amount));
+            }
+        }
+    }
+
+    let existing: HashMap<_, _> = accounts.destinations.iter().copied().collect();
+    let mut deliveries = Vec::with_capacity(merged.len());
+    let mut skipped = Vec::new();
+    for (owner, requested) in merged {
+        let destination = destination(mint, &owner);
+        let exists = existing.get(&destination).copied();
+        // A token account yet to be created receives like an empty one
+        let created;
+        let destination_data = match exists {
+            Some(data) => data,
+            None => {
+                created = empty_account(mint, &owner);
+                &created
+            }
+        };
+        let preflight = PreflightAccounts {
+            config: accounts.config,
+            mint: accounts.mint,
+            destination: destination_data,
+            exempt_registry: accounts.exempt_registry,
+            // Recipients are wallets, which can't have a trusted owner record
+            trusted_owner: None,
+        };
+        let verdict = |amount| check_transfer_offline(mint, &destination, &preflight, clock, amount);
+
+        let (amount, exempt) = match verdict(requested)? {
+            TransferVerdict::Allowed { remaining } => (requested, remaining == u64::MAX),
+            TransferVerdict::Rejected { reason } => {
+                let clamped = match (config.policy, reason) {
+                    (AirdropPolicy::Fail, _) => return Err(Error::RecipientRejected { owner, reason }),
+                    (AirdropPolicy::ClampToCap, HookError::WalletCapExceeded) => largest_allowed(requested, &verdict)?,
+                    // Nothing fits while transfers are paused
+                    _ => 0,
+                };
+                if clamped == 0 {
+                    skipped.push(Skipped { owner, requested, reason });
+                    continue;
+                }
+                (clamped, false)
+            }
+        };
+
+        let mut instructions = Vec::with_capacity(2);
+        if exists.is_none() {
+            instructions.push(create_associated_token_account_idempotent(
+                &config.payer,
+                &owner,
+                mint,
+                &spl_token_2022::id(),
+            ));
+        }
+        let mut transfer = spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::id(),
+            &config.source,
+            mint,
+            &destination,
+            &config.authority,
+            &[],
+            amount,
+            decimals,
+        )?;
+        let token_accounts = [(config.source, accounts.source), (destination, destination_data)];
+        add_extra_accounts_for_transfer_offline(&mut transfer, *mint, accounts.meta_list, &token_accounts)?;
+        instructions.push(transfer);
+        deliveries.push(Delivery { owner, destination, requested, amount, exempt, instructions });
+    }
+
+    let needed = deliveries.iter().map(|delivery: &Delivery| delivery.amount).sum();
+    let available = StateWithExtensions::<Account>::unpack(accounts.source)?.base.amount;
+    if needed > available {
+        return Err(Error::InsufficientFunds { needed, available });
+    }
+
+    Ok(AirdropPlan { mint: *mint, config, transactions: pack(deliveries, &config), skipped })
+}
+
+/// The largest amount up to `requested` that `verdict` allows; allowing is monotone in the amount
+fn largest_allowed(requested: u64, verdict: &impl Fn(u64) -> Result<TransferVerdict, Error>) -> Result<u64, Error> {
+    let (mut allowed, mut rejected) = (0, requested);
+    while rejected - allowed > 1 {
+        let middle = allowed + (rejected - allowed) / 2;
+        match verdict(middle)? {
+            TransferVerdict::Allowed { .. } => allowed = middle,
+            TransferVerdict::Rejected { .. } => rejected = middle,
+        }
+    }
+    Ok(allowed)
+}
+
+/// An initialized, empty token account of `mint` owned by `owner`
+fn empty_account(mint: &Pubkey, owner: &Pubkey) -> Vec<u8> {
+    let account = Account { mint: *mint, owner: *owner, state: AccountState::Initialized, ..Account::default() };
+    let mut data = vec![0; Account::LEN];
+    Account::pack(account, &mut data).expect("an account packs into its own length");
+    data
+}
+
+/// Whether `instructions` fit into one transaction paid for by `payer`
+fn fits(instructions: &[Instruction], payer: &Pubkey) -> bool {
+    let message = Message::new(instructions, Some(payer));
+    if message.account_keys.len() > MAX_TX_ACCOUNT_LOCKS {
+        return false;
+    }
+    // A transaction is its signatures, behind their count as a compact-u16, then the message; any count
+    // small enough to fit the packet at all takes one byte
+    let signatures = usize::from(message.header.num_required_signatures) * SIGNATURE_SIZE;
+    bincode::serialized_size(&message).map_or(false, |size| 1 + signatures + size as usize <= PACKET_DATA_SIZE)
+}
+
+/// `deliveries` grouped into as few transactions as fit, in order
+fn pack(deliveries: Vec<Delivery>, config: &AirdropConfig) -> Vec<Vec<Delivery>> {
+    let mut transactions: Vec<Vec<Delivery>> = Vec::new();
+    let mut instructions = Vec::new();
+    for delivery in deliveries {
+        instructions.extend(delivery.instructions.iter().cloned());
+        match transactions.last_mut() {
+            Some(transaction) if fits(&instructions, &config.payer) => transaction.push(delivery),
+            _ => {
+                instructions = delivery.instructions.clone();
+                transactions.push(vec![delivery]);
+            }
+        }
+    }
+    transactions
+}
+
+/// Owners a previous run of the airdrop already delivered to, one `<owner> <signature>` per line
+#[cfg(feature = "rpc")]
+fn read_checkpoint(path: &Path) -> Result<HashSet<Pubkey>, Error> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(error) => return Err(error.into()),
+    };
+    let mut delivered = HashSet::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let owner = line.split_whitespace().next().and_then(|owner| owner.parse().ok());
+        delivered.insert(owner.ok_or(ProgramError::InvalidArgument)?);
+    }
+    Ok(delivered)
+}
+
+/// Send `plan`'s transactions in order, signed by `payer` and the source `authority`, recording each
+/// delivery in the `checkpoint` file as it lands. Owners the checkpoint already lists are left out, so an
+/// interrupted airdrop is resumed by running it again with the same file, even from a fresh plan. A
+/// transaction is sent up to three times; one the program rejects stops the airdrop.
+#[cfg(feature = "rpc")]
+pub async fn execute(
+    rpc: &RpcClient,
+    plan: &AirdropPlan,
+    payer: &Keypair,
+    authority: &Keypair,
+    checkpoint: &Path,
+) -> Result<Vec<Signature>, Error> {
+    let delivered = read_checkpoint(checkpoint)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(checkpoint)?;
+    let mut signatures = Vec::with_capacity(plan.transactions.len());
+    for transaction in &plan.transactions {
+        let pending: Vec<_> = transaction.iter().filter(|delivery| !delivered.contains(&delivery.owner)).collect();
+        if pending.is_empty() {
+            continue;
+        }
+        let instructions: Vec<_> = pending.iter().flat_map(|delivery| delivery.instructions.iter().cloned()).collect();
+
+        let mut attempt = 1;
+        let signature = loop {
+            let blockhash = rpc.get_latest_blockhash().await?;
+            let transaction = Transaction::new_signed_with_payer(
+                &instructions,
+                Some(&payer.pubkey()),
+                &[payer, authority],
+                blockhash,
+            );
+            let error = match rpc.send_and_confirm_transaction(&transaction).await {
+                Ok(signature) => break signature,
+                Err(error) => error,
+            };
+            // A timeout can hide a transaction that landed after all
+            let signature = transaction.signatures[0];
+            if let Ok(Some(Ok(()))) = rpc.get_signature_status(&signature).await {
+                break signature;
+            }
+            if error.get_transaction_error().is_some() || attempt == SEND_ATTEMPTS {
+                return Err(error.into());
+            }
+            attempt += 1;
+        };
+
+        for delivery in pending {
+            writeln!(file, "{} {}", delivery.owner, signature)?;
+        }
+        file.flush()?;
+        signatures.push(signature);
+    }
+    Ok(signatures)
+}
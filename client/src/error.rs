@@ -0,0 +1,71 @@
+use std::fmt;
+
+use anchor_lang::solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use one_kx_hook::HookError;
+#[cfg(feature = "rpc")]
+use solana_client::client_error::ClientError;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The instruction isn't a Token-2022 `transfer_checked` of the mint
+    NotATransfer,
+    /// An account the client reads doesn't exist
+    AccountNotFound(Pubkey),
+    /// Account data that doesn't unpack, or an extra account that can't be resolved
+    Program(ProgramError),
+    /// The program's own code refused the accounts, as it would on-chain
+    Anchor(anchor_lang::error::Error),
+    #[cfg(feature = "rpc")]
+    Rpc(Box<ClientError>),
+    /// The airdrop's source holds less than it hands out
+    InsufficientFunds { needed: u64, available: u64 },
+    /// An airdrop recipient the `Fail` policy refuses to plan around
+    RecipientRejected { owner: Pubkey, reason: HookError },
+    /// The airdrop checkpoint file can't be read or written
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotATransfer => write!(f, "not a Token-2022 transfer_checked of the mint"),
+            Self::AccountNotFound(address) => write!(f, "account {} not found", address),
+            Self::Program(error) => write!(f, "{}", error),
+            Self::Anchor(error) => write!(f, "{}", error),
+            #[cfg(feature = "rpc")]
+            Self::Rpc(error) => write!(f, "rpc: {}", error),
+            Self::InsufficientFunds { needed, available } => {
+                write!(f, "the source holds {} of the {} to hand out", available, needed)
+            }
+            Self::RecipientRejected { owner, reason } => write!(f, "{} would be rejected: {}", owner, reason),
+            Self::Io(error) => write!(f, "checkpoint: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ProgramError> for Error {
+    fn from(error: ProgramError) -> Self {
+        Self::Program(error)
+    }
+}
+
+impl From<anchor_lang::error::Error> for Error {
+    fn from(error: anchor_lang::error::Error) -> Self {
+        Self::Anchor(error)
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl From<ClientError> for Error {
+    fn from(error: ClientError) -> Self {
+        Self::Rpc(Box::new(error))
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
@@ -0,0 +1,157 @@
+// Decoding the program's events from a transaction.
+//
+// Governance instructions emit through `emit_cpi!`, so their events are the data of a self-CPI after
+// `EVENT_IX_TAG_LE`; the transfer hook's own events are `Program data:` log lines. Either way the payload
+// is an 8 byte discriminator and the Borsh encoded fields. Unknown discriminators and payloads that don't
+// decode are skipped, so a decoder older than the program misses new events instead of failing.
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::{AnchorDeserialize, Discriminator, Event};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+pub use one_kx_hook::events::*;
+
+use crate::ID;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+macro_rules! hook_events {
+    ($($event:ident),* $(,)?) => {
+        /// Any event the program emits
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum HookEvent {
+            $($event($event),)*
+        }
+
+        impl HookEvent {
+            /// Every event's name, in declaration order
+            pub const NAMES: &'static [&'static str] = &[$(stringify!($event)),*];
+
+            /// An event payload: the discriminator, then the fields
+            pub fn decode(payload: &[u8]) -> Option<Self> {
+                let (discriminator, mut fields) = (payload.get(..8)?, payload.get(8..)?);
+                $(
+                    if discriminator == $event::DISCRIMINATOR {
+                        return $event::deserialize(&mut fields).ok().map(Self::$event);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$event(_) => stringify!($event),)*
+                }
+            }
+
+            /// The payload `decode` reads
+            pub fn data(&self) -> Vec<u8> {
+                match self {
+                    $(Self::$event(event) => Event::data(event),)*
+                }
+            }
+        }
+    };
+}
+
+hook_events!(
+    WalletCapUpdateProposed,
+    WalletCapUpdated,
+    WalletCapUpdateCanceled,
+    WalletCapUpdateVetoed,
+    WalletCapUpdateExpired,
+    ProposalExpiryUpdated,
+    MinCapFloorRaised,
+    VetoAuthorityUpdateProposed,
+    VetoAuthorityUpdated,
+    VetoAuthorityUpdateCanceled,
+    CapUpdateRoleUpdated,
+    GovernanceTransferProposed,
+    GovernanceAuthorityUpdated,
+    GovernanceTransferCanceled,
+    ExemptEntryAdded,
+    ExemptEntryRemoved,
+    ExemptRegistryPruned,
+    TrustedProgramUpdated,
+    DevWalletUpdateProposed,
+    DevWalletUpdated,
+    TimelockUpdateProposed,
+    TimelockUpdated,
+    TimelockUpdateCanceled,
+    DevWalletUpdateCanceled,
+    DevExemptionExpiryUpdated,
+    DevExemptionExpired,
+    AirdropAllowanceUpdated,
+    PermitSignerUpdated,
+    PermitRedeemed,
+    CircuitBreakerUpdated,
+    CircuitBreakerTriggered,
+    CircuitBreakerCleared,
+    MaxHoldersUpdated,
+    GovernanceMembersUpdated,
+    GovernanceActionProposed,
+    GovernanceActionApproved,
+    GuardianUpdated,
+    PauseUpdated,
+    ConfigFrozen,
+    GovernanceRenounced,
+    ConfigMigrated,
+    ExtraAccountMetasUpdated,
+    ExtraAccountMetaListClosed,
+    ConfigClosed,
+    DevWalletIsGovernanceAuthority,
+    HookTransferChecked,
+    TransferEventsUpdated,
+    SnapshotTaken,
+    SnapshotClosed,
+    HookDisableProposed,
+    HookDisableCanceled,
+    HookPermanentlyDisabled,
+    LockupCreated,
+    LockupShortened,
+    MaxCapChangeTightened,
+    StakingProgramUpdated,
+    StakingVaultRegistered,
+    BridgeCustodyRegistered,
+    LaunchDetected,
+    LaunchDetectionReset,
+    SniperFlagged,
+    SniperFlagCleared,
+    SnipeWindowUpdated,
+    WalletOwnersRequiredUpdated,
+    MemoThresholdUpdated,
+    DevOutflowLimitLowered,
+    SetupFinalized,
+    SurplusLamportsWithdrawn,
+);
+
+/// The `emit!` events of a transaction's log, from the `Program data:` lines logged while the program
+/// itself was executing; lines other programs log are never decoded as its events
+pub fn parse_logs(logs: &[String]) -> Vec<HookEvent> {
+    let program = ID.to_string();
+    let mut invoked = Vec::new();
+    let mut events = Vec::new();
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if invoked.last() == Some(&program.as_str()) {
+                events.extend(BASE64.decode(data).ok().as_deref().and_then(HookEvent::decode));
+            }
+        } else if let Some(log) = log.strip_prefix("Program ") {
+            let mut words = log.split(' ');
+            match (words.next(), words.next()) {
+                (Some(program_id), Some("invoke")) => invoked.push(program_id),
+                (Some(_), Some("success" | "failed:")) => {
+                    invoked.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+/// The `emit_cpi!` event carried by the data of an inner instruction to the program, or None if it's any
+/// other instruction. Only pass instructions whose program is the hook: the data alone doesn't prove it.
+pub fn parse_event_cpi(data: &[u8]) -> Option<HookEvent> {
+    data.strip_prefix(&EVENT_IX_TAG_LE).and_then(HookEvent::decode)
+}
@@ -0,0 +1,84 @@
+// Completing a Token-2022 `transfer_checked` of a hooked mint with the accounts the hook needs.
+//
+// Token-2022 only finds the hook's accounts among the instruction's own: a bare `transfer_checked`
+// fails before the hook runs. The accounts are resolved from the mint's extra account meta list the way
+// SPL's offchain helper does, and appended in the same order: the resolved accounts, the hook program,
+// then the meta list.
+
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+#[cfg(feature = "rpc")]
+use solana_client::nonblocking::rpc_client::RpcClient;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_token_2022::instruction::TokenInstruction;
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+use spl_type_length_value::state::TlvStateBorrowed;
+
+use crate::{extra_metas_pda, Error, ID};
+
+/// Source, mint, destination and authority of `transfer`, a `transfer_checked` of `mint`, with its amount
+fn transfer_accounts(transfer: &Instruction, mint: &Pubkey) -> Result<([Pubkey; 4], u64), Error> {
+    let Ok(TokenInstruction::TransferChecked { amount, .. }) = TokenInstruction::unpack(&transfer.data) else {
+        return Err(Error::NotATransfer);
+    };
+    match transfer.accounts.as_slice() {
+        [source, transfer_mint, destination, authority, ..]
+            if transfer.program_id == spl_token_2022::id() && transfer_mint.pubkey == *mint =>
+        {
+            Ok(([source.pubkey, *mint, destination.pubkey, authority.pubkey], amount))
+        }
+        _ => Err(Error::NotATransfer),
+    }
+}
+
+/// Fetch what resolving the hook's accounts reads and append them to `transfer`, a `transfer_checked`
+/// of `mint`
+#[cfg(feature = "rpc")]
+pub async fn add_extra_accounts_for_transfer(
+    rpc: &RpcClient,
+    transfer: &mut Instruction,
+    mint: Pubkey,
+) -> Result<(), Error> {
+    let ([source, _, destination, _], _) = transfer_accounts(transfer, &mint)?;
+    let addresses = [extra_metas_pda(&mint), source, destination];
+    let mut fetched = Vec::with_capacity(addresses.len());
+    for (address, account) in addresses.iter().zip(rpc.get_multiple_accounts(&addresses).await?) {
+        fetched.push((*address, account.ok_or(Error::AccountNotFound(*address))?.data));
+    }
+    let token_accounts: Vec<_> = fetched[1..].iter().map(|(address, data)| (*address, data.as_slice())).collect();
+    add_extra_accounts_for_transfer_offline(transfer, mint, &fetched[0].1, &token_accounts)
+}
+
+/// `add_extra_accounts_for_transfer` over accounts fetched beforehand: `meta_list_data` is the mint's meta
+/// list account, and `token_accounts` holds at least the transfer's source and destination, whose owners
+/// seed the holder and lockup PDAs
+pub fn add_extra_accounts_for_transfer_offline(
+    transfer: &mut Instruction,
+    mint: Pubkey,
+    meta_list_data: &[u8],
+    token_accounts: &[(Pubkey, &[u8])],
+) -> Result<(), Error> {
+    let ([source, mint, destination, authority], amount) = transfer_accounts(transfer, &mint)?;
+    let meta_list = extra_metas_pda(&mint);
+    let state = TlvStateBorrowed::unpack(meta_list_data)?;
+    let extra_metas = ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&state)?;
+    let execute_data = TransferHookInstruction::Execute { amount }.pack();
+
+    // The accounts `execute` is called with, growing as they resolve: later seeds may index earlier ones
+    let data_of = |key: &Pubkey| token_accounts.iter().find(|(address, _)| address == key).map(|(_, data)| *data);
+    let mut execute_accounts = vec![source, mint, destination, authority, meta_list];
+    let mut resolved = Vec::with_capacity(extra_metas.data().len());
+    for extra_meta in extra_metas.data() {
+        let meta = extra_meta
+            .resolve(&execute_data, &ID, |index| execute_accounts.get(index).map(|key| (key, data_of(key))))?;
+        execute_accounts.push(meta.pubkey);
+        resolved.push(meta);
+    }
+
+    transfer.accounts.extend(resolved);
+    transfer.accounts.push(AccountMeta::new_readonly(ID, false));
+    transfer.accounts.push(AccountMeta::new_readonly(meta_list, false));
+    Ok(())
+}
@@ -0,0 +1,197 @@
+// Setting up a hooked mint in one transaction: the Token-2022 mint with its transfer hook pointing here,
+// its config, governance log, config extension, sniper log and extra account meta list, the check that
+// finalizes the setup, and the launch wallet cap.
+//
+// Nothing half-made is left behind if a step fails, and no one else can claim the config in between, as
+// the mint is created and initialized in the same transaction. The payer is the mint authority and the
+// hook and metadata pointer authority, and pays for every account.
+
+use std::fmt;
+
+use anchor_lang::solana_program::{instruction::Instruction, pubkey::Pubkey, system_instruction};
+use one_kx_hook::logic::validate_cap_proposal;
+use one_kx_hook::{CapKind, CapUnit, HookError, WALLET_CAP_RAW};
+#[cfg(feature = "rpc")]
+use solana_client::nonblocking::rpc_client::RpcClient;
+#[cfg(feature = "rpc")]
+use solana_sdk::{
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use spl_token_2022::{extension::ExtensionType, state::Mint};
+
+use crate::{args, config_pda, execute_immediate_cap_increase, extra_metas_pda, Error, Governance};
+
+/// The timelock a launched config starts with, the program's own 48 hours
+pub const LAUNCH_TIMELOCK_SECONDS: u32 = 48 * 60 * 60;
+
+#[derive(Clone, Copy, Debug)]
+pub struct LaunchParams {
+    pub decimals: u8,
+    /// The wallet cap in raw units. Launching above the program's default raises it right away, which the
+    /// governance authority signs, so it has to be the payer; caps below it need a timelocked proposal
+    pub cap: u64,
+    pub dev_wallet: Pubkey,
+    pub governance_authority: Pubkey,
+    /// Where the mint's metadata lives, recorded in a metadata pointer extension
+    pub metadata_pointer: Option<Pubkey>,
+}
+
+/// One instruction of a launch, with what it does
+pub struct LaunchStep {
+    pub description: String,
+    pub instruction: Instruction,
+}
+
+/// The instructions of a launch, in the order they run; printing it shows the plan without sending it
+pub struct LaunchPlan {
+    pub mint: Pubkey,
+    pub payer: Pubkey,
+    /// Lamports the mint account is funded with
+    pub mint_rent: u64,
+    pub steps: Vec<LaunchStep>,
+}
+
+impl LaunchPlan {
+    pub fn instructions(&self) -> Vec<Instruction> {
+        self.steps.iter().map(|step| step.instruction.clone()).collect()
+    }
+}
+
+impl fmt::Display for LaunchPlan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Launch of {} paid for by {}, in one transaction:", self.mint, self.payer)?;
+        for (index, step) in self.steps.iter().enumerate() {
+            writeln!(f, "  {}. {}", index + 1, step.description)?;
+        }
+        Ok(())
+    }
+}
+
+fn extensions(params: &LaunchParams) -> Vec<ExtensionType> {
+    let mut extensions = vec![ExtensionType::TransferHook];
+    if params.metadata_pointer.is_some() {
+        extensions.push(ExtensionType::MetadataPointer);
+    }
+    extensions
+}
+
+/// The size of the mint account `params` launches
+pub fn mint_space(params: &LaunchParams) -> Result<usize, Error> {
+    Ok(ExtensionType::try_calculate_account_len::<Mint>(&extensions(params))?)
+}
+
+/// Plan the launch of `mint` as `plan_offline` does, fetching the mint account's rent
+#[cfg(feature = "rpc")]
+pub async fn plan(rpc: &RpcClient, mint: &Pubkey, payer: &Pubkey, params: &LaunchParams) -> Result<LaunchPlan, Error> {
+    let mint_rent = rpc.get_minimum_balance_for_rent_exemption(mint_space(params)?).await?;
+    plan_offline(mint, payer, mint_rent, params)
+}
+
+/// The launch of `mint` as `params` describe it, funding the mint account with `mint_rent` lamports
+pub fn plan_offline(mint: &Pubkey, payer: &Pubkey, mint_rent: u64, params: &LaunchParams) -> Result<LaunchPlan, Error> {
+    // Refused up front rather than by the transaction, as the program would
+    validate_cap_proposal(CapKind::Wallet, params.cap)?;
+    if params.cap < WALLET_CAP_RAW {
+        return Err(Error::Anchor(HookError::TimelockRequired.into()));
+    }
+    if params.cap > WALLET_CAP_RAW && params.governance_authority != *payer {
+        return Err(Error::Anchor(HookError::UnauthorizedGovernance.into()));
+    }
+
+    let token_program = spl_token_2022::id();
+    let space = mint_space(params)?;
+    let mut steps = vec![LaunchStep {
+        description: format!("Create the mint account, {} bytes funded with {} lamports", space, mint_rent),
+        instruction: system_instruction::create_account(payer, mint, mint_rent, space as u64, &token_program),
+    }];
+    steps.push(LaunchStep {
+        description: format!("Point the mint's transfer hook at {}", crate::ID),
+        instruction: spl_token_2022::extension::transfer_hook::instruction::initialize(
+            &token_program,
+            mint,
+            Some(*payer),
+            Some(crate::ID),
+        )?,
+    });
+    if let Some(metadata) = params.metadata_pointer {
+        steps.push(LaunchStep {
+            description: format!("Point the mint's metadata at {}", metadata),
+            instruction: spl_token_2022::extension::metadata_pointer::instruction::initialize(
+                &token_program,
+                mint,
+                Some(*payer),
+                Some(metadata),
+            )?,
+        });
+    }
+    steps.push(LaunchStep {
+        description: format!("Initialize the mint with {} decimals and mint authority {}", params.decimals, payer),
+        instruction: spl_token_2022::instruction::initialize_mint2(&token_program, mint, payer, None, params.decimals)?,
+    });
+
+    let initialize = args::Initialize {
+        dev_wallet: params.dev_wallet,
+        governance_authority: params.governance_authority,
+        cap_unit: CapUnit::Raw,
+        timelock_seconds: LAUNCH_TIMELOCK_SECONDS,
+        min_cap_floor: 0,
+        max_cap_change_bps: 0,
+        strict_authorities: false,
+        allow_confidential: false,
+    };
+    steps.push(LaunchStep {
+        description: format!(
+            "Create the config {}, dev wallet {} and governance authority {}",
+            config_pda(mint),
+            params.dev_wallet,
+            params.governance_authority
+        ),
+        instruction: crate::initialize(mint, payer, payer, initialize),
+    });
+    steps.push(LaunchStep {
+        description: format!("Create the governance log {}", crate::governance_log_pda(mint)),
+        instruction: crate::init_governance_log(mint, payer),
+    });
+    steps.push(LaunchStep {
+        description: format!("Create the config extension {}", crate::config_extension_pda(mint)),
+        instruction: crate::init_config_extension(mint, payer),
+    });
+    steps.push(LaunchStep {
+        description: format!("Create the sniper log {}", crate::sniper_log_pda(mint)),
+        instruction: crate::init_sniper_log(mint, payer),
+    });
+    steps.push(LaunchStep {
+        description: format!("Create the extra account meta list {}", extra_metas_pda(mint)),
+        instruction: crate::init_extra_account_meta_list(mint, payer),
+    });
+    steps.push(LaunchStep {
+        description: "Finalize the setup so the hook lets transfers through".to_string(),
+        instruction: crate::finalize_setup(mint),
+    });
+    if params.cap > WALLET_CAP_RAW {
+        let increase = args::ExecuteImmediateCapIncrease { new_cap: params.cap };
+        steps.push(LaunchStep {
+            description: format!("Raise the wallet cap from {} to {}", WALLET_CAP_RAW, params.cap),
+            instruction: execute_immediate_cap_increase(mint, &Governance::authority(*payer), increase),
+        });
+    }
+
+    Ok(LaunchPlan { mint: *mint, payer: *payer, mint_rent, steps })
+}
+
+/// Launch a new hooked mint as `params` describe it, returning its address and the launch's signature
+#[cfg(feature = "rpc")]
+pub async fn create_hooked_mint(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    params: &LaunchParams,
+) -> Result<(Pubkey, Signature), Error> {
+    let mint = Keypair::new();
+    let plan = plan(rpc, &mint.pubkey(), &payer.pubkey(), params).await?;
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let transaction =
+        Transaction::new_signed_with_payer(&plan.instructions(), Some(&payer.pubkey()), &[payer, &mint], blockhash);
+    let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+    Ok((mint.pubkey(), signature))
+}
@@ -0,0 +1,983 @@
+// Off-chain client for the one_kx_hook program: PDA derivation and an instruction builder per program
+// instruction.
+//
+// Builders take the program's own Anchor argument structs, re-exported as `args`, so a call carries
+// exactly the fields the instruction deserializes and its data is the discriminator Anchor dispatches
+// on. Accounts are derived from the mint wherever the program derives them; only signers, recipients
+// and the accounts a caller picks are passed in. `add_extra_accounts_for_transfer` completes a
+// Token-2022 `transfer_checked` of a hooked mint with the hook's accounts, `check_transfer` tells
+// whether one will bounce, `events` decodes what the program emits, `launch` sets up a new hooked mint,
+// `airdrop` hands one out to many wallets within the cap and `registered_mints` lists the mints set up
+// through the factory. What fetches or sends is behind the `rpc` feature; without it the crate builds
+// for wasm32-unknown-unknown.
+
+use anchor_lang::solana_program::{
+    bpf_loader_upgradeable, instruction::Instruction, pubkey::Pubkey, system_program, sysvar,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use one_kx_hook::accounts;
+
+pub mod airdrop;
+mod error;
+pub mod events;
+mod extra_accounts;
+pub mod launch;
+mod preflight;
+mod registry;
+
+pub use error::Error;
+pub use extra_accounts::*;
+pub use one_kx_hook::instruction as args;
+pub use one_kx_hook::{
+    CapKind, CapUnit, CapUpdateRole, ExemptKind, GlobalConfig, GovernanceAction, MintRegistryEntry, ID,
+};
+pub use preflight::*;
+pub use registry::*;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &ID).0
+}
+
+/// The mint's `HookConfig`
+pub fn config_pda(mint: &Pubkey) -> Pubkey {
+    find(&[b"config", mint.as_ref()])
+}
+
+/// The extra account meta list Token-2022 resolves the hook's accounts from
+pub fn extra_metas_pda(mint: &Pubkey) -> Pubkey {
+    find(&[b"extra-account-metas", mint.as_ref()])
+}
+
+pub fn exempt_registry_pda(mint: &Pubkey) -> Pubkey {
+    find(&[b"exempt-registry", mint.as_ref()])
+}
+
+pub fn used_nonces_pda(mint: &Pubkey) -> Pubkey {
+    find(&[b"used-nonces", mint.as_ref()])
+}
+
+pub fn governance_log_pda(mint: &Pubkey) -> Pubkey {
+    find(&[b"gov-log", mint.as_ref()])
+}
+
+/// The mint's `ConfigExtension`, where the hook records the launch
+pub fn config_extension_pda(mint: &Pubkey) -> Pubkey {
+    find(&[b"config-ext", mint.as_ref()])
+}
+
+/// The mint's `SniperLog`, listing owners who bought within the snipe window
+pub fn sniper_log_pda(mint: &Pubkey) -> Pubkey {
+    find(&[b"sniper-log", mint.as_ref()])
+}
+
+/// `owner`'s `HolderState`, keyed by the wallet rather than a token account
+pub fn holder_state_pda(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"holder", mint.as_ref(), owner.as_ref()])
+}
+
+/// `owner`'s `TrustedOwner` record, which exempts the token accounts of a trusted program's PDA
+pub fn trusted_owner_pda(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"trusted-owner", mint.as_ref(), owner.as_ref()])
+}
+
+/// `owner`'s `Lockup`, keyed by the wallet rather than a token account
+pub fn lockup_pda(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"lockup", mint.as_ref(), owner.as_ref()])
+}
+
+/// The `index`th holder snapshot; the next one taken is at the config's `snapshot_count`
+pub fn snapshot_pda(mint: &Pubkey, index: u64) -> Pubkey {
+    find(&[b"snapshot", mint.as_ref(), &index.to_le_bytes()])
+}
+
+/// The multisig approval of the action with `action_hash`, see `GovernanceAction::hash`
+pub fn governance_approval_pda(mint: &Pubkey, action_hash: &[u8; 32]) -> Pubkey {
+    find(&[b"governance-approval", mint.as_ref(), action_hash.as_ref()])
+}
+
+/// The deployment's singleton `GlobalConfig`
+pub fn global_config_pda() -> Pubkey {
+    find(&[b"global"])
+}
+
+/// The `page`th `MintRegistry` page; mints registered now go on the global config's `current_page()`
+pub fn mint_registry_pda(page: u32) -> Pubkey {
+    find(&[b"mint-registry", &page.to_le_bytes()])
+}
+
+/// The PDA that signs the program's event self-CPIs, passed to every instruction that emits through `emit_cpi!`
+pub fn event_authority_pda() -> Pubkey {
+    find(&[b"__event_authority"])
+}
+
+fn instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction { program_id: ID, accounts: accounts.to_account_metas(None), data: args.data() }
+}
+
+/// Who a governed instruction is authorized by: the governance authority's signature, or under multisig
+/// governance any signer together with a threshold approval of the exact action
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Governance {
+    pub authority: Pubkey,
+    pub approval: Option<Pubkey>,
+}
+
+impl Governance {
+    /// Signed by the config's single governance authority
+    pub fn authority(authority: Pubkey) -> Self {
+        Self { authority, approval: None }
+    }
+
+    /// Signed by `signer` on the strength of the approval of `action` opened in governance `epoch`
+    pub fn approved(signer: Pubkey, mint: &Pubkey, action: &GovernanceAction, epoch: u64) -> Self {
+        Self { authority: signer, approval: Some(governance_approval_pda(mint, &action.hash(mint, epoch))) }
+    }
+}
+
+/// The accounts of one `transfer_checked`, from which the hook's own accounts are derived the way the
+/// extra account meta list resolves them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    /// The signer of the transfer: the source's owner or its delegate
+    pub authority: Pubkey,
+    /// Owner recorded in the source token account
+    pub source_owner: Pubkey,
+    /// Owner recorded in the destination token account
+    pub destination_owner: Pubkey,
+}
+
+impl Transfer {
+    fn execute_accounts(&self) -> accounts::Execute {
+        accounts::Execute {
+            source: self.source,
+            mint: self.mint,
+            destination: self.destination,
+            owner: self.authority,
+            extra_account_meta_list: extra_metas_pda(&self.mint),
+            config: config_pda(&self.mint),
+            exempt_registry: exempt_registry_pda(&self.mint),
+            instructions_sysvar: sysvar::instructions::ID,
+            used_nonces: used_nonces_pda(&self.mint),
+            source_holder: holder_state_pda(&self.mint, &self.source_owner),
+            destination_holder: holder_state_pda(&self.mint, &self.destination_owner),
+            source_lockup: lockup_pda(&self.mint, &self.source_owner),
+            config_extension: config_extension_pda(&self.mint),
+            sniper_log: sniper_log_pda(&self.mint),
+            destination_trusted_owner: trusted_owner_pda(&self.mint, &self.destination_owner),
+        }
+    }
+}
+
+/// A builder for one of the many governed instructions over the config / authority / mint / approval layout
+macro_rules! governed {
+    ($(#[$doc:meta])* $name:ident, $accounts:ident, $args:ident {}) => {
+        $(#[$doc])*
+        pub fn $name(mint: &Pubkey, governance: &Governance) -> Instruction {
+            instruction(governed!(@accounts $accounts, mint, governance), args::$args {})
+        }
+    };
+    ($(#[$doc:meta])* $name:ident, $accounts:ident, $args:ident) => {
+        $(#[$doc])*
+        pub fn $name(mint: &Pubkey, governance: &Governance, args: args::$args) -> Instruction {
+            instruction(governed!(@accounts $accounts, mint, governance), args)
+        }
+    };
+    (@accounts $accounts:ident, $mint:ident, $governance:ident) => {
+        accounts::$accounts {
+            config: config_pda($mint),
+            governance_authority: $governance.authority,
+            mint: *$mint,
+            approval: $governance.approval,
+            event_authority: event_authority_pda(),
+            program: ID,
+        }
+    };
+}
+
+/// Create the mint's config, signed by its mint authority
+pub fn initialize(mint: &Pubkey, payer: &Pubkey, mint_authority: &Pubkey, args: args::Initialize) -> Instruction {
+    let accounts = accounts::Initialize {
+        payer: *payer,
+        config: config_pda(mint),
+        mint: *mint,
+        mint_authority: *mint_authority,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Create the deployment's `GlobalConfig`, signed by the program's upgrade authority
+pub fn init_global_config(payer: &Pubkey, upgrade_authority: &Pubkey, args: args::InitGlobalConfig) -> Instruction {
+    let accounts = accounts::InitGlobalConfig {
+        payer: *payer,
+        global_config: global_config_pda(),
+        upgrade_authority: *upgrade_authority,
+        program_data: Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::ID).0,
+        system_program: system_program::ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Create registry page `page`, which has to be the global config's `current_page()`
+pub fn init_mint_registry_page(payer: &Pubkey, page: u32) -> Instruction {
+    let accounts = accounts::InitMintRegistryPage {
+        payer: *payer,
+        global_config: global_config_pda(),
+        mint_registry: mint_registry_pda(page),
+        system_program: system_program::ID,
+    };
+    instruction(accounts, args::InitMintRegistryPage {})
+}
+
+/// Create the mint's config and record it on registry page `page`, the global config's `current_page()`,
+/// signed by the mint authority or the factory admin
+pub fn initialize_for_mint(
+    mint: &Pubkey,
+    payer: &Pubkey,
+    initializer: &Pubkey,
+    page: u32,
+    args: args::InitializeForMint,
+) -> Instruction {
+    let accounts = accounts::InitializeForMint {
+        payer: *payer,
+        config: config_pda(mint),
+        mint: *mint,
+        initializer: *initializer,
+        global_config: global_config_pda(),
+        mint_registry: mint_registry_pda(page),
+        system_program: system_program::ID,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Change the parameters `initialize_for_mint` falls back on, signed by the factory admin
+pub fn set_global_defaults(factory_admin: &Pubkey, args: args::SetGlobalDefaults) -> Instruction {
+    let accounts = accounts::SetGlobalDefaults { global_config: global_config_pda(), factory_admin: *factory_admin };
+    instruction(accounts, args)
+}
+
+/// Re-sync the mint's parameters selected by `args.fields` (`GlobalConfig::ADOPT_*`) with the global
+/// defaults
+pub fn adopt_global_defaults(mint: &Pubkey, governance: &Governance, args: args::AdoptGlobalDefaults) -> Instruction {
+    let accounts = accounts::AdoptGlobalDefaults {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        global_config: global_config_pda(),
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// The hook's Anchor-dispatched transfer check, with the token program after the resolved accounts
+pub fn transfer_hook(transfer: &Transfer, args: args::TransferHook) -> Instruction {
+    let execute = transfer.execute_accounts();
+    let accounts = accounts::TransferHook {
+        source: execute.source,
+        mint: execute.mint,
+        destination: execute.destination,
+        owner: execute.owner,
+        extra_account_meta_list: execute.extra_account_meta_list,
+        config: execute.config,
+        exempt_registry: execute.exempt_registry,
+        instructions_sysvar: execute.instructions_sysvar,
+        used_nonces: execute.used_nonces,
+        source_holder: execute.source_holder,
+        destination_holder: execute.destination_holder,
+        source_lockup: execute.source_lockup,
+        config_extension: execute.config_extension,
+        sniper_log: execute.sniper_log,
+        destination_trusted_owner: execute.destination_trusted_owner,
+        token_program: anchor_spl::token_2022::ID,
+    };
+    instruction(accounts, args)
+}
+
+/// `execute` through its Anchor discriminator, over the accounts Token-2022 passes for `transfer`.
+/// Token-2022 itself calls it through the transfer hook interface's discriminator.
+pub fn execute(transfer: &Transfer, args: args::Execute) -> Instruction {
+    instruction(transfer.execute_accounts(), args)
+}
+
+pub fn init_extra_account_meta_list(mint: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::InitExtraAccountMetaList {
+        payer: *payer,
+        extra_account_meta_list: extra_metas_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+    };
+    instruction(accounts, args::InitExtraAccountMetaList {})
+}
+
+pub fn update_extra_account_meta_list(mint: &Pubkey, governance: &Governance, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::UpdateExtraAccountMetaList {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        payer: *payer,
+        extra_account_meta_list: extra_metas_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::UpdateExtraAccountMetaList {})
+}
+
+pub fn finalize_setup(mint: &Pubkey) -> Instruction {
+    let accounts = accounts::FinalizeSetup {
+        config: config_pda(mint),
+        extra_account_meta_list: extra_metas_pda(mint),
+        mint: *mint,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::FinalizeSetup {})
+}
+
+pub fn close_extra_account_meta_list(
+    mint: &Pubkey,
+    governance: &Governance,
+    recipient: &Pubkey,
+    args: args::CloseExtraAccountMetaList,
+) -> Instruction {
+    let accounts = accounts::CloseExtraAccountMetaList {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        extra_account_meta_list: extra_metas_pda(mint),
+        recipient: *recipient,
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+fn cap_update_accounts(mint: &Pubkey, governance: &Governance) -> accounts::ProposeWalletCapUpdate {
+    accounts::ProposeWalletCapUpdate {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        mint: *mint,
+        governance_log: governance_log_pda(mint),
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    }
+}
+
+pub fn propose_wallet_cap_update(
+    mint: &Pubkey,
+    governance: &Governance,
+    args: args::ProposeWalletCapUpdate,
+) -> Instruction {
+    instruction(cap_update_accounts(mint, governance), args)
+}
+
+pub fn propose_cap_update(mint: &Pubkey, governance: &Governance, args: args::ProposeCapUpdate) -> Instruction {
+    instruction(cap_update_accounts(mint, governance), args)
+}
+
+pub fn cancel_wallet_cap_update(mint: &Pubkey, governance: &Governance) -> Instruction {
+    let accounts = accounts::CancelWalletCapUpdate {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        mint: *mint,
+        governance_log: governance_log_pda(mint),
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::CancelWalletCapUpdate {})
+}
+
+/// Execute a matured cap update, signed by any `executor` unless the config sets an executor authority
+pub fn execute_wallet_cap_update(mint: &Pubkey, executor: &Pubkey) -> Instruction {
+    let accounts = accounts::ExecuteWalletCapUpdate {
+        config: config_pda(mint),
+        executor: *executor,
+        mint: *mint,
+        governance_log: governance_log_pda(mint),
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::ExecuteWalletCapUpdate {})
+}
+
+pub fn sweep_expired_proposal(mint: &Pubkey) -> Instruction {
+    let accounts = accounts::SweepExpiredProposal {
+        config: config_pda(mint),
+        mint: *mint,
+        governance_log: governance_log_pda(mint),
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::SweepExpiredProposal {})
+}
+
+pub fn init_governance_log(mint: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::InitGovernanceLog {
+        payer: *payer,
+        config: config_pda(mint),
+        governance_log: governance_log_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+    };
+    instruction(accounts, args::InitGovernanceLog {})
+}
+
+pub fn init_config_extension(mint: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::InitConfigExtension {
+        payer: *payer,
+        config: config_pda(mint),
+        config_extension: config_extension_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+    };
+    instruction(accounts, args::InitConfigExtension {})
+}
+
+pub fn init_sniper_log(mint: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::InitSniperLog {
+        payer: *payer,
+        config: config_pda(mint),
+        sniper_log: sniper_log_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+    };
+    instruction(accounts, args::InitSniperLog {})
+}
+
+/// Read a page of the governance log as return data
+pub fn get_governance_log(mint: &Pubkey, args: args::GetGovernanceLog) -> Instruction {
+    let accounts = accounts::GetGovernanceLog { governance_log: governance_log_pda(mint), mint: *mint };
+    instruction(accounts, args)
+}
+
+/// Read the config as return data
+pub fn get_config(mint: &Pubkey) -> Instruction {
+    let accounts = accounts::GetConfig { config: config_pda(mint), mint: *mint };
+    instruction(accounts, args::GetConfig {})
+}
+
+/// Ask whether `destination` may receive `amount` now, as return data
+pub fn can_receive(mint: &Pubkey, destination: &Pubkey, args: args::CanReceive) -> Instruction {
+    let accounts = accounts::CanReceive {
+        config: config_pda(mint),
+        mint: *mint,
+        destination: *destination,
+        exempt_registry: exempt_registry_pda(mint),
+    };
+    instruction(accounts, args)
+}
+
+/// The same question as `can_receive`, answered as a two-byte `CheckTransferResult`; this is the
+/// instruction other programs CPI into, built here for simulating it off-chain
+pub fn check_transfer_instruction(mint: &Pubkey, destination: &Pubkey, args: args::CheckTransfer) -> Instruction {
+    let accounts = accounts::CheckTransfer {
+        config: config_pda(mint),
+        mint: *mint,
+        destination: *destination,
+        exempt_registry: exempt_registry_pda(mint),
+    };
+    instruction(accounts, args)
+}
+
+/// Accept a pending governance transfer, signed by the new authority
+pub fn accept_governance_authority(mint: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    let accounts = accounts::AcceptGovernanceAuthority {
+        config: config_pda(mint),
+        new_authority: *new_authority,
+        mint: *mint,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::AcceptGovernanceAuthority {})
+}
+
+/// Open the approval of `args.action`, signed by a governance member. `epoch` is the config's current
+/// `governance_epoch`, which the approval's address commits to.
+pub fn propose_action(
+    mint: &Pubkey,
+    payer: &Pubkey,
+    member: &Pubkey,
+    epoch: u64,
+    args: args::ProposeAction,
+) -> Instruction {
+    let accounts = accounts::ProposeAction {
+        payer: *payer,
+        config: config_pda(mint),
+        member: *member,
+        approval: governance_approval_pda(mint, &args.action.hash(mint, epoch)),
+        mint: *mint,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Add a governance member's approval to the action with `args.action_hash`
+pub fn approve_action(mint: &Pubkey, member: &Pubkey, args: args::ApproveAction) -> Instruction {
+    let accounts = accounts::ApproveAction {
+        config: config_pda(mint),
+        member: *member,
+        approval: governance_approval_pda(mint, &args.action_hash),
+        mint: *mint,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Close a spent or stale `approval`, refunding the `payer` that opened it
+pub fn close_governance_approval(mint: &Pubkey, approval: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts =
+        accounts::CloseGovernanceApproval { config: config_pda(mint), approval: *approval, payer: *payer, mint: *mint };
+    instruction(accounts, args::CloseGovernanceApproval {})
+}
+
+pub fn init_exempt_registry(
+    mint: &Pubkey,
+    governance: &Governance,
+    payer: &Pubkey,
+    args: args::InitExemptRegistry,
+) -> Instruction {
+    let accounts = accounts::InitExemptRegistry {
+        payer: *payer,
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        exempt_registry: exempt_registry_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+        approval: governance.approval,
+    };
+    instruction(accounts, args)
+}
+
+pub fn add_exempt_entry(
+    mint: &Pubkey,
+    governance: &Governance,
+    payer: &Pubkey,
+    args: args::AddExemptEntry,
+) -> Instruction {
+    let accounts = accounts::AddExemptEntry {
+        payer: *payer,
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        exempt_registry: exempt_registry_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// `rent_recipient` must be the one the registry was initialized with
+pub fn remove_exempt_entry(
+    mint: &Pubkey,
+    governance: &Governance,
+    rent_recipient: &Pubkey,
+    args: args::RemoveExemptEntry,
+) -> Instruction {
+    let accounts = accounts::RemoveExemptEntry {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        exempt_registry: exempt_registry_pda(mint),
+        rent_recipient: *rent_recipient,
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Register `vault`, a token account of the mint owned by `vault_owner`, a PDA account of the staking program
+/// that `args.seeds` derive, as a staking vault
+pub fn register_staking_vault(
+    mint: &Pubkey,
+    governance: &Governance,
+    payer: &Pubkey,
+    vault: &Pubkey,
+    vault_owner: &Pubkey,
+    args: args::RegisterStakingVault,
+) -> Instruction {
+    let accounts = accounts::RegisterStakingVault {
+        payer: *payer,
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        exempt_registry: exempt_registry_pda(mint),
+        vault: *vault,
+        vault_owner: *vault_owner,
+        mint: *mint,
+        system_program: system_program::ID,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Register `owner`, a PDA account of one of the mint's trusted programs that `seeds` (bump included) derive,
+/// so token accounts it owns are exempt
+pub fn register_trusted_owner(mint: &Pubkey, payer: &Pubkey, owner: &Pubkey, seeds: Vec<Vec<u8>>) -> Instruction {
+    let accounts = accounts::RegisterTrustedOwner {
+        payer: *payer,
+        config: config_pda(mint),
+        trusted_owner: trusted_owner_pda(mint, owner),
+        owner: *owner,
+        mint: *mint,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::RegisterTrustedOwner { seeds })
+}
+
+/// Register `custody`, a token account of the mint owned by `custody_owner`, a PDA account of
+/// `args.bridge_program`, as a bridge custody account
+pub fn register_bridge_custody(
+    mint: &Pubkey,
+    governance: &Governance,
+    payer: &Pubkey,
+    custody: &Pubkey,
+    custody_owner: &Pubkey,
+    args: args::RegisterBridgeCustody,
+) -> Instruction {
+    let accounts = accounts::RegisterBridgeCustody {
+        payer: *payer,
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        exempt_registry: exempt_registry_pda(mint),
+        custody: *custody,
+        custody_owner: *custody_owner,
+        mint: *mint,
+        system_program: system_program::ID,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Forget the detected launch so the next buy out of a pool vault records it again; usable once
+pub fn reset_launch_detection(mint: &Pubkey, governance: &Governance) -> Instruction {
+    let accounts = accounts::ResetLaunchDetection {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        config_extension: config_extension_pda(mint),
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::ResetLaunchDetection {})
+}
+
+/// Set how long after the launch buys out of a pool vault flag their buyer; 0 flags nobody
+pub fn set_snipe_window(mint: &Pubkey, governance: &Governance, args: args::SetSnipeWindow) -> Instruction {
+    let accounts = accounts::SetSnipeWindow {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        config_extension: config_extension_pda(mint),
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Require a memo in transactions moving more than `args.threshold` raw units between non-exempt accounts;
+/// None lifts the requirement
+pub fn set_memo_required_above(
+    mint: &Pubkey,
+    governance: &Governance,
+    args: args::SetMemoRequiredAbove,
+) -> Instruction {
+    let accounts = accounts::SetMemoRequiredAbove {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        config_extension: config_extension_pda(mint),
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Set or lower the dev wallets' daily outflow limit into pool vaults; it can never be raised again
+pub fn lower_dev_outflow_limit(
+    mint: &Pubkey,
+    governance: &Governance,
+    args: args::LowerDevOutflowLimit,
+) -> Instruction {
+    let accounts = accounts::LowerDevOutflowLimit {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        config_extension: config_extension_pda(mint),
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Take an owner off the mint's sniper log
+pub fn clear_sniper_flag(mint: &Pubkey, governance: &Governance, args: args::ClearSniperFlag) -> Instruction {
+    let accounts = accounts::ClearSniperFlag {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        sniper_log: sniper_log_pda(mint),
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Drop expired registry entries; `rent_recipient` must be the one the registry was initialized with
+pub fn prune_exempt_registry(mint: &Pubkey, rent_recipient: &Pubkey) -> Instruction {
+    let accounts = accounts::PruneExemptRegistry {
+        exempt_registry: exempt_registry_pda(mint),
+        rent_recipient: *rent_recipient,
+        mint: *mint,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::PruneExemptRegistry {})
+}
+
+pub fn init_used_nonces(mint: &Pubkey, governance: &Governance, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::InitUsedNonces {
+        payer: *payer,
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        used_nonces: used_nonces_pda(mint),
+        mint: *mint,
+        system_program: system_program::ID,
+        approval: governance.approval,
+    };
+    instruction(accounts, args::InitUsedNonces {})
+}
+
+/// Create `args.owner`'s `HolderState`; anyone may pay for it
+pub fn init_holder_state(mint: &Pubkey, payer: &Pubkey, args: args::InitHolderState) -> Instruction {
+    let accounts = accounts::InitHolderState {
+        payer: *payer,
+        holder_state: holder_state_pda(mint, &args.owner),
+        mint: *mint,
+        system_program: system_program::ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Close `owner`'s emptied `HolderState`, once `token_account` of theirs is empty too
+pub fn close_holder_state(mint: &Pubkey, owner: &Pubkey, token_account: &Pubkey) -> Instruction {
+    let accounts = accounts::CloseHolderState {
+        owner: *owner,
+        holder_state: holder_state_pda(mint, owner),
+        token_account: *token_account,
+        mint: *mint,
+    };
+    instruction(accounts, args::CloseHolderState {})
+}
+
+/// Take the next holder snapshot, created at `index`: the config's current `snapshot_count`
+pub fn take_snapshot(mint: &Pubkey, payer: &Pubkey, index: u64) -> Instruction {
+    let accounts = accounts::TakeSnapshot {
+        payer: *payer,
+        config: config_pda(mint),
+        snapshot: snapshot_pda(mint, index),
+        mint: *mint,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::TakeSnapshot {})
+}
+
+pub fn close_snapshot(
+    mint: &Pubkey,
+    governance: &Governance,
+    rent_recipient: &Pubkey,
+    args: args::CloseSnapshot,
+) -> Instruction {
+    let accounts = accounts::CloseSnapshot {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        snapshot: snapshot_pda(mint, args.index),
+        rent_recipient: *rent_recipient,
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+pub fn create_lockup(mint: &Pubkey, governance: &Governance, payer: &Pubkey, args: args::CreateLockup) -> Instruction {
+    let accounts = accounts::CreateLockup {
+        payer: *payer,
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        lockup: lockup_pda(mint, &args.owner),
+        mint: *mint,
+        system_program: system_program::ID,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+pub fn shorten_lockup(mint: &Pubkey, governance: &Governance, args: args::ShortenLockup) -> Instruction {
+    let accounts = accounts::ShortenLockup {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        lockup: lockup_pda(mint, &args.owner),
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// Migrate the config to the current layout, signed by the governance authority itself
+pub fn migrate_config(mint: &Pubkey, governance_authority: &Pubkey, payer: &Pubkey) -> Instruction {
+    let accounts = accounts::MigrateConfig {
+        config: config_pda(mint),
+        governance_authority: *governance_authority,
+        payer: *payer,
+        mint: *mint,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::MigrateConfig {})
+}
+
+pub fn close_config(
+    mint: &Pubkey,
+    governance: &Governance,
+    recipient: &Pubkey,
+    args: args::CloseConfig,
+) -> Instruction {
+    let accounts = accounts::CloseConfig {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        recipient: *recipient,
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+pub fn withdraw_surplus_lamports(mint: &Pubkey, governance: &Governance, recipient: &Pubkey) -> Instruction {
+    let accounts = accounts::WithdrawSurplusLamports {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        extra_account_meta_list: extra_metas_pda(mint),
+        recipient: *recipient,
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::WithdrawSurplusLamports {})
+}
+
+/// End the dev wallet exemption once its expiry has passed (permissionless crank)
+pub fn finalize_dev_exemption(mint: &Pubkey) -> Instruction {
+    let accounts = accounts::FinalizeDevExemption {
+        config: config_pda(mint),
+        mint: *mint,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args::FinalizeDevExemption {})
+}
+
+/// Pause or unpause transfers; the guardian may sign a pause, unpausing needs governance
+pub fn set_pause(mint: &Pubkey, governance: &Governance, args: args::SetPause) -> Instruction {
+    let accounts = accounts::SetPause {
+        config: config_pda(mint),
+        authority: governance.authority,
+        mint: *mint,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+/// `propose_dev_wallet_update`, first checking that `dev_wallet_token_account`, if given, is the new
+/// wallet's token account of the mint
+pub fn set_dev_wallet_verified(
+    mint: &Pubkey,
+    governance: &Governance,
+    dev_wallet_token_account: Option<Pubkey>,
+    args: args::SetDevWalletVerified,
+) -> Instruction {
+    let accounts = accounts::SetDevWalletVerified {
+        config: config_pda(mint),
+        governance_authority: governance.authority,
+        mint: *mint,
+        dev_wallet_token_account,
+        approval: governance.approval,
+        event_authority: event_authority_pda(),
+        program: ID,
+    };
+    instruction(accounts, args)
+}
+
+governed!(execute_immediate_cap_increase, ImmediateCapIncrease, ExecuteImmediateCapIncrease);
+governed!(raise_min_cap_floor, RaiseMinCapFloor, RaiseMinCapFloor);
+governed!(set_proposal_expiry, SetProposalExpiry, SetProposalExpiry);
+governed!(set_cap_update_role, SetCapUpdateRole, SetCapUpdateRole);
+governed!(propose_veto_authority_update, VetoAuthorityUpdate, ProposeVetoAuthorityUpdate);
+governed!(execute_veto_authority_update, VetoAuthorityUpdate, ExecuteVetoAuthorityUpdate {});
+governed!(cancel_veto_authority_update, VetoAuthorityUpdate, CancelVetoAuthorityUpdate {});
+governed!(propose_governance_transfer, GovernanceTransfer, ProposeGovernanceTransfer);
+governed!(cancel_governance_transfer, GovernanceTransfer, CancelGovernanceTransfer {});
+governed!(renounce_governance, GovernanceTransfer, RenounceGovernance {});
+governed!(freeze_config, FreezeConfig, FreezeConfig {});
+governed!(set_governance_members, SetGovernanceMembers, SetGovernanceMembers);
+governed!(set_trusted_program, SetTrustedProgram, SetTrustedProgram);
+governed!(set_staking_program, SetStakingProgram, SetStakingProgram);
+governed!(propose_dev_wallet_update, ProposeDevWalletUpdate, ProposeDevWalletUpdate);
+governed!(execute_dev_wallet_update, ExecuteDevWalletUpdate, ExecuteDevWalletUpdate {});
+governed!(cancel_dev_wallet_update, CancelDevWalletUpdate, CancelDevWalletUpdate {});
+governed!(propose_timelock_update, TimelockUpdate, ProposeTimelockUpdate);
+governed!(execute_timelock_update, TimelockUpdate, ExecuteTimelockUpdate {});
+governed!(cancel_timelock_update, TimelockUpdate, CancelTimelockUpdate {});
+governed!(set_dev_exemption_expiry, SetDevExemptionExpiry, SetDevExemptionExpiry);
+governed!(set_airdrop_allowance, SetAirdropAllowance, SetAirdropAllowance);
+governed!(set_permit_signer, SetPermitSigner, SetPermitSigner);
+governed!(set_circuit_breaker, SetCircuitBreaker, SetCircuitBreaker);
+governed!(clear_circuit_breaker, SetCircuitBreaker, ClearCircuitBreaker {});
+governed!(set_guardian, SetGuardian, SetGuardian);
+governed!(set_max_holders, SetMaxHolders, SetMaxHolders);
+governed!(set_transfer_events, SetTransferEvents, SetTransferEvents);
+governed!(set_require_wallet_owners, SetRequireWalletOwners, SetRequireWalletOwners);
+governed!(propose_hook_disable, HookDisable, ProposeHookDisable {});
+governed!(permanently_disable_hook, HookDisable, PermanentlyDisableHook {});
+governed!(cancel_hook_disable, HookDisable, CancelHookDisable {});
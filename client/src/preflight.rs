@@ -0,0 +1,118 @@
+// Telling before signing whether a transfer will bounce.
+//
+// The verdict comes from the program's own `preflight`, compiled into the client: the code `can_receive`
+// and `execute` decide the receiving side with, run on fetched account data instead of a transaction
+// context. Like `can_receive` it covers the destination only: exemptions, the pause, the strict wallet
+// owner mode and the wallet cap.
+
+use anchor_lang::solana_program::{clock::Clock, pubkey::Pubkey};
+#[cfg(feature = "rpc")]
+use anchor_lang::solana_program::{program_error::ProgramError, sysvar};
+use anchor_lang::AccountDeserialize;
+use one_kx_hook::{ExemptRegistry, HookConfig, HookError, Receipt, ReceiveReason, TrustedOwner};
+#[cfg(feature = "rpc")]
+use solana_client::nonblocking::rpc_client::RpcClient;
+
+use crate::Error;
+#[cfg(feature = "rpc")]
+use crate::{config_pda, exempt_registry_pda, trusted_owner_pda, ID};
+
+/// What `execute` would do with a wallet-to-wallet transfer into the destination
+#[derive(Clone, Copy, Debug)]
+pub enum TransferVerdict {
+    /// Let through, leaving `remaining` room under the cap in cap units; u64::MAX if the destination is
+    /// exempt or the hook disabled
+    Allowed { remaining: u64 },
+    /// Rejected with `reason`, unless an airdrop allowance or a permit lets an over-cap receive through
+    Rejected { reason: HookError },
+}
+
+/// The raw account data a preflight reads
+pub struct PreflightAccounts<'a> {
+    pub config: &'a [u8],
+    pub mint: &'a [u8],
+    pub destination: &'a [u8],
+    /// The exempt registry, if it has been created
+    pub exempt_registry: Option<&'a [u8]>,
+    /// The destination owner's `TrustedOwner` record, if it has been created
+    pub trusted_owner: Option<&'a [u8]>,
+}
+
+/// Fetch the config, mint, destination, exempt registry and clock, then the destination owner's trusted
+/// owner record, and decide a transfer of `amount` into `destination`, a token account of `mint`
+#[cfg(feature = "rpc")]
+pub async fn check_transfer(
+    rpc: &RpcClient,
+    mint: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> Result<TransferVerdict, Error> {
+    let addresses = [config_pda(&mint), mint, destination, exempt_registry_pda(&mint), sysvar::clock::ID];
+    let accounts = rpc.get_multiple_accounts(&addresses).await?;
+    let fetched = |index: usize| accounts[index].as_ref().ok_or(Error::AccountNotFound(addresses[index]));
+    let (config, mint_account, destination_account, clock) = (fetched(0)?, fetched(1)?, fetched(2)?, fetched(4)?);
+    let registry = accounts[3].as_ref();
+    // As `can_receive` requires
+    for account in [mint_account, destination_account] {
+        if account.owner != spl_token_2022::id() {
+            return Err(Error::Anchor(HookError::InvalidAccountOwner.into()));
+        }
+    }
+    let clock: Clock = bincode::deserialize(&clock.data).map_err(|_| ProgramError::InvalidAccountData)?;
+    // Resolved from the owner the destination stores, as Token-2022 does for the hook
+    let destination_owner: [u8; 32] = destination_account
+        .data
+        .get(32..64)
+        .and_then(|owner| owner.try_into().ok())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let trusted_owner = trusted_owner_pda(&mint, &Pubkey::new_from_array(destination_owner));
+    let trusted_owner = rpc.get_account_with_commitment(&trusted_owner, rpc.commitment()).await?.value;
+
+    let accounts = PreflightAccounts {
+        config: &config.data,
+        mint: &mint_account.data,
+        destination: &destination_account.data,
+        // An uninitialized PDA means no registry exemptions, as on-chain
+        exempt_registry: registry.filter(|registry| registry.owner == ID).map(|registry| &registry.data[..]),
+        trusted_owner: trusted_owner.as_ref().filter(|record| record.owner == ID).map(|record| &record.data[..]),
+    };
+    check_transfer_offline(&mint, &destination, &accounts, &clock, amount)
+}
+
+/// `check_transfer` over account data fetched beforehand, at `clock`
+pub fn check_transfer_offline(
+    mint: &Pubkey,
+    destination: &Pubkey,
+    accounts: &PreflightAccounts,
+    clock: &Clock,
+    amount: u64,
+) -> Result<TransferVerdict, Error> {
+    let config = HookConfig::try_deserialize(&mut &accounts.config[..])?;
+    if !config.is_supported_version() {
+        return Err(Error::Anchor(HookError::UnsupportedVersion.into()));
+    }
+    let registry = match accounts.exempt_registry {
+        Some(data) if !data.is_empty() => Some(ExemptRegistry::try_deserialize(&mut &data[..])?),
+        _ => None,
+    };
+    // A record made for another mint doesn't count, as on-chain
+    let trusted_owner = match accounts.trusted_owner {
+        Some(data) if !data.is_empty() => Some(TrustedOwner::try_deserialize(&mut &data[..])?),
+        _ => None,
+    };
+    let trusted_owner = trusted_owner.filter(|record| record.mint == *mint);
+    let receipt = Receipt { destination, destination_data: accounts.destination, mint_data: accounts.mint, amount };
+
+    let result = one_kx_hook::preflight(&config, registry.as_ref(), &receipt, trusted_owner.as_ref(), mint, clock)?;
+    Ok(if result.allowed {
+        TransferVerdict::Allowed { remaining: result.remaining_capacity }
+    } else if result.reason_code == ReceiveReason::Paused as u8 {
+        TransferVerdict::Rejected { reason: HookError::CircuitBreakerTripped }
+    } else if result.reason_code == ReceiveReason::ProgramOwned as u8 {
+        TransferVerdict::Rejected { reason: HookError::ProgramOwnedDestination }
+    } else if result.reason_code == ReceiveReason::NotFinalized as u8 {
+        TransferVerdict::Rejected { reason: HookError::SetupNotFinalized }
+    } else {
+        TransferVerdict::Rejected { reason: HookError::WalletCapExceeded }
+    })
+}
@@ -0,0 +1,41 @@
+// Every mint initialized through the factory path, read from the MintRegistry pages: one
+// `getProgramAccounts` filtered on their discriminator finds them all, whatever the page count.
+
+use anchor_lang::AccountDeserialize;
+#[cfg(feature = "rpc")]
+use anchor_lang::Discriminator;
+use one_kx_hook::{MintRegistry, MintRegistryEntry};
+#[cfg(feature = "rpc")]
+use solana_account_decoder::UiAccountEncoding;
+#[cfg(feature = "rpc")]
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+
+use crate::Error;
+#[cfg(feature = "rpc")]
+use crate::ID;
+
+/// Fetch every MintRegistry page and list the mints on them in registration order
+#[cfg(feature = "rpc")]
+pub async fn registered_mints(rpc: &RpcClient) -> Result<Vec<MintRegistryEntry>, Error> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, MintRegistry::DISCRIMINATOR.to_vec()))]),
+        account_config: RpcAccountInfoConfig { encoding: Some(UiAccountEncoding::Base64), ..Default::default() },
+        ..Default::default()
+    };
+    let pages = rpc.get_program_accounts_with_config(&ID, config).await?;
+    registered_mints_offline(pages.iter().map(|(_, account)| &account.data[..]))
+}
+
+/// `registered_mints` over page data fetched beforehand, in any order
+pub fn registered_mints_offline<'a>(
+    pages: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<Vec<MintRegistryEntry>, Error> {
+    let mut pages =
+        pages.into_iter().map(|mut data| MintRegistry::try_deserialize(&mut data)).collect::<Result<Vec<_>, _>>()?;
+    pages.sort_by_key(|page| page.page);
+    Ok(pages.into_iter().flat_map(|page| page.entries).collect())
+}
@@ -0,0 +1,151 @@
+// `airdrop::plan_offline` over a recipient already at the cap, the exempt dev wallet and a fresh wallet:
+// the policy decides the first, the second is sent in full, and the planned transactions land.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+use one_kx_hook::HookError;
+use onekx_hook_client::airdrop::{self, AirdropAccounts, AirdropConfig, AirdropPlan, AirdropPolicy};
+use onekx_hook_client::Error;
+use solana_sdk::{
+    clock::Clock,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{Harness, HookedMint, DECIMALS, WALLET_CAP_RAW};
+
+struct Fixture {
+    harness: Harness,
+    mint: HookedMint,
+    distributor: Keypair,
+    source: Pubkey,
+    at_cap: Pubkey,
+    fresh: Pubkey,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let mut harness = Harness::new().await;
+        let mint = harness.create_hooked_mint().await;
+        let distributor = Keypair::new();
+        let source = harness.create_token_account(&mint, &distributor.pubkey(), 10 * WALLET_CAP_RAW).await;
+
+        // Already holding the cap in its associated token account
+        let at_cap = Pubkey::new_unique();
+        let payer = harness.context.payer.pubkey();
+        let destination = airdrop::destination(&mint.address, &at_cap);
+        let fill = [
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer,
+                &at_cap,
+                &mint.address,
+                &spl_token_2022::id(),
+            ),
+            spl_token_2022::instruction::mint_to_checked(
+                &spl_token_2022::id(),
+                &mint.address,
+                &destination,
+                &mint.mint_authority.pubkey(),
+                &[],
+                WALLET_CAP_RAW,
+                DECIMALS,
+            )
+            .unwrap(),
+        ];
+        let mint_authority = mint.mint_authority.insecure_clone();
+        harness.process(&fill, &[&mint_authority]).await.0.unwrap();
+
+        Self { harness, mint, distributor, source, at_cap, fresh: Pubkey::new_unique() }
+    }
+
+    fn recipients(&self) -> Vec<(Pubkey, u64)> {
+        vec![(self.at_cap, 1), (self.mint.dev_wallet, 3 * WALLET_CAP_RAW), (self.fresh, WALLET_CAP_RAW + 7)]
+    }
+
+    async fn data(&mut self, address: Pubkey) -> Option<Vec<u8>> {
+        self.harness.context.banks_client.get_account(address).await.unwrap().map(|account| account.data)
+    }
+
+    async fn plan(&mut self, policy: AirdropPolicy) -> Result<AirdropPlan, Error> {
+        let mint = self.mint.address;
+        let config = self.data(self.mint.config()).await.unwrap();
+        let mint_data = self.data(mint).await.unwrap();
+        let meta_list = self.data(self.mint.extra_account_meta_list()).await.unwrap();
+        let source = self.data(self.source).await.unwrap();
+        let mut destinations = Vec::new();
+        for (owner, _) in self.recipients() {
+            let address = airdrop::destination(&mint, &owner);
+            if let Some(data) = self.data(address).await {
+                destinations.push((address, data));
+            }
+        }
+        let destinations: Vec<_> = destinations.iter().map(|(address, data)| (*address, data.as_slice())).collect();
+        let clock: Clock = self.harness.context.banks_client.get_sysvar().await.unwrap();
+
+        let accounts = AirdropAccounts {
+            config: &config,
+            mint: &mint_data,
+            meta_list: &meta_list,
+            source: &source,
+            exempt_registry: None,
+            destinations: &destinations,
+        };
+        let config = AirdropConfig {
+            source: self.source,
+            authority: self.distributor.pubkey(),
+            payer: self.harness.context.payer.pubkey(),
+            policy,
+        };
+        airdrop::plan_offline(&mint, self.recipients(), config, &accounts, &clock)
+    }
+
+    async fn balance(&mut self, owner: &Pubkey) -> u64 {
+        self.harness.balance(&airdrop::destination(&self.mint.address, owner)).await
+    }
+}
+
+#[tokio::test]
+async fn clamps_to_the_cap_and_delivers_in_full_to_exempt_recipients() {
+    let mut fixture = Fixture::new().await;
+    let plan = fixture.plan(AirdropPolicy::ClampToCap).await.unwrap();
+
+    assert_eq!(plan.skipped.len(), 1);
+    assert_eq!(plan.skipped[0].owner, fixture.at_cap);
+    assert!(matches!(plan.skipped[0].reason, HookError::WalletCapExceeded));
+    let delivered: Vec<_> =
+        plan.deliveries().map(|delivery| (delivery.owner, delivery.amount, delivery.exempt)).collect();
+    assert_eq!(
+        delivered,
+        [(fixture.mint.dev_wallet, 3 * WALLET_CAP_RAW, true), (fixture.fresh, WALLET_CAP_RAW, false)]
+    );
+    assert_eq!(plan.total(), 4 * WALLET_CAP_RAW);
+
+    // The fresh wallet's token account is created on the way
+    let distributor = fixture.distributor.insecure_clone();
+    for transaction in &plan.transactions {
+        let instructions: Vec<_> = transaction.iter().flat_map(|delivery| delivery.instructions.clone()).collect();
+        fixture.harness.process(&instructions, &[&distributor]).await.0.unwrap();
+    }
+    let (dev_wallet, fresh, at_cap) = (fixture.mint.dev_wallet, fixture.fresh, fixture.at_cap);
+    assert_eq!(fixture.balance(&dev_wallet).await, 3 * WALLET_CAP_RAW);
+    assert_eq!(fixture.balance(&fresh).await, WALLET_CAP_RAW);
+    assert_eq!(fixture.balance(&at_cap).await, WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn skip_leaves_out_and_fail_refuses_recipients_over_the_cap() {
+    let mut fixture = Fixture::new().await;
+
+    let plan = fixture.plan(AirdropPolicy::Skip).await.unwrap();
+    let skipped: Vec<_> = plan.skipped.iter().map(|skipped| (skipped.owner, skipped.requested)).collect();
+    assert_eq!(skipped, [(fixture.at_cap, 1), (fixture.fresh, WALLET_CAP_RAW + 7)]);
+    let delivered: Vec<_> = plan.deliveries().map(|delivery| delivery.owner).collect();
+    assert_eq!(delivered, [fixture.mint.dev_wallet]);
+
+    match fixture.plan(AirdropPolicy::Fail).await {
+        Err(Error::RecipientRejected { owner, reason: HookError::WalletCapExceeded }) => {
+            assert_eq!(owner, fixture.at_cap)
+        }
+        other => panic!("planned past the cap: {:?}", other.map(|plan| plan.total())),
+    }
+}
@@ -0,0 +1,109 @@
+// The event decoder against the program: the committed layout fixtures of every event, and events the
+// harness captures from real transactions.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use onekx_hook_client::events::{parse_event_cpi, parse_logs, HookEvent};
+use onekx_hook_client::{self as client, args, Governance};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{compiled_program_available, Harness};
+
+/// The program's layout snapshot, which pins the bytes of every event
+const LAYOUTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../programs/1kx_hook/tests/snapshots/layouts.txt");
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap()).collect()
+}
+
+/// Name and payload of every event in the layout snapshot
+fn fixtures() -> Vec<(String, Vec<u8>)> {
+    let layouts = std::fs::read_to_string(LAYOUTS).unwrap();
+    layouts
+        .lines()
+        .filter_map(|line| line.strip_prefix("event "))
+        .map(|line| {
+            let [name, discriminator, fields] = line.split(' ').collect::<Vec<_>>()[..] else {
+                panic!("malformed layout line: {}", line)
+            };
+            (name.to_string(), unhex(&format!("{}{}", discriminator, fields)))
+        })
+        .collect()
+}
+
+#[test]
+fn every_snapshotted_event_decodes() {
+    let fixtures = fixtures();
+    let mut names: Vec<_> = fixtures.iter().map(|(name, _)| name.as_str()).collect();
+    let mut decodable = HookEvent::NAMES.to_vec();
+    names.sort_unstable();
+    decodable.sort_unstable();
+    assert_eq!(names, decodable, "the decoder and the program's events differ");
+
+    for (name, payload) in &fixtures {
+        let event = HookEvent::decode(payload).unwrap_or_else(|| panic!("{} doesn't decode", name));
+        assert_eq!(event.name(), name);
+        assert_eq!(&event.data(), payload, "{} doesn't round-trip", name);
+        // A truncated payload is skipped rather than misread
+        assert_eq!(HookEvent::decode(&payload[..payload.len() - 1]), None, "{} decodes truncated", name);
+    }
+}
+
+#[test]
+fn only_the_programs_own_log_lines_are_decoded() {
+    let (_, payload) = &fixtures()[0];
+    let data = format!("Program data: {}", BASE64.encode(payload));
+    let unknown = format!("Program data: {}", BASE64.encode([0xff; 16]));
+    let other = Pubkey::new_unique();
+    let logs = [
+        format!("Program {} invoke [1]", other),
+        data.clone(),
+        format!("Program {} invoke [2]", client::ID),
+        "Program log: Instruction: Execute".to_string(),
+        data.clone(),
+        unknown,
+        format!("Program {} consumed 1000 of 200000 compute units", client::ID),
+        format!("Program {} success", client::ID),
+        data,
+        format!("Program {} success", other),
+    ];
+    assert_eq!(parse_logs(&logs), vec![HookEvent::decode(payload).unwrap()]);
+}
+
+#[tokio::test]
+async fn events_of_harness_transactions_decode() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let signer = mint.governance.insecure_clone();
+    let governance = Governance::authority(signer.pubkey());
+
+    // A governance instruction emits through its self-CPI, which the native harness logs with its tag
+    let enable = client::set_transfer_events(&mint.address, &governance, args::SetTransferEvents { enabled: true });
+    let logs = harness.process_for_logs(&[enable], &[&signer]).await;
+    assert_eq!(parse_logs(&logs), vec![]);
+    if !compiled_program_available() {
+        let events: Vec<_> = logs
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .filter_map(|data| parse_event_cpi(&BASE64.decode(data).unwrap()))
+            .collect();
+        let [HookEvent::TransferEventsUpdated(updated)] = &events[..] else { panic!("unexpected events {:?}", events) };
+        assert_eq!(updated.mint, mint.address);
+    }
+
+    // The transfer hook logs its event
+    let owner = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 5).await;
+    let destination = harness.create_token_account(&mint, &recipient, 0).await;
+    let transfer = harness.transfer_instruction(&mint, &source, &destination, &owner.pubkey(), 5).await;
+    let logs = harness.process_for_logs(&[transfer], &[&owner]).await;
+    let events = parse_logs(&logs);
+    let [HookEvent::HookTransferChecked(checked)] = &events[..] else { panic!("unexpected events {:?}", events) };
+    assert_eq!((checked.mint, checked.destination_owner), (mint.address, recipient));
+    assert_eq!((checked.amount, checked.post_balance), (5, 5));
+}
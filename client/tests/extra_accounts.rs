@@ -0,0 +1,89 @@
+// `add_extra_accounts_for_transfer_offline` completing a bare `transfer_checked` of a hooked mint, over
+// account data fetched from the harness the way a batch-fetching integrator would have it.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+use onekx_hook_client::{self as client, Error};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{Harness, HookedMint, DECIMALS};
+
+fn transfer_checked(mint: &HookedMint, source: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::id(),
+        source,
+        &mint.address,
+        destination,
+        owner,
+        &[],
+        1,
+        DECIMALS,
+    )
+    .unwrap()
+}
+
+async fn account_data(harness: &mut Harness, address: Pubkey) -> Vec<u8> {
+    harness.context.banks_client.get_account(address).await.unwrap().unwrap().data
+}
+
+#[tokio::test]
+async fn the_augmented_transfer_executes_where_the_bare_one_fails() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 2).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    let bare = transfer_checked(&mint, &source, &destination, &owner.pubkey());
+    assert!(harness.process(&[bare.clone()], &[&owner]).await.0.is_err());
+
+    let meta_list = account_data(&mut harness, mint.extra_account_meta_list()).await;
+    let source_data = account_data(&mut harness, source).await;
+    let destination_data = account_data(&mut harness, destination).await;
+    let token_accounts = [(source, source_data.as_slice()), (destination, destination_data.as_slice())];
+    let mut augmented = bare;
+    client::add_extra_accounts_for_transfer_offline(&mut augmented, mint.address, &meta_list, &token_accounts).unwrap();
+
+    // Exactly what SPL's own resolver appends
+    let resolved = harness.transfer_instruction(&mint, &source, &destination, &owner.pubkey(), 1).await;
+    assert_eq!(augmented, resolved);
+    let appended: Vec<_> = augmented.accounts[4..].iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(appended[0], mint.config());
+    assert_eq!(appended[appended.len() - 2..], [client::ID, mint.extra_account_meta_list()]);
+
+    harness.process(&[augmented], &[&owner]).await.0.unwrap();
+    assert_eq!(harness.balance(&destination).await, 1);
+}
+
+#[tokio::test]
+async fn only_transfers_of_the_mint_are_augmented() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let other = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 1).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    let meta_list = account_data(&mut harness, mint.extra_account_meta_list()).await;
+
+    let transfer = transfer_checked(&mint, &source, &destination, &owner.pubkey());
+    let not_a_transfer = spl_token_2022::instruction::burn_checked(
+        &spl_token_2022::id(),
+        &source,
+        &mint.address,
+        &owner.pubkey(),
+        &[],
+        1,
+        DECIMALS,
+    )
+    .unwrap();
+    for (mut instruction, claimed_mint) in [(transfer, other.address), (not_a_transfer, mint.address)] {
+        let untouched = instruction.clone();
+        let result = client::add_extra_accounts_for_transfer_offline(&mut instruction, claimed_mint, &meta_list, &[]);
+        assert!(matches!(result, Err(Error::NotATransfer)));
+        assert_eq!(instruction, untouched);
+    }
+}
@@ -0,0 +1,82 @@
+// A mint launched from `launch::plan_offline` in one transaction, then transferred under the cap it was
+// launched with.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+use one_kx_hook::HookError;
+use onekx_hook_client::launch::{self, LaunchParams};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_token_2022::extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::Mint;
+use test_utils::{hook_error, Harness, HookedMint, DECIMALS, WALLET_CAP_RAW};
+
+const LAUNCH_CAP: u64 = 2 * WALLET_CAP_RAW;
+
+fn params(governance_authority: Pubkey) -> LaunchParams {
+    LaunchParams {
+        decimals: DECIMALS,
+        cap: LAUNCH_CAP,
+        dev_wallet: Pubkey::new_unique(),
+        governance_authority,
+        metadata_pointer: Some(Pubkey::new_unique()),
+    }
+}
+
+#[tokio::test]
+async fn launches_in_one_transaction_and_caps_transfers() {
+    let mut harness = Harness::new().await;
+    let payer = harness.context.payer.insecure_clone();
+    let mint = Keypair::new();
+    let params = params(payer.pubkey());
+    let rent = harness.context.banks_client.get_rent().await.unwrap();
+    let mint_rent = rent.minimum_balance(launch::mint_space(&params).unwrap());
+    let plan = launch::plan_offline(&mint.pubkey(), &payer.pubkey(), mint_rent, &params).unwrap();
+    assert_eq!(plan.steps.len(), 11, "{}", plan);
+    harness.process(&plan.instructions(), &[&mint]).await.0.unwrap();
+
+    let hooked = HookedMint {
+        address: mint.pubkey(),
+        mint_authority: payer.insecure_clone(),
+        dev_wallet: params.dev_wallet,
+        governance: payer.insecure_clone(),
+    };
+    let config = harness.config(&hooked).await;
+    assert_eq!(config.wallet_cap_raw, LAUNCH_CAP);
+    assert_eq!((config.dev_wallets[0], config.governance_authority), (params.dev_wallet, payer.pubkey()));
+    let mint_account = harness.context.banks_client.get_account(mint.pubkey()).await.unwrap().unwrap();
+    let state = StateWithExtensions::<Mint>::unpack(&mint_account.data).unwrap();
+    assert_eq!(state.base.decimals, DECIMALS);
+    let pointer = state.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(Option::<Pubkey>::from(pointer.metadata_address), params.metadata_pointer);
+
+    // The launch cap holds, not the program's default
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&hooked, &owner.pubkey(), LAUNCH_CAP + 1).await;
+    let destination = harness.create_token_account(&hooked, &Pubkey::new_unique(), 0).await;
+    let (result, _) = harness.transfer(&hooked, &source, &destination, &owner, LAUNCH_CAP).await;
+    assert_eq!(result, Ok(()));
+    let (result, _) = harness.transfer(&hooked, &source, &destination, &owner, 1).await;
+    assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+}
+
+#[test]
+fn refuses_caps_the_launch_cannot_set() {
+    let (mint, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let refusal = |params: LaunchParams| match launch::plan_offline(&mint, &payer, 0, &params) {
+        Err(onekx_hook_client::Error::Anchor(error)) => error,
+        _ => panic!("planned a cap of {}", params.cap),
+    };
+
+    // Lowering the cap takes a timelocked proposal, and raising it the governance authority's signature
+    let lowered = LaunchParams { cap: WALLET_CAP_RAW - 1, ..params(payer) };
+    assert_eq!(refusal(lowered), HookError::TimelockRequired.into());
+    assert_eq!(refusal(params(Pubkey::new_unique())), HookError::UnauthorizedGovernance.into());
+
+    // At the default cap anyone may govern, as nothing raises it
+    let unraised = LaunchParams { cap: WALLET_CAP_RAW, ..params(Pubkey::new_unique()) };
+    assert_eq!(launch::plan_offline(&mint, &payer, 0, &unraised).unwrap().steps.len(), 10);
+}
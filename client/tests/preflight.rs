@@ -0,0 +1,95 @@
+// `check_transfer_offline` against the program: for a grid of destination balances and amounts, the
+// preflight verdict must be what Token-2022's `transfer_checked` then does.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+use onekx_hook_client::{self as client, args, Governance, PreflightAccounts, TransferVerdict};
+use solana_sdk::{
+    clock::Clock,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{hook_error, Harness, HookedMint, WALLET_CAP_RAW};
+
+const BALANCES: [u64; 4] = [0, 1, WALLET_CAP_RAW - 1, WALLET_CAP_RAW];
+const AMOUNTS: [u64; 4] = [0, 1, 2, WALLET_CAP_RAW];
+
+async fn account_data(harness: &mut Harness, address: Pubkey) -> Vec<u8> {
+    harness.context.banks_client.get_account(address).await.unwrap().unwrap().data
+}
+
+/// The preflight verdict for `amount` into `destination`, then the outcome of actually sending it
+async fn preflight_then_transfer(
+    harness: &mut Harness,
+    mint: &HookedMint,
+    (source, owner): (&Pubkey, &Keypair),
+    destination: &Pubkey,
+    amount: u64,
+) {
+    let config = account_data(harness, mint.config()).await;
+    let mint_data = account_data(harness, mint.address).await;
+    let destination_data = account_data(harness, *destination).await;
+    let clock: Clock = harness.context.banks_client.get_sysvar().await.unwrap();
+    let accounts = PreflightAccounts {
+        config: &config,
+        mint: &mint_data,
+        destination: &destination_data,
+        exempt_registry: None,
+        trusted_owner: None,
+    };
+    let verdict = client::check_transfer_offline(&mint.address, destination, &accounts, &clock, amount).unwrap();
+
+    let balance = harness.balance(destination).await;
+    let (result, _) = harness.transfer(mint, source, destination, owner, amount).await;
+    let case = format!("{} into a balance of {}", amount, balance);
+    match verdict {
+        TransferVerdict::Allowed { remaining } => {
+            assert_eq!(result, Ok(()), "{} was preflighted as allowed", case);
+            let cap = harness.config(mint).await.wallet_cap_raw;
+            if remaining != u64::MAX {
+                assert_eq!(remaining, cap - harness.balance(destination).await, "{}", case);
+            }
+        }
+        TransferVerdict::Rejected { reason } => {
+            assert_eq!(result, Err(hook_error(reason)), "{} was preflighted as rejected", case)
+        }
+    }
+}
+
+#[tokio::test]
+async fn verdicts_match_execution_across_balances_and_amounts() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let funding = (BALANCES.len() * AMOUNTS.len()) as u64 * WALLET_CAP_RAW;
+    let source = harness.create_token_account(&mint, &owner.pubkey(), funding).await;
+
+    for balance in BALANCES {
+        for amount in AMOUNTS {
+            let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), balance).await;
+            preflight_then_transfer(&mut harness, &mint, (&source, &owner), &destination, amount).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn verdicts_match_execution_for_exempt_and_paused_destinations() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 4 * WALLET_CAP_RAW).await;
+    let dev_wallet = harness.create_token_account(&mint, &mint.dev_wallet, WALLET_CAP_RAW).await;
+    let holder = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    // The dev wallet receives past the cap
+    preflight_then_transfer(&mut harness, &mint, (&source, &owner), &dev_wallet, WALLET_CAP_RAW).await;
+
+    // A pause holds back everything but exempt destinations
+    let signer = mint.governance.insecure_clone();
+    let governance = Governance::authority(signer.pubkey());
+    let pause = client::set_pause(&mint.address, &governance, args::SetPause { paused: true });
+    harness.process(&[pause], &[&signer]).await.0.unwrap();
+    preflight_then_transfer(&mut harness, &mint, (&source, &owner), &holder, 1).await;
+    preflight_then_transfer(&mut harness, &mint, (&source, &owner), &dev_wallet, 1).await;
+}
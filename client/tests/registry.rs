@@ -0,0 +1,76 @@
+// Listing the factory's mints: registry pages as the program writes them, set up through the client's
+// builders, decoded and ordered the way `registered_mints` reads them from `getProgramAccounts`.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+use anchor_lang::{AccountSerialize, Discriminator};
+use one_kx_hook::{MintRegistry, MintRegistryEntry};
+use onekx_hook_client::{self as client, args, CapUnit};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{Harness, HookedMint, TIMELOCK_SECONDS};
+
+fn initialize_for_mint(payer: &Pubkey, mint: &HookedMint, initializer: &Pubkey) -> Instruction {
+    let args = args::InitializeForMint {
+        dev_wallet: mint.dev_wallet,
+        governance_authority: mint.governance.pubkey(),
+        cap_unit: CapUnit::Raw,
+        timelock_seconds: Some(TIMELOCK_SECONDS),
+        min_cap_floor: Some(0),
+        max_cap_change_bps: Some(0),
+        strict_authorities: false,
+        allow_confidential: false,
+    };
+    client::initialize_for_mint(&mint.address, payer, initializer, 0, args)
+}
+
+#[tokio::test]
+async fn registered_mints_lists_the_factory_mints_in_order() {
+    let mut harness = Harness::new().await;
+    let upgrade_authority = Keypair::new();
+    harness.set_upgrade_authority(&upgrade_authority.pubkey());
+    let payer = harness.context.payer.pubkey();
+    let admin = Keypair::new();
+    let setup = [
+        client::init_global_config(
+            &payer,
+            &upgrade_authority.pubkey(),
+            args::InitGlobalConfig { factory_admin: admin.pubkey() },
+        ),
+        client::init_mint_registry_page(&payer, 0),
+    ];
+    harness.process(&setup, &[&upgrade_authority]).await.0.unwrap();
+
+    let first = harness.create_mint().await;
+    let second = harness.create_mint().await;
+    harness.process(&[initialize_for_mint(&payer, &first, &admin.pubkey())], &[&admin]).await.0.unwrap();
+    let authority = second.mint_authority.insecure_clone();
+    harness.process(&[initialize_for_mint(&payer, &second, &authority.pubkey())], &[&authority]).await.0.unwrap();
+
+    // What the discriminator filter selects: the registry page, not the configs next to it
+    let page = harness.context.banks_client.get_account(client::mint_registry_pda(0)).await.unwrap().unwrap();
+    let config = harness.context.banks_client.get_account(first.config()).await.unwrap().unwrap();
+    assert_eq!(page.owner, client::ID);
+    assert!(page.data.starts_with(&MintRegistry::DISCRIMINATOR));
+    assert!(!config.data.starts_with(&MintRegistry::DISCRIMINATOR));
+
+    let mints = client::registered_mints_offline([&page.data[..]]).unwrap();
+    let listed: Vec<_> = mints.iter().map(|entry| (entry.mint, entry.creator)).collect();
+    assert_eq!(listed, [(first.address, admin.pubkey()), (second.address, authority.pubkey())]);
+
+    // Pages come back from the RPC node in no particular order
+    let later = MintRegistry {
+        page: 1,
+        entries: vec![MintRegistryEntry { mint: Pubkey::new_unique(), creator: admin.pubkey(), created_slot: 7 }],
+    };
+    let mut later_data = Vec::new();
+    later.try_serialize(&mut later_data).unwrap();
+    let all = client::registered_mints_offline([&later_data[..], &page.data[..]]).unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(all[..2], mints[..]);
+    assert_eq!(all[2], later.entries[0]);
+}
@@ -0,0 +1,163 @@
+// Instructions built by the client, executed against the program in the same Token-2022 harness its own
+// end-to-end suites use: the data must deserialize into the arguments Anchor expects and the derived
+// accounts must pass the program's seed constraints.
+
+#[path = "../../programs/1kx_hook/tests/test_utils/mod.rs"]
+mod test_utils;
+
+use one_kx_hook::HookError;
+use onekx_hook_client::{self as client, args, CapUnit, Governance, Transfer};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{hook_error, Harness, HookedMint, DECIMALS, TIMELOCK_SECONDS, WALLET_CAP_RAW};
+
+fn governance(mint: &HookedMint) -> Governance {
+    Governance::authority(mint.governance.pubkey())
+}
+
+#[tokio::test]
+async fn pdas_match_the_accounts_the_program_created() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+
+    assert_eq!(client::config_pda(&mint.address), mint.config());
+    assert_eq!(client::extra_metas_pda(&mint.address), mint.extra_account_meta_list());
+    assert_eq!(client::governance_log_pda(&mint.address), mint.governance_log());
+    assert_eq!(client::event_authority_pda(), test_utils::event_authority());
+    for address in [mint.config(), mint.extra_account_meta_list(), mint.governance_log()] {
+        let account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+        assert_eq!(account.owner, client::ID);
+    }
+}
+
+#[tokio::test]
+async fn a_mint_set_up_through_the_client_enforces_the_cap() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_mint().await;
+    let payer = harness.context.payer.pubkey();
+    let authority = mint.mint_authority.insecure_clone();
+
+    let initialize = client::initialize(
+        &mint.address,
+        &payer,
+        &authority.pubkey(),
+        args::Initialize {
+            dev_wallet: mint.dev_wallet,
+            governance_authority: mint.governance.pubkey(),
+            cap_unit: CapUnit::Raw,
+            timelock_seconds: TIMELOCK_SECONDS,
+            min_cap_floor: 0,
+            max_cap_change_bps: 0,
+            strict_authorities: false,
+            allow_confidential: false,
+        },
+    );
+    let setup = [
+        initialize,
+        client::init_governance_log(&mint.address, &payer),
+        client::init_extra_account_meta_list(&mint.address, &payer),
+        client::finalize_setup(&mint.address),
+    ];
+    harness.process(&setup, &[&authority]).await.0.unwrap();
+    let config = harness.config(&mint).await;
+    assert_eq!((config.dev_wallets[0], config.governance_authority), (mint.dev_wallet, mint.governance.pubkey()));
+
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW + 1).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    harness.transfer(&mint, &source, &destination, &owner, WALLET_CAP_RAW).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &source, &destination, &owner, 1).await;
+    assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+    harness.process(&[client::get_config(&mint.address)], &[]).await.0.unwrap();
+}
+
+#[tokio::test]
+async fn governance_instructions_from_the_client_execute() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let signer = mint.governance.insecure_clone();
+    let governance = governance(&mint);
+
+    // The same bytes and accounts the harness builds by hand
+    let propose = args::ProposeWalletCapUpdate { new_cap: 2_000_000_000, overwrite: false, memo_hash: [0; 32] };
+    let propose = client::propose_wallet_cap_update(&mint.address, &governance, propose);
+    assert_eq!(propose, mint.propose_wallet_cap(2_000_000_000, false));
+    assert_eq!(client::cancel_wallet_cap_update(&mint.address, &governance), mint.cancel_wallet_cap_update());
+    let executor = Pubkey::new_unique();
+    assert_eq!(client::execute_wallet_cap_update(&mint.address, &executor), mint.execute_wallet_cap_update(&executor));
+
+    let guardian = Pubkey::new_unique();
+    let instructions = [
+        propose,
+        client::cancel_wallet_cap_update(&mint.address, &governance),
+        client::set_guardian(&mint.address, &governance, args::SetGuardian { guardian: Some(guardian) }),
+        client::set_proposal_expiry(&mint.address, &governance, args::SetProposalExpiry { expiry_seconds: 3_600 }),
+        client::set_pause(&mint.address, &governance, args::SetPause { paused: true }),
+    ];
+    harness.process(&instructions, &[&signer]).await.0.unwrap();
+    let config = harness.config(&mint).await;
+    assert!(config.pending_cap_update.is_none());
+    assert_eq!(config.guardian, Some(guardian));
+    assert!(config.is_paused(harness.unix_timestamp().await));
+
+    // A no-argument governed instruction, and one that rejects a signer that isn't the authority
+    let instructions = [
+        client::set_pause(&mint.address, &governance, args::SetPause { paused: false }),
+        client::freeze_config(&mint.address, &governance),
+    ];
+    harness.process(&instructions, &[&signer]).await.0.unwrap();
+    assert!(harness.config(&mint).await.frozen);
+    let impostor = Keypair::new();
+    let set_max_holders = client::set_max_holders(
+        &mint.address,
+        &Governance::authority(impostor.pubkey()),
+        args::SetMaxHolders { max_holders: 10 },
+    );
+    let (result, _) = harness.process(&[set_max_holders], &[&impostor]).await;
+    assert_eq!(result, Err(hook_error(HookError::ConfigFrozen)));
+}
+
+#[tokio::test]
+async fn execute_carries_the_accounts_token_2022_resolves() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 1).await;
+    let destination = harness.create_token_account(&mint, &recipient, 0).await;
+
+    let transfer = Transfer {
+        mint: mint.address,
+        source,
+        destination,
+        authority: owner.pubkey(),
+        source_owner: owner.pubkey(),
+        destination_owner: recipient,
+    };
+    let built = client::execute(&transfer, args::Execute { amount: 1 });
+    let resolved = harness.execute_instruction(&mint, &source, &destination, &owner.pubkey(), 1).await;
+    let addresses = |instruction: &Instruction| instruction.accounts.iter().map(|meta| meta.pubkey).collect::<Vec<_>>();
+    assert_eq!(addresses(&built), addresses(&resolved));
+    assert_eq!((built.program_id, &built.data), (resolved.program_id, &resolved.data));
+
+    // The hook only runs inside a transfer, so hand Token-2022 the client's accounts in place of the
+    // resolved ones: the meta list, what follows it and the hook program
+    let mut transfer = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::id(),
+        &source,
+        &mint.address,
+        &destination,
+        &owner.pubkey(),
+        &[],
+        1,
+        DECIMALS,
+    )
+    .unwrap();
+    transfer.accounts.extend(built.accounts[4..].iter().cloned());
+    transfer.accounts.push(AccountMeta::new_readonly(client::ID, false));
+    harness.process(&[transfer], &[&owner]).await.0.unwrap();
+    assert_eq!(harness.balance(&destination).await, 1);
+}
@@ -0,0 +1,33 @@
+// The crate without `rpc` has to keep building for wasm32-unknown-unknown, where browser frontends link
+// it: anything reaching for solana-client or solana-sdk from the offline half breaks this check first.
+//
+// Without the target installed the check is skipped, unless `CI` is set, where a skip would hide a break.
+
+use std::path::Path;
+use std::process::Command;
+
+const TARGET: &str = "wasm32-unknown-unknown";
+
+fn target_installed() -> bool {
+    let output = Command::new("rustup").args(["target", "list", "--installed"]).output();
+    output.is_ok_and(|output| String::from_utf8_lossy(&output.stdout).lines().any(|line| line.trim() == TARGET))
+}
+
+#[test]
+fn checks_for_wasm32_without_rpc() {
+    if !target_installed() {
+        assert!(std::env::var_os("CI").is_none(), "{} isn't installed: rustup target add {}", TARGET, TARGET);
+        eprintln!("skipping the wasm check: {} isn't installed", TARGET);
+        return;
+    }
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // A target directory of its own, so the check doesn't wait on the lock of the build running it
+    let status = Command::new(env!("CARGO"))
+        .args(["check", "--lib", "--example", "config_pda", "--target", TARGET, "--no-default-features"])
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", manifest_dir.join("target").join("wasm-check"))
+        .status()
+        .unwrap();
+    assert!(status.success(), "cargo check --target {} --no-default-features failed", TARGET);
+}
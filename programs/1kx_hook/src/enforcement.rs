@@ -0,0 +1,1339 @@
+//! Transfer enforcement shared by `transfer_hook`, `execute`, the interface fallback, `can_receive` and
+//! the `check_transfer` instruction.
+//!
+//! `check_transfer` makes every cap decision from raw account data, so it runs without an Anchor
+//! context; `enforce` validates the accounts and applies the stateful parts around it. The destination
+//! side lives in `check_receive`, which `preflight` runs on its own for the two views.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program, program_pack::Pack, sysvar::instructions::load_instruction_at_checked,
+};
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use spl_token_2022::extension::{
+    transfer_hook::TransferHookAccount, AccountType, BaseStateWithExtensions, StateWithExtensions,
+};
+use spl_token_2022::state::{Account, AccountState, Multisig};
+
+use crate::errors::HookError;
+use crate::events::{CircuitBreakerTriggered, HookTransferChecked, LaunchDetected, PermitRedeemed, SniperFlagged};
+use crate::logic::{
+    balance_in_cap_units, check_token_account_mints, check_transfer_authority, classify_transfer,
+    ed25519_verified_messages, enforce_cap, is_confidential_credit, is_exempt, net_transfer_amount,
+    post_transfer_balance, TransferKind,
+};
+use crate::state::{
+    CanReceiveResult, ConfigExtension, ExemptRegistry, HolderState, HookConfig, Lockup, Permit, ReceiveReason,
+    SniperLog, TrustedOwner, UsedNonces,
+};
+use crate::MEMO_PROGRAM_IDS;
+
+/// Accounts every transfer hook entry point passes, in the order Token-2022 resolves them
+pub struct HookAccounts<'a, 'info> {
+    pub source: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+    pub owner: &'a AccountInfo<'info>,
+    pub config: &'a mut HookConfig,
+    pub exempt_registry: &'a AccountInfo<'info>,
+    pub instructions_sysvar: &'a AccountInfo<'info>,
+    pub used_nonces: &'a AccountInfo<'info>,
+    pub source_holder: &'a AccountInfo<'info>,
+    pub destination_holder: &'a AccountInfo<'info>,
+    pub source_lockup: &'a AccountInfo<'info>,
+    pub config_extension: &'a AccountInfo<'info>,
+    pub sniper_log: &'a AccountInfo<'info>,
+    pub destination_trusted_owner: &'a AccountInfo<'info>,
+}
+
+/// A transfer as seen by the cap checks
+pub struct Transfer<'a> {
+    pub source: &'a Pubkey,
+    pub source_data: &'a [u8],
+    pub destination: &'a Pubkey,
+    pub destination_data: &'a [u8],
+    pub mint_data: &'a [u8],
+    pub amount: u64,
+}
+
+impl<'a> Transfer<'a> {
+    pub fn receipt(&self) -> Receipt<'a> {
+        Receipt {
+            destination: self.destination,
+            destination_data: self.destination_data,
+            mint_data: self.mint_data,
+            amount: self.amount,
+        }
+    }
+}
+
+/// The receiving side of a transfer, which is all `can_receive` is given
+pub struct Receipt<'a> {
+    pub destination: &'a Pubkey,
+    pub destination_data: &'a [u8],
+    pub mint_data: &'a [u8],
+    pub amount: u64,
+}
+
+/// Outcome of `check_transfer` for a transfer it did not reject outright
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// Moves no tokens between accounts, so there is nothing to record either
+    Unchanged,
+    /// Within the applicable cap
+    Allowed,
+    /// Exempt from the cap: a registered exempt account, a dev wallet or a trusted program's pool authority
+    Exempt,
+    /// Leaves the destination over its cap; only the airdrop allowance or a permit can let it through
+    OverCap { destination_owner: Pubkey },
+}
+
+/// Validate the accounts, decide the transfer, then record it in the config
+pub fn enforce(accounts: HookAccounts, amount: u64) -> Result<()> {
+    validate_accounts(&accounts)?;
+    // A permanently disabled hook allows everything; only the account checks above still apply
+    if accounts.config.hook_disabled {
+        return Ok(());
+    }
+    // Nothing moves before finalize_setup has checked the mint's setup, not even from a dev wallet
+    require!(accounts.config.finalized, HookError::SetupNotFinalized);
+    // Only a mint initialized with allow_confidential can have confidential accounts to credit
+    if accounts.config.allow_confidential {
+        let confidential = is_confidential_credit(&accounts.destination.try_borrow_data()?, amount)?;
+        require!(!confidential, HookError::ConfidentialTransferUnsupported);
+    }
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let registry = load_exempt_registry(accounts.exempt_registry)?;
+    let trusted_owner = load_trusted_owner(accounts.destination_trusted_owner, accounts.mint.key)?;
+    let (verdict, checked, buy, sell) = {
+        let source_data = accounts.source.try_borrow_data()?;
+        let destination_data = accounts.destination.try_borrow_data()?;
+        let mint_data = accounts.mint.try_borrow_data()?;
+        let transfer = Transfer {
+            source: accounts.source.key,
+            source_data: &source_data,
+            destination: accounts.destination.key,
+            destination_data: &destination_data,
+            mint_data: &mint_data,
+            amount,
+        };
+        let verdict = check_transfer(accounts.config, registry.as_ref(), trusted_owner.as_ref(), &transfer, &clock)?;
+        hook_log!("Verdict: {:?}", verdict);
+        // Built while the account data is borrowed, emitted once the transfer is known to go through
+        let checked = if accounts.config.emit_transfer_events && verdict != Verdict::Unchanged {
+            Some(transfer_checked(accounts.config, registry.as_ref(), &transfer, &clock, accounts.mint.key, &verdict)?)
+        } else {
+            None
+        };
+        let buy = registry.as_ref().is_some_and(|registry| registry.is_pool_vault(accounts.source.key, now));
+        let sell = registry.as_ref().is_some_and(|registry| registry.is_pool_vault(accounts.destination.key, now));
+        (verdict, checked, buy, sell)
+    };
+    if verdict == Verdict::Unchanged {
+        return Ok(());
+    }
+
+    // A locked-up owner can't send what is still locked, whatever the destination
+    check_lockup(accounts.source_lockup, accounts.mint.key, &accounts.source.try_borrow_data()?, amount, now)?;
+
+    // The dev wallets' sells into pool vaults are held to their daily outflow limit, though they are exempt
+    // from the caps; moving tokens anywhere else doesn't count
+    if sell && accounts.config.is_dev_wallet(&token_account_owner(accounts.source)?) {
+        record_dev_outflow(accounts.config_extension, accounts.mint.key, amount, now)?;
+    }
+
+    // Transfers above the memo threshold need a memo in the transaction unless either side is exempt. Mints
+    // without a threshold never read the instructions sysvar.
+    if let Some(threshold) = memo_threshold(accounts.config_extension, accounts.mint.key)? {
+        if amount > threshold && verdict != Verdict::Exempt {
+            let source_exempt = registry.as_ref().is_some_and(|registry| registry.is_exempt(accounts.source.key, now))
+                || is_exempt(accounts.config, &token_account_owner(accounts.source)?, now);
+            require!(source_exempt || has_memo(accounts.instructions_sysvar), HookError::MemoRequired);
+        }
+    }
+
+    // Every transfer counts toward the circuit breaker window, exempt or not. The pause was checked
+    // above, before this volume is recorded, so the transfer that trips the breaker still lands.
+    let config = accounts.config;
+    if config.record_volume(amount, clock.slot, now) {
+        emit!(CircuitBreakerTriggered {
+            mint: *accounts.mint.key,
+            slot: clock.slot,
+            window_start_slot: config.window_start_slot,
+            paused_until: config.paused_until,
+            triggered_at: now,
+        });
+    }
+
+    // Holder accounting covers every transfer, exempt or not
+    let holders = HolderAccounts {
+        source: accounts.source,
+        destination: accounts.destination,
+        source_holder: accounts.source_holder,
+        destination_holder: accounts.destination_holder,
+    };
+    track_holders(config, accounts.mint.key, &holders, amount, now)?;
+
+    // The first buy out of a registered pool vault marks when trading began; those soon after flag snipers
+    if buy {
+        let buy_accounts = BuyAccounts {
+            config_extension: accounts.config_extension,
+            sniper_log: accounts.sniper_log,
+            pool_vault: accounts.source,
+            destination: accounts.destination,
+        };
+        record_buy(&buy_accounts, accounts.mint.key, amount, &clock)?;
+    }
+
+    // Over-cap airdrops sent from a dev wallet consume the governance-set allowance, anything else
+    // needs a permit in this transaction
+    if let Verdict::OverCap { destination_owner } = verdict {
+        if !(config.is_dev_wallet(&token_account_owner(accounts.source)?) && config.consume_airdrop_allowance()) {
+            redeem_permit(
+                config,
+                accounts.mint.key,
+                &destination_owner,
+                amount,
+                &clock,
+                accounts.instructions_sysvar,
+                accounts.used_nonces,
+            )?;
+        }
+    }
+
+    config.record_transfer_stats(amount)?;
+    if let Some(checked) = checked {
+        emit!(checked);
+    }
+    Ok(())
+}
+
+/// Require the hook to have been called by Token-2022 mid-transfer, with token accounts of the
+/// hooked mint and the authority allowed to move the source's tokens
+pub fn validate_accounts(accounts: &HookAccounts) -> Result<()> {
+    require!(accounts.config.is_supported_version(), HookError::UnsupportedVersion);
+    let config = accounts.config;
+    check_pda(accounts.exempt_registry, b"exempt-registry", accounts.mint.key, config.exempt_registry_bump)?;
+    check_pda(accounts.used_nonces, b"used-nonces", accounts.mint.key, config.used_nonces_bump)?;
+    require!(accounts.source.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+    require!(accounts.destination.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+    require!(accounts.mint.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+
+    // Each token account is unpacked once here; the checks below all read from that
+    let (source_data, destination_data) = (accounts.source.try_borrow_data()?, accounts.destination.try_borrow_data()?);
+    let source = StateWithExtensions::<Account>::unpack(&source_data)?;
+    let destination = StateWithExtensions::<Account>::unpack(&destination_data)?;
+
+    // Only Token-2022 mid-transfer can have flagged both accounts, so crafted direct calls stop here
+    require!(is_transferring(&source), HookError::NotInTransfer);
+    require!(is_transferring(&destination), HookError::NotInTransfer);
+
+    check_token_account_mints(&source.base, &destination.base, accounts.mint.key)?;
+    check_transfer_authority(&source.base, accounts.owner.key)
+}
+
+/// Require `info` to be the mint's PDA for `seed`: one `create_program_address` with the bump the config
+/// stored
+fn check_pda(info: &AccountInfo, seed: &[u8], mint: &Pubkey, bump: u8) -> Result<()> {
+    let address = Pubkey::create_program_address(&[seed, mint.as_ref(), &[bump]], &crate::ID)
+        .map_err(|_| anchor_lang::error::ErrorCode::ConstraintSeeds)?;
+    require_keys_eq!(*info.key, address, anchor_lang::error::ErrorCode::ConstraintSeeds);
+    Ok(())
+}
+
+/// Decide a transfer against the config. `trusted_owner` is the destination owner's record from
+/// `register_trusted_owner`, if it has one, for the trusted program exemption.
+pub fn check_transfer(
+    config: &HookConfig,
+    registry: Option<&ExemptRegistry>,
+    trusted_owner: Option<&TrustedOwner>,
+    transfer: &Transfer,
+    clock: &Clock,
+) -> Result<Verdict> {
+    // No-op transfers cannot change any balance; skip the cap logic entirely
+    if transfer.amount == 0 {
+        return Ok(Verdict::Unchanged);
+    }
+
+    // Neither can a transfer from an account to itself, which some routers emit; allow it
+    // rather than checking the account's own balance plus the amount against the cap
+    if transfer.source == transfer.destination {
+        return Ok(Verdict::Unchanged);
+    }
+
+    let now = clock.unix_timestamp;
+    let source = fast_account_view(transfer.source_data)?;
+    let transfer_kind = classify_transfer(registry, transfer.source, transfer.destination, now);
+
+    // Sells into a registered pool vault are size-limited unless the seller is an exempt dev wallet
+    if transfer_kind == TransferKind::Sell && config.sell_limit_exceeded(transfer.amount) {
+        require!(is_exempt(config, &source.owner, now), HookError::SellLimitExceeded);
+    }
+
+    let receipt = transfer.receipt();
+    let verdict = check_receive(config, registry, trusted_owner, transfer_kind, &receipt, clock)?;
+
+    // Unstaking back over the cap is refused with an error of its own, so the staking UI can explain it.
+    // Neither the airdrop allowance nor a permit lets it through.
+    if transfer_kind == TransferKind::Unstake && matches!(verdict, Verdict::OverCap { .. }) {
+        return err!(HookError::UnstakeWouldExceedCap);
+    }
+    Ok(verdict)
+}
+
+/// Decide the receiving side of a transfer of `transfer_kind` that moves tokens: the destination's
+/// exemptions, the pause and the applicable cap
+pub fn check_receive(
+    config: &HookConfig,
+    registry: Option<&ExemptRegistry>,
+    trusted_owner: Option<&TrustedOwner>,
+    transfer_kind: TransferKind,
+    receipt: &Receipt,
+    clock: &Clock,
+) -> Result<Verdict> {
+    let now = clock.unix_timestamp;
+
+    // Registered exempt token accounts (pool vaults) are matched by address
+    if registry.is_some_and(|registry| registry.is_exempt(receipt.destination, now)) {
+        return Ok(Verdict::Exempt);
+    }
+
+    // So is the primary dev wallet's ATA, where most over-cap launch transfers land, which saves unpacking
+    // it. Dev wallets receiving into any other account are caught by the owner check below
+    if config.is_dev_wallet_ata(receipt.destination) && config.dev_exemption_active(now) {
+        return Ok(Verdict::Exempt);
+    }
+
+    let destination = fast_account_view(receipt.destination_data)?;
+
+    // Dev wallets are exempt from the cap until the exemption sunsets
+    let destination_owner = destination.owner;
+    if is_exempt(config, &destination_owner, now) {
+        return Ok(Verdict::Exempt);
+    }
+
+    // Pool authorities of whitelisted AMM programs are exempt
+    if is_trusted_program_owner(config, &destination_owner, trusted_owner) {
+        return Ok(Verdict::Exempt);
+    }
+
+    // In strict mode every other owner must be a wallet. PDAs are off the curve that keypairs are on, so
+    // the key the destination already stores tells them apart without the owner's account.
+    if config.require_wallet_owners && !destination_owner.is_on_curve() {
+        return err!(HookError::ProgramOwnedDestination);
+    }
+
+    // Exempt destinations were let through above; everything else halts while the breaker is tripped
+    require!(!config.is_paused(now), HookError::CircuitBreakerTripped);
+
+    let post_balance = post_balance(config, receipt, destination.amount, clock)?;
+
+    // Buys out of a registered pool vault are held to the buy cap, everything else to the wallet cap.
+    // The unit conversion applies to the whole post-transfer balance, so nothing is left to add to it.
+    if enforce_cap(config.cap(transfer_kind.cap_kind()), post_balance, 0).is_err() {
+        return Ok(Verdict::OverCap { destination_owner });
+    }
+    Ok(Verdict::Allowed)
+}
+
+/// What `can_receive` reports for `receipt`: the verdict `execute` would reach for a wallet-to-wallet
+/// transfer of the amount, with bounces reported instead of raised. Over-cap receives are reported as
+/// rejected even though an airdrop allowance or a permit could still let them through.
+pub fn preflight(
+    config: &HookConfig,
+    registry: Option<&ExemptRegistry>,
+    receipt: &Receipt,
+    trusted_owner: Option<&TrustedOwner>,
+    mint: &Pubkey,
+    clock: &Clock,
+) -> Result<CanReceiveResult> {
+    let destination = StateWithExtensions::<Account>::unpack(receipt.destination_data)?;
+    require_keys_eq!(destination.base.mint, *mint, HookError::DestinationMintMismatch);
+    if config.hook_disabled {
+        let reason_code = ReceiveReason::HookDisabled as u8;
+        return Ok(CanReceiveResult { allowed: true, reason_code, remaining_capacity: u64::MAX });
+    }
+    if !config.finalized {
+        let reason_code = ReceiveReason::NotFinalized as u8;
+        return Ok(CanReceiveResult { allowed: false, reason_code, remaining_capacity: 0 });
+    }
+
+    let kind = TransferKind::WalletToWallet;
+    let (allowed, reason, remaining_capacity) =
+        match check_receive(config, registry, trusted_owner, kind, receipt, clock) {
+            Ok(Verdict::Exempt) => (true, ReceiveReason::Exempt, u64::MAX),
+            Ok(Verdict::OverCap { .. }) => (false, ReceiveReason::OverCap, 0),
+            Ok(Verdict::Allowed | Verdict::Unchanged) => {
+                let post_balance = post_balance(config, receipt, destination.base.amount, clock)?;
+                (true, ReceiveReason::Allowed, config.cap(kind.cap_kind()).saturating_sub(post_balance))
+            }
+            Err(error) if error == anchor_lang::error::Error::from(HookError::CircuitBreakerTripped) => {
+                (false, ReceiveReason::Paused, 0)
+            }
+            Err(error) if error == anchor_lang::error::Error::from(HookError::ProgramOwnedDestination) => {
+                (false, ReceiveReason::ProgramOwned, 0)
+            }
+            Err(error) => return Err(error),
+        };
+
+    // A zero amount moves nothing, so `execute` lets it through whatever the destination's state
+    let (allowed, reason) = if receipt.amount == 0 { (true, ReceiveReason::Unchanged) } else { (allowed, reason) };
+    Ok(CanReceiveResult { allowed, reason_code: reason as u8, remaining_capacity })
+}
+
+/// Post-transfer balance from what the destination actually receives after transfer fees, in the
+/// units the cap is expressed in
+pub fn post_balance(config: &HookConfig, receipt: &Receipt, destination_amount: u64, clock: &Clock) -> Result<u64> {
+    let received = net_transfer_amount(receipt.mint_data, receipt.amount, clock.epoch)?;
+    let post_balance = post_transfer_balance(destination_amount, received)?;
+    balance_in_cap_units(config.cap_unit, receipt.mint_data, post_balance, clock.unix_timestamp)
+}
+
+/// The `HookTransferChecked` event for a transfer `check_transfer` let through with `verdict`
+pub fn transfer_checked(
+    config: &HookConfig,
+    registry: Option<&ExemptRegistry>,
+    transfer: &Transfer,
+    clock: &Clock,
+    mint: &Pubkey,
+    verdict: &Verdict,
+) -> Result<HookTransferChecked> {
+    let destination = fast_account_view(transfer.destination_data)?;
+    let transfer_kind = classify_transfer(registry, transfer.source, transfer.destination, clock.unix_timestamp);
+    Ok(HookTransferChecked {
+        mint: *mint,
+        slot: clock.slot,
+        destination_owner: destination.owner,
+        amount: transfer.amount,
+        post_balance: post_balance(config, &transfer.receipt(), destination.amount, clock)?,
+        cap: config.cap(transfer_kind.cap_kind()),
+        exempt: *verdict == Verdict::Exempt,
+    })
+}
+
+/// Allow an over-cap receive backed by a permit that an ed25519 instruction in this transaction
+/// verified, recording its nonce so it cannot be replayed. Fails with the first permit's error if
+/// none applies, or `WalletCapExceeded` when there is no permit at all.
+pub fn redeem_permit(
+    config: &HookConfig,
+    mint: &Pubkey,
+    destination_owner: &Pubkey,
+    amount: u64,
+    clock: &Clock,
+    instructions_sysvar: &AccountInfo,
+    used_nonces: &AccountInfo,
+) -> Result<()> {
+    if config.permit_signer == Pubkey::default() {
+        return err!(HookError::WalletCapExceeded);
+    }
+
+    let mut first_error = None;
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+        if instruction.program_id != ed25519_program::ID {
+            continue;
+        }
+        for message in ed25519_verified_messages(&instruction.data, &config.permit_signer) {
+            let permit = match Permit::decode(message) {
+                Some(permit) => permit,
+                None => continue,
+            };
+            let redeemed = permit
+                .check(mint, destination_owner, amount, clock.unix_timestamp)
+                .and_then(|()| record_permit_nonce(used_nonces, permit.nonce));
+            match redeemed {
+                Ok(()) => {
+                    emit!(PermitRedeemed {
+                        mint: *mint,
+                        slot: clock.slot,
+                        destination_owner: *destination_owner,
+                        amount,
+                        nonce: permit.nonce,
+                        redeemed_at: clock.unix_timestamp,
+                    });
+                    return Ok(());
+                }
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+    }
+
+    Err(first_error.unwrap_or_else(|| HookError::WalletCapExceeded.into()))
+}
+
+pub fn record_permit_nonce(used_nonces: &AccountInfo, nonce: u64) -> Result<()> {
+    require!(
+        used_nonces.owner == &crate::ID && !used_nonces.data_is_empty(),
+        HookError::UsedNoncesNotInitialized
+    );
+    let mut data = used_nonces.try_borrow_mut_data()?;
+    let mut nonces = UsedNonces::try_deserialize(&mut &data[..])?;
+    nonces.mark_used(nonce)?;
+    let mut writer: &mut [u8] = &mut data[..];
+    nonces.try_serialize(&mut writer)
+}
+
+/// Whether Token-2022 has set the token account's `transferring` flag, which it only does for the
+/// duration of its CPI into the hook
+pub fn is_transferring(account: &StateWithExtensions<Account>) -> bool {
+    account.get_extension::<TransferHookAccount>().is_ok_and(|hook| bool::from(hook.transferring))
+}
+
+/// Owner of a Token-2022 token account
+pub fn token_account_owner(info: &AccountInfo) -> Result<Pubkey> {
+    Ok(fast_account_view(&info.try_borrow_data()?)?.owner)
+}
+
+/// The base account fields the cap checks read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountView {
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+/// `owner` and `amount` of a Token-2022 account, read at their fixed offsets in the base account instead
+/// of unpacking it with `StateWithExtensions`, which runs on every transfer. Refuses everything the
+/// unpack refuses: data shorter than a base account, a multisig, an uninitialized account, a malformed
+/// option tag and extended data whose account type isn't `Account` or that has no room for an extension
+/// after it. The extensions themselves are left to whoever reads them, as they are by the unpack.
+pub fn fast_account_view(data: &[u8]) -> Result<AccountView> {
+    const STATE_OFFSET: usize = 108;
+    // The `COption` tags of `delegate`, `is_native` and `close_authority`
+    const OPTION_TAG_OFFSETS: [usize; 3] = [72, 109, 129];
+
+    let is_account = data.len() >= Account::LEN
+        && data.len() != Multisig::LEN
+        && [AccountState::Initialized as u8, AccountState::Frozen as u8].contains(&data[STATE_OFFSET])
+        && OPTION_TAG_OFFSETS.iter().all(|&offset| matches!(data[offset..offset + 4], [0 | 1, 0, 0, 0]))
+        && (data.len() == Account::LEN
+            || (data.len() > Account::LEN + 1 && data[Account::LEN] == AccountType::Account as u8));
+    if !is_account {
+        return Err(ProgramError::InvalidAccountData.into());
+    }
+    let view = AccountView {
+        owner: Pubkey::try_from(&data[32..64]).unwrap(),
+        amount: u64::from_le_bytes(data[64..72].try_into().unwrap()),
+    };
+
+    // The full unpack remains the reference; debug builds, and so every test, check the view against it
+    debug_assert!(StateWithExtensions::<Account>::unpack(data)
+        .is_ok_and(|account| account.base.owner == view.owner && account.base.amount == view.amount));
+    Ok(view)
+}
+
+/// The token accounts of a transfer and their owners' HolderState PDAs
+pub struct HolderAccounts<'a, 'info> {
+    pub source: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+    pub source_holder: &'a AccountInfo<'info>,
+    pub destination_holder: &'a AccountInfo<'info>,
+}
+
+/// Update both owners' HolderStates for a transfer: their lifetime stats, and while a holder limit is
+/// set their tracked balances and the config's holder count.
+///
+/// The hook cannot create accounts, so both owners need a HolderState (`init_holder_state`) before
+/// they can send or receive while holder tracking is on. Otherwise the stats are best-effort and an
+/// owner without one is skipped.
+pub fn track_holders(
+    config: &mut HookConfig,
+    mint: &Pubkey,
+    holders: &HolderAccounts,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    let tracking = config.max_holders > 0;
+    // Checked before the token accounts are unpacked, so untracked transfers pay next to nothing
+    if !tracking && !holder_state_exists(holders.source_holder) && !holder_state_exists(holders.destination_holder)
+    {
+        return Ok(());
+    }
+
+    let source_owner = token_account_owner(holders.source)?;
+    let destination_owner = token_account_owner(holders.destination)?;
+    if source_owner == destination_owner {
+        return Ok(());
+    }
+
+    let mut source_state = optional_holder_state(holders.source_holder, mint, &source_owner, tracking)?;
+    let mut destination_state =
+        optional_holder_state(holders.destination_holder, mint, &destination_owner, tracking)?;
+    if let (true, Some(source), Some(destination)) = (tracking, source_state.as_mut(), destination_state.as_mut()) {
+        config.apply_holder_transfer(source, destination, amount)?;
+    }
+    if let Some(state) = source_state.as_mut() {
+        state.record_sent(amount, now);
+        store_holder_state(holders.source_holder, state)?;
+    }
+    if let Some(state) = destination_state.as_mut() {
+        state.record_received(amount, now);
+        store_holder_state(holders.destination_holder, state)?;
+    }
+    Ok(())
+}
+
+/// Reject a transfer that would leave the source below what its owner's Lockup still holds back at `now`.
+/// Owners without a Lockup PDA aren't locked.
+pub fn check_lockup(info: &AccountInfo, mint: &Pubkey, source_data: &[u8], amount: u64, now: i64) -> Result<()> {
+    // Checked before anything is unpacked, so unlocked owners pay next to nothing
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(());
+    }
+    let lockup = Lockup::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let source = fast_account_view(source_data)?;
+    require!(lockup.mint == *mint && lockup.owner == source.owner, HookError::InvalidLockup);
+    require!(source.amount.saturating_sub(amount) >= lockup.locked_at(now), HookError::TokensLocked);
+    Ok(())
+}
+
+/// Accounts a buy out of a pool vault is recorded in
+pub struct BuyAccounts<'a, 'info> {
+    pub config_extension: &'a AccountInfo<'info>,
+    pub sniper_log: &'a AccountInfo<'info>,
+    pub pool_vault: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+}
+
+/// Record a buy out of a pool vault in the mint's ConfigExtension: the first one marks the launch, which is
+/// kept from then on, and any landing in the snipe window flags the destination owner. Mints without a
+/// ConfigExtension record nothing.
+pub fn record_buy(accounts: &BuyAccounts, mint: &Pubkey, amount: u64, clock: &Clock) -> Result<()> {
+    let info = accounts.config_extension;
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(());
+    }
+    let mut extension = ConfigExtension::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require_keys_eq!(extension.mint, *mint, HookError::InvalidConfigExtension);
+    let now = clock.unix_timestamp;
+    let window_used = extension.launch_window_used;
+    let launched = extension.record_launch(now, clock.slot);
+    let snipe = extension.is_snipe(now);
+    if launched || extension.launch_window_used != window_used {
+        let mut data = info.try_borrow_mut_data()?;
+        let mut writer: &mut [u8] = &mut data[..];
+        extension.try_serialize(&mut writer)?;
+    }
+    if launched {
+        emit!(LaunchDetected {
+            mint: *mint,
+            slot: clock.slot,
+            pool_vault: *accounts.pool_vault.key,
+            amount,
+            detected_at: now,
+        });
+    }
+    if snipe {
+        let owner = token_account_owner(accounts.destination)?;
+        let logged = flag_sniper(accounts.sniper_log, mint, owner)?;
+        emit!(SniperFlagged {
+            mint: *mint,
+            slot: clock.slot,
+            owner,
+            amount,
+            seconds_after_launch: extension.seconds_since_launch(now).unwrap_or_default(),
+            logged,
+        });
+    }
+    Ok(())
+}
+
+/// List `owner` in the mint's SniperLog, returning whether it is listed. A log that is full or hasn't been
+/// created lists nobody; the flag still goes out in the event.
+fn flag_sniper(info: &AccountInfo, mint: &Pubkey, owner: Pubkey) -> Result<bool> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(false);
+    }
+    let mut log = SniperLog::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require_keys_eq!(log.mint, *mint, HookError::InvalidSniperLog);
+    if log.sniper_list.contains(&owner) {
+        return Ok(true);
+    }
+    let logged = log.flag(owner);
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    log.try_serialize(&mut writer)?;
+    Ok(logged)
+}
+
+/// Count a dev wallet's sell into a pool vault against the outflow limit in the mint's ConfigExtension.
+/// Mints without a ConfigExtension have no limit.
+fn record_dev_outflow(info: &AccountInfo, mint: &Pubkey, amount: u64, now: i64) -> Result<()> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(());
+    }
+    let mut extension = ConfigExtension::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require_keys_eq!(extension.mint, *mint, HookError::InvalidConfigExtension);
+    if extension.dev_daily_outflow_limit.is_none() {
+        return Ok(());
+    }
+    extension.record_dev_outflow(amount, now)?;
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    extension.try_serialize(&mut writer)
+}
+
+/// The mint's memo threshold, or None if its ConfigExtension hasn't been created or sets none
+fn memo_threshold(info: &AccountInfo, mint: &Pubkey) -> Result<Option<u64>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    let extension = ConfigExtension::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    require_keys_eq!(extension.mint, *mint, HookError::InvalidConfigExtension);
+    Ok(extension.memo_required_above)
+}
+
+/// Whether the transaction has an SPL Memo instruction. A memo written through a CPI isn't in the
+/// instructions sysvar, so only a top-level memo instruction counts.
+pub fn has_memo(instructions_sysvar: &AccountInfo) -> bool {
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        if MEMO_PROGRAM_IDS.contains(&instruction.program_id) {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+/// Whether `info` has been created as a HolderState (or at least as an account of this program)
+pub fn holder_state_exists(info: &AccountInfo) -> bool {
+    info.owner == &crate::ID && !info.data_is_empty()
+}
+
+/// The owner's HolderState, or None if it hasn't been created and isn't `required`
+fn optional_holder_state(
+    info: &AccountInfo,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    required: bool,
+) -> Result<Option<HolderState>> {
+    if !required && !holder_state_exists(info) {
+        return Ok(None);
+    }
+    load_holder_state(info, mint, owner).map(Some)
+}
+
+pub fn load_holder_state(info: &AccountInfo, mint: &Pubkey, owner: &Pubkey) -> Result<HolderState> {
+    require!(holder_state_exists(info), HookError::HolderStateNotInitialized);
+    let data = info.try_borrow_data()?;
+    // Accounts created before the lifetime stats end at the balance; the stats read as zero
+    let state = if data.len() < HolderState::SPACE {
+        let mut padded = data.to_vec();
+        padded.resize(HolderState::SPACE, 0);
+        HolderState::try_deserialize(&mut &padded[..])?
+    } else {
+        HolderState::try_deserialize(&mut &data[..])?
+    };
+    require!(
+        state.mint == *mint && state.owner == *owner,
+        HookError::InvalidHolderState
+    );
+    Ok(state)
+}
+
+pub fn store_holder_state(info: &AccountInfo, state: &HolderState) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    if data.len() < HolderState::SPACE {
+        // An older, shorter account keeps tracking the balance but has no room for the stats
+        let mut serialized = Vec::with_capacity(HolderState::SPACE);
+        state.try_serialize(&mut serialized)?;
+        let len = data.len();
+        data.copy_from_slice(&serialized[..len]);
+        return Ok(());
+    }
+    let mut writer: &mut [u8] = &mut data[..];
+    state.try_serialize(&mut writer)
+}
+
+/// Deserialize the exempt registry if it has been created; an uninitialized PDA means no registry exemptions
+pub fn load_exempt_registry(info: &AccountInfo) -> Result<Option<ExemptRegistry>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    let data = info.try_borrow_data()?;
+    Ok(Some(ExemptRegistry::try_deserialize(&mut &data[..])?))
+}
+
+/// Deserialize a `TrustedOwner` record if it has been created; an uninitialized PDA, or a record made for
+/// another mint, means its owner isn't exempt
+pub fn load_trusted_owner(info: &AccountInfo, mint: &Pubkey) -> Result<Option<TrustedOwner>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+    let record = TrustedOwner::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    Ok(Some(record).filter(|record| record.mint == *mint))
+}
+
+/// Whether `owner` was registered as a PDA account of a program that is still trusted.
+///
+/// spl-tlv-account-resolution 0.6 can only resolve PDAs (or fixed keys), not an address read out of
+/// another account's data, so Token-2022 can't pass the owner's own account. The ExtraAccountMetaList
+/// resolves the owner's `TrustedOwner` record from the destination instead, which
+/// `register_trusted_owner` creates after re-deriving the owner under its program.
+pub fn is_trusted_program_owner(config: &HookConfig, owner: &Pubkey, trusted_owner: Option<&TrustedOwner>) -> bool {
+    trusted_owner.is_some_and(|record| record.owner == *owner && config.is_trusted_program(&record.program))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        assert_hook_error, interest_bearing_mint, mint_with_transfer_fee, plain_mint, test_config,
+        token_account_data,
+    };
+    use crate::state::{CapUnit, ExemptEntry, ExemptKind};
+    use crate::{CONFIG_VERSION, WALLET_CAP_RAW};
+    use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+    use spl_token_2022::extension::{
+        immutable_owner::ImmutableOwner, memo_transfer::MemoTransfer, BaseStateWithExtensionsMut, ExtensionType,
+        StateWithExtensionsMut,
+    };
+
+    const NOW: i64 = 1_700_000_000;
+
+    /// A wallet-to-wallet transfer between fresh accounts, the destination already holding `balance`
+    struct Fixture {
+        mint: Pubkey,
+        source: Pubkey,
+        source_owner: Pubkey,
+        destination: Pubkey,
+        destination_owner: Pubkey,
+        balance: u64,
+        mint_data: Vec<u8>,
+        trusted_owner: Option<TrustedOwner>,
+    }
+
+    impl Fixture {
+        fn new(balance: u64) -> Self {
+            Self {
+                mint: Pubkey::new_unique(),
+                source: Pubkey::new_unique(),
+                source_owner: Pubkey::new_unique(),
+                destination: Pubkey::new_unique(),
+                destination_owner: Pubkey::new_unique(),
+                balance,
+                mint_data: plain_mint(),
+                trusted_owner: None,
+            }
+        }
+
+        fn check(&self, config: &HookConfig, registry: Option<&ExemptRegistry>, amount: u64) -> Result<Verdict> {
+            let source_data = token_account_data(self.mint, self.source_owner, 1_000 * WALLET_CAP_RAW);
+            let destination_data = token_account_data(self.mint, self.destination_owner, self.balance);
+            let transfer = Transfer {
+                source: &self.source,
+                source_data: &source_data,
+                destination: &self.destination,
+                destination_data: &destination_data,
+                mint_data: &self.mint_data,
+                amount,
+            };
+            let clock = Clock { unix_timestamp: NOW, ..Clock::default() };
+            check_transfer(config, registry, self.trusted_owner.as_ref(), &transfer, &clock)
+        }
+
+        fn over_cap(&self) -> Verdict {
+            Verdict::OverCap { destination_owner: self.destination_owner }
+        }
+    }
+
+    fn registry(entries: Vec<ExemptEntry>) -> ExemptRegistry {
+        ExemptRegistry { rent_recipient: Pubkey::new_unique(), entries }
+    }
+
+    fn pool_vault(token_account: Pubkey) -> ExemptEntry {
+        ExemptEntry { token_account, kind: ExemptKind::PoolVault, expires_at: None }
+    }
+
+    #[test]
+    fn test_balances_against_the_wallet_cap() {
+        let config = test_config();
+        let fixture = Fixture::new(WALLET_CAP_RAW - 100);
+        assert_eq!(fixture.check(&config, None, 100).unwrap(), Verdict::Allowed);
+        assert_eq!(fixture.check(&config, None, 101).unwrap(), fixture.over_cap());
+    }
+
+    #[test]
+    fn test_balance_overflow_is_not_reported_as_over_cap() {
+        let config = test_config();
+        let fixture = Fixture::new(u64::MAX);
+        assert_hook_error(fixture.check(&config, None, 1), HookError::ArithmeticOverflow);
+
+        let fixture = Fixture::new(WALLET_CAP_RAW);
+        assert_hook_error(fixture.check(&config, None, u64::MAX), HookError::ArithmeticOverflow);
+        assert_eq!(fixture.check(&config, None, u64::MAX - WALLET_CAP_RAW).unwrap(), fixture.over_cap());
+    }
+
+    #[test]
+    fn test_no_op_transfers_are_unchanged() {
+        let mut config = test_config();
+        config.paused_until = i64::MAX;
+        let mut fixture = Fixture::new(WALLET_CAP_RAW);
+        assert_eq!(fixture.check(&config, None, 0).unwrap(), Verdict::Unchanged);
+
+        // A self-transfer is skipped before anything is unpacked or the pause is consulted
+        fixture.destination = fixture.source;
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Unchanged);
+    }
+
+    #[test]
+    fn test_buys_use_the_buy_cap() {
+        let mut config = test_config();
+        config.buy_cap_raw = WALLET_CAP_RAW / 2;
+        let fixture = Fixture::new(0);
+        let registry = registry(vec![pool_vault(fixture.source)]);
+
+        assert_eq!(fixture.check(&config, Some(&registry), WALLET_CAP_RAW / 2).unwrap(), Verdict::Allowed);
+        assert_eq!(fixture.check(&config, Some(&registry), WALLET_CAP_RAW / 2 + 1).unwrap(), fixture.over_cap());
+
+        // The same amount from a wallet is held to the wallet cap
+        assert_eq!(fixture.check(&config, None, WALLET_CAP_RAW / 2 + 1).unwrap(), Verdict::Allowed);
+    }
+
+    #[test]
+    fn test_sell_limit() {
+        let mut config = test_config();
+        config.max_sell_raw = 1_000;
+        let mut fixture = Fixture::new(0);
+        let registry = registry(vec![pool_vault(fixture.destination)]);
+
+        assert_eq!(fixture.check(&config, Some(&registry), 1_000).unwrap(), Verdict::Exempt);
+        assert_hook_error(fixture.check(&config, Some(&registry), 1_001), HookError::SellLimitExceeded);
+
+        // Only sells are limited
+        assert_eq!(fixture.check(&config, None, 1_001).unwrap(), Verdict::Allowed);
+
+        // Dev wallets may sell past the limit until their exemption sunsets
+        fixture.source_owner = config.dev_wallets[0];
+        assert_eq!(fixture.check(&config, Some(&registry), 1_001).unwrap(), Verdict::Exempt);
+        config.dev_exemption_expires_at = Some(NOW);
+        assert_hook_error(fixture.check(&config, Some(&registry), 1_001), HookError::SellLimitExceeded);
+    }
+
+    #[test]
+    fn test_registry_exempt_destination() {
+        let mut config = test_config();
+        config.paused_until = i64::MAX;
+        let fixture = Fixture::new(WALLET_CAP_RAW);
+        let mut registry = registry(vec![ExemptEntry {
+            token_account: fixture.destination,
+            kind: ExemptKind::Generic,
+            expires_at: Some(NOW + 1),
+        }]);
+
+        // Exempt even while paused and over the cap
+        assert_eq!(fixture.check(&config, Some(&registry), 1).unwrap(), Verdict::Exempt);
+
+        // Until the entry expires
+        registry.entries[0].expires_at = Some(NOW);
+        assert_hook_error(fixture.check(&config, Some(&registry), 1), HookError::CircuitBreakerTripped);
+    }
+
+    #[test]
+    fn test_dev_wallet_destination() {
+        let mut config = test_config();
+        let mut fixture = Fixture::new(WALLET_CAP_RAW);
+        fixture.destination_owner = config.dev_wallets[0];
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Exempt);
+
+        // Once the exemption sunsets dev wallets are capped like everyone else
+        config.dev_exemption_expires_at = Some(NOW);
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), fixture.over_cap());
+    }
+
+    #[test]
+    fn test_dev_wallet_ata_destination_is_matched_by_address() {
+        let mut config = test_config();
+        let mut fixture = Fixture::new(WALLET_CAP_RAW);
+        config.refresh_dev_wallet_ata(&fixture.mint);
+        let dev_wallet = config.dev_wallets[0];
+        let ata = get_associated_token_address_with_program_id(&dev_wallet, &fixture.mint, &TOKEN_2022_PROGRAM_ID);
+
+        // The fixture's destination data names a stranger as the owner, so only the address can exempt it
+        fixture.destination = ata;
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Exempt);
+
+        config.dev_exemption_expires_at = Some(NOW);
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), fixture.over_cap());
+    }
+
+    #[test]
+    fn test_trusted_program_destination() {
+        let amm_program = Pubkey::new_unique();
+        let mut config = test_config();
+        config.trusted_programs[0] = amm_program;
+        let mut fixture = Fixture::new(WALLET_CAP_RAW);
+        let (pool_authority, _) = Pubkey::find_program_address(&[b"pool-authority"], &amm_program);
+        fixture.destination_owner = pool_authority;
+
+        // The exemption needs the owner's record from register_trusted_owner
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), fixture.over_cap());
+        let record = TrustedOwner { mint: fixture.mint, owner: pool_authority, program: amm_program };
+        fixture.trusted_owner = Some(record.clone());
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Exempt);
+
+        // A record for another owner doesn't count
+        fixture.trusted_owner = Some(TrustedOwner { owner: Pubkey::new_unique(), ..record });
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), fixture.over_cap());
+
+        // Nor does one once governance no longer trusts the program
+        fixture.trusted_owner = Some(record);
+        config.trusted_programs[0] = Pubkey::default();
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), fixture.over_cap());
+    }
+
+    #[test]
+    fn test_strict_mode_requires_wallet_owners() {
+        let amm_program = Pubkey::new_unique();
+        let mut config = test_config();
+        config.require_wallet_owners = true;
+        let mut fixture = Fixture::new(0);
+        // The ed25519 base point stands in for a keypair's public key
+        let mut wallet = [0x66u8; 32];
+        wallet[0] = 0x58;
+        fixture.destination_owner = Pubkey::new_from_array(wallet);
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Allowed);
+
+        let (pool_authority, _) = Pubkey::find_program_address(&[b"pool-authority"], &amm_program);
+        fixture.destination_owner = pool_authority;
+        assert_hook_error(fixture.check(&config, None, 1), HookError::ProgramOwnedDestination);
+
+        // Exempt destinations are let through: registered token accounts and trusted programs' PDAs
+        let vaults = registry(vec![pool_vault(fixture.destination)]);
+        assert_eq!(fixture.check(&config, Some(&vaults), 1).unwrap(), Verdict::Exempt);
+        config.trusted_programs[0] = amm_program;
+        fixture.trusted_owner = Some(TrustedOwner { mint: fixture.mint, owner: pool_authority, program: amm_program });
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Exempt);
+
+        config.require_wallet_owners = false;
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Allowed);
+    }
+
+    #[test]
+    fn test_circuit_breaker_pause() {
+        let mut config = test_config();
+        config.paused_until = NOW + 1;
+        let fixture = Fixture::new(0);
+        assert_hook_error(fixture.check(&config, None, 1), HookError::CircuitBreakerTripped);
+
+        config.paused_until = NOW;
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), Verdict::Allowed);
+    }
+
+    #[test]
+    fn test_cap_counts_what_the_destination_receives() {
+        let config = test_config();
+
+        // 1% fee: the destination receives 99 of 100, which fits exactly
+        let mut fixture = Fixture::new(WALLET_CAP_RAW - 99);
+        fixture.mint_data = mint_with_transfer_fee(100, u64::MAX);
+        assert_eq!(fixture.check(&config, None, 100).unwrap(), Verdict::Allowed);
+        assert_eq!(fixture.check(&config, None, 200).unwrap(), fixture.over_cap());
+
+        // UI units scale the balance by accrued interest before comparing
+        let mut config = config;
+        config.cap_unit = CapUnit::Ui;
+        let mut fixture = Fixture::new(WALLET_CAP_RAW - 1);
+        fixture.mint_data = interest_bearing_mint(1_000, NOW - 31_556_736);
+        assert_eq!(fixture.check(&config, None, 1).unwrap(), fixture.over_cap());
+    }
+
+    #[test]
+    fn test_transfer_checked_event() {
+        let mut config = test_config();
+        let mut fixture = Fixture::new(WALLET_CAP_RAW - 100);
+        let source_data = token_account_data(fixture.mint, fixture.source_owner, 1_000 * WALLET_CAP_RAW);
+        let clock = Clock { slot: 42, unix_timestamp: NOW, ..Clock::default() };
+        let checked = |fixture: &Fixture, config: &HookConfig, verdict: Verdict| {
+            let destination_data = token_account_data(fixture.mint, fixture.destination_owner, fixture.balance);
+            let transfer = Transfer {
+                source: &fixture.source,
+                source_data: &source_data,
+                destination: &fixture.destination,
+                destination_data: &destination_data,
+                mint_data: &fixture.mint_data,
+                amount: 40,
+            };
+            transfer_checked(config, None, &transfer, &clock, &fixture.mint, &verdict).unwrap()
+        };
+
+        let event = checked(&fixture, &config, Verdict::Allowed);
+        assert_eq!(event.mint, fixture.mint);
+        assert_eq!(event.slot, 42);
+        assert_eq!(event.destination_owner, fixture.destination_owner);
+        assert_eq!(event.amount, 40);
+        assert_eq!(event.post_balance, WALLET_CAP_RAW - 60);
+        assert_eq!(event.cap - event.post_balance, 60);
+        assert!(!event.exempt);
+
+        // Net of the transfer fee, like the cap check: 1% of 40 rounds up to 1
+        fixture.mint_data = mint_with_transfer_fee(100, u64::MAX);
+        assert_eq!(checked(&fixture, &config, Verdict::Allowed).post_balance, WALLET_CAP_RAW - 61);
+
+        // Exempt destinations still report the cap they would otherwise be held to
+        config.wallet_cap_raw = WALLET_CAP_RAW / 2;
+        let event = checked(&fixture, &config, Verdict::Exempt);
+        assert_eq!(event.cap, WALLET_CAP_RAW / 2);
+        assert!(event.exempt);
+    }
+
+    #[test]
+    fn test_preflight_reports_instead_of_raising() {
+        let mut config = test_config();
+        let mut fixture = Fixture::new(WALLET_CAP_RAW - 100);
+        let preflight_for = |fixture: &Fixture, config: &HookConfig, mint: &Pubkey, amount: u64| {
+            let destination_data = token_account_data(fixture.mint, fixture.destination_owner, fixture.balance);
+            let receipt = Receipt {
+                destination: &fixture.destination,
+                destination_data: &destination_data,
+                mint_data: &fixture.mint_data,
+                amount,
+            };
+            let clock = Clock { unix_timestamp: NOW, ..Clock::default() };
+            preflight(config, None, &receipt, None, mint, &clock)
+                .map(|result| (result.allowed, result.reason_code, result.remaining_capacity))
+        };
+        let check = |fixture: &Fixture, config: &HookConfig, amount: u64| {
+            preflight_for(fixture, config, &fixture.mint, amount).unwrap()
+        };
+        let reason = |reason: ReceiveReason| reason as u8;
+
+        assert_eq!(check(&fixture, &config, 40), (true, reason(ReceiveReason::Allowed), 60));
+        assert_eq!(check(&fixture, &config, 100), (true, reason(ReceiveReason::Allowed), 0));
+        assert_eq!(check(&fixture, &config, 101), (false, reason(ReceiveReason::OverCap), 0));
+        assert_eq!(check(&fixture, &config, 0), (true, reason(ReceiveReason::Unchanged), 100));
+
+        // The pause bounces everything but exempt destinations and zero amounts
+        config.paused_until = i64::MAX;
+        assert_eq!(check(&fixture, &config, 1), (false, reason(ReceiveReason::Paused), 0));
+        assert_eq!(check(&fixture, &config, 0), (true, reason(ReceiveReason::Unchanged), 0));
+        fixture.destination_owner = config.dev_wallets[0];
+        assert_eq!(check(&fixture, &config, 101), (true, reason(ReceiveReason::Exempt), u64::MAX));
+
+        // Before the setup is finalized nothing is received, exempt or not
+        config.finalized = false;
+        assert_eq!(check(&fixture, &config, 1), (false, reason(ReceiveReason::NotFinalized), 0));
+        // Unless a disabled hook lets everything through; an older config version is no excuse
+        config.hook_disabled = true;
+        assert_eq!(check(&fixture, &config, 1), (true, reason(ReceiveReason::HookDisabled), u64::MAX));
+        config.hook_disabled = false;
+        config.version = 2;
+        assert_eq!(check(&fixture, &config, 1), (false, reason(ReceiveReason::NotFinalized), 0));
+        config.version = CONFIG_VERSION;
+        config.finalized = true;
+
+        // A token account of another mint is an error, not a verdict
+        assert_hook_error(
+            preflight_for(&fixture, &config, &Pubkey::new_unique(), 1),
+            HookError::DestinationMintMismatch,
+        );
+    }
+
+    /// An account of `mint` carrying `extensions`, laid out the way Token-2022 creates it
+    pub(super) fn extended_account_data(
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        extensions: &[ExtensionType],
+    ) -> Vec<u8> {
+        let space = ExtensionType::try_calculate_account_len::<Account>(extensions).unwrap();
+        let mut data = vec![0u8; space];
+        let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::ImmutableOwner => {
+                    state.init_extension::<ImmutableOwner>(true).unwrap();
+                }
+                ExtensionType::TransferHookAccount => {
+                    state.init_extension::<TransferHookAccount>(true).unwrap().transferring = true.into();
+                }
+                ExtensionType::MemoTransfer => {
+                    state.init_extension::<MemoTransfer>(true).unwrap().require_incoming_transfer_memos = true.into();
+                }
+                _ => unreachable!("not used by these tests"),
+            }
+        }
+        state.base = Account { mint, owner, amount, state: AccountState::Initialized, ..Default::default() };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_fast_account_view_matches_the_unpack() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let buffers = [
+            token_account_data(mint, owner, 7),
+            extended_account_data(mint, owner, 7, &[ExtensionType::ImmutableOwner]),
+            extended_account_data(mint, owner, 7, &[ExtensionType::TransferHookAccount]),
+            extended_account_data(
+                mint,
+                owner,
+                7,
+                &[ExtensionType::ImmutableOwner, ExtensionType::TransferHookAccount, ExtensionType::MemoTransfer],
+            ),
+        ];
+        for data in &buffers {
+            let unpacked = StateWithExtensions::<Account>::unpack(data).unwrap();
+            let view = fast_account_view(data).unwrap();
+            assert_eq!((view.owner, view.amount), (unpacked.base.owner, unpacked.base.amount));
+            assert_eq!(view, AccountView { owner, amount: 7 });
+        }
+
+        // A frozen account is still an account
+        let mut frozen = Account::unpack(&token_account_data(mint, owner, u64::MAX)).unwrap();
+        frozen.state = AccountState::Frozen;
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(frozen, &mut data).unwrap();
+        assert_eq!(fast_account_view(&data).unwrap(), AccountView { owner, amount: u64::MAX });
+    }
+
+    #[test]
+    fn test_fast_account_view_refuses_what_the_unpack_refuses() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let extended = extended_account_data(mint, owner, 1, &[ExtensionType::TransferHookAccount]);
+
+        let mut wrong_type = extended.clone();
+        wrong_type[Account::LEN] = AccountType::Mint as u8;
+        let mut uninitialized = extended.clone();
+        uninitialized[108] = AccountState::Uninitialized as u8;
+        let mut bad_option_tag = extended.clone();
+        bad_option_tag[129] = 2;
+        let rejected = [
+            extended[..Account::LEN - 1].to_vec(),
+            vec![0u8; Multisig::LEN],
+            plain_mint(),
+            wrong_type,
+            uninitialized,
+            bad_option_tag,
+        ];
+        for data in &rejected {
+            assert!(StateWithExtensions::<Account>::unpack(data).is_err());
+            assert!(fast_account_view(data).is_err());
+        }
+    }
+
+    #[test]
+    fn test_token_accounts_must_unpack() {
+        let config = test_config();
+        let fixture = Fixture::new(0);
+        let source_data = token_account_data(fixture.mint, fixture.source_owner, 1);
+        let transfer = Transfer {
+            source: &fixture.source,
+            source_data: &source_data,
+            destination: &fixture.destination,
+            destination_data: &plain_mint(),
+            mint_data: &fixture.mint_data,
+            amount: 1,
+        };
+        let clock = Clock { unix_timestamp: NOW, ..Clock::default() };
+        assert!(check_transfer(&config, None, None, &transfer, &clock).is_err());
+    }
+}
+
+/// `fast_account_view` reads the destination's data on every transfer, and anyone can pass any buffer as
+/// the destination, so it is fed arbitrary and corrupted accounts here. The unpack stays the reference.
+#[cfg(test)]
+mod proptests {
+    use proptest::prelude::*;
+    use spl_token_2022::extension::ExtensionType;
+
+    use super::tests::extended_account_data;
+    use super::*;
+    use crate::tests::token_account_data;
+
+    /// Offsets where a corrupted byte changes whether the data is an account at all
+    const CRITICAL_OFFSETS: [usize; 8] = [72, 75, 108, 109, 112, 129, 132, Account::LEN];
+
+    /// What an accepted buffer has to satisfy: the unpack accepts it too and reads the same owner and
+    /// amount, and so does the base account it re-serializes to
+    fn assert_consistent(data: &[u8]) {
+        let Ok(view) = fast_account_view(data) else {
+            return;
+        };
+        let base = StateWithExtensions::<Account>::unpack(data).expect("accepted what the unpack refuses").base;
+        assert_eq!(view, AccountView { owner: base.owner, amount: base.amount });
+
+        let mut repacked = data.to_vec();
+        Account::pack(base, &mut repacked[..Account::LEN]).unwrap();
+        assert_eq!(fast_account_view(&repacked).unwrap(), view);
+    }
+
+    /// Every layout the transfer tests use, for any mint, owner and amount
+    fn valid_account() -> impl Strategy<Value = Vec<u8>> {
+        (any::<[u8; 32]>(), any::<[u8; 32]>(), any::<u64>(), 0..4usize).prop_map(|(mint, owner, amount, layout)| {
+            let (mint, owner) = (Pubkey::new_from_array(mint), Pubkey::new_from_array(owner));
+            match layout {
+                0 => token_account_data(mint, owner, amount),
+                1 => extended_account_data(mint, owner, amount, &[ExtensionType::ImmutableOwner]),
+                2 => extended_account_data(mint, owner, amount, &[ExtensionType::TransferHookAccount]),
+                _ => extended_account_data(
+                    mint,
+                    owner,
+                    amount,
+                    &[ExtensionType::ImmutableOwner, ExtensionType::TransferHookAccount, ExtensionType::MemoTransfer],
+                ),
+            }
+        })
+    }
+
+    /// A valid account with a few bytes overwritten, then optionally cut short and padded with garbage
+    fn corrupted_account() -> impl Strategy<Value = Vec<u8>> {
+        let offset = prop_oneof![prop::sample::select(CRITICAL_OFFSETS.to_vec()), 0..Account::LEN + 16];
+        let byte = prop_oneof![0..4u8, any::<u8>()];
+        (
+            valid_account(),
+            prop::collection::vec((offset, byte), 0..8),
+            proptest::option::of(any::<prop::sample::Index>()),
+            prop::collection::vec(any::<u8>(), 0..16),
+        )
+            .prop_map(|(mut data, writes, cut, tail)| {
+                for (offset, byte) in writes {
+                    if let Some(slot) = data.get_mut(offset) {
+                        *slot = byte;
+                    }
+                }
+                if let Some(cut) = cut {
+                    data.truncate(cut.index(data.len() + 1));
+                }
+                data.extend(tail);
+                data
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_bytes_never_panic(data in prop::collection::vec(any::<u8>(), 0..400)) {
+            assert_consistent(&data);
+        }
+
+        #[test]
+        fn corrupted_accounts_are_refused_or_read_consistently(data in corrupted_account()) {
+            assert_consistent(&data);
+        }
+    }
+
+    /// The fixed corpus: every truncation of a fully extended account, and every byte of it set to the
+    /// values most likely to slip past a check
+    #[test]
+    fn every_truncation_and_single_byte_corruption_is_consistent() {
+        let extensions =
+            [ExtensionType::ImmutableOwner, ExtensionType::TransferHookAccount, ExtensionType::MemoTransfer];
+        let data = extended_account_data(Pubkey::new_unique(), Pubkey::new_unique(), u64::MAX, &extensions);
+        for length in 0..=data.len() {
+            assert_consistent(&data[..length]);
+        }
+        for offset in 0..data.len() {
+            for byte in [0, 1, 2, 3, 0x80, 0xff] {
+                let mut corrupted = data.clone();
+                corrupted[offset] = byte;
+                assert_consistent(&corrupted);
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum HookError {
+    #[msg("Wallet cap exceeded")]
+    WalletCapExceeded,
+    #[msg("Insufficient account space for ExtraAccountMetaList")]
+    InsufficientAccountSpace,
+    #[msg("Unauthorized governance operation")]
+    UnauthorizedGovernance,
+    #[msg("Invalid wallet cap value")]
+    InvalidWalletCap,
+    #[msg("No pending cap update")]
+    NoPendingUpdate,
+    #[msg("Timelock period has not expired")]
+    TimelockNotExpired,
+    #[msg("Invalid account owner")]
+    InvalidAccountOwner,
+    #[msg("Invalid migration version")]
+    InvalidMigrationVersion,
+    #[msg("Unsupported version")]
+    UnsupportedVersion,
+    #[msg("Unsupported migration path")]
+    UnsupportedMigration,
+    #[msg("Exempt registry is full")]
+    ExemptRegistryFull,
+    #[msg("Invalid trusted program slot")]
+    InvalidTrustedProgramSlot,
+    #[msg("Invalid dev wallet slot")]
+    InvalidDevWalletSlot,
+    #[msg("Dev exemption expiry can only be moved earlier")]
+    DevExemptionExtension,
+    #[msg("Dev exemption has not expired")]
+    DevExemptionNotExpired,
+    #[msg("Dev exemption expiry already finalized")]
+    DevExemptionAlreadyFinalized,
+    #[msg("Token account is not in the exempt registry")]
+    ExemptEntryNotFound,
+    #[msg("Rent recipient does not match the registry")]
+    InvalidRentRecipient,
+    #[msg("Permit has expired")]
+    PermitExpired,
+    #[msg("Permit is for a different destination")]
+    PermitWrongDestination,
+    #[msg("Permit nonce has already been used")]
+    PermitNonceReplayed,
+    #[msg("Permit is for a different mint")]
+    PermitWrongMint,
+    #[msg("Transfer amount exceeds the permit")]
+    PermitAmountExceeded,
+    #[msg("Permit nonce out of range")]
+    PermitNonceOutOfRange,
+    #[msg("Used nonces account has not been initialized")]
+    UsedNoncesNotInitialized,
+    #[msg("Sell amount exceeds the sell limit")]
+    SellLimitExceeded,
+    #[msg("Sell limit is below the minimum allowed")]
+    SellLimitBelowFloor,
+    #[msg("Transfers are paused by the circuit breaker or an emergency pause")]
+    CircuitBreakerTripped,
+    #[msg("Circuit breaker needs a window and cooldown when enabled")]
+    InvalidCircuitBreakerConfig,
+    #[msg("Maximum number of holders reached")]
+    MaxHoldersReached,
+    #[msg("Holder state account has not been initialized")]
+    HolderStateNotInitialized,
+    #[msg("Holder state does not belong to this mint and owner")]
+    InvalidHolderState,
+    #[msg("UI unit caps need an interest-bearing mint")]
+    UiCapUnsupported,
+    #[msg("No pending governance transfer")]
+    NoPendingGovernanceTransfer,
+    #[msg("Signer is not the pending governance authority")]
+    NotPendingGovernanceAuthority,
+    #[msg("Governance has been renounced")]
+    GovernanceRenounced,
+    #[msg("Multisig governance is not enabled")]
+    MultisigGovernanceDisabled,
+    #[msg("Signer is not a governance member")]
+    NotGovernanceMember,
+    #[msg("Member has already approved this action")]
+    GovernanceActionAlreadyApproved,
+    #[msg("Multisig governance requires an approval for this action")]
+    GovernanceApprovalRequired,
+    #[msg("Approval was given in an earlier governance epoch")]
+    StaleGovernanceApproval,
+    #[msg("Approval is for a different action")]
+    GovernanceActionMismatch,
+    #[msg("Not enough member approvals")]
+    GovernanceThresholdNotMet,
+    #[msg("Governance approval can still be used")]
+    GovernanceApprovalStillActive,
+    #[msg("Duplicate governance members or threshold above the member count")]
+    InvalidGovernanceMembers,
+    #[msg("The guardian can pause but not unpause")]
+    GuardianCannotUnpause,
+    #[msg("Timelock is shorter than the minimum")]
+    InvalidTimelock,
+    #[msg("Pending proposal has expired")]
+    ProposalExpired,
+    #[msg("Pending proposal has not expired yet")]
+    ProposalNotExpired,
+    #[msg("Proposal expiry window must be non-zero")]
+    InvalidProposalExpiry,
+    #[msg("A proposal is already pending; pass overwrite to replace it")]
+    ProposalAlreadyPending,
+    #[msg("Only the executor authority can execute cap updates")]
+    UnauthorizedExecutor,
+    #[msg("Only cap increases can skip the timelock")]
+    TimelockRequired,
+    #[msg("Wallet cap would be below the minimum cap floor")]
+    CapBelowFloor,
+    #[msg("The minimum cap floor can only be raised")]
+    CapFloorLowered,
+    #[msg("Wallet cap change exceeds the maximum swing per proposal")]
+    CapChangeTooLarge,
+    #[msg("Config is frozen")]
+    ConfigFrozen,
+    #[msg("Lift the emergency pause before freezing the config")]
+    FreezeWhilePaused,
+    #[msg("Mint's TransferHook extension does not point at this program")]
+    MintNotConfiguredForHook,
+    #[msg("Signer is neither the mint authority nor, where accepted, the factory admin")]
+    UnauthorizedInitializer,
+    #[msg("Token accounts are not in a Token-2022 transfer")]
+    NotInTransfer,
+    #[msg("Source token account belongs to a different mint")]
+    SourceMintMismatch,
+    #[msg("Destination token account belongs to a different mint")]
+    DestinationMintMismatch,
+    #[msg("Owner account is neither the source's owner nor its delegate")]
+    OwnerMismatch,
+    #[msg("Requested extra account metas differ from the ones this hook resolves")]
+    ExtraAccountMetasMismatch,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Closing requires confirming the mint no longer routes transfers through this hook")]
+    MintDetachUnconfirmed,
+    #[msg("Cancel the pending cap update before closing the config")]
+    PendingCapUpdateExists,
+    #[msg("Dev wallet and governance authority must be set, and distinct from each other and the payer when strict")]
+    InvalidAuthority,
+    #[msg("Holder state can only be closed once the owner holds no tokens")]
+    HolderBalanceNotZero,
+    #[msg("Too soon since the last snapshot")]
+    SnapshotTooSoon,
+    #[msg("Hook has been permanently disabled")]
+    HookDisabled,
+    #[msg("A permanent hook disable is already pending")]
+    HookDisableAlreadyPending,
+    #[msg("Transfer would move tokens that are still locked up")]
+    TokensLocked,
+    #[msg("Lockup account does not belong to the source owner")]
+    InvalidLockup,
+    #[msg("Linear release must end after the cliff")]
+    InvalidLockupSchedule,
+    #[msg("Lockups can only be shortened")]
+    LockupExtension,
+    #[msg("Token account is not the proposed dev wallet's account of this mint")]
+    DevWalletAccountMismatch,
+    #[msg("Only the program's upgrade authority can create the global config")]
+    NotUpgradeAuthority,
+    #[msg("Fields mask is empty or names fields without a global default")]
+    InvalidGlobalDefaultsFields,
+    #[msg("The maximum cap swing per change can only be tightened")]
+    CapChangeLimitLoosened,
+    #[msg("Unstaking this amount would leave the wallet over its cap")]
+    UnstakeWouldExceedCap,
+    #[msg("Token account is not owned by a PDA account of the staking program")]
+    NotStakingVault,
+    #[msg("Token account is not owned by a PDA account of the bridge program")]
+    NotBridgeCustody,
+    #[msg("Bridge custody accounts can only be exempted through register_bridge_custody")]
+    BridgeCustodyNotVerified,
+    #[msg("Confidential transfers hide amounts from the hook, capping only public balances; needs allow_confidential")]
+    UnsupportedMintExtension,
+    #[msg("Confidential transfer amounts are hidden from the hook and can't be checked against the cap")]
+    ConfidentialTransferUnsupported,
+    #[msg("Config extension account belongs to another mint")]
+    InvalidConfigExtension,
+    #[msg("No launch has been detected")]
+    LaunchNotDetected,
+    #[msg("Launch detection can only be reset once, before a snipe window has been measured from it")]
+    LaunchResetUnavailable,
+    #[msg("Sniper log account belongs to another mint")]
+    InvalidSniperLog,
+    #[msg("Owner is not in the sniper log")]
+    SniperNotFlagged,
+    #[msg("Snipe window exceeds the maximum")]
+    InvalidSnipeWindow,
+    #[msg("Destination is owned by a program-derived address and isn't exempt")]
+    ProgramOwnedDestination,
+    #[msg("Transfers above the memo threshold need an SPL Memo instruction in the transaction")]
+    MemoRequired,
+    #[msg("Dev wallet sells into pool vaults would exceed the daily outflow limit")]
+    DevOutflowLimitExceeded,
+    #[msg("The dev wallets' daily outflow limit can only be lowered")]
+    DevOutflowLimitRaised,
+    #[msg("Transfers are blocked until finalize_setup has verified the mint's setup")]
+    SetupNotFinalized,
+    #[msg("Setup has already been finalized")]
+    SetupAlreadyFinalized,
+    #[msg("Extra account meta list hasn't been created by this program")]
+    ExtraAccountMetaListMissing,
+    #[msg("Owner is not a PDA account of a trusted program")]
+    NotTrustedProgramOwner,
+}
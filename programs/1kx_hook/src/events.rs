@@ -0,0 +1,803 @@
+//! Program events.
+//!
+//! Instructions emit with `emit_cpi!`: the event travels as the data of a self-CPI signed by the
+//! `__event_authority` PDA, after the 8 byte `EVENT_IX_TAG_LE`, so indexers read it from the transaction's
+//! inner instructions and RPCs that truncate long logs cannot drop it. The transfer hook is the exception.
+//! It runs on every transfer, where a self-CPI would add its compute to each one, so the events it emits
+//! (`PermitRedeemed`, `CircuitBreakerTriggered` and `HookTransferChecked`) stay in the log via `emit!`.
+//!
+//! Every event leads with the mint it concerns and the slot it was emitted in, so an indexer watching
+//! several mints can attribute it without fetching the transaction. Changing any event's fields means
+//! raising `EVENT_SCHEMA_VERSION`. Events derive `Debug` and `PartialEq` for off-chain decoders, such as
+//! the client crate's.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CapKind, CapUpdateRole, ExemptKind, GovernanceAction};
+use crate::{MAX_DEV_WALLETS, MAX_GOVERNANCE_MEMBERS};
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalletCapUpdateProposed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub kind: CapKind,
+    pub new_cap: u64,
+    pub current_cap: u64,
+    pub proposed_at: i64,
+    pub execution_time: i64,
+    pub governance_authority: Pubkey,
+    pub proposal_nonce: u64,
+    pub proposed_by: Pubkey,
+    pub memo_hash: [u8; 32],
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalletCapUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub kind: CapKind,
+    pub old_cap: u64,
+    pub new_cap: u64,
+    pub updated_at: i64,
+    pub executed_by: Pubkey,
+    pub proposal_nonce: u64, // 0 for immediate increases, which have no proposal
+    pub immediate: bool,     // Applied by execute_immediate_cap_increase without a timelock
+    pub memo_hash: [u8; 32], // Zero for immediate increases
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalletCapUpdateCanceled {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub kind: CapKind,
+    pub canceled_cap: u64,
+    pub current_cap: u64,
+    pub canceled_at: i64,
+    pub governance_authority: Pubkey,
+    pub proposal_nonce: u64,
+    pub memo_hash: [u8; 32],
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalletCapUpdateVetoed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub kind: CapKind,
+    pub vetoed_cap: u64,
+    pub current_cap: u64,
+    pub vetoed_at: i64,
+    pub veto_authority: Pubkey,
+    pub proposal_nonce: u64,
+    pub memo_hash: [u8; 32],
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalletCapUpdateExpired {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub kind: CapKind,
+    pub expired_cap: u64,
+    pub current_cap: u64,
+    pub expires_at: i64,
+    pub swept_at: i64,
+    pub proposal_nonce: u64,
+    pub memo_hash: [u8; 32],
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProposalExpiryUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_expiry_seconds: u32,
+    pub new_expiry_seconds: u32,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinCapFloorRaised {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_floor: u64,
+    pub new_floor: u64,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VetoAuthorityUpdateProposed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_veto_authority: Option<Pubkey>,
+    pub new_veto_authority: Option<Pubkey>,
+    pub proposed_at: i64,
+    pub execution_time: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VetoAuthorityUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_veto_authority: Option<Pubkey>,
+    pub new_veto_authority: Option<Pubkey>,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VetoAuthorityUpdateCanceled {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub veto_authority: Option<Pubkey>,
+    pub canceled_veto_authority: Option<Pubkey>,
+    pub canceled_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapUpdateRoleUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub role: CapUpdateRole,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceTransferProposed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub proposed_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceAuthorityUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub updated_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceTransferCanceled {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub canceled_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExemptEntryAdded {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub token_account: Pubkey,
+    pub kind: ExemptKind,
+    pub expires_at: Option<i64>,
+    pub added_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExemptEntryRemoved {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub token_account: Pubkey,
+    pub removed_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExemptRegistryPruned {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub removed: u32,
+    pub remaining: u32,
+    pub refunded_lamports: u64,
+    pub pruned_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustedProgramUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub index: u8,
+    pub old_program: Pubkey,
+    pub new_program: Pubkey,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevWalletUpdateProposed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub index: u8,
+    pub old_wallet: Pubkey,
+    pub new_wallet: Pubkey,
+    pub proposed_at: i64,
+    pub execution_time: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevWalletUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub index: u8,
+    pub old_wallet: Pubkey,
+    pub new_wallet: Pubkey,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimelockUpdateProposed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_timelock_seconds: u32,
+    pub new_timelock_seconds: u32,
+    pub proposed_at: i64,
+    pub execution_time: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimelockUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_timelock_seconds: u32,
+    pub new_timelock_seconds: u32,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimelockUpdateCanceled {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub timelock_seconds: u32,
+    pub canceled_timelock_seconds: u32,
+    pub canceled_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevWalletUpdateCanceled {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub index: u8,
+    pub old_wallet: Pubkey,
+    pub canceled_wallet: Pubkey,
+    pub canceled_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevExemptionExpiryUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_expires_at: Option<i64>,
+    pub new_expires_at: i64,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevExemptionExpired {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub expired_at: i64,
+    pub finalized_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AirdropAllowanceUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_allowance: u32,
+    pub new_allowance: u32,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermitSignerUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_signer: Pubkey,
+    pub new_signer: Pubkey,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+/// Logged by the transfer hook with `emit!`
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermitRedeemed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub destination_owner: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub redeemed_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub threshold_raw: u64,
+    pub window_slots: u64,
+    pub cooldown: i64,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+/// Logged by the transfer hook with `emit!`
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerTriggered {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub window_start_slot: u64,
+    pub paused_until: i64,
+    pub triggered_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitBreakerCleared {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub paused_until: i64,
+    pub cleared_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaxHoldersUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_max_holders: u32,
+    pub new_max_holders: u32,
+    pub holder_count: u32,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceMembersUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub members: [Pubkey; MAX_GOVERNANCE_MEMBERS],
+    pub threshold: u8,
+    pub epoch: u64,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceActionProposed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub action_hash: [u8; 32],
+    pub action: GovernanceAction,
+    pub epoch: u64,
+    pub proposer: Pubkey,
+    pub proposed_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceActionApproved {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub action_hash: [u8; 32],
+    pub member: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
+    pub approved_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuardianUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_guardian: Option<Pubkey>,
+    pub new_guardian: Option<Pubkey>,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PauseUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub paused: bool,
+    pub updated_at: i64,
+    pub authority: Pubkey, // Governance submitter or guardian
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigFrozen {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub wallet_cap_raw: u64, // The cap that is now permanent
+    pub dev_wallets: [Pubkey; MAX_DEV_WALLETS],
+    pub frozen_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceRenounced {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_authority: Pubkey,
+    pub wallet_cap_raw: u64, // The cap that is now permanent
+    pub renounced_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigMigrated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_version: u8,
+    pub new_version: u8,
+    pub migrated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtraAccountMetasUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_count: u32,
+    pub new_count: u32,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtraAccountMetaListClosed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub recipient: Pubkey,
+    pub lamports: u64, // Rent returned to the recipient
+    pub closed_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigClosed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub recipient: Pubkey,
+    pub lamports: u64, // Rent returned to the recipient
+    pub closed_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+/// Emitted by `initialize` when the dev wallet and governance authority are the same key
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevWalletIsGovernanceAuthority {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub authority: Pubkey,
+}
+
+/// Emitted by the hook for every transfer it lets through while `emit_transfer_events` is on.
+/// `post_balance` and `cap` are in the config's cap units, so the remaining capacity is their difference.
+/// Logged with `emit!`, unlike the instruction events.
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookTransferChecked {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub destination_owner: Pubkey,
+    pub amount: u64,
+    pub post_balance: u64, // Destination balance after the transfer, net of transfer fees
+    pub cap: u64,          // Cap that applied to this transfer, exempt or not
+    pub exempt: bool,      // Destination was exempt from the cap
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferEventsUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub enabled: bool,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotTaken {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub index: u64,
+    pub supply: u64,
+    pub wallet_cap_raw: u64,
+    pub paused: bool,
+    pub taken_at: i64,
+    pub payer: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotClosed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub lamports: u64, // Rent returned to the snapshot's payer
+    pub closed_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookDisableProposed {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub proposed_at: i64,
+    pub execution_time: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookDisableCanceled {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub execution_time: i64, // Execution time of the canceled disable
+    pub canceled_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+/// Emitted once the hook stops enforcing anything; there is no way back
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HookPermanentlyDisabled {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub disabled_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockupCreated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub owner: Pubkey,
+    pub locked_amount: u64,
+    pub locked_until: i64,
+    pub linear_release_end: Option<i64>,
+    pub created_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockupShortened {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub owner: Pubkey,
+    pub old_locked_until: i64,
+    pub locked_until: i64,
+    pub old_linear_release_end: Option<i64>,
+    pub linear_release_end: Option<i64>,
+    pub shortened_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaxCapChangeTightened {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_bps: u16,
+    pub new_bps: u16,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StakingProgramUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub index: u8,
+    pub old_program: Pubkey,
+    pub new_program: Pubkey,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StakingVaultRegistered {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub token_account: Pubkey,
+    pub vault_owner: Pubkey,
+    pub registered_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BridgeCustodyRegistered {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub token_account: Pubkey,
+    pub custody_owner: Pubkey,
+    pub bridge_program: Pubkey,
+    pub registered_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LaunchDetected {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub pool_vault: Pubkey,
+    pub amount: u64, // Raw amount of the buy that marked the launch
+    pub detected_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LaunchDetectionReset {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_detected_at: i64,
+    pub old_detected_slot: u64,
+    pub reset_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SniperFlagged {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub seconds_after_launch: i64,
+    pub logged: bool, // False once the sniper log is full or if it hasn't been created
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SniperFlagCleared {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub owner: Pubkey,
+    pub cleared_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnipeWindowUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_window_seconds: u32,
+    pub new_window_seconds: u32,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalletOwnersRequiredUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub enabled: bool,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoThresholdUpdated {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_threshold: Option<u64>,
+    pub new_threshold: Option<u64>,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevOutflowLimitLowered {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub old_limit: Option<u64>,
+    pub new_limit: u64,
+    pub updated_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetupFinalized {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub meta_count: u32,
+    pub finalized_at: i64,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SurplusLamportsWithdrawn {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub recipient: Pubkey,
+    pub config_lamports: u64,
+    pub extra_account_meta_list_lamports: u64,
+    pub withdrawn_at: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustedOwnerRegistered {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub owner: Pubkey,
+    pub program: Pubkey,
+    pub registered_at: i64,
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::GovernanceAuthorityUpdated;
+use crate::state::HookConfig;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptGovernanceAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced,
+        constraint = config.pending_governance_authority.is_some() @ HookError::NoPendingGovernanceTransfer,
+        constraint = config.pending_governance_authority == Some(new_authority.key()) @ HookError::NotPendingGovernanceAuthority
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub new_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn accept_governance_authority(ctx: Context<AcceptGovernanceAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let old_authority = config.governance_authority;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    config.governance_authority = new_authority;
+    config.pending_governance_authority = None;
+    // Cap update roles the outgoing authority still held move over with it
+    if config.proposer_authority == old_authority {
+        config.proposer_authority = new_authority;
+    }
+    if config.executor_authority == old_authority {
+        config.executor_authority = new_authority;
+    }
+
+    let clock = Clock::get()?;
+    emit_cpi!(GovernanceAuthorityUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_authority,
+        new_authority,
+        updated_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
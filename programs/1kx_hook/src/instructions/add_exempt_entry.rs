@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ExemptEntryAdded;
+use crate::instructions::grow_account;
+use crate::state::{ExemptEntry, ExemptKind, ExemptRegistry, GovernanceAction, GovernanceApproval, HookConfig};
+use crate::MAX_EXEMPT_ENTRIES;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddExemptEntry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    #[account(mut, seeds = [b"exempt-registry", mint.key().as_ref()], bump)]
+    pub exempt_registry: Account<'info, ExemptRegistry>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn add_exempt_entry(
+    ctx: Context<AddExemptEntry>,
+    token_account: Pubkey,
+    kind: ExemptKind,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::AddExemptEntry { token_account, kind, expires_at },
+    )?;
+    // A bridge custody tag is only as good as the ownership check behind it
+    require!(kind != ExemptKind::BridgeCustody, HookError::BridgeCustodyNotVerified);
+
+    let clock = Clock::get()?;
+    let registry = &mut ctx.accounts.exempt_registry;
+
+    if let Some(entry) = registry.entries.iter_mut().find(|e| e.token_account == token_account) {
+        entry.kind = kind;
+        entry.expires_at = expires_at;
+    } else {
+        require!(registry.entries.len() < MAX_EXEMPT_ENTRIES, HookError::ExemptRegistryFull);
+        registry.entries.push(ExemptEntry { token_account, kind, expires_at });
+
+        // Grow the account by one entry, topping up rent from the payer
+        grow_account(
+            &registry.to_account_info(),
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            ExemptRegistry::space(registry.entries.len()),
+        )?;
+    }
+
+    emit_cpi!(ExemptEntryAdded {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        token_account,
+        kind,
+        expires_at,
+        added_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
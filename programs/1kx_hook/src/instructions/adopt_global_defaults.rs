@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{MaxCapChangeTightened, MinCapFloorRaised, TimelockUpdateProposed};
+use crate::state::{GlobalConfig, GovernanceAction, GovernanceApproval, HookConfig, PendingTimelockUpdate};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AdoptGlobalDefaults<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(seeds = [b"global"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn adopt_global_defaults(ctx: Context<AdoptGlobalDefaults>, fields: u8) -> Result<()> {
+    require!(fields != 0 && fields & !GlobalConfig::ADOPT_ALL == 0, HookError::InvalidGlobalDefaultsFields);
+    let global = &ctx.accounts.global_config;
+    let (timelock_seconds, min_cap_floor, max_cap_change_bps) =
+        (global.default_timelock_seconds, global.default_min_cap_floor, global.default_max_cap_change_bps);
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::AdoptGlobalDefaults { fields, timelock_seconds, min_cap_floor, max_cap_change_bps },
+    )?;
+
+    // Each field keeps the guarantees of its own instruction: the floor only rises, the swing limit only
+    // tightens and the timelock only changes after the current one has run
+    let mint = ctx.accounts.mint.key();
+    let governance_authority = ctx.accounts.governance_authority.key();
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.config;
+    if fields & GlobalConfig::ADOPT_MIN_CAP_FLOOR != 0 {
+        let old_floor = config.raise_min_cap_floor(min_cap_floor)?;
+        emit_cpi!(MinCapFloorRaised {
+            mint,
+            slot: clock.slot,
+            old_floor,
+            new_floor: min_cap_floor,
+            updated_at: clock.unix_timestamp,
+            governance_authority,
+        });
+    }
+    if fields & GlobalConfig::ADOPT_MAX_CAP_CHANGE_BPS != 0 {
+        let old_bps = config.tighten_max_cap_change_bps(max_cap_change_bps)?;
+        emit_cpi!(MaxCapChangeTightened {
+            mint,
+            slot: clock.slot,
+            old_bps,
+            new_bps: max_cap_change_bps,
+            updated_at: clock.unix_timestamp,
+            governance_authority,
+        });
+    }
+    if fields & GlobalConfig::ADOPT_TIMELOCK != 0 {
+        // As `propose_timelock_update`: pending until `execute_timelock_update`
+        let execution_time = config.timelock_execution_time(clock.unix_timestamp)?;
+        config.pending_timelock_update =
+            Some(PendingTimelockUpdate { timelock_seconds, proposed_at: clock.unix_timestamp, execution_time });
+        emit_cpi!(TimelockUpdateProposed {
+            mint,
+            slot: clock.slot,
+            old_timelock_seconds: config.timelock_seconds,
+            new_timelock_seconds: timelock_seconds,
+            proposed_at: clock.unix_timestamp,
+            execution_time,
+            governance_authority,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::GovernanceActionApproved;
+use crate::state::{GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(action_hash: [u8; 32])]
+pub struct ApproveAction<'info> {
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub member: Signer<'info>,
+
+    #[account(mut, seeds = [b"governance-approval", mint.key().as_ref(), action_hash.as_ref()], bump)]
+    pub approval: Account<'info, GovernanceApproval>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn approve_action(ctx: Context<ApproveAction>, action_hash: [u8; 32]) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let member = ctx.accounts.member.key();
+    let approval = &mut ctx.accounts.approval;
+    approval.approve(config, &member)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(GovernanceActionApproved {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        action_hash,
+        member,
+        approvals: approval.approval_count(),
+        threshold: config.governance_threshold,
+        approved_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::enforcement;
+use crate::errors::HookError;
+use crate::state::{CanReceiveResult, HookConfig};
+
+#[derive(Accounts)]
+pub struct CanReceive<'info> {
+    #[account(seeds = [b"config", mint.key().as_ref()], bump = config.bump)]
+    pub config: Account<'info, HookConfig>,
+
+    /// CHECK: Mint; its transfer fee and interest extensions feed the cap check
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token account that would receive; its mint is checked in `preflight`
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: Exempt registry PDA; may not have been created, in which case nothing is registry-exempt
+    #[account(seeds = [b"exempt-registry", mint.key().as_ref()], bump)]
+    pub exempt_registry: UncheckedAccount<'info>,
+}
+
+pub fn can_receive(ctx: Context<CanReceive>, amount: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let result = receive_preflight(
+        &accounts.config,
+        &accounts.mint,
+        &accounts.destination,
+        &accounts.exempt_registry,
+        ctx.remaining_accounts,
+        amount,
+    )?;
+    set_return_data(&result.try_to_vec()?);
+    Ok(())
+}
+
+/// `preflight` over the accounts `can_receive` and `check_transfer` both take. Accounts that aren't what
+/// they claim to be are errors; a receive the hook would reject is a result.
+pub(crate) fn receive_preflight(
+    config: &HookConfig,
+    mint: &AccountInfo,
+    destination: &AccountInfo,
+    exempt_registry: &AccountInfo,
+    remaining_accounts: &[AccountInfo],
+    amount: u64,
+) -> Result<CanReceiveResult> {
+    require!(config.is_supported_version(), HookError::UnsupportedVersion);
+    require!(destination.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+    require!(mint.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+
+    let registry = enforcement::load_exempt_registry(exempt_registry)?;
+    let trusted_owner = match remaining_accounts.first() {
+        Some(info) => enforcement::load_trusted_owner(info, mint.key)?,
+        None => None,
+    };
+    let destination_data = destination.try_borrow_data()?;
+    let mint_data = mint.try_borrow_data()?;
+    let receipt = enforcement::Receipt {
+        destination: destination.key,
+        destination_data: &destination_data,
+        mint_data: &mint_data,
+        amount,
+    };
+    enforcement::preflight(config, registry.as_ref(), &receipt, trusted_owner.as_ref(), mint.key, &Clock::get()?)
+}
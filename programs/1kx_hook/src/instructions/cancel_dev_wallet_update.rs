@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::DevWalletUpdateCanceled;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelDevWalletUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn cancel_dev_wallet_update(ctx: Context<CancelDevWalletUpdate>) -> Result<()> {
+    if !ctx.accounts.config.is_veto_authority(&ctx.accounts.governance_authority.key()) {
+        ctx.accounts.config.authorize(
+            &ctx.accounts.governance_authority.key(),
+            ctx.accounts.approval.as_deref(),
+            &ctx.accounts.mint.key(),
+            &GovernanceAction::CancelDevWalletUpdate,
+        )?;
+    }
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    let canceled_update = config
+        .pending_dev_wallet_update
+        .take()
+        .ok_or(HookError::NoPendingUpdate)?;
+
+    emit_cpi!(DevWalletUpdateCanceled {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index: canceled_update.index,
+        old_wallet: config.dev_wallets[canceled_update.index as usize],
+        canceled_wallet: canceled_update.new_wallet,
+        canceled_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{WalletCapUpdateCanceled, WalletCapUpdateVetoed};
+use crate::state::{GovernanceAction, GovernanceApproval, GovernanceLog, GovernanceLogAction, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelWalletCapUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"gov-log", mint.key().as_ref()], bump)]
+    pub governance_log: Box<Account<'info, GovernanceLog>>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn cancel_wallet_cap_update(ctx: Context<CancelWalletCapUpdate>) -> Result<()> {
+    let vetoed = ctx.accounts.config.is_veto_authority(&ctx.accounts.governance_authority.key());
+    if !vetoed {
+        ctx.accounts.config.authorize_cancel(
+            &ctx.accounts.governance_authority.key(),
+            ctx.accounts.approval.as_deref(),
+            &ctx.accounts.mint.key(),
+            &GovernanceAction::CancelCapUpdate,
+        )?;
+    }
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(
+        config.pending_cap_update.is_some(),
+        HookError::NoPendingUpdate
+    );
+
+    let canceled_update = config.pending_cap_update.take().unwrap();
+    let action = if vetoed { GovernanceLogAction::Vetoed } else { GovernanceLogAction::Canceled };
+    ctx.accounts.governance_log.record(
+        action,
+        &canceled_update,
+        clock.unix_timestamp,
+        ctx.accounts.governance_authority.key(),
+    );
+
+    if vetoed {
+        emit_cpi!(WalletCapUpdateVetoed {
+            mint: ctx.accounts.mint.key(),
+            slot: clock.slot,
+            kind: canceled_update.kind,
+            vetoed_cap: canceled_update.new_cap,
+            current_cap: config.cap(canceled_update.kind),
+            vetoed_at: clock.unix_timestamp,
+            veto_authority: ctx.accounts.governance_authority.key(),
+            proposal_nonce: canceled_update.nonce,
+            memo_hash: canceled_update.memo_hash,
+        });
+        return Ok(());
+    }
+
+    emit_cpi!(WalletCapUpdateCanceled {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        kind: canceled_update.kind,
+        canceled_cap: canceled_update.new_cap,
+        current_cap: config.cap(canceled_update.kind),
+        canceled_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+        proposal_nonce: canceled_update.nonce,
+        memo_hash: canceled_update.memo_hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+use crate::instructions::can_receive::receive_preflight;
+use crate::state::{CheckTransferResult, HookConfig};
+
+#[derive(Accounts)]
+pub struct CheckTransfer<'info> {
+    #[account(seeds = [b"config", mint.key().as_ref()], bump = config.bump)]
+    pub config: Account<'info, HookConfig>,
+
+    /// CHECK: Mint; its transfer fee and interest extensions feed the cap check
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Token account that would receive; its mint is checked in `preflight`
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: Exempt registry PDA; may not have been created, in which case nothing is registry-exempt
+    #[account(seeds = [b"exempt-registry", mint.key().as_ref()], bump)]
+    pub exempt_registry: UncheckedAccount<'info>,
+}
+
+pub fn check_transfer(ctx: Context<CheckTransfer>, amount: u64) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let result = receive_preflight(
+        &accounts.config,
+        &accounts.mint,
+        &accounts.destination,
+        &accounts.exempt_registry,
+        ctx.remaining_accounts,
+        amount,
+    )?;
+    let result = CheckTransferResult { allowed: result.allowed, reason_code: result.reason_code };
+    set_return_data(&result.try_to_vec()?);
+    Ok(())
+}
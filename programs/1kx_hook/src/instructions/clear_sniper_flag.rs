@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::SniperFlagCleared;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig, SniperLog};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClearSniperFlag<'info> {
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"sniper-log", mint.key().as_ref()], bump)]
+    pub sniper_log: Box<Account<'info, SniperLog>>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn clear_sniper_flag(ctx: Context<ClearSniperFlag>, owner: Pubkey) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ClearSniperFlag { owner },
+    )?;
+
+    ctx.accounts.sniper_log.clear(&owner)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(SniperFlagCleared {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        owner,
+        cleared_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
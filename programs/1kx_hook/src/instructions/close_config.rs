@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ConfigClosed;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseConfig<'info> {
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Receives the config's rent; any account governance chooses
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn close_config(ctx: Context<CloseConfig>, confirm_mint_detached: bool) -> Result<()> {
+    let recipient = ctx.accounts.recipient.key();
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::CloseConfig { recipient },
+    )?;
+    require!(confirm_mint_detached, HookError::MintDetachUnconfirmed);
+    require!(ctx.accounts.config.pending_cap_update.is_none(), HookError::PendingCapUpdateExists);
+
+    let clock = Clock::get()?;
+    // The `close` constraint moves the lamports and zeroes the account once the handler returns
+    emit_cpi!(ConfigClosed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        recipient,
+        lamports: ctx.accounts.config.to_account_info().lamports(),
+        closed_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ExtraAccountMetaListClosed;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseExtraAccountMetaList<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Extra account meta list PDA; closed in the handler
+    #[account(mut, owner = crate::ID, seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the list's rent; any account governance chooses
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn close_extra_account_meta_list(
+    ctx: Context<CloseExtraAccountMetaList>,
+    confirm_mint_detached: bool,
+) -> Result<()> {
+    let recipient = ctx.accounts.recipient.key();
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::CloseExtraAccountMetaList { recipient },
+    )?;
+    require!(confirm_mint_detached, HookError::MintDetachUnconfirmed);
+
+    let list = ctx.accounts.extra_account_meta_list.to_account_info();
+    let lamports = list.lamports();
+    anchor_lang::common::close(list, ctx.accounts.recipient.to_account_info())?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(ExtraAccountMetaListClosed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        recipient,
+        lamports,
+        closed_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::state::{GovernanceApproval, HookConfig};
+
+#[derive(Accounts)]
+pub struct CloseGovernanceApproval<'info> {
+    #[account(seeds = [b"config", mint.key().as_ref()], bump)]
+    pub config: Account<'info, HookConfig>,
+
+    #[account(
+        mut,
+        close = payer,
+        constraint = approval.mint == mint.key() @ HookError::GovernanceActionMismatch,
+        constraint = approval.epoch != config.governance_epoch || config.governance_renounced
+            @ HookError::GovernanceApprovalStillActive
+    )]
+    pub approval: Account<'info, GovernanceApproval>,
+
+    /// CHECK: Receives the approval's rent; must be whoever paid for it
+    #[account(mut, address = approval.payer @ HookError::InvalidRentRecipient)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn close_governance_approval(_ctx: Context<CloseGovernanceApproval>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccount;
+
+use crate::enforcement;
+use crate::errors::HookError;
+
+#[derive(Accounts)]
+pub struct CloseHolderState<'info> {
+    /// Receives the rent back
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: HolderState PDA of `owner`; decoded with `load_holder_state` so accounts from before the
+    /// lifetime stats can be closed too
+    #[account(mut, owner = crate::ID, seeds = [b"holder", mint.key().as_ref(), owner.key().as_ref()], bump)]
+    pub holder_state: UncheckedAccount<'info>,
+
+    /// CHECK: The owner's token account of the mint; checked in the handler
+    #[account(owner = TOKEN_2022_PROGRAM_ID @ HookError::InvalidAccountOwner)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn close_holder_state(ctx: Context<CloseHolderState>) -> Result<()> {
+    let (owner, mint) = (ctx.accounts.owner.key(), ctx.accounts.mint.key());
+    let state = enforcement::load_holder_state(&ctx.accounts.holder_state, &mint, &owner)?;
+
+    // The owner has to hold nothing; closing a holder with a tracked balance would also leave the
+    // holder count too high
+    let data = ctx.accounts.token_account.try_borrow_data()?;
+    let token_account = StateWithExtensions::<TokenAccount>::unpack(&data)?;
+    require!(
+        token_account.base.mint == mint && token_account.base.owner == owner,
+        HookError::InvalidHolderState
+    );
+    require!(state.balance == 0 && token_account.base.amount == 0, HookError::HolderBalanceNotZero);
+
+    let holder_state = ctx.accounts.holder_state.to_account_info();
+    let owner_info = ctx.accounts.owner.to_account_info();
+    **owner_info.try_borrow_mut_lamports()? += holder_state.lamports();
+    **holder_state.try_borrow_mut_lamports()? = 0;
+    holder_state.assign(&System::id());
+    holder_state.realloc(0, false)?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::SnapshotClosed;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig, Snapshot};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct CloseSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [b"snapshot", mint.key().as_ref(), &index.to_le_bytes()],
+        bump
+    )]
+    pub snapshot: Account<'info, Snapshot>,
+
+    /// CHECK: Whoever paid for the snapshot; gets its rent back
+    #[account(mut, address = snapshot.payer @ HookError::InvalidRentRecipient)]
+    pub rent_recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn close_snapshot(ctx: Context<CloseSnapshot>, index: u64) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::CloseSnapshot { index },
+    )?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(SnapshotClosed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index,
+        recipient: ctx.accounts.rent_recipient.key(),
+        lamports: ctx.accounts.snapshot.to_account_info().lamports(),
+        closed_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::LockupCreated;
+use crate::logic::validate_lockup_schedule;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig, Lockup};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct CreateLockup<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Lockup::SPACE,
+        seeds = [b"lockup", mint.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub lockup: Account<'info, Lockup>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn create_lockup(
+    ctx: Context<CreateLockup>,
+    owner: Pubkey,
+    locked_amount: u64,
+    locked_until: i64,
+    linear_release_end: Option<i64>,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::CreateLockup { owner, locked_amount, locked_until, linear_release_end },
+    )?;
+
+    validate_lockup_schedule(locked_until, linear_release_end)?;
+
+    let lockup = &mut ctx.accounts.lockup;
+    lockup.mint = ctx.accounts.mint.key();
+    lockup.owner = owner;
+    lockup.locked_amount = locked_amount;
+    lockup.locked_until = locked_until;
+    lockup.linear_release_end = linear_release_end;
+
+    let clock = Clock::get()?;
+    emit_cpi!(LockupCreated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        owner,
+        locked_amount,
+        locked_until,
+        linear_release_end,
+        created_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
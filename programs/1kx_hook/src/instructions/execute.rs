@@ -0,0 +1,103 @@
+use std::collections::BTreeSet;
+
+use anchor_lang::prelude::*;
+use anchor_lang::{Accounts, AccountsExit, Bumps};
+
+use crate::enforcement;
+use crate::state::HookConfig;
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    /// CHECK: Source
+    pub source: UncheckedAccount<'info>,
+    
+    /// CHECK: Mint  
+    pub mint: UncheckedAccount<'info>,
+    
+    /// CHECK: Destination
+    pub destination: UncheckedAccount<'info>,
+    
+    /// CHECK: Owner
+    pub owner: UncheckedAccount<'info>,
+    
+    /// CHECK: Extra account meta list
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    
+    // The stored bump spares every transfer a find_program_address
+    #[account(mut, seeds = [b"config", mint.key().as_ref()], bump = config.bump)]
+    pub config: Account<'info, HookConfig>,
+
+    /// CHECK: Exempt registry PDA, checked against the stored bump in `validate_accounts`; may not have been
+    /// created, in which case nothing is registry-exempt
+    pub exempt_registry: UncheckedAccount<'info>,
+
+    /// CHECK: Instructions sysvar, scanned for ed25519 permit verifications
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// CHECK: Used permit nonces PDA, checked against the stored bump in `validate_accounts`; only
+    /// deserialized when a permit is redeemed
+    #[account(mut)]
+    pub used_nonces: UncheckedAccount<'info>,
+
+    /// CHECK: HolderState PDA of the source owner; validated in `track_holders`
+    #[account(mut)]
+    pub source_holder: UncheckedAccount<'info>,
+
+    /// CHECK: HolderState PDA of the destination owner; validated in `track_holders`
+    #[account(mut)]
+    pub destination_holder: UncheckedAccount<'info>,
+
+    /// CHECK: Lockup PDA of the source owner; validated in `check_lockup`
+    pub source_lockup: UncheckedAccount<'info>,
+
+    /// CHECK: ConfigExtension PDA; may not have been created, validated in `record_buy`
+    #[account(mut)]
+    pub config_extension: UncheckedAccount<'info>,
+
+    /// CHECK: SniperLog PDA; may not have been created, validated in `record_buy`
+    #[account(mut)]
+    pub sniper_log: UncheckedAccount<'info>,
+
+    /// CHECK: TrustedOwner PDA of the destination owner; may not have been created, validated in
+    /// `load_trusted_owner`
+    pub destination_trusted_owner: UncheckedAccount<'info>,
+}
+
+impl<'info> Execute<'info> {
+    fn hook_accounts(&mut self) -> enforcement::HookAccounts<'_, 'info> {
+        enforcement::HookAccounts {
+            source: &self.source,
+            mint: &self.mint,
+            destination: &self.destination,
+            owner: &self.owner,
+            config: &mut self.config,
+            exempt_registry: &self.exempt_registry,
+            instructions_sysvar: &self.instructions_sysvar,
+            used_nonces: &self.used_nonces,
+            source_holder: &self.source_holder,
+            destination_holder: &self.destination_holder,
+            source_lockup: &self.source_lockup,
+            config_extension: &self.config_extension,
+            sniper_log: &self.sniper_log,
+            destination_trusted_owner: &self.destination_trusted_owner,
+        }
+    }
+}
+
+pub fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
+    enforcement::enforce(ctx.accounts.hook_accounts(), amount)
+}
+
+/// `execute` as Token-2022 calls it, through the interface discriminator. Dispatched the way Anchor's
+/// generated entry point does it, minus the instruction name it logs on every transfer
+pub fn dispatch_execute<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], amount: u64) -> Result<()> {
+    hook_log!("Instruction: Execute");
+    let mut bumps = <Execute as Bumps>::Bumps::default();
+    let mut reallocs = BTreeSet::new();
+    let mut remaining_accounts = accounts;
+    let mut execute_accounts =
+        Execute::try_accounts(program_id, &mut remaining_accounts, &amount.to_le_bytes(), &mut bumps, &mut reallocs)?;
+    execute(Context::new(program_id, &mut execute_accounts, remaining_accounts, bumps), amount)?;
+    execute_accounts.exit(program_id)
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::DevWalletUpdated;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteDevWalletUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn execute_dev_wallet_update(ctx: Context<ExecuteDevWalletUpdate>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ExecuteDevWalletUpdate,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    let (index, old_wallet, new_wallet) = config.apply_pending_dev_wallet_update(clock.unix_timestamp)?;
+    if index == 0 {
+        config.refresh_dev_wallet_ata(&ctx.accounts.mint.key());
+    }
+
+    emit_cpi!(DevWalletUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index,
+        old_wallet,
+        new_wallet,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
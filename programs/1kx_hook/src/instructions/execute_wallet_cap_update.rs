@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::WalletCapUpdated;
+use crate::state::{GovernanceLog, GovernanceLogAction, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteWalletCapUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    /// Whoever cranks the matured update; may simply be the fee payer
+    pub executor: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"gov-log", mint.key().as_ref()], bump)]
+    pub governance_log: Box<Account<'info, GovernanceLog>>,
+}
+
+pub fn execute_wallet_cap_update(ctx: Context<ExecuteWalletCapUpdate>) -> Result<()> {
+    ctx.accounts.config.authorize_execution(&ctx.accounts.executor.key())?;
+
+    let config= &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    let pending_update = config.pending_cap_update
+        .as_ref()
+        .ok_or(HookError::NoPendingUpdate)?;
+
+    // Check if timelock has expired
+    require!(
+        clock.unix_timestamp >= pending_update.execution_time,
+        HookError::TimelockNotExpired
+    );
+    // A failed execution can't clear the proposal (the transaction rolls back); sweep_expired_proposal does
+    require!(!pending_update.is_expired(clock.unix_timestamp), HookError::ProposalExpired);
+    // The floor may have been raised since the proposal was made
+    config.check_cap_floor(pending_update.kind, pending_update.new_cap)?;
+
+    let kind = pending_update.kind;
+    let new_cap = pending_update.new_cap;
+    let nonce = pending_update.nonce;
+    let memo_hash = pending_update.memo_hash;
+    ctx.accounts.governance_log.record(
+        GovernanceLogAction::Executed,
+        pending_update,
+        clock.unix_timestamp,
+        ctx.accounts.executor.key(),
+    );
+    let old_cap = config.set_cap(kind, new_cap);
+    config.pending_cap_update = None;
+
+    emit_cpi!(WalletCapUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        kind,
+        old_cap,
+        new_cap,
+        updated_at: clock.unix_timestamp,
+        executed_by: ctx.accounts.executor.key(),
+        proposal_nonce: nonce,
+        immediate: false,
+        memo_hash,
+    });
+
+    Ok(())
+}
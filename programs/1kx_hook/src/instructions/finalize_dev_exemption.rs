@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::DevExemptionExpired;
+use crate::state::HookConfig;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeDevExemption<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn finalize_dev_exemption(ctx: Context<FinalizeDevExemption>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(!config.dev_exemption_finalized, HookError::DevExemptionAlreadyFinalized);
+    require!(
+        !config.dev_exemption_active(clock.unix_timestamp),
+        HookError::DevExemptionNotExpired
+    );
+
+    config.dev_exemption_finalized = true;
+
+    emit_cpi!(DevExemptionExpired {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        // Only reachable once an expiry has been set
+        expired_at: config.dev_exemption_expires_at.unwrap_or_default(),
+        finalized_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
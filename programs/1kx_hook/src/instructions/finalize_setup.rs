@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::errors::HookError;
+use crate::events::SetupFinalized;
+use crate::logic::mint_uses_this_hook;
+use crate::meta_list::{extra_account_metas, holds_current_metas};
+use crate::state::HookConfig;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeSetup<'info> {
+    // Frozen configs can still be finalized: the flag records facts about the setup, not a parameter
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    /// CHECK: Extra account meta list PDA; its owner and metas are checked in the handler
+    #[account(seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: The hooked mint; its TransferHook extension is checked in the handler
+    #[account(owner = TOKEN_2022_PROGRAM_ID @ HookError::InvalidAccountOwner)]
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn finalize_setup(ctx: Context<FinalizeSetup>) -> Result<()> {
+    require!(!ctx.accounts.config.finalized, HookError::SetupAlreadyFinalized);
+    require!(mint_uses_this_hook(&ctx.accounts.mint.try_borrow_data()?)?, HookError::MintNotConfiguredForHook);
+
+    // Token-2022 resolves the hook's accounts from this list, so it has to hold what `execute` expects
+    let list = &ctx.accounts.extra_account_meta_list;
+    require!(list.owner == &crate::ID && !list.data_is_empty(), HookError::ExtraAccountMetaListMissing);
+    require!(holds_current_metas(&list.try_borrow_data()?)?, HookError::ExtraAccountMetasMismatch);
+
+    ctx.accounts.config.finalized = true;
+
+    let clock = Clock::get()?;
+    emit_cpi!(SetupFinalized {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        meta_count: extra_account_metas()?.len() as u32,
+        finalized_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
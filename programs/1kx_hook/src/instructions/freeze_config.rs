@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ConfigFrozen;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FreezeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn freeze_config(ctx: Context<FreezeConfig>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::FreezeConfig,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    // Nothing could ever lift an emergency pause afterwards
+    require!(config.paused_until != i64::MAX, HookError::FreezeWhilePaused);
+    config.freeze();
+
+    let clock = Clock::get()?;
+    emit_cpi!(ConfigFrozen {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        wallet_cap_raw: config.wallet_cap_raw,
+        dev_wallets: config.dev_wallets,
+        frozen_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+use crate::errors::HookError;
+use crate::state::{ConfigView, HookConfig, HookConfigV1};
+use crate::CONFIG_VERSION;
+
+#[derive(Accounts)]
+pub struct GetConfig<'info> {
+    /// CHECK: Decoded by hand in the handler so configs still on the v1 layout can be viewed too
+    #[account(owner = crate::ID, seeds = [b"config", mint.key().as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn get_config(ctx: Context<GetConfig>) -> Result<()> {
+    let data = ctx.accounts.config.try_borrow_data()?;
+    require!(
+        data.len() > 8 && data[..8] == HookConfig::DISCRIMINATOR,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    // The view has the same shape whichever layout it is read from
+    let config = match data[8] {
+        1 => HookConfigV1::deserialize(&mut &data[8..])?.into_v2(ctx.bumps.config)?,
+        2 | CONFIG_VERSION => HookConfig::try_deserialize(&mut &data[..])?,
+        _ => return Err(HookError::UnsupportedVersion.into()),
+    };
+
+    let view = ConfigView::new(&config, Clock::get()?.unix_timestamp);
+    set_return_data(&view.try_to_vec()?);
+    Ok(())
+}
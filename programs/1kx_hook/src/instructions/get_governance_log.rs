@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+use crate::state::GovernanceLog;
+
+#[derive(Accounts)]
+pub struct GetGovernanceLog<'info> {
+    #[account(seeds = [b"gov-log", mint.key().as_ref()], bump)]
+    pub governance_log: Box<Account<'info, GovernanceLog>>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn get_governance_log(ctx: Context<GetGovernanceLog>, page: u8) -> Result<()> {
+    let entries = ctx.accounts.governance_log.page(page);
+    set_return_data(&entries.try_to_vec()?);
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{GovernanceRenounced, GovernanceTransferCanceled, GovernanceTransferProposed};
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct GovernanceTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn propose_governance_transfer(ctx: Context<GovernanceTransfer>, new_authority: Pubkey) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ProposeGovernanceTransfer { new_authority },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    config.pending_governance_authority = Some(new_authority);
+
+    let clock = Clock::get()?;
+    emit_cpi!(GovernanceTransferProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_authority: config.governance_authority,
+        new_authority,
+        proposed_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_governance_transfer(ctx: Context<GovernanceTransfer>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::CancelGovernanceTransfer,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let new_authority = config
+        .pending_governance_authority
+        .take()
+        .ok_or(HookError::NoPendingGovernanceTransfer)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(GovernanceTransferCanceled {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_authority: config.governance_authority,
+        new_authority,
+        canceled_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn renounce_governance(ctx: Context<GovernanceTransfer>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::RenounceGovernance,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let old_authority = config.renounce_governance();
+
+    let clock = Clock::get()?;
+    emit_cpi!(GovernanceRenounced {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_authority,
+        wallet_cap_raw: config.wallet_cap_raw,
+        renounced_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
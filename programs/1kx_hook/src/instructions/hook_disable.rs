@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{HookDisableCanceled, HookDisableProposed, HookPermanentlyDisabled};
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct HookDisable<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn propose_hook_disable(ctx: Context<HookDisable>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ProposeHookDisable,
+    )?;
+
+    let clock = Clock::get()?;
+    let execution_time = ctx.accounts.config.propose_hook_disable(clock.unix_timestamp)?;
+
+    emit_cpi!(HookDisableProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn permanently_disable_hook(ctx: Context<HookDisable>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::PermanentlyDisableHook,
+    )?;
+
+    let clock = Clock::get()?;
+    ctx.accounts.config.apply_hook_disable(clock.unix_timestamp)?;
+
+    emit_cpi!(HookPermanentlyDisabled {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        disabled_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn cancel_hook_disable(ctx: Context<HookDisable>) -> Result<()> {
+    if !ctx.accounts.config.is_veto_authority(&ctx.accounts.governance_authority.key()) {
+        ctx.accounts.config.authorize(
+            &ctx.accounts.governance_authority.key(),
+            ctx.accounts.approval.as_deref(),
+            &ctx.accounts.mint.key(),
+            &GovernanceAction::CancelHookDisable,
+        )?;
+    }
+
+    let execution_time = ctx
+        .accounts
+        .config
+        .pending_hook_disable
+        .take()
+        .ok_or(HookError::NoPendingUpdate)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(HookDisableCanceled {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        execution_time,
+        canceled_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::WalletCapUpdated;
+use crate::state::{CapKind, GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ImmediateCapIncrease<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn execute_immediate_cap_increase(ctx: Context<ImmediateCapIncrease>, new_cap: u64) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ImmediateCapIncrease { new_cap },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let old_cap = config.apply_immediate_cap_increase(new_cap)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(WalletCapUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        kind: CapKind::Wallet,
+        old_cap,
+        new_cap,
+        updated_at: clock.unix_timestamp,
+        executed_by: ctx.accounts.governance_authority.key(),
+        proposal_nonce: 0,
+        immediate: true,
+        memo_hash: [0; 32],
+    });
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{ConfigExtension, HookConfig};
+
+#[derive(Accounts)]
+pub struct InitConfigExtension<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"config", mint.key().as_ref()], bump)]
+    pub config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ConfigExtension::SPACE,
+        seeds = [b"config-ext", mint.key().as_ref()],
+        bump
+    )]
+    pub config_extension: Box<Account<'info, ConfigExtension>>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_config_extension(ctx: Context<InitConfigExtension>) -> Result<()> {
+    ctx.accounts.config_extension.mint = ctx.accounts.mint.key();
+    Ok(())
+}
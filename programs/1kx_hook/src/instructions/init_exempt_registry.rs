@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::state::{ExemptRegistry, GovernanceAction, GovernanceApproval, HookConfig};
+
+#[derive(Accounts)]
+pub struct InitExemptRegistry<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = ExemptRegistry::space(0),
+        seeds = [b"exempt-registry", mint.key().as_ref()],
+        bump
+    )]
+    pub exempt_registry: Account<'info, ExemptRegistry>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn init_exempt_registry(
+    ctx: Context<InitExemptRegistry>,
+    rent_recipient: Pubkey,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::InitExemptRegistry { rent_recipient },
+    )?;
+
+    let registry = &mut ctx.accounts.exempt_registry;
+    registry.rent_recipient = rent_recipient;
+    registry.entries = Vec::new();
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+
+use crate::errors::HookError;
+use crate::meta_list::{extra_account_meta_list_size, extra_account_metas};
+
+#[derive(Accounts)]
+pub struct InitExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// CHECK: Extra account meta list PDA
+    #[account(
+        init,
+        payer = payer,
+        // Exactly the TLV data; Token-2022 reads the account raw, so there is no Anchor discriminator
+        space = extra_account_meta_list_size()?,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+    
+    /// CHECK: Mint
+    pub mint: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_extra_account_meta_list(
+    ctx: Context<InitExtraAccountMetaList>,
+) -> Result<()> {
+    let account_metas = extra_account_metas()?;
+
+    let account_size = ExtraAccountMetaList::size_of(account_metas.len())?;
+    let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+
+    // Provide detailed error information for debugging
+    msg!("Required account size: {}", account_size);
+    msg!("Allocated space: {}", data.len());
+
+    require!(
+        data.len() >= account_size,
+        HookError::InsufficientAccountSpace
+    );
+
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &account_metas)?;
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+use crate::errors::HookError;
+use crate::state::GlobalConfig;
+use crate::TIMELOCK_DURATION;
+
+#[derive(Accounts)]
+pub struct InitGlobalConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(init, payer = payer, space = GlobalConfig::SPACE, seeds = [b"global"], bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The program's upgrade authority; the address is a singleton, so anyone else could claim it first
+    pub upgrade_authority: Signer<'info>,
+
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ HookError::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_global_config(ctx: Context<InitGlobalConfig>, factory_admin: Pubkey) -> Result<()> {
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.factory_admin = factory_admin;
+    global_config.mint_count = 0;
+    global_config.bump = ctx.bumps.global_config;
+    // What a mint gets with no factory defaults: the 48 hour timelock, no floor and no swing limit
+    global_config.default_timelock_seconds = TIMELOCK_DURATION as u32;
+    global_config.default_min_cap_floor = 0;
+    global_config.default_max_cap_change_bps = 0;
+    Ok(())
+}
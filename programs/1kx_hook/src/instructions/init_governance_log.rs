@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{GovernanceLog, HookConfig};
+
+#[derive(Accounts)]
+pub struct InitGovernanceLog<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"config", mint.key().as_ref()], bump)]
+    pub config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = GovernanceLog::SPACE,
+        seeds = [b"gov-log", mint.key().as_ref()],
+        bump
+    )]
+    pub governance_log: Box<Account<'info, GovernanceLog>>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_governance_log(_ctx: Context<InitGovernanceLog>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::state::HolderState;
+
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct InitHolderState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = HolderState::SPACE,
+        seeds = [b"holder", mint.key().as_ref(), owner.as_ref()],
+        bump
+    )]
+    pub holder_state: Account<'info, HolderState>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_holder_state(ctx: Context<InitHolderState>, owner: Pubkey) -> Result<()> {
+    let holder = &mut ctx.accounts.holder_state;
+    holder.mint = ctx.accounts.mint.key();
+    holder.owner = owner;
+    holder.balance = 0;
+    holder.lifetime_received = 0;
+    holder.lifetime_sent = 0;
+    holder.transfer_count = 0;
+    holder.first_seen_ts = 0;
+    Ok(())
+}
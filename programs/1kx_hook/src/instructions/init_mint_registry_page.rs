@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{GlobalConfig, MintRegistry};
+
+#[derive(Accounts)]
+pub struct InitMintRegistryPage<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"global"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// Always the page the next mint goes on, so pages are created in order and none is skipped
+    #[account(
+        init,
+        payer = payer,
+        space = MintRegistry::SPACE,
+        seeds = [b"mint-registry", &global_config.current_page().to_le_bytes()],
+        bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_mint_registry_page(ctx: Context<InitMintRegistryPage>) -> Result<()> {
+    let registry = &mut ctx.accounts.mint_registry;
+    registry.page = ctx.accounts.global_config.current_page();
+    registry.entries = Vec::new();
+    Ok(())
+}
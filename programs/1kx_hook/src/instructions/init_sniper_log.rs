@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{HookConfig, SniperLog};
+
+#[derive(Accounts)]
+pub struct InitSniperLog<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [b"config", mint.key().as_ref()], bump)]
+    pub config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = SniperLog::SPACE,
+        seeds = [b"sniper-log", mint.key().as_ref()],
+        bump
+    )]
+    pub sniper_log: Box<Account<'info, SniperLog>>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_sniper_log(ctx: Context<InitSniperLog>) -> Result<()> {
+    ctx.accounts.sniper_log.mint = ctx.accounts.mint.key();
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig, UsedNonces};
+
+#[derive(Accounts)]
+pub struct InitUsedNonces<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = UsedNonces::SPACE,
+        seeds = [b"used-nonces", mint.key().as_ref()],
+        bump
+    )]
+    pub used_nonces: Account<'info, UsedNonces>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn init_used_nonces(ctx: Context<InitUsedNonces>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::InitUsedNonces,
+    )?;
+    Ok(())
+}
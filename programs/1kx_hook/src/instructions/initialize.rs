@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::errors::HookError;
+use crate::events::DevWalletIsGovernanceAuthority;
+use crate::logic::{
+    mint_authority, mint_has_confidential_transfers, mint_supports_ui_cap, mint_uses_this_hook,
+    validate_initial_authorities, validate_timelock,
+};
+use crate::params::BUILD_PROFILE;
+use crate::state::{CapUnit, HookConfig};
+use crate::{
+    CONFIG_VERSION, DEFAULT_PROPOSAL_EXPIRY_SECONDS, MAX_DEV_WALLETS, MAX_GOVERNANCE_MEMBERS, MAX_TRUSTED_PROGRAMS,
+    WALLET_CAP_RAW,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = HookConfig::SPACE,
+        seeds = [b"config", mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    /// CHECK: Mint account
+    pub mint: UncheckedAccount<'info>,
+    
+    /// The mint's mint authority, checked against the unpacked mint
+    pub mint_authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize(
+    ctx: Context<Initialize>,
+    dev_wallet: Pubkey,
+    governance_authority: Pubkey,
+    cap_unit: CapUnit,
+    timelock_seconds: u32,
+    min_cap_floor: u64,
+    max_cap_change_bps: u16,
+    strict_authorities: bool,
+    allow_confidential: bool,
+) -> Result<()> {
+    let params = ConfigParams {
+        dev_wallet,
+        governance_authority,
+        cap_unit,
+        timelock_seconds,
+        min_cap_floor,
+        max_cap_change_bps,
+        strict_authorities,
+        allow_confidential,
+    };
+    let accounts = &mut ctx.accounts;
+    let initializer = Initializer::MintAuthority(accounts.mint_authority.key());
+    init_config(&mut accounts.config, ctx.bumps.config, &accounts.mint, &accounts.payer.key(), initializer, params)?;
+
+    // Legal, but the dev wallet's cap exemption then also covers the governance key
+    if dev_wallet == governance_authority {
+        emit_cpi!(DevWalletIsGovernanceAuthority {
+            mint: ctx.accounts.mint.key(),
+            slot: Clock::get()?.slot,
+            authority: governance_authority,
+        });
+    }
+    Ok(())
+}
+
+/// The arguments `initialize` and `initialize_for_mint` write a new config from
+pub(crate) struct ConfigParams {
+    pub dev_wallet: Pubkey,
+    pub governance_authority: Pubkey,
+    pub cap_unit: CapUnit,
+    pub timelock_seconds: u32,
+    pub min_cap_floor: u64,
+    pub max_cap_change_bps: u16,
+    pub strict_authorities: bool,
+    pub allow_confidential: bool,
+}
+
+/// Who signed for a new config
+pub(crate) enum Initializer {
+    /// Must be the mint's current mint authority
+    MintAuthority(Pubkey),
+    /// Either the mint authority or the factory admin of the `GlobalConfig`
+    Factory { signer: Pubkey, factory_admin: Pubkey },
+}
+
+/// Validate `params` and the mint, check `initializer` may claim the mint's config and write it
+pub(crate) fn init_config(
+    config: &mut HookConfig,
+    bump: u8,
+    mint: &AccountInfo,
+    payer: &Pubkey,
+    initializer: Initializer,
+    params: ConfigParams,
+) -> Result<()> {
+    let ConfigParams {
+        dev_wallet,
+        governance_authority,
+        cap_unit,
+        timelock_seconds,
+        min_cap_floor,
+        max_cap_change_bps,
+        strict_authorities,
+        allow_confidential,
+    } = params;
+    validate_initial_authorities(&dev_wallet, &governance_authority, payer, strict_authorities)?;
+    validate_timelock(timelock_seconds)?;
+    require!(min_cap_floor <= WALLET_CAP_RAW, HookError::CapBelowFloor);
+
+    // Only a Token-2022 mint that already routes its transfers here can be governed by this program
+    require!(mint.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+    require!(mint_uses_this_hook(&mint.try_borrow_data()?)?, HookError::MintNotConfiguredForHook);
+    // Confidential amounts never reach the hook, so on such a mint the cap only holds for public
+    // balances; that takes an explicit opt-in
+    let confidential = mint_has_confidential_transfers(&mint.try_borrow_data()?)?;
+    require!(!confidential || allow_confidential, HookError::UnsupportedMintExtension);
+    // The config address only depends on the mint, so only its mint authority may claim it, or the
+    // factory admin the upgrade authority trusted with every mint. A mint whose authority is gone can
+    // only be initialized by the factory admin
+    let authority = mint_authority(&mint.try_borrow_data()?)?;
+    let authorized = match initializer {
+        Initializer::MintAuthority(signer) => authority == Some(signer),
+        Initializer::Factory { signer, factory_admin } => authority == Some(signer) || signer == factory_admin,
+    };
+    require!(authorized, HookError::UnauthorizedInitializer);
+
+    // UI units only differ from raw units for mints whose displayed amount drifts from the raw one
+    if cap_unit == CapUnit::Ui {
+        require!(mint_supports_ui_cap(&mint.try_borrow_data()?)?, HookError::UiCapUnsupported);
+    }
+
+    // A short-timelocks build on mainnet would give holders minutes instead of days, so every new config says
+    // which parameters it gets
+    msg!("Build profile: {}", BUILD_PROFILE);
+
+    config.version = CONFIG_VERSION;
+    config.dev_wallets = [Pubkey::default(); MAX_DEV_WALLETS];
+    config.dev_wallets[0] = dev_wallet;
+    config.wallet_cap_raw = WALLET_CAP_RAW;
+    config.governance_authority = governance_authority;
+    config.pending_cap_update = None;
+    config.trusted_programs = [Pubkey::default(); MAX_TRUSTED_PROGRAMS];
+    config.pending_dev_wallet_update = None;
+    config.dev_exemption_expires_at = None;
+    config.dev_exemption_finalized = false;
+    config.airdrop_allowance = 0;
+    config.permit_signer = Pubkey::default();
+    config.buy_cap_raw = WALLET_CAP_RAW;
+    config.max_sell_raw = 0;
+    config.circuit_breaker_threshold_raw = 0;
+    config.circuit_breaker_window_slots = 0;
+    config.circuit_breaker_cooldown = 0;
+    config.window_start_slot = 0;
+    config.window_volume = 0;
+    config.paused_until = 0;
+    config.holder_count = 0;
+    config.max_holders = 0;
+    config.cap_unit = cap_unit;
+    config.pending_governance_authority = None;
+    config.governance_renounced = false;
+    config.governance_members = [Pubkey::default(); MAX_GOVERNANCE_MEMBERS];
+    config.governance_threshold = 0;
+    config.governance_epoch = 0;
+    config.guardian = None;
+    config.timelock_seconds = timelock_seconds;
+    config.pending_timelock_update = None;
+    config.proposal_expiry_seconds = DEFAULT_PROPOSAL_EXPIRY_SECONDS;
+    config.proposal_nonce = 0;
+    config.proposer_authority = governance_authority;
+    config.executor_authority = Pubkey::default();
+    config.veto_authority = None;
+    config.pending_veto_authority_update = None;
+    config.min_cap_floor = min_cap_floor;
+    config.max_cap_change_bps = max_cap_change_bps;
+    config.frozen = false;
+    config.bump = bump;
+    config.emit_transfer_events = false;
+    config.total_transfers = 0;
+    config.total_volume = 0;
+    config.snapshot_count = 0;
+    config.last_snapshot_slot = 0;
+    config.pending_hook_disable = None;
+    config.hook_disabled = false;
+    config.staking_program_slot = 0;
+    config.allow_confidential = confidential;
+    config.refresh_dev_wallet_ata(mint.key);
+    config.store_pda_bumps(mint.key);
+    Ok(())
+}
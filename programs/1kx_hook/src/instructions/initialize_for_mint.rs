@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::events::DevWalletIsGovernanceAuthority;
+use crate::instructions::initialize::{init_config, ConfigParams, Initializer};
+use crate::state::{CapUnit, GlobalConfig, HookConfig, MintRegistry, MintRegistryEntry};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeForMint<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = HookConfig::SPACE,
+        seeds = [b"config", mint.key().as_ref()],
+        bump
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    /// CHECK: Mint account
+    pub mint: UncheckedAccount<'info>,
+
+    /// The mint's mint authority or the factory admin
+    pub initializer: Signer<'info>,
+
+    #[account(mut, seeds = [b"global"], bump = global_config.bump)]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// The page the mint is recorded on; `init_mint_registry_page` creates it once the previous one is full
+    #[account(mut, seeds = [b"mint-registry", &global_config.current_page().to_le_bytes()], bump)]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_for_mint(
+    ctx: Context<InitializeForMint>,
+    dev_wallet: Pubkey,
+    governance_authority: Pubkey,
+    cap_unit: CapUnit,
+    timelock_seconds: Option<u32>,
+    min_cap_floor: Option<u64>,
+    max_cap_change_bps: Option<u16>,
+    strict_authorities: bool,
+    allow_confidential: bool,
+) -> Result<()> {
+    // Parameters left out are the factory's defaults, copied so the config never depends on them later
+    let accounts = &mut ctx.accounts;
+    let global = &accounts.global_config;
+    let params = ConfigParams {
+        dev_wallet,
+        governance_authority,
+        cap_unit,
+        timelock_seconds: timelock_seconds.unwrap_or(global.default_timelock_seconds),
+        min_cap_floor: min_cap_floor.unwrap_or(global.default_min_cap_floor),
+        max_cap_change_bps: max_cap_change_bps.unwrap_or(global.default_max_cap_change_bps),
+        strict_authorities,
+        allow_confidential,
+    };
+    let initializer = accounts.initializer.key();
+    let factory = Initializer::Factory { signer: initializer, factory_admin: accounts.global_config.factory_admin };
+    init_config(&mut accounts.config, ctx.bumps.config, &accounts.mint, &accounts.payer.key(), factory, params)?;
+
+    let slot = Clock::get()?.slot;
+    accounts.mint_registry.entries.push(MintRegistryEntry {
+        mint: accounts.mint.key(),
+        creator: initializer,
+        created_slot: slot,
+    });
+    accounts.global_config.mint_count += 1;
+
+    // Legal, but the dev wallet's cap exemption then also covers the governance key
+    if dev_wallet == governance_authority {
+        emit_cpi!(DevWalletIsGovernanceAuthority {
+            mint: ctx.accounts.mint.key(),
+            slot,
+            authority: governance_authority,
+        });
+    }
+    Ok(())
+}
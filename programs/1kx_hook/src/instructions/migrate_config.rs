@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ConfigMigrated;
+use crate::state::{HookConfig, HookConfigV1};
+use crate::{CONFIG_VERSION, SUPPORTED_CONFIG_VERSION};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: Decoded by hand in the handler since it may hold an older layout; governance is checked there
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"config", mint.key().as_ref()],
+        bump
+    )]
+    pub config: UncheckedAccount<'info>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// Funds the rent for the larger account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+
+    // The account may still hold an older layout, so it is decoded by hand rather than as Account<HookConfig>
+    let (current_version, migrated) = {
+        let data = config_info.try_borrow_data()?;
+        require!(
+            data.len() > 8 && data[..8] == HookConfig::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        let current_version = data[8];
+        // Only the version 2 layout has the flags, and a frozen or disabled config is never migrated again
+        if current_version >= 2 {
+            let config = HookConfig::try_deserialize(&mut &data[..])?;
+            require!(!config.frozen, HookError::ConfigFrozen);
+            require!(!config.hook_disabled, HookError::HookDisabled);
+        }
+
+        require!(current_version <= SUPPORTED_CONFIG_VERSION, HookError::UnsupportedVersion);
+        require!(current_version < CONFIG_VERSION, HookError::InvalidMigrationVersion);
+
+        // Version-specific migration logic
+        let mut migrated = match current_version {
+            1 => HookConfigV1::deserialize(&mut &data[8..])?.into_v2(ctx.bumps.config)?,
+            // Same layout; only the version moves on
+            2 => HookConfig::try_deserialize(&mut &data[..])?,
+            _ => return Err(HookError::UnsupportedMigration.into()),
+        };
+        // An older config was already passing transfers, so it stays finalized rather than stopping them
+        migrated.version = CONFIG_VERSION;
+        migrated.finalized = true;
+        migrated.refresh_dev_wallet_ata(&ctx.accounts.mint.key());
+        migrated.store_pda_bumps(&ctx.accounts.mint.key());
+        (current_version, migrated)
+    };
+
+    require_keys_eq!(
+        migrated.governance_authority,
+        ctx.accounts.governance_authority.key(),
+        HookError::UnauthorizedGovernance
+    );
+
+    // Top up rent for the larger layout before growing the account
+    let required_lamports = Rent::get()?.minimum_balance(HookConfig::SPACE);
+    let shortfall = required_lamports.saturating_sub(config_info.lamports());
+    if shortfall > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: config_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    if config_info.data_len() < HookConfig::SPACE {
+        config_info.realloc(HookConfig::SPACE, true)?;
+    }
+
+    let mut data = config_info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    migrated.try_serialize(&mut writer)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(ConfigMigrated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_version: current_version,
+        new_version: migrated.version,
+        migrated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
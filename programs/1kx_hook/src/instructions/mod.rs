@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+
+pub mod accept_governance_authority;
+pub mod add_exempt_entry;
+pub mod adopt_global_defaults;
+pub mod approve_action;
+pub mod can_receive;
+pub mod cancel_dev_wallet_update;
+pub mod cancel_wallet_cap_update;
+pub mod check_transfer;
+pub mod clear_sniper_flag;
+pub mod close_config;
+pub mod close_extra_account_meta_list;
+pub mod close_governance_approval;
+pub mod close_holder_state;
+pub mod close_snapshot;
+pub mod create_lockup;
+pub mod execute;
+pub mod execute_dev_wallet_update;
+pub mod execute_wallet_cap_update;
+pub mod finalize_dev_exemption;
+pub mod finalize_setup;
+pub mod freeze_config;
+pub mod get_config;
+pub mod get_governance_log;
+pub mod governance_transfer;
+pub mod hook_disable;
+pub mod immediate_cap_increase;
+pub mod init_config_extension;
+pub mod init_exempt_registry;
+pub mod init_extra_account_meta_list;
+pub mod init_global_config;
+pub mod init_governance_log;
+pub mod init_holder_state;
+pub mod init_mint_registry_page;
+pub mod init_sniper_log;
+pub mod init_used_nonces;
+pub mod initialize;
+pub mod initialize_for_mint;
+pub mod lower_dev_outflow_limit;
+pub mod migrate_config;
+pub mod propose_action;
+pub mod propose_dev_wallet_update;
+pub mod propose_wallet_cap_update;
+pub mod prune_exempt_registry;
+pub mod raise_min_cap_floor;
+pub mod register_bridge_custody;
+pub mod register_staking_vault;
+pub mod register_trusted_owner;
+pub mod remove_exempt_entry;
+pub mod reset_launch_detection;
+pub mod set_airdrop_allowance;
+pub mod set_cap_update_role;
+pub mod set_circuit_breaker;
+pub mod set_dev_exemption_expiry;
+pub mod set_dev_wallet_verified;
+pub mod set_global_defaults;
+pub mod set_governance_members;
+pub mod set_guardian;
+pub mod set_max_holders;
+pub mod set_memo_required_above;
+pub mod set_pause;
+pub mod set_permit_signer;
+pub mod set_proposal_expiry;
+pub mod set_require_wallet_owners;
+pub mod set_snipe_window;
+pub mod set_staking_program;
+pub mod set_transfer_events;
+pub mod set_trusted_program;
+pub mod shorten_lockup;
+pub mod sweep_expired_proposal;
+pub mod take_snapshot;
+pub mod timelock_update;
+pub mod transfer_hook;
+pub mod update_extra_account_meta_list;
+pub mod veto_authority_update;
+pub mod withdraw_surplus_lamports;
+
+pub use accept_governance_authority::*;
+pub use add_exempt_entry::*;
+pub use adopt_global_defaults::*;
+pub use approve_action::*;
+pub use can_receive::*;
+pub use cancel_dev_wallet_update::*;
+pub use cancel_wallet_cap_update::*;
+pub use check_transfer::*;
+pub use clear_sniper_flag::*;
+pub use close_config::*;
+pub use close_extra_account_meta_list::*;
+pub use close_governance_approval::*;
+pub use close_holder_state::*;
+pub use close_snapshot::*;
+pub use create_lockup::*;
+pub use execute::*;
+pub use execute_dev_wallet_update::*;
+pub use execute_wallet_cap_update::*;
+pub use finalize_dev_exemption::*;
+pub use finalize_setup::*;
+pub use freeze_config::*;
+pub use get_config::*;
+pub use get_governance_log::*;
+pub use governance_transfer::*;
+pub use hook_disable::*;
+pub use immediate_cap_increase::*;
+pub use init_config_extension::*;
+pub use init_exempt_registry::*;
+pub use init_extra_account_meta_list::*;
+pub use init_global_config::*;
+pub use init_governance_log::*;
+pub use init_holder_state::*;
+pub use init_mint_registry_page::*;
+pub use init_sniper_log::*;
+pub use init_used_nonces::*;
+pub use initialize::*;
+pub use initialize_for_mint::*;
+pub use lower_dev_outflow_limit::*;
+pub use migrate_config::*;
+pub use propose_action::*;
+pub use propose_dev_wallet_update::*;
+pub use propose_wallet_cap_update::*;
+pub use prune_exempt_registry::*;
+pub use raise_min_cap_floor::*;
+pub use register_bridge_custody::*;
+pub use register_staking_vault::*;
+pub use register_trusted_owner::*;
+pub use remove_exempt_entry::*;
+pub use reset_launch_detection::*;
+pub use set_airdrop_allowance::*;
+pub use set_cap_update_role::*;
+pub use set_circuit_breaker::*;
+pub use set_dev_exemption_expiry::*;
+pub use set_dev_wallet_verified::*;
+pub use set_global_defaults::*;
+pub use set_governance_members::*;
+pub use set_guardian::*;
+pub use set_max_holders::*;
+pub use set_memo_required_above::*;
+pub use set_pause::*;
+pub use set_permit_signer::*;
+pub use set_proposal_expiry::*;
+pub use set_require_wallet_owners::*;
+pub use set_snipe_window::*;
+pub use set_staking_program::*;
+pub use set_transfer_events::*;
+pub use set_trusted_program::*;
+pub use shorten_lockup::*;
+pub use sweep_expired_proposal::*;
+pub use take_snapshot::*;
+pub use timelock_update::*;
+pub use transfer_hook::*;
+pub use update_extra_account_meta_list::*;
+pub use veto_authority_update::*;
+pub use withdraw_surplus_lamports::*;
+
+/// Grow a program-owned account to `space` bytes, topping its rent up from `payer` first
+pub(crate) fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    let shortfall = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if shortfall > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer { from: payer.clone(), to: account.clone() },
+            ),
+            shortfall,
+        )?;
+    }
+    Ok(account.realloc(space, false)?)
+}
+
+/// Shrink a program-owned account to `space` bytes and move any lamports above rent exemption to
+/// `recipient`. Returns the refunded amount.
+pub(crate) fn shrink_account(account: &AccountInfo, recipient: &AccountInfo, space: usize) -> Result<u64> {
+    if account.data_len() > space {
+        account.realloc(space, false)?;
+    }
+    let surplus = account
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(space));
+    if surplus > 0 {
+        **account.try_borrow_mut_lamports()? -= surplus;
+        **recipient.try_borrow_mut_lamports()? += surplus;
+    }
+    Ok(surplus)
+}
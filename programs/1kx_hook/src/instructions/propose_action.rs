@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::GovernanceActionProposed;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(action: GovernanceAction)]
+pub struct ProposeAction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced,
+        constraint = config.governance_threshold > 0 @ HookError::MultisigGovernanceDisabled
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub member: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = GovernanceApproval::SPACE,
+        seeds = [b"governance-approval", mint.key().as_ref(), &action.hash(&mint.key(), config.governance_epoch)],
+        bump
+    )]
+    pub approval: Account<'info, GovernanceApproval>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn propose_action(ctx: Context<ProposeAction>, action: GovernanceAction) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let mint = ctx.accounts.mint.key();
+    let proposer = ctx.accounts.member.key();
+    let action_hash = action.hash(&mint, config.governance_epoch);
+
+    let approval = &mut ctx.accounts.approval;
+    approval.mint = mint;
+    approval.action_hash = action_hash;
+    approval.epoch = config.governance_epoch;
+    approval.approvals = 0;
+    approval.payer = ctx.accounts.payer.key();
+    approval.approve(config, &proposer)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(GovernanceActionProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        action_hash,
+        action,
+        epoch: config.governance_epoch,
+        proposer,
+        proposed_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::DevWalletUpdateProposed;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeDevWalletUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn propose_dev_wallet_update(
+    ctx: Context<ProposeDevWalletUpdate>,
+    index: u8,
+    new_wallet: Pubkey,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ProposeDevWalletUpdate { index, new_wallet },
+    )?;
+
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.config;
+    let (old_wallet, execution_time) = config.propose_dev_wallet_update(index, new_wallet, clock.unix_timestamp)?;
+
+    emit_cpi!(DevWalletUpdateProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index,
+        old_wallet,
+        new_wallet,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
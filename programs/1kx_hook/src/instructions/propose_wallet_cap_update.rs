@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{WalletCapUpdateCanceled, WalletCapUpdateProposed};
+use crate::logic::validate_cap_proposal;
+use crate::state::{
+    CapKind, GovernanceAction, GovernanceApproval, GovernanceLog, GovernanceLogAction, HookConfig, PendingCapUpdate,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeWalletCapUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"gov-log", mint.key().as_ref()], bump)]
+    pub governance_log: Box<Account<'info, GovernanceLog>>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn propose_cap_update(
+    ctx: Context<ProposeWalletCapUpdate>,
+    kind: CapKind,
+    new_cap: u64,
+    overwrite: bool,
+    memo_hash: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.config.authorize_proposal(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ProposeCapUpdate { kind, new_cap, overwrite, memo_hash },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    validate_cap_proposal(kind, new_cap)?;
+    config.check_cap_floor(kind, new_cap)?;
+    config.check_cap_change(kind, new_cap)?;
+
+    let governance_authority = ctx.accounts.governance_authority.key();
+    let governance_log = &mut ctx.accounts.governance_log;
+    if let Some(replaced_update) = config.pending_cap_update.take() {
+        require!(overwrite, HookError::ProposalAlreadyPending);
+        governance_log.record(
+            GovernanceLogAction::Canceled,
+            &replaced_update,
+            clock.unix_timestamp,
+            governance_authority,
+        );
+        emit_cpi!(WalletCapUpdateCanceled {
+            mint: ctx.accounts.mint.key(),
+            slot: clock.slot,
+            kind: replaced_update.kind,
+            canceled_cap: replaced_update.new_cap,
+            current_cap: config.cap(replaced_update.kind),
+            canceled_at: clock.unix_timestamp,
+            governance_authority,
+            proposal_nonce: replaced_update.nonce,
+            memo_hash: replaced_update.memo_hash,
+        });
+    }
+
+    let execution_time = config.timelock_execution_time(clock.unix_timestamp)?;
+    let expires_at = execution_time
+        .checked_add(i64::from(config.proposal_expiry_seconds))
+        .ok_or(HookError::ArithmeticOverflow)?;
+    let nonce = config.next_proposal_nonce();
+
+    let proposal = PendingCapUpdate {
+        kind,
+        new_cap,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+        expires_at,
+        nonce,
+        proposed_by: governance_authority,
+        memo_hash,
+    };
+    governance_log.record(GovernanceLogAction::Proposed, &proposal, clock.unix_timestamp, governance_authority);
+    config.pending_cap_update = Some(proposal);
+
+    emit_cpi!(WalletCapUpdateProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        kind,
+        new_cap,
+        current_cap: config.cap(kind),
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+        governance_authority,
+        proposal_nonce: nonce,
+        proposed_by: governance_authority,
+        memo_hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ExemptRegistryPruned;
+use crate::state::ExemptRegistry;
+use super::shrink_account;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PruneExemptRegistry<'info> {
+    #[account(mut, seeds = [b"exempt-registry", mint.key().as_ref()], bump)]
+    pub exempt_registry: Account<'info, ExemptRegistry>,
+    
+    /// CHECK: Receives freed rent; must match the registry's configured recipient
+    #[account(mut, address = exempt_registry.rent_recipient @ HookError::InvalidRentRecipient)]
+    pub rent_recipient: UncheckedAccount<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+}
+
+pub fn prune_exempt_registry(ctx: Context<PruneExemptRegistry>) -> Result<()> {
+    let clock = Clock::get()?;
+    let registry = &mut ctx.accounts.exempt_registry;
+
+    let removed = registry.prune_expired(clock.unix_timestamp);
+    let space = ExemptRegistry::space(registry.entries.len());
+    let refunded = shrink_account(&registry.to_account_info(), &ctx.accounts.rent_recipient, space)?;
+
+    emit_cpi!(ExemptRegistryPruned {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        removed: removed as u32,
+        remaining: registry.entries.len() as u32,
+        refunded_lamports: refunded,
+        pruned_at: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
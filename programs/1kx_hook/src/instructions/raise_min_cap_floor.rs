@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::MinCapFloorRaised;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RaiseMinCapFloor<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn raise_min_cap_floor(ctx: Context<RaiseMinCapFloor>, min_cap_floor: u64) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::RaiseMinCapFloor { min_cap_floor },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let old_floor = config.raise_min_cap_floor(min_cap_floor)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(MinCapFloorRaised {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_floor,
+        new_floor: min_cap_floor,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
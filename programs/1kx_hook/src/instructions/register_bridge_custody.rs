@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccount;
+
+use crate::errors::HookError;
+use crate::events::BridgeCustodyRegistered;
+use crate::instructions::grow_account;
+use crate::state::{ExemptEntry, ExemptKind, ExemptRegistry, GovernanceAction, GovernanceApproval, HookConfig};
+use crate::MAX_EXEMPT_ENTRIES;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RegisterBridgeCustody<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"exempt-registry", mint.key().as_ref()], bump)]
+    pub exempt_registry: Account<'info, ExemptRegistry>,
+
+    /// CHECK: Token account to register; checked in the handler to be the mint's and owned by `custody_owner`
+    pub custody: UncheckedAccount<'info>,
+
+    /// CHECK: The custody account's owner, which has to be a PDA account of the bridge program
+    pub custody_owner: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn register_bridge_custody(ctx: Context<RegisterBridgeCustody>, bridge_program: Pubkey) -> Result<()> {
+    let custody = &ctx.accounts.custody;
+    let custody_owner = &ctx.accounts.custody_owner;
+    let token_account = custody.key();
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::RegisterBridgeCustody { token_account, bridge_program },
+    )?;
+
+    require!(custody.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+    {
+        let data = custody.try_borrow_data()?;
+        let account = StateWithExtensions::<TokenAccount>::unpack(&data)?;
+        require_keys_eq!(account.base.mint, ctx.accounts.mint.key(), HookError::DestinationMintMismatch);
+        require_keys_eq!(account.base.owner, custody_owner.key(), HookError::NotBridgeCustody);
+    }
+    // Governance vouches for the bridge program; the chain vouches for the custody account being its PDA's
+    require!(
+        bridge_program != Pubkey::default()
+            && !custody_owner.key.is_on_curve()
+            && custody_owner.owner == &bridge_program,
+        HookError::NotBridgeCustody
+    );
+
+    let registry = &mut ctx.accounts.exempt_registry;
+    if let Some(entry) = registry.entries.iter_mut().find(|entry| entry.token_account == token_account) {
+        // Custody doesn't lapse
+        entry.kind = ExemptKind::BridgeCustody;
+        entry.expires_at = None;
+    } else {
+        require!(registry.entries.len() < MAX_EXEMPT_ENTRIES, HookError::ExemptRegistryFull);
+        registry.entries.push(ExemptEntry { token_account, kind: ExemptKind::BridgeCustody, expires_at: None });
+        grow_account(
+            &registry.to_account_info(),
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            ExemptRegistry::space(registry.entries.len()),
+        )?;
+    }
+
+    let clock = Clock::get()?;
+    emit_cpi!(BridgeCustodyRegistered {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        token_account,
+        custody_owner: custody_owner.key(),
+        bridge_program,
+        registered_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
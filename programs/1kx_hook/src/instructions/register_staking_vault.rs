@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::Account as TokenAccount;
+
+use crate::errors::HookError;
+use crate::events::StakingVaultRegistered;
+use crate::instructions::grow_account;
+use crate::state::{ExemptEntry, ExemptKind, ExemptRegistry, GovernanceAction, GovernanceApproval, HookConfig};
+use crate::MAX_EXEMPT_ENTRIES;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RegisterStakingVault<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"exempt-registry", mint.key().as_ref()], bump)]
+    pub exempt_registry: Account<'info, ExemptRegistry>,
+
+    /// CHECK: Token account to register; checked in the handler to be the mint's and owned by `vault_owner`
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: The vault's owner, which the handler re-derives from `seeds` under the staking program
+    pub vault_owner: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn register_staking_vault(ctx: Context<RegisterStakingVault>, seeds: Vec<Vec<u8>>) -> Result<()> {
+    let vault = &ctx.accounts.vault;
+    let vault_owner = &ctx.accounts.vault_owner;
+    let token_account = vault.key();
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::RegisterStakingVault { token_account },
+    )?;
+
+    require!(vault.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+    {
+        let data = vault.try_borrow_data()?;
+        let account = StateWithExtensions::<TokenAccount>::unpack(&data)?;
+        require_keys_eq!(account.base.mint, ctx.accounts.mint.key(), HookError::DestinationMintMismatch);
+        require_keys_eq!(account.base.owner, vault_owner.key(), HookError::NotStakingVault);
+    }
+    // The same derivation `register_trusted_owner` checks for pool authorities
+    let is_staking_pda = ctx.accounts.config.is_staking_pda(vault_owner.key, vault_owner.owner, &seeds);
+    require!(is_staking_pda, HookError::NotStakingVault);
+
+    let registry = &mut ctx.accounts.exempt_registry;
+    if let Some(entry) = registry.entries.iter_mut().find(|entry| entry.token_account == token_account) {
+        // Already exempt; only what it is changes, any expiry governance set stays
+        entry.kind = ExemptKind::StakingVault;
+    } else {
+        require!(registry.entries.len() < MAX_EXEMPT_ENTRIES, HookError::ExemptRegistryFull);
+        registry.entries.push(ExemptEntry { token_account, kind: ExemptKind::StakingVault, expires_at: None });
+        grow_account(
+            &registry.to_account_info(),
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            ExemptRegistry::space(registry.entries.len()),
+        )?;
+    }
+
+    let clock = Clock::get()?;
+    emit_cpi!(StakingVaultRegistered {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        token_account,
+        vault_owner: vault_owner.key(),
+        registered_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
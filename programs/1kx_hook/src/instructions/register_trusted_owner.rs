@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::TrustedOwnerRegistered;
+use crate::state::{HookConfig, TrustedOwner};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RegisterTrustedOwner<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump = config.bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TrustedOwner::SPACE,
+        seeds = [b"trusted-owner", mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub trusted_owner: Account<'info, TrustedOwner>,
+
+    /// CHECK: The owner to register, which the handler re-derives from `seeds` under the program owning it
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_trusted_owner(ctx: Context<RegisterTrustedOwner>, seeds: Vec<Vec<u8>>) -> Result<()> {
+    let owner = &ctx.accounts.owner;
+    require!(ctx.accounts.config.is_trusted_pda(owner.key, owner.owner, &seeds), HookError::NotTrustedProgramOwner);
+
+    let (mint, owner, program) = (ctx.accounts.mint.key(), owner.key(), *owner.owner);
+
+    let record = &mut ctx.accounts.trusted_owner;
+    record.mint = mint;
+    record.owner = owner;
+    record.program = program;
+
+    let clock = Clock::get()?;
+    emit_cpi!(TrustedOwnerRegistered { mint, slot: clock.slot, owner, program, registered_at: clock.unix_timestamp });
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ExemptEntryRemoved;
+use crate::state::{ExemptRegistry, GovernanceAction, GovernanceApproval, HookConfig};
+use super::shrink_account;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveExemptEntry<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    #[account(mut, seeds = [b"exempt-registry", mint.key().as_ref()], bump)]
+    pub exempt_registry: Account<'info, ExemptRegistry>,
+    
+    /// CHECK: Receives freed rent; must match the registry's configured recipient
+    #[account(mut, address = exempt_registry.rent_recipient @ HookError::InvalidRentRecipient)]
+    pub rent_recipient: UncheckedAccount<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn remove_exempt_entry(
+    ctx: Context<RemoveExemptEntry>,
+    token_account: Pubkey,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::RemoveExemptEntry { token_account },
+    )?;
+
+    let registry = &mut ctx.accounts.exempt_registry;
+    let position = registry
+        .entries
+        .iter()
+        .position(|e| e.token_account == token_account)
+        .ok_or(HookError::ExemptEntryNotFound)?;
+    registry.entries.remove(position);
+
+    let space = ExemptRegistry::space(registry.entries.len());
+    shrink_account(&registry.to_account_info(), &ctx.accounts.rent_recipient, space)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(ExemptEntryRemoved {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        token_account,
+        removed_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
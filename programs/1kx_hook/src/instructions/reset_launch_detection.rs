@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::LaunchDetectionReset;
+use crate::state::{ConfigExtension, GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResetLaunchDetection<'info> {
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config-ext", mint.key().as_ref()], bump)]
+    pub config_extension: Account<'info, ConfigExtension>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn reset_launch_detection(ctx: Context<ResetLaunchDetection>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ResetLaunchDetection,
+    )?;
+
+    let (old_detected_at, old_detected_slot) = ctx.accounts.config_extension.reset_launch()?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(LaunchDetectionReset {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_detected_at,
+        old_detected_slot,
+        reset_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::CapUpdateRoleUpdated;
+use crate::state::{CapUpdateRole, GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCapUpdateRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_cap_update_role(ctx: Context<SetCapUpdateRole>, role: CapUpdateRole, authority: Pubkey) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetCapUpdateRole { role, authority },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let slot = match role {
+        CapUpdateRole::Proposer => &mut config.proposer_authority,
+        CapUpdateRole::Executor => &mut config.executor_authority,
+    };
+    let old_authority = std::mem::replace(slot, authority);
+
+    let clock = Clock::get()?;
+    emit_cpi!(CapUpdateRoleUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        role,
+        old_authority,
+        new_authority: authority,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{CircuitBreakerCleared, CircuitBreakerUpdated};
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCircuitBreaker<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_circuit_breaker(
+    ctx: Context<SetCircuitBreaker>,
+    threshold_raw: u64,
+    window_slots: u64,
+    cooldown: i64,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetCircuitBreaker { threshold_raw, window_slots, cooldown },
+    )?;
+
+    require!(
+        threshold_raw == 0 || (window_slots > 0 && cooldown > 0),
+        HookError::InvalidCircuitBreakerConfig
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.circuit_breaker_threshold_raw = threshold_raw;
+    config.circuit_breaker_window_slots = window_slots;
+    config.circuit_breaker_cooldown = cooldown;
+
+    let clock = Clock::get()?;
+    emit_cpi!(CircuitBreakerUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        threshold_raw,
+        window_slots,
+        cooldown,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn clear_circuit_breaker(ctx: Context<SetCircuitBreaker>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ClearCircuitBreaker,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    let paused_until = config.paused_until;
+    config.reset_circuit_breaker(clock.slot);
+
+    emit_cpi!(CircuitBreakerCleared {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        paused_until,
+        cleared_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::errors::HookError;
+use crate::events::DevWalletUpdateProposed;
+use crate::logic::check_dev_wallet_token_account;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDevWalletVerified<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: The new dev wallet's existing token account; its mint and owner are checked in the handler
+    pub dev_wallet_token_account: Option<UncheckedAccount<'info>>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_dev_wallet_verified(ctx: Context<SetDevWalletVerified>, index: u8, new_wallet: Pubkey) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetDevWalletVerified { index, new_wallet },
+    )?;
+
+    // Catches a wallet mistyped as, or confused with, its token account before the timelock starts
+    if let Some(token_account) = &ctx.accounts.dev_wallet_token_account {
+        require!(token_account.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+        check_dev_wallet_token_account(&token_account.try_borrow_data()?, &ctx.accounts.mint.key(), &new_wallet)?;
+    }
+
+    let clock = Clock::get()?;
+    let config = &mut ctx.accounts.config;
+    let (old_wallet, execution_time) = config.propose_dev_wallet_update(index, new_wallet, clock.unix_timestamp)?;
+
+    emit_cpi!(DevWalletUpdateProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index,
+        old_wallet,
+        new_wallet,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::logic::validate_timelock;
+use crate::state::GlobalConfig;
+use crate::WALLET_CAP_RAW;
+
+#[derive(Accounts)]
+pub struct SetGlobalDefaults<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global_config.bump,
+        has_one = factory_admin @ HookError::UnauthorizedInitializer
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    pub factory_admin: Signer<'info>,
+}
+
+pub fn set_global_defaults(
+    ctx: Context<SetGlobalDefaults>,
+    timelock_seconds: u32,
+    min_cap_floor: u64,
+    max_cap_change_bps: u16,
+) -> Result<()> {
+    // What `initialize` would accept, so a default can always be initialized with
+    validate_timelock(timelock_seconds)?;
+    require!(min_cap_floor <= WALLET_CAP_RAW, HookError::CapBelowFloor);
+
+    let global_config = &mut ctx.accounts.global_config;
+    global_config.default_timelock_seconds = timelock_seconds;
+    global_config.default_min_cap_floor = min_cap_floor;
+    global_config.default_max_cap_change_bps = max_cap_change_bps;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::GovernanceMembersUpdated;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+use crate::MAX_GOVERNANCE_MEMBERS;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetGovernanceMembers<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_governance_members(
+    ctx: Context<SetGovernanceMembers>,
+    members: [Pubkey; MAX_GOVERNANCE_MEMBERS],
+    threshold: u8,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetGovernanceMembers { members, threshold },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    config.set_governance_members(members, threshold)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(GovernanceMembersUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        members,
+        threshold,
+        epoch: config.governance_epoch,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::MemoThresholdUpdated;
+use crate::state::{ConfigExtension, GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetMemoRequiredAbove<'info> {
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config-ext", mint.key().as_ref()], bump)]
+    pub config_extension: Account<'info, ConfigExtension>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_memo_required_above(ctx: Context<SetMemoRequiredAbove>, threshold: Option<u64>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetMemoRequiredAbove { threshold },
+    )?;
+
+    let extension = &mut ctx.accounts.config_extension;
+    let old_threshold = extension.memo_required_above;
+    extension.memo_required_above = threshold;
+
+    let clock = Clock::get()?;
+    emit_cpi!(MemoThresholdUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_threshold,
+        new_threshold: threshold,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
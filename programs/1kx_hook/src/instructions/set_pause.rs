@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::PauseUpdated;
+use crate::state::{GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    /// Governance authority, or the guardian when pausing
+    pub authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_pause(ctx: Context<SetPause>, paused: bool) -> Result<()> {
+    ctx.accounts.config.authorize_pause(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        paused,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    if paused {
+        config.paused_until = i64::MAX;
+    } else {
+        config.reset_circuit_breaker(clock.slot);
+    }
+
+    emit_cpi!(PauseUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        paused,
+        updated_at: clock.unix_timestamp,
+        authority: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::PermitSignerUpdated;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPermitSigner<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_permit_signer(
+    ctx: Context<SetPermitSigner>,
+    permit_signer: Pubkey,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetPermitSigner { permit_signer },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let old_signer = config.permit_signer;
+    config.permit_signer = permit_signer;
+
+    let clock = Clock::get()?;
+    emit_cpi!(PermitSignerUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_signer,
+        new_signer: permit_signer,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::ProposalExpiryUpdated;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetProposalExpiry<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_proposal_expiry(ctx: Context<SetProposalExpiry>, expiry_seconds: u32) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetProposalExpiry { expiry_seconds },
+    )?;
+
+    require!(expiry_seconds > 0, HookError::InvalidProposalExpiry);
+
+    let config = &mut ctx.accounts.config;
+    let old_expiry_seconds = config.proposal_expiry_seconds;
+    config.proposal_expiry_seconds = expiry_seconds;
+
+    let clock = Clock::get()?;
+    emit_cpi!(ProposalExpiryUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_expiry_seconds,
+        new_expiry_seconds: expiry_seconds,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
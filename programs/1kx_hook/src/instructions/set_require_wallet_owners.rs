@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::WalletOwnersRequiredUpdated;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetRequireWalletOwners<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_require_wallet_owners(ctx: Context<SetRequireWalletOwners>, enabled: bool) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetRequireWalletOwners { enabled },
+    )?;
+
+    ctx.accounts.config.require_wallet_owners = enabled;
+
+    let clock = Clock::get()?;
+    emit_cpi!(WalletOwnersRequiredUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        enabled,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
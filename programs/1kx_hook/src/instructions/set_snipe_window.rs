@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::SnipeWindowUpdated;
+use crate::state::{ConfigExtension, GovernanceAction, GovernanceApproval, HookConfig};
+use crate::MAX_SNIPE_WINDOW_SECONDS;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetSnipeWindow<'info> {
+    #[account(
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"config-ext", mint.key().as_ref()], bump)]
+    pub config_extension: Account<'info, ConfigExtension>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_snipe_window(ctx: Context<SetSnipeWindow>, window_seconds: u32) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetSnipeWindow { window_seconds },
+    )?;
+    require!(window_seconds <= MAX_SNIPE_WINDOW_SECONDS, HookError::InvalidSnipeWindow);
+
+    let extension = &mut ctx.accounts.config_extension;
+    let old_window_seconds = extension.snipe_window_seconds;
+    extension.snipe_window_seconds = window_seconds;
+
+    let clock = Clock::get()?;
+    emit_cpi!(SnipeWindowUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_window_seconds,
+        new_window_seconds: window_seconds,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
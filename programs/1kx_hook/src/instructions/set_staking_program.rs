@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::StakingProgramUpdated;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetStakingProgram<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_staking_program(ctx: Context<SetStakingProgram>, index: u8, program_id: Pubkey) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetStakingProgram { index, program_id },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    require!(usize::from(index) < config.trusted_programs.len(), HookError::InvalidTrustedProgramSlot);
+    let old_program = config.staking_program().unwrap_or_default();
+
+    // The staking program is a trusted program with a marked slot: its PDAs get the same destination
+    // exemption as pool authorities, and moving it to another slot takes it out of the old one
+    if let Some(previous) = config.staking_program_slot.checked_sub(1) {
+        config.trusted_programs[usize::from(previous)] = Pubkey::default();
+    }
+    config.trusted_programs[usize::from(index)] = program_id;
+    config.staking_program_slot = if program_id == Pubkey::default() { 0 } else { index + 1 };
+
+    let clock = Clock::get()?;
+    emit_cpi!(StakingProgramUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index,
+        old_program,
+        new_program: program_id,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
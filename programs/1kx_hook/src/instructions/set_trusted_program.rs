@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::TrustedProgramUpdated;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTrustedProgram<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+    
+    pub governance_authority: Signer<'info>,
+    
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+    
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn set_trusted_program(
+    ctx: Context<SetTrustedProgram>,
+    index: u8,
+    program_id: Pubkey,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::SetTrustedProgram { index, program_id },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let slot = config
+        .trusted_programs
+        .get_mut(index as usize)
+        .ok_or(HookError::InvalidTrustedProgramSlot)?;
+
+    let old_program = *slot;
+    *slot = program_id;
+    // Whatever takes the staking program's slot is a plain trusted program
+    if usize::from(config.staking_program_slot) == usize::from(index) + 1 {
+        config.staking_program_slot = 0;
+    }
+
+    let clock = Clock::get()?;
+    emit_cpi!(TrustedProgramUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index,
+        old_program,
+        new_program: program_id,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
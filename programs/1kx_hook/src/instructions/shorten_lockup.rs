@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::LockupShortened;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig, Lockup};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(owner: Pubkey)]
+pub struct ShortenLockup<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"lockup", mint.key().as_ref(), owner.as_ref()], bump)]
+    pub lockup: Account<'info, Lockup>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn shorten_lockup(
+    ctx: Context<ShortenLockup>,
+    owner: Pubkey,
+    locked_until: i64,
+    linear_release_end: Option<i64>,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ShortenLockup { owner, locked_until, linear_release_end },
+    )?;
+
+    let lockup = &mut ctx.accounts.lockup;
+    let (old_locked_until, old_linear_release_end) = (lockup.locked_until, lockup.linear_release_end);
+    lockup.shorten(locked_until, linear_release_end)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(LockupShortened {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        owner,
+        old_locked_until,
+        locked_until,
+        old_linear_release_end,
+        linear_release_end,
+        shortened_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::WalletCapUpdateExpired;
+use crate::state::{GovernanceLog, GovernanceLogAction, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepExpiredProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"gov-log", mint.key().as_ref()], bump)]
+    pub governance_log: Box<Account<'info, GovernanceLog>>,
+}
+
+pub fn sweep_expired_proposal(ctx: Context<SweepExpiredProposal>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    let expired_update = config.take_expired_cap_update(clock.unix_timestamp)?;
+    // Nobody has to sign a sweep, so there is no actor to record
+    ctx.accounts.governance_log.record(
+        GovernanceLogAction::Expired,
+        &expired_update,
+        clock.unix_timestamp,
+        Pubkey::default(),
+    );
+
+    emit_cpi!(WalletCapUpdateExpired {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        kind: expired_update.kind,
+        expired_cap: expired_update.new_cap,
+        current_cap: config.cap(expired_update.kind),
+        expires_at: expired_update.expires_at,
+        swept_at: clock.unix_timestamp,
+        proposal_nonce: expired_update.nonce,
+        memo_hash: expired_update.memo_hash,
+    });
+
+    Ok(())
+}
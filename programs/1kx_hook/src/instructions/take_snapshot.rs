@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::errors::HookError;
+use crate::events::SnapshotTaken;
+use crate::logic::mint_supply;
+use crate::state::{HookConfig, Snapshot};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TakeSnapshot<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Snapshot::SPACE,
+        seeds = [b"snapshot", mint.key().as_ref(), &config.snapshot_count.to_le_bytes()],
+        bump
+    )]
+    pub snapshot: Account<'info, Snapshot>,
+
+    /// CHECK: The hooked mint; its supply is read in the handler
+    #[account(owner = TOKEN_2022_PROGRAM_ID @ HookError::InvalidAccountOwner)]
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn take_snapshot(ctx: Context<TakeSnapshot>) -> Result<()> {
+    let clock = Clock::get()?;
+    let supply = mint_supply(&ctx.accounts.mint.try_borrow_data()?)?;
+
+    let config = &mut ctx.accounts.config;
+    let index = config.claim_snapshot(clock.slot)?;
+
+    let snapshot = &mut ctx.accounts.snapshot;
+    snapshot.mint = ctx.accounts.mint.key();
+    snapshot.index = index;
+    snapshot.slot = clock.slot;
+    snapshot.unix_timestamp = clock.unix_timestamp;
+    snapshot.supply = supply;
+    snapshot.wallet_cap_raw = config.wallet_cap_raw;
+    snapshot.cap_unit = config.cap_unit;
+    snapshot.paused = config.is_paused(clock.unix_timestamp);
+    snapshot.holder_count = config.holder_count;
+    snapshot.total_transfers = config.total_transfers;
+    snapshot.total_volume = config.total_volume;
+    snapshot.payer = ctx.accounts.payer.key();
+
+    emit_cpi!(SnapshotTaken {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        index,
+        supply,
+        wallet_cap_raw: snapshot.wallet_cap_raw,
+        paused: snapshot.paused,
+        taken_at: clock.unix_timestamp,
+        payer: snapshot.payer,
+    });
+
+    Ok(())
+}
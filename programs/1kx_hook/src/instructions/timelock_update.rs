@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{TimelockUpdateCanceled, TimelockUpdateProposed, TimelockUpdated};
+use crate::logic::validate_timelock;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig, PendingTimelockUpdate};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TimelockUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn propose_timelock_update(ctx: Context<TimelockUpdate>, timelock_seconds: u32) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ProposeTimelockUpdate { timelock_seconds },
+    )?;
+
+    validate_timelock(timelock_seconds)?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    let execution_time = config.timelock_execution_time(clock.unix_timestamp)?;
+
+    config.pending_timelock_update = Some(PendingTimelockUpdate {
+        timelock_seconds,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+    });
+
+    emit_cpi!(TimelockUpdateProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_timelock_seconds: config.timelock_seconds,
+        new_timelock_seconds: timelock_seconds,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn execute_timelock_update(ctx: Context<TimelockUpdate>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ExecuteTimelockUpdate,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    let (old_timelock_seconds, new_timelock_seconds) = config.apply_pending_timelock_update(clock.unix_timestamp)?;
+
+    emit_cpi!(TimelockUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_timelock_seconds,
+        new_timelock_seconds,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn cancel_timelock_update(ctx: Context<TimelockUpdate>) -> Result<()> {
+    if !ctx.accounts.config.is_veto_authority(&ctx.accounts.governance_authority.key()) {
+        ctx.accounts.config.authorize(
+            &ctx.accounts.governance_authority.key(),
+            ctx.accounts.approval.as_deref(),
+            &ctx.accounts.mint.key(),
+            &GovernanceAction::CancelTimelockUpdate,
+        )?;
+    }
+
+    let config = &mut ctx.accounts.config;
+    let canceled_update = config
+        .pending_timelock_update
+        .take()
+        .ok_or(HookError::NoPendingUpdate)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(TimelockUpdateCanceled {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        timelock_seconds: config.timelock_seconds,
+        canceled_timelock_seconds: canceled_update.timelock_seconds,
+        canceled_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
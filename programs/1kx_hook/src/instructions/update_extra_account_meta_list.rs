@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_type_length_value::state::TlvStateBorrowed;
+
+use crate::errors::HookError;
+use crate::events::ExtraAccountMetasUpdated;
+use crate::meta_list::{extra_account_meta_list_size, extra_account_metas};
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+use super::shrink_account;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// Funds the rent for a longer list and receives the surplus of a shorter one
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Extra account meta list PDA; its TLV data is rewritten in the handler
+    #[account(mut, owner = crate::ID, seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn update_extra_account_meta_list(ctx: Context<UpdateExtraAccountMetaList>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::UpdateExtraAccountMetaList,
+    )?;
+
+    let list = ctx.accounts.extra_account_meta_list.to_account_info();
+    let old_count = {
+        let data = list.try_borrow_data()?;
+        let state = TlvStateBorrowed::unpack(&data)?;
+        ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&state)?.data().len()
+    };
+    let account_metas = extra_account_metas()?;
+    let account_size = extra_account_meta_list_size()?;
+
+    // A longer list needs the account grown (and its rent topped up) before it is rewritten
+    if account_size > list.data_len() {
+        let shortfall = Rent::get()?.minimum_balance(account_size).saturating_sub(list.lamports());
+        if shortfall > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: list.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        list.realloc(account_size, true)?;
+    }
+    ExtraAccountMetaList::update::<ExecuteInstruction>(&mut list.try_borrow_mut_data()?, &account_metas)?;
+    // A shorter one leaves a tail to trim, whose rent goes back to the payer
+    shrink_account(&list, &ctx.accounts.payer, account_size)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(ExtraAccountMetasUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_count: old_count as u32,
+        new_count: account_metas.len() as u32,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
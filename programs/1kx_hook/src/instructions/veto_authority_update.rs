@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::{VetoAuthorityUpdateCanceled, VetoAuthorityUpdateProposed, VetoAuthorityUpdated};
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig, PendingVetoAuthorityUpdate};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VetoAuthorityUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn propose_veto_authority_update(
+    ctx: Context<VetoAuthorityUpdate>,
+    veto_authority: Option<Pubkey>,
+) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ProposeVetoAuthorityUpdate { veto_authority },
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    let execution_time = config.timelock_execution_time(clock.unix_timestamp)?;
+
+    config.pending_veto_authority_update = Some(PendingVetoAuthorityUpdate {
+        veto_authority,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+    });
+
+    emit_cpi!(VetoAuthorityUpdateProposed {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_veto_authority: config.veto_authority,
+        new_veto_authority: veto_authority,
+        proposed_at: clock.unix_timestamp,
+        execution_time,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn execute_veto_authority_update(ctx: Context<VetoAuthorityUpdate>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::ExecuteVetoAuthorityUpdate,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+    let (old_veto_authority, new_veto_authority) =
+        config.apply_pending_veto_authority_update(clock.unix_timestamp)?;
+
+    emit_cpi!(VetoAuthorityUpdated {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        old_veto_authority,
+        new_veto_authority,
+        updated_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
+
+pub fn cancel_veto_authority_update(ctx: Context<VetoAuthorityUpdate>) -> Result<()> {
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::CancelVetoAuthorityUpdate,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let canceled_update = config
+        .pending_veto_authority_update
+        .take()
+        .ok_or(HookError::NoPendingUpdate)?;
+
+    let clock = Clock::get()?;
+    emit_cpi!(VetoAuthorityUpdateCanceled {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        veto_authority: config.veto_authority,
+        canceled_veto_authority: canceled_update.veto_authority,
+        canceled_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
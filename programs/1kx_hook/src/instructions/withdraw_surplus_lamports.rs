@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::HookError;
+use crate::events::SurplusLamportsWithdrawn;
+use super::shrink_account;
+use crate::state::{GovernanceAction, GovernanceApproval, HookConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawSurplusLamports<'info> {
+    #[account(
+        mut,
+        seeds = [b"config", mint.key().as_ref()],
+        bump,
+        constraint = config.is_supported_version() @ HookError::UnsupportedVersion,
+        constraint = !config.frozen @ HookError::ConfigFrozen,
+        constraint = !config.governance_renounced @ HookError::GovernanceRenounced
+    )]
+    pub config: Account<'info, HookConfig>,
+
+    pub governance_authority: Signer<'info>,
+
+    /// CHECK: Extra account meta list PDA; only lamports above its rent exemption are moved
+    #[account(mut, owner = crate::ID, seeds = [b"extra-account-metas", mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the surplus; any account governance chooses
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Mint account for seed derivation
+    pub mint: UncheckedAccount<'info>,
+
+    /// Threshold approval of this exact call; stands in for the authority's signature under multisig governance
+    pub approval: Option<Account<'info, GovernanceApproval>>,
+}
+
+pub fn withdraw_surplus_lamports(ctx: Context<WithdrawSurplusLamports>) -> Result<()> {
+    let recipient = ctx.accounts.recipient.key();
+    ctx.accounts.config.authorize(
+        &ctx.accounts.governance_authority.key(),
+        ctx.accounts.approval.as_deref(),
+        &ctx.accounts.mint.key(),
+        &GovernanceAction::WithdrawSurplusLamports { recipient },
+    )?;
+
+    // Both accounts are program-owned, so the lamports move without a system program transfer. Sizes
+    // are left as they are; only what was sent on top of the rent exemption goes.
+    let recipient_info = ctx.accounts.recipient.to_account_info();
+    let config = ctx.accounts.config.to_account_info();
+    let config_lamports = shrink_account(&config, &recipient_info, config.data_len())?;
+    let list = ctx.accounts.extra_account_meta_list.to_account_info();
+    let extra_account_meta_list_lamports = shrink_account(&list, &recipient_info, list.data_len())?;
+
+    // Nothing sent to either account: succeed without an event
+    if config_lamports == 0 && extra_account_meta_list_lamports == 0 {
+        return Ok(());
+    }
+
+    let clock = Clock::get()?;
+    emit_cpi!(SurplusLamportsWithdrawn {
+        mint: ctx.accounts.mint.key(),
+        slot: clock.slot,
+        recipient,
+        config_lamports,
+        extra_account_meta_list_lamports,
+        withdrawn_at: clock.unix_timestamp,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+
+    Ok(())
+}
@@ -1,754 +1,741 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_2022::{Token2022, ID as TOKEN_2022_PROGRAM_ID};
-use spl_transfer_hook_interface::instruction::ExecuteInstruction;
-use spl_tlv_account_resolution::{
-    account::ExtraAccountMeta,
-    seeds::Seed,
-    state::ExtraAccountMetaList,
-};
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+
+/// `msg!` for the transfer hook path, which runs on every transfer of the mint: the log, arguments and
+/// all, is only compiled into builds with the `debug-logs` feature. Governance instructions keep logging
+/// with `msg!`.
+macro_rules! hook_log {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "debug-logs")]
+        anchor_lang::prelude::msg!($($arg)*);
+    }};
+}
+
+mod enforcement;
+pub mod errors;
+pub mod events;
+pub mod instructions;
+pub mod logic;
+mod meta_list;
+pub mod params;
+pub mod state;
+
+pub use errors::*;
+pub use events::*;
+pub use instructions::*;
+pub use state::*;
+
+// The build-time time parameters, where the rest of the program reaches its constants
+use params::{DEFAULT_PROPOSAL_EXPIRY_SECONDS, HOOK_DISABLE_TIMELOCK_SECONDS, MIN_TIMELOCK_SECONDS, TIMELOCK_DURATION};
+
+// The receiving side of the transfer check, for off-chain preflights linking the program without its
+// entrypoint: the same code `can_receive` and `execute` decide receives with
+#[cfg(feature = "no-entrypoint")]
+pub use enforcement::{preflight, Receipt};
 
 declare_id!("HU8xgmKfWv16e77BX6DEDBCXv8wmdxhYH5TPTSEGu4E2");
 
-const WALLET_CAP_RAW: u64 = 5_000_000_000; // 5 tokens with 9 decimals (0.5% of 1000 supply)
+/// Layout version of the program's events, raised whenever their fields change; published in the IDL's
+/// constants so decoders can tell layouts apart. Version 2 starts every event with `mint` and `slot`.
+#[constant]
+pub const EVENT_SCHEMA_VERSION: u8 = 2;
+
+// The wallet cap every config starts at; public for clients that set up mints with another one
+pub const WALLET_CAP_RAW: u64 = 5_000_000_000; // 5 tokens with 9 decimals (0.5% of 1000 supply)
+
+// Current HookConfig layout version written by initialize and targeted by migrate_config. Version 3 keeps
+// the version 2 layout; migrating to it marks configs from before finalize_setup as finalized
+const CONFIG_VERSION: u8 = 3;
+
+// Newest config layout this build can read; configs written by a newer program are refused rather than misread
+const SUPPORTED_CONFIG_VERSION: u8 = CONFIG_VERSION;
+
+// Layout version of the ConfigView returned by get_config, independent of the account layout
+const CONFIG_VIEW_VERSION: u8 = 5;
+
+// Number of dev wallet slots exempt from the cap (treasury, marketing, liquidity, ...)
+const MAX_DEV_WALLETS: usize = 4;
+
+// Maximum number of entries in the exempt registry (bounds the hook's lookup cost)
+const MAX_EXEMPT_ENTRIES: usize = 16;
+
+// Number of AMM program ids whose PDAs are auto-exempt as destination owners
+const MAX_TRUSTED_PROGRAMS: usize = 4;
+
+// Domain prefix of the messages signed for over-cap permits
+const PERMIT_MESSAGE_PREFIX: &[u8] = b"1kx-hook-permit";
+
+// Size of the UsedNonces bitmap; permits can use nonces 0..USED_NONCES_BITMAP_LEN * 8
+const USED_NONCES_BITMAP_LEN: usize = 256;
 
-// Space calculation for ExtraAccountMetaList with 1 account
-// Being generous with space allocation to ensure sufficient room
-const EXTRA_ACCOUNT_META_LIST_SIZE: usize = 128;
+// Maximum number of multisig governance members
+const MAX_GOVERNANCE_MEMBERS: usize = 5;
+
+// Number of most recent cap governance actions the governance log keeps
+const GOVERNANCE_LOG_LEN: usize = 32;
+
+// Entries per get_governance_log page; a whole log would overflow the 1024 byte return data limit
+const GOVERNANCE_LOG_PAGE_LEN: usize = 16;
+
+// Domain separator for the hash multisig approvals commit to
+const GOVERNANCE_ACTION_PREFIX: &[u8] = b"1kx-hook-governance-action";
+
+// Smallest non-zero sell limit governance may propose: 0.05% of the 1000 token supply
+const MIN_SELL_LIMIT_RAW: u64 = 500_000_000;
+
+// Fewest slots between two snapshots (about an hour at 400ms slots), so anyone can take them without
+// flooding the chain with snapshot accounts
+const SNAPSHOT_INTERVAL_SLOTS: u64 = 9_000;
+
+// Bytes left at the end of HookConfig for fields added later
+const HOOK_CONFIG_RESERVED_LEN: usize = 15;
+
+// Bytes left at the end of ConfigExtension for fields added later
+const CONFIG_EXTENSION_RESERVED_LEN: usize = 90;
+
+// Owners a SniperLog lists; the log is allocated in full up front, so the hook never has to grow it
+const SNIPER_LOG_LEN: usize = 64;
+
+// Longest snipe window governance can set, measured from the detected launch
+const MAX_SNIPE_WINDOW_SECONDS: u32 = 86_400;
+
+// Length of the window the dev wallets' daily outflow limit counts their sells into pool vaults over
+const DEV_OUTFLOW_WINDOW_SECONDS: i64 = 24 * 60 * 60;
+
+// SPL Memo program ids, current and original; either one's instruction counts as a transfer's memo
+const MEMO_PROGRAM_IDS: [Pubkey; 2] = [
+    anchor_lang::solana_program::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"),
+    anchor_lang::solana_program::pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo"),
+];
+
+// Mints per MintRegistry page; a full page is allocated up front, so this bounds its rent
+const MINTS_PER_REGISTRY_PAGE: usize = 64;
 
 #[program]
 pub mod one_kx_hook {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, dev_wallet: Pubkey, governance_authority: Pubkey) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        config.version = 1;
-        config.dev_wallet = dev_wallet;
-        config.wallet_cap_raw = WALLET_CAP_RAW;
-        config.governance_authority = governance_authority;
-        config.pending_cap_update = None;
-        Ok(())
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        dev_wallet: Pubkey,
+        governance_authority: Pubkey,
+        cap_unit: CapUnit,
+        timelock_seconds: u32,
+        min_cap_floor: u64,
+        max_cap_change_bps: u16,
+        strict_authorities: bool,
+        allow_confidential: bool,
+    ) -> Result<()> {
+        instructions::initialize(
+            ctx,
+            dev_wallet,
+            governance_authority,
+            cap_unit,
+            timelock_seconds,
+            min_cap_floor,
+            max_cap_change_bps,
+            strict_authorities,
+            allow_confidential,
+        )
+    }
+
+    /// Create the singleton `GlobalConfig` naming the factory admin (program upgrade authority only)
+    pub fn init_global_config(ctx: Context<InitGlobalConfig>, factory_admin: Pubkey) -> Result<()> {
+        instructions::init_global_config(ctx, factory_admin)
+    }
+
+    /// Create the `MintRegistry` page the next mint registered through `initialize_for_mint` goes on, once
+    /// the previous page is full (permissionless; the payer funds it)
+    pub fn init_mint_registry_page(ctx: Context<InitMintRegistryPage>) -> Result<()> {
+        instructions::init_mint_registry_page(ctx)
+    }
+
+    /// Set the timelock, cap floor and swing limit `initialize_for_mint` defaults to (factory admin only)
+    pub fn set_global_defaults(
+        ctx: Context<SetGlobalDefaults>,
+        timelock_seconds: u32,
+        min_cap_floor: u64,
+        max_cap_change_bps: u16,
+    ) -> Result<()> {
+        instructions::set_global_defaults(ctx, timelock_seconds, min_cap_floor, max_cap_change_bps)
+    }
+
+    /// `initialize`, signed by either the mint authority or the factory admin, recording the mint with its
+    /// creator and slot in the `MintRegistry`. The timelock, cap floor and swing limit not given are the
+    /// global defaults
+    pub fn initialize_for_mint(
+        ctx: Context<InitializeForMint>,
+        dev_wallet: Pubkey,
+        governance_authority: Pubkey,
+        cap_unit: CapUnit,
+        timelock_seconds: Option<u32>,
+        min_cap_floor: Option<u64>,
+        max_cap_change_bps: Option<u16>,
+        strict_authorities: bool,
+        allow_confidential: bool,
+    ) -> Result<()> {
+        instructions::initialize_for_mint(
+            ctx,
+            dev_wallet,
+            governance_authority,
+            cap_unit,
+            timelock_seconds,
+            min_cap_floor,
+            max_cap_change_bps,
+            strict_authorities,
+            allow_confidential,
+        )
     }
 
     pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
-        // Validate account ownership
-        require!(ctx.accounts.source.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
-        require!(ctx.accounts.destination.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
-        require!(ctx.accounts.mint.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
-        
-        let config = &ctx.accounts.config;
-        
-        // Parse destination token account
-        let destination_data = ctx.accounts.destination.try_borrow_data()?;
-        let destination_account = spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Account>::unpack(&destination_data)?;
-        
-        // Check if destination is dev wallet (exempt from cap)
-        let destination_owner = destination_account.base.owner;
-        if destination_owner == config.dev_wallet {
-            return Ok(()); // Dev wallet exempt from cap restrictions
-        }
-        
-        // Calculate post-transfer balance
-        let post_balance = destination_account.base.amount.saturating_add(amount);
-        
-        // Enforce wallet cap for non-dev wallets
-        require!(
-            post_balance <= config.wallet_cap_raw,
-            HookError::WalletCapExceeded
-        );
-        
-        Ok(())
+        instructions::transfer_hook(ctx, amount)
     }
 
     // Required for SPL Transfer Hook Interface
     pub fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
-        // Validate account ownership
-        require!(ctx.accounts.source.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
-        require!(ctx.accounts.destination.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
-        require!(ctx.accounts.mint.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
-        
-        let destination = ctx.accounts.destination.clone(); 
-        let config = &ctx.accounts.config;
-        
-        // Parse destination token account
-        let destination_data = destination.try_borrow_data()?;
-        let destination_account = spl_token_2022::extension::StateWithExtensions::<spl_token_2022::state::Account>::unpack(&destination_data)?;
-        
-        // Check if destination is dev wallet (exempt from cap)
-        let destination_owner = destination_account.base.owner;
-        if destination_owner == config.dev_wallet {
-            return Ok(()); // Dev wallet exempt from cap restrictions
-        }
-        
-        // Calculate post-transfer balance
-        let post_balance = destination_account.base.amount.saturating_add(amount);
-        
-        // Enforce wallet cap for non-dev wallets
-        require!(
-            post_balance <= config.wallet_cap_raw,
-            HookError::WalletCapExceeded
-        );
-        
-        Ok(())
+        instructions::execute(ctx, amount)
     }
 
     // Initialize extra account metas for the hook
     pub fn init_extra_account_meta_list(
         ctx: Context<InitExtraAccountMetaList>,
     ) -> Result<()> {
-        let account_metas = vec![
-            ExtraAccountMeta::new_with_seeds(
-                &[
-                    Seed::Literal { bytes: b"config".to_vec() },
-                    Seed::AccountKey { index: 0 }, // mint
-                ],
-                false, // is_signer
-                false, // is_writable
-            )?,
-        ];
-
-        let account_size = ExtraAccountMetaList::size_of(account_metas.len())?;
-        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
-        
-        // Provide detailed error information for debugging
-        msg!("Required account size: {}", account_size);
-        msg!("Allocated space: {}", data.len());
-        
-        require!(
-            data.len() >= account_size,
-            HookError::InsufficientAccountSpace
-        );
-        
-        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &account_metas)?;
-        
-        Ok(())
+        instructions::init_extra_account_meta_list(ctx)
+    }
+
+    /// Rewrite the extra account meta list with the accounts this program version resolves, resizing
+    /// the account to fit, so mints initialized by an older version keep working (governance authority only)
+    pub fn update_extra_account_meta_list(ctx: Context<UpdateExtraAccountMetaList>) -> Result<()> {
+        instructions::update_extra_account_meta_list(ctx)
+    }
+
+    /// Check that the mint's TransferHook extension points at this program and that its extra account
+    /// meta list holds the accounts `execute` expects, then let transfers through (permissionless).
+    /// Until this has run the hook refuses every transfer.
+    pub fn finalize_setup(ctx: Context<FinalizeSetup>) -> Result<()> {
+        instructions::finalize_setup(ctx)
+    }
+
+    /// Close the extra account meta list and send its rent to `recipient` (governance authority only).
+    /// Token-2022 can't resolve the hook's accounts without it, so the caller has to confirm the mint
+    /// no longer routes transfers through this hook.
+    pub fn close_extra_account_meta_list(
+        ctx: Context<CloseExtraAccountMetaList>,
+        confirm_mint_detached: bool,
+    ) -> Result<()> {
+        instructions::close_extra_account_meta_list(ctx, confirm_mint_detached)
+    }
+
+    /// Token-2022 and standard SPL tooling call the hook with the transfer hook interface's own
+    /// discriminators rather than Anchor sighashes, so those land here and are routed by hand
+    pub fn fallback<'info>(program_id: &Pubkey, accounts: &'info [AccountInfo<'info>], data: &[u8]) -> Result<()> {
+        match TransferHookInstruction::unpack(data)? {
+            // Same accounts as the Anchor `execute` instruction, whose arguments are just the amount
+            TransferHookInstruction::Execute { amount } => instructions::dispatch_execute(program_id, accounts, amount),
+            TransferHookInstruction::InitializeExtraAccountMetaList { extra_account_metas } => {
+                meta_list::initialize_spl_extra_account_meta_list(program_id, accounts, &extra_account_metas)
+            }
+            _ => Err(ProgramError::InvalidInstructionData.into()),
+        }
     }
 
     /// Propose a new wallet cap (timelock mechanism)
     pub fn propose_wallet_cap_update(
         ctx: Context<ProposeWalletCapUpdate>,
         new_cap: u64,
+        overwrite: bool,
+        memo_hash: [u8; 32],
     ) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        let clock = Clock::get()?;
-        
-        // Validate the new cap is reasonable
-        require!(new_cap > 0, HookError::InvalidWalletCap);
-        
-        // Maximum reasonable cap: 10% of expected total supply (1000 tokens)
-        let max_reasonable_cap = 100_000_000_000u64; // 100 tokens with 9 decimals
-        require!(new_cap <= max_reasonable_cap, HookError::InvalidWalletCap);
-        
-        // Set timelock period (48 hours)
-        let timelock_duration = 48 * 60 * 60; // 48 hours in seconds
-        let execution_time = clock.unix_timestamp + timelock_duration;
-        
-        config.pending_cap_update = Some(PendingCapUpdate {
-            new_cap,
-            proposed_at: clock.unix_timestamp,
-            execution_time,
-        });
-        
-        emit!(WalletCapUpdateProposed {
-            new_cap,
-            current_cap: config.wallet_cap_raw,
-            proposed_at: clock.unix_timestamp,
-            execution_time,
-            governance_authority: ctx.accounts.governance_authority.key(),
-        });
-        
-        Ok(())
-    }
-
-    /// Execute a previously proposed wallet cap update (after timelock)
+        instructions::propose_cap_update(ctx, CapKind::Wallet, new_cap, overwrite, memo_hash)
+    }
+
+    /// Propose a new value for the wallet cap, buy cap or sell limit (timelock mechanism). A pending
+    /// cap proposal is only replaced with `overwrite`, which cancels it openly and restarts the timelock.
+    /// `memo_hash` is the client's hash of the off-chain rationale (e.g. the forum post) and is echoed
+    /// by every event in the proposal's lifecycle.
+    pub fn propose_cap_update(
+        ctx: Context<ProposeWalletCapUpdate>,
+        kind: CapKind,
+        new_cap: u64,
+        overwrite: bool,
+        memo_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::propose_cap_update(ctx, kind, new_cap, overwrite, memo_hash)
+    }
+
+    /// Execute a previously proposed cap update (after timelock). Governance committed to the change when
+    /// proposing it, so anyone can crank it once it matures unless an executor authority is set.
     pub fn execute_wallet_cap_update(ctx: Context<ExecuteWalletCapUpdate>) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        let clock = Clock::get()?;
-        
-        let pending_update = config.pending_cap_update
-            .as_ref()
-            .ok_or(HookError::NoPendingUpdate)?;
-        
-        // Check if timelock has expired
-        require!(
-            clock.unix_timestamp >= pending_update.execution_time,
-            HookError::TimelockNotExpired
-        );
-        
-        let old_cap = config.wallet_cap_raw;
-        config.wallet_cap_raw = pending_update.new_cap;
-        config.pending_cap_update = None;
-        
-        emit!(WalletCapUpdated {
-            old_cap,
-            new_cap: config.wallet_cap_raw,
-            updated_at: clock.unix_timestamp,
-            governance_authority: ctx.accounts.governance_authority.key(),
-        });
-        
-        Ok(())
-    }
-
-    /// Cancel a pending wallet cap update (governance authority only)
+        instructions::execute_wallet_cap_update(ctx)
+    }
+
+    /// Raise the wallet cap right away (governance authority only). Raising the cap only loosens the
+    /// restriction on holders, so it skips the timelock; lowering it still goes through propose/execute.
+    pub fn execute_immediate_cap_increase(ctx: Context<ImmediateCapIncrease>, new_cap: u64) -> Result<()> {
+        instructions::execute_immediate_cap_increase(ctx, new_cap)
+    }
+
+    /// Re-sync the timelock, cap floor and swing limit selected by `fields` (`GlobalConfig::ADOPT_*` bits)
+    /// from the global defaults, within the rules of each: the floor only rises, the swing limit only
+    /// tightens and the timelock is proposed rather than set (governance authority only)
+    pub fn adopt_global_defaults(ctx: Context<AdoptGlobalDefaults>, fields: u8) -> Result<()> {
+        instructions::adopt_global_defaults(ctx, fields)
+    }
+
+    /// Raise the lowest wallet cap governance may set; the floor can never be lowered again
+    /// (governance authority only)
+    pub fn raise_min_cap_floor(ctx: Context<RaiseMinCapFloor>, min_cap_floor: u64) -> Result<()> {
+        instructions::raise_min_cap_floor(ctx, min_cap_floor)
+    }
+
+    /// Cancel a pending cap update (proposer authority, veto authority or governance)
     pub fn cancel_wallet_cap_update(ctx: Context<CancelWalletCapUpdate>) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        let clock = Clock::get()?;
-        
-        require!(
-            config.pending_cap_update.is_some(),
-            HookError::NoPendingUpdate
-        );
-        
-        let canceled_update = config.pending_cap_update.take().unwrap();
-        
-        emit!(WalletCapUpdateCanceled {
-            canceled_cap: canceled_update.new_cap,
-            current_cap: config.wallet_cap_raw,
-            canceled_at: clock.unix_timestamp,
-            governance_authority: ctx.accounts.governance_authority.key(),
-        });
-        
-        Ok(())
-    }
-
-    /// Update governance authority (requires current governance authority)
-    pub fn update_governance_authority(
-        ctx: Context<UpdateGovernanceAuthority>,
-        new_governance_authority: Pubkey,
+        instructions::cancel_wallet_cap_update(ctx)
+    }
+
+    /// Clear a cap proposal that was never executed within its window. Permissionless, so anyone can
+    /// take a forgotten proposal off the table.
+    pub fn sweep_expired_proposal(ctx: Context<SweepExpiredProposal>) -> Result<()> {
+        instructions::sweep_expired_proposal(ctx)
+    }
+
+    /// Create the cap governance log for a mint. Permissionless: the log only ever holds what the cap
+    /// instructions write to it.
+    pub fn init_governance_log(ctx: Context<InitGovernanceLog>) -> Result<()> {
+        instructions::init_governance_log(ctx)
+    }
+
+    /// Create the mint's config extension, where the hook records the launch. Permissionless: the
+    /// extension only ever holds what the hook and governance write to it.
+    pub fn init_config_extension(ctx: Context<InitConfigExtension>) -> Result<()> {
+        instructions::init_config_extension(ctx)
+    }
+
+    /// Create the mint's sniper log at its full size, so the hook can list snipers without paying rent.
+    /// Permissionless: only the hook and governance write to it.
+    pub fn init_sniper_log(ctx: Context<InitSniperLog>) -> Result<()> {
+        instructions::init_sniper_log(ctx)
+    }
+
+    /// Return one page of the governance log, oldest first, via return data. Page 0 starts at the
+    /// oldest retained entry; pages past the end are empty.
+    pub fn get_governance_log(ctx: Context<GetGovernanceLog>, page: u8) -> Result<()> {
+        instructions::get_governance_log(ctx, page)
+    }
+
+    /// Return the caps, dev wallets, pending cap proposal and pause state as a `ConfigView` via return
+    /// data. Anyone can call it, and it reads v1 configs that haven't been migrated yet.
+    pub fn get_config(ctx: Context<GetConfig>) -> Result<()> {
+        instructions::get_config(ctx)
+    }
+
+    /// Report whether `destination` could receive `amount` right now, as a `CanReceiveResult` via
+    /// return data, so wallets can warn before signing a transfer the cap would reject. It applies the
+    /// same exemptions and caps as `execute` to a wallet-to-wallet transfer and never writes state. The
+    /// destination owner's `TrustedOwner` record goes in the first remaining account, if it has one.
+    pub fn can_receive(ctx: Context<CanReceive>, amount: u64) -> Result<()> {
+        instructions::can_receive(ctx, amount)
+    }
+
+    /// `can_receive` for other programs to CPI into: the verdict as a two-byte `CheckTransferResult`
+    /// via return data, so a staking or escrow program can tell a release would bounce off the cap
+    /// before making it. A rejection is a verdict, never an error; accounts that don't check out are.
+    pub fn check_transfer(ctx: Context<CheckTransfer>, amount: u64) -> Result<()> {
+        instructions::check_transfer(ctx, amount)
+    }
+
+    /// Set how long matured cap proposals stay executable; applies to proposals made from now on
+    /// (governance authority only)
+    pub fn set_proposal_expiry(ctx: Context<SetProposalExpiry>, expiry_seconds: u32) -> Result<()> {
+        instructions::set_proposal_expiry(ctx, expiry_seconds)
+    }
+
+    /// Reassign who may propose or execute cap updates (governance authority only)
+    pub fn set_cap_update_role(ctx: Context<SetCapUpdateRole>, role: CapUpdateRole, authority: Pubkey) -> Result<()> {
+        instructions::set_cap_update_role(ctx, role, authority)
+    }
+
+    /// Propose setting (Some) or clearing (None) the veto authority. The change waits out the timelock,
+    /// so governance can't drop the veto right before pushing a change through.
+    pub fn propose_veto_authority_update(
+        ctx: Context<VetoAuthorityUpdate>,
+        veto_authority: Option<Pubkey>,
     ) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        let old_authority = config.governance_authority;
-        
-        config.governance_authority = new_governance_authority;
-        
-        emit!(GovernanceAuthorityUpdated {
-            old_authority,
-            new_authority: new_governance_authority,
-            updated_at: Clock::get()?.unix_timestamp,
-        });
-        
-        Ok(())
-    }
-
-    /// Migrate HookConfig to a newer version
-    pub fn migrate_config(ctx: Context<MigrateConfig>, target_version: u8) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        let current_version = config.version;
-        
-        require!(target_version > current_version, HookError::InvalidMigrationVersion);
-        require!(target_version <= 1, HookError::UnsupportedVersion); // Update this as new versions are added
-        
-        // Version-specific migration logic
-        match (current_version, target_version) {
-            // Future migrations will be handled here
-            // Example: (1, 2) => { /* migrate from v1 to v2 */ },
-            _ => return Err(HookError::UnsupportedMigration.into()),
-        }
-        
-        config.version = target_version;
-        
-        emit!(ConfigMigrated {
-            old_version: current_version,
-            new_version: target_version,
-            migrated_at: Clock::get()?.unix_timestamp,
-            governance_authority: ctx.accounts.governance_authority.key(),
-        });
-        
-        Ok(())
+        instructions::propose_veto_authority_update(ctx, veto_authority)
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + 1 + 32 + 8 + 32 + 1 + (1 + 8 + 8 + 8), // discriminator + version + dev_wallet + wallet_cap_raw + governance_authority + Option<PendingCapUpdate>
-        seeds = [b"config", mint.key().as_ref()],
-        bump
-    )]
-    pub config: Account<'info, HookConfig>,
-    
-    /// CHECK: Mint account
-    pub mint: UncheckedAccount<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+    /// Execute a previously proposed veto authority change (after timelock)
+    pub fn execute_veto_authority_update(ctx: Context<VetoAuthorityUpdate>) -> Result<()> {
+        instructions::execute_veto_authority_update(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct TransferHook<'info> {
-    /// CHECK: Source token account
-    pub source: UncheckedAccount<'info>,
-    
-    /// CHECK: Mint
-    pub mint: UncheckedAccount<'info>,
-    
-    /// CHECK: Destination token account
-    pub destination: UncheckedAccount<'info>,
-    
-    /// CHECK: Owner of source
-    pub owner: UncheckedAccount<'info>,
-    
-    /// CHECK: Extra accounts
-    pub extra_account_meta_list: UncheckedAccount<'info>,
-    
-    #[account(seeds = [b"config", mint.key().as_ref()], bump)]
-    pub config: Account<'info, HookConfig>,
-    
-    pub token_program: Program<'info, Token2022>,
-}
+    /// Cancel a pending veto authority change (governance authority only; the veto authority can't
+    /// block its own replacement)
+    pub fn cancel_veto_authority_update(ctx: Context<VetoAuthorityUpdate>) -> Result<()> {
+        instructions::cancel_veto_authority_update(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct Execute<'info> {
-    /// CHECK: Source
-    pub source: UncheckedAccount<'info>,
-    
-    /// CHECK: Mint  
-    pub mint: UncheckedAccount<'info>,
-    
-    /// CHECK: Destination
-    pub destination: UncheckedAccount<'info>,
-    
-    /// CHECK: Owner
-    pub owner: UncheckedAccount<'info>,
-    
-    /// CHECK: Extra account meta list
-    pub extra_account_meta_list: UncheckedAccount<'info>,
-    
-    #[account(seeds = [b"config", mint.key().as_ref()], bump)]
-    pub config: Account<'info, HookConfig>,
-    
-    pub token_program: Program<'info, Token2022>,
-}
+    /// Propose a new governance authority (requires current governance authority).
+    /// Nothing changes until the proposed authority accepts; a new proposal replaces any pending one.
+    pub fn propose_governance_transfer(ctx: Context<GovernanceTransfer>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_governance_transfer(ctx, new_authority)
+    }
 
-#[derive(Accounts)]
-pub struct InitExtraAccountMetaList<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    
-    /// CHECK: Extra account meta list PDA
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + EXTRA_ACCOUNT_META_LIST_SIZE, // 8 bytes discriminator + calculated size
-        seeds = [b"extra-account-metas", mint.key().as_ref()],
-        bump
-    )]
-    pub extra_account_meta_list: UncheckedAccount<'info>,
-    
-    /// CHECK: Mint
-    pub mint: UncheckedAccount<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+    /// Accept a pending governance transfer (requires the proposed authority)
+    pub fn accept_governance_authority(ctx: Context<AcceptGovernanceAuthority>) -> Result<()> {
+        instructions::accept_governance_authority(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct ProposeWalletCapUpdate<'info> {
-    #[account(
-        mut,
-        seeds = [b"config", mint.key().as_ref()],
-        bump,
-        constraint = config.governance_authority == governance_authority.key() @ HookError::UnauthorizedGovernance
-    )]
-    pub config: Account<'info, HookConfig>,
-    
-    pub governance_authority: Signer<'info>,
-    
-    /// CHECK: Mint account for seed derivation
-    pub mint: UncheckedAccount<'info>,
-}
+    /// Cancel a pending governance transfer (requires current governance authority)
+    pub fn cancel_governance_transfer(ctx: Context<GovernanceTransfer>) -> Result<()> {
+        instructions::cancel_governance_transfer(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct ExecuteWalletCapUpdate<'info> {
-    #[account(
-        mut,
-        seeds = [b"config", mint.key().as_ref()],
-        bump,
-        constraint = config.governance_authority == governance_authority.key() @ HookError::UnauthorizedGovernance
-    )]
-    pub config: Account<'info, HookConfig>,
-    
-    pub governance_authority: Signer<'info>,
-    
-    /// CHECK: Mint account for seed derivation
-    pub mint: UncheckedAccount<'info>,
-}
+    /// Permanently give up governance (requires current governance authority). Pending updates are
+    /// dropped so nothing proposed earlier can still execute; every governance instruction fails afterwards.
+    pub fn renounce_governance(ctx: Context<GovernanceTransfer>) -> Result<()> {
+        instructions::renounce_governance(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct CancelWalletCapUpdate<'info> {
-    #[account(
-        mut,
-        seeds = [b"config", mint.key().as_ref()],
-        bump,
-        constraint = config.governance_authority == governance_authority.key() @ HookError::UnauthorizedGovernance
-    )]
-    pub config: Account<'info, HookConfig>,
-    
-    pub governance_authority: Signer<'info>,
-    
-    /// CHECK: Mint account for seed derivation
-    pub mint: UncheckedAccount<'info>,
-}
+    /// Make every parameter permanent (governance authority only). Unlike renouncing, this holds even
+    /// against migrate_config; pending updates are dropped and only the transfer hook keeps running.
+    pub fn freeze_config(ctx: Context<FreezeConfig>) -> Result<()> {
+        instructions::freeze_config(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct UpdateGovernanceAuthority<'info> {
-    #[account(
-        mut,
-        seeds = [b"config", mint.key().as_ref()],
-        bump,
-        constraint = config.governance_authority == governance_authority.key() @ HookError::UnauthorizedGovernance
-    )]
-    pub config: Account<'info, HookConfig>,
-    
-    pub governance_authority: Signer<'info>,
-    
-    /// CHECK: Mint account for seed derivation
-    pub mint: UncheckedAccount<'info>,
-}
+    /// Set the governance members and how many of them must approve each governance instruction. A zero
+    /// threshold hands control back to the single governance authority. Outstanding approvals become stale.
+    pub fn set_governance_members(
+        ctx: Context<SetGovernanceMembers>,
+        members: [Pubkey; MAX_GOVERNANCE_MEMBERS],
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::set_governance_members(ctx, members, threshold)
+    }
 
-#[derive(Accounts)]
-pub struct MigrateConfig<'info> {
-    #[account(
-        mut,
-        seeds = [b"config", mint.key().as_ref()],
-        bump,
-        constraint = config.governance_authority == governance_authority.key() @ HookError::UnauthorizedGovernance
-    )]
-    pub config: Account<'info, HookConfig>,
-    
-    pub governance_authority: Signer<'info>,
-    
-    /// CHECK: Mint account for seed derivation
-    pub mint: UncheckedAccount<'info>,
-}
+    /// Open a multisig approval for `action` in the current governance epoch, counting the proposing
+    /// member's approval (governance member only)
+    pub fn propose_action(ctx: Context<ProposeAction>, action: GovernanceAction) -> Result<()> {
+        instructions::propose_action(ctx, action)
+    }
 
-#[account]
-pub struct HookConfig {
-    pub version: u8,
-    pub dev_wallet: Pubkey,
-    pub wallet_cap_raw: u64,
-    pub governance_authority: Pubkey,
-    pub pending_cap_update: Option<PendingCapUpdate>,
-}
+    /// Add a governance member's approval to a proposed action (governance member only)
+    pub fn approve_action(ctx: Context<ApproveAction>, action_hash: [u8; 32]) -> Result<()> {
+        instructions::approve_action(ctx, action_hash)
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct PendingCapUpdate {
-    pub new_cap: u64,
-    pub proposed_at: i64,
-    pub execution_time: i64, // When the update can be executed (timelock)
-}
+    /// Close an approval that can no longer be used and refund its rent (permissionless crank)
+    pub fn close_governance_approval(ctx: Context<CloseGovernanceApproval>) -> Result<()> {
+        instructions::close_governance_approval(ctx)
+    }
 
-#[event]
-pub struct WalletCapUpdateProposed {
-    pub new_cap: u64,
-    pub current_cap: u64,
-    pub proposed_at: i64,
-    pub execution_time: i64,
-    pub governance_authority: Pubkey,
-}
+    /// Create the exempt registry for a mint (governance authority only)
+    pub fn init_exempt_registry(
+        ctx: Context<InitExemptRegistry>,
+        rent_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::init_exempt_registry(ctx, rent_recipient)
+    }
 
-#[event]
-pub struct WalletCapUpdated {
-    pub old_cap: u64,
-    pub new_cap: u64,
-    pub updated_at: i64,
-    pub governance_authority: Pubkey,
-}
+    /// Exempt a token account, optionally until `expires_at`; re-adding updates the kind and expiry
+    /// (governance authority only). Transfers out of `PoolVault` entries are treated as buys.
+    pub fn add_exempt_entry(
+        ctx: Context<AddExemptEntry>,
+        token_account: Pubkey,
+        kind: ExemptKind,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::add_exempt_entry(ctx, token_account, kind, expires_at)
+    }
 
-#[event]
-pub struct WalletCapUpdateCanceled {
-    pub canceled_cap: u64,
-    pub current_cap: u64,
-    pub canceled_at: i64,
-    pub governance_authority: Pubkey,
-}
+    /// Remove a token account from the exempt registry (governance authority only)
+    pub fn remove_exempt_entry(
+        ctx: Context<RemoveExemptEntry>,
+        token_account: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_exempt_entry(ctx, token_account)
+    }
 
-#[event]
-pub struct GovernanceAuthorityUpdated {
-    pub old_authority: Pubkey,
-    pub new_authority: Pubkey,
-    pub updated_at: i64,
-}
+    /// Drop expired exemptions and refund the freed rent (permissionless crank)
+    pub fn prune_exempt_registry(ctx: Context<PruneExemptRegistry>) -> Result<()> {
+        instructions::prune_exempt_registry(ctx)
+    }
 
-#[event]
-pub struct ConfigMigrated {
-    pub old_version: u8,
-    pub new_version: u8,
-    pub migrated_at: i64,
-    pub governance_authority: Pubkey,
-}
+    /// Set or clear (with `Pubkey::default()`) a trusted AMM program slot (governance authority only)
+    pub fn set_trusted_program(
+        ctx: Context<SetTrustedProgram>,
+        index: u8,
+        program_id: Pubkey,
+    ) -> Result<()> {
+        instructions::set_trusted_program(ctx, index, program_id)
+    }
 
-#[error_code]
-pub enum HookError {
-    #[msg("Wallet cap exceeded")]
-    WalletCapExceeded,
-    #[msg("Insufficient account space for ExtraAccountMetaList")]
-    InsufficientAccountSpace,
-    #[msg("Unauthorized governance operation")]
-    UnauthorizedGovernance,
-    #[msg("Invalid wallet cap value")]
-    InvalidWalletCap,
-    #[msg("No pending cap update")]
-    NoPendingUpdate,
-    #[msg("Timelock period has not expired")]
-    TimelockNotExpired,
-    #[msg("Invalid account owner")]
-    InvalidAccountOwner,
-    #[msg("Invalid migration version")]
-    InvalidMigrationVersion,
-    #[msg("Unsupported version")]
-    UnsupportedVersion,
-    #[msg("Unsupported migration path")]
-    UnsupportedMigration,
-}
+    /// Designate the staking program, putting it in trusted program slot `index` and taking any previous
+    /// one out of its slot, or clear it with `Pubkey::default()` (governance authority only)
+    pub fn set_staking_program(
+        ctx: Context<SetStakingProgram>,
+        index: u8,
+        program_id: Pubkey,
+    ) -> Result<()> {
+        instructions::set_staking_program(ctx, index, program_id)
+    }
 
-// Unit tests for core business logic
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Register a token account owned by a PDA account of the staking program, which `seeds` (bump included)
+    /// derive, as a `StakingVault` (governance authority only). Staking into it is exempt from the cap;
+    /// unstaking out of it is held to the wallet cap and fails with `UnstakeWouldExceedCap`.
+    pub fn register_staking_vault(ctx: Context<RegisterStakingVault>, seeds: Vec<Vec<u8>>) -> Result<()> {
+        instructions::register_staking_vault(ctx, seeds)
+    }
 
-    #[test]
-    fn test_wallet_cap_constants() {
-        // Test that wallet cap is correctly set to 5 tokens (5% of 1000 supply)
-        assert_eq!(WALLET_CAP_RAW, 5_000_000_000); // 5 tokens with 9 decimals
-        
-        // Test that cap is 0.5% of expected 1000 token supply
-        let total_supply = 1000_000_000_000u64; // 1000 tokens with 9 decimals
-        let expected_cap_percentage = (WALLET_CAP_RAW * 1000) / total_supply; // per mille
-        assert_eq!(expected_cap_percentage, 5); // 0.5% = 5 per mille
-    }
-
-    #[test]
-    fn test_balance_calculations() {
-        // Test saturating_add behavior for balance calculations
-        let current_balance = 4_000_000_000u64; // 4 tokens
-        let transfer_amount = 1_000_000_000u64; // 1 token
-        let post_balance = current_balance.saturating_add(transfer_amount);
-        assert_eq!(post_balance, 5_000_000_000u64); // Should equal cap exactly
-        
-        // Test just under cap
-        let under_cap_balance = 4_999_999_999u64; // Just under 5 tokens
-        let small_transfer = 1u64; // 1 lamport
-        let post_balance_under = under_cap_balance.saturating_add(small_transfer);
-        assert_eq!(post_balance_under, 5_000_000_000u64); // Should equal cap exactly
-        
-        // Test overflow protection
-        let large_balance = u64::MAX - 1000;
-        let large_transfer = 2000u64;
-        let protected_balance = large_balance.saturating_add(large_transfer);
-        assert_eq!(protected_balance, u64::MAX); // Should saturate at MAX
-    }
-
-    #[test]
-    fn test_cap_enforcement_logic() {
-        // Test cases for wallet cap enforcement
-        struct TestCase {
-            current_balance: u64,
-            transfer_amount: u64,
-            should_pass: bool,
-            description: &'static str,
-        }
-        
-        let test_cases = vec![
-            TestCase {
-                current_balance: 0,
-                transfer_amount: 5_000_000_000,
-                should_pass: true,
-                description: "Zero to exactly cap should pass",
-            },
-            TestCase {
-                current_balance: 0,
-                transfer_amount: 4_999_999_999,
-                should_pass: true,
-                description: "Zero to just under cap should pass",
-            },
-            TestCase {
-                current_balance: 0,
-                transfer_amount: 5_000_000_001,
-                should_pass: false,
-                description: "Zero to over cap should fail",
-            },
-            TestCase {
-                current_balance: 3_000_000_000,
-                transfer_amount: 2_000_000_000,
-                should_pass: true,
-                description: "Partial to exactly cap should pass",
-            },
-            TestCase {
-                current_balance: 3_000_000_000,
-                transfer_amount: 2_000_000_001,
-                should_pass: false,
-                description: "Partial to over cap should fail",
-            },
-            TestCase {
-                current_balance: 5_000_000_000,
-                transfer_amount: 1,
-                should_pass: false,
-                description: "At cap receiving any amount should fail",
-            },
-            TestCase {
-                current_balance: 1,
-                transfer_amount: 1,
-                should_pass: true,
-                description: "Tiny amounts under cap should pass",
-            },
-        ];
-        
-        for test_case in test_cases {
-            let post_balance = test_case.current_balance.saturating_add(test_case.transfer_amount);
-            let passes_cap_check = post_balance <= WALLET_CAP_RAW;
-            
-            assert_eq!(
-                passes_cap_check, 
-                test_case.should_pass,
-                "Test case failed: {} (balance: {}, transfer: {}, post: {}, cap: {})",
-                test_case.description,
-                test_case.current_balance,
-                test_case.transfer_amount,
-                post_balance,
-                WALLET_CAP_RAW
-            );
-        }
+    /// Record that `owner` is a PDA account of a trusted program (permissionless), so the hook exempts
+    /// token accounts it owns, such as the vaults of new pools. `seeds`, bump included, have to derive
+    /// `owner` under the program owning its account. The record stops counting once governance clears the
+    /// program's trusted slot.
+    pub fn register_trusted_owner(ctx: Context<RegisterTrustedOwner>, seeds: Vec<Vec<u8>>) -> Result<()> {
+        instructions::register_trusted_owner(ctx, seeds)
     }
 
-    #[test]
-    fn test_dev_wallet_exemption_logic() {
-        use anchor_lang::prelude::Pubkey;
-        
-        // Mock dev wallet address for testing
-        let dev_wallet = Pubkey::new_unique();
-        let regular_wallet = Pubkey::new_unique();
-        
-        // Test case structure for dev wallet exemption
-        struct DevWalletTestCase {
-            destination_owner: Pubkey,
-            current_balance: u64,
-            transfer_amount: u64,
-            is_dev_wallet: bool,
-            should_pass: bool,
-            description: &'static str,
-        }
-        
-        let test_cases = vec![
-            DevWalletTestCase {
-                destination_owner: dev_wallet,
-                current_balance: 0,
-                transfer_amount: 1_000_000_000_000, // 1000 tokens - way over cap
-                is_dev_wallet: true,
-                should_pass: true,
-                description: "Dev wallet should accept any amount (initial mint scenario)",
-            },
-            DevWalletTestCase {
-                destination_owner: dev_wallet,
-                current_balance: 1_000_000_000_000, // Already has 1000 tokens
-                transfer_amount: 1_000_000_000, // 1 more token
-                is_dev_wallet: true,
-                should_pass: true,
-                description: "Dev wallet should accept additional tokens beyond cap",
-            },
-            DevWalletTestCase {
-                destination_owner: regular_wallet,
-                current_balance: 0,
-                transfer_amount: 1_000_000_000_000, // 1000 tokens - way over cap
-                is_dev_wallet: false,
-                should_pass: false,
-                description: "Regular wallet should be rejected for over-cap transfers",
-            },
-            DevWalletTestCase {
-                destination_owner: regular_wallet,
-                current_balance: 4_000_000_000, // 4 tokens
-                transfer_amount: 1_000_000_000, // 1 token - exactly at cap
-                is_dev_wallet: false,
-                should_pass: true,
-                description: "Regular wallet should accept transfers up to cap",
-            },
-            DevWalletTestCase {
-                destination_owner: regular_wallet,
-                current_balance: 5_000_000_000, // Already at cap
-                transfer_amount: 1, // Even 1 lamport over
-                is_dev_wallet: false,
-                should_pass: false,
-                description: "Regular wallet at cap should reject any additional tokens",
-            },
-        ];
-        
-        for test_case in test_cases {
-            let is_dev_wallet_check = test_case.destination_owner == dev_wallet;
-            assert_eq!(is_dev_wallet_check, test_case.is_dev_wallet, 
-                "Dev wallet identification failed for: {}", test_case.description);
-            
-            // Simulate the cap enforcement logic
-            let should_pass = if is_dev_wallet_check {
-                true // Dev wallet always passes
-            } else {
-                let post_balance = test_case.current_balance.saturating_add(test_case.transfer_amount);
-                post_balance <= WALLET_CAP_RAW
-            };
-            
-            assert_eq!(
-                should_pass,
-                test_case.should_pass,
-                "Dev wallet exemption test failed: {} (owner: {:?}, balance: {}, transfer: {}, is_dev: {})",
-                test_case.description,
-                test_case.destination_owner,
-                test_case.current_balance,
-                test_case.transfer_amount,
-                test_case.is_dev_wallet
-            );
-        }
+    /// Exempt a bridge's custody token account, tagged `BridgeCustody`, after checking its owner is a PDA
+    /// account of `bridge_program` (governance authority only)
+    pub fn register_bridge_custody(ctx: Context<RegisterBridgeCustody>, bridge_program: Pubkey) -> Result<()> {
+        instructions::register_bridge_custody(ctx, bridge_program)
+    }
+
+    /// Forget the detected launch so the next buy out of a pool vault records it again. Usable once, and
+    /// only before a snipe window has been measured from it (governance authority only)
+    pub fn reset_launch_detection(ctx: Context<ResetLaunchDetection>) -> Result<()> {
+        instructions::reset_launch_detection(ctx)
+    }
+
+    /// Set how long after the launch a buy out of a pool vault flags its buyer as a sniper, at most a day;
+    /// 0 flags nobody (governance authority only)
+    pub fn set_snipe_window(ctx: Context<SetSnipeWindow>, window_seconds: u32) -> Result<()> {
+        instructions::set_snipe_window(ctx, window_seconds)
+    }
+
+    /// Take an owner off the sniper log, e.g. a market maker flagged by its first fill (governance
+    /// authority only)
+    pub fn clear_sniper_flag(ctx: Context<ClearSniperFlag>, owner: Pubkey) -> Result<()> {
+        instructions::clear_sniper_flag(ctx, owner)
+    }
+
+    /// Require an SPL Memo instruction in any transaction moving more than `threshold` tokens (raw units)
+    /// between accounts that aren't exempt, or lift the requirement with None (governance authority only)
+    pub fn set_memo_required_above(ctx: Context<SetMemoRequiredAbove>, threshold: Option<u64>) -> Result<()> {
+        instructions::set_memo_required_above(ctx, threshold)
+    }
+
+    /// Set or lower how much the dev wallets may sell into pool vaults per 24 hours, in raw units. The limit
+    /// can never be raised or removed again (governance authority only)
+    pub fn lower_dev_outflow_limit(ctx: Context<LowerDevOutflowLimit>, limit: u64) -> Result<()> {
+        instructions::lower_dev_outflow_limit(ctx, limit)
+    }
+
+    /// Propose replacing (or clearing, with `Pubkey::default()`) a dev wallet slot (timelock mechanism)
+    pub fn propose_dev_wallet_update(
+        ctx: Context<ProposeDevWalletUpdate>,
+        index: u8,
+        new_wallet: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_dev_wallet_update(ctx, index, new_wallet)
+    }
+
+    /// Execute a previously proposed dev wallet update (after timelock)
+    pub fn execute_dev_wallet_update(ctx: Context<ExecuteDevWalletUpdate>) -> Result<()> {
+        instructions::execute_dev_wallet_update(ctx)
+    }
+
+    /// Cancel a pending dev wallet update (governance or veto authority)
+    pub fn cancel_dev_wallet_update(ctx: Context<CancelDevWalletUpdate>) -> Result<()> {
+        instructions::cancel_dev_wallet_update(ctx)
+    }
+
+    /// Propose a new timelock duration. The change itself waits out the current timelock, so governance
+    /// can't shorten the delay and push a parameter change through in one go.
+    pub fn propose_timelock_update(ctx: Context<TimelockUpdate>, timelock_seconds: u32) -> Result<()> {
+        instructions::propose_timelock_update(ctx, timelock_seconds)
+    }
+
+    /// Execute a previously proposed timelock duration change (after timelock)
+    pub fn execute_timelock_update(ctx: Context<TimelockUpdate>) -> Result<()> {
+        instructions::execute_timelock_update(ctx)
+    }
+
+    /// Cancel a pending timelock duration change (governance or veto authority)
+    pub fn cancel_timelock_update(ctx: Context<TimelockUpdate>) -> Result<()> {
+        instructions::cancel_timelock_update(ctx)
+    }
+
+    /// Set when the dev wallet exemption ends; can only ever be moved earlier (governance authority only)
+    pub fn set_dev_exemption_expiry(
+        ctx: Context<SetDevExemptionExpiry>,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::set_dev_exemption_expiry(ctx, expires_at)
+    }
+
+    /// Record that the dev wallet exemption has expired (permissionless crank for indexers)
+    pub fn finalize_dev_exemption(ctx: Context<FinalizeDevExemption>) -> Result<()> {
+        instructions::finalize_dev_exemption(ctx)
+    }
+
+    /// Set how many over-cap transfers from a dev wallet the hook will still allow (governance authority only)
+    pub fn set_airdrop_allowance(
+        ctx: Context<SetAirdropAllowance>,
+        allowance: u32,
+    ) -> Result<()> {
+        instructions::set_airdrop_allowance(ctx, allowance)
+    }
+
+    /// Set the key whose ed25519-signed permits allow a one-off over-cap receive, or disable permits
+    /// with `Pubkey::default()` (governance authority only)
+    pub fn set_permit_signer(
+        ctx: Context<SetPermitSigner>,
+        permit_signer: Pubkey,
+    ) -> Result<()> {
+        instructions::set_permit_signer(ctx, permit_signer)
+    }
+
+    /// Create the bitmap of redeemed permit nonces (governance authority only)
+    pub fn init_used_nonces(ctx: Context<InitUsedNonces>) -> Result<()> {
+        instructions::init_used_nonces(ctx)
+    }
+
+    /// Configure the circuit breaker: pause transfers for `cooldown` seconds once more than
+    /// `threshold_raw` moves within `window_slots` slots; a zero threshold disables it (governance authority only)
+    pub fn set_circuit_breaker(
+        ctx: Context<SetCircuitBreaker>,
+        threshold_raw: u64,
+        window_slots: u64,
+        cooldown: i64,
+    ) -> Result<()> {
+        instructions::set_circuit_breaker(ctx, threshold_raw, window_slots, cooldown)
+    }
+
+    /// Lift a tripped circuit breaker before its cooldown ends and start a fresh window (governance authority only)
+    pub fn clear_circuit_breaker(ctx: Context<SetCircuitBreaker>) -> Result<()> {
+        instructions::clear_circuit_breaker(ctx)
+    }
+
+    /// Set or clear the guardian, a key that may pause transfers but change nothing else (governance authority only)
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+        instructions::set_guardian(ctx, guardian)
+    }
+
+    /// Pause transfers until further notice, or lift any pause including a tripped circuit breaker.
+    /// The guardian may pause; unpausing needs governance.
+    pub fn set_pause(ctx: Context<SetPause>, paused: bool) -> Result<()> {
+        instructions::set_pause(ctx, paused)
+    }
+
+    /// Create the holder tracking account for `owner`; anyone may pay for it
+    pub fn init_holder_state(ctx: Context<InitHolderState>, owner: Pubkey) -> Result<()> {
+        instructions::init_holder_state(ctx, owner)
+    }
+
+    /// Close the signer's holder tracking account and refund its rent to them; only once its tracked
+    /// balance and the signer's given token account are both empty
+    pub fn close_holder_state(ctx: Context<CloseHolderState>) -> Result<()> {
+        instructions::close_holder_state(ctx)
+    }
+
+    /// Set the maximum number of holders, or disable holder tracking with 0 (governance authority only)
+    pub fn set_max_holders(ctx: Context<SetMaxHolders>, max_holders: u32) -> Result<()> {
+        instructions::set_max_holders(ctx, max_holders)
+    }
+
+    /// Turn the per-transfer `HookTransferChecked` event on or off (governance authority only)
+    pub fn set_transfer_events(ctx: Context<SetTransferEvents>, enabled: bool) -> Result<()> {
+        instructions::set_transfer_events(ctx, enabled)
+    }
+
+    /// Require destination owners to be wallets: token accounts owned by a program-derived address are
+    /// refused unless exempt, e.g. pool vaults in the exempt registry (governance authority only)
+    pub fn set_require_wallet_owners(ctx: Context<SetRequireWalletOwners>, enabled: bool) -> Result<()> {
+        instructions::set_require_wallet_owners(ctx, enabled)
+    }
+
+    /// Record the mint's supply, wallet cap, pause state and transfer counters in a new `Snapshot` account;
+    /// anyone may pay for one, at most once every SNAPSHOT_INTERVAL_SLOTS
+    pub fn take_snapshot(ctx: Context<TakeSnapshot>) -> Result<()> {
+        instructions::take_snapshot(ctx)
+    }
+
+    /// Close snapshot `index` and refund its rent to whoever took it (governance authority only)
+    pub fn close_snapshot(ctx: Context<CloseSnapshot>, index: u64) -> Result<()> {
+        instructions::close_snapshot(ctx, index)
+    }
+
+    /// Propose disabling the hook for good, executable after a fixed 7 day timelock (governance authority only)
+    pub fn propose_hook_disable(ctx: Context<HookDisable>) -> Result<()> {
+        instructions::propose_hook_disable(ctx)
     }
 
-    #[test]
-    fn test_extra_account_meta_list_size() {
-        // Test that our allocated space is sufficient for the expected data
-        let expected_account_count = 1; // We have 1 extra account (config PDA)
-        
-        // Calculate required size using the same logic as the program
-        // This should match the calculation in init_extra_account_meta_list
-        let base_size = 8; // discriminator
-        let meta_size_per_account = 32; // Approximate size per ExtraAccountMeta
-        let estimated_size = base_size + (expected_account_count * meta_size_per_account);
-        
-        assert!(
-            EXTRA_ACCOUNT_META_LIST_SIZE >= estimated_size,
-            "Allocated space ({}) should be >= estimated requirement ({})",
-            EXTRA_ACCOUNT_META_LIST_SIZE,
-            estimated_size
-        );
-        
-        // Ensure we have reasonable buffer space but not excessive waste
-        assert!(
-            EXTRA_ACCOUNT_META_LIST_SIZE <= estimated_size * 3,
-            "Allocated space ({}) should not be more than 3x estimated requirement ({})",
-            EXTRA_ACCOUNT_META_LIST_SIZE,
-            estimated_size * 3
-        );
-    }
-
-    #[test]
-    fn test_hook_config_size() {
-        // Test that HookConfig struct size matches our space allocation
-        // discriminator (8) + version (1) + dev_wallet (32) + wallet_cap_raw (8) + governance_authority (32) + Option<PendingCapUpdate> (1 + 8 + 8 + 8)
-        let expected_size = 8 + 1 + 32 + 8 + 32 + 1 + (8 + 8 + 8);
-        assert_eq!(expected_size, 106);
-        
-        // This should match the space allocated in the Initialize account structure
-        assert!(expected_size <= 106, "HookConfig too large for allocated space");
-    }
-
-    #[test]
-    fn test_seed_derivation_constants() {
-        // Test that our seed constants are correctly defined
-        let config_seed = b"config";
-        let extra_metas_seed = b"extra-account-metas";
-        
-        assert_eq!(config_seed.len(), 6);
-        assert_eq!(extra_metas_seed.len(), 19);
-        
-        // Ensure seeds are reasonable length (Solana PDA seeds have limits)
-        assert!(config_seed.len() <= 32, "Config seed too long");
-        assert!(extra_metas_seed.len() <= 32, "Extra metas seed too long");
+    /// Execute a matured disable proposal. From then on the hook lets every transfer through after its
+    /// account checks, and nothing can turn it back on (governance authority only).
+    pub fn permanently_disable_hook(ctx: Context<HookDisable>) -> Result<()> {
+        instructions::permanently_disable_hook(ctx)
+    }
+
+    /// Cancel the pending hook disable (governance or veto authority)
+    pub fn cancel_hook_disable(ctx: Context<HookDisable>) -> Result<()> {
+        instructions::cancel_hook_disable(ctx)
+    }
+
+    /// Lock `owner`'s outbound transfers until `locked_until`, then release `locked_amount` linearly
+    /// until `linear_release_end` if one is given (governance authority only)
+    pub fn create_lockup(
+        ctx: Context<CreateLockup>,
+        owner: Pubkey,
+        locked_amount: u64,
+        locked_until: i64,
+        linear_release_end: Option<i64>,
+    ) -> Result<()> {
+        instructions::create_lockup(ctx, owner, locked_amount, locked_until, linear_release_end)
+    }
+
+    /// Move a lockup's cliff and release end earlier; neither can ever move later (governance authority only)
+    pub fn shorten_lockup(
+        ctx: Context<ShortenLockup>,
+        owner: Pubkey,
+        locked_until: i64,
+        linear_release_end: Option<i64>,
+    ) -> Result<()> {
+        instructions::shorten_lockup(ctx, owner, locked_until, linear_release_end)
+    }
+
+    /// Propose a dev wallet update like `propose_dev_wallet_update`, first checking that the optional
+    /// token account is `new_wallet`'s account of this mint. The owner may be a multisig or a PDA; the
+    /// update still waits out the timelock (governance authority only)
+    pub fn set_dev_wallet_verified(
+        ctx: Context<SetDevWalletVerified>,
+        index: u8,
+        new_wallet: Pubkey,
+    ) -> Result<()> {
+        instructions::set_dev_wallet_verified(ctx, index, new_wallet)
+    }
+
+    /// Migrate HookConfig to the next layout version. Exactly one version is crossed per call, so
+    /// a config several versions behind runs every intermediate conversion in order.
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config(ctx)
+    }
+
+    /// Close the config and send its rent to `recipient` (governance authority only). Every transfer
+    /// of a mint still hooked to this program fails once the config is gone, so the caller has to
+    /// confirm the mint no longer routes transfers through it; a pending cap update must be canceled first.
+    pub fn close_config(ctx: Context<CloseConfig>, confirm_mint_detached: bool) -> Result<()> {
+        instructions::close_config(ctx, confirm_mint_detached)
+    }
+
+    /// Send the lamports the config and the extra account meta list hold above their rent exemption to
+    /// `recipient`, such as SOL sent to either address by mistake (governance authority only). Both stay
+    /// open and rent exempt; with no surplus nothing moves.
+    pub fn withdraw_surplus_lamports(ctx: Context<WithdrawSurplusLamports>) -> Result<()> {
+        instructions::withdraw_surplus_lamports(ctx)
     }
 }
+
+// Unit tests for core business logic
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,417 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use spl_token_2022::extension::{
+    confidential_transfer::{ConfidentialTransferAccount, ConfidentialTransferMint},
+    interest_bearing_mint::InterestBearingConfig,
+    transfer_fee::TransferFeeConfig,
+    BaseStateWithExtensions, StateWithExtensions,
+};
+
+use crate::errors::HookError;
+use crate::state::{CapKind, CapUnit, ExemptRegistry, HookConfig};
+use crate::{MIN_SELL_LIMIT_RAW, MIN_TIMELOCK_SECONDS};
+
+/// Messages an ed25519 precompile instruction verified against `signer`.
+///
+/// Only signatures whose public key, signature and message all live in the instruction's own data
+/// are returned, so the bytes are exactly what the precompile checked.
+pub fn ed25519_verified_messages<'a>(data: &'a [u8], signer: &Pubkey) -> Vec<&'a [u8]> {
+    // num_signatures (u8) + padding (u8), then 7 u16 offsets per signature
+    const OFFSETS_START: usize = 2;
+    const OFFSETS_SIZE: usize = 14;
+    const THIS_INSTRUCTION: usize = u16::MAX as usize;
+
+    let read_u16 = |at: usize| data.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let num_signatures = data.first().copied().unwrap_or(0) as usize;
+
+    (0..num_signatures)
+        .filter_map(|i| {
+            let base = OFFSETS_START + i * OFFSETS_SIZE;
+            let signature_ix = read_u16(base + 2)?;
+            let public_key_offset = read_u16(base + 4)?;
+            let public_key_ix = read_u16(base + 6)?;
+            let message_offset = read_u16(base + 8)?;
+            let message_size = read_u16(base + 10)?;
+            let message_ix = read_u16(base + 12)?;
+            if signature_ix != THIS_INSTRUCTION || public_key_ix != THIS_INSTRUCTION || message_ix != THIS_INSTRUCTION {
+                return None;
+            }
+            if data.get(public_key_offset..public_key_offset + 32)? != signer.as_ref() {
+                return None;
+            }
+            data.get(message_offset..message_offset + message_size)
+        })
+        .collect()
+}
+
+/// How a transfer relates to the registered pool and staking vaults
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferKind {
+    Buy,            // Out of a pool vault
+    Sell,           // Into a pool vault
+    Unstake,        // Out of a staking vault
+    WalletToWallet, // Neither side is a pool vault, nor the source a staking vault
+}
+
+impl TransferKind {
+    /// The balance cap applied to the destination
+    pub fn cap_kind(self) -> CapKind {
+        match self {
+            TransferKind::Buy => CapKind::Buy,
+            TransferKind::Sell | TransferKind::Unstake | TransferKind::WalletToWallet => CapKind::Wallet,
+        }
+    }
+}
+
+/// Classify a transfer by its token accounts; a transfer out of a pool or staking vault is a buy or an
+/// unstake even if it lands in a pool vault
+pub fn classify_transfer(
+    registry: Option<&ExemptRegistry>,
+    source: &Pubkey,
+    destination: &Pubkey,
+    now: i64,
+) -> TransferKind {
+    match registry {
+        Some(registry) if registry.is_pool_vault(source, now) => TransferKind::Buy,
+        Some(registry) if registry.is_staking_vault(source, now) => TransferKind::Unstake,
+        Some(registry) if registry.is_pool_vault(destination, now) => TransferKind::Sell,
+        _ => TransferKind::WalletToWallet,
+    }
+}
+
+/// Balance the destination holds once `amount` lands on its `current` balance. Token-2022 keeps
+/// balances within the supply, so an overflow means corrupt account data rather than a large transfer.
+pub fn post_transfer_balance(current: u64, amount: u64) -> Result<u64> {
+    current.checked_add(amount).ok_or_else(|| HookError::ArithmeticOverflow.into())
+}
+
+/// Reject a transfer of `amount` onto a `current` balance that would leave it above `cap`
+pub fn enforce_cap(cap: u64, current: u64, amount: u64) -> Result<()> {
+    require!(post_transfer_balance(current, amount)? <= cap, HookError::WalletCapExceeded);
+    Ok(())
+}
+
+/// Whether `owner` is a dev wallet whose exemption from the caps and the sell limit hasn't sunset at `now`
+pub fn is_exempt(config: &HookConfig, owner: &Pubkey, now: i64) -> bool {
+    config.is_dev_wallet(owner) && config.dev_exemption_active(now)
+}
+
+/// Bounds for proposed cap values
+pub fn validate_cap_proposal(kind: CapKind, new_cap: u64) -> Result<()> {
+    match kind {
+        // The sell limit can be lifted entirely but never set below the floor
+        CapKind::Sell => require!(new_cap == 0 || new_cap >= MIN_SELL_LIMIT_RAW, HookError::SellLimitBelowFloor),
+        CapKind::Wallet | CapKind::Buy => require!(new_cap > 0, HookError::InvalidWalletCap),
+    }
+
+    // Maximum reasonable cap: 10% of expected total supply (1000 tokens)
+    let max_reasonable_cap = 100_000_000_000u64; // 100 tokens with 9 decimals
+    require!(new_cap <= max_reasonable_cap, HookError::InvalidWalletCap);
+    Ok(())
+}
+
+/// Amount the destination is credited for a transfer of `amount`: net of the mint's transfer fee for
+/// `epoch` if it has the TransferFeeConfig extension, otherwise the gross amount
+pub fn net_transfer_amount(mint_data: &[u8], amount: u64, epoch: u64) -> Result<u64> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => {
+            let fee = fee_config
+                .calculate_epoch_fee(epoch, amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            Ok(amount.saturating_sub(fee))
+        }
+        Err(_) => Ok(amount),
+    }
+}
+
+/// Whether the mint's TransferHook extension names this program as its hook
+pub fn mint_uses_this_hook(mint_data: &[u8]) -> Result<bool> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    Ok(mint
+        .get_extension::<spl_token_2022::extension::transfer_hook::TransferHook>()
+        .is_ok_and(|hook| Option::<Pubkey>::from(hook.program_id) == Some(crate::ID)))
+}
+
+/// The mint's current mint authority, if it still has one
+pub fn mint_authority(mint_data: &[u8]) -> Result<Option<Pubkey>> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    Ok(mint.base.mint_authority.into())
+}
+
+/// The mint's raw supply
+pub fn mint_supply(mint_data: &[u8]) -> Result<u64> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    Ok(mint.base.supply)
+}
+
+/// Whether the mint has an extension that makes UI amounts diverge from raw amounts.
+///
+/// Only interest-bearing mints qualify: the scaled UI amount extension is not part of the
+/// spl-token-2022 release this program builds against.
+pub fn mint_supports_ui_cap(mint_data: &[u8]) -> Result<bool> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    Ok(mint.get_extension::<InterestBearingConfig>().is_ok())
+}
+
+/// Whether the mint has the confidential transfer extension, whose encrypted amounts the hook never sees
+pub fn mint_has_confidential_transfers(mint_data: &[u8]) -> Result<bool> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    Ok(mint.get_extension::<ConfidentialTransferMint>().is_ok())
+}
+
+/// Whether a transfer the hook is shown is a confidential credit it can't evaluate: no public amount,
+/// into an account that takes confidential credits
+pub fn is_confidential_credit(destination_data: &[u8], amount: u64) -> Result<bool> {
+    if amount > 0 {
+        return Ok(false);
+    }
+    let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(destination_data)?;
+    Ok(account
+        .get_extension::<ConfidentialTransferAccount>()
+        .is_ok_and(|confidential| bool::from(confidential.allow_confidential_credits)))
+}
+
+/// Factor by which the mint's interest has grown raw balances at `unix_timestamp`, using the same
+/// continuous compounding as `InterestBearingConfig::amount_to_ui_amount` (without the decimals shift)
+pub fn interest_growth(config: &InterestBearingConfig, unix_timestamp: i64) -> Option<f64> {
+    const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+    const ONE_IN_BASIS_POINTS: f64 = 10_000.0;
+
+    let initialization_timestamp = i64::from(config.initialization_timestamp);
+    let last_update_timestamp = i64::from(config.last_update_timestamp);
+    let pre_update_timespan = last_update_timestamp.checked_sub(initialization_timestamp)?;
+    let post_update_timespan = unix_timestamp.checked_sub(last_update_timestamp)?;
+
+    let exp = |rate: i16, timespan: i64| -> Option<f64> {
+        let numerator = (rate as i128).checked_mul(timespan as i128)? as f64;
+        Some((numerator / SECONDS_PER_YEAR / ONE_IN_BASIS_POINTS).exp())
+    };
+    let pre_update_exp = exp(i16::from(config.pre_update_average_rate), pre_update_timespan)?;
+    let post_update_exp = exp(i16::from(config.current_rate), post_update_timespan)?;
+    Some(pre_update_exp * post_update_exp)
+}
+
+/// Express a raw balance in the units the cap uses. Raw mode returns it untouched; UI mode scales
+/// it by the accrued interest so the cap tracks the amount wallets display.
+pub fn balance_in_cap_units(unit: CapUnit, mint_data: &[u8], raw_balance: u64, unix_timestamp: i64) -> Result<u64> {
+    match unit {
+        CapUnit::Raw => Ok(raw_balance),
+        CapUnit::Ui => {
+            let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+            let interest = mint
+                .get_extension::<InterestBearingConfig>()
+                .map_err(|_| HookError::UiCapUnsupported)?;
+            let growth = interest_growth(interest, unix_timestamp).ok_or(ProgramError::ArithmeticOverflow)?;
+            // Float to int casts saturate, so an enormous balance stays over any cap
+            Ok((raw_balance as f64 * growth) as u64)
+        }
+    }
+}
+
+/// Neither initial key may be the default pubkey: a default governance authority can never sign, bricking
+/// governance for good. With `strict`, the dev wallet, governance authority and payer must all differ too.
+pub fn validate_initial_authorities(
+    dev_wallet: &Pubkey,
+    governance_authority: &Pubkey,
+    payer: &Pubkey,
+    strict: bool,
+) -> Result<()> {
+    require!(*dev_wallet != Pubkey::default(), HookError::InvalidAuthority);
+    require!(*governance_authority != Pubkey::default(), HookError::InvalidAuthority);
+    if strict {
+        require!(
+            dev_wallet != governance_authority && dev_wallet != payer && governance_authority != payer,
+            HookError::InvalidAuthority
+        );
+    }
+    Ok(())
+}
+
+/// Timelocks must be at least MIN_TIMELOCK_SECONDS, except on devnet builds where any delay (even none) is allowed
+pub fn validate_timelock(timelock_seconds: u32) -> Result<()> {
+    if cfg!(feature = "devnet") {
+        return Ok(());
+    }
+    require!(timelock_seconds >= MIN_TIMELOCK_SECONDS, HookError::InvalidTimelock);
+    Ok(())
+}
+
+/// A linear release has to end after the cliff it starts at
+pub fn validate_lockup_schedule(locked_until: i64, linear_release_end: Option<i64>) -> Result<()> {
+    require!(
+        linear_release_end.map_or(true, |end| end > locked_until),
+        HookError::InvalidLockupSchedule
+    );
+    Ok(())
+}
+
+/// Require the source and destination token accounts, as already unpacked, to both be accounts of `mint`
+pub fn check_token_account_mints(
+    source: &spl_token_2022::state::Account,
+    destination: &spl_token_2022::state::Account,
+    mint: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(source.mint, *mint, HookError::SourceMintMismatch);
+    require_keys_eq!(destination.mint, *mint, HookError::DestinationMintMismatch);
+    Ok(())
+}
+
+/// Require a dev wallet's token account to be an account of `mint` owned by `wallet`. Only the token
+/// account's owner field is compared, so multisig and PDA owners are as good as keypairs
+pub fn check_dev_wallet_token_account(data: &[u8], mint: &Pubkey, wallet: &Pubkey) -> Result<()> {
+    let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(data)?;
+    require_keys_eq!(account.base.mint, *mint, HookError::DevWalletAccountMismatch);
+    require_keys_eq!(account.base.owner, *wallet, HookError::DevWalletAccountMismatch);
+    Ok(())
+}
+
+/// Require `authority`, the owner account Token-2022 passes to the hook, to be the one allowed to move
+/// the source's tokens: its owner, or its delegate while any delegated amount remains. Routers and
+/// aggregators usually transfer as a delegate, so the owner account can't be assumed to own the source
+pub fn check_transfer_authority(source: &spl_token_2022::state::Account, authority: &Pubkey) -> Result<()> {
+    let is_delegate = source.delegate == COption::Some(*authority) && source.delegated_amount > 0;
+    require!(source.owner == *authority || is_delegate, HookError::OwnerMismatch);
+    Ok(())
+}
+
+/// Whether `seeds`, bump included, derive `address` as a PDA of `program`. Only that program can sign for
+/// such an address, unlike a PDA some other program created and then assigned to it
+pub fn is_program_address(address: &Pubkey, program: &Pubkey, seeds: &[Vec<u8>]) -> bool {
+    let seeds: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+    Pubkey::create_program_address(&seeds, program).map_or(false, |derived| derived == *address)
+}
+
+/// Properties of the cap, window and release arithmetic over the whole input range, without a validator
+#[cfg(test)]
+mod proptests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::state::Lockup;
+    use crate::tests::{mint_with_transfer_fee, test_config};
+
+    /// Mostly arbitrary values, with the edges of the `u64` range drawn often enough to matter
+    fn amount() -> impl Strategy<Value = u64> {
+        prop_oneof![
+            4 => any::<u64>(),
+            2 => 0..=10_000_000_000u64,
+            1 => Just(0),
+            1 => Just(u64::MAX),
+            1 => (0..=1_000u64).prop_map(|offset| u64::MAX - offset),
+        ]
+    }
+
+    /// A linear or cliff-only schedule anywhere in the `i64` range that `validate_lockup_schedule` accepts
+    fn lockup() -> impl Strategy<Value = Lockup> {
+        (amount(), any::<i64>(), proptest::option::of(any::<i64>()))
+            .prop_filter("release must end after the cliff", |(_, locked_until, end)| {
+                validate_lockup_schedule(*locked_until, *end).is_ok()
+            })
+            .prop_map(|(locked_amount, locked_until, linear_release_end)| Lockup {
+                mint: Pubkey::default(),
+                owner: Pubkey::default(),
+                locked_amount,
+                locked_until,
+                linear_release_end,
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn cap_decision_matches_the_reference(cap in amount(), balance in amount(), amount in amount()) {
+            let post = u128::from(balance) + u128::from(amount);
+            match enforce_cap(cap, balance, amount) {
+                Ok(()) => prop_assert!(post <= u128::from(cap)),
+                Err(error) if post > u128::from(u64::MAX) => {
+                    prop_assert_eq!(error, anchor_lang::error::Error::from(HookError::ArithmeticOverflow))
+                }
+                Err(error) => {
+                    prop_assert!(post > u128::from(cap));
+                    prop_assert_eq!(error, anchor_lang::error::Error::from(HookError::WalletCapExceeded));
+                }
+            }
+            prop_assert_eq!(post_transfer_balance(balance, amount).ok(), balance.checked_add(amount));
+        }
+
+        #[test]
+        fn smaller_transfers_and_larger_caps_stay_allowed(
+            cap in amount(),
+            balance in amount(),
+            amount in amount(),
+            smaller in any::<u64>(),
+        ) {
+            prop_assume!(enforce_cap(cap, balance, amount).is_ok());
+            let smaller_amount = smaller.checked_rem(amount).unwrap_or(0);
+            let smaller_balance = smaller.checked_rem(balance).unwrap_or(0);
+            prop_assert!(enforce_cap(cap, balance, smaller_amount).is_ok());
+            prop_assert!(enforce_cap(cap, smaller_balance, amount).is_ok());
+            prop_assert!(enforce_cap(cap.saturating_add(1), balance, amount).is_ok());
+        }
+
+        #[test]
+        fn fees_never_credit_more_than_the_gross_amount(
+            basis_points in 0..=10_000u16,
+            maximum_fee in amount(),
+            amount in amount(),
+            epoch in any::<u64>(),
+        ) {
+            let mint = mint_with_transfer_fee(basis_points, maximum_fee);
+            let net = net_transfer_amount(&mint, amount, epoch).unwrap();
+            prop_assert!(net <= amount);
+            prop_assert!(amount - net <= maximum_fee);
+        }
+
+        #[test]
+        fn volume_windows_reset_once_they_end(
+            threshold in amount(),
+            window_slots in amount(),
+            window_start_slot in amount(),
+            window_volume in amount(),
+            amount in amount(),
+            slot in amount(),
+            // Leaves room for the cooldown, which saturates at the end of time
+            now in i64::MIN..i64::MAX - 60,
+        ) {
+            let mut config = test_config();
+            config.circuit_breaker_threshold_raw = threshold;
+            config.circuit_breaker_window_slots = window_slots;
+            config.circuit_breaker_cooldown = 60;
+            config.window_start_slot = window_start_slot;
+            config.window_volume = window_volume;
+            let was_paused = config.is_paused(now);
+
+            let tripped = config.record_volume(amount, slot, now);
+            if threshold == 0 {
+                // A disabled breaker doesn't even track the window
+                prop_assert!(!tripped);
+                prop_assert_eq!((config.window_start_slot, config.window_volume), (window_start_slot, window_volume));
+                return Ok(());
+            }
+            if slot >= window_start_slot.saturating_add(window_slots) {
+                prop_assert_eq!((config.window_start_slot, config.window_volume), (slot, amount));
+            } else {
+                prop_assert_eq!(config.window_start_slot, window_start_slot);
+                prop_assert_eq!(config.window_volume, window_volume.saturating_add(amount));
+            }
+            prop_assert_eq!(tripped, config.window_volume > threshold && !was_paused);
+            prop_assert!(config.is_paused(now) || !tripped);
+        }
+
+        #[test]
+        fn lockups_only_release_over_time(lockup in lockup(), now in any::<i64>(), later in any::<i64>()) {
+            let (earlier, later) = (now.min(later), now.max(later));
+            let (locked_earlier, locked_later) = (lockup.locked_at(earlier), lockup.locked_at(later));
+            prop_assert!(locked_later <= locked_earlier);
+
+            if earlier < lockup.locked_until {
+                prop_assert_eq!(locked_earlier, u64::MAX);
+            } else {
+                prop_assert!(locked_earlier <= lockup.locked_amount);
+            }
+            if later >= lockup.linear_release_end.unwrap_or(lockup.locked_until) {
+                prop_assert_eq!(locked_later, 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_type_length_value::state::TlvStateBorrowed;
+
+use crate::errors::HookError;
+use crate::logic::mint_authority;
+
+/// Accounts Token-2022 resolves and appends to every `execute` CPI, in `Execute` order after the
+/// validation account
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"config".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false, // is_signer
+            true,  // is_writable (the hook records transfer stats and decrements the airdrop allowance)
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"exempt-registry".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        ExtraAccountMeta::new_with_pubkey(
+            &anchor_lang::solana_program::sysvar::instructions::ID,
+            false, // is_signer
+            false, // is_writable
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"used-nonces".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false, // is_signer
+            true,  // is_writable (redeemed permit nonces are recorded in the hook)
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"holder".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData { account_index: 0, data_index: 32, length: 32 }, // source owner
+            ],
+            false, // is_signer
+            true,  // is_writable (holder balance and lifetime stats)
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"holder".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 }, // destination owner
+            ],
+            false, // is_signer
+            true,  // is_writable (holder balance and lifetime stats)
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"lockup".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData { account_index: 0, data_index: 32, length: 32 }, // source owner
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"config-ext".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false, // is_signer
+            true,  // is_writable (the first buy out of a pool vault records the launch)
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"sniper-log".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+            ],
+            false, // is_signer
+            true,  // is_writable (buys in the snipe window list their buyer)
+        )?,
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"trusted-owner".to_vec() },
+                Seed::AccountKey { index: 1 }, // mint
+                Seed::AccountData { account_index: 2, data_index: 32, length: 32 }, // destination owner
+            ],
+            false, // is_signer
+            false, // is_writable
+        )?,
+    ])
+}
+
+/// Whether `data` decodes to exactly the metas `extra_account_metas` defines, the ones `execute` expects
+pub fn holds_current_metas(data: &[u8]) -> Result<bool> {
+    let Ok(state) = TlvStateBorrowed::unpack(data) else {
+        return Ok(false);
+    };
+    let Ok(list) = ExtraAccountMetaList::unpack_with_tlv_state::<ExecuteInstruction>(&state) else {
+        return Ok(false);
+    };
+    Ok(list.data() == extra_account_metas()?.as_slice())
+}
+
+/// Exact size of the extra account meta list account holding `extra_account_metas`
+pub fn extra_account_meta_list_size() -> Result<usize> {
+    Ok(ExtraAccountMetaList::size_of(extra_account_metas()?.len())?)
+}
+
+/// The transfer hook interface's `InitializeExtraAccountMetaList`, for SPL tooling that doesn't speak
+/// Anchor. Accounts are the extra account meta list PDA (pre-funded for rent, as the SPL CLI does),
+/// the mint and its mint authority as signer, then the system program. The hook defines its own
+/// metas, so the instruction may only carry an empty list or exactly those.
+pub fn initialize_spl_extra_account_meta_list<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    requested_metas: &[ExtraAccountMeta],
+) -> Result<()> {
+    let [extra_account_meta_list, mint, authority, system_program, ..] = accounts else {
+        return Err(anchor_lang::error::ErrorCode::AccountNotEnoughKeys.into());
+    };
+    require!(mint.owner == &TOKEN_2022_PROGRAM_ID, HookError::InvalidAccountOwner);
+    require!(authority.is_signer, anchor_lang::error::ErrorCode::AccountNotSigner);
+    require!(
+        mint_authority(&mint.try_borrow_data()?)? == Some(authority.key()),
+        HookError::UnauthorizedInitializer
+    );
+
+    let account_metas = extra_account_metas()?;
+    require!(
+        requested_metas.is_empty() || requested_metas == account_metas.as_slice(),
+        HookError::ExtraAccountMetasMismatch
+    );
+
+    let (address, bump) = Pubkey::find_program_address(&[b"extra-account-metas", mint.key.as_ref()], program_id);
+    require_keys_eq!(extra_account_meta_list.key(), address, anchor_lang::error::ErrorCode::ConstraintSeeds);
+    let account_size = extra_account_meta_list_size()?;
+    require!(
+        Rent::get()?.is_exempt(extra_account_meta_list.lamports(), account_size),
+        anchor_lang::error::ErrorCode::ConstraintRentExempt
+    );
+
+    let signer_seeds: &[&[u8]] = &[b"extra-account-metas", mint.key.as_ref(), &[bump]];
+    let pda_and_system = [extra_account_meta_list.clone(), system_program.clone()];
+    invoke_signed(
+        &system_instruction::allocate(&address, account_size as u64),
+        &pda_and_system,
+        &[signer_seeds],
+    )?;
+    invoke_signed(&system_instruction::assign(&address, program_id), &pda_and_system, &[signer_seeds])?;
+
+    let mut data = extra_account_meta_list.try_borrow_mut_data()?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &account_metas)?;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+//! The program's time parameters, selected at build time.
+//!
+//! Standard builds use the delays holders rely on. Builds with the `short-timelocks` feature swap them
+//! for second-scale values so QA can walk a timelock, a proposal expiry or a permanent disable through on
+//! devnet; `initialize` logs `BUILD_PROFILE` so such a build is recognizable wherever it was deployed.
+//! Pausing has no maximum duration to shorten: a pause holds until lifted, and the circuit breaker's
+//! cooldown is set per config by governance.
+
+#[cfg(all(feature = "short-timelocks", feature = "mainnet"))]
+compile_error!("the `short-timelocks` feature must never be enabled in a `mainnet` build");
+
+/// Which set of time parameters this build was compiled with
+#[cfg(not(feature = "short-timelocks"))]
+pub const BUILD_PROFILE: &str = "standard";
+#[cfg(feature = "short-timelocks")]
+pub const BUILD_PROFILE: &str = "short-timelocks";
+
+/// Default delay between proposing and executing a parameter change (48 hours); configs migrated from v1
+/// get it
+#[cfg(not(feature = "short-timelocks"))]
+pub const TIMELOCK_DURATION: i64 = 48 * 60 * 60;
+#[cfg(feature = "short-timelocks")]
+pub const TIMELOCK_DURATION: i64 = 60;
+
+/// Shortest configurable timelock outside devnet builds (1 hour)
+#[cfg(not(feature = "short-timelocks"))]
+pub const MIN_TIMELOCK_SECONDS: u32 = 60 * 60;
+#[cfg(feature = "short-timelocks")]
+pub const MIN_TIMELOCK_SECONDS: u32 = 1;
+
+/// How long a matured cap proposal stays executable before it expires (7 days), unless governance changes
+/// it
+#[cfg(not(feature = "short-timelocks"))]
+pub const DEFAULT_PROPOSAL_EXPIRY_SECONDS: u32 = 7 * 24 * 60 * 60;
+#[cfg(feature = "short-timelocks")]
+pub const DEFAULT_PROPOSAL_EXPIRY_SECONDS: u32 = 10 * 60;
+
+/// Delay before a proposed permanent hook disable can execute (7 days); fixed, unlike the configurable
+/// timelock
+#[cfg(not(feature = "short-timelocks"))]
+pub const HOOK_DISABLE_TIMELOCK_SECONDS: i64 = 7 * 24 * 60 * 60;
+#[cfg(feature = "short-timelocks")]
+pub const HOOK_DISABLE_TIMELOCK_SECONDS: i64 = 5 * 60;
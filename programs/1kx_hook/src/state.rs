@@ -0,0 +1,1437 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::errors::HookError;
+use crate::logic::{is_program_address, validate_cap_proposal, validate_lockup_schedule};
+use crate::{
+    CONFIG_EXTENSION_RESERVED_LEN, CONFIG_VIEW_VERSION, DEFAULT_PROPOSAL_EXPIRY_SECONDS, DEV_OUTFLOW_WINDOW_SECONDS,
+    GOVERNANCE_ACTION_PREFIX, GOVERNANCE_LOG_LEN, GOVERNANCE_LOG_PAGE_LEN, HOOK_CONFIG_RESERVED_LEN,
+    HOOK_DISABLE_TIMELOCK_SECONDS, MAX_DEV_WALLETS, MAX_GOVERNANCE_MEMBERS, MAX_TRUSTED_PROGRAMS,
+    MINTS_PER_REGISTRY_PAGE, PERMIT_MESSAGE_PREFIX, SNAPSHOT_INTERVAL_SLOTS, SNIPER_LOG_LEN, SUPPORTED_CONFIG_VERSION,
+    TIMELOCK_DURATION, USED_NONCES_BITMAP_LEN,
+};
+
+#[account]
+#[derive(InitSpace)]
+pub struct HookConfig {
+    pub version: u8,
+    pub dev_wallets: [Pubkey; MAX_DEV_WALLETS], // Owners exempt from the cap (zero = unused slot)
+    pub wallet_cap_raw: u64,
+    pub governance_authority: Pubkey,
+    pub pending_cap_update: Option<PendingCapUpdate>,
+    pub trusted_programs: [Pubkey; MAX_TRUSTED_PROGRAMS], // AMM programs whose PDAs are exempt owners (zero = unused slot)
+    pub pending_dev_wallet_update: Option<PendingDevWalletUpdate>,
+    pub dev_exemption_expires_at: Option<i64>, // After this time dev wallets are capped like everyone else
+    pub dev_exemption_finalized: bool,         // Set by the finalize_dev_exemption crank
+    pub airdrop_allowance: u32,                // Remaining over-cap transfers allowed from dev wallets
+    pub permit_signer: Pubkey,                 // Signs one-off over-cap permits (zero = permits disabled)
+    pub buy_cap_raw: u64,                      // Cap applied to transfers out of registered pool vaults
+    pub max_sell_raw: u64,                     // Largest transfer into a registered pool vault (0 = no limit)
+    pub circuit_breaker_threshold_raw: u64,    // Volume per window that trips the breaker (0 = disabled)
+    pub circuit_breaker_window_slots: u64,     // Length of the volume window in slots
+    pub circuit_breaker_cooldown: i64,         // Seconds transfers stay paused once tripped
+    pub window_start_slot: u64,
+    pub window_volume: u64,
+    pub paused_until: i64, // Unix timestamp; transfers to non-exempt destinations fail before it
+    pub holder_count: u32, // Owners with a non-zero tracked balance
+    pub max_holders: u32,  // 0 = no limit and no holder tracking
+    pub cap_unit: CapUnit, // Whether caps compare against raw or interest-adjusted (UI) balances
+    pub pending_governance_authority: Option<Pubkey>, // Proposed authority; takes over once it accepts
+    pub governance_renounced: bool, // Once set, no governance instruction can run again
+    pub governance_members: [Pubkey; MAX_GOVERNANCE_MEMBERS], // Pubkey::default() marks an empty slot
+    pub governance_threshold: u8, // Member approvals each governance instruction needs; 0 = single authority
+    pub governance_epoch: u64,    // Bumped whenever approvals are used or members change, staling older ones
+    pub guardian: Option<Pubkey>, // May pause transfers, nothing else
+    pub timelock_seconds: u32,    // Delay between proposing and executing timelocked changes
+    pub pending_timelock_update: Option<PendingTimelockUpdate>,
+    pub proposal_expiry_seconds: u32, // How long a matured cap proposal stays executable
+    pub proposal_nonce: u64,          // Nonce of the most recent cap proposal; 0 before the first
+    pub proposer_authority: Pubkey,   // Signs cap proposals in place of the governance authority
+    pub executor_authority: Pubkey,   // Only key that may execute matured cap proposals; default = anyone
+    pub veto_authority: Option<Pubkey>, // May cancel pending changes, nothing else
+    pub pending_veto_authority_update: Option<PendingVetoAuthorityUpdate>,
+    pub min_cap_floor: u64, // Lowest wallet cap governance may set; can only ever be raised
+    pub max_cap_change_bps: u16, // Largest wallet cap swing per change, in bps of the current cap; 0 = no limit
+    pub frozen: bool,            // Once set, nothing but the transfer hook can touch the config again
+    pub bump: u8,                // Canonical bump of this config PDA, so the hook need not re-derive it
+    pub emit_transfer_events: bool, // Emit HookTransferChecked for every transfer; costs compute, so off by default
+    pub total_transfers: u64,       // Transfers the hook has let through, zero-amount and self transfers aside
+    pub total_volume: u64,          // Raw amount of those transfers; saturates rather than failing a transfer
+    pub snapshot_count: u64,        // Snapshots taken so far; the next one gets this index
+    pub last_snapshot_slot: u64,    // Slot of the latest snapshot, for take_snapshot's rate limit
+    pub pending_hook_disable: Option<i64>, // Execution time of a proposed permanent disable
+    pub hook_disabled: bool,               // Once set, the hook lets every transfer through; can never be unset
+    pub dev_wallet_ata: Pubkey,            // Dev wallet slot 0's ATA, see refresh_dev_wallet_ata
+    pub staking_program_slot: u8, // Trusted program slot holding the staking program, plus one; 0 = none
+    pub allow_confidential: bool, // The mint has confidential transfers and `initialize` was told to accept them
+    pub require_wallet_owners: bool, // Destinations owned by a program-derived address must be exempt
+    pub finalized: bool, // finalize_setup has checked the meta list and the mint's hook; nothing moves before
+    pub exempt_registry_bump: u8, // See store_pda_bumps
+    pub used_nonces_bump: u8,
+    // Zeroed tail that new fields are carved out of, so adding one doesn't resize existing configs
+    pub reserved: [u8; HOOK_CONFIG_RESERVED_LEN],
+}
+
+impl HookConfig {
+    pub const SPACE: usize = 8 + HookConfig::INIT_SPACE;
+
+    /// Whether this build understands the stored layout: version 0 is never written, so it marks garbage
+    pub fn is_supported_version(&self) -> bool {
+        (1..=SUPPORTED_CONFIG_VERSION).contains(&self.version)
+    }
+
+    /// Derive and store the bumps of the mint's exempt registry and used nonces PDAs, at `initialize` and
+    /// `migrate_config`. The hook checks both addresses on every transfer, whether or not the accounts have
+    /// been created, and with the bumps stored that is a `create_program_address` instead of a search.
+    pub fn store_pda_bumps(&mut self, mint: &Pubkey) {
+        self.exempt_registry_bump = Pubkey::find_program_address(&[b"exempt-registry", mint.as_ref()], &crate::ID).1;
+        self.used_nonces_bump = Pubkey::find_program_address(&[b"used-nonces", mint.as_ref()], &crate::ID).1;
+    }
+
+    /// Current value of the given cap
+    pub fn cap(&self, kind: CapKind) -> u64 {
+        match kind {
+            CapKind::Wallet => self.wallet_cap_raw,
+            CapKind::Buy => self.buy_cap_raw,
+            CapKind::Sell => self.max_sell_raw,
+        }
+    }
+
+    /// Replace the given cap, returning its previous value
+    pub fn set_cap(&mut self, kind: CapKind, new_cap: u64) -> u64 {
+        let cap = match kind {
+            CapKind::Wallet => &mut self.wallet_cap_raw,
+            CapKind::Buy => &mut self.buy_cap_raw,
+            CapKind::Sell => &mut self.max_sell_raw,
+        };
+        std::mem::replace(cap, new_cap)
+    }
+
+    /// Raise the wallet cap without a timelock, returning its previous value. Anything that isn't a
+    /// strict increase has to be proposed instead.
+    pub fn apply_immediate_cap_increase(&mut self, new_cap: u64) -> Result<u64> {
+        validate_cap_proposal(CapKind::Wallet, new_cap)?;
+        self.check_cap_floor(CapKind::Wallet, new_cap)?;
+        self.check_cap_change(CapKind::Wallet, new_cap)?;
+        require!(new_cap > self.wallet_cap_raw, HookError::TimelockRequired);
+        Ok(self.set_cap(CapKind::Wallet, new_cap))
+    }
+
+    /// Reject wallet caps below the floor; other cap kinds have no floor here
+    pub fn check_cap_floor(&self, kind: CapKind, new_cap: u64) -> Result<()> {
+        if kind == CapKind::Wallet {
+            require!(new_cap >= self.min_cap_floor, HookError::CapBelowFloor);
+        }
+        Ok(())
+    }
+
+    /// Reject wallet cap changes that move the cap by more than max_cap_change_bps of its current value
+    pub fn check_cap_change(&self, kind: CapKind, new_cap: u64) -> Result<()> {
+        if kind != CapKind::Wallet || self.max_cap_change_bps == 0 {
+            return Ok(());
+        }
+        // Compare change * 10_000 against cap * bps so nothing is lost to rounding
+        let change = u128::from(new_cap.abs_diff(self.wallet_cap_raw));
+        let scaled_change = change.checked_mul(10_000).ok_or(ProgramError::ArithmeticOverflow)?;
+        let allowed = u128::from(self.wallet_cap_raw)
+            .checked_mul(u128::from(self.max_cap_change_bps))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        require!(scaled_change <= allowed, HookError::CapChangeTooLarge);
+        Ok(())
+    }
+
+    /// Raise the cap floor, returning the old one. The floor can't go down, and can't go above the
+    /// current wallet cap.
+    pub fn raise_min_cap_floor(&mut self, min_cap_floor: u64) -> Result<u64> {
+        require!(min_cap_floor >= self.min_cap_floor, HookError::CapFloorLowered);
+        require!(min_cap_floor <= self.wallet_cap_raw, HookError::CapBelowFloor);
+        Ok(std::mem::replace(&mut self.min_cap_floor, min_cap_floor))
+    }
+
+    /// Narrow the largest wallet cap swing per change, returning the old limit. It can only get tighter,
+    /// and 0 (no limit) is never tighter than a limit
+    pub fn tighten_max_cap_change_bps(&mut self, max_cap_change_bps: u16) -> Result<u16> {
+        require!(
+            max_cap_change_bps != 0 && (self.max_cap_change_bps == 0 || max_cap_change_bps <= self.max_cap_change_bps),
+            HookError::CapChangeLimitLoosened
+        );
+        Ok(std::mem::replace(&mut self.max_cap_change_bps, max_cap_change_bps))
+    }
+
+    /// Whether the circuit breaker is holding transfers at `now`
+    pub fn is_paused(&self, now: i64) -> bool {
+        now < self.paused_until
+    }
+
+    /// Add `amount` to the current volume window, starting a new window lazily once `slot` is past
+    /// the old one. Returns true if this transfer tripped the breaker.
+    pub fn record_volume(&mut self, amount: u64, slot: u64, now: i64) -> bool {
+        if self.circuit_breaker_threshold_raw == 0 {
+            return false;
+        }
+        if slot >= self.window_start_slot.saturating_add(self.circuit_breaker_window_slots) {
+            self.window_start_slot = slot;
+            self.window_volume = 0;
+        }
+        self.window_volume = self.window_volume.saturating_add(amount);
+
+        if self.window_volume > self.circuit_breaker_threshold_raw && !self.is_paused(now) {
+            self.paused_until = now.saturating_add(self.circuit_breaker_cooldown);
+            return true;
+        }
+        false
+    }
+
+    /// Claim the next snapshot index at `slot`; after the first, snapshots must be SNAPSHOT_INTERVAL_SLOTS apart
+    pub fn claim_snapshot(&mut self, slot: u64) -> Result<u64> {
+        require!(
+            self.snapshot_count == 0 || slot >= self.last_snapshot_slot.saturating_add(SNAPSHOT_INTERVAL_SLOTS),
+            HookError::SnapshotTooSoon
+        );
+        let index = self.snapshot_count;
+        self.snapshot_count = index.checked_add(1).ok_or(HookError::ArithmeticOverflow)?;
+        self.last_snapshot_slot = slot;
+        Ok(index)
+    }
+
+    /// Count a transfer of `amount` the hook let through
+    pub fn record_transfer_stats(&mut self, amount: u64) -> Result<()> {
+        self.total_transfers = self.total_transfers.checked_add(1).ok_or(HookError::ArithmeticOverflow)?;
+        self.total_volume = self.total_volume.saturating_add(amount);
+        Ok(())
+    }
+
+    /// Clear any pause and start a fresh volume window at `slot`
+    pub fn reset_circuit_breaker(&mut self, slot: u64) {
+        self.paused_until = 0;
+        self.window_start_slot = slot;
+        self.window_volume = 0;
+    }
+
+    /// Move `amount` between two owners' tracked balances, counting owners that exit (balance reaches
+    /// zero) and enter (balance leaves zero). The source is applied first so a wallet handing its
+    /// whole balance to a new one does not need a free slot.
+    pub fn apply_holder_transfer(
+        &mut self,
+        source: &mut HolderState,
+        destination: &mut HolderState,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        // Balances received before tracking started are unknown, so the source saturates at zero
+        let source_before = source.balance;
+        source.balance = source.balance.saturating_sub(amount);
+        if source_before > 0 && source.balance == 0 {
+            self.holder_count = self.holder_count.saturating_sub(1);
+        }
+
+        if destination.balance == 0 {
+            require!(
+                self.max_holders == 0 || self.holder_count < self.max_holders,
+                HookError::MaxHoldersReached
+            );
+            self.holder_count = self.holder_count.checked_add(1).ok_or(HookError::ArithmeticOverflow)?;
+        }
+        destination.balance = destination.balance.saturating_add(amount);
+        Ok(())
+    }
+
+    /// Whether a sell of `amount` is over the sell limit (if one is set)
+    pub fn sell_limit_exceeded(&self, amount: u64) -> bool {
+        self.max_sell_raw != 0 && amount > self.max_sell_raw
+    }
+
+    /// Whether `owner` is one of the configured dev wallets
+    pub fn is_dev_wallet(&self, owner: &Pubkey) -> bool {
+        *owner != Pubkey::default() && self.dev_wallets.contains(owner)
+    }
+
+    /// Cache dev wallet slot 0's associated token account, so `check_receive` can recognize the most
+    /// common exempt destination by address before unpacking it. Call whenever slot 0 changes; an empty
+    /// slot clears the cache.
+    pub fn refresh_dev_wallet_ata(&mut self, mint: &Pubkey) {
+        self.dev_wallet_ata = if self.dev_wallets[0] == Pubkey::default() {
+            Pubkey::default()
+        } else {
+            get_associated_token_address_with_program_id(&self.dev_wallets[0], mint, &TOKEN_2022_PROGRAM_ID)
+        };
+    }
+
+    /// Whether `token_account` is dev wallet slot 0's associated token account, as cached by
+    /// `refresh_dev_wallet_ata`. Token-2022 ATAs have an immutable owner, so it is still the wallet's
+    pub fn is_dev_wallet_ata(&self, token_account: &Pubkey) -> bool {
+        self.dev_wallet_ata != Pubkey::default() && *token_account == self.dev_wallet_ata
+    }
+
+    /// Whether the dev wallet exemption still applies at `now`
+    pub fn dev_exemption_active(&self, now: i64) -> bool {
+        self.dev_exemption_expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+
+    /// Set the dev exemption expiry, refusing to extend an existing one. Returns the previous expiry.
+    pub fn shorten_dev_exemption(&mut self, expires_at: i64) -> Result<Option<i64>> {
+        let old_expires_at = self.dev_exemption_expires_at;
+        if let Some(current) = old_expires_at {
+            require!(expires_at < current, HookError::DevExemptionExtension);
+        }
+        self.dev_exemption_expires_at = Some(expires_at);
+        Ok(old_expires_at)
+    }
+
+    /// Authorize a governance instruction. With multisig governance off the governance authority must sign;
+    /// with it on, anyone may submit `action` together with an approval from the current epoch that reached
+    /// the threshold. Using an approval advances the epoch, so it can never be used twice.
+    pub fn authorize(
+        &mut self,
+        signer: &Pubkey,
+        approval: Option<&GovernanceApproval>,
+        mint: &Pubkey,
+        action: &GovernanceAction,
+    ) -> Result<()> {
+        if self.governance_threshold == 0 {
+            require_keys_eq!(*signer, self.governance_authority, HookError::UnauthorizedGovernance);
+            return Ok(());
+        }
+
+        let approval = approval.ok_or(HookError::GovernanceApprovalRequired)?;
+        require!(approval.epoch == self.governance_epoch, HookError::StaleGovernanceApproval);
+        require!(
+            approval.action_hash == action.hash(mint, self.governance_epoch),
+            HookError::GovernanceActionMismatch
+        );
+        require!(
+            approval.approval_count() >= self.governance_threshold,
+            HookError::GovernanceThresholdNotMet
+        );
+        self.governance_epoch += 1;
+        Ok(())
+    }
+
+    /// Authorize a cap proposal. The proposer authority signs where other instructions need the governance
+    /// authority; multisig governance still needs a threshold approval.
+    pub fn authorize_proposal(
+        &mut self,
+        signer: &Pubkey,
+        approval: Option<&GovernanceApproval>,
+        mint: &Pubkey,
+        action: &GovernanceAction,
+    ) -> Result<()> {
+        if self.governance_threshold == 0 {
+            require_keys_eq!(*signer, self.proposer_authority, HookError::UnauthorizedGovernance);
+            return Ok(());
+        }
+        self.authorize(signer, approval, mint, action)
+    }
+
+    /// Authorize canceling a cap proposal: the proposer authority may withdraw proposals, otherwise it is governance
+    pub fn authorize_cancel(
+        &mut self,
+        signer: &Pubkey,
+        approval: Option<&GovernanceApproval>,
+        mint: &Pubkey,
+        action: &GovernanceAction,
+    ) -> Result<()> {
+        if *signer == self.proposer_authority {
+            return Ok(());
+        }
+        self.authorize(signer, approval, mint, action)
+    }
+
+    /// Whether `signer` is the veto authority, which may cancel pending changes without governance
+    pub fn is_veto_authority(&self, signer: &Pubkey) -> bool {
+        self.veto_authority == Some(*signer)
+    }
+
+    /// Authorize executing a matured cap proposal
+    pub fn authorize_execution(&self, executor: &Pubkey) -> Result<()> {
+        if self.executor_authority != Pubkey::default() {
+            require_keys_eq!(*executor, self.executor_authority, HookError::UnauthorizedExecutor);
+        }
+        Ok(())
+    }
+
+    /// Authorize `set_pause`: the guardian may pause on its own signature, everything else is governance
+    pub fn authorize_pause(
+        &mut self,
+        signer: &Pubkey,
+        approval: Option<&GovernanceApproval>,
+        mint: &Pubkey,
+        paused: bool,
+    ) -> Result<()> {
+        let is_guardian = self.guardian == Some(*signer);
+        if paused && is_guardian {
+            return Ok(());
+        }
+
+        let authorized = self.authorize(signer, approval, mint, &GovernanceAction::SetPause { paused });
+        if authorized.is_err() && is_guardian {
+            return err!(HookError::GuardianCannotUnpause);
+        }
+        authorized
+    }
+
+    /// Slot of `member` in governance_members
+    pub fn governance_member_index(&self, member: &Pubkey) -> Option<usize> {
+        if *member == Pubkey::default() {
+            return None;
+        }
+        self.governance_members.iter().position(|m| m == member)
+    }
+
+    /// Replace the governance members and threshold, staling every outstanding approval
+    pub fn set_governance_members(
+        &mut self,
+        members: [Pubkey; MAX_GOVERNANCE_MEMBERS],
+        threshold: u8,
+    ) -> Result<()> {
+        let active: Vec<&Pubkey> = members.iter().filter(|m| **m != Pubkey::default()).collect();
+        for (i, member) in active.iter().enumerate() {
+            require!(!active[i + 1..].contains(member), HookError::InvalidGovernanceMembers);
+        }
+        require!(threshold as usize <= active.len(), HookError::InvalidGovernanceMembers);
+
+        self.governance_members = members;
+        self.governance_threshold = threshold;
+        self.governance_epoch += 1;
+        Ok(())
+    }
+
+    /// Give up governance for good, clearing everything still pending, and return the old authority
+    pub fn renounce_governance(&mut self) -> Pubkey {
+        let old_authority = self.governance_authority;
+        self.governance_renounced = true;
+        self.governance_authority = Pubkey::default();
+        self.clear_pending_updates();
+        self.guardian = None;
+        old_authority
+    }
+
+    /// Freeze every parameter for good, clearing everything still pending
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+        self.clear_pending_updates();
+    }
+
+    pub(crate) fn clear_pending_updates(&mut self) {
+        self.pending_cap_update = None;
+        self.pending_dev_wallet_update = None;
+        self.pending_governance_authority = None;
+        self.pending_timelock_update = None;
+        self.pending_veto_authority_update = None;
+        self.pending_hook_disable = None;
+    }
+
+    /// Use up one over-cap airdrop, returning false once the allowance is exhausted
+    pub fn consume_airdrop_allowance(&mut self) -> bool {
+        match self.airdrop_allowance.checked_sub(1) {
+            Some(remaining) => {
+                self.airdrop_allowance = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Assign the nonce for a new cap proposal
+    pub fn next_proposal_nonce(&mut self) -> u64 {
+        self.proposal_nonce += 1;
+        self.proposal_nonce
+    }
+
+    /// Remove the pending cap update if it has expired
+    pub fn take_expired_cap_update(&mut self, now: i64) -> Result<PendingCapUpdate> {
+        let pending = self.pending_cap_update.as_ref().ok_or(HookError::NoPendingUpdate)?;
+        require!(pending.is_expired(now), HookError::ProposalNotExpired);
+        Ok(self.pending_cap_update.take().unwrap())
+    }
+
+    /// Delay applied to new timelocked proposals
+    pub fn timelock_duration(&self) -> i64 {
+        i64::from(self.timelock_seconds)
+    }
+
+    /// When a timelocked proposal made at `now` becomes executable
+    pub fn timelock_execution_time(&self, now: i64) -> Result<i64> {
+        Ok(now.checked_add(self.timelock_duration()).ok_or(HookError::ArithmeticOverflow)?)
+    }
+
+    /// Apply the pending timelock duration change once its own timelock has expired, returning (old, new)
+    pub fn apply_pending_timelock_update(&mut self, now: i64) -> Result<(u32, u32)> {
+        let pending = self
+            .pending_timelock_update
+            .as_ref()
+            .ok_or(HookError::NoPendingUpdate)?;
+        require!(now >= pending.execution_time, HookError::TimelockNotExpired);
+
+        let old_timelock_seconds = self.timelock_seconds;
+        self.timelock_seconds = pending.timelock_seconds;
+        self.pending_timelock_update = None;
+        Ok((old_timelock_seconds, self.timelock_seconds))
+    }
+
+    /// Schedule disabling the hook for good, HOOK_DISABLE_TIMELOCK_SECONDS from `now`; returns the execution time
+    pub fn propose_hook_disable(&mut self, now: i64) -> Result<i64> {
+        require!(!self.hook_disabled, HookError::HookDisabled);
+        require!(self.pending_hook_disable.is_none(), HookError::HookDisableAlreadyPending);
+        let execution_time = now.checked_add(HOOK_DISABLE_TIMELOCK_SECONDS).ok_or(HookError::ArithmeticOverflow)?;
+        self.pending_hook_disable = Some(execution_time);
+        Ok(execution_time)
+    }
+
+    /// Disable the hook once the pending disable's timelock has expired. Nothing clears `hook_disabled` again.
+    pub fn apply_hook_disable(&mut self, now: i64) -> Result<()> {
+        let execution_time = self.pending_hook_disable.ok_or(HookError::NoPendingUpdate)?;
+        require!(now >= execution_time, HookError::TimelockNotExpired);
+        self.pending_hook_disable = None;
+        self.hook_disabled = true;
+        Ok(())
+    }
+
+    /// Apply the pending veto authority change once its timelock has expired, returning (old, new)
+    pub fn apply_pending_veto_authority_update(&mut self, now: i64) -> Result<(Option<Pubkey>, Option<Pubkey>)> {
+        let pending = self
+            .pending_veto_authority_update
+            .as_ref()
+            .ok_or(HookError::NoPendingUpdate)?;
+        require!(now >= pending.execution_time, HookError::TimelockNotExpired);
+
+        let old_veto_authority = std::mem::replace(&mut self.veto_authority, pending.veto_authority);
+        self.pending_veto_authority_update = None;
+        Ok((old_veto_authority, self.veto_authority))
+    }
+
+    /// Queue `new_wallet` for dev wallet slot `index` behind the timelock, returning (old, execution time)
+    pub fn propose_dev_wallet_update(&mut self, index: u8, new_wallet: Pubkey, now: i64) -> Result<(Pubkey, i64)> {
+        let old_wallet = *self
+            .dev_wallets
+            .get(index as usize)
+            .ok_or(HookError::InvalidDevWalletSlot)?;
+
+        let execution_time = self.timelock_execution_time(now)?;
+
+        self.pending_dev_wallet_update = Some(PendingDevWalletUpdate {
+            index,
+            new_wallet,
+            proposed_at: now,
+            execution_time,
+        });
+        Ok((old_wallet, execution_time))
+    }
+
+    /// Apply the pending dev wallet update once its timelock has expired, returning (index, old, new)
+    pub fn apply_pending_dev_wallet_update(&mut self, now: i64) -> Result<(u8, Pubkey, Pubkey)> {
+        let pending = self
+            .pending_dev_wallet_update
+            .as_ref()
+            .ok_or(HookError::NoPendingUpdate)?;
+        
+        require!(now >= pending.execution_time, HookError::TimelockNotExpired);
+        
+        let index = pending.index;
+        let new_wallet = pending.new_wallet;
+        let slot = self
+            .dev_wallets
+            .get_mut(index as usize)
+            .ok_or(HookError::InvalidDevWalletSlot)?;
+        let old_wallet = *slot;
+        *slot = new_wallet;
+        self.pending_dev_wallet_update = None;
+        
+        Ok((index, old_wallet, new_wallet))
+    }
+
+    /// Whether `program_id` is one of the configured trusted AMM programs
+    pub fn is_trusted_program(&self, program_id: &Pubkey) -> bool {
+        *program_id != Pubkey::default() && self.trusted_programs.contains(program_id)
+    }
+
+    /// Whether `seeds` derive a destination owner as a PDA of `program`, a trusted program. Owning the
+    /// account isn't enough: a keypair account or another program's PDA can be assigned to a trusted
+    /// program, but neither derives from it.
+    pub fn is_trusted_pda(&self, owner: &Pubkey, program: &Pubkey, seeds: &[Vec<u8>]) -> bool {
+        self.is_trusted_program(program) && is_program_address(owner, program, seeds)
+    }
+
+    /// The trusted program `set_staking_program` designated, if any
+    pub fn staking_program(&self) -> Option<Pubkey> {
+        let index = usize::from(self.staking_program_slot.checked_sub(1)?);
+        self.trusted_programs.get(index).copied().filter(|program_id| *program_id != Pubkey::default())
+    }
+
+    /// Whether `seeds` derive a vault owner as a PDA of `program`, the staking program, as `is_trusted_pda`
+    /// checks for pool authorities
+    pub fn is_staking_pda(&self, owner: &Pubkey, program: &Pubkey, seeds: &[Vec<u8>]) -> bool {
+        self.staking_program() == Some(*program) && is_program_address(owner, program, seeds)
+    }
+}
+
+/// Token accounts (e.g. AMM pool vaults) exempt from the cap, each optionally until an expiry
+#[account]
+pub struct ExemptRegistry {
+    pub rent_recipient: Pubkey, // Receives rent freed when entries are removed or pruned
+    pub entries: Vec<ExemptEntry>,
+}
+
+impl ExemptRegistry {
+    /// Account space for a registry holding `entries` entries
+    pub const fn space(entries: usize) -> usize {
+        8 + 32 + 4 + entries * ExemptEntry::SIZE
+    }
+
+    /// Whether `token_account` has an exemption that is still live at `now`
+    pub fn is_exempt(&self, token_account: &Pubkey, now: i64) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.token_account == *token_account && !entry.is_expired(now))
+    }
+
+    /// Whether `token_account` is a live registered pool vault at `now`
+    pub fn is_pool_vault(&self, token_account: &Pubkey, now: i64) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.token_account == *token_account && entry.kind == ExemptKind::PoolVault && !entry.is_expired(now)
+        })
+    }
+
+    /// Whether `token_account` is a live registered staking vault at `now`
+    pub fn is_staking_vault(&self, token_account: &Pubkey, now: i64) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.token_account == *token_account && entry.kind == ExemptKind::StakingVault && !entry.is_expired(now)
+        })
+    }
+
+    /// Whether `token_account` is a live registered bridge custody account at `now`
+    pub fn is_bridge_custody(&self, token_account: &Pubkey, now: i64) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.token_account == *token_account && entry.kind == ExemptKind::BridgeCustody && !entry.is_expired(now)
+        })
+    }
+
+    /// Remove every entry expired at `now`, returning how many were removed
+    pub fn prune_expired(&mut self, now: i64) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| !entry.is_expired(now));
+        before - self.entries.len()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct ExemptEntry {
+    pub token_account: Pubkey,
+    pub kind: ExemptKind,
+    pub expires_at: Option<i64>, // None = no expiry
+}
+
+impl ExemptEntry {
+    pub const SIZE: usize = 32 + 1 + 1 + 8;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.map_or(false, |expires_at| now >= expires_at)
+    }
+}
+
+/// Tracked balance of one owner, used to count holders, and the owner's lifetime transfer stats
+#[account]
+pub struct HolderState {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub balance: u64, // Sum received minus sum sent since the account was created, while holder tracking is on
+    pub lifetime_received: u64,
+    pub lifetime_sent: u64,
+    pub transfer_count: u32, // Transfers in or out
+    pub first_seen_ts: i64,  // Time of the first recorded transfer; 0 before it
+}
+
+impl HolderState {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 4 + 8;
+
+    /// Count a transfer of `amount` to the owner. Stats saturate so they can never block a transfer.
+    pub fn record_received(&mut self, amount: u64, now: i64) {
+        self.lifetime_received = self.lifetime_received.saturating_add(amount);
+        self.record_transfer(now);
+    }
+
+    /// Count a transfer of `amount` from the owner
+    pub fn record_sent(&mut self, amount: u64, now: i64) {
+        self.lifetime_sent = self.lifetime_sent.saturating_add(amount);
+        self.record_transfer(now);
+    }
+
+    fn record_transfer(&mut self, now: i64) {
+        self.transfer_count = self.transfer_count.saturating_add(1);
+        if self.first_seen_ts == 0 {
+            self.first_seen_ts = now;
+        }
+    }
+}
+
+/// Outbound lockup of one owner, such as a team allocation. Nothing can leave the owner's accounts before
+/// `locked_until`; with a `linear_release_end`, `locked_amount` then unlocks linearly until that time.
+#[account]
+pub struct Lockup {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub locked_amount: u64, // Raw amount still held back when the linear release starts
+    pub locked_until: i64,  // Cliff
+    pub linear_release_end: Option<i64>, // None unlocks everything at the cliff
+}
+
+impl Lockup {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 9;
+
+    /// Raw amount the owner must keep at `now`: everything before the cliff, then a linearly shrinking
+    /// share of `locked_amount` until the release ends
+    pub fn locked_at(&self, now: i64) -> u64 {
+        if now < self.locked_until {
+            return u64::MAX;
+        }
+        match self.linear_release_end {
+            Some(end) if now < end => {
+                // Widened so schedules at the far ends of the i64 range can't overflow
+                let remaining = (i128::from(end) - i128::from(now)) as u128;
+                let duration = (i128::from(end) - i128::from(self.locked_until)) as u128;
+                (self.locked_amount as u128 * remaining / duration) as u64
+            }
+            _ => 0,
+        }
+    }
+
+    /// Move the cliff and release end earlier. Neither may ever move later, so a lockup can't hold
+    /// tokens longer than it promised when they were sent to the owner.
+    pub fn shorten(&mut self, locked_until: i64, linear_release_end: Option<i64>) -> Result<()> {
+        validate_lockup_schedule(locked_until, linear_release_end)?;
+        let released_at = |cliff: i64, end: Option<i64>| end.unwrap_or(cliff);
+        require!(
+            locked_until <= self.locked_until
+                && released_at(locked_until, linear_release_end)
+                    <= released_at(self.locked_until, self.linear_release_end),
+            HookError::LockupExtension
+        );
+        self.locked_until = locked_until;
+        self.linear_release_end = linear_release_end;
+        Ok(())
+    }
+}
+
+/// Record that `owner` is a PDA account of a trusted program, made by `register_trusted_owner`. Token-2022
+/// can't pass the hook an owner's account, only PDAs derived from it, so the hook reads this instead.
+#[account]
+pub struct TrustedOwner {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub program: Pubkey, // Program the owner was derived from, which owned its account when registered
+}
+
+impl TrustedOwner {
+    pub const SPACE: usize = 8 + 32 + 32 + 32;
+}
+
+/// Per-mint hook state that doesn't fit in HookConfig's reserved bytes. The hook reads it on every
+/// transfer, but a mint whose extension hasn't been created yet simply records nothing in it.
+#[account]
+pub struct ConfigExtension {
+    pub mint: Pubkey,
+    pub launch_detected_at: Option<i64>, // Time of the first buy out of a registered pool vault
+    pub launch_detected_slot: Option<u64>,
+    pub launch_window_used: bool,  // A snipe window has been measured from the launch, which fixes it for good
+    pub launch_reset_used: bool,   // reset_launch_detection has been spent
+    pub snipe_window_seconds: u32, // Buys this soon after the launch flag their buyer; 0 flags nobody
+    pub memo_required_above: Option<u64>, // Larger transfers between non-exempt accounts need a memo
+    pub dev_daily_outflow_limit: Option<u64>, // Most the dev wallets may sell into pool vaults per window
+    pub dev_outflow_window_start: i64,
+    pub dev_outflow_in_window: u64,
+    pub reserved: [u8; CONFIG_EXTENSION_RESERVED_LEN],
+}
+
+impl ConfigExtension {
+    pub const SPACE: usize = 8 + 32 + 9 + 9 + 1 + 1 + 4 + 9 + 9 + 8 + 8 + CONFIG_EXTENSION_RESERVED_LEN;
+
+    /// Record the launch at the first buy; a launch already recorded is kept. Returns whether this call
+    /// recorded it.
+    pub fn record_launch(&mut self, now: i64, slot: u64) -> bool {
+        if self.launch_detected_at.is_some() {
+            return false;
+        }
+        self.launch_detected_at = Some(now);
+        self.launch_detected_slot = Some(slot);
+        true
+    }
+
+    /// Seconds since the detected launch, or None before one has been detected
+    pub fn seconds_since_launch(&self, now: i64) -> Option<i64> {
+        self.launch_detected_at.map(|detected_at| now.saturating_sub(detected_at))
+    }
+
+    /// Whether `now` falls within the `window_seconds` after the detected launch; never before a launch.
+    /// Measuring a window from the launch takes away governance's chance to reset it.
+    pub fn in_launch_window(&mut self, now: i64, window_seconds: i64) -> bool {
+        let Some(elapsed) = self.seconds_since_launch(now) else {
+            return false;
+        };
+        self.launch_window_used = true;
+        elapsed < window_seconds
+    }
+
+    /// Whether a buy at `now` lands in the snipe window. A window of 0 flags nobody and measures nothing.
+    pub fn is_snipe(&mut self, now: i64) -> bool {
+        self.snipe_window_seconds > 0 && self.in_launch_window(now, i64::from(self.snipe_window_seconds))
+    }
+
+    /// Forget the detected launch so the next buy records it again, returning its time and slot. Allowed
+    /// once, and only while no snipe window has been measured from it.
+    pub fn reset_launch(&mut self) -> Result<(i64, u64)> {
+        let (Some(detected_at), Some(detected_slot)) = (self.launch_detected_at, self.launch_detected_slot) else {
+            return err!(HookError::LaunchNotDetected);
+        };
+        require!(!self.launch_window_used && !self.launch_reset_used, HookError::LaunchResetUnavailable);
+        self.launch_detected_at = None;
+        self.launch_detected_slot = None;
+        self.launch_reset_used = true;
+        Ok((detected_at, detected_slot))
+    }
+
+    /// Count a dev wallet's sell of `amount` into a pool vault at `now` against the daily outflow limit,
+    /// starting a new window once 24 hours have passed since the current one began. Nothing is counted
+    /// without a limit.
+    pub fn record_dev_outflow(&mut self, amount: u64, now: i64) -> Result<()> {
+        let Some(limit) = self.dev_daily_outflow_limit else {
+            return Ok(());
+        };
+        if now.saturating_sub(self.dev_outflow_window_start) >= DEV_OUTFLOW_WINDOW_SECONDS {
+            self.dev_outflow_window_start = now;
+            self.dev_outflow_in_window = 0;
+        }
+        let in_window = self.dev_outflow_in_window.checked_add(amount).ok_or(HookError::ArithmeticOverflow)?;
+        require!(in_window <= limit, HookError::DevOutflowLimitExceeded);
+        self.dev_outflow_in_window = in_window;
+        Ok(())
+    }
+
+    /// Set the dev wallets' daily outflow limit, returning the old one. Once set it can only be lowered.
+    pub fn lower_dev_outflow_limit(&mut self, limit: u64) -> Result<Option<u64>> {
+        require!(
+            self.dev_daily_outflow_limit.map_or(true, |current| limit <= current),
+            HookError::DevOutflowLimitRaised
+        );
+        Ok(self.dev_daily_outflow_limit.replace(limit))
+    }
+}
+
+/// Owners who bought out of a pool vault within the snipe window, for governance to review. Being listed
+/// blocks nothing. Once the log is full, later snipers only show in `SniperFlagged` events: the owners
+/// flagged first are never pushed out.
+#[account]
+pub struct SniperLog {
+    pub mint: Pubkey,
+    pub dropped: u32, // Flags that found the log full
+    pub sniper_list: Vec<Pubkey>,
+}
+
+impl SniperLog {
+    pub const SPACE: usize = 8 + 32 + 4 + 4 + SNIPER_LOG_LEN * 32;
+
+    /// List `owner`, once however often it buys. Returns whether it is listed, false if the log is full.
+    pub fn flag(&mut self, owner: Pubkey) -> bool {
+        if self.sniper_list.contains(&owner) {
+            return true;
+        }
+        if self.sniper_list.len() >= SNIPER_LOG_LEN {
+            self.dropped = self.dropped.saturating_add(1);
+            return false;
+        }
+        self.sniper_list.push(owner);
+        true
+    }
+
+    /// Take `owner` off the list, keeping the others in the order they were flagged
+    pub fn clear(&mut self, owner: &Pubkey) -> Result<()> {
+        let index = self.sniper_list.iter().position(|flagged| flagged == owner).ok_or(HookError::SniperNotFlagged)?;
+        self.sniper_list.remove(index);
+        Ok(())
+    }
+}
+
+/// Point-in-time record of the mint's supply and the config, for governance and analytics to cite
+#[account]
+pub struct Snapshot {
+    pub mint: Pubkey,
+    pub index: u64,
+    pub slot: u64,
+    pub unix_timestamp: i64,
+    pub supply: u64, // Raw mint supply
+    pub wallet_cap_raw: u64,
+    pub cap_unit: CapUnit,
+    pub paused: bool,
+    pub holder_count: u32, // Only maintained while holder tracking is on
+    pub total_transfers: u64,
+    pub total_volume: u64,
+    pub payer: Pubkey, // Refunded when governance closes the snapshot
+}
+
+impl Snapshot {
+    pub const SPACE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 4 + 8 + 8 + 32;
+}
+
+/// Settings of the whole deployment rather than of one mint, created once by the program's upgrade authority.
+/// Only ever read by `initialize_for_mint` and `adopt_global_defaults`, never on the transfer path.
+#[account]
+pub struct GlobalConfig {
+    pub factory_admin: Pubkey, // May initialize configs through `initialize_for_mint` without the mint authority
+    pub mint_count: u64,       // Mints registered so far; the next one goes on page current_page()
+    pub bump: u8,
+    // Filled in by `initialize_for_mint` for the parameters it isn't given; set by the factory admin
+    pub default_timelock_seconds: u32,
+    pub default_min_cap_floor: u64,
+    pub default_max_cap_change_bps: u16,
+}
+
+impl GlobalConfig {
+    pub const SPACE: usize = 8 + 32 + 8 + 1 + 4 + 8 + 2;
+
+    /// `adopt_global_defaults` field bits
+    pub const ADOPT_TIMELOCK: u8 = 1 << 0;
+    pub const ADOPT_MIN_CAP_FLOOR: u8 = 1 << 1;
+    pub const ADOPT_MAX_CAP_CHANGE_BPS: u8 = 1 << 2;
+    pub const ADOPT_ALL: u8 = Self::ADOPT_TIMELOCK | Self::ADOPT_MIN_CAP_FLOOR | Self::ADOPT_MAX_CAP_CHANGE_BPS;
+
+    /// The MintRegistry page the next registered mint is recorded on
+    pub fn current_page(&self) -> u32 {
+        (self.mint_count / MINTS_PER_REGISTRY_PAGE as u64) as u32
+    }
+}
+
+/// One page of the mints initialized through `initialize_for_mint`, so tooling can list every hooked mint
+/// with a single `getProgramAccounts` filtered on the discriminator
+#[account]
+pub struct MintRegistry {
+    pub page: u32,
+    pub entries: Vec<MintRegistryEntry>, // In registration order, up to MINTS_PER_REGISTRY_PAGE
+}
+
+impl MintRegistry {
+    pub const SPACE: usize = 8 + 4 + 4 + MINTS_PER_REGISTRY_PAGE * MintRegistryEntry::SIZE;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MintRegistryEntry {
+    pub mint: Pubkey,
+    pub creator: Pubkey, // Signer that initialized the config: the mint authority or the factory admin
+    pub created_slot: u64,
+}
+
+impl MintRegistryEntry {
+    pub const SIZE: usize = 32 + 32 + 8;
+}
+
+/// Multisig approvals collected for one governance action
+#[account]
+pub struct GovernanceApproval {
+    pub mint: Pubkey,
+    pub action_hash: [u8; 32], // GovernanceAction::hash of the approved call
+    pub epoch: u64,            // Governance epoch the approvals were given in
+    pub approvals: u8,         // Bitmap over governance_members slots
+    pub payer: Pubkey,         // Refunded when the approval is closed
+}
+
+impl GovernanceApproval {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1 + 32;
+
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.count_ones() as u8
+    }
+
+    /// Record `member`'s approval; it only counts in the epoch the approval was opened in
+    pub fn approve(&mut self, config: &HookConfig, member: &Pubkey) -> Result<()> {
+        require!(self.epoch == config.governance_epoch, HookError::StaleGovernanceApproval);
+        let index = config
+            .governance_member_index(member)
+            .ok_or(HookError::NotGovernanceMember)?;
+        let bit = 1u8 << index;
+        require!(self.approvals & bit == 0, HookError::GovernanceActionAlreadyApproved);
+        self.approvals |= bit;
+        Ok(())
+    }
+}
+
+/// Bitmap of permit nonces that have already been redeemed
+#[account]
+pub struct UsedNonces {
+    pub bitmap: [u8; USED_NONCES_BITMAP_LEN],
+}
+
+impl UsedNonces {
+    pub const SPACE: usize = 8 + USED_NONCES_BITMAP_LEN;
+
+    pub(crate) fn bit(nonce: u64) -> Result<(usize, u8)> {
+        let index = usize::try_from(nonce)
+            .ok()
+            .filter(|index| *index < USED_NONCES_BITMAP_LEN * 8)
+            .ok_or(HookError::PermitNonceOutOfRange)?;
+        Ok((index / 8, 1 << (index % 8)))
+    }
+
+    pub fn is_used(&self, nonce: u64) -> Result<bool> {
+        let (byte, mask) = Self::bit(nonce)?;
+        Ok(self.bitmap[byte] & mask != 0)
+    }
+
+    /// Record `nonce` as redeemed, failing if it already was
+    pub fn mark_used(&mut self, nonce: u64) -> Result<()> {
+        require!(!self.is_used(nonce)?, HookError::PermitNonceReplayed);
+        let (byte, mask) = Self::bit(nonce)?;
+        self.bitmap[byte] |= mask;
+        Ok(())
+    }
+}
+
+/// Ring buffer of the most recent cap governance actions, so tooling can reconstruct history without
+/// replaying events
+#[account]
+pub struct GovernanceLog {
+    pub total_entries: u64, // Entries ever recorded; the next one goes to total_entries % GOVERNANCE_LOG_LEN
+    pub entries: Vec<GovernanceLogEntry>,
+}
+
+impl GovernanceLog {
+    pub const SPACE: usize = 8 + 8 + 4 + GOVERNANCE_LOG_LEN * GovernanceLogEntry::SIZE;
+
+    /// Record `action` on `proposal`, overwriting the oldest entry once the log is full
+    pub fn record(
+        &mut self,
+        action: GovernanceLogAction,
+        proposal: &PendingCapUpdate,
+        timestamp: i64,
+        actor: Pubkey,
+    ) {
+        let entry = GovernanceLogEntry {
+            action,
+            kind: proposal.kind,
+            nonce: proposal.nonce,
+            value: proposal.new_cap,
+            timestamp,
+            actor,
+        };
+        if self.entries.len() < GOVERNANCE_LOG_LEN {
+            self.entries.push(entry);
+        } else {
+            let slot = (self.total_entries % GOVERNANCE_LOG_LEN as u64) as usize;
+            self.entries[slot] = entry;
+        }
+        self.total_entries += 1;
+    }
+
+    /// Entries `page * GOVERNANCE_LOG_PAGE_LEN..` counted from the oldest retained one
+    pub fn page(&self, page: u8) -> Vec<GovernanceLogEntry> {
+        let oldest = if self.entries.len() < GOVERNANCE_LOG_LEN {
+            0
+        } else {
+            (self.total_entries % GOVERNANCE_LOG_LEN as u64) as usize
+        };
+        (0..self.entries.len())
+            .map(|offset| self.entries[(oldest + offset) % self.entries.len()].clone())
+            .skip(usize::from(page) * GOVERNANCE_LOG_PAGE_LEN)
+            .take(GOVERNANCE_LOG_PAGE_LEN)
+            .collect()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceLogEntry {
+    pub action: GovernanceLogAction,
+    pub kind: CapKind,
+    pub nonce: u64,
+    pub value: u64, // Proposed cap
+    pub timestamp: i64,
+    pub actor: Pubkey, // Signer behind the action; default for permissionless sweeps
+}
+
+impl GovernanceLogEntry {
+    pub const SIZE: usize = 1 + 1 + 8 + 8 + 8 + 32;
+}
+
+/// What happened to a cap proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GovernanceLogAction {
+    Proposed,
+    Canceled,
+    Executed,
+    Expired,
+    Vetoed,
+}
+
+/// Snapshot of the config returned by `get_config`, so clients can read it without knowing the account
+/// layout. It is versioned on its own, so a config reads the same before and after a migration; fields
+/// are only ever appended, and CONFIG_VIEW_VERSION is raised when they are.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigView {
+    pub view_version: u8,
+    pub wallet_cap_raw: u64,
+    pub buy_cap_raw: u64,
+    pub max_sell_raw: u64,
+    pub cap_unit: CapUnit,
+    pub dev_wallets: [Pubkey; MAX_DEV_WALLETS],
+    pub governance_authority: Pubkey,
+    pub pending_cap_update: Option<CapProposalView>,
+    pub paused_until: i64,
+    pub paused: bool, // Whether transfers were paused at the time of the call
+    pub total_transfers: u64, // Added in view version 2
+    pub total_volume: u64,
+    pub allow_confidential: bool, // Added in view version 3
+    pub require_wallet_owners: bool, // Added in view version 4
+    pub finalized: bool, // Added in view version 5
+}
+
+impl ConfigView {
+    pub fn new(config: &HookConfig, now: i64) -> Self {
+        ConfigView {
+            view_version: CONFIG_VIEW_VERSION,
+            wallet_cap_raw: config.wallet_cap_raw,
+            buy_cap_raw: config.buy_cap_raw,
+            max_sell_raw: config.max_sell_raw,
+            cap_unit: config.cap_unit,
+            dev_wallets: config.dev_wallets,
+            governance_authority: config.governance_authority,
+            pending_cap_update: config.pending_cap_update.as_ref().map(|pending| CapProposalView {
+                kind: pending.kind,
+                new_cap: pending.new_cap,
+                proposed_at: pending.proposed_at,
+                execution_time: pending.execution_time,
+                expires_at: pending.expires_at,
+                nonce: pending.nonce,
+                proposed_by: pending.proposed_by,
+            }),
+            paused_until: config.paused_until,
+            paused: config.is_paused(now),
+            total_transfers: config.total_transfers,
+            total_volume: config.total_volume,
+            allow_confidential: config.allow_confidential,
+            require_wallet_owners: config.require_wallet_owners,
+            finalized: config.finalized,
+        }
+    }
+}
+
+/// The pending cap proposal as reported by `ConfigView`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CapProposalView {
+    pub kind: CapKind,
+    pub new_cap: u64,
+    pub proposed_at: i64,
+    pub execution_time: i64,
+    pub expires_at: i64,
+    pub nonce: u64,
+    pub proposed_by: Pubkey,
+}
+
+/// Preflight verdict returned by `can_receive`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CanReceiveResult {
+    pub allowed: bool,
+    pub reason_code: u8,         // A ReceiveReason
+    pub remaining_capacity: u64, // Room left under the cap after the transfer, in cap units; u64::MAX if exempt
+}
+
+/// `check_transfer`'s verdict, two bytes for a calling program to branch on after the CPI
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CheckTransferResult {
+    pub allowed: bool,
+    pub reason_code: u8, // A ReceiveReason
+}
+
+/// Why `can_receive` or `check_transfer` allowed or rejected a transfer; reported as `reason_code`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ReceiveReason {
+    Allowed = 0,      // Within the cap
+    Exempt = 1,       // Registry-exempt account, dev wallet or trusted program pool authority
+    Unchanged = 2,    // Zero amount, which moves nothing
+    OverCap = 3,      // Would leave the destination over its cap
+    Paused = 4,       // Transfers to non-exempt destinations are paused
+    HookDisabled = 5, // The hook has been permanently disabled and lets everything through
+    ProgramOwned = 6, // Owned by a program-derived address, which the config requires to be exempt
+    NotFinalized = 7, // finalize_setup hasn't been called, so no transfer goes through yet
+}
+
+/// Off-chain authorization for one over-cap receive. The permit signer signs
+/// `PERMIT_MESSAGE_PREFIX || borsh(Permit)` and the transaction carries an ed25519 verify instruction for it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Permit {
+    pub mint: Pubkey,
+    pub destination_owner: Pubkey,
+    pub max_amount: u64,
+    pub nonce: u64,
+    pub expiry: i64, // Unix timestamp after which the permit is invalid
+}
+
+impl Permit {
+    /// The exact bytes the permit signer signs
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = PERMIT_MESSAGE_PREFIX.to_vec();
+        self.serialize(&mut message).expect("writing to a Vec cannot fail");
+        message
+    }
+
+    pub fn decode(message: &[u8]) -> Option<Permit> {
+        let body = message.strip_prefix(PERMIT_MESSAGE_PREFIX)?;
+        Permit::try_from_slice(body).ok()
+    }
+
+    /// Check the permit covers this transfer; the nonce is checked separately against UsedNonces
+    pub fn check(&self, mint: &Pubkey, destination_owner: &Pubkey, amount: u64, now: i64) -> Result<()> {
+        require_keys_eq!(self.mint, *mint, HookError::PermitWrongMint);
+        require_keys_eq!(self.destination_owner, *destination_owner, HookError::PermitWrongDestination);
+        require!(now < self.expiry, HookError::PermitExpired);
+        require!(amount <= self.max_amount, HookError::PermitAmountExceeded);
+        Ok(())
+    }
+}
+
+/// What an exempt registry entry is, beyond being exempt from the cap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExemptKind {
+    Generic,
+    PoolVault,     // AMM pool vault; transfers out of it are buys
+    StakingVault,  // Vault of the staking program, see register_staking_vault; transfers out of it are unstakes
+    BridgeCustody, // Bridge custody account, see register_bridge_custody; only added by that instruction
+}
+
+/// A governance instruction with its parameters, as committed to by multisig approvals. New variants
+/// must be appended so existing hashes keep their meaning.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum GovernanceAction {
+    ProposeCapUpdate { kind: CapKind, new_cap: u64, overwrite: bool, memo_hash: [u8; 32] },
+    ExecuteCapUpdate, // Unused since cap execution became permissionless; kept for the variant order
+    CancelCapUpdate,
+    ProposeGovernanceTransfer { new_authority: Pubkey },
+    CancelGovernanceTransfer,
+    RenounceGovernance,
+    InitExemptRegistry { rent_recipient: Pubkey },
+    AddExemptEntry { token_account: Pubkey, kind: ExemptKind, expires_at: Option<i64> },
+    RemoveExemptEntry { token_account: Pubkey },
+    SetTrustedProgram { index: u8, program_id: Pubkey },
+    ProposeDevWalletUpdate { index: u8, new_wallet: Pubkey },
+    ExecuteDevWalletUpdate,
+    CancelDevWalletUpdate,
+    SetDevExemptionExpiry { expires_at: i64 },
+    SetAirdropAllowance { allowance: u32 },
+    SetPermitSigner { permit_signer: Pubkey },
+    InitUsedNonces,
+    SetCircuitBreaker { threshold_raw: u64, window_slots: u64, cooldown: i64 },
+    ClearCircuitBreaker,
+    SetMaxHolders { max_holders: u32 },
+    SetGovernanceMembers { members: [Pubkey; MAX_GOVERNANCE_MEMBERS], threshold: u8 },
+    SetGuardian { guardian: Option<Pubkey> },
+    SetPause { paused: bool },
+    ProposeTimelockUpdate { timelock_seconds: u32 },
+    ExecuteTimelockUpdate,
+    CancelTimelockUpdate,
+    SetProposalExpiry { expiry_seconds: u32 },
+    SetCapUpdateRole { role: CapUpdateRole, authority: Pubkey },
+    ProposeVetoAuthorityUpdate { veto_authority: Option<Pubkey> },
+    ExecuteVetoAuthorityUpdate,
+    CancelVetoAuthorityUpdate,
+    ImmediateCapIncrease { new_cap: u64 },
+    RaiseMinCapFloor { min_cap_floor: u64 },
+    FreezeConfig,
+    UpdateExtraAccountMetaList,
+    CloseExtraAccountMetaList { recipient: Pubkey },
+    CloseConfig { recipient: Pubkey },
+    SetTransferEvents { enabled: bool },
+    CloseSnapshot { index: u64 },
+    ProposeHookDisable,
+    PermanentlyDisableHook,
+    CancelHookDisable,
+    CreateLockup { owner: Pubkey, locked_amount: u64, locked_until: i64, linear_release_end: Option<i64> },
+    ShortenLockup { owner: Pubkey, locked_until: i64, linear_release_end: Option<i64> },
+    SetDevWalletVerified { index: u8, new_wallet: Pubkey },
+    // The global values are part of the action, so an approval lapses if the factory admin changes them
+    AdoptGlobalDefaults { fields: u8, timelock_seconds: u32, min_cap_floor: u64, max_cap_change_bps: u16 },
+    SetStakingProgram { index: u8, program_id: Pubkey },
+    RegisterBridgeCustody { token_account: Pubkey, bridge_program: Pubkey },
+    ResetLaunchDetection,
+    SetSnipeWindow { window_seconds: u32 },
+    ClearSniperFlag { owner: Pubkey },
+    SetRequireWalletOwners { enabled: bool },
+    SetMemoRequiredAbove { threshold: Option<u64> },
+    LowerDevOutflowLimit { limit: u64 },
+    WithdrawSurplusLamports { recipient: Pubkey },
+    RegisterStakingVault { token_account: Pubkey },
+}
+
+impl GovernanceAction {
+    /// Hash of the action bound to a mint and governance epoch, so an approval can't be replayed for other
+    /// parameters, another mint or after the members change
+    pub fn hash(&self, mint: &Pubkey, epoch: u64) -> [u8; 32] {
+        let action = self.try_to_vec().expect("serializing into a Vec cannot fail");
+        hashv(&[GOVERNANCE_ACTION_PREFIX, mint.as_ref(), &epoch.to_le_bytes(), &action]).to_bytes()
+    }
+}
+
+/// A governed cap or limit
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapKind {
+    Wallet,
+    Buy,
+    Sell, // max_sell_raw; 0 disables the sell limit
+}
+
+/// Delegated roles in the cap update lifecycle
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapUpdateRole {
+    Proposer,
+    Executor,
+}
+
+/// Units the balance caps are expressed in
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapUnit {
+    Raw, // Base units as stored in the token account
+    Ui,  // Base units after applying the mint's accrued interest, i.e. what wallets display
+}
+
+/// Version 1 HookConfig layout (single dev wallet), read only by `migrate_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct HookConfigV1 {
+    pub version: u8,
+    pub dev_wallet: Pubkey,
+    pub wallet_cap_raw: u64,
+    pub governance_authority: Pubkey,
+    pub pending_cap_update: Option<PendingCapUpdateV1>,
+}
+
+impl HookConfigV1 {
+    /// Convert to the v2 layout, keeping the existing dev wallet in slot 0. Its ATA isn't cached yet,
+    /// since that needs the mint; see `HookConfig::refresh_dev_wallet_ata`
+    pub fn into_v2(self, bump: u8) -> Result<HookConfig> {
+        let mut dev_wallets = [Pubkey::default(); MAX_DEV_WALLETS];
+        dev_wallets[0] = self.dev_wallet;
+        // A carried-over proposal becomes proposal 1
+        let proposal_nonce = u64::from(self.pending_cap_update.is_some());
+        let pending_cap_update = match self.pending_cap_update {
+            Some(pending) => Some(PendingCapUpdate {
+                kind: CapKind::Wallet,
+                new_cap: pending.new_cap,
+                proposed_at: pending.proposed_at,
+                execution_time: pending.execution_time,
+                expires_at: pending
+                    .execution_time
+                    .checked_add(i64::from(DEFAULT_PROPOSAL_EXPIRY_SECONDS))
+                    .ok_or(HookError::ArithmeticOverflow)?,
+                nonce: 1,
+                proposed_by: self.governance_authority,
+                memo_hash: [0; 32],
+            }),
+            None => None,
+        };
+        Ok(HookConfig {
+            version: 2,
+            dev_wallets,
+            wallet_cap_raw: self.wallet_cap_raw,
+            governance_authority: self.governance_authority,
+            pending_cap_update,
+            trusted_programs: [Pubkey::default(); MAX_TRUSTED_PROGRAMS],
+            pending_dev_wallet_update: None,
+            dev_exemption_expires_at: None,
+            dev_exemption_finalized: false,
+            airdrop_allowance: 0,
+            permit_signer: Pubkey::default(),
+            buy_cap_raw: self.wallet_cap_raw,
+            max_sell_raw: 0,
+            circuit_breaker_threshold_raw: 0,
+            circuit_breaker_window_slots: 0,
+            circuit_breaker_cooldown: 0,
+            window_start_slot: 0,
+            window_volume: 0,
+            paused_until: 0,
+            holder_count: 0,
+            max_holders: 0,
+            cap_unit: CapUnit::Raw,
+            pending_governance_authority: None,
+            governance_renounced: false,
+            governance_members: [Pubkey::default(); MAX_GOVERNANCE_MEMBERS],
+            governance_threshold: 0,
+            governance_epoch: 0,
+            guardian: None,
+            timelock_seconds: TIMELOCK_DURATION as u32,
+            pending_timelock_update: None,
+            proposal_expiry_seconds: DEFAULT_PROPOSAL_EXPIRY_SECONDS,
+            proposal_nonce,
+            proposer_authority: self.governance_authority,
+            executor_authority: self.governance_authority,
+            veto_authority: None,
+            pending_veto_authority_update: None,
+            min_cap_floor: 0,
+            max_cap_change_bps: 0,
+            frozen: false,
+            bump,
+            emit_transfer_events: false,
+            total_transfers: 0,
+            total_volume: 0,
+            snapshot_count: 0,
+            last_snapshot_slot: 0,
+            pending_hook_disable: None,
+            hook_disabled: false,
+            dev_wallet_ata: Pubkey::default(),
+            staking_program_slot: 0,
+            allow_confidential: false,
+            require_wallet_owners: false,
+            // A v1 config was already enforcing transfers before finalize_setup existed
+            finalized: true,
+            exempt_registry_bump: 0,
+            used_nonces_bump: 0,
+            reserved: [0; HOOK_CONFIG_RESERVED_LEN],
+        })
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug)]
+pub struct PendingCapUpdate {
+    pub kind: CapKind,
+    pub new_cap: u64,
+    pub proposed_at: i64,
+    pub execution_time: i64, // When the update can be executed (timelock)
+    pub expires_at: i64,     // From here on the update can no longer be executed, only swept
+    pub nonce: u64,          // Identifies the proposal across its events and governance log entries
+    pub proposed_by: Pubkey,
+    pub memo_hash: [u8; 32], // Client-supplied hash of the off-chain rationale, e.g. the forum post
+}
+
+impl PendingCapUpdate {
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Version 1 pending cap update (wallet cap only), read only by `migrate_config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PendingCapUpdateV1 {
+    pub new_cap: u64,
+    pub proposed_at: i64,
+    pub execution_time: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug)]
+pub struct PendingTimelockUpdate {
+    pub timelock_seconds: u32,
+    pub proposed_at: i64,
+    pub execution_time: i64, // When the update can be executed (under the timelock it replaces)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug)]
+pub struct PendingVetoAuthorityUpdate {
+    pub veto_authority: Option<Pubkey>, // None clears the veto authority
+    pub proposed_at: i64,
+    pub execution_time: i64, // When the update can be executed (timelock)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug)]
+pub struct PendingDevWalletUpdate {
+    pub index: u8,
+    pub new_wallet: Pubkey,
+    pub proposed_at: i64,
+    pub execution_time: i64, // When the update can be executed (timelock)
+}
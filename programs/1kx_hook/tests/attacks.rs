@@ -0,0 +1,133 @@
+// Known ways around the wallet cap, each played end to end through Token-2022's `transfer_checked`.
+//
+// The cap is checked per destination token account, so some of them work today. Each scenario reports
+// whether the attacker got more past the cap than one account may hold, and the test pins that against
+// the expectation below. A mitigation (ATA enforcement, per-owner aggregate tracking) flips the
+// expectations it closes in the same change that lands it.
+
+mod test_utils;
+
+use one_kx_hook::HookError;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{hook_error, Harness, DECIMALS, WALLET_CAP_RAW};
+
+/// One owner filling several fresh token accounts, each just under the cap: nothing ties them together
+const SPLIT_ACROSS_ACCOUNTS_BYPASSES_CAP: bool = true;
+/// Relaying what a full wallet can't take through an intermediary wallet into it
+const RELAY_THROUGH_INTERMEDIARY_BYPASSES_CAP: bool = false;
+/// Sending to yourself, into the same account or into another one you own
+const SELF_TRANSFER_BYPASSES_CAP: bool = false;
+/// A delegate of the source sending, instead of its owner
+const DELEGATE_TRANSFER_BYPASSES_CAP: bool = false;
+
+/// Token accounts the split attack spreads over
+const SPLIT_ACCOUNTS: u64 = 3;
+
+fn assert_expectation(attack: &str, bypassed: bool, expected: bool) {
+    assert_eq!(
+        bypassed, expected,
+        "{} {} the cap, against its recorded expectation; update the expectation if a mitigation changed this",
+        attack,
+        if bypassed { "bypassed" } else { "no longer bypasses" }
+    );
+}
+
+#[tokio::test]
+async fn splitting_holdings_across_token_accounts() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let funder = Keypair::new();
+    let source = harness.create_token_account(&mint, &funder.pubkey(), SPLIT_ACCOUNTS * WALLET_CAP_RAW).await;
+
+    let attacker = Pubkey::new_unique();
+    let mut held = 0;
+    for _ in 0..SPLIT_ACCOUNTS {
+        let account = harness.create_token_account(&mint, &attacker, 0).await;
+        if harness.transfer(&mint, &source, &account, &funder, WALLET_CAP_RAW - 1).await.0.is_ok() {
+            held += harness.balance(&account).await;
+        }
+    }
+
+    assert_expectation("splitting across accounts", held > WALLET_CAP_RAW, SPLIT_ACROSS_ACCOUNTS_BYPASSES_CAP);
+}
+
+#[tokio::test]
+async fn relaying_through_an_intermediary_wallet() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let sender = Keypair::new();
+    let source = harness.create_token_account(&mint, &sender.pubkey(), 2 * WALLET_CAP_RAW).await;
+    let target = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    let relay = Keypair::new();
+    let intermediary = harness.create_token_account(&mint, &relay.pubkey(), 0).await;
+
+    // The target takes a full cap directly, then one more token by way of the intermediary
+    harness.transfer(&mint, &source, &target, &sender, WALLET_CAP_RAW).await.0.unwrap();
+    harness.transfer(&mint, &source, &intermediary, &sender, 1).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &intermediary, &target, &relay, 1).await;
+    let bypassed = result.is_ok();
+    if !bypassed {
+        assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+    }
+
+    assert_expectation("relaying through an intermediary", bypassed, RELAY_THROUGH_INTERMEDIARY_BYPASSES_CAP);
+}
+
+#[tokio::test]
+async fn transferring_to_yourself() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    // Minting doesn't go through the hook, so the account starts past the cap
+    let account = harness.create_token_account(&mint, &owner.pubkey(), 2 * WALLET_CAP_RAW).await;
+    let second_account = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+
+    // Into the same account it goes through, since nothing moves
+    harness.transfer(&mint, &account, &account, &owner, WALLET_CAP_RAW).await.0.unwrap();
+    let grew = harness.balance(&account).await > 2 * WALLET_CAP_RAW;
+    // Into another account of the same owner it is capped like any other transfer
+    let (result, _) = harness.transfer(&mint, &account, &second_account, &owner, 1).await;
+    let bypassed = grew || result.is_ok();
+    if !bypassed {
+        assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+    }
+
+    assert_expectation("transferring to yourself", bypassed, SELF_TRANSFER_BYPASSES_CAP);
+}
+
+#[tokio::test]
+async fn transferring_as_a_delegate() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 2 * WALLET_CAP_RAW).await;
+    let full = harness.create_token_account(&mint, &Pubkey::new_unique(), WALLET_CAP_RAW).await;
+    let empty = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    let delegate = Keypair::new();
+    let approve = spl_token_2022::instruction::approve_checked(
+        &spl_token_2022::id(),
+        &source,
+        &mint.address,
+        &delegate.pubkey(),
+        &owner.pubkey(),
+        &[],
+        2 * WALLET_CAP_RAW,
+        DECIMALS,
+    )
+    .unwrap();
+    harness.process(&[approve], &[&owner]).await.0.unwrap();
+
+    // The delegate can move tokens at all, but not past the destination's cap
+    harness.transfer(&mint, &source, &empty, &delegate, 1).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &source, &full, &delegate, 1).await;
+    let bypassed = result.is_ok();
+    if !bypassed {
+        assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+    }
+
+    assert_expectation("transferring as a delegate", bypassed, DELEGATE_TRANSFER_BYPASSES_CAP);
+}
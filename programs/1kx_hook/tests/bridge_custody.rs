@@ -0,0 +1,147 @@
+// Bridge custody: a bridge's custody token account holds everything bridged out, far past the cap. Governance
+// registers it naming the bridge program, and the account only goes in if its owner really is a PDA
+// account of that program.
+
+mod test_utils;
+
+use anchor_lang::AccountDeserialize;
+use one_kx_hook::{accounts, instruction, ExemptKind, ExemptRegistry, HookError};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{event_authority, hook_error, pda, program_instruction, Harness, HookedMint, WALLET_CAP_RAW};
+
+fn exempt_registry(mint: &HookedMint) -> Pubkey {
+    pda(&[b"exempt-registry", mint.address.as_ref()])
+}
+
+/// Create the exempt registry and an account at `owner` owned by `program`, the way a bridge holds its
+/// custody signer, returning a token account of the mint owned by it
+async fn custody(harness: &mut Harness, mint: &HookedMint, owner: &Pubkey, program: &Pubkey) -> Pubkey {
+    let payer = harness.context.payer.pubkey();
+    let init_exempt_registry = program_instruction(
+        accounts::InitExemptRegistry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry: exempt_registry(mint),
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+        },
+        instruction::InitExemptRegistry { rent_recipient: payer },
+    );
+    let governance = mint.governance.insecure_clone();
+    harness.process(&[init_exempt_registry], &[&governance]).await.0.unwrap();
+    let account = Account { lamports: 1_000_000, data: vec![], owner: *program, executable: false, rent_epoch: 0 };
+    harness.context.set_account(owner, &account.into());
+    harness.create_token_account(mint, owner, 0).await
+}
+
+fn register_bridge_custody(
+    payer: Pubkey,
+    mint: &HookedMint,
+    custody: &Pubkey,
+    custody_owner: &Pubkey,
+    bridge_program: Pubkey,
+) -> Instruction {
+    program_instruction(
+        accounts::RegisterBridgeCustody {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry: exempt_registry(mint),
+            custody: *custody,
+            custody_owner: *custody_owner,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::RegisterBridgeCustody { bridge_program },
+    )
+}
+
+#[tokio::test]
+async fn registered_custody_holds_past_the_cap() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let bridge = Pubkey::new_unique();
+    let custody_signer = Pubkey::find_program_address(&[b"custody_signer"], &bridge).0;
+    let custody = custody(&mut harness, &mint, &custody_signer, &bridge).await;
+    let payer = harness.context.payer.pubkey();
+    let governance = mint.governance.insecure_clone();
+    let register = register_bridge_custody(payer, &mint, &custody, &custody_signer, bridge);
+    harness.process(&[register], &[&governance]).await.0.unwrap();
+
+    let registry = harness.context.banks_client.get_account(exempt_registry(&mint)).await.unwrap().unwrap();
+    let registry = ExemptRegistry::try_deserialize(&mut &registry.data[..]).unwrap();
+    assert_eq!(registry.entries.len(), 1);
+    assert_eq!((registry.entries[0].token_account, registry.entries[0].kind), (custody, ExemptKind::BridgeCustody));
+
+    // Two holders at the cap both bridge out everything
+    let (alice, bob) = (Keypair::new(), Keypair::new());
+    let alice_wallet = harness.create_token_account(&mint, &alice.pubkey(), WALLET_CAP_RAW).await;
+    let bob_wallet = harness.create_token_account(&mint, &bob.pubkey(), WALLET_CAP_RAW).await;
+    harness.transfer(&mint, &alice_wallet, &custody, &alice, WALLET_CAP_RAW).await.0.unwrap();
+    harness.transfer(&mint, &bob_wallet, &custody, &bob, WALLET_CAP_RAW).await.0.unwrap();
+    assert_eq!(harness.balance(&custody).await, 2 * WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn only_accounts_of_the_claimed_bridge_register() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let (bridge, other_bridge) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let custody_signer = Pubkey::find_program_address(&[b"custody_signer"], &other_bridge).0;
+    let custody = custody(&mut harness, &mint, &custody_signer, &other_bridge).await;
+    let payer = harness.context.payer.pubkey();
+    let governance = mint.governance.insecure_clone();
+
+    // The owner is a PDA account of another bridge than the one claimed
+    let register = register_bridge_custody(payer, &mint, &custody, &custody_signer, bridge);
+    assert_eq!(
+        harness.process(&[register], &[&governance]).await.0.unwrap_err(),
+        hook_error(HookError::NotBridgeCustody)
+    );
+
+    // A keypair account assigned to the bridge program is no PDA of it
+    let assigned = Keypair::new().pubkey();
+    let account = Account { lamports: 1_000_000, data: vec![], owner: bridge, executable: false, rent_epoch: 0 };
+    harness.context.set_account(&assigned, &account.into());
+    let assigned_custody = harness.create_token_account(&mint, &assigned, 0).await;
+    let register = register_bridge_custody(payer, &mint, &assigned_custody, &assigned, bridge);
+    assert_eq!(
+        harness.process(&[register], &[&governance]).await.0.unwrap_err(),
+        hook_error(HookError::NotBridgeCustody)
+    );
+
+    // Nor can the tag be had without the check
+    let add = program_instruction(
+        accounts::AddExemptEntry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry: exempt_registry(&mint),
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::AddExemptEntry { token_account: custody, kind: ExemptKind::BridgeCustody, expires_at: None },
+    );
+    assert_eq!(
+        harness.process(&[add], &[&governance]).await.0.unwrap_err(),
+        hook_error(HookError::BridgeCustodyNotVerified)
+    );
+
+    // Named correctly, the same account registers
+    let register = register_bridge_custody(payer, &mint, &custody, &custody_signer, other_bridge);
+    harness.process(&[register], &[&governance]).await.0.unwrap();
+}
@@ -0,0 +1,57 @@
+// `check_transfer` as another program sees it: the mock caller in the harness CPIs in, reads the verdict
+// back as return data and releases or holds the tokens on it. Rejections come back as verdicts the caller
+// branches on; accounts that aren't what they claim to be fail the caller's instruction.
+
+mod test_utils;
+
+use one_kx_hook::{HookError, ReceiveReason};
+use solana_sdk::pubkey::Pubkey;
+use test_utils::{check_transfer_caller, hook_error, Harness, HookedMint, WALLET_CAP_RAW};
+
+/// Have the caller release `amount` into `destination` and return what it logged about it
+async fn release(harness: &mut Harness, mint: &HookedMint, destination: &Pubkey, amount: u64) -> String {
+    let logs =
+        harness.process_for_logs(&[check_transfer_caller::release(&mint.address, destination, amount)], &[]).await;
+    let decisions: Vec<_> = logs
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program log: "))
+        .filter(|log| log.starts_with("release ") || log.starts_with("hold "))
+        .collect();
+    assert_eq!(decisions.len(), 1, "{:?}", logs);
+    decisions[0].to_string()
+}
+
+#[tokio::test]
+async fn callers_branch_on_the_verdict() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let at_cap = harness.create_token_account(&mint, &Pubkey::new_unique(), WALLET_CAP_RAW).await;
+    let fresh = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    let dev_wallet = harness.create_token_account(&mint, &mint.dev_wallet, WALLET_CAP_RAW).await;
+
+    assert_eq!(release(&mut harness, &mint, &fresh, WALLET_CAP_RAW).await, format!("release {}", WALLET_CAP_RAW));
+    let over_cap = format!("hold {}: reason {}", WALLET_CAP_RAW + 1, ReceiveReason::OverCap as u8);
+    assert_eq!(release(&mut harness, &mint, &fresh, WALLET_CAP_RAW + 1).await, over_cap);
+    assert_eq!(
+        release(&mut harness, &mint, &at_cap, 1).await,
+        format!("hold 1: reason {}", ReceiveReason::OverCap as u8)
+    );
+    // A zero amount moves nothing, and the dev wallet is exempt
+    assert_eq!(release(&mut harness, &mint, &at_cap, 0).await, "release 0");
+    assert_eq!(release(&mut harness, &mint, &dev_wallet, 1).await, "release 1");
+}
+
+#[tokio::test]
+async fn malformed_accounts_fail_the_caller() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let other = harness.create_hooked_mint().await;
+    let other_mints = harness.create_token_account(&other, &Pubkey::new_unique(), 0).await;
+
+    // Not a token account at all
+    let release = check_transfer_caller::release(&mint.address, &Pubkey::new_unique(), 1);
+    assert_eq!(harness.process(&[release], &[]).await.0.unwrap_err(), hook_error(HookError::InvalidAccountOwner));
+    // A token account of another mint
+    let release = check_transfer_caller::release(&mint.address, &other_mints, 1);
+    assert_eq!(harness.process(&[release], &[]).await.0.unwrap_err(), hook_error(HookError::DestinationMintMismatch));
+}
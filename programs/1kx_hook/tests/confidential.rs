@@ -0,0 +1,103 @@
+// Mints with confidential transfers: their encrypted amounts never reach the hook, so `initialize`
+// refuses such a mint unless told with `allow_confidential` that only public balances will be capped.
+// The mint is written as a buffer with the extension in place, as Token-2022 would leave it.
+
+mod test_utils;
+
+use one_kx_hook::{accounts, instruction, CapUnit, HookError};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    program_option::COption,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use spl_pod::optional_keys::OptionalNonZeroPubkey;
+use spl_token_2022::extension::{
+    confidential_transfer::ConfidentialTransferMint, transfer_hook::TransferHook, BaseStateWithExtensionsMut,
+    ExtensionType, StateWithExtensionsMut,
+};
+use spl_token_2022::state::Mint;
+use test_utils::{event_authority, hook_error, program_instruction, Harness, HookedMint, DECIMALS, TIMELOCK_SECONDS};
+
+/// A mint routed through the hook with confidential transfers enabled, with nothing of the hook set up yet
+async fn confidential_mint(harness: &mut Harness) -> HookedMint {
+    let mint = HookedMint {
+        address: Pubkey::new_unique(),
+        mint_authority: Keypair::new(),
+        dev_wallet: Pubkey::new_unique(),
+        governance: Keypair::new(),
+    };
+    let extensions = [ExtensionType::TransferHook, ExtensionType::ConfidentialTransferMint];
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap();
+    let mut data = vec![0u8; space];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    let hook = state.init_extension::<TransferHook>(true).unwrap();
+    hook.program_id = OptionalNonZeroPubkey::try_from(Some(one_kx_hook::ID)).unwrap();
+    state.init_extension::<ConfidentialTransferMint>(true).unwrap().auto_approve_new_accounts = true.into();
+    state.base = Mint {
+        mint_authority: COption::Some(mint.mint_authority.pubkey()),
+        decimals: DECIMALS,
+        is_initialized: true,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    let lamports = harness.context.banks_client.get_rent().await.unwrap().minimum_balance(space);
+    let account = Account { lamports, data, owner: spl_token_2022::id(), executable: false, rent_epoch: 0 };
+    harness.context.set_account(&mint.address, &account.into());
+    mint
+}
+
+fn initialize(payer: Pubkey, mint: &HookedMint, allow_confidential: bool) -> Instruction {
+    program_instruction(
+        accounts::Initialize {
+            payer,
+            config: mint.config(),
+            mint: mint.address,
+            mint_authority: mint.mint_authority.pubkey(),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::Initialize {
+            dev_wallet: mint.dev_wallet,
+            governance_authority: mint.governance.pubkey(),
+            cap_unit: CapUnit::Raw,
+            timelock_seconds: TIMELOCK_SECONDS,
+            min_cap_floor: 0,
+            max_cap_change_bps: 0,
+            strict_authorities: false,
+            allow_confidential,
+        },
+    )
+}
+
+#[tokio::test]
+async fn confidential_mints_need_allow_confidential() {
+    let mut harness = Harness::new().await;
+    let payer = harness.context.payer.pubkey();
+    let mint = confidential_mint(&mut harness).await;
+    let authority = mint.mint_authority.insecure_clone();
+
+    let refused = harness.process(&[initialize(payer, &mint, false)], &[&authority]).await.0;
+    assert_eq!(refused.unwrap_err(), hook_error(HookError::UnsupportedMintExtension));
+    assert!(harness.context.banks_client.get_account(mint.config()).await.unwrap().is_none());
+
+    harness.process(&[initialize(payer, &mint, true)], &[&authority]).await.0.unwrap();
+    assert!(harness.config(&mint).await.allow_confidential);
+}
+
+#[tokio::test]
+async fn allow_confidential_is_only_recorded_for_confidential_mints() {
+    let mut harness = Harness::new().await;
+    let payer = harness.context.payer.pubkey();
+    let mint = harness.create_mint().await;
+    let authority = mint.mint_authority.insecure_clone();
+
+    // Nothing to accept on a plain hooked mint, so the transfer hook never looks for confidential credits
+    harness.process(&[initialize(payer, &mint, true)], &[&authority]).await.0.unwrap();
+    assert!(!harness.config(&mint).await.allow_confidential);
+}
@@ -0,0 +1,71 @@
+// Compute-unit regression tests for the transfer path.
+//
+// Every transfer is a real `transfer_checked` that reaches the hook through Token-2022's CPI, and the
+// consumed units come from the transaction metadata, so they cover Token-2022's transfer, its resolution
+// of the extra account metas and the hook's `execute` together. They are only meaningful under
+// `cargo test-sbf`; plain `cargo test` runs the hook natively, where it costs nothing.
+
+mod test_utils;
+
+use one_kx_hook::HookError;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{compiled_program_available, hook_error, Harness, HookedMint, WALLET_CAP_RAW};
+
+// Ceilings for a whole `transfer_checked` transaction, one per path the hook takes. Raising one needs a
+// reason in the commit that does it: the hook runs on every transfer of the mint.
+const EXEMPT_TRANSFER_CU: u64 = 45_000;
+const CAPPED_TRANSFER_CU: u64 = 60_000;
+const REJECTED_TRANSFER_CU: u64 = 60_000;
+
+/// A hooked mint and a source account holding a full wallet cap, with the owner that signs for it
+async fn setup() -> (Harness, HookedMint, Pubkey, Keypair) {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+    (harness, mint, source, owner)
+}
+
+/// Fail if `consumed` is over `budget`, once the units are the compiled program's
+fn assert_within_budget(scenario: &str, consumed: u64, budget: u64) {
+    if !compiled_program_available() {
+        eprintln!("{}: {} compute units with the native hook, checked under `cargo test-sbf`", scenario, consumed);
+        return;
+    }
+    assert!(consumed <= budget, "{} used {} compute units, over its {} budget", scenario, consumed, budget);
+}
+
+#[tokio::test]
+async fn exempt_transfer_stays_within_budget() {
+    let (mut harness, mint, source, owner) = setup().await;
+    let destination = harness.create_token_account(&mint, &mint.dev_wallet, WALLET_CAP_RAW).await;
+
+    // The dev wallet already holds a full cap, so only the exemption lets this through
+    let (result, consumed) = harness.transfer(&mint, &source, &destination, &owner, 1).await;
+    result.unwrap();
+    assert_within_budget("exempt transfer", consumed, EXEMPT_TRANSFER_CU);
+}
+
+#[tokio::test]
+async fn capped_transfer_stays_within_budget() {
+    let (mut harness, mint, source, owner) = setup().await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    let (result, consumed) = harness.transfer(&mint, &source, &destination, &owner, WALLET_CAP_RAW).await;
+    result.unwrap();
+    assert_within_budget("capped transfer", consumed, CAPPED_TRANSFER_CU);
+}
+
+#[tokio::test]
+async fn rejected_transfer_stays_within_budget() {
+    let (mut harness, mint, source, owner) = setup().await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), WALLET_CAP_RAW).await;
+
+    // A rejection still pays for everything up to the failed check
+    let (result, consumed) = harness.transfer(&mint, &source, &destination, &owner, 1).await;
+    assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+    assert_within_budget("rejected transfer", consumed, REJECTED_TRANSFER_CU);
+}
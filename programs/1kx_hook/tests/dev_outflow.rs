@@ -0,0 +1,131 @@
+// The dev wallets' daily outflow limit: the dev wallets are exempt from the caps, but once governance sets a
+// limit their sells into registered pool vaults are counted over 24-hour windows and refused past it. Moves
+// to any other account don't count, and the limit can only come down.
+
+mod test_utils;
+
+use anchor_lang::AccountDeserialize;
+use one_kx_hook::{accounts, instruction, ConfigExtension, DevOutflowLimitLowered, ExemptKind, HookError};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{decode_event, event_authority, hook_error, pda, program_instruction, Harness, HookedMint};
+
+const DAY: i64 = 24 * 60 * 60;
+
+/// A hooked mint whose dev wallet is `dev`, with a registered pool vault
+async fn mint_with_pool(harness: &mut Harness, dev: &Keypair) -> (HookedMint, Pubkey) {
+    let mint = harness.create_hooked_mint_with_dev_wallet(dev.pubkey()).await;
+    let vault = harness.create_token_account(&mint, &Keypair::new().pubkey(), 0).await;
+    let payer = harness.context.payer.pubkey();
+    let exempt_registry = pda(&[b"exempt-registry", mint.address.as_ref()]);
+    let init_exempt_registry = program_instruction(
+        accounts::InitExemptRegistry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+        },
+        instruction::InitExemptRegistry { rent_recipient: payer },
+    );
+    let add = program_instruction(
+        accounts::AddExemptEntry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::AddExemptEntry { token_account: vault, kind: ExemptKind::PoolVault, expires_at: None },
+    );
+    let governance = mint.governance.insecure_clone();
+    harness.process(&[init_exempt_registry, add], &[&governance]).await.0.unwrap();
+    (mint, vault)
+}
+
+fn lower_dev_outflow_limit(mint: &HookedMint, limit: u64) -> Instruction {
+    program_instruction(
+        accounts::LowerDevOutflowLimit {
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            config_extension: mint.config_extension(),
+            mint: mint.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::LowerDevOutflowLimit { limit },
+    )
+}
+
+async fn config_extension(harness: &mut Harness, mint: &HookedMint) -> ConfigExtension {
+    let account = harness.context.banks_client.get_account(mint.config_extension()).await.unwrap().unwrap();
+    ConfigExtension::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+#[tokio::test]
+async fn dev_sells_into_pools_are_held_to_the_daily_limit() {
+    let mut harness = Harness::new().await;
+    let dev = Keypair::new();
+    let (mint, pool) = mint_with_pool(&mut harness, &dev).await;
+    let dev_wallet = harness.create_token_account(&mint, &dev.pubkey(), 10_000).await;
+    let governance = mint.governance.insecure_clone();
+
+    let events = harness.process_for_events(&[lower_dev_outflow_limit(&mint, 1_000)], &[&governance]).await;
+    let lowered = events.iter().find_map(|event| decode_event::<DevOutflowLimitLowered>(event)).expect("event");
+    assert_eq!((lowered.old_limit, lowered.new_limit), (None, 1_000));
+
+    // Sells add up within the window until the limit
+    harness.transfer(&mint, &dev_wallet, &pool, &dev, 600).await.0.unwrap();
+    harness.transfer(&mint, &dev_wallet, &pool, &dev, 400).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &dev_wallet, &pool, &dev, 1).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::DevOutflowLimitExceeded));
+    assert_eq!(config_extension(&mut harness, &mint).await.dev_outflow_in_window, 1_000);
+
+    // Moving tokens to the treasury isn't a sell, and other holders' sells aren't the dev wallet's
+    let treasury = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    harness.transfer(&mint, &dev_wallet, &treasury, &dev, 5_000).await.0.unwrap();
+    let holder = Keypair::new();
+    let holder_wallet = harness.create_token_account(&mint, &holder.pubkey(), 2_000).await;
+    harness.transfer(&mint, &holder_wallet, &pool, &holder, 2_000).await.0.unwrap();
+    assert_eq!(config_extension(&mut harness, &mint).await.dev_outflow_in_window, 1_000);
+
+    // Governance can lower the limit but never raise it back
+    let (result, _) = harness.process(&[lower_dev_outflow_limit(&mint, 2_000)], &[&governance]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::DevOutflowLimitRaised));
+    harness.process(&[lower_dev_outflow_limit(&mint, 500)], &[&governance]).await.0.unwrap();
+    assert_eq!(config_extension(&mut harness, &mint).await.dev_daily_outflow_limit, Some(500));
+}
+
+#[tokio::test]
+async fn the_outflow_window_rolls_over_after_a_day() {
+    let mut harness = Harness::new().await;
+    let dev = Keypair::new();
+    let (mint, pool) = mint_with_pool(&mut harness, &dev).await;
+    let dev_wallet = harness.create_token_account(&mint, &dev.pubkey(), 10_000).await;
+    let governance = mint.governance.insecure_clone();
+    harness.process(&[lower_dev_outflow_limit(&mint, 1_000)], &[&governance]).await.0.unwrap();
+
+    harness.transfer(&mint, &dev_wallet, &pool, &dev, 1_000).await.0.unwrap();
+    let window_start = config_extension(&mut harness, &mint).await.dev_outflow_window_start;
+
+    // Still the same window a second before the day is up
+    harness.warp_to_timestamp(window_start + DAY - 1).await;
+    let (result, _) = harness.transfer(&mint, &dev_wallet, &pool, &dev, 1).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::DevOutflowLimitExceeded));
+
+    harness.warp_to_timestamp(window_start + DAY).await;
+    harness.transfer(&mint, &dev_wallet, &pool, &dev, 999).await.0.unwrap();
+    let extension = config_extension(&mut harness, &mint).await;
+    assert_eq!((extension.dev_outflow_window_start, extension.dev_outflow_in_window), (window_start + DAY, 999));
+}
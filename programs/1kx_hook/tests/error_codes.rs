@@ -0,0 +1,490 @@
+// Every `HookError` a client can see, pinned to its numeric code and to a test that triggers it.
+//
+// Clients match on the custom error code, so a refactor must not change which error a path returns
+// or where a variant sits in the `#[error_code]` ordering. The negative paths below run end-to-end
+// through the shared harness; `covered_by` names the test for every other variant, wherever it lives,
+// and fails to compile once a variant is added without one.
+
+mod test_utils;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::instruction::InstructionError;
+use one_kx_hook::{accounts, instruction, ExemptKind, GovernanceAction, HookError};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+    transaction::TransactionError,
+};
+use spl_tlv_account_resolution::account::ExtraAccountMeta;
+use test_utils::{event_authority, pda, program_instruction, Harness, HookedMint, WALLET_CAP_RAW};
+
+const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/interface.txt");
+
+/// How a variant is exercised
+enum Coverage {
+    /// By the named test, in this suite or any other
+    Test(&'static str),
+    /// Not reachable through the program; the arm says why
+    Unreachable,
+}
+
+fn covered_by(error: HookError) -> Coverage {
+    match error {
+        HookError::WalletCapExceeded => Coverage::Test("over_cap_transfer_fails_with_wallet_cap_exceeded"),
+        // `init_extra_account_meta_list` allocates the list at exactly the size it then checks for
+        HookError::InsufficientAccountSpace => Coverage::Unreachable,
+        HookError::UnauthorizedGovernance => Coverage::Test("wrong_signer_fails_with_unauthorized_governance"),
+        HookError::InvalidWalletCap => Coverage::Test("zero_and_oversized_caps_fail_with_invalid_wallet_cap"),
+        HookError::NoPendingUpdate => Coverage::Test("executing_without_a_proposal_fails_with_no_pending_update"),
+        HookError::TimelockNotExpired => Coverage::Test("executing_early_fails_with_timelock_not_expired"),
+        HookError::InvalidAccountOwner => Coverage::Test("system_owned_token_account_fails_with_invalid_account_owner"),
+        HookError::InvalidMigrationVersion => Coverage::Test("v1_config_migrates_and_keeps_enforcing_its_cap"),
+        HookError::UnsupportedVersion => Coverage::Test("unknown_config_versions_are_refused"),
+        HookError::UnsupportedMigration => Coverage::Test("versionless_config_fails_with_unsupported_migration"),
+        HookError::ExemptRegistryFull => Coverage::Test("exempt_registry_refuses_a_full_registry_and_unknown_entries"),
+        HookError::InvalidTrustedProgramSlot => Coverage::Test("out_of_range_slots_fail_with_invalid_slot_errors"),
+        HookError::InvalidDevWalletSlot => Coverage::Test("out_of_range_slots_fail_with_invalid_slot_errors"),
+        HookError::DevExemptionExtension => Coverage::Test("test_dev_exemption_sunset"),
+        HookError::DevExemptionNotExpired => Coverage::Test("dev_exemption_finalizes_once_and_only_after_expiry"),
+        HookError::DevExemptionAlreadyFinalized => Coverage::Test("dev_exemption_finalizes_once_and_only_after_expiry"),
+        HookError::ExemptEntryNotFound => Coverage::Test("exempt_registry_refuses_a_full_registry_and_unknown_entries"),
+        HookError::InvalidRentRecipient => Coverage::Test("governance_closes_snapshots_to_refund_the_payer"),
+        HookError::PermitExpired => Coverage::Test("test_permit_checks"),
+        HookError::PermitWrongDestination => Coverage::Test("test_permit_checks"),
+        HookError::PermitNonceReplayed => Coverage::Test("test_permit_nonce_replay"),
+        HookError::PermitWrongMint => Coverage::Test("test_permit_checks"),
+        HookError::PermitAmountExceeded => Coverage::Test("test_permit_checks"),
+        HookError::PermitNonceOutOfRange => Coverage::Test("test_permit_nonce_replay"),
+        HookError::UsedNoncesNotInitialized => Coverage::Test("test_permit_nonce_needs_the_used_nonces_account"),
+        HookError::SellLimitExceeded => Coverage::Test("test_sell_limit"),
+        HookError::SellLimitBelowFloor => Coverage::Test("test_sell_limit_floor_enforced_on_proposals"),
+        HookError::CircuitBreakerTripped => Coverage::Test("a_disabled_hook_enforces_nothing_and_cannot_be_reenabled"),
+        HookError::InvalidCircuitBreakerConfig => Coverage::Test("invalid_governance_settings_are_refused"),
+        HookError::MaxHoldersReached => Coverage::Test("test_max_holders_boundary"),
+        HookError::HolderStateNotInitialized => Coverage::Test("holder_stats_are_best_effort_without_holder_tracking"),
+        HookError::InvalidHolderState => Coverage::Test("close_holder_state_requires_an_empty_balance"),
+        HookError::UiCapUnsupported => Coverage::Test("test_raw_cap_unit_is_unchanged"),
+        HookError::NoPendingGovernanceTransfer => Coverage::Test("wrong_signer_cannot_accept_governance_transfer"),
+        HookError::NotPendingGovernanceAuthority => Coverage::Test("wrong_signer_cannot_accept_governance_transfer"),
+        HookError::GovernanceRenounced => Coverage::Test("every_governance_instruction_fails_after_renounce"),
+        HookError::MultisigGovernanceDisabled => Coverage::Test("single_signer_governance_cannot_propose_actions"),
+        HookError::NotGovernanceMember => Coverage::Test("two_of_three_multisig_lifecycle"),
+        HookError::GovernanceActionAlreadyApproved => Coverage::Test("two_of_three_multisig_lifecycle"),
+        HookError::GovernanceApprovalRequired => Coverage::Test("two_of_three_multisig_lifecycle"),
+        HookError::StaleGovernanceApproval => Coverage::Test("two_of_three_multisig_lifecycle"),
+        HookError::GovernanceActionMismatch => Coverage::Test("two_of_three_multisig_lifecycle"),
+        HookError::GovernanceThresholdNotMet => Coverage::Test("two_of_three_multisig_lifecycle"),
+        HookError::GovernanceApprovalStillActive => Coverage::Test("stale_multisig_approval_is_rejected"),
+        HookError::InvalidGovernanceMembers => Coverage::Test("test_governance_members_validation"),
+        HookError::GuardianCannotUnpause => Coverage::Test("guardian_can_only_pause"),
+        HookError::InvalidTimelock => Coverage::Test("proposals_use_the_configured_timelock"),
+        HookError::ProposalExpired => Coverage::Test("expired_cap_proposal_is_rejected_and_swept"),
+        HookError::ProposalNotExpired => Coverage::Test("expired_cap_proposal_is_rejected_and_swept"),
+        HookError::InvalidProposalExpiry => Coverage::Test("invalid_governance_settings_are_refused"),
+        HookError::ProposalAlreadyPending => Coverage::Test("pending_cap_proposal_is_only_replaced_with_overwrite"),
+        HookError::UnauthorizedExecutor => Coverage::Test("cap_update_roles_gate_each_step"),
+        HookError::TimelockRequired => Coverage::Test("cap_increases_skip_the_timelock_but_decreases_do_not"),
+        HookError::CapBelowFloor => Coverage::Test("cap_floor_is_set_at_initialize_and_only_rises"),
+        HookError::CapFloorLowered => Coverage::Test("cap_floor_is_set_at_initialize_and_only_rises"),
+        HookError::CapChangeTooLarge => Coverage::Test("cap_proposals_are_limited_to_the_max_swing"),
+        HookError::ConfigFrozen => Coverage::Test("every_mutating_instruction_fails_once_frozen"),
+        HookError::FreezeWhilePaused => Coverage::Test("every_mutating_instruction_fails_once_frozen"),
+        HookError::MintNotConfiguredForHook => Coverage::Test("initialize_requires_a_mint_hooked_to_this_program"),
+        HookError::UnauthorizedInitializer => Coverage::Test("only_the_mint_authority_can_initialize"),
+        HookError::NotInTransfer => Coverage::Test("execute_outside_a_transfer_is_rejected"),
+        HookError::SourceMintMismatch => Coverage::Test("test_token_account_mints_must_match"),
+        HookError::DestinationMintMismatch => Coverage::Test("test_preflight_reports_instead_of_raising"),
+        HookError::OwnerMismatch => Coverage::Test("execute_requires_the_source_owner_account"),
+        HookError::ExtraAccountMetasMismatch => Coverage::Test("meta_list_initialization_refuses_other_metas"),
+        HookError::ArithmeticOverflow => Coverage::Test("test_balance_overflow_is_not_reported_as_over_cap"),
+        HookError::MintDetachUnconfirmed => Coverage::Test("closing_the_meta_list_and_config_returns_their_rent"),
+        HookError::PendingCapUpdateExists => Coverage::Test("closing_the_meta_list_and_config_returns_their_rent"),
+        HookError::InvalidAuthority => Coverage::Test("initialize_rejects_unusable_authorities"),
+        HookError::HolderBalanceNotZero => Coverage::Test("close_holder_state_requires_an_empty_balance"),
+        HookError::SnapshotTooSoon => Coverage::Test("snapshots_are_numbered_and_rate_limited"),
+        HookError::HookDisabled => Coverage::Test("a_disabled_hook_enforces_nothing_and_cannot_be_reenabled"),
+        HookError::HookDisableAlreadyPending => Coverage::Test("test_hook_disable_waits_seven_days_and_sticks"),
+        HookError::TokensLocked => {
+            Coverage::Test("lockups_hold_outbound_transfers_until_the_cliff_then_release_linearly")
+        }
+        HookError::InvalidLockup => {
+            Coverage::Test("lockups_hold_outbound_transfers_until_the_cliff_then_release_linearly")
+        }
+        HookError::InvalidLockupSchedule => Coverage::Test("lockups_can_only_be_shortened"),
+        HookError::LockupExtension => Coverage::Test("lockups_can_only_be_shortened"),
+        HookError::DevWalletAccountMismatch => Coverage::Test("dev_wallet_exemption_covers_multisig_and_pda_owners"),
+        HookError::NotUpgradeAuthority => Coverage::Test("global_config_needs_the_upgrade_authority"),
+        HookError::InvalidGlobalDefaultsFields => Coverage::Test("adopt_global_defaults_resyncs_selected_fields"),
+        HookError::CapChangeLimitLoosened => Coverage::Test("adopt_global_defaults_resyncs_selected_fields"),
+        HookError::UnstakeWouldExceedCap => Coverage::Test("staking_vaults_take_any_amount_and_unstakes_are_capped"),
+        HookError::NotStakingVault => Coverage::Test("only_vaults_of_the_staking_program_register"),
+        HookError::NotBridgeCustody => Coverage::Test("only_accounts_of_the_claimed_bridge_register"),
+        HookError::BridgeCustodyNotVerified => Coverage::Test("only_accounts_of_the_claimed_bridge_register"),
+        HookError::UnsupportedMintExtension => Coverage::Test("confidential_mints_need_allow_confidential"),
+        HookError::ConfidentialTransferUnsupported => Coverage::Test("test_confidential_transfers_are_detected"),
+        HookError::InvalidConfigExtension => Coverage::Test("the_launch_is_only_recorded_in_the_mints_own_extension"),
+        HookError::LaunchNotDetected => Coverage::Test("governance_resets_the_launch_once"),
+        HookError::LaunchResetUnavailable => Coverage::Test("governance_resets_the_launch_once"),
+        HookError::InvalidSniperLog => Coverage::Test("snipers_are_only_listed_in_the_mints_own_log"),
+        HookError::SniperNotFlagged => Coverage::Test("the_sniper_log_saturates_and_governance_clears_it"),
+        HookError::InvalidSnipeWindow => Coverage::Test("buys_in_the_snipe_window_flag_their_buyer"),
+        HookError::ProgramOwnedDestination => Coverage::Test("strict_mode_refuses_program_owned_destinations"),
+        HookError::MemoRequired => Coverage::Test("transfers_above_the_threshold_need_a_memo"),
+        HookError::DevOutflowLimitExceeded => Coverage::Test("dev_sells_into_pools_are_held_to_the_daily_limit"),
+        HookError::DevOutflowLimitRaised => Coverage::Test("dev_sells_into_pools_are_held_to_the_daily_limit"),
+        HookError::SetupNotFinalized => Coverage::Test("nothing_moves_before_the_setup_is_finalized"),
+        HookError::SetupAlreadyFinalized => Coverage::Test("nothing_moves_before_the_setup_is_finalized"),
+        HookError::ExtraAccountMetaListMissing => Coverage::Test("finalize_setup_checks_the_meta_list_and_the_mint"),
+        HookError::NotTrustedProgramOwner => Coverage::Test("only_pdas_of_trusted_programs_register"),
+    }
+}
+
+/// Every variant in declaration order
+const ALL_ERRORS: [HookError; 101] = [
+    HookError::WalletCapExceeded, HookError::InsufficientAccountSpace, HookError::UnauthorizedGovernance,
+    HookError::InvalidWalletCap, HookError::NoPendingUpdate, HookError::TimelockNotExpired,
+    HookError::InvalidAccountOwner, HookError::InvalidMigrationVersion, HookError::UnsupportedVersion,
+    HookError::UnsupportedMigration, HookError::ExemptRegistryFull, HookError::InvalidTrustedProgramSlot,
+    HookError::InvalidDevWalletSlot, HookError::DevExemptionExtension, HookError::DevExemptionNotExpired,
+    HookError::DevExemptionAlreadyFinalized, HookError::ExemptEntryNotFound, HookError::InvalidRentRecipient,
+    HookError::PermitExpired, HookError::PermitWrongDestination, HookError::PermitNonceReplayed,
+    HookError::PermitWrongMint, HookError::PermitAmountExceeded, HookError::PermitNonceOutOfRange,
+    HookError::UsedNoncesNotInitialized, HookError::SellLimitExceeded, HookError::SellLimitBelowFloor,
+    HookError::CircuitBreakerTripped, HookError::InvalidCircuitBreakerConfig, HookError::MaxHoldersReached,
+    HookError::HolderStateNotInitialized, HookError::InvalidHolderState, HookError::UiCapUnsupported,
+    HookError::NoPendingGovernanceTransfer, HookError::NotPendingGovernanceAuthority, HookError::GovernanceRenounced,
+    HookError::MultisigGovernanceDisabled, HookError::NotGovernanceMember, HookError::GovernanceActionAlreadyApproved,
+    HookError::GovernanceApprovalRequired, HookError::StaleGovernanceApproval, HookError::GovernanceActionMismatch,
+    HookError::GovernanceThresholdNotMet, HookError::GovernanceApprovalStillActive, HookError::InvalidGovernanceMembers,
+    HookError::GuardianCannotUnpause, HookError::InvalidTimelock, HookError::ProposalExpired,
+    HookError::ProposalNotExpired, HookError::InvalidProposalExpiry, HookError::ProposalAlreadyPending,
+    HookError::UnauthorizedExecutor, HookError::TimelockRequired, HookError::CapBelowFloor, HookError::CapFloorLowered,
+    HookError::CapChangeTooLarge, HookError::ConfigFrozen, HookError::FreezeWhilePaused,
+    HookError::MintNotConfiguredForHook, HookError::UnauthorizedInitializer, HookError::NotInTransfer,
+    HookError::SourceMintMismatch, HookError::DestinationMintMismatch, HookError::OwnerMismatch,
+    HookError::ExtraAccountMetasMismatch, HookError::ArithmeticOverflow, HookError::MintDetachUnconfirmed,
+    HookError::PendingCapUpdateExists, HookError::InvalidAuthority, HookError::HolderBalanceNotZero,
+    HookError::SnapshotTooSoon, HookError::HookDisabled, HookError::HookDisableAlreadyPending, HookError::TokensLocked,
+    HookError::InvalidLockup, HookError::InvalidLockupSchedule, HookError::LockupExtension,
+    HookError::DevWalletAccountMismatch, HookError::NotUpgradeAuthority, HookError::InvalidGlobalDefaultsFields,
+    HookError::CapChangeLimitLoosened, HookError::UnstakeWouldExceedCap, HookError::NotStakingVault,
+    HookError::NotBridgeCustody, HookError::BridgeCustodyNotVerified, HookError::UnsupportedMintExtension,
+    HookError::ConfidentialTransferUnsupported, HookError::InvalidConfigExtension, HookError::LaunchNotDetected,
+    HookError::LaunchResetUnavailable, HookError::InvalidSniperLog, HookError::SniperNotFlagged,
+    HookError::InvalidSnipeWindow, HookError::ProgramOwnedDestination, HookError::MemoRequired,
+    HookError::DevOutflowLimitExceeded, HookError::DevOutflowLimitRaised, HookError::SetupNotFinalized,
+    HookError::SetupAlreadyFinalized, HookError::ExtraAccountMetaListMissing, HookError::NotTrustedProgramOwner,
+];
+
+/// A failed first instruction with the custom code `code`
+fn custom(code: u32) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(0, InstructionError::Custom(code)))
+}
+
+/// Send `instructions` signed by the mint's governance authority
+async fn as_governance(
+    harness: &mut Harness,
+    mint: &HookedMint,
+    instructions: &[Instruction],
+) -> Result<(), TransactionError> {
+    let governance = mint.governance.insecure_clone();
+    harness.process(instructions, &[&governance]).await.0
+}
+
+#[test]
+fn error_codes_follow_the_declaration_order() {
+    let snapshot = std::fs::read_to_string(SNAPSHOT).unwrap();
+    let declared: Vec<(String, u32)> = snapshot
+        .lines()
+        .filter_map(|line| line.strip_prefix("error "))
+        .map(|line| {
+            let (name, code) = line.split_once(' ').unwrap();
+            (name.to_string(), code.parse().unwrap())
+        })
+        .collect();
+    assert_eq!(declared.len(), ALL_ERRORS.len(), "a HookError variant is missing from ALL_ERRORS");
+    for (index, (error, (name, code))) in ALL_ERRORS.iter().zip(&declared).enumerate() {
+        assert_eq!(&format!("{:?}", error), name);
+        assert_eq!(ERROR_CODE_OFFSET + *error as u32, *code);
+        assert_eq!(*code, 6000 + index as u32, "{} moved in the #[error_code] ordering", name);
+    }
+}
+
+#[test]
+fn every_error_has_a_test() {
+    // The sources of every suite and of the unit tests, where the named tests have to be defined
+    let root = env!("CARGO_MANIFEST_DIR");
+    let mut sources = String::new();
+    let mut directories = vec![format!("{}/src", root), format!("{}/tests", root)];
+    while let Some(directory) = directories.pop() {
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                directories.push(path.display().to_string());
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                sources.push_str(&std::fs::read_to_string(path).unwrap());
+            }
+        }
+    }
+
+    for error in ALL_ERRORS {
+        if let Coverage::Test(test) = covered_by(error) {
+            assert!(sources.contains(&format!("fn {}(", test)), "{:?} names {}, which doesn't exist", error, test);
+        }
+    }
+}
+
+#[tokio::test]
+async fn over_cap_transfer_fails_with_wallet_cap_exceeded() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 1).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), WALLET_CAP_RAW).await;
+
+    let (result, _) = harness.transfer(&mint, &source, &destination, &owner, 1).await;
+    assert_eq!(result, custom(6000));
+}
+
+#[tokio::test]
+async fn wrong_signer_fails_with_unauthorized_governance() {
+    let mut harness = Harness::new().await;
+    let mut mint = harness.create_hooked_mint().await;
+    mint.governance = Keypair::new();
+
+    let result = as_governance(&mut harness, &mint, &[mint.propose_wallet_cap(2_000_000_000, false)]).await;
+    assert_eq!(result, custom(6002));
+}
+
+#[tokio::test]
+async fn zero_and_oversized_caps_fail_with_invalid_wallet_cap() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+
+    // Zero, and one past the 100 token ceiling
+    for new_cap in [0, 100_000_000_001] {
+        let result = as_governance(&mut harness, &mint, &[mint.propose_wallet_cap(new_cap, false)]).await;
+        assert_eq!(result, custom(6003));
+    }
+}
+
+#[tokio::test]
+async fn executing_without_a_proposal_fails_with_no_pending_update() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let cranker = Keypair::new();
+
+    let (result, _) = harness.process(&[mint.execute_wallet_cap_update(&cranker.pubkey())], &[&cranker]).await;
+    assert_eq!(result, custom(6004));
+}
+
+#[tokio::test]
+async fn executing_early_fails_with_timelock_not_expired() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let cranker = Keypair::new();
+
+    as_governance(&mut harness, &mint, &[mint.propose_wallet_cap(2_000_000_000, false)]).await.unwrap();
+    let (result, _) = harness.process(&[mint.execute_wallet_cap_update(&cranker.pubkey())], &[&cranker]).await;
+    assert_eq!(result, custom(6005));
+}
+
+#[tokio::test]
+async fn system_owned_token_account_fails_with_invalid_account_owner() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    // Byte for byte the real destination, but owned by the system program instead of Token-2022
+    let fake = Pubkey::new_unique();
+    let real = harness.context.banks_client.get_account(destination).await.unwrap().unwrap();
+    harness.context.set_account(&fake, &Account { owner: system_program::ID, ..real }.into());
+
+    let execute = harness.execute_instruction(&mint, &source, &fake, &owner.pubkey(), 1).await;
+    assert_eq!(harness.process(&[execute], &[]).await.0, custom(6006));
+}
+
+#[tokio::test]
+async fn versionless_config_fails_with_unsupported_migration() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+
+    // A version byte older than any layout the program shipped
+    let mut config = harness.context.banks_client.get_account(mint.config()).await.unwrap().unwrap();
+    config.data[8] = 0;
+    harness.context.set_account(&mint.config(), &config.into());
+
+    let migrate = program_instruction(
+        accounts::MigrateConfig {
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            payer: harness.context.payer.pubkey(),
+            mint: mint.address,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::MigrateConfig {},
+    );
+    assert_eq!(as_governance(&mut harness, &mint, &[migrate]).await, custom(6009));
+}
+
+#[tokio::test]
+async fn exempt_registry_refuses_a_full_registry_and_unknown_entries() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let payer = harness.context.payer.pubkey();
+    let exempt_registry = pda(&[b"exempt-registry", mint.address.as_ref()]);
+    let init = program_instruction(
+        accounts::InitExemptRegistry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+        },
+        instruction::InitExemptRegistry { rent_recipient: payer },
+    );
+    as_governance(&mut harness, &mint, &[init]).await.unwrap();
+
+    let remove = program_instruction(
+        accounts::RemoveExemptEntry {
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            rent_recipient: payer,
+            mint: mint.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::RemoveExemptEntry { token_account: Pubkey::new_unique() },
+    );
+    assert_eq!(as_governance(&mut harness, &mint, &[remove]).await, custom(6016));
+
+    let add = || {
+        program_instruction(
+            accounts::AddExemptEntry {
+                payer,
+                config: mint.config(),
+                governance_authority: mint.governance.pubkey(),
+                exempt_registry,
+                mint: mint.address,
+                system_program: system_program::ID,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::AddExemptEntry {
+                token_account: Pubkey::new_unique(),
+                kind: ExemptKind::Generic,
+                expires_at: None,
+            },
+        )
+    };
+    for _ in 0..16 {
+        as_governance(&mut harness, &mint, &[add()]).await.unwrap();
+    }
+    assert_eq!(as_governance(&mut harness, &mint, &[add()]).await, custom(6010));
+}
+
+#[tokio::test]
+async fn out_of_range_slots_fail_with_invalid_slot_errors() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+
+    // Both tables have four slots
+    let trusted_program =
+        mint.governance_instruction(instruction::SetTrustedProgram { index: 4, program_id: Pubkey::new_unique() });
+    assert_eq!(as_governance(&mut harness, &mint, &[trusted_program]).await, custom(6011));
+    let dev_wallet =
+        mint.governance_instruction(instruction::ProposeDevWalletUpdate { index: 4, new_wallet: Pubkey::new_unique() });
+    assert_eq!(as_governance(&mut harness, &mint, &[dev_wallet]).await, custom(6012));
+}
+
+#[tokio::test]
+async fn dev_exemption_finalizes_once_and_only_after_expiry() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let finalize = || {
+        program_instruction(
+            accounts::FinalizeDevExemption {
+                config: mint.config(),
+                mint: mint.address,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::FinalizeDevExemption {},
+        )
+    };
+
+    // Without an expiry the exemption never lapses
+    assert_eq!(harness.process(&[finalize()], &[]).await.0, custom(6014));
+
+    let expire = mint.governance_instruction(instruction::SetDevExemptionExpiry { expires_at: 0 });
+    as_governance(&mut harness, &mint, &[expire]).await.unwrap();
+    harness.process(&[finalize()], &[]).await.0.unwrap();
+    assert_eq!(harness.process(&[finalize()], &[]).await.0, custom(6015));
+}
+
+#[tokio::test]
+async fn invalid_governance_settings_are_refused() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+
+    // A live threshold needs a window and a cooldown
+    let breaker =
+        mint.governance_instruction(instruction::SetCircuitBreaker { threshold_raw: 1, window_slots: 0, cooldown: 0 });
+    assert_eq!(as_governance(&mut harness, &mint, &[breaker]).await, custom(6028));
+    let expiry = mint.governance_instruction(instruction::SetProposalExpiry { expiry_seconds: 0 });
+    assert_eq!(as_governance(&mut harness, &mint, &[expiry]).await, custom(6049));
+}
+
+#[tokio::test]
+async fn single_signer_governance_cannot_propose_actions() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let epoch = harness.config(&mint).await.governance_epoch;
+
+    let action = GovernanceAction::SetProposalExpiry { expiry_seconds: 60 };
+    let approval = pda(&[b"governance-approval", mint.address.as_ref(), &action.hash(&mint.address, epoch)]);
+    let propose = program_instruction(
+        accounts::ProposeAction {
+            payer: harness.context.payer.pubkey(),
+            config: mint.config(),
+            member: mint.governance.pubkey(),
+            approval,
+            mint: mint.address,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::ProposeAction { action },
+    );
+    assert_eq!(as_governance(&mut harness, &mint, &[propose]).await, custom(6036));
+}
+
+#[tokio::test]
+async fn meta_list_initialization_refuses_other_metas() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let mint_authority = mint.mint_authority.insecure_clone();
+
+    // SPL tooling may pass the metas it wants written; only the program's own list is accepted
+    let other = ExtraAccountMeta::new_with_pubkey(&Pubkey::new_unique(), false, false).unwrap();
+    let init = spl_transfer_hook_interface::instruction::initialize_extra_account_meta_list(
+        &one_kx_hook::ID,
+        &mint.extra_account_meta_list(),
+        &mint.address,
+        &mint_authority.pubkey(),
+        &[other],
+    );
+    assert_eq!(harness.process(&[init], &[&mint_authority]).await.0, custom(6064));
+}
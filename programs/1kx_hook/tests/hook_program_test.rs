@@ -0,0 +1,3545 @@
+// Runtime tests for the hook's execute path and governance instructions.
+//
+// The hook is invoked directly with injected Token-2022 accounts. Under `cargo test-sbf` the
+// compiled program is loaded so compute units are real; plain `cargo test` runs the native entrypoint.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    hash::hash,
+    instruction::{AccountMeta, InstructionError},
+    log::sol_log_data,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    sysvar,
+};
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_lang::{
+    AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use one_kx_hook::{
+    accounts, instruction, params, CanReceiveResult, CapKind, CapUnit, CapUpdateRole, ConfigClosed, ConfigExtension,
+    ConfigFrozen, ConfigMigrated, ConfigView, DevWalletIsGovernanceAuthority, ExemptKind, ExtraAccountMetaListClosed,
+    ExtraAccountMetasUpdated, GovernanceAction, GovernanceLogAction, GovernanceLogEntry, HolderState, HookConfig,
+    HookDisableProposed, HookError, HookPermanentlyDisabled, HookTransferChecked, LockupCreated, LockupShortened,
+    ReceiveReason, Snapshot, SnapshotClosed, SnapshotTaken, SniperFlagged, SniperLog, TransferEventsUpdated,
+    WalletCapUpdateCanceled, WalletCapUpdateExpired, WalletCapUpdateProposed, WalletCapUpdateVetoed, WalletCapUpdated,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::{ExecuteInstruction, TransferHookInstruction};
+use spl_pod::optional_keys::OptionalNonZeroPubkey;
+use spl_token_2022::extension::{
+    transfer_hook::{TransferHook as TransferHookExtension, TransferHookAccount},
+    BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+};
+use spl_token_2022::state::{Account as TokenAccount, AccountState, Mint, Multisig};
+
+const WALLET_CAP_RAW: u64 = 5_000_000_000;
+
+// Deliberately not the 48 hour default, so tests can tell the configured timelock is the one applied
+const TIMELOCK_SECONDS: u32 = 6 * 60 * 60;
+
+// Mirrors the program's minimum gap between snapshots
+const SNAPSHOT_INTERVAL_SLOTS: u64 = 9_000;
+
+// Compute units a full `execute` may use in a release build; it runs on every transfer of the mint
+const HOOK_CU_BUDGET: u64 = 40_000;
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // BanksClient returns no inner instructions, so log the data of the program's event self-CPIs where
+    // `send_for_events` finds it, as an indexer would read it from the transaction's inner instructions
+    if data.starts_with(&EVENT_IX_TAG_LE) {
+        sol_log_data(&[data]);
+    }
+    // Anchor's entrypoint ties the account slice lifetime to the account data; leaking the
+    // slice satisfies that for the lifetime of the test process
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    one_kx_hook::entry(program_id, accounts, data)
+}
+
+// Stands in for a DAO program such as SPL Governance (Realms)
+const MOCK_DAO_ID: Pubkey = Pubkey::new_from_array([0xda; 32]);
+
+/// The mock DAO's governance PDA, which it signs for the way Realms does when executing a passed proposal
+fn dao_governance() -> Pubkey {
+    Pubkey::find_program_address(&[b"governance"], &MOCK_DAO_ID).0
+}
+
+/// Forward the instruction data to the program in the first account, over the remaining accounts,
+/// with the governance PDA seed-signed through `invoke_signed`
+fn mock_dao(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (governance, bump) = Pubkey::find_program_address(&[b"governance"], program_id);
+    let (target, forwarded) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let metas = forwarded
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer || *account.key == governance,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    let instruction = Instruction { program_id: *target.key, accounts: metas, data: data.to_vec() };
+    invoke_signed(&instruction, accounts, &[&[b"governance", &[bump]]])
+}
+
+/// `instruction` routed through the mock DAO, which supplies the governance PDA's signature
+fn via_dao(instruction: Instruction) -> Instruction {
+    let governance = dao_governance();
+    let mut accounts = vec![AccountMeta::new_readonly(instruction.program_id, false)];
+    accounts.extend(instruction.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: meta.is_signer && meta.pubkey != governance,
+        ..meta
+    }));
+    Instruction { program_id: MOCK_DAO_ID, accounts, data: instruction.data }
+}
+
+fn compiled_program_available() -> bool {
+    std::env::var("BPF_OUT_DIR").is_ok()
+}
+
+fn program_test() -> ProgramTest {
+    let mut test = if compiled_program_available() {
+        let mut test = ProgramTest::new("one_kx_hook", one_kx_hook::ID, None);
+        test.prefer_bpf(true);
+        test
+    } else {
+        ProgramTest::new("one_kx_hook", one_kx_hook::ID, processor!(process_instruction))
+    };
+    // Always native: it has no compiled counterpart
+    test.add_builtin_program("mock_dao", MOCK_DAO_ID, processor!(mock_dao));
+    test
+}
+
+fn token_2022_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token_2022::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn mint_account(mint_authority: &Pubkey) -> Account {
+    hooked_mint_account(Some(one_kx_hook::ID), Some(*mint_authority))
+}
+
+/// A mint whose TransferHook extension points at `hook_program_id`, or one without the extension for `None`
+fn hooked_mint_account(hook_program_id: Option<Pubkey>, mint_authority: Option<Pubkey>) -> Account {
+    let mint = Mint {
+        mint_authority: mint_authority.into(),
+        decimals: 9,
+        is_initialized: true,
+        supply: 1_000_000_000_000,
+        ..Default::default()
+    };
+    let Some(hook_program_id) = hook_program_id else {
+        let mut data = vec![0u8; Mint::LEN];
+        Mint::pack(mint, &mut data).unwrap();
+        return token_2022_account(data);
+    };
+
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+    let mut data = vec![0u8; space];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    let hook = state.init_extension::<TransferHookExtension>(true).unwrap();
+    hook.program_id = OptionalNonZeroPubkey::try_from(Some(hook_program_id)).unwrap();
+    state.base = mint;
+    state.pack_base();
+    state.init_account_type().unwrap();
+    token_2022_account(data)
+}
+
+/// A token account of a hooked mint, flagged as mid-transfer the way Token-2022 leaves it during its CPI
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let extensions = [ExtensionType::TransferHookAccount];
+    let space = ExtensionType::try_calculate_account_len::<TokenAccount>(&extensions).unwrap();
+    let mut data = vec![0u8; space];
+    let mut state = StateWithExtensionsMut::<TokenAccount>::unpack_uninitialized(&mut data).unwrap();
+    state.init_extension::<TransferHookAccount>(true).unwrap().transferring = true.into();
+    state.base = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: AccountState::Initialized,
+        ..Default::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    token_2022_account(data)
+}
+
+/// An initialized Token-2022 `m`-of-`signers.len()` multisig, the usual owner of a team treasury
+fn multisig_account(m: u8, signers: &[Pubkey]) -> Account {
+    let mut multisig = Multisig { m, n: signers.len() as u8, is_initialized: true, ..Default::default() };
+    multisig.signers[..signers.len()].copy_from_slice(signers);
+    let mut data = vec![0u8; Multisig::LEN];
+    Multisig::pack(multisig, &mut data).unwrap();
+    token_2022_account(data)
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &one_kx_hook::ID).0
+}
+
+/// The PDA that signs the program's event self-CPIs, passed to every instruction that emits through `emit_cpi!`
+fn event_authority() -> Pubkey {
+    pda(&[b"__event_authority"])
+}
+
+fn program_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: one_kx_hook::ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+/// A started test validator with an initialized config and a set of funded token accounts
+struct Harness {
+    context: ProgramTestContext,
+    mint: Pubkey,
+    mint_authority: Keypair,
+    governance: Keypair,
+    owners: Vec<Pubkey>,
+    token_accounts: Vec<Pubkey>,
+}
+
+impl Harness {
+    /// One token account per entry in `balances`, each with its own owner
+    async fn new(balances: &[u64]) -> Self {
+        let mut harness = Self::new_uninitialized(balances).await;
+        harness.initialize_config(0, 0).await;
+        harness
+    }
+
+    /// Like `new` but without the config, for tests that exercise `initialize` itself
+    async fn new_uninitialized(balances: &[u64]) -> Self {
+        let mint_authority = Keypair::new();
+        Self::with_mint_account(balances, mint_account(&mint_authority.pubkey()), mint_authority).await
+    }
+
+    /// Like `new_uninitialized`, with `mint_account` standing in for the hooked mint
+    async fn with_mint_account(balances: &[u64], mint_account: Account, mint_authority: Keypair) -> Self {
+        let mut test = program_test();
+        let mint = Pubkey::new_unique();
+        test.add_account(mint, mint_account);
+
+        let owners: Vec<Pubkey> = balances.iter().map(|_| Pubkey::new_unique()).collect();
+        let token_accounts: Vec<Pubkey> = balances.iter().map(|_| Pubkey::new_unique()).collect();
+        for ((address, owner), balance) in token_accounts.iter().zip(&owners).zip(balances) {
+            test.add_account(*address, token_account(&mint, owner, *balance));
+        }
+
+        Self {
+            context: test.start_with_context().await,
+            mint,
+            mint_authority,
+            governance: Keypair::new(),
+            owners,
+            token_accounts,
+        }
+    }
+
+    fn config(&self) -> Pubkey {
+        pda(&[b"config", self.mint.as_ref()])
+    }
+
+    fn extra_account_meta_list(&self) -> Pubkey {
+        pda(&[b"extra-account-metas", self.mint.as_ref()])
+    }
+
+    /// Put `metas` in the extra account meta list, as an older program version may have left it
+    async fn write_extra_account_meta_list(&mut self, metas: &[ExtraAccountMeta]) {
+        let size = ExtraAccountMetaList::size_of(metas.len()).unwrap();
+        let mut data = vec![0u8; size];
+        ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, metas).unwrap();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let account = Account {
+            lamports: rent.minimum_balance(size),
+            data,
+            owner: one_kx_hook::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context.set_account(&self.extra_account_meta_list(), &account.into());
+    }
+
+    async fn initialize_config(&mut self, min_cap_floor: u64, max_cap_change_bps: u16) {
+        let initialize = self.initialize_instruction(TIMELOCK_SECONDS, min_cap_floor, max_cap_change_bps);
+        self.send_initialize(initialize).await.unwrap();
+        let init_governance_log = program_instruction(
+            accounts::InitGovernanceLog {
+                payer: self.context.payer.pubkey(),
+                config: self.config(),
+                governance_log: self.governance_log(),
+                mint: self.mint,
+                system_program: system_program::ID,
+            },
+            instruction::InitGovernanceLog {},
+        );
+        self.send(init_governance_log).await.unwrap();
+        self.mark_finalized().await;
+    }
+
+    /// Set the config's `finalized` flag in place of `finalize_setup`, which checks a Token-2022 meta list
+    /// these tests never create since they call `execute` directly
+    async fn mark_finalized(&mut self) {
+        let mut config = self.config_account().await;
+        config.finalized = true;
+        self.store_config(&config).await;
+    }
+
+    fn governance_log(&self) -> Pubkey {
+        pda(&[b"gov-log", self.mint.as_ref()])
+    }
+
+    /// Accounts for proposing or cancelling a cap update as `authority`
+    fn cap_update_accounts(&self, authority: &Pubkey) -> accounts::ProposeWalletCapUpdate {
+        accounts::ProposeWalletCapUpdate {
+            config: self.config(),
+            governance_authority: *authority,
+            mint: self.mint,
+            governance_log: self.governance_log(),
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        }
+    }
+
+    fn propose_wallet_cap(&self, authority: &Pubkey, new_cap: u64, overwrite: bool) -> Instruction {
+        self.propose_wallet_cap_with_memo(authority, new_cap, overwrite, [0; 32])
+    }
+
+    fn propose_wallet_cap_with_memo(
+        &self,
+        authority: &Pubkey,
+        new_cap: u64,
+        overwrite: bool,
+        memo_hash: [u8; 32],
+    ) -> Instruction {
+        program_instruction(
+            self.cap_update_accounts(authority),
+            instruction::ProposeWalletCapUpdate { new_cap, overwrite, memo_hash },
+        )
+    }
+
+    fn initialize_instruction(
+        &self,
+        timelock_seconds: u32,
+        min_cap_floor: u64,
+        max_cap_change_bps: u16,
+    ) -> Instruction {
+        self.initialize_with(instruction::Initialize {
+            dev_wallet: Pubkey::new_unique(),
+            governance_authority: self.governance.pubkey(),
+            cap_unit: CapUnit::Raw,
+            timelock_seconds,
+            min_cap_floor,
+            max_cap_change_bps,
+            strict_authorities: false,
+            allow_confidential: false,
+        })
+    }
+
+    fn initialize_with(&self, args: instruction::Initialize) -> Instruction {
+        program_instruction(
+            accounts::Initialize {
+                payer: self.context.payer.pubkey(),
+                config: self.config(),
+                mint: self.mint,
+                mint_authority: self.mint_authority.pubkey(),
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            args,
+        )
+    }
+
+    /// Current bank slot; the test validator keeps advancing it in the background
+    async fn slot(&mut self) -> u64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.slot
+    }
+
+    /// Move the bank clock `seconds` forward
+    async fn warp_clock(&mut self, seconds: i64) {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        self.set_unix_timestamp(clock.unix_timestamp + seconds).await;
+    }
+
+    async fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// `execute_wallet_cap_update` cranked by `executor`
+    fn execute_cap_update(&self, executor: &Pubkey) -> Instruction {
+        let accounts = accounts::ExecuteWalletCapUpdate {
+            config: self.config(),
+            executor: *executor,
+            mint: self.mint,
+            governance_log: self.governance_log(),
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        };
+        program_instruction(accounts, instruction::ExecuteWalletCapUpdate {})
+    }
+
+    fn set_cap_update_role(&self, authority: &Pubkey, role: CapUpdateRole, new_authority: Pubkey) -> Instruction {
+        program_instruction(
+            accounts::SetCapUpdateRole {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::SetCapUpdateRole { role, authority: new_authority },
+        )
+    }
+
+    fn veto_authority_update(&self, authority: &Pubkey, data: impl InstructionData) -> Instruction {
+        program_instruction(
+            accounts::VetoAuthorityUpdate {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            data,
+        )
+    }
+
+    fn lockup(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[b"lockup", self.mint.as_ref(), owner.as_ref()])
+    }
+
+    fn create_lockup(&self, authority: &Pubkey, owner: Pubkey, locked_amount: u64, locked_until: i64) -> Instruction {
+        self.create_vesting_lockup(authority, owner, locked_amount, locked_until, None)
+    }
+
+    fn create_vesting_lockup(
+        &self,
+        authority: &Pubkey,
+        owner: Pubkey,
+        locked_amount: u64,
+        locked_until: i64,
+        linear_release_end: Option<i64>,
+    ) -> Instruction {
+        program_instruction(
+            accounts::CreateLockup {
+                payer: self.context.payer.pubkey(),
+                config: self.config(),
+                governance_authority: *authority,
+                lockup: self.lockup(&owner),
+                mint: self.mint,
+                system_program: system_program::ID,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::CreateLockup { owner, locked_amount, locked_until, linear_release_end },
+        )
+    }
+
+    fn shorten_lockup(
+        &self,
+        authority: &Pubkey,
+        owner: Pubkey,
+        locked_until: i64,
+        linear_release_end: Option<i64>,
+    ) -> Instruction {
+        program_instruction(
+            accounts::ShortenLockup {
+                config: self.config(),
+                governance_authority: *authority,
+                lockup: self.lockup(&owner),
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::ShortenLockup { owner, locked_until, linear_release_end },
+        )
+    }
+
+    fn hook_disable(&self, authority: &Pubkey, data: impl InstructionData) -> Instruction {
+        program_instruction(
+            accounts::HookDisable {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            data,
+        )
+    }
+
+    fn immediate_cap_increase(&self, authority: &Pubkey, new_cap: u64) -> Instruction {
+        program_instruction(
+            accounts::ImmediateCapIncrease {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::ExecuteImmediateCapIncrease { new_cap },
+        )
+    }
+
+    fn raise_min_cap_floor(&self, authority: &Pubkey, min_cap_floor: u64) -> Instruction {
+        program_instruction(
+            accounts::RaiseMinCapFloor {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::RaiseMinCapFloor { min_cap_floor },
+        )
+    }
+
+    fn sweep_expired_proposal(&self) -> Instruction {
+        let accounts = accounts::SweepExpiredProposal {
+            config: self.config(),
+            mint: self.mint,
+            governance_log: self.governance_log(),
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        };
+        program_instruction(accounts, instruction::SweepExpiredProposal {})
+    }
+
+    /// The hook's `execute` instruction for a transfer between two of the harness token accounts
+    fn execute_instruction(&self, source: usize, destination: usize, amount: u64) -> Instruction {
+        let mint = self.mint;
+        Instruction {
+            program_id: one_kx_hook::ID,
+            accounts: accounts::Execute {
+                source: self.token_accounts[source],
+                mint,
+                destination: self.token_accounts[destination],
+                owner: self.owners[source],
+                extra_account_meta_list: pda(&[b"extra-account-metas", mint.as_ref()]),
+                config: self.config(),
+                exempt_registry: pda(&[b"exempt-registry", mint.as_ref()]),
+                instructions_sysvar: sysvar::instructions::ID,
+                used_nonces: pda(&[b"used-nonces", mint.as_ref()]),
+                source_holder: pda(&[b"holder", mint.as_ref(), self.owners[source].as_ref()]),
+                destination_holder: pda(&[b"holder", mint.as_ref(), self.owners[destination].as_ref()]),
+                source_lockup: self.lockup(&self.owners[source]),
+                config_extension: pda(&[b"config-ext", mint.as_ref()]),
+                sniper_log: pda(&[b"sniper-log", mint.as_ref()]),
+                destination_trusted_owner: pda(&[b"trusted-owner", mint.as_ref(), self.owners[destination].as_ref()]),
+            }
+            .to_account_metas(None),
+            data: instruction::Execute { amount }.data(),
+        }
+    }
+
+    async fn config_account(&mut self) -> HookConfig {
+        let account = self.context.banks_client.get_account(self.config()).await.unwrap().unwrap();
+        HookConfig::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn governance_transfer_accounts(&self, authority: &Pubkey) -> accounts::GovernanceTransfer {
+        accounts::GovernanceTransfer {
+            config: self.config(),
+            governance_authority: *authority,
+            mint: self.mint,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        }
+    }
+
+    fn propose_governance_transfer(&self, authority: &Pubkey, new_authority: Pubkey) -> Instruction {
+        program_instruction(
+            self.governance_transfer_accounts(authority),
+            instruction::ProposeGovernanceTransfer { new_authority },
+        )
+    }
+
+    fn cancel_governance_transfer(&self, authority: &Pubkey) -> Instruction {
+        program_instruction(self.governance_transfer_accounts(authority), instruction::CancelGovernanceTransfer {})
+    }
+
+    fn accept_governance_authority(&self, new_authority: &Pubkey) -> Instruction {
+        program_instruction(
+            accounts::AcceptGovernanceAuthority {
+                config: self.config(),
+                new_authority: *new_authority,
+                mint: self.mint,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::AcceptGovernanceAuthority {},
+        )
+    }
+
+    fn freeze_config(&self, authority: &Pubkey) -> Instruction {
+        program_instruction(
+            accounts::FreezeConfig {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::FreezeConfig {},
+        )
+    }
+
+    fn update_extra_account_meta_list(&self, authority: &Pubkey) -> Instruction {
+        program_instruction(
+            accounts::UpdateExtraAccountMetaList {
+                config: self.config(),
+                governance_authority: *authority,
+                payer: self.context.payer.pubkey(),
+                extra_account_meta_list: self.extra_account_meta_list(),
+                mint: self.mint,
+                system_program: system_program::ID,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::UpdateExtraAccountMetaList {},
+        )
+    }
+
+    fn init_extra_account_meta_list(&self) -> Instruction {
+        program_instruction(
+            accounts::InitExtraAccountMetaList {
+                payer: self.context.payer.pubkey(),
+                extra_account_meta_list: self.extra_account_meta_list(),
+                mint: self.mint,
+                system_program: system_program::ID,
+            },
+            instruction::InitExtraAccountMetaList {},
+        )
+    }
+
+    fn close_extra_account_meta_list(&self, authority: &Pubkey, recipient: &Pubkey, confirm: bool) -> Instruction {
+        program_instruction(
+            accounts::CloseExtraAccountMetaList {
+                config: self.config(),
+                governance_authority: *authority,
+                extra_account_meta_list: self.extra_account_meta_list(),
+                recipient: *recipient,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::CloseExtraAccountMetaList { confirm_mint_detached: confirm },
+        )
+    }
+
+    fn close_config(&self, authority: &Pubkey, recipient: &Pubkey, confirm: bool) -> Instruction {
+        program_instruction(
+            accounts::CloseConfig {
+                config: self.config(),
+                governance_authority: *authority,
+                recipient: *recipient,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::CloseConfig { confirm_mint_detached: confirm },
+        )
+    }
+
+    fn renounce_governance(&self, authority: &Pubkey) -> Instruction {
+        program_instruction(self.governance_transfer_accounts(authority), instruction::RenounceGovernance {})
+    }
+
+    /// The current governance epoch's approval PDA for `action`, and the hash it commits to
+    async fn approval_for(&mut self, action: &GovernanceAction) -> (Pubkey, [u8; 32]) {
+        let epoch = self.config_account().await.governance_epoch;
+        let action_hash = action.hash(&self.mint, epoch);
+        (pda(&[b"governance-approval", self.mint.as_ref(), &action_hash]), action_hash)
+    }
+
+    fn set_governance_members(&self, authority: &Pubkey, members: &[Pubkey], threshold: u8) -> Instruction {
+        let mut slots = [Pubkey::default(); 5];
+        slots[..members.len()].copy_from_slice(members);
+        program_instruction(
+            accounts::SetGovernanceMembers {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::SetGovernanceMembers { members: slots, threshold },
+        )
+    }
+
+    fn propose_action(&self, member: &Pubkey, approval: Pubkey, action: GovernanceAction) -> Instruction {
+        program_instruction(
+            accounts::ProposeAction {
+                payer: self.context.payer.pubkey(),
+                config: self.config(),
+                member: *member,
+                approval,
+                mint: self.mint,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::ProposeAction { action },
+        )
+    }
+
+    fn approve_action(&self, member: &Pubkey, approval: Pubkey, action_hash: [u8; 32]) -> Instruction {
+        program_instruction(
+            accounts::ApproveAction {
+                config: self.config(),
+                member: *member,
+                approval,
+                mint: self.mint,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::ApproveAction { action_hash },
+        )
+    }
+
+    fn set_guardian(&self, authority: &Pubkey, guardian: Option<Pubkey>) -> Instruction {
+        program_instruction(
+            accounts::SetGuardian {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::SetGuardian { guardian },
+        )
+    }
+
+    fn set_pause(&self, authority: &Pubkey, paused: bool) -> Instruction {
+        program_instruction(
+            accounts::SetPause {
+                config: self.config(),
+                authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::SetPause { paused },
+        )
+    }
+
+    fn set_transfer_events(&self, authority: &Pubkey, enabled: bool) -> Instruction {
+        program_instruction(
+            accounts::SetTransferEvents {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::SetTransferEvents { enabled },
+        )
+    }
+
+    /// Refund a governance approval's rent to the fee payer, who paid for it
+    fn close_governance_approval(&self, approval: Pubkey) -> Instruction {
+        program_instruction(
+            accounts::CloseGovernanceApproval {
+                config: self.config(),
+                approval,
+                payer: self.context.payer.pubkey(),
+                mint: self.mint,
+            },
+            instruction::CloseGovernanceApproval {},
+        )
+    }
+
+    /// `propose_cap_update` submitted by the fee payer on the strength of `approval` alone
+    fn propose_cap_update_with(&self, approval: Option<Pubkey>, new_cap: u64) -> Instruction {
+        program_instruction(
+            accounts::ProposeWalletCapUpdate {
+                approval,
+                ..self.cap_update_accounts(&self.context.payer.pubkey())
+            },
+            instruction::ProposeCapUpdate { kind: CapKind::Wallet, new_cap, overwrite: false, memo_hash: [0; 32] },
+        )
+    }
+
+    /// Create the exempt registry, refunding rent to the fee payer
+    fn init_exempt_registry(&self, authority: &Pubkey) -> Instruction {
+        let payer = self.context.payer.pubkey();
+        program_instruction(
+            accounts::InitExemptRegistry {
+                payer,
+                config: self.config(),
+                governance_authority: *authority,
+                exempt_registry: pda(&[b"exempt-registry", self.mint.as_ref()]),
+                mint: self.mint,
+                system_program: system_program::ID,
+                approval: None,
+            },
+            instruction::InitExemptRegistry { rent_recipient: payer },
+        )
+    }
+
+    /// Register `token_account` as a generic exempt account that never expires
+    fn add_exempt_entry(&self, authority: &Pubkey, token_account: Pubkey) -> Instruction {
+        program_instruction(
+            accounts::AddExemptEntry {
+                payer: self.context.payer.pubkey(),
+                config: self.config(),
+                governance_authority: *authority,
+                exempt_registry: pda(&[b"exempt-registry", self.mint.as_ref()]),
+                mint: self.mint,
+                system_program: system_program::ID,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::AddExemptEntry { token_account, kind: ExemptKind::Generic, expires_at: None },
+        )
+    }
+
+    /// Propose `new_wallet` for dev wallet slot `index`, checked against `token_account` if given
+    fn set_dev_wallet_verified(
+        &self,
+        authority: &Pubkey,
+        index: u8,
+        new_wallet: Pubkey,
+        token_account: Option<Pubkey>,
+    ) -> Instruction {
+        program_instruction(
+            accounts::SetDevWalletVerified {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                dev_wallet_token_account: token_account,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::SetDevWalletVerified { index, new_wallet },
+        )
+    }
+
+    fn execute_dev_wallet_update(&self, authority: &Pubkey) -> Instruction {
+        program_instruction(
+            accounts::ExecuteDevWalletUpdate {
+                config: self.config(),
+                governance_authority: *authority,
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::ExecuteDevWalletUpdate {},
+        )
+    }
+
+    async fn transaction(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Transaction {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        Transaction::new_signed_with_payer(&[instruction], Some(&self.context.payer.pubkey()), &all_signers, blockhash)
+    }
+
+    async fn send(&mut self, instruction: Instruction) -> Result<(), TransactionError> {
+        self.send_signed(instruction, &[]).await
+    }
+
+    /// Send an `initialize_instruction`, signed by the mint authority
+    async fn send_initialize(&mut self, initialize: Instruction) -> Result<(), TransactionError> {
+        let mint_authority = self.mint_authority.insecure_clone();
+        self.send_signed(initialize, &[&mint_authority]).await
+    }
+
+    /// Send with extra signers beyond the fee payer
+    async fn send_signed(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Result<(), TransactionError> {
+        let transaction = self.transaction(instruction, signers).await;
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|error| error.unwrap())
+    }
+
+    /// Send with extra signers and return the events the transaction emitted, in order: `emit!` payloads
+    /// from the log and `emit_cpi!` payloads from the event self-CPIs' instruction data, minus its tag.
+    /// Self-CPIs are only seen by the native entrypoint, so under `cargo test-sbf` this has the log alone.
+    async fn send_for_events(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Vec<Vec<u8>> {
+        self.send_for_logged_data(instruction, signers)
+            .await
+            .into_iter()
+            .map(|data| match data.strip_prefix(&EVENT_IX_TAG_LE) {
+                Some(event) => event.to_vec(),
+                None => data,
+            })
+            .collect()
+    }
+
+    /// Send with extra signers and return every `Program data:` payload the transaction logged, in order,
+    /// including the event self-CPI instruction data `process_instruction` logs
+    async fn send_for_logged_data(&mut self, instruction: Instruction, signers: &[&Keypair]) -> Vec<Vec<u8>> {
+        let transaction = self.transaction(instruction, signers).await;
+        let processed = self.context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+        processed.result.unwrap();
+        processed
+            .metadata
+            .unwrap()
+            .log_messages
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .map(|data| BASE64.decode(data).unwrap())
+            .collect()
+    }
+
+    /// Set or clear the `transferring` flag Token-2022 keeps on a harness token account
+    async fn set_transferring(&mut self, index: usize, transferring: bool) {
+        let address = self.token_accounts[index];
+        let mut account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let mut state = StateWithExtensionsMut::<TokenAccount>::unpack(&mut account.data).unwrap();
+        state.get_extension_mut::<TransferHookAccount>().unwrap().transferring = transferring.into();
+        self.context.set_account(&address, &account.into());
+    }
+
+    /// `wallet`'s associated token account for the harness mint
+    fn ata(&self, wallet: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(wallet, &self.mint, &spl_token_2022::id())
+    }
+
+    /// Add a token account of the harness mint at `address`, returning its index
+    async fn add_token_account(&mut self, address: Pubkey, owner: Pubkey, amount: u64) -> usize {
+        self.context.set_account(&address, &token_account(&self.mint, &owner, amount).into());
+        self.token_accounts.push(address);
+        self.owners.push(owner);
+        self.token_accounts.len() - 1
+    }
+
+    /// Hand a harness token account, balance and all, to `owner`
+    async fn set_owner(&mut self, index: usize, owner: Pubkey) {
+        let address = self.token_accounts[index];
+        let mut account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        let mut state = StateWithExtensionsMut::<TokenAccount>::unpack(&mut account.data).unwrap();
+        state.base.owner = owner;
+        state.pack_base();
+        self.context.set_account(&address, &account.into());
+        self.owners[index] = owner;
+    }
+
+    fn migrate_config(&self, authority: &Pubkey) -> Instruction {
+        program_instruction(
+            accounts::MigrateConfig {
+                config: self.config(),
+                governance_authority: *authority,
+                payer: self.context.payer.pubkey(),
+                mint: self.mint,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::MigrateConfig {},
+        )
+    }
+
+    /// Overwrite the version byte that follows the config's discriminator
+    async fn set_config_version(&mut self, version: u8) {
+        let address = self.config();
+        let mut account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        account.data[8] = version;
+        self.context.set_account(&address, &account.into());
+    }
+
+    fn holder_state(&self, owner: &Pubkey) -> Pubkey {
+        pda(&[b"holder", self.mint.as_ref(), owner.as_ref()])
+    }
+
+    fn init_holder_state(&self, owner: Pubkey) -> Instruction {
+        program_instruction(
+            accounts::InitHolderState {
+                payer: self.context.payer.pubkey(),
+                holder_state: self.holder_state(&owner),
+                mint: self.mint,
+                system_program: system_program::ID,
+            },
+            instruction::InitHolderState { owner },
+        )
+    }
+
+    async fn holder_state_account(&mut self, owner: &Pubkey) -> Option<HolderState> {
+        let account = self.context.banks_client.get_account(self.holder_state(owner)).await.unwrap()?;
+        Some(HolderState::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    fn snapshot(&self, index: u64) -> Pubkey {
+        pda(&[b"snapshot", self.mint.as_ref(), &index.to_le_bytes()])
+    }
+
+    /// `take_snapshot` paid for by the fee payer, at the config's next index
+    async fn take_snapshot(&mut self) -> Instruction {
+        let index = self.config_account().await.snapshot_count;
+        program_instruction(
+            accounts::TakeSnapshot {
+                payer: self.context.payer.pubkey(),
+                config: self.config(),
+                snapshot: self.snapshot(index),
+                mint: self.mint,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::TakeSnapshot {},
+        )
+    }
+
+    /// `close_snapshot` refunding the fee payer, who takes the snapshots in these tests
+    fn close_snapshot(&self, authority: &Pubkey, index: u64) -> Instruction {
+        program_instruction(
+            accounts::CloseSnapshot {
+                config: self.config(),
+                governance_authority: *authority,
+                snapshot: self.snapshot(index),
+                rent_recipient: self.context.payer.pubkey(),
+                mint: self.mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::CloseSnapshot { index },
+        )
+    }
+
+    async fn snapshot_account(&mut self, index: u64) -> Option<Snapshot> {
+        let account = self.context.banks_client.get_account(self.snapshot(index)).await.unwrap()?;
+        Some(Snapshot::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    /// Overwrite the stored config, for state governance would take a timelock to reach
+    async fn store_config(&mut self, config: &HookConfig) {
+        let address = self.config();
+        let mut account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        config.try_serialize(&mut account.data.as_mut_slice()).unwrap();
+        self.context.set_account(&address, &account.into());
+    }
+
+    /// Give token account 0 a fresh owner that can sign, with accounts 0 and 1 at rest the way
+    /// Token-2022 leaves them, so they can be moved with `token_2022_transfer`
+    async fn prepare_token_2022_transfer(&mut self) -> Keypair {
+        let owner = Keypair::new();
+        let account = token_account(&self.mint, &owner.pubkey(), WALLET_CAP_RAW);
+        self.context.set_account(&self.token_accounts[0], &account.into());
+        self.owners[0] = owner.pubkey();
+        self.set_transferring(0, false).await;
+        self.set_transferring(1, false).await;
+        owner
+    }
+
+    /// `transfer_checked` of `amount` from token account 0 to 1 plus the validation account, the
+    /// accounts it resolves and the hook program
+    fn token_2022_transfer(&self, owner: &Pubkey, amount: u64) -> Instruction {
+        let mut transfer = spl_token_2022::instruction::transfer_checked(
+            &spl_token_2022::id(),
+            &self.token_accounts[0],
+            &self.mint,
+            &self.token_accounts[1],
+            owner,
+            &[],
+            amount,
+            9,
+        )
+        .unwrap();
+        transfer.accounts.extend(self.execute_instruction(0, 1, amount).accounts.into_iter().skip(4));
+        transfer.accounts.push(AccountMeta::new_readonly(one_kx_hook::ID, false));
+        transfer
+    }
+
+    async fn execute(&mut self, source: usize, destination: usize, amount: u64) -> Result<(), TransactionError> {
+        let instruction = self.execute_instruction(source, destination, amount);
+        self.send(instruction).await
+    }
+
+    /// Compute units a successful `execute` consumes
+    async fn execute_compute_units(&mut self, source: usize, destination: usize, amount: u64) -> u64 {
+        let instruction = self.execute_instruction(source, destination, amount);
+        let transaction = self.transaction(instruction, &[]).await;
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        simulation.simulation_details.unwrap().units_consumed
+    }
+
+    /// One page of the governance log as returned by the `get_governance_log` view
+    async fn governance_log_page(&mut self, page: u8) -> Vec<GovernanceLogEntry> {
+        let accounts = accounts::GetGovernanceLog { governance_log: self.governance_log(), mint: self.mint };
+        let view = program_instruction(accounts, instruction::GetGovernanceLog { page });
+        let transaction = self.transaction(view, &[]).await;
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        Vec::<GovernanceLogEntry>::try_from_slice(&return_data.data).unwrap()
+    }
+
+    /// Simulate `get_config` and decode the view it returns
+    async fn config_view(&mut self) -> ConfigView {
+        let accounts = accounts::GetConfig { config: self.config(), mint: self.mint };
+        let view = program_instruction(accounts, instruction::GetConfig {});
+        let transaction = self.transaction(view, &[]).await;
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        ConfigView::try_from_slice(&return_data.data).unwrap()
+    }
+
+    /// Simulate `can_receive` for `amount` into the token account at `destination`
+    async fn can_receive(&mut self, destination: usize, amount: u64) -> CanReceiveResult {
+        let accounts = accounts::CanReceive {
+            config: self.config(),
+            mint: self.mint,
+            destination: self.token_accounts[destination],
+            exempt_registry: pda(&[b"exempt-registry", self.mint.as_ref()]),
+        };
+        let preflight = program_instruction(accounts, instruction::CanReceive { amount });
+        let transaction = self.transaction(preflight, &[]).await;
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        CanReceiveResult::try_from_slice(&return_data.data).unwrap()
+    }
+}
+
+fn hook_error(error: HookError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+}
+
+/// Decode an event payload as `T`, or None if it is a different event
+fn decode_event<T: Discriminator + AnchorDeserialize>(payload: &[u8]) -> Option<T> {
+    let (discriminator, data) = payload.split_at(8);
+    (discriminator == T::DISCRIMINATOR).then(|| T::deserialize(&mut &data[..]).unwrap())
+}
+
+#[tokio::test]
+async fn zero_amount_transfer_to_capped_destination_succeeds() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, WALLET_CAP_RAW]).await;
+
+    // The destination is already at the cap, so any real amount is rejected...
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::WalletCapExceeded)));
+
+    // ...but a zero-amount transfer is a no-op and passes
+    harness.execute(0, 1, 0).await.unwrap();
+}
+
+#[tokio::test]
+async fn zero_amount_transfer_still_checks_account_owners() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+
+    // A source that is not a Token-2022 account fails before the fast path
+    let mut instruction = harness.execute_instruction(0, 1, 0);
+    instruction.accounts[0].pubkey = harness.context.payer.pubkey();
+    assert_eq!(harness.send(instruction).await, Err(hook_error(HookError::InvalidAccountOwner)));
+}
+
+#[tokio::test]
+async fn execute_outside_a_transfer_is_rejected() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+
+    // Mid-transfer, as Token-2022 invokes it, the hook runs as usual
+    harness.execute(0, 1, 1_000_000_000).await.unwrap();
+
+    // Real token accounts that aren't in a transfer mean the hook was called directly, whichever
+    // side is unflagged and whatever the amount
+    for (index, amount) in [(0, 1_000_000_000), (1, 1_000_000_000), (0, 0)] {
+        harness.set_transferring(index, false).await;
+        assert_eq!(harness.execute(0, 1, amount).await, Err(hook_error(HookError::NotInTransfer)));
+        harness.set_transferring(index, true).await;
+    }
+    harness.execute(0, 1, 1_000_000_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn execute_requires_the_source_owner_account() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+
+    // The owner account Token-2022 passes has to be the one allowed to move the source's tokens
+    let mut instruction = harness.execute_instruction(0, 1, 1_000_000_000);
+    instruction.accounts[3].pubkey = harness.owners[1];
+    assert_eq!(harness.send(instruction).await, Err(hook_error(HookError::OwnerMismatch)));
+    harness.execute(0, 1, 1_000_000_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn extra_account_meta_list_resolves_the_execute_accounts() {
+    let mut harness = Harness::new(&[0, 0]).await;
+    let extra_account_meta_list = harness.extra_account_meta_list();
+    let init_metas = harness.init_extra_account_meta_list();
+    harness.send(init_metas).await.unwrap();
+
+    // Sized for exactly the ten metas, with no room for an Anchor discriminator
+    let list = harness.context.banks_client.get_account(extra_account_meta_list).await.unwrap().unwrap();
+    assert_eq!(list.owner, one_kx_hook::ID);
+    assert_eq!(list.data.len(), ExtraAccountMetaList::size_of(10).unwrap());
+
+    // Token-2022 checks the accounts appended to its execute CPI against the list the same way
+    let execute = harness.execute_instruction(0, 1, 1);
+    let mut accounts = Vec::new();
+    for meta in &execute.accounts {
+        let account = harness.context.banks_client.get_account(meta.pubkey).await.unwrap().unwrap_or_default();
+        accounts.push((meta.clone(), account));
+    }
+    let account_infos: Vec<AccountInfo> = accounts
+        .iter_mut()
+        .map(|(meta, account)| {
+            AccountInfo::new(
+                &meta.pubkey,
+                meta.is_signer,
+                meta.is_writable,
+                &mut account.lamports,
+                &mut account.data,
+                &account.owner,
+                account.executable,
+                account.rent_epoch,
+            )
+        })
+        .collect();
+    ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+        &account_infos,
+        &execute.data,
+        &one_kx_hook::ID,
+        &list.data,
+    )
+    .unwrap();
+
+    // A holder PDA for the wrong owner is caught
+    let mut swapped = account_infos.clone();
+    swapped.swap(9, 10);
+    assert!(ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+        &swapped,
+        &execute.data,
+        &one_kx_hook::ID,
+        &list.data,
+    )
+    .is_err());
+}
+
+#[tokio::test]
+async fn token_2022_transfer_runs_the_hook_end_to_end() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, WALLET_CAP_RAW - 1_000_000_000]).await;
+    let (mint, destination) = (harness.mint, harness.token_accounts[1]);
+    let payer = harness.context.payer.pubkey();
+    let mint_authority = harness.mint_authority.insecure_clone();
+
+    // SPL tooling funds the meta list PDA, then creates it through the interface's own instruction
+    let extra_account_meta_list = pda(&[b"extra-account-metas", mint.as_ref()]);
+    let rent = harness.context.banks_client.get_rent().await.unwrap();
+    let lamports = rent.minimum_balance(ExtraAccountMetaList::size_of(10).unwrap());
+    harness.send(system_instruction::transfer(&payer, &extra_account_meta_list, lamports)).await.unwrap();
+    let init_metas = spl_transfer_hook_interface::instruction::initialize_extra_account_meta_list(
+        &one_kx_hook::ID,
+        &extra_account_meta_list,
+        &mint,
+        &mint_authority.pubkey(),
+        &[],
+    );
+    harness.send_signed(init_metas, &[&mint_authority]).await.unwrap();
+
+    let owner = harness.prepare_token_2022_transfer().await;
+    let transfer = |harness: &Harness, amount| harness.token_2022_transfer(&owner.pubkey(), amount);
+    // Balance and `transferring` flag of a token account
+    let token_state = |account: &Account| {
+        let state = StateWithExtensions::<TokenAccount>::unpack(&account.data).unwrap();
+        let transferring = bool::from(state.get_extension::<TransferHookAccount>().unwrap().transferring);
+        (state.base.amount, transferring)
+    };
+
+    // Token-2022 reaches the hook through its interface discriminator, which runs the cap check
+    harness.send_signed(transfer(&harness, 1_000_000_000), &[&owner]).await.unwrap();
+    let destination_account = harness.context.banks_client.get_account(destination).await.unwrap().unwrap();
+    assert_eq!(token_state(&destination_account), (WALLET_CAP_RAW, false));
+    assert_eq!(
+        harness.send_signed(transfer(&harness, 1), &[&owner]).await,
+        Err(hook_error(HookError::WalletCapExceeded))
+    );
+}
+
+#[tokio::test]
+async fn update_extra_account_meta_list_resizes_lists_from_older_versions() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, WALLET_CAP_RAW - 1_000_000_000]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let extra_account_meta_list = harness.extra_account_meta_list();
+    let rent = harness.context.banks_client.get_rent().await.unwrap();
+    let current_size = ExtraAccountMetaList::size_of(10).unwrap();
+
+    // The original list only resolved the config, so Token-2022 can't pass the hook its other accounts
+    let config_meta = ExtraAccountMeta::new_with_seeds(
+        &[Seed::Literal { bytes: b"config".to_vec() }, Seed::AccountKey { index: 1 }],
+        false,
+        true,
+    )
+    .unwrap();
+    harness.write_extra_account_meta_list(&[config_meta]).await;
+    let owner = harness.prepare_token_2022_transfer().await;
+    assert!(harness.send_signed(harness.token_2022_transfer(&owner.pubkey(), 1), &[&owner]).await.is_err());
+
+    // Only governance may rewrite it
+    let stranger = Keypair::new();
+    let update = harness.update_extra_account_meta_list(&stranger.pubkey());
+    assert_eq!(
+        harness.send_signed(update, &[&stranger]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+
+    // Growing tops up the rent from the payer
+    let update = harness.update_extra_account_meta_list(&authority);
+    let events = harness.send_for_events(update, &[&governance]).await;
+    let updated = decode_event::<ExtraAccountMetasUpdated>(&events[0]).expect("update event");
+    assert_eq!((updated.old_count, updated.new_count), (1, 9));
+    let list = harness.context.banks_client.get_account(extra_account_meta_list).await.unwrap().unwrap();
+    assert_eq!(list.data.len(), current_size);
+    assert_eq!(list.lamports, rent.minimum_balance(current_size));
+
+    // A real transfer now resolves every hook account
+    harness.send_signed(harness.token_2022_transfer(&owner.pubkey(), 1_000_000_000), &[&owner]).await.unwrap();
+
+    // A list with more accounts than this version resolves shrinks back, refunding the surplus rent
+    let stale: Vec<ExtraAccountMeta> = (0..10)
+        .map(|_| ExtraAccountMeta::new_with_pubkey(&Pubkey::new_unique(), false, false).unwrap())
+        .collect();
+    harness.write_extra_account_meta_list(&stale).await;
+    let update = harness.update_extra_account_meta_list(&authority);
+    let events = harness.send_for_events(update, &[&governance]).await;
+    let updated = decode_event::<ExtraAccountMetasUpdated>(&events[0]).expect("update event");
+    assert_eq!((updated.old_count, updated.new_count), (10, 9));
+    let list = harness.context.banks_client.get_account(extra_account_meta_list).await.unwrap().unwrap();
+    assert_eq!(list.data.len(), current_size);
+    assert_eq!(list.lamports, rent.minimum_balance(current_size));
+
+    // The hook sees the full accounts again: the destination is at the cap and the next transfer is held to it
+    assert_eq!(
+        harness.send_signed(harness.token_2022_transfer(&owner.pubkey(), 1), &[&owner]).await,
+        Err(hook_error(HookError::WalletCapExceeded))
+    );
+}
+
+#[tokio::test]
+async fn closing_the_meta_list_and_config_returns_their_rent() {
+    let mut harness = Harness::new(&[0, 0]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let (config, extra_account_meta_list) = (harness.config(), harness.extra_account_meta_list());
+    let recipient = Pubkey::new_unique();
+    let init_metas = harness.init_extra_account_meta_list();
+    harness.send(init_metas).await.unwrap();
+
+    // Both closes need governance and an explicit confirmation that the mint has been detached
+    let stranger = Keypair::new();
+    let close = harness.close_extra_account_meta_list(&stranger.pubkey(), &recipient, true);
+    assert_eq!(
+        harness.send_signed(close, &[&stranger]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+    let close = harness.close_extra_account_meta_list(&authority, &recipient, false);
+    assert_eq!(
+        harness.send_signed(close, &[&governance]).await,
+        Err(hook_error(HookError::MintDetachUnconfirmed))
+    );
+
+    let list = harness.context.banks_client.get_account(extra_account_meta_list).await.unwrap();
+    let list_lamports = list.unwrap().lamports;
+    let close = harness.close_extra_account_meta_list(&authority, &recipient, true);
+    let events = harness.send_for_events(close, &[&governance]).await;
+    let closed = decode_event::<ExtraAccountMetaListClosed>(&events[0]).expect("close event");
+    assert_eq!((closed.recipient, closed.lamports), (recipient, list_lamports));
+    assert!(harness.context.banks_client.get_account(extra_account_meta_list).await.unwrap().is_none());
+    assert_eq!(harness.context.banks_client.get_balance(recipient).await.unwrap(), list_lamports);
+
+    // A pending cap update has to be canceled explicitly first
+    let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let close = harness.close_config(&authority, &recipient, true);
+    assert_eq!(
+        harness.send_signed(close, &[&governance]).await,
+        Err(hook_error(HookError::PendingCapUpdateExists))
+    );
+    let cancel = program_instruction(harness.cap_update_accounts(&authority), instruction::CancelWalletCapUpdate {});
+    harness.send_signed(cancel, &[&governance]).await.unwrap();
+    let close = harness.close_config(&authority, &recipient, false);
+    assert_eq!(
+        harness.send_signed(close, &[&governance]).await,
+        Err(hook_error(HookError::MintDetachUnconfirmed))
+    );
+
+    let config_lamports = harness.context.banks_client.get_account(config).await.unwrap().unwrap().lamports;
+    let close = harness.close_config(&authority, &recipient, true);
+    let events = harness.send_for_events(close, &[&governance]).await;
+    let closed = decode_event::<ConfigClosed>(&events[0]).expect("close event");
+    assert_eq!((closed.recipient, closed.lamports), (recipient, config_lamports));
+    assert!(harness.context.banks_client.get_account(config).await.unwrap().is_none());
+    assert_eq!(
+        harness.context.banks_client.get_balance(recipient).await.unwrap(),
+        list_lamports + config_lamports
+    );
+
+    // Which is why the mint must be detached: the hook can't run without its config
+    assert!(harness.execute(0, 1, 1).await.is_err());
+}
+
+#[tokio::test]
+async fn zero_amount_path_uses_materially_fewer_compute_units() {
+    if !compiled_program_available() {
+        eprintln!("skipping: compute units are only meaningful for the compiled program (cargo test-sbf)");
+        return;
+    }
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+
+    let zero = harness.execute_compute_units(0, 1, 0).await;
+    let full = harness.execute_compute_units(0, 1, 1).await;
+    assert!(zero * 5 < full * 4, "zero-amount path used {} CU vs {} CU for the full path", zero, full);
+}
+
+#[tokio::test]
+async fn transfer_events_report_the_remaining_capacity_only_when_enabled() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, WALLET_CAP_RAW / 2]).await;
+    let governance = harness.governance.insecure_clone();
+    let transfer_events = |events: Vec<Vec<u8>>| -> Vec<HookTransferChecked> {
+        events.iter().filter_map(|payload| decode_event::<HookTransferChecked>(payload)).collect()
+    };
+
+    // Off by default
+    assert!(!harness.config_account().await.emit_transfer_events);
+    let transfer = harness.execute_instruction(0, 1, 1_000);
+    assert!(transfer_events(harness.send_for_events(transfer, &[]).await).is_empty());
+
+    // Only governance may turn them on
+    let stranger = Keypair::new();
+    let enable = harness.set_transfer_events(&stranger.pubkey(), true);
+    assert_eq!(
+        harness.send_signed(enable, &[&stranger]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+    let enable = harness.set_transfer_events(&governance.pubkey(), true);
+    let events = harness.send_for_events(enable, &[&governance]).await;
+    let updated = decode_event::<TransferEventsUpdated>(&events[0]).expect("update event");
+    assert!(updated.enabled);
+    assert_eq!(updated.governance_authority, governance.pubkey());
+
+    let transfer = harness.execute_instruction(0, 1, 1_000);
+    let checked = transfer_events(harness.send_for_events(transfer, &[]).await);
+    assert_eq!(checked.len(), 1);
+    let checked = &checked[0];
+    assert_eq!(checked.mint, harness.mint);
+    assert_eq!(checked.destination_owner, harness.owners[1]);
+    assert_eq!(checked.amount, 1_000);
+    assert_eq!(checked.post_balance, WALLET_CAP_RAW / 2 + 1_000);
+    assert_eq!(checked.cap, WALLET_CAP_RAW);
+    assert_eq!(checked.cap - checked.post_balance, WALLET_CAP_RAW / 2 - 1_000);
+    assert!(!checked.exempt);
+
+    // A zero-amount transfer changes nothing, so there is nothing to report
+    let transfer = harness.execute_instruction(0, 1, 0);
+    assert!(transfer_events(harness.send_for_events(transfer, &[]).await).is_empty());
+
+    let disable = harness.set_transfer_events(&governance.pubkey(), false);
+    harness.send_signed(disable, &[&governance]).await.unwrap();
+    let transfer = harness.execute_instruction(0, 1, 1_000);
+    assert!(transfer_events(harness.send_for_events(transfer, &[]).await).is_empty());
+}
+
+#[tokio::test]
+async fn governance_events_are_recovered_from_the_event_cpi_instruction_data() {
+    if compiled_program_available() {
+        eprintln!("skipping: event self-CPIs are only visible to the native entrypoint");
+        return;
+    }
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+    let governance = harness.governance.insecure_clone();
+    let memo_hash = [7; 32];
+
+    // The proposal event never reaches the program log, where a truncated log would lose it: it only
+    // travels as the data of the self-CPI, the event instruction tag followed by the event itself
+    let propose = harness.propose_wallet_cap_with_memo(&governance.pubkey(), 8_000_000_000, false, memo_hash);
+    let sent_after = harness.slot().await;
+    let logged = harness.send_for_logged_data(propose, &[&governance]).await;
+    assert!(!logged.is_empty());
+    assert!(logged.iter().all(|data| data.starts_with(&EVENT_IX_TAG_LE)));
+    let proposed = logged
+        .iter()
+        .find_map(|data| decode_event::<WalletCapUpdateProposed>(&data[EVENT_IX_TAG_LE.len()..]))
+        .expect("proposal event");
+    assert_eq!(proposed.mint, harness.mint);
+    assert!((sent_after..=harness.slot().await).contains(&proposed.slot));
+    assert_eq!(proposed.kind, CapKind::Wallet);
+    assert_eq!(proposed.new_cap, 8_000_000_000);
+    assert_eq!(proposed.current_cap, WALLET_CAP_RAW);
+    assert_eq!(proposed.execution_time - proposed.proposed_at, TIMELOCK_SECONDS as i64);
+    assert_eq!(proposed.governance_authority, governance.pubkey());
+    assert_eq!(proposed.proposed_by, governance.pubkey());
+    assert_eq!(proposed.memo_hash, memo_hash);
+
+    // The hook's own event stays a plain log entry, with no self-CPI on the transfer path
+    let enable = harness.set_transfer_events(&governance.pubkey(), true);
+    harness.send_signed(enable, &[&governance]).await.unwrap();
+    let transfer = harness.execute_instruction(0, 1, 1_000);
+    let logged = harness.send_for_logged_data(transfer, &[]).await;
+    assert_eq!(logged.len(), 1);
+    assert!(decode_event::<HookTransferChecked>(&logged[0]).is_some());
+}
+
+// Logging on the hook path belongs behind `hook_log!`, which the debug-logs feature compiles in
+#[cfg(not(feature = "debug-logs"))]
+#[tokio::test]
+async fn the_hook_path_logs_nothing_and_stays_within_its_budget() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+
+    // Called the way Token-2022 calls it, with the interface discriminator, under the budget as the limit
+    let mut execute = harness.execute_instruction(0, 1, 1);
+    execute.data = TransferHookInstruction::Execute { amount: 1 }.pack();
+    let limit = ComputeBudgetInstruction::set_compute_unit_limit(HOOK_CU_BUDGET as u32);
+    let blockhash = harness.context.get_new_latest_blockhash().await.unwrap();
+    let payer = &harness.context.payer;
+    let transaction = Transaction::new_signed_with_payer(&[limit, execute], Some(&payer.pubkey()), &[payer], blockhash);
+    let simulation = harness.context.banks_client.simulate_transaction(transaction).await.unwrap();
+    simulation.result.unwrap().unwrap();
+
+    let details = simulation.simulation_details.unwrap();
+    let logged: Vec<&String> = details.logs.iter().filter(|line| line.starts_with("Program log:")).collect();
+    assert!(logged.is_empty(), "the hook path logged {:?}", logged);
+    if compiled_program_available() {
+        eprintln!("interface execute: {} CU", details.units_consumed);
+        assert!(details.units_consumed <= HOOK_CU_BUDGET, "execute used {} CU", details.units_consumed);
+    }
+}
+
+#[tokio::test]
+async fn transfer_events_compute_unit_cost() {
+    if !compiled_program_available() {
+        eprintln!("skipping: compute units are only meaningful for the compiled program (cargo test-sbf)");
+        return;
+    }
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+    let governance = harness.governance.insecure_clone();
+
+    let disabled = harness.execute_compute_units(0, 1, 1).await;
+    let enable = harness.set_transfer_events(&governance.pubkey(), true);
+    harness.send_signed(enable, &[&governance]).await.unwrap();
+    let enabled = harness.execute_compute_units(0, 1, 1).await;
+
+    eprintln!(
+        "full execute path: {} CU without transfer events, {} CU with them (+{} CU)",
+        disabled,
+        enabled,
+        enabled.saturating_sub(disabled)
+    );
+    assert!(enabled <= HOOK_CU_BUDGET, "execute used {} CU, over the {} CU budget", enabled, HOOK_CU_BUDGET);
+}
+
+#[tokio::test]
+async fn transfer_stats_count_every_transfer_the_hook_lets_through() {
+    let mut harness = Harness::new(&[100 * WALLET_CAP_RAW, 0, WALLET_CAP_RAW]).await;
+    let mut config = harness.config_account().await;
+    assert_eq!((config.total_transfers, config.total_volume), (0, 0));
+
+    // Owner 2 becomes a dev wallet, so its over-cap receives are exempt
+    config.dev_wallets[1] = harness.owners[2];
+    harness.store_config(&config).await;
+
+    harness.execute(0, 1, 1_000).await.unwrap();
+    harness.execute(0, 1, 2_000).await.unwrap();
+    harness.execute(0, 2, WALLET_CAP_RAW).await.unwrap();
+    let config = harness.config_account().await;
+    assert_eq!((config.total_transfers, config.total_volume), (3, WALLET_CAP_RAW + 3_000));
+
+    // A transfer the cap rejects is rolled back with everything else it touched; no-ops aren't counted
+    assert_eq!(harness.execute(0, 1, WALLET_CAP_RAW + 1).await, Err(hook_error(HookError::WalletCapExceeded)));
+    harness.execute(0, 1, 0).await.unwrap();
+    harness.execute(1, 1, 1_000).await.unwrap();
+    let view = harness.config_view().await;
+    assert_eq!((view.total_transfers, view.total_volume), (3, WALLET_CAP_RAW + 3_000));
+
+    // The volume saturates instead of blocking transfers
+    let mut config = harness.config_account().await;
+    config.total_volume = u64::MAX - 1;
+    harness.store_config(&config).await;
+    harness.execute(0, 1, 1_000).await.unwrap();
+    let config = harness.config_account().await;
+    assert_eq!((config.total_transfers, config.total_volume), (4, u64::MAX));
+}
+
+#[tokio::test]
+async fn holder_stats_are_best_effort_without_holder_tracking() {
+    let mut harness = Harness::new(&[100 * WALLET_CAP_RAW, 0, 0]).await;
+    let (sender, receiver, untracked) = (harness.owners[0], harness.owners[1], harness.owners[2]);
+    for owner in [sender, receiver] {
+        let init = harness.init_holder_state(owner);
+        harness.send(init).await.unwrap();
+    }
+
+    // Owner 2 has no HolderState, which only means its side goes unrecorded
+    harness.set_unix_timestamp(1_700_000_000).await;
+    harness.execute(0, 1, 1_000).await.unwrap();
+    harness.execute(0, 2, 500).await.unwrap();
+    harness.execute(0, 1, 0).await.unwrap();
+
+    let sent = harness.holder_state_account(&sender).await.unwrap();
+    assert_eq!((sent.lifetime_sent, sent.lifetime_received, sent.transfer_count), (1_500, 0, 2));
+    let received = harness.holder_state_account(&receiver).await.unwrap();
+    assert_eq!((received.lifetime_sent, received.lifetime_received, received.transfer_count), (0, 1_000, 1));
+    assert_eq!((sent.first_seen_ts, received.first_seen_ts), (1_700_000_000, 1_700_000_000));
+    assert!(harness.holder_state_account(&untracked).await.is_none());
+
+    // Without a holder limit the balances that feed the holder count stay untouched
+    assert_eq!((sent.balance, received.balance), (0, 0));
+    assert_eq!(harness.config_account().await.holder_count, 0);
+
+    // With one, both HolderStates become mandatory and carry the stats as well
+    let mut config = harness.config_account().await;
+    config.max_holders = 10;
+    harness.store_config(&config).await;
+    assert_eq!(harness.execute(0, 2, 1).await, Err(hook_error(HookError::HolderStateNotInitialized)));
+    harness.execute(0, 1, 2_000).await.unwrap();
+    let received = harness.holder_state_account(&receiver).await.unwrap();
+    assert_eq!((received.balance, received.lifetime_received, received.transfer_count), (2_000, 3_000, 2));
+    assert_eq!(harness.config_account().await.holder_count, 1);
+}
+
+#[tokio::test]
+async fn holder_state_from_before_the_stats_keeps_tracking_balances() {
+    let mut harness = Harness::new(&[100 * WALLET_CAP_RAW, 0]).await;
+    let (sender, receiver) = (harness.owners[0], harness.owners[1]);
+    let mut config = harness.config_account().await;
+    config.max_holders = 10;
+    harness.store_config(&config).await;
+
+    // The 80 byte layout older builds created: discriminator, mint, owner and balance
+    for owner in [sender, receiver] {
+        let mut data = HolderState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(harness.mint.as_ref());
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        let rent = harness.context.banks_client.get_rent().await.unwrap();
+        let mut account = Account::new(rent.minimum_balance(data.len()), data.len(), &one_kx_hook::ID);
+        account.data = data;
+        let address = harness.holder_state(&owner);
+        harness.context.set_account(&address, &account.into());
+    }
+
+    harness.execute(0, 1, 1_000).await.unwrap();
+    let address = harness.holder_state(&receiver);
+    let account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), 80);
+    assert_eq!(account.data[72..], 1_000u64.to_le_bytes());
+    assert_eq!(harness.config_account().await.holder_count, 1);
+}
+
+#[tokio::test]
+async fn close_holder_state_requires_an_empty_balance() {
+    let mut harness = Harness::new(&[]).await;
+    let owner = Keypair::new();
+    let init = harness.init_holder_state(owner.pubkey());
+    harness.send(init).await.unwrap();
+    let holder_state = harness.holder_state(&owner.pubkey());
+    let rent = harness.context.banks_client.get_account(holder_state).await.unwrap().unwrap().lamports;
+
+    let token_account = Pubkey::new_unique();
+    let close = program_instruction(
+        accounts::CloseHolderState {
+            owner: owner.pubkey(),
+            holder_state,
+            token_account,
+            mint: harness.mint,
+        },
+        instruction::CloseHolderState {},
+    );
+
+    harness.context.set_account(&token_account, &token_account(&harness.mint, &owner.pubkey(), 1).into());
+    assert_eq!(
+        harness.send_signed(close.clone(), &[&owner]).await,
+        Err(hook_error(HookError::HolderBalanceNotZero))
+    );
+
+    // Someone else's empty token account doesn't count
+    harness.context.set_account(&token_account, &token_account(&harness.mint, &Pubkey::new_unique(), 0).into());
+    assert_eq!(
+        harness.send_signed(close.clone(), &[&owner]).await,
+        Err(hook_error(HookError::InvalidHolderState))
+    );
+
+    harness.context.set_account(&token_account, &token_account(&harness.mint, &owner.pubkey(), 0).into());
+    harness.send_signed(close, &[&owner]).await.unwrap();
+    assert!(harness.context.banks_client.get_account(holder_state).await.unwrap().is_none());
+    assert_eq!(harness.context.banks_client.get_balance(owner.pubkey()).await.unwrap(), rent);
+}
+
+#[tokio::test]
+async fn snapshots_are_numbered_and_rate_limited() {
+    let mut harness = Harness::new(&[1_000, 0]).await;
+    let payer = harness.context.payer.pubkey();
+    harness.execute(0, 1, 400).await.unwrap();
+
+    let take = harness.take_snapshot().await;
+    let events = harness.send_for_events(take, &[]).await;
+    let taken = decode_event::<SnapshotTaken>(&events[0]).expect("snapshot event");
+    let first = harness.snapshot_account(0).await.expect("snapshot 0");
+    assert_eq!((taken.index, taken.supply, taken.payer), (0, 1_000_000_000_000, payer));
+    assert_eq!((first.index, first.slot, first.supply), (0, taken.slot, 1_000_000_000_000));
+    assert_eq!((first.wallet_cap_raw, first.paused), (WALLET_CAP_RAW, false));
+    assert_eq!((first.total_transfers, first.total_volume), (1, 400));
+    let config = harness.config_account().await;
+    assert_eq!((config.snapshot_count, config.last_snapshot_slot), (1, first.slot));
+
+    // The next one has to wait out the interval
+    let take = harness.take_snapshot().await;
+    assert_eq!(harness.send(take).await, Err(hook_error(HookError::SnapshotTooSoon)));
+
+    let authority = harness.governance.pubkey();
+    let pause = harness.set_pause(&authority, true);
+    let governance = harness.governance.insecure_clone();
+    harness.send_signed(pause, &[&governance]).await.unwrap();
+    harness.context.warp_to_slot(first.slot + SNAPSHOT_INTERVAL_SLOTS).unwrap();
+    let take = harness.take_snapshot().await;
+    harness.send(take).await.unwrap();
+    let second = harness.snapshot_account(1).await.expect("snapshot 1");
+    assert_eq!(second.index, 1);
+    assert!(second.slot >= first.slot + SNAPSHOT_INTERVAL_SLOTS);
+    assert!(second.paused);
+    assert_eq!(harness.snapshot_account(0).await.expect("snapshot 0").slot, first.slot);
+}
+
+#[tokio::test]
+async fn governance_closes_snapshots_to_refund_the_payer() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let payer = harness.context.payer.pubkey();
+    let take = harness.take_snapshot().await;
+    harness.send(take).await.unwrap();
+    let snapshot = harness.snapshot(0);
+    let rent = harness.context.banks_client.get_account(snapshot).await.unwrap().unwrap().lamports;
+
+    let outsider = Keypair::new();
+    let close = harness.close_snapshot(&outsider.pubkey(), 0);
+    assert_eq!(
+        harness.send_signed(close, &[&outsider]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+
+    // The rent only goes back to whoever paid it
+    let mut close = harness.close_snapshot(&governance.pubkey(), 0);
+    close.accounts[3].pubkey = Pubkey::new_unique();
+    assert_eq!(
+        harness.send_signed(close, &[&governance]).await,
+        Err(hook_error(HookError::InvalidRentRecipient))
+    );
+
+    let balance = harness.context.banks_client.get_balance(payer).await.unwrap();
+    let close = harness.close_snapshot(&governance.pubkey(), 0);
+    let events = harness.send_for_events(close, &[&governance]).await;
+    let closed = decode_event::<SnapshotClosed>(&events[0]).expect("close event");
+    assert_eq!((closed.index, closed.recipient, closed.lamports), (0, payer, rent));
+    assert!(harness.snapshot_account(0).await.is_none());
+    assert!(harness.context.banks_client.get_balance(payer).await.unwrap() > balance);
+
+    // Closing doesn't free the index for reuse; the next snapshot still gets index 1
+    assert_eq!(harness.config_account().await.snapshot_count, 1);
+}
+
+/// A harness with dev wallet slot 0's ATA and a second account of the same wallet, returning their indices
+async fn harness_with_dev_wallet_accounts() -> (Harness, usize, usize) {
+    let mut harness = Harness::new(&[100 * WALLET_CAP_RAW]).await;
+    let dev_wallet = harness.config_account().await.dev_wallets[0];
+    let ata = harness.add_token_account(harness.ata(&dev_wallet), dev_wallet, 0).await;
+    let other = harness.add_token_account(Pubkey::new_unique(), dev_wallet, 0).await;
+    (harness, ata, other)
+}
+
+#[tokio::test]
+async fn dev_wallet_ata_is_exempt_by_address_and_other_accounts_by_owner() {
+    let (mut harness, ata, other) = harness_with_dev_wallet_accounts().await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let old_wallet = harness.owners[ata];
+    assert!(harness.config_account().await.is_dev_wallet_ata(&harness.token_accounts[ata]));
+
+    harness.execute(0, ata, 2 * WALLET_CAP_RAW).await.unwrap();
+    harness.execute(0, other, 2 * WALLET_CAP_RAW).await.unwrap();
+
+    // Replacing slot 0 moves the cache to the new wallet's ATA, and the old one is capped again
+    let new_wallet = Pubkey::new_unique();
+    let propose = harness.set_dev_wallet_verified(&authority, 0, new_wallet, None);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let execute = harness.execute_dev_wallet_update(&authority);
+    harness.send_signed(execute, &[&governance]).await.unwrap();
+    let config = harness.config_account().await;
+    assert!(config.is_dev_wallet_ata(&harness.ata(&new_wallet)));
+    assert!(!config.is_dev_wallet_ata(&harness.ata(&old_wallet)));
+    assert_eq!(harness.execute(0, ata, 1).await, Err(hook_error(HookError::WalletCapExceeded)));
+
+    let new_ata = harness.add_token_account(harness.ata(&new_wallet), new_wallet, 0).await;
+    harness.execute(0, new_ata, 2 * WALLET_CAP_RAW).await.unwrap();
+}
+
+#[tokio::test]
+async fn dev_wallet_ata_fast_path_compute_units() {
+    if !compiled_program_available() {
+        eprintln!("skipping: compute units are only meaningful for the compiled program (cargo test-sbf)");
+        return;
+    }
+    let (mut harness, ata, other) = harness_with_dev_wallet_accounts().await;
+
+    let fast = harness.execute_compute_units(0, ata, 2 * WALLET_CAP_RAW).await;
+    let fallback = harness.execute_compute_units(0, other, 2 * WALLET_CAP_RAW).await;
+    eprintln!(
+        "over-cap dev wallet receive: {} CU into its ATA, {} CU into another account (-{} CU)",
+        fast,
+        fallback,
+        fallback.saturating_sub(fast)
+    );
+    assert!(fast < fallback, "ATA path used {} CU vs {} CU for the owner check", fast, fallback);
+}
+
+/// A harness whose token accounts 1 and 2 are owned by a 2-of-3 SPL multisig and by a PDA, the
+/// owners a team treasury or a vesting program uses instead of a keypair
+async fn harness_with_account_owners() -> Harness {
+    let mut harness = Harness::new(&[100 * WALLET_CAP_RAW, 0, 0, 0]).await;
+    let multisig = Pubkey::new_unique();
+    let signers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    harness.context.set_account(&multisig, &multisig_account(2, &signers).into());
+    let vault = Pubkey::find_program_address(&[b"vault"], &MOCK_DAO_ID).0;
+    assert!(!vault.is_on_curve());
+
+    harness.set_owner(1, multisig).await;
+    harness.set_owner(2, vault).await;
+    for destination in [1, 2] {
+        assert_eq!(
+            harness.execute(0, destination, WALLET_CAP_RAW + 1).await,
+            Err(hook_error(HookError::WalletCapExceeded))
+        );
+    }
+    harness
+}
+
+#[tokio::test]
+async fn dev_wallet_exemption_covers_multisig_and_pda_owners() {
+    let mut harness = harness_with_account_owners().await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let (multisig, vault) = (harness.owners[1], harness.owners[2]);
+
+    // The token account has to be the proposed wallet's account of this mint
+    let crossed = harness.set_dev_wallet_verified(&authority, 1, multisig, Some(harness.token_accounts[2]));
+    assert_eq!(
+        harness.send_signed(crossed, &[&governance]).await,
+        Err(hook_error(HookError::DevWalletAccountMismatch))
+    );
+    let not_token_2022 = harness.set_dev_wallet_verified(&authority, 1, multisig, Some(harness.config()));
+    assert_eq!(
+        harness.send_signed(not_token_2022, &[&governance]).await,
+        Err(hook_error(HookError::InvalidAccountOwner))
+    );
+    // The multisig account itself is owned by Token-2022 but isn't a token account
+    let the_multisig = harness.set_dev_wallet_verified(&authority, 1, multisig, Some(multisig));
+    assert!(harness.send_signed(the_multisig, &[&governance]).await.is_err());
+    assert!(harness.config_account().await.pending_dev_wallet_update.is_none());
+
+    // Verified proposals still wait out the timelock, the token account is optional
+    for (index, wallet, token_account) in [(1, multisig, Some(harness.token_accounts[1])), (2, vault, None)] {
+        let propose = harness.set_dev_wallet_verified(&authority, index, wallet, token_account);
+        harness.send_signed(propose, &[&governance]).await.unwrap();
+        let execute = harness.execute_dev_wallet_update(&authority);
+        assert_eq!(
+            harness.send_signed(execute, &[&governance]).await,
+            Err(hook_error(HookError::TimelockNotExpired))
+        );
+        harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+        let execute = harness.execute_dev_wallet_update(&authority);
+        harness.send_signed(execute, &[&governance]).await.unwrap();
+    }
+    assert_eq!(harness.config_account().await.dev_wallets[1..3], [multisig, vault]);
+
+    // Both owners now take over-cap receives, and the multisig can send out of its own account
+    harness.execute(0, 1, 2 * WALLET_CAP_RAW).await.unwrap();
+    harness.execute(0, 2, 2 * WALLET_CAP_RAW).await.unwrap();
+    harness.execute(1, 3, 1_000).await.unwrap();
+    let preflight = harness.can_receive(2, 10 * WALLET_CAP_RAW).await;
+    assert_eq!((preflight.allowed, preflight.reason_code), (true, ReceiveReason::Exempt as u8));
+}
+
+#[tokio::test]
+async fn exempt_registry_covers_multisig_and_pda_owned_accounts() {
+    let mut harness = harness_with_account_owners().await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    // Registry entries match the token account's address, so its owner never comes into it
+    let init = harness.init_exempt_registry(&authority);
+    harness.send_signed(init, &[&governance]).await.unwrap();
+    for destination in [1, 2] {
+        let add = harness.add_exempt_entry(&authority, harness.token_accounts[destination]);
+        harness.send_signed(add, &[&governance]).await.unwrap();
+        harness.execute(0, destination, 2 * WALLET_CAP_RAW).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn execute_uses_the_stored_config_bump() {
+    let mut harness = Harness::new(&[0, 0]).await;
+    let (_, canonical_bump) = Pubkey::find_program_address(&[b"config", harness.mint.as_ref()], &one_kx_hook::ID);
+    let mut config = harness.config_account().await;
+    assert_eq!(config.bump, canonical_bump);
+
+    if compiled_program_available() {
+        let units = harness.execute_compute_units(0, 1, 1).await;
+        eprintln!("full execute path: {} CU", units);
+        assert!(units <= HOOK_CU_BUDGET, "execute used {} CU, over the {} CU budget", units, HOOK_CU_BUDGET);
+    }
+
+    // The seeds are checked against the stored bump instead of searching for the canonical one
+    config.bump = canonical_bump.wrapping_sub(1);
+    let address = harness.config();
+    let mut account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+    config.try_serialize(&mut account.data.as_mut_slice()).unwrap();
+    harness.context.set_account(&address, &account.into());
+    let seeds_mismatch = Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(anchor_lang::error::ErrorCode::ConstraintSeeds.into()),
+    ));
+    assert_eq!(harness.execute(0, 1, 1).await, seeds_mismatch);
+
+    // So are the exempt registry's and the used nonces', which are checked whether or not they exist
+    config.bump = canonical_bump;
+    let mint = harness.mint;
+    let (_, registry_bump) = Pubkey::find_program_address(&[b"exempt-registry", mint.as_ref()], &one_kx_hook::ID);
+    let (_, nonces_bump) = Pubkey::find_program_address(&[b"used-nonces", mint.as_ref()], &one_kx_hook::ID);
+    assert_eq!((config.exempt_registry_bump, config.used_nonces_bump), (registry_bump, nonces_bump));
+    config.exempt_registry_bump = registry_bump.wrapping_sub(1);
+    config.used_nonces_bump = nonces_bump.wrapping_sub(1);
+    harness.store_config(&config).await;
+    assert_eq!(harness.execute(0, 1, 1).await, seeds_mismatch);
+}
+
+#[tokio::test]
+async fn unknown_config_versions_are_refused() {
+    let mut harness = Harness::new(&[0, 0]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let supported = harness.config_account().await.version;
+
+    // Uninitialized garbage and layouts written by a newer program are never interpreted
+    for version in [0, supported + 1] {
+        harness.set_config_version(version).await;
+        assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::UnsupportedVersion)));
+        assert_eq!(harness.execute(0, 1, 0).await, Err(hook_error(HookError::UnsupportedVersion)));
+        let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+        assert_eq!(
+            harness.send_signed(propose, &[&governance]).await,
+            Err(hook_error(HookError::UnsupportedVersion))
+        );
+    }
+
+    harness.set_config_version(supported).await;
+    harness.execute(0, 1, 1).await.unwrap();
+    let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+}
+
+#[tokio::test]
+async fn v1_config_migrates_and_keeps_enforcing_its_cap() {
+    let mut harness = Harness::new(&[0, 6_000_000_000, 6_000_000_000]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let (address, dev_wallet) = (harness.config(), harness.owners[2]);
+
+    // The v1 account exactly as the first release laid it out: discriminator, version, dev_wallet,
+    // wallet_cap_raw, governance_authority and Option<PendingCapUpdateV1>, in 106 bytes
+    let mut v1 = HookConfig::DISCRIMINATOR.to_vec();
+    v1.push(1);
+    v1.extend_from_slice(dev_wallet.as_ref());
+    v1.extend_from_slice(&7_000_000_000u64.to_le_bytes());
+    v1.extend_from_slice(authority.as_ref());
+    v1.push(1);
+    v1.extend_from_slice(&9_000_000_000u64.to_le_bytes());
+    v1.extend_from_slice(&10i64.to_le_bytes());
+    v1.extend_from_slice(&20i64.to_le_bytes());
+    assert_eq!(v1.len(), 106);
+    let rent = harness.context.banks_client.get_rent().await.unwrap();
+    let mut account = Account::new(rent.minimum_balance(v1.len()), v1.len(), &one_kx_hook::ID);
+    account.data = v1;
+    harness.context.set_account(&address, &account.into());
+
+    // The hook can't read the old layout at all until it is migrated, but the view can
+    assert!(harness.execute(0, 1, 1).await.is_err());
+    let view = harness.config_view().await;
+    assert_eq!((view.wallet_cap_raw, view.dev_wallets[0]), (7_000_000_000, dev_wallet));
+    assert_eq!(view.pending_cap_update.as_ref().map(|pending| pending.new_cap), Some(9_000_000_000));
+
+    let stranger = Keypair::new();
+    let migrate = harness.migrate_config(&stranger.pubkey());
+    assert_eq!(
+        harness.send_signed(migrate, &[&stranger]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+    let migrate = harness.migrate_config(&authority);
+    let events = harness.send_for_events(migrate, &[&governance]).await;
+    let migrated = decode_event::<ConfigMigrated>(&events[0]).expect("migration event");
+    assert_eq!((migrated.old_version, migrated.new_version), (1, 3));
+
+    // Grown to the current layout and topped up to stay rent exempt
+    let account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), HookConfig::SPACE);
+    assert_eq!(account.lamports, rent.minimum_balance(HookConfig::SPACE));
+
+    let (_, canonical_bump) = Pubkey::find_program_address(&[b"config", harness.mint.as_ref()], &one_kx_hook::ID);
+    let config = harness.config_account().await;
+    assert_eq!((config.version, config.bump), (3, canonical_bump));
+    assert_eq!(config.dev_wallets[0], dev_wallet);
+    assert!(config.dev_wallets[1..].iter().all(|wallet| *wallet == Pubkey::default()));
+    assert_eq!(config.wallet_cap_raw, 7_000_000_000);
+    assert_eq!(config.governance_authority, authority);
+    let pending = config.pending_cap_update.expect("pending update carried over");
+    assert_eq!(pending.kind, CapKind::Wallet);
+    assert_eq!((pending.new_cap, pending.proposed_at, pending.execution_time), (9_000_000_000, 10, 20));
+    assert!(config.is_dev_wallet_ata(&harness.ata(&dev_wallet)));
+    assert!(config.finalized);
+    let registry_seeds: &[&[u8]] = &[b"exempt-registry", harness.mint.as_ref()];
+    let (_, registry_bump) = Pubkey::find_program_address(registry_seeds, &one_kx_hook::ID);
+    assert_eq!(config.exempt_registry_bump, registry_bump);
+    assert_eq!(config.reserved, [0; 15]);
+
+    // Clients reading the view see the same config after the migration as before it
+    assert_eq!(harness.config_view().await, view);
+
+    // Already current: another call has nothing to migrate
+    let migrate = harness.migrate_config(&authority);
+    assert_eq!(
+        harness.send_signed(migrate, &[&governance]).await,
+        Err(hook_error(HookError::InvalidMigrationVersion))
+    );
+
+    // Without a finalize_setup call, the 7 token cap and the dev wallet exemption behave exactly as before
+    harness.execute(0, 1, 1_000_000_000).await.unwrap();
+    assert_eq!(harness.execute(0, 1, 1_000_000_001).await, Err(hook_error(HookError::WalletCapExceeded)));
+    harness.execute(0, 2, 2_000_000_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn v2_configs_transfer_once_migrated_across_the_finalize_setup_upgrade() {
+    let mut harness = Harness::new(&[0, 0]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    // A v2 config as deployed before finalize_setup existed, with the flag's byte still reserved, waits
+    // for the migration like any unfinalized config
+    let mut config = harness.config_account().await;
+    config.version = 2;
+    config.finalized = false;
+    harness.store_config(&config).await;
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::SetupNotFinalized)));
+    assert!(!harness.config_view().await.finalized);
+
+    let migrate = harness.migrate_config(&authority);
+    let events = harness.send_for_events(migrate, &[&governance]).await;
+    let migrated = decode_event::<ConfigMigrated>(&events[0]).expect("migration event");
+    assert_eq!((migrated.old_version, migrated.new_version), (2, 3));
+    let config = harness.config_account().await;
+    assert!(config.finalized);
+    harness.execute(0, 1, 1).await.unwrap();
+
+    // The flag alone decides: a current config without it still waits for finalize_setup
+    let mut config = harness.config_account().await;
+    config.finalized = false;
+    harness.store_config(&config).await;
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::SetupNotFinalized)));
+}
+
+#[tokio::test]
+async fn self_transfer_is_a_no_op_at_any_balance() {
+    let below = WALLET_CAP_RAW - 1_000_000_000;
+    let above = WALLET_CAP_RAW + 1_000_000_000;
+    let mut harness = Harness::new(&[below, WALLET_CAP_RAW, above, 0]).await;
+
+    // Sending to itself never changes the balance, so it passes below, at and above the cap
+    for account in 0..3 {
+        harness.execute(account, account, 1).await.unwrap();
+        harness.execute(account, account, 2_000_000_000).await.unwrap();
+    }
+
+    // The same amounts to a different account are still capped
+    harness.execute(0, 3, 2_000_000_000).await.unwrap();
+    assert_eq!(harness.execute(1, 0, 2_000_000_000).await, Err(hook_error(HookError::WalletCapExceeded)));
+}
+
+#[tokio::test]
+async fn governance_transfer_takes_effect_only_on_accept() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let new_authority = Keypair::new();
+
+    let propose = harness.propose_governance_transfer(&governance.pubkey(), new_authority.pubkey());
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let config = harness.config_account().await;
+    assert_eq!(config.governance_authority, governance.pubkey());
+    assert_eq!(config.pending_governance_authority, Some(new_authority.pubkey()));
+
+    let accept = harness.accept_governance_authority(&new_authority.pubkey());
+    harness.send_signed(accept, &[&new_authority]).await.unwrap();
+    let config = harness.config_account().await;
+    assert_eq!(config.governance_authority, new_authority.pubkey());
+    assert_eq!(config.pending_governance_authority, None);
+
+    // The previous authority has lost its powers
+    let propose = harness.propose_governance_transfer(&governance.pubkey(), governance.pubkey());
+    assert_eq!(
+        harness.send_signed(propose, &[&governance]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+}
+
+#[tokio::test]
+async fn wrong_signer_cannot_accept_governance_transfer() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let new_authority = Keypair::new();
+    let intruder = Keypair::new();
+
+    // Nothing to accept before a proposal
+    let accept = harness.accept_governance_authority(&new_authority.pubkey());
+    assert_eq!(
+        harness.send_signed(accept, &[&new_authority]).await,
+        Err(hook_error(HookError::NoPendingGovernanceTransfer))
+    );
+
+    let propose = harness.propose_governance_transfer(&governance.pubkey(), new_authority.pubkey());
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+
+    // Neither a stranger nor the current authority can accept on the proposed authority's behalf
+    for signer in [&intruder, &governance] {
+        let accept = harness.accept_governance_authority(&signer.pubkey());
+        assert_eq!(
+            harness.send_signed(accept, &[signer]).await,
+            Err(hook_error(HookError::NotPendingGovernanceAuthority))
+        );
+    }
+
+    // Only the current authority can cancel, and a canceled transfer can no longer be accepted
+    let cancel = harness.cancel_governance_transfer(&intruder.pubkey());
+    assert_eq!(harness.send_signed(cancel, &[&intruder]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+    let cancel = harness.cancel_governance_transfer(&governance.pubkey());
+    harness.send_signed(cancel, &[&governance]).await.unwrap();
+    let accept = harness.accept_governance_authority(&new_authority.pubkey());
+    assert_eq!(
+        harness.send_signed(accept, &[&new_authority]).await,
+        Err(hook_error(HookError::NoPendingGovernanceTransfer))
+    );
+    assert_eq!(harness.config_account().await.governance_authority, governance.pubkey());
+}
+
+#[tokio::test]
+async fn one_step_governance_update_is_gone() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let new_authority = Pubkey::new_unique();
+
+    // The removed instruction, encoded as clients built it: sighash of its name followed by the new key
+    let mut data = hash(b"global:update_governance_authority").to_bytes()[..8].to_vec();
+    data.extend_from_slice(new_authority.as_ref());
+    let mut update = harness.propose_governance_transfer(&governance.pubkey(), new_authority);
+    update.data = data;
+
+    // Unknown discriminators fall through to the transfer hook interface routing, which refuses them
+    assert_eq!(
+        harness.send_signed(update, &[&governance]).await,
+        Err(TransactionError::InstructionError(0, InstructionError::InvalidInstructionData))
+    );
+    assert_eq!(harness.config_account().await.governance_authority, governance.pubkey());
+}
+
+/// One attempt at every governance-gated instruction, as the governance authority
+fn governance_instructions(harness: &Harness) -> Vec<Instruction> {
+    let payer = harness.context.payer.pubkey();
+    let (config, mint) = (harness.config(), harness.mint);
+    let authority = harness.governance.pubkey();
+    let exempt_registry = pda(&[b"exempt-registry", mint.as_ref()]);
+    // Most governance instructions share the config / authority / mint layout
+    let governed = || accounts::SetCircuitBreaker {
+        config,
+        governance_authority: authority,
+        mint,
+        approval: None,
+        event_authority: event_authority(),
+        program: one_kx_hook::ID,
+    };
+
+    vec![
+        harness.propose_wallet_cap(&authority, 8_000_000_000, true),
+        program_instruction(
+            harness.cap_update_accounts(&authority),
+            instruction::ProposeCapUpdate {
+                kind: CapKind::Buy,
+                new_cap: 8_000_000_000,
+                overwrite: true,
+                memo_hash: [0; 32],
+            },
+        ),
+        program_instruction(harness.cap_update_accounts(&authority), instruction::CancelWalletCapUpdate {}),
+        harness.propose_governance_transfer(&authority, Pubkey::new_unique()),
+        harness.cancel_governance_transfer(&authority),
+        harness.renounce_governance(&authority),
+        program_instruction(
+            accounts::AddExemptEntry {
+                payer,
+                config,
+                governance_authority: authority,
+                exempt_registry,
+                mint,
+                system_program: system_program::ID,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::AddExemptEntry {
+                token_account: Pubkey::new_unique(),
+                kind: ExemptKind::Generic,
+                expires_at: None,
+            },
+        ),
+        program_instruction(
+            accounts::RemoveExemptEntry {
+                config,
+                governance_authority: authority,
+                exempt_registry,
+                rent_recipient: payer,
+                mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::RemoveExemptEntry { token_account: Pubkey::new_unique() },
+        ),
+        program_instruction(governed(), instruction::SetTrustedProgram { index: 0, program_id: Pubkey::new_unique() }),
+        program_instruction(
+            governed(),
+            instruction::ProposeDevWalletUpdate { index: 1, new_wallet: Pubkey::new_unique() },
+        ),
+        program_instruction(governed(), instruction::ExecuteDevWalletUpdate {}),
+        program_instruction(governed(), instruction::CancelDevWalletUpdate {}),
+        program_instruction(governed(), instruction::SetDevExemptionExpiry { expires_at: 0 }),
+        program_instruction(governed(), instruction::SetAirdropAllowance { allowance: 1 }),
+        program_instruction(governed(), instruction::SetPermitSigner { permit_signer: Pubkey::new_unique() }),
+        program_instruction(
+            accounts::InitUsedNonces {
+                payer,
+                config,
+                governance_authority: authority,
+                used_nonces: pda(&[b"used-nonces", mint.as_ref()]),
+                mint,
+                system_program: system_program::ID,
+                approval: None,
+            },
+            instruction::InitUsedNonces {},
+        ),
+        program_instruction(governed(), instruction::SetCircuitBreaker { threshold_raw: 1, window_slots: 1, cooldown: 1 }),
+        program_instruction(governed(), instruction::ClearCircuitBreaker {}),
+        program_instruction(governed(), instruction::SetMaxHolders { max_holders: 1 }),
+        harness.set_transfer_events(&authority, true),
+        harness.close_snapshot(&authority, 0),
+        harness.hook_disable(&authority, instruction::ProposeHookDisable {}),
+        harness.hook_disable(&authority, instruction::PermanentlyDisableHook {}),
+        harness.hook_disable(&authority, instruction::CancelHookDisable {}),
+        harness.create_lockup(&authority, Pubkey::new_unique(), 1, 0),
+        harness.shorten_lockup(&authority, harness.owners[1], 0, None),
+        harness.set_dev_wallet_verified(&authority, 1, harness.owners[1], Some(harness.token_accounts[1])),
+        program_instruction(governed(), instruction::SetGuardian { guardian: Some(Pubkey::new_unique()) }),
+        harness.set_pause(&authority, true),
+        program_instruction(governed(), instruction::ProposeTimelockUpdate { timelock_seconds: 3_600 }),
+        program_instruction(governed(), instruction::ExecuteTimelockUpdate {}),
+        program_instruction(governed(), instruction::CancelTimelockUpdate {}),
+        program_instruction(governed(), instruction::SetProposalExpiry { expiry_seconds: 1 }),
+        harness.set_cap_update_role(&authority, CapUpdateRole::Executor, Pubkey::new_unique()),
+        harness.veto_authority_update(
+            &authority,
+            instruction::ProposeVetoAuthorityUpdate { veto_authority: Some(Pubkey::new_unique()) },
+        ),
+        harness.veto_authority_update(&authority, instruction::ExecuteVetoAuthorityUpdate {}),
+        harness.veto_authority_update(&authority, instruction::CancelVetoAuthorityUpdate {}),
+        harness.immediate_cap_increase(&authority, 9_000_000_000),
+        harness.raise_min_cap_floor(&authority, 1),
+        harness.freeze_config(&authority),
+        harness.update_extra_account_meta_list(&authority),
+        harness.close_extra_account_meta_list(&authority, &payer, true),
+        harness.close_config(&authority, &payer, true),
+    ]
+}
+
+#[tokio::test]
+async fn every_governance_instruction_fails_after_renounce() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, WALLET_CAP_RAW]).await;
+    let governance = harness.governance.insecure_clone();
+    let payer = harness.context.payer.pubkey();
+    let (config, mint) = (harness.config(), harness.mint);
+    let authority = governance.pubkey();
+    // Most governance instructions share the config / authority / mint layout
+    let governed = || accounts::SetCircuitBreaker {
+        config,
+        governance_authority: authority,
+        mint,
+        approval: None,
+        event_authority: event_authority(),
+        program: one_kx_hook::ID,
+    };
+
+    // Leave something pending of every kind, plus a registry, a snapshot and a lockup so the exemption,
+    // snapshot and lockup instructions get past account loading
+    let take_snapshot = harness.take_snapshot().await;
+    harness.send(take_snapshot).await.unwrap();
+    let setup = [
+        harness.init_exempt_registry(&authority),
+        harness.create_lockup(&authority, harness.owners[1], 1, 0),
+        harness.propose_wallet_cap(&authority, 8_000_000_000, false),
+        program_instruction(
+            governed(),
+            instruction::ProposeDevWalletUpdate { index: 1, new_wallet: Pubkey::new_unique() },
+        ),
+        harness.propose_governance_transfer(&authority, Pubkey::new_unique()),
+    ];
+    for instruction in setup {
+        harness.send_signed(instruction, &[&governance]).await.unwrap();
+    }
+
+    let renounce = harness.renounce_governance(&authority);
+    harness.send_signed(renounce, &[&governance]).await.unwrap();
+    let state = harness.config_account().await;
+    assert!(state.governance_renounced);
+    assert_eq!(state.governance_authority, Pubkey::default());
+    assert!(state.pending_cap_update.is_none());
+    assert!(state.pending_dev_wallet_update.is_none());
+    assert!(state.pending_governance_authority.is_none());
+    assert_eq!(state.wallet_cap_raw, WALLET_CAP_RAW);
+
+    for (index, instruction) in governance_instructions(&harness).into_iter().enumerate() {
+        assert_eq!(
+            harness.send_signed(instruction, &[&governance]).await,
+            Err(hook_error(HookError::GovernanceRenounced)),
+            "governance instruction #{} still ran after renounce",
+            index
+        );
+    }
+
+    // Permissionless execution has nothing left to execute
+    let execute = harness.execute_cap_update(&payer);
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::NoPendingUpdate)));
+
+    // Accepting needs the proposed key, which is gone along with the proposal
+    let accept = harness.accept_governance_authority(&authority);
+    assert_eq!(
+        harness.send_signed(accept, &[&governance]).await,
+        Err(hook_error(HookError::GovernanceRenounced))
+    );
+
+    // The cap itself keeps being enforced
+    assert_eq!(harness.config_account().await.wallet_cap_raw, WALLET_CAP_RAW);
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::WalletCapExceeded)));
+
+    // Creating the registry is governance-gated too; it needs a mint that has none yet
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let renounce = harness.renounce_governance(&governance.pubkey());
+    harness.send_signed(renounce, &[&governance]).await.unwrap();
+    let init_exempt_registry = harness.init_exempt_registry(&governance.pubkey());
+    assert_eq!(
+        harness.send_signed(init_exempt_registry, &[&governance]).await,
+        Err(hook_error(HookError::GovernanceRenounced))
+    );
+}
+
+#[tokio::test]
+async fn every_mutating_instruction_fails_once_frozen() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, WALLET_CAP_RAW]).await;
+    let governance = harness.governance.insecure_clone();
+    let payer = harness.context.payer.pubkey();
+    let (config, mint) = (harness.config(), harness.mint);
+    let authority = governance.pubkey();
+
+    // Leave something pending of every kind, plus a registry, a snapshot and a lockup so the exemption,
+    // snapshot and lockup instructions get past account loading
+    let take_snapshot = harness.take_snapshot().await;
+    harness.send(take_snapshot).await.unwrap();
+    let setup = [
+        harness.init_exempt_registry(&authority),
+        harness.create_lockup(&authority, harness.owners[1], 1, 0),
+        harness.propose_wallet_cap(&authority, 8_000_000_000, false),
+        program_instruction(
+            accounts::SetCircuitBreaker {
+                config,
+                governance_authority: authority,
+                mint,
+                approval: None,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::ProposeDevWalletUpdate { index: 1, new_wallet: Pubkey::new_unique() },
+        ),
+        harness.propose_governance_transfer(&authority, Pubkey::new_unique()),
+    ];
+    for instruction in setup {
+        harness.send_signed(instruction, &[&governance]).await.unwrap();
+    }
+
+    // An emergency pause would outlive the freeze, so it has to be lifted first
+    let pause = harness.set_pause(&authority, true);
+    harness.send_signed(pause, &[&governance]).await.unwrap();
+    let freeze = harness.freeze_config(&authority);
+    assert_eq!(harness.send_signed(freeze, &[&governance]).await, Err(hook_error(HookError::FreezeWhilePaused)));
+    let unpause = harness.set_pause(&authority, false);
+    harness.send_signed(unpause, &[&governance]).await.unwrap();
+
+    let freeze = harness.freeze_config(&authority);
+    let events = harness.send_for_events(freeze, &[&governance]).await;
+    let frozen = decode_event::<ConfigFrozen>(&events[0]).expect("freeze event");
+    let state = harness.config_account().await;
+    assert_eq!((frozen.wallet_cap_raw, frozen.dev_wallets), (WALLET_CAP_RAW, state.dev_wallets));
+    assert!(state.frozen);
+    assert!(state.pending_cap_update.is_none());
+    assert!(state.pending_dev_wallet_update.is_none());
+    assert!(state.pending_governance_authority.is_none());
+
+    for (index, instruction) in governance_instructions(&harness).into_iter().enumerate() {
+        assert_eq!(
+            harness.send_signed(instruction, &[&governance]).await,
+            Err(hook_error(HookError::ConfigFrozen)),
+            "governance instruction #{} still ran after freeze",
+            index
+        );
+    }
+
+    // Permissionless and migration paths are closed as well
+    let migrate = harness.migrate_config(&authority);
+    let take_snapshot = harness.take_snapshot().await;
+    let attempts = [
+        harness.execute_cap_update(&payer),
+        harness.sweep_expired_proposal(),
+        program_instruction(
+            accounts::FinalizeDevExemption {
+                config,
+                mint,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::FinalizeDevExemption {},
+        ),
+        harness.accept_governance_authority(&authority),
+        migrate,
+        take_snapshot,
+    ];
+    for (index, instruction) in attempts.into_iter().enumerate() {
+        assert_eq!(
+            harness.send_signed(instruction, &[&governance]).await,
+            Err(hook_error(HookError::ConfigFrozen)),
+            "instruction #{} still ran after freeze",
+            index
+        );
+    }
+
+    // The hook keeps enforcing the frozen cap
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::WalletCapExceeded)));
+    harness.execute(0, 1, 0).await.unwrap();
+}
+
+#[tokio::test]
+async fn dao_governance_pda_signs_through_cpi() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let dao = dao_governance();
+
+    // Hand governance to the DAO; its PDA accepts from inside the DAO program
+    let propose = harness.propose_governance_transfer(&governance.pubkey(), dao);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let accept = via_dao(harness.accept_governance_authority(&dao));
+    harness.send(accept).await.unwrap();
+    let state = harness.config_account().await;
+    assert_eq!((state.governance_authority, state.proposer_authority), (dao, dao));
+
+    // An executed DAO proposal lands as an ordinary cap proposal, no keypair involved
+    let propose = via_dao(harness.propose_wallet_cap(&dao, 8_000_000_000, false));
+    harness.send(propose).await.unwrap();
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    assert_eq!((pending.kind, pending.new_cap), (CapKind::Wallet, 8_000_000_000));
+
+    // The old key gave up proposing along with governance
+    let propose = harness.propose_wallet_cap(&governance.pubkey(), 7_000_000_000, true);
+    assert_eq!(harness.send_signed(propose, &[&governance]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+
+    // Without the DAO program's seeds the PDA is just an unsigned account
+    let mut unsigned = harness.propose_wallet_cap(&dao, 7_000_000_000, true);
+    unsigned.accounts[1].is_signer = false;
+    let not_signer = anchor_lang::error::ErrorCode::AccountNotSigner as u32;
+    assert_eq!(
+        harness.send(unsigned).await,
+        Err(TransactionError::InstructionError(0, InstructionError::Custom(not_signer)))
+    );
+}
+
+#[tokio::test]
+async fn proposals_use_the_configured_timelock() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let (config, mint) = (harness.config(), harness.mint);
+    let governed = || accounts::TimelockUpdate {
+        config,
+        governance_authority: authority,
+        mint,
+        approval: None,
+        event_authority: event_authority(),
+        program: one_kx_hook::ID,
+    };
+
+    let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    assert_eq!(pending.execution_time - pending.proposed_at, i64::from(TIMELOCK_SECONDS));
+
+    // Shortening the timelock waits out the current one, so it can't be used to rush the cap change through
+    let shorten = program_instruction(governed(), instruction::ProposeTimelockUpdate { timelock_seconds: 3_600 });
+    harness.send_signed(shorten, &[&governance]).await.unwrap();
+    let execute = program_instruction(governed(), instruction::ExecuteTimelockUpdate {});
+    assert_eq!(
+        harness.send_signed(execute, &[&governance]).await,
+        Err(hook_error(HookError::TimelockNotExpired))
+    );
+    assert_eq!(harness.config_account().await.timelock_seconds, TIMELOCK_SECONDS);
+
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let execute = program_instruction(governed(), instruction::ExecuteTimelockUpdate {});
+    harness.send_signed(execute, &[&governance]).await.unwrap();
+    let state = harness.config_account().await;
+    assert_eq!(state.timelock_seconds, 3_600);
+    assert!(state.pending_timelock_update.is_none());
+
+    // New proposals pick up the shorter delay
+    let repropose = harness.propose_wallet_cap(&authority, 9_000_000_000, true);
+    harness.send_signed(repropose, &[&governance]).await.unwrap();
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    assert_eq!(pending.execution_time - pending.proposed_at, 3_600);
+
+    // Below the minimum is rejected both at proposal and at initialize
+    let too_short = instruction::ProposeTimelockUpdate { timelock_seconds: params::MIN_TIMELOCK_SECONDS - 1 };
+    let too_short = program_instruction(governed(), too_short);
+    assert_eq!(
+        harness.send_signed(too_short, &[&governance]).await,
+        Err(hook_error(HookError::InvalidTimelock))
+    );
+    let mut fresh = Harness::new_uninitialized(&[]).await;
+    let initialize = fresh.initialize_instruction(0, 0, 0);
+    assert_eq!(fresh.send_initialize(initialize).await, Err(hook_error(HookError::InvalidTimelock)));
+}
+
+#[tokio::test]
+async fn initialize_requires_a_mint_hooked_to_this_program() {
+    let mint_authority = Keypair::new();
+    let mut legacy_mint = mint_account(&mint_authority.pubkey());
+    legacy_mint.owner = anchor_spl::token::ID;
+    for (mint_account, error) in [
+        (hooked_mint_account(None, Some(mint_authority.pubkey())), HookError::MintNotConfiguredForHook),
+        (
+            hooked_mint_account(Some(Pubkey::new_unique()), Some(mint_authority.pubkey())),
+            HookError::MintNotConfiguredForHook,
+        ),
+        (legacy_mint, HookError::InvalidAccountOwner),
+    ] {
+        let mut harness = Harness::with_mint_account(&[], mint_account, mint_authority.insecure_clone()).await;
+        let initialize = harness.initialize_instruction(TIMELOCK_SECONDS, 0, 0);
+        assert_eq!(harness.send_initialize(initialize).await, Err(hook_error(error)));
+        assert!(harness.context.banks_client.get_account(harness.config()).await.unwrap().is_none());
+    }
+
+    // A mint that routes its transfers here can be initialized
+    let mut harness = Harness::new_uninitialized(&[]).await;
+    let initialize = harness.initialize_instruction(TIMELOCK_SECONDS, 0, 0);
+    harness.send_initialize(initialize).await.unwrap();
+    assert_eq!(harness.config_account().await.wallet_cap_raw, WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn only_the_mint_authority_can_initialize() {
+    let mut harness = Harness::new_uninitialized(&[]).await;
+
+    // Someone racing the team to the config PDA can't sign for the mint authority
+    let attacker = Keypair::new();
+    let mut initialize = harness.initialize_instruction(TIMELOCK_SECONDS, 0, 0);
+    initialize.accounts[3].pubkey = attacker.pubkey();
+    assert_eq!(
+        harness.send_signed(initialize, &[&attacker]).await,
+        Err(hook_error(HookError::UnauthorizedInitializer))
+    );
+    assert!(harness.context.banks_client.get_account(harness.config()).await.unwrap().is_none());
+
+    let initialize = harness.initialize_instruction(TIMELOCK_SECONDS, 0, 0);
+    harness.send_initialize(initialize).await.unwrap();
+    assert_eq!(harness.config_account().await.governance_authority, harness.governance.pubkey());
+
+    // A mint without a mint authority can't be initialized by anyone
+    let mint_authority = Keypair::new();
+    let mut harness =
+        Harness::with_mint_account(&[], hooked_mint_account(Some(one_kx_hook::ID), None), mint_authority).await;
+    let initialize = harness.initialize_instruction(TIMELOCK_SECONDS, 0, 0);
+    assert_eq!(harness.send_initialize(initialize).await, Err(hook_error(HookError::UnauthorizedInitializer)));
+}
+
+#[tokio::test]
+async fn initialize_rejects_unusable_authorities() {
+    let mut harness = Harness::new_uninitialized(&[]).await;
+    let (payer, governance) = (harness.context.payer.pubkey(), harness.governance.pubkey());
+    let dev_wallet = Pubkey::new_unique();
+    let args = |dev_wallet, governance_authority, strict_authorities| instruction::Initialize {
+        dev_wallet,
+        governance_authority,
+        cap_unit: CapUnit::Raw,
+        timelock_seconds: TIMELOCK_SECONDS,
+        min_cap_floor: 0,
+        max_cap_change_bps: 0,
+        strict_authorities,
+        allow_confidential: false,
+    };
+
+    for (dev_wallet, governance_authority, strict) in [
+        (Pubkey::default(), governance, false),
+        (dev_wallet, Pubkey::default(), false),
+        (dev_wallet, dev_wallet, true),
+        (payer, governance, true),
+        (dev_wallet, payer, true),
+    ] {
+        let initialize = harness.initialize_with(args(dev_wallet, governance_authority, strict));
+        assert_eq!(
+            harness.send_initialize(initialize).await,
+            Err(hook_error(HookError::InvalidAuthority)),
+            "dev wallet {} / governance {} (strict: {}) accepted",
+            dev_wallet,
+            governance_authority,
+            strict
+        );
+    }
+    assert!(harness.context.banks_client.get_account(harness.config()).await.unwrap().is_none());
+
+    // Without the strict flag one key may be both, which is flagged with an event
+    let initialize = harness.initialize_with(args(governance, governance, false));
+    let mint_authority = harness.mint_authority.insecure_clone();
+    let events = harness.send_for_events(initialize, &[&mint_authority]).await;
+    let warning = decode_event::<DevWalletIsGovernanceAuthority>(&events[0]).expect("warning event");
+    assert_eq!((warning.mint, warning.authority), (harness.mint, governance));
+    let config = harness.config_account().await;
+    assert_eq!((config.dev_wallets[0], config.governance_authority), (governance, governance));
+}
+
+/// A harness with a pending wallet cap proposal, plus an execute instruction for it cranked by the fee payer
+async fn pending_cap_proposal() -> (Harness, Instruction) {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let propose = harness.propose_wallet_cap(&governance.pubkey(), 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let execute = harness.execute_cap_update(&harness.context.payer.pubkey());
+    (harness, execute)
+}
+
+#[tokio::test]
+async fn pending_cap_proposal_is_only_replaced_with_overwrite() {
+    let (mut harness, _) = pending_cap_proposal().await;
+    let governance = harness.governance.insecure_clone();
+    let original = harness.config_account().await.pending_cap_update.unwrap();
+
+    let propose = harness.propose_wallet_cap(&governance.pubkey(), 9_000_000_000, false);
+    assert_eq!(
+        harness.send_signed(propose, &[&governance]).await,
+        Err(hook_error(HookError::ProposalAlreadyPending))
+    );
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    assert_eq!((pending.new_cap, pending.execution_time), (original.new_cap, original.execution_time));
+
+    // Overwriting cancels the old proposal on the record before proposing the new one, and restarts the timelock
+    harness.warp_clock(60 * 60).await;
+    let overwrite = harness.propose_wallet_cap(&governance.pubkey(), 9_000_000_000, true);
+    let events = harness.send_for_events(overwrite, &[&governance]).await;
+    assert_eq!(events.len(), 2);
+    let canceled = decode_event::<WalletCapUpdateCanceled>(&events[0]).expect("cancel event first");
+    assert_eq!(canceled.canceled_cap, 8_000_000_000);
+    let proposed = decode_event::<WalletCapUpdateProposed>(&events[1]).expect("proposal event second");
+    assert_eq!(proposed.new_cap, 9_000_000_000);
+
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    assert_eq!(pending.new_cap, 9_000_000_000);
+    assert_eq!(pending.proposed_at, original.proposed_at + 60 * 60);
+    assert_eq!(pending.execution_time, pending.proposed_at + i64::from(TIMELOCK_SECONDS));
+}
+
+#[tokio::test]
+async fn cap_proposal_executes_until_it_expires() {
+    let (mut harness, execute) = pending_cap_proposal().await;
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    assert_eq!(pending.expires_at - pending.execution_time, i64::from(params::DEFAULT_PROPOSAL_EXPIRY_SECONDS));
+
+    harness.set_unix_timestamp(pending.expires_at - 1).await;
+    harness.send(execute).await.unwrap();
+    let state = harness.config_account().await;
+    assert_eq!(state.wallet_cap_raw, 8_000_000_000);
+    assert!(state.pending_cap_update.is_none());
+}
+
+#[tokio::test]
+async fn expired_cap_proposal_is_rejected_and_swept() {
+    let (mut harness, execute) = pending_cap_proposal().await;
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+
+    // Nothing to sweep while the proposal is still live
+    let sweep = harness.sweep_expired_proposal();
+    assert_eq!(harness.send(sweep).await, Err(hook_error(HookError::ProposalNotExpired)));
+
+    harness.set_unix_timestamp(pending.expires_at).await;
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::ProposalExpired)));
+    assert_eq!(harness.config_account().await.wallet_cap_raw, WALLET_CAP_RAW);
+
+    // Sweeping needs no governance signature; the fee payer here is unrelated to governance
+    let sweep = harness.sweep_expired_proposal();
+    harness.send(sweep).await.unwrap();
+    let state = harness.config_account().await;
+    assert!(state.pending_cap_update.is_none());
+    assert_eq!(state.wallet_cap_raw, WALLET_CAP_RAW);
+
+    let sweep = harness.sweep_expired_proposal();
+    assert_eq!(harness.send(sweep).await, Err(hook_error(HookError::NoPendingUpdate)));
+}
+
+#[tokio::test]
+async fn proposal_nonces_tie_events_to_the_governance_log() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    assert!(harness.governance_log_page(0).await.is_empty());
+
+    let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+    let events = harness.send_for_events(propose, &[&governance]).await;
+    assert_eq!(decode_event::<WalletCapUpdateProposed>(&events[0]).unwrap().proposal_nonce, 1);
+
+    let cancel = program_instruction(harness.cap_update_accounts(&authority), instruction::CancelWalletCapUpdate {});
+    let events = harness.send_for_events(cancel, &[&governance]).await;
+    assert_eq!(decode_event::<WalletCapUpdateCanceled>(&events[0]).unwrap().proposal_nonce, 1);
+
+    // Canceling doesn't hand the nonce out again
+    let propose = harness.propose_wallet_cap(&authority, 7_000_000_000, false);
+    let events = harness.send_for_events(propose, &[&governance]).await;
+    assert_eq!(decode_event::<WalletCapUpdateProposed>(&events[0]).unwrap().proposal_nonce, 2);
+    assert_eq!(harness.config_account().await.pending_cap_update.unwrap().nonce, 2);
+
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let cranker = Keypair::new();
+    let execute = harness.execute_cap_update(&cranker.pubkey());
+    let events = harness.send_for_events(execute, &[&cranker]).await;
+    assert_eq!(decode_event::<WalletCapUpdated>(&events[0]).unwrap().proposal_nonce, 2);
+    assert_eq!(harness.config_account().await.proposal_nonce, 2);
+
+    let log = harness.governance_log_page(0).await;
+    let summary: Vec<_> = log.iter().map(|entry| (entry.action, entry.nonce, entry.value, entry.actor)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (GovernanceLogAction::Proposed, 1, 8_000_000_000, authority),
+            (GovernanceLogAction::Canceled, 1, 8_000_000_000, authority),
+            (GovernanceLogAction::Proposed, 2, 7_000_000_000, authority),
+            (GovernanceLogAction::Executed, 2, 7_000_000_000, cranker.pubkey()),
+        ]
+    );
+    assert!(log.windows(2).all(|pair| pair[0].timestamp <= pair[1].timestamp));
+    assert!(harness.governance_log_page(1).await.is_empty());
+}
+
+#[tokio::test]
+async fn can_receive_agrees_with_execute_at_every_cap_boundary() {
+    let balances = [0, 1, WALLET_CAP_RAW / 2, WALLET_CAP_RAW - 1, WALLET_CAP_RAW, WALLET_CAP_RAW + 1];
+    let mut all = vec![100 * WALLET_CAP_RAW];
+    all.extend_from_slice(&balances);
+    let mut harness = Harness::new(&all).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    for paused in [false, true] {
+        if paused {
+            let pause = harness.set_pause(&authority, true);
+            harness.send_signed(pause, &[&governance]).await.unwrap();
+        }
+        for (index, balance) in balances.into_iter().enumerate() {
+            let room = WALLET_CAP_RAW.saturating_sub(balance);
+            let amounts = [0, 1, room.saturating_sub(1), room, room + 1, WALLET_CAP_RAW];
+            for amount in amounts {
+                let destination = index + 1;
+                let preflight = harness.can_receive(destination, amount).await;
+                let outcome = harness.execute(0, destination, amount).await;
+                assert_eq!(
+                    preflight.allowed,
+                    outcome.is_ok(),
+                    "balance {} amount {} paused {}: {:?} vs {:?}",
+                    balance,
+                    amount,
+                    paused,
+                    preflight,
+                    outcome
+                );
+
+                let expected_reason = match (amount, &outcome) {
+                    (0, _) => ReceiveReason::Unchanged,
+                    (_, Ok(())) => ReceiveReason::Allowed,
+                    (_, _) if paused => ReceiveReason::Paused,
+                    _ => ReceiveReason::OverCap,
+                };
+                assert_eq!(preflight.reason_code, expected_reason as u8);
+                if amount > 0 && outcome.is_ok() {
+                    assert_eq!(preflight.remaining_capacity, WALLET_CAP_RAW - balance - amount);
+                }
+            }
+        }
+    }
+
+    // Every account is read-only and none has to sign, so it is always safe to simulate
+    let accounts = accounts::CanReceive {
+        config: harness.config(),
+        mint: harness.mint,
+        destination: harness.token_accounts[1],
+        exempt_registry: pda(&[b"exempt-registry", harness.mint.as_ref()]),
+    };
+    assert!(accounts.to_account_metas(None).iter().all(|meta| !meta.is_writable && !meta.is_signer));
+}
+
+#[tokio::test]
+async fn get_config_reports_caps_proposal_and_pause_state() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    // Readable with nothing but the config and the mint, and no signer beyond the fee payer
+    let config = harness.config_account().await;
+    let view = harness.config_view().await;
+    assert_eq!(view.view_version, 5);
+    assert_eq!((view.wallet_cap_raw, view.buy_cap_raw, view.max_sell_raw), (WALLET_CAP_RAW, config.buy_cap_raw, 0));
+    assert_eq!(view.cap_unit, CapUnit::Raw);
+    assert_eq!(view.dev_wallets, config.dev_wallets);
+    assert_eq!(view.governance_authority, authority);
+    assert_eq!(view.pending_cap_update, None);
+    assert_eq!((view.paused_until, view.paused), (0, false));
+    assert!(!view.allow_confidential);
+    assert!(!view.require_wallet_owners);
+    assert!(view.finalized);
+
+    let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    let proposal = harness.config_view().await.pending_cap_update.expect("pending proposal");
+    assert_eq!(proposal.kind, CapKind::Wallet);
+    assert_eq!((proposal.new_cap, proposal.nonce, proposal.proposed_by), (8_000_000_000, 1, authority));
+    assert_eq!(
+        (proposal.proposed_at, proposal.execution_time, proposal.expires_at),
+        (pending.proposed_at, pending.execution_time, pending.expires_at)
+    );
+
+    let pause = harness.set_pause(&authority, true);
+    harness.send_signed(pause, &[&governance]).await.unwrap();
+    let view = harness.config_view().await;
+    assert_eq!((view.paused_until, view.paused), (i64::MAX, true));
+}
+
+#[tokio::test]
+async fn memo_hash_follows_the_proposal_lifecycle() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let (canceled_memo, executed_memo, expired_memo) = ([1; 32], [2; 32], [3; 32]);
+
+    // The proposer and memo are stored on the proposal and announced with it
+    let propose = harness.propose_wallet_cap_with_memo(&authority, 8_000_000_000, false, canceled_memo);
+    let events = harness.send_for_events(propose, &[&governance]).await;
+    let proposed = decode_event::<WalletCapUpdateProposed>(&events[0]).unwrap();
+    assert_eq!((proposed.proposed_by, proposed.memo_hash), (authority, canceled_memo));
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    assert_eq!((pending.proposed_by, pending.memo_hash), (authority, canceled_memo));
+
+    let cancel = program_instruction(harness.cap_update_accounts(&authority), instruction::CancelWalletCapUpdate {});
+    let events = harness.send_for_events(cancel, &[&governance]).await;
+    assert_eq!(decode_event::<WalletCapUpdateCanceled>(&events[0]).unwrap().memo_hash, canceled_memo);
+
+    // Overwriting echoes the replaced proposal's memo on its cancel event
+    let propose = harness.propose_wallet_cap_with_memo(&authority, 8_000_000_000, false, canceled_memo);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let overwrite = harness.propose_wallet_cap_with_memo(&authority, 7_000_000_000, true, executed_memo);
+    let events = harness.send_for_events(overwrite, &[&governance]).await;
+    assert_eq!(decode_event::<WalletCapUpdateCanceled>(&events[0]).unwrap().memo_hash, canceled_memo);
+    assert_eq!(decode_event::<WalletCapUpdateProposed>(&events[1]).unwrap().memo_hash, executed_memo);
+
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let cranker = Keypair::new();
+    let execute = harness.execute_cap_update(&cranker.pubkey());
+    let events = harness.send_for_events(execute, &[&cranker]).await;
+    assert_eq!(decode_event::<WalletCapUpdated>(&events[0]).unwrap().memo_hash, executed_memo);
+
+    let propose = harness.propose_wallet_cap_with_memo(&authority, 6_000_000_000, false, expired_memo);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let pending = harness.config_account().await.pending_cap_update.unwrap();
+    harness.set_unix_timestamp(pending.expires_at).await;
+    let sweep = harness.sweep_expired_proposal();
+    let events = harness.send_for_events(sweep, &[]).await;
+    assert_eq!(decode_event::<WalletCapUpdateExpired>(&events[0]).unwrap().memo_hash, expired_memo);
+}
+
+#[tokio::test]
+async fn cap_update_roles_gate_each_step() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let (proposer, executor, outsider) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let cancel = |harness: &Harness, signer: &Keypair| {
+        program_instruction(harness.cap_update_accounts(&signer.pubkey()), instruction::CancelWalletCapUpdate {})
+    };
+
+    for (role, key) in [(CapUpdateRole::Proposer, proposer.pubkey()), (CapUpdateRole::Executor, executor.pubkey())] {
+        let assign = harness.set_cap_update_role(&governance.pubkey(), role, key);
+        harness.send_signed(assign, &[&governance]).await.unwrap();
+    }
+    let state = harness.config_account().await;
+    assert_eq!((state.proposer_authority, state.executor_authority), (proposer.pubkey(), executor.pubkey()));
+
+    // Holding a role doesn't allow reassigning one
+    let grab = harness.set_cap_update_role(&proposer.pubkey(), CapUpdateRole::Executor, proposer.pubkey());
+    assert_eq!(harness.send_signed(grab, &[&proposer]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+
+    // Only the proposer proposes, governance included
+    for signer in [&governance, &executor, &outsider] {
+        let propose = harness.propose_wallet_cap(&signer.pubkey(), 8_000_000_000, false);
+        assert_eq!(harness.send_signed(propose, &[signer]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+    }
+
+    // The proposer and governance may cancel, nobody else
+    for canceler in [&proposer, &governance] {
+        let propose = harness.propose_wallet_cap(&proposer.pubkey(), 8_000_000_000, false);
+        harness.send_signed(propose, &[&proposer]).await.unwrap();
+        for signer in [&executor, &outsider] {
+            let attempt = cancel(&harness, signer);
+            let result = harness.send_signed(attempt, &[signer]).await;
+            assert_eq!(result, Err(hook_error(HookError::UnauthorizedGovernance)));
+        }
+        let withdraw = cancel(&harness, canceler);
+        harness.send_signed(withdraw, &[canceler]).await.unwrap();
+        assert!(harness.config_account().await.pending_cap_update.is_none());
+    }
+
+    // Only the executor executes
+    let propose = harness.propose_wallet_cap(&proposer.pubkey(), 8_000_000_000, false);
+    harness.send_signed(propose, &[&proposer]).await.unwrap();
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    for signer in [&governance, &proposer, &outsider] {
+        let execute = harness.execute_cap_update(&signer.pubkey());
+        assert_eq!(harness.send_signed(execute, &[signer]).await, Err(hook_error(HookError::UnauthorizedExecutor)));
+    }
+    let execute = harness.execute_cap_update(&executor.pubkey());
+    harness.send_signed(execute, &[&executor]).await.unwrap();
+    assert_eq!(harness.config_account().await.wallet_cap_raw, 8_000_000_000);
+}
+
+#[tokio::test]
+async fn anyone_can_execute_a_matured_cap_proposal() {
+    let (mut harness, _) = pending_cap_proposal().await;
+    let cranker = Keypair::new();
+
+    // Not before the timelock, whoever asks
+    let execute = harness.execute_cap_update(&cranker.pubkey());
+    assert_eq!(harness.send_signed(execute, &[&cranker]).await, Err(hook_error(HookError::TimelockNotExpired)));
+
+    // Afterwards an unrelated key cranks it without the governance key signing anything
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let execute = harness.execute_cap_update(&cranker.pubkey());
+    let events = harness.send_for_events(execute, &[&cranker]).await;
+    let updated = decode_event::<WalletCapUpdated>(&events[0]).expect("cap update event");
+    assert_eq!(updated.executed_by, cranker.pubkey());
+    assert_eq!((updated.old_cap, updated.new_cap), (WALLET_CAP_RAW, 8_000_000_000));
+    let state = harness.config_account().await;
+    assert_eq!(state.wallet_cap_raw, 8_000_000_000);
+    assert!(state.pending_cap_update.is_none());
+}
+
+#[tokio::test]
+async fn cap_increases_skip_the_timelock_but_decreases_do_not() {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    // Lowering the cap, or "raising" it to the same value, has to be proposed
+    for new_cap in [WALLET_CAP_RAW - 1, WALLET_CAP_RAW] {
+        let decrease = harness.immediate_cap_increase(&authority, new_cap);
+        assert_eq!(harness.send_signed(decrease, &[&governance]).await, Err(hook_error(HookError::TimelockRequired)));
+    }
+    let too_high = harness.immediate_cap_increase(&authority, 100_000_000_001);
+    assert_eq!(harness.send_signed(too_high, &[&governance]).await, Err(hook_error(HookError::InvalidWalletCap)));
+    let outsider = Keypair::new();
+    let increase = harness.immediate_cap_increase(&outsider.pubkey(), 8_000_000_000);
+    assert_eq!(harness.send_signed(increase, &[&outsider]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+
+    // An increase applies in the same slot, with nothing left pending
+    let before: Clock = harness.context.banks_client.get_sysvar().await.unwrap();
+    let increase = harness.immediate_cap_increase(&authority, 8_000_000_000);
+    let events = harness.send_for_events(increase, &[&governance]).await;
+    let updated = decode_event::<WalletCapUpdated>(&events[0]).expect("cap update event");
+    assert!(updated.immediate);
+    assert_eq!((updated.old_cap, updated.new_cap, updated.proposal_nonce), (WALLET_CAP_RAW, 8_000_000_000, 0));
+    let state = harness.config_account().await;
+    assert_eq!(state.wallet_cap_raw, 8_000_000_000);
+    assert!(state.pending_cap_update.is_none());
+    let after: Clock = harness.context.banks_client.get_sysvar().await.unwrap();
+    assert_eq!((after.slot, updated.updated_at), (before.slot, before.unix_timestamp));
+}
+
+#[tokio::test]
+async fn cap_floor_is_set_at_initialize_and_only_rises() {
+    const FLOOR: u64 = 2_000_000_000;
+    let mut harness = Harness::new_uninitialized(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    // The starting cap has to clear the floor
+    let initialize = harness.initialize_instruction(TIMELOCK_SECONDS, WALLET_CAP_RAW + 1, 0);
+    assert_eq!(harness.send_initialize(initialize).await, Err(hook_error(HookError::CapBelowFloor)));
+    harness.initialize_config(FLOOR, 0).await;
+    assert_eq!(harness.config_account().await.min_cap_floor, FLOOR);
+
+    // Proposals below the floor are refused, ones at it go through
+    let propose = harness.propose_wallet_cap(&authority, FLOOR - 1, false);
+    assert_eq!(harness.send_signed(propose, &[&governance]).await, Err(hook_error(HookError::CapBelowFloor)));
+    let propose = harness.propose_wallet_cap(&authority, FLOOR, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+
+    // The floor can't come down, but raising it also blocks the pending proposal now under it
+    let lower = harness.raise_min_cap_floor(&authority, FLOOR - 1);
+    assert_eq!(harness.send_signed(lower, &[&governance]).await, Err(hook_error(HookError::CapFloorLowered)));
+    let raise = harness.raise_min_cap_floor(&authority, FLOOR + 1);
+    harness.send_signed(raise, &[&governance]).await.unwrap();
+    assert_eq!(harness.config_account().await.min_cap_floor, FLOOR + 1);
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let execute = harness.execute_cap_update(&harness.context.payer.pubkey());
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::CapBelowFloor)));
+    assert_eq!(harness.config_account().await.wallet_cap_raw, WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn cap_proposals_are_limited_to_the_max_swing() {
+    let mut harness = Harness::new_uninitialized(&[]).await;
+    harness.initialize_config(0, 5_000).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    // ±50% of the 5 token cap, in both directions and for immediate increases
+    for new_cap in [WALLET_CAP_RAW * 3 / 2 + 1, WALLET_CAP_RAW / 2 - 1] {
+        let propose = harness.propose_wallet_cap(&authority, new_cap, false);
+        assert_eq!(harness.send_signed(propose, &[&governance]).await, Err(hook_error(HookError::CapChangeTooLarge)));
+    }
+    let increase = harness.immediate_cap_increase(&authority, WALLET_CAP_RAW * 3 / 2 + 1);
+    assert_eq!(harness.send_signed(increase, &[&governance]).await, Err(hook_error(HookError::CapChangeTooLarge)));
+
+    for new_cap in [WALLET_CAP_RAW / 2, WALLET_CAP_RAW * 3 / 2] {
+        let propose = harness.propose_wallet_cap(&authority, new_cap, true);
+        harness.send_signed(propose, &[&governance]).await.unwrap();
+    }
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let execute = harness.execute_cap_update(&harness.context.payer.pubkey());
+    harness.send(execute).await.unwrap();
+    assert_eq!(harness.config_account().await.wallet_cap_raw, WALLET_CAP_RAW * 3 / 2);
+}
+
+/// A harness with `veto` installed as veto authority, through the timelock
+async fn with_veto_authority(veto: &Keypair) -> Harness {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let propose = harness.veto_authority_update(
+        &governance.pubkey(),
+        instruction::ProposeVetoAuthorityUpdate { veto_authority: Some(veto.pubkey()) },
+    );
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    let execute = harness.veto_authority_update(&governance.pubkey(), instruction::ExecuteVetoAuthorityUpdate {});
+    harness.send_signed(execute, &[&governance]).await.unwrap();
+    assert_eq!(harness.config_account().await.veto_authority, Some(veto.pubkey()));
+    harness
+}
+
+#[tokio::test]
+async fn veto_authority_cancels_but_never_proposes() {
+    let veto = Keypair::new();
+    let mut harness = with_veto_authority(&veto).await;
+    let governance = harness.governance.insecure_clone();
+    let veto_cancel =
+        program_instruction(harness.cap_update_accounts(&veto.pubkey()), instruction::CancelWalletCapUpdate {});
+
+    // Vetoing is a cancel that announces itself as a veto
+    let propose = harness.propose_wallet_cap(&governance.pubkey(), 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    let events = harness.send_for_events(veto_cancel.clone(), &[&veto]).await;
+    let vetoed = decode_event::<WalletCapUpdateVetoed>(&events[0]).expect("veto event");
+    assert_eq!((vetoed.vetoed_cap, vetoed.veto_authority, vetoed.proposal_nonce), (8_000_000_000, veto.pubkey(), 1));
+    assert!(decode_event::<WalletCapUpdateCanceled>(&events[0]).is_none());
+    assert!(harness.config_account().await.pending_cap_update.is_none());
+    let last = harness.governance_log_page(0).await.pop().unwrap();
+    assert_eq!((last.action, last.actor), (GovernanceLogAction::Vetoed, veto.pubkey()));
+
+    // Other pending changes can be vetoed too
+    let (config, mint) = (harness.config(), harness.mint);
+    let timelock = |governance_authority: Pubkey| accounts::TimelockUpdate {
+        config,
+        governance_authority,
+        mint,
+        approval: None,
+        event_authority: event_authority(),
+        program: one_kx_hook::ID,
+    };
+    let shorten = instruction::ProposeTimelockUpdate { timelock_seconds: 3_600 };
+    harness.send_signed(program_instruction(timelock(governance.pubkey()), shorten), &[&governance]).await.unwrap();
+    let cancel = program_instruction(timelock(veto.pubkey()), instruction::CancelTimelockUpdate {});
+    harness.send_signed(cancel, &[&veto]).await.unwrap();
+    assert!(harness.config_account().await.pending_timelock_update.is_none());
+
+    // ...but the veto authority can't put anything on the table or touch the roles
+    let attempts = [
+        harness.propose_wallet_cap(&veto.pubkey(), 8_000_000_000, false),
+        program_instruction(timelock(veto.pubkey()), instruction::ProposeTimelockUpdate { timelock_seconds: 3_600 }),
+        harness.veto_authority_update(&veto.pubkey(), instruction::ProposeVetoAuthorityUpdate { veto_authority: None }),
+        harness.set_cap_update_role(&veto.pubkey(), CapUpdateRole::Proposer, veto.pubkey()),
+    ];
+    for instruction in attempts {
+        let result = harness.send_signed(instruction, &[&veto]).await;
+        assert_eq!(result, Err(hook_error(HookError::UnauthorizedGovernance)));
+    }
+}
+
+#[tokio::test]
+async fn a_disabled_hook_enforces_nothing_and_cannot_be_reenabled() {
+    let mut harness = Harness::new(&[100 * WALLET_CAP_RAW, WALLET_CAP_RAW]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+
+    let propose = harness.hook_disable(&authority, instruction::ProposeHookDisable {});
+    let events = harness.send_for_events(propose, &[&governance]).await;
+    let proposed = decode_event::<HookDisableProposed>(&events[0]).expect("proposal event");
+    assert_eq!(proposed.execution_time, proposed.proposed_at + params::HOOK_DISABLE_TIMELOCK_SECONDS);
+
+    // The fixed disable timelock has to pass first
+    let disable = harness.hook_disable(&authority, instruction::PermanentlyDisableHook {});
+    assert_eq!(
+        harness.send_signed(disable, &[&governance]).await,
+        Err(hook_error(HookError::TimelockNotExpired))
+    );
+    harness.set_unix_timestamp(proposed.execution_time).await;
+
+    // Pause as well, on top of the cap the destination is already at
+    let pause = harness.set_pause(&authority, true);
+    harness.send_signed(pause, &[&governance]).await.unwrap();
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::CircuitBreakerTripped)));
+
+    let disable = harness.hook_disable(&authority, instruction::PermanentlyDisableHook {});
+    let events = harness.send_for_events(disable, &[&governance]).await;
+    let disabled = decode_event::<HookPermanentlyDisabled>(&events[0]).expect("disable event");
+    assert_eq!(disabled.disabled_at, proposed.execution_time);
+    let config = harness.config_account().await;
+    assert!(config.hook_disabled && config.pending_hook_disable.is_none());
+
+    // Neither the pause nor the cap applies any more, and the preflight view agrees
+    harness.execute(0, 1, 10 * WALLET_CAP_RAW).await.unwrap();
+    let preflight = harness.can_receive(1, 10 * WALLET_CAP_RAW).await;
+    assert!(preflight.allowed);
+    assert_eq!(preflight.reason_code, ReceiveReason::HookDisabled as u8);
+
+    // The account checks still run
+    harness.set_transferring(0, false).await;
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::NotInTransfer)));
+    harness.set_transferring(0, true).await;
+
+    // Nor does finalize_setup's flag; a config that somehow lost it still lets everything through
+    let mut config = harness.config_account().await;
+    config.finalized = false;
+    harness.store_config(&config).await;
+    harness.execute(0, 1, 1).await.unwrap();
+    assert!(harness.can_receive(1, 1).await.allowed);
+
+    // Nothing turns it back on: there is no re-enable, a new proposal is refused and migration won't run
+    let propose = harness.hook_disable(&authority, instruction::ProposeHookDisable {});
+    assert_eq!(harness.send_signed(propose, &[&governance]).await, Err(hook_error(HookError::HookDisabled)));
+    let cancel = harness.hook_disable(&authority, instruction::CancelHookDisable {});
+    assert_eq!(harness.send_signed(cancel, &[&governance]).await, Err(hook_error(HookError::NoPendingUpdate)));
+    let migrate = harness.migrate_config(&authority);
+    assert_eq!(harness.send_signed(migrate, &[&governance]).await, Err(hook_error(HookError::HookDisabled)));
+    let unpause = harness.set_pause(&authority, false);
+    harness.send_signed(unpause, &[&governance]).await.unwrap();
+    assert!(harness.config_account().await.hook_disabled);
+    harness.execute(0, 1, 10 * WALLET_CAP_RAW).await.unwrap();
+}
+
+#[tokio::test]
+async fn lockups_hold_outbound_transfers_until_the_cliff_then_release_linearly() {
+    let mut harness = Harness::new(&[1_000, 0, 1_000]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let now = 1_700_000_000;
+    harness.set_unix_timestamp(now).await;
+
+    // 1_000 stays locked until the cliff, then unlocks over the following 1_000 seconds
+    let (cliff, release_end) = (now + 100, now + 1_100);
+    let create = harness.create_vesting_lockup(&authority, harness.owners[0], 1_000, cliff, Some(release_end));
+    let events = harness.send_for_events(create, &[&governance]).await;
+    let created = decode_event::<LockupCreated>(&events[0]).expect("lockup event");
+    assert_eq!((created.owner, created.locked_until), (harness.owners[0], cliff));
+
+    // Before the cliff the locked wallet can't send anything, but it can still receive
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::TokensLocked)));
+    harness.execute(2, 0, 1).await.unwrap();
+    // Wallets without a lockup are unaffected
+    harness.execute(2, 1, 1_000).await.unwrap();
+
+    // Halfway through the release 500 is still held back, so 500 of the 1_000 balance can go
+    harness.set_unix_timestamp(cliff + 500).await;
+    harness.execute(0, 1, 500).await.unwrap();
+    assert_eq!(harness.execute(0, 1, 501).await, Err(hook_error(HookError::TokensLocked)));
+    harness.set_unix_timestamp(cliff + 900).await;
+    harness.execute(0, 1, 900).await.unwrap();
+    assert_eq!(harness.execute(0, 1, 901).await, Err(hook_error(HookError::TokensLocked)));
+
+    harness.set_unix_timestamp(release_end).await;
+    harness.execute(0, 1, 1_000).await.unwrap();
+
+    // A lockup of another owner doesn't stand in for the source's own
+    let create = harness.create_lockup(&authority, harness.owners[2], 1_000, i64::MAX);
+    harness.send_signed(create, &[&governance]).await.unwrap();
+    let mut execute = harness.execute_instruction(0, 1, 1);
+    execute.accounts[11].pubkey = harness.lockup(&harness.owners[2]);
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::InvalidLockup)));
+}
+
+/// Register the first token account as a pool vault, so that transfers out of it are buys
+async fn register_pool_vault(harness: &mut Harness) {
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let init = harness.init_exempt_registry(&authority);
+    harness.send_signed(init, &[&governance]).await.unwrap();
+    let mut add = harness.add_exempt_entry(&authority, harness.token_accounts[0]);
+    add.data = instruction::AddExemptEntry {
+        token_account: harness.token_accounts[0],
+        kind: ExemptKind::PoolVault,
+        expires_at: None,
+    }
+    .data();
+    harness.send_signed(add, &[&governance]).await.unwrap();
+}
+
+/// A ConfigExtension of `mint` with the given snipe window, and nothing recorded yet
+fn config_extension(mint: Pubkey, snipe_window_seconds: u32) -> ConfigExtension {
+    ConfigExtension {
+        mint,
+        launch_detected_at: None,
+        launch_detected_slot: None,
+        launch_window_used: false,
+        launch_reset_used: false,
+        snipe_window_seconds,
+        memo_required_above: None,
+        dev_daily_outflow_limit: None,
+        dev_outflow_window_start: 0,
+        dev_outflow_in_window: 0,
+        reserved: [0; 90],
+    }
+}
+
+/// Put an account of this program holding `value` at `address`
+fn set_program_account<T: AccountSerialize>(harness: &mut Harness, address: &Pubkey, value: &T) {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    let account = Account { lamports: 1_000_000, data, owner: one_kx_hook::ID, executable: false, rent_epoch: 0 };
+    harness.context.set_account(address, &account.into());
+}
+
+#[tokio::test]
+async fn the_launch_is_only_recorded_in_the_mints_own_extension() {
+    let mut harness = Harness::new(&[1_000, 0]).await;
+    register_pool_vault(&mut harness).await;
+
+    // Passed directly, another mint's extension can't take the launch
+    let address = Pubkey::new_unique();
+    set_program_account(&mut harness, &address, &config_extension(Pubkey::new_unique(), 0));
+    let mut execute = harness.execute_instruction(0, 1, 1);
+    execute.accounts[12].pubkey = address;
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::InvalidConfigExtension)));
+    // Without an extension of its own the mint records nothing, and the buy goes through
+    harness.execute(0, 1, 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn snipers_are_only_listed_in_the_mints_own_log() {
+    let mut harness = Harness::new(&[1_000, 0]).await;
+    register_pool_vault(&mut harness).await;
+    let extension = pda(&[b"config-ext", harness.mint.as_ref()]);
+    set_program_account(&mut harness, &extension, &config_extension(harness.mint, 60));
+
+    // Passed directly, another mint's log can't take the flag
+    let log = SniperLog { mint: Pubkey::new_unique(), dropped: 0, sniper_list: Vec::new() };
+    let address = Pubkey::new_unique();
+    set_program_account(&mut harness, &address, &log);
+    let mut execute = harness.execute_instruction(0, 1, 1);
+    execute.accounts[13].pubkey = address;
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::InvalidSniperLog)));
+
+    // Without a log of its own the mint lists nobody, but still reports the snipe
+    let events = harness.send_for_events(harness.execute_instruction(0, 1, 1), &[]).await;
+    let flagged = events.iter().find_map(|event| decode_event::<SniperFlagged>(event)).expect("sniper event");
+    assert_eq!((flagged.owner, flagged.amount, flagged.logged), (harness.owners[1], 1, false));
+}
+
+#[tokio::test]
+async fn lockups_can_only_be_shortened() {
+    let mut harness = Harness::new(&[1_000, 0]).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let owner = harness.owners[0];
+    let now = 1_700_000_000;
+    harness.set_unix_timestamp(now).await;
+
+    let stranger = Keypair::new();
+    let create = harness.create_lockup(&stranger.pubkey(), owner, 1_000, now + 100);
+    assert_eq!(
+        harness.send_signed(create, &[&stranger]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+    let create = harness.create_vesting_lockup(&authority, owner, 1_000, now + 100, Some(now + 100));
+    assert_eq!(
+        harness.send_signed(create, &[&governance]).await,
+        Err(hook_error(HookError::InvalidLockupSchedule))
+    );
+    let create = harness.create_vesting_lockup(&authority, owner, 1_000, now + 100, Some(now + 1_100));
+    harness.send_signed(create, &[&governance]).await.unwrap();
+
+    // Neither date can move later, and a release can't be stretched past what was promised
+    for (locked_until, linear_release_end) in [(now + 101, Some(now + 1_100)), (now + 100, Some(now + 1_101))] {
+        let extend = harness.shorten_lockup(&authority, owner, locked_until, linear_release_end);
+        assert_eq!(
+            harness.send_signed(extend, &[&governance]).await,
+            Err(hook_error(HookError::LockupExtension))
+        );
+    }
+
+    // Pulling the cliff in to now and dropping the release frees everything at once
+    let shorten = harness.shorten_lockup(&authority, owner, now, None);
+    let events = harness.send_for_events(shorten, &[&governance]).await;
+    let shortened = decode_event::<LockupShortened>(&events[0]).expect("shorten event");
+    assert_eq!((shortened.old_locked_until, shortened.locked_until), (now + 100, now));
+    assert_eq!((shortened.old_linear_release_end, shortened.linear_release_end), (Some(now + 1_100), None));
+    harness.execute(0, 1, 1_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn removing_the_veto_authority_respects_the_timelock() {
+    let veto = Keypair::new();
+    let mut harness = with_veto_authority(&veto).await;
+    let governance = harness.governance.insecure_clone();
+    let authority = governance.pubkey();
+    let veto_cancel =
+        program_instruction(harness.cap_update_accounts(&veto.pubkey()), instruction::CancelWalletCapUpdate {});
+
+    let remove =
+        harness.veto_authority_update(&authority, instruction::ProposeVetoAuthorityUpdate { veto_authority: None });
+    harness.send_signed(remove, &[&governance]).await.unwrap();
+    let execute = harness.veto_authority_update(&authority, instruction::ExecuteVetoAuthorityUpdate {});
+    assert_eq!(
+        harness.send_signed(execute.clone(), &[&governance]).await,
+        Err(hook_error(HookError::TimelockNotExpired))
+    );
+
+    // A change proposed alongside the removal can still be vetoed while the removal waits
+    let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    harness.send_signed(veto_cancel.clone(), &[&veto]).await.unwrap();
+
+    // The veto authority can't block its own removal
+    let keep = harness.veto_authority_update(&veto.pubkey(), instruction::CancelVetoAuthorityUpdate {});
+    assert_eq!(harness.send_signed(keep, &[&veto]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+
+    harness.warp_clock(i64::from(TIMELOCK_SECONDS)).await;
+    harness.send_signed(execute, &[&governance]).await.unwrap();
+    let state = harness.config_account().await;
+    assert!(state.veto_authority.is_none());
+    assert!(state.pending_veto_authority_update.is_none());
+
+    // Once removed, the old veto key is just another outsider
+    let propose = harness.propose_wallet_cap(&authority, 8_000_000_000, false);
+    harness.send_signed(propose, &[&governance]).await.unwrap();
+    assert_eq!(harness.send_signed(veto_cancel, &[&veto]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+    assert!(harness.config_account().await.pending_cap_update.is_some());
+}
+
+/// A harness whose governance is a 2-of-3 multisig over the returned members
+async fn two_of_three() -> (Harness, [Keypair; 3]) {
+    let mut harness = Harness::new(&[]).await;
+    let governance = harness.governance.insecure_clone();
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let keys: Vec<Pubkey> = members.iter().map(|m| m.pubkey()).collect();
+    let set_members = harness.set_governance_members(&governance.pubkey(), &keys, 2);
+    harness.send_signed(set_members, &[&governance]).await.unwrap();
+    (harness, members)
+}
+
+#[tokio::test]
+async fn two_of_three_multisig_lifecycle() {
+    let (mut harness, [alice, bob, carol]) = two_of_three().await;
+    let governance = harness.governance.insecure_clone();
+    let new_cap = 8_000_000_000;
+    let action =
+        GovernanceAction::ProposeCapUpdate { kind: CapKind::Wallet, new_cap, overwrite: false, memo_hash: [0; 32] };
+    let (approval, action_hash) = harness.approval_for(&action).await;
+
+    // The single authority no longer suffices on its own
+    let mut direct = harness.propose_cap_update_with(None, new_cap);
+    direct.accounts[1].pubkey = governance.pubkey();
+    assert_eq!(
+        harness.send_signed(direct, &[&governance]).await,
+        Err(hook_error(HookError::GovernanceApprovalRequired))
+    );
+
+    // Proposing counts as the first approval, which is below the threshold
+    let propose = harness.propose_action(&alice.pubkey(), approval, action);
+    harness.send_signed(propose, &[&alice]).await.unwrap();
+    let execute = harness.propose_cap_update_with(Some(approval), new_cap);
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::GovernanceThresholdNotMet)));
+
+    // Approvals are one per member, members only
+    let outsider = Keypair::new();
+    for (signer, error) in [
+        (&alice, HookError::GovernanceActionAlreadyApproved),
+        (&outsider, HookError::NotGovernanceMember),
+        (&governance, HookError::NotGovernanceMember),
+    ] {
+        let approve = harness.approve_action(&signer.pubkey(), approval, action_hash);
+        assert_eq!(harness.send_signed(approve, &[signer]).await, Err(hook_error(error)));
+    }
+    let approve = harness.approve_action(&bob.pubkey(), approval, action_hash);
+    harness.send_signed(approve, &[&bob]).await.unwrap();
+
+    // The approval is bound to its parameters; with them, anyone can execute it
+    let different_cap = harness.propose_cap_update_with(Some(approval), new_cap + 1);
+    assert_eq!(harness.send(different_cap).await, Err(hook_error(HookError::GovernanceActionMismatch)));
+    let execute = harness.propose_cap_update_with(Some(approval), new_cap);
+    harness.send(execute).await.unwrap();
+    let config = harness.config_account().await;
+    assert_eq!(config.pending_cap_update.unwrap().new_cap, new_cap);
+
+    // Executing used the approval up, so it can't be replayed and late approvals are refused
+    let replay = harness.propose_cap_update_with(Some(approval), new_cap);
+    assert_eq!(harness.send(replay).await, Err(hook_error(HookError::StaleGovernanceApproval)));
+    let approve = harness.approve_action(&carol.pubkey(), approval, action_hash);
+    assert_eq!(harness.send_signed(approve, &[&carol]).await, Err(hook_error(HookError::StaleGovernanceApproval)));
+}
+
+#[tokio::test]
+async fn stale_multisig_approval_is_rejected() {
+    let (mut harness, [alice, bob, carol]) = two_of_three().await;
+    let propose = |new_cap| GovernanceAction::ProposeCapUpdate {
+        kind: CapKind::Wallet,
+        new_cap,
+        overwrite: false,
+        memo_hash: [0; 32],
+    };
+    let (first, second) = (propose(8_000_000_000), propose(9_000_000_000));
+    let (first_approval, first_hash) = harness.approval_for(&first).await;
+    let (second_approval, second_hash) = harness.approval_for(&second).await;
+
+    // Both proposals reach the threshold in the same epoch
+    for (proposer, approver, approval, action_hash, action) in [
+        (&alice, &bob, first_approval, first_hash, first),
+        (&carol, &alice, second_approval, second_hash, second),
+    ] {
+        let propose = harness.propose_action(&proposer.pubkey(), approval, action);
+        harness.send_signed(propose, &[proposer]).await.unwrap();
+        let approve = harness.approve_action(&approver.pubkey(), approval, action_hash);
+        harness.send_signed(approve, &[approver]).await.unwrap();
+    }
+
+    // Executing the first moves the epoch on, which stales the second
+    let execute = harness.propose_cap_update_with(Some(first_approval), 8_000_000_000);
+    harness.send(execute).await.unwrap();
+    let execute = harness.propose_cap_update_with(Some(second_approval), 9_000_000_000);
+    assert_eq!(harness.send(execute).await, Err(hook_error(HookError::StaleGovernanceApproval)));
+    assert_eq!(harness.config_account().await.pending_cap_update.unwrap().new_cap, 8_000_000_000);
+
+    // Stale approvals can be closed for their rent; live ones cannot
+    let close = harness.close_governance_approval(second_approval);
+    harness.send(close).await.unwrap();
+    assert!(harness.context.banks_client.get_account(second_approval).await.unwrap().is_none());
+    let (live_approval, _) = harness.approval_for(&GovernanceAction::ClearCircuitBreaker).await;
+    let propose = harness.propose_action(&bob.pubkey(), live_approval, GovernanceAction::ClearCircuitBreaker);
+    harness.send_signed(propose, &[&bob]).await.unwrap();
+    let close = harness.close_governance_approval(live_approval);
+    assert_eq!(harness.send(close).await, Err(hook_error(HookError::GovernanceApprovalStillActive)));
+}
+
+#[tokio::test]
+async fn guardian_can_only_pause() {
+    let mut harness = Harness::new(&[WALLET_CAP_RAW, 0]).await;
+    let governance = harness.governance.insecure_clone();
+    let guardian = Keypair::new();
+    let set_guardian = harness.set_guardian(&governance.pubkey(), Some(guardian.pubkey()));
+    harness.send_signed(set_guardian, &[&governance]).await.unwrap();
+
+    // The guardian's pause halts transfers
+    let pause = harness.set_pause(&guardian.pubkey(), true);
+    harness.send_signed(pause, &[&guardian]).await.unwrap();
+    assert_eq!(harness.execute(0, 1, 1).await, Err(hook_error(HookError::CircuitBreakerTripped)));
+
+    // It cannot lift the pause or touch anything else
+    let unpause = harness.set_pause(&guardian.pubkey(), false);
+    assert_eq!(
+        harness.send_signed(unpause, &[&guardian]).await,
+        Err(hook_error(HookError::GuardianCannotUnpause))
+    );
+    let mut propose = harness.propose_cap_update_with(None, 8_000_000_000);
+    propose.accounts[1].pubkey = guardian.pubkey();
+    assert_eq!(
+        harness.send_signed(propose, &[&guardian]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+    let replace_guardian = harness.set_guardian(&guardian.pubkey(), Some(Pubkey::new_unique()));
+    assert_eq!(
+        harness.send_signed(replace_guardian, &[&guardian]).await,
+        Err(hook_error(HookError::UnauthorizedGovernance))
+    );
+
+    // Governance unpauses and clears the guardian, which then can't pause either
+    let unpause = harness.set_pause(&governance.pubkey(), false);
+    harness.send_signed(unpause, &[&governance]).await.unwrap();
+    harness.execute(0, 1, 1).await.unwrap();
+    let clear_guardian = harness.set_guardian(&governance.pubkey(), None);
+    harness.send_signed(clear_guardian, &[&governance]).await.unwrap();
+    assert_eq!(harness.config_account().await.guardian, None);
+    let pause = harness.set_pause(&guardian.pubkey(), true);
+    assert_eq!(harness.send_signed(pause, &[&guardian]).await, Err(hook_error(HookError::UnauthorizedGovernance)));
+}
@@ -0,0 +1,421 @@
+// Pins the program's public interface: every instruction's discriminator and argument layout, the
+// account discriminators and sizes, the event schema version and discriminators and the error codes.
+// Clients and the IDL depend on all of them, so a refactor has to leave the rendering byte-for-byte unchanged.
+//
+// After an intentional interface change, rerun with UPDATE_INTERFACE_SNAPSHOT=1 to rewrite the snapshot.
+
+use std::fmt::Write;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{Discriminator, InstructionData};
+use one_kx_hook::{
+    instruction, AirdropAllowanceUpdated, BridgeCustodyRegistered, CapKind, CapUnit, CapUpdateRole,
+    CapUpdateRoleUpdated, CircuitBreakerCleared, CircuitBreakerTriggered, CircuitBreakerUpdated, ConfigClosed,
+    ConfigExtension, ConfigFrozen, ConfigMigrated, DevExemptionExpired, DevExemptionExpiryUpdated,
+    DevOutflowLimitLowered, DevWalletIsGovernanceAuthority, DevWalletUpdateCanceled, DevWalletUpdateProposed,
+    DevWalletUpdated, EVENT_SCHEMA_VERSION, ExemptEntryAdded, ExemptEntryRemoved, ExemptKind, ExemptRegistry,
+    ExemptRegistryPruned, ExtraAccountMetaListClosed, ExtraAccountMetasUpdated, GlobalConfig, GovernanceAction,
+    GovernanceActionApproved, GovernanceActionProposed, GovernanceApproval, GovernanceAuthorityUpdated, GovernanceLog,
+    GovernanceMembersUpdated, GovernanceRenounced, GovernanceTransferCanceled, GovernanceTransferProposed,
+    GuardianUpdated, HolderState, HookConfig, HookDisableCanceled, HookDisableProposed, HookError,
+    HookPermanentlyDisabled, HookTransferChecked, LaunchDetected, LaunchDetectionReset, Lockup, LockupCreated,
+    LockupShortened, MaxCapChangeTightened, MaxHoldersUpdated, MemoThresholdUpdated, MinCapFloorRaised, MintRegistry,
+    PauseUpdated, PermitRedeemed, PermitSignerUpdated, ProposalExpiryUpdated, SetupFinalized, Snapshot, SnapshotClosed,
+    SnapshotTaken, SnipeWindowUpdated, SniperFlagCleared, SniperFlagged, SniperLog, StakingProgramUpdated,
+    StakingVaultRegistered, SurplusLamportsWithdrawn, TimelockUpdateCanceled, TimelockUpdateProposed, TimelockUpdated,
+    TransferEventsUpdated, TrustedOwner, TrustedOwnerRegistered, TrustedProgramUpdated, UsedNonces,
+    VetoAuthorityUpdateCanceled, VetoAuthorityUpdateProposed, VetoAuthorityUpdated, WalletCapUpdateCanceled,
+    WalletCapUpdateExpired, WalletCapUpdateProposed, WalletCapUpdateVetoed, WalletCapUpdated,
+    WalletOwnersRequiredUpdated,
+};
+
+const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/interface.txt");
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// Instruction data: the discriminator followed by the Borsh encoded arguments
+fn instruction(out: &mut String, name: &str, args: impl InstructionData) {
+    writeln!(out, "instruction {} {}", name, hex(&args.data())).unwrap();
+}
+
+fn account(out: &mut String, name: &str, discriminator: &[u8], space: usize) {
+    writeln!(out, "account {} {} {}", name, hex(discriminator), space).unwrap();
+}
+
+fn event(out: &mut String, name: &str, discriminator: &[u8]) {
+    writeln!(out, "event {} {}", name, hex(discriminator)).unwrap();
+}
+
+fn error(out: &mut String, name: &str, error: HookError) {
+    writeln!(out, "error {} {}", name, anchor_lang::error::ERROR_CODE_OFFSET + error as u32).unwrap();
+}
+
+fn render() -> String {
+    let mut out = String::new();
+
+    instruction(&mut out, "initialize", instruction::Initialize {
+        dev_wallet: key(1),
+        governance_authority: key(2),
+        cap_unit: CapUnit::Ui,
+        timelock_seconds: 4,
+        min_cap_floor: 5,
+        max_cap_change_bps: 6,
+        strict_authorities: true,
+        allow_confidential: true,
+    });
+    instruction(&mut out, "init_global_config", instruction::InitGlobalConfig { factory_admin: key(1) });
+    instruction(&mut out, "init_mint_registry_page", instruction::InitMintRegistryPage {});
+    instruction(&mut out, "set_global_defaults", instruction::SetGlobalDefaults {
+        timelock_seconds: 1,
+        min_cap_floor: 2,
+        max_cap_change_bps: 3,
+    });
+    instruction(&mut out, "initialize_for_mint", instruction::InitializeForMint {
+        dev_wallet: key(1),
+        governance_authority: key(2),
+        cap_unit: CapUnit::Ui,
+        timelock_seconds: Some(4),
+        min_cap_floor: Some(5),
+        max_cap_change_bps: Some(6),
+        strict_authorities: true,
+        allow_confidential: true,
+    });
+    instruction(&mut out, "transfer_hook", instruction::TransferHook { amount: 1 });
+    instruction(&mut out, "execute", instruction::Execute { amount: 1 });
+    instruction(&mut out, "init_extra_account_meta_list", instruction::InitExtraAccountMetaList {});
+    instruction(&mut out, "update_extra_account_meta_list", instruction::UpdateExtraAccountMetaList {});
+    instruction(&mut out, "finalize_setup", instruction::FinalizeSetup {});
+    instruction(&mut out, "close_extra_account_meta_list", instruction::CloseExtraAccountMetaList {
+        confirm_mint_detached: true,
+    });
+    instruction(&mut out, "propose_wallet_cap_update", instruction::ProposeWalletCapUpdate {
+        new_cap: 1,
+        overwrite: true,
+        memo_hash: [3; 32],
+    });
+    instruction(&mut out, "propose_cap_update", instruction::ProposeCapUpdate {
+        kind: CapKind::Sell,
+        new_cap: 2,
+        overwrite: true,
+        memo_hash: [4; 32],
+    });
+    instruction(&mut out, "execute_wallet_cap_update", instruction::ExecuteWalletCapUpdate {});
+    instruction(&mut out, "execute_immediate_cap_increase", instruction::ExecuteImmediateCapIncrease { new_cap: 1 });
+    instruction(&mut out, "adopt_global_defaults", instruction::AdoptGlobalDefaults { fields: 7 });
+    instruction(&mut out, "raise_min_cap_floor", instruction::RaiseMinCapFloor { min_cap_floor: 1 });
+    instruction(&mut out, "cancel_wallet_cap_update", instruction::CancelWalletCapUpdate {});
+    instruction(&mut out, "sweep_expired_proposal", instruction::SweepExpiredProposal {});
+    instruction(&mut out, "init_governance_log", instruction::InitGovernanceLog {});
+    instruction(&mut out, "init_config_extension", instruction::InitConfigExtension {});
+    instruction(&mut out, "init_sniper_log", instruction::InitSniperLog {});
+    instruction(&mut out, "get_governance_log", instruction::GetGovernanceLog { page: 1 });
+    instruction(&mut out, "get_config", instruction::GetConfig {});
+    instruction(&mut out, "can_receive", instruction::CanReceive { amount: 1 });
+    instruction(&mut out, "check_transfer", instruction::CheckTransfer { amount: 1 });
+    instruction(&mut out, "set_proposal_expiry", instruction::SetProposalExpiry { expiry_seconds: 1 });
+    instruction(&mut out, "set_cap_update_role", instruction::SetCapUpdateRole {
+        role: CapUpdateRole::Executor,
+        authority: key(2),
+    });
+    instruction(&mut out, "propose_veto_authority_update", instruction::ProposeVetoAuthorityUpdate {
+        veto_authority: Some(key(1)),
+    });
+    instruction(&mut out, "execute_veto_authority_update", instruction::ExecuteVetoAuthorityUpdate {});
+    instruction(&mut out, "cancel_veto_authority_update", instruction::CancelVetoAuthorityUpdate {});
+    instruction(&mut out, "propose_governance_transfer", instruction::ProposeGovernanceTransfer {
+        new_authority: key(1),
+    });
+    instruction(&mut out, "accept_governance_authority", instruction::AcceptGovernanceAuthority {});
+    instruction(&mut out, "cancel_governance_transfer", instruction::CancelGovernanceTransfer {});
+    instruction(&mut out, "renounce_governance", instruction::RenounceGovernance {});
+    instruction(&mut out, "freeze_config", instruction::FreezeConfig {});
+    instruction(&mut out, "set_governance_members", instruction::SetGovernanceMembers {
+        members: [key(1); 5],
+        threshold: 2,
+    });
+    instruction(&mut out, "propose_action", instruction::ProposeAction {
+        action: GovernanceAction::SetCircuitBreaker { threshold_raw: 1,
+        window_slots: 2,
+        cooldown: -3 },
+    });
+    instruction(&mut out, "approve_action", instruction::ApproveAction { action_hash: [1; 32] });
+    instruction(&mut out, "close_governance_approval", instruction::CloseGovernanceApproval {});
+    instruction(&mut out, "init_exempt_registry", instruction::InitExemptRegistry { rent_recipient: key(1) });
+    instruction(&mut out, "add_exempt_entry", instruction::AddExemptEntry {
+        token_account: key(1),
+        kind: ExemptKind::PoolVault,
+        expires_at: Some(-3),
+    });
+    instruction(&mut out, "remove_exempt_entry", instruction::RemoveExemptEntry { token_account: key(1) });
+    instruction(&mut out, "prune_exempt_registry", instruction::PruneExemptRegistry {});
+    instruction(&mut out, "set_trusted_program", instruction::SetTrustedProgram { index: 1, program_id: key(2) });
+    instruction(&mut out, "set_staking_program", instruction::SetStakingProgram { index: 1, program_id: key(2) });
+    instruction(&mut out, "register_staking_vault", instruction::RegisterStakingVault { seeds: vec![vec![1]] });
+    instruction(&mut out, "register_trusted_owner", instruction::RegisterTrustedOwner { seeds: vec![vec![1]] });
+    instruction(&mut out, "register_bridge_custody", instruction::RegisterBridgeCustody { bridge_program: key(1) });
+    instruction(&mut out, "reset_launch_detection", instruction::ResetLaunchDetection {});
+    instruction(&mut out, "set_snipe_window", instruction::SetSnipeWindow { window_seconds: 1 });
+    instruction(&mut out, "clear_sniper_flag", instruction::ClearSniperFlag { owner: key(1) });
+    instruction(&mut out, "set_memo_required_above", instruction::SetMemoRequiredAbove { threshold: Some(1) });
+    instruction(&mut out, "lower_dev_outflow_limit", instruction::LowerDevOutflowLimit { limit: 1 });
+    instruction(&mut out, "propose_dev_wallet_update", instruction::ProposeDevWalletUpdate {
+        index: 1,
+        new_wallet: key(2),
+    });
+    instruction(&mut out, "execute_dev_wallet_update", instruction::ExecuteDevWalletUpdate {});
+    instruction(&mut out, "cancel_dev_wallet_update", instruction::CancelDevWalletUpdate {});
+    instruction(&mut out, "propose_timelock_update", instruction::ProposeTimelockUpdate { timelock_seconds: 1 });
+    instruction(&mut out, "execute_timelock_update", instruction::ExecuteTimelockUpdate {});
+    instruction(&mut out, "cancel_timelock_update", instruction::CancelTimelockUpdate {});
+    instruction(&mut out, "set_dev_exemption_expiry", instruction::SetDevExemptionExpiry { expires_at: -1 });
+    instruction(&mut out, "finalize_dev_exemption", instruction::FinalizeDevExemption {});
+    instruction(&mut out, "set_airdrop_allowance", instruction::SetAirdropAllowance { allowance: 1 });
+    instruction(&mut out, "set_permit_signer", instruction::SetPermitSigner { permit_signer: key(1) });
+    instruction(&mut out, "init_used_nonces", instruction::InitUsedNonces {});
+    instruction(&mut out, "set_circuit_breaker", instruction::SetCircuitBreaker {
+        threshold_raw: 1,
+        window_slots: 2,
+        cooldown: -3,
+    });
+    instruction(&mut out, "clear_circuit_breaker", instruction::ClearCircuitBreaker {});
+    instruction(&mut out, "set_guardian", instruction::SetGuardian { guardian: Some(key(1)) });
+    instruction(&mut out, "set_pause", instruction::SetPause { paused: true });
+    instruction(&mut out, "init_holder_state", instruction::InitHolderState { owner: key(1) });
+    instruction(&mut out, "close_holder_state", instruction::CloseHolderState {});
+    instruction(&mut out, "set_max_holders", instruction::SetMaxHolders { max_holders: 1 });
+    instruction(&mut out, "set_transfer_events", instruction::SetTransferEvents { enabled: true });
+    instruction(&mut out, "set_require_wallet_owners", instruction::SetRequireWalletOwners { enabled: true });
+    instruction(&mut out, "take_snapshot", instruction::TakeSnapshot {});
+    instruction(&mut out, "close_snapshot", instruction::CloseSnapshot { index: 1 });
+    instruction(&mut out, "propose_hook_disable", instruction::ProposeHookDisable {});
+    instruction(&mut out, "permanently_disable_hook", instruction::PermanentlyDisableHook {});
+    instruction(&mut out, "cancel_hook_disable", instruction::CancelHookDisable {});
+    instruction(&mut out, "create_lockup", instruction::CreateLockup {
+        owner: key(1),
+        locked_amount: 2,
+        locked_until: -3,
+        linear_release_end: Some(-4),
+    });
+    instruction(&mut out, "shorten_lockup", instruction::ShortenLockup {
+        owner: key(1),
+        locked_until: -2,
+        linear_release_end: Some(-3),
+    });
+    instruction(&mut out, "set_dev_wallet_verified", instruction::SetDevWalletVerified {
+        index: 1,
+        new_wallet: key(2),
+    });
+    instruction(&mut out, "migrate_config", instruction::MigrateConfig {});
+    instruction(&mut out, "close_config", instruction::CloseConfig { confirm_mint_detached: true });
+    instruction(&mut out, "withdraw_surplus_lamports", instruction::WithdrawSurplusLamports {});
+
+    account(&mut out, "HookConfig", &HookConfig::DISCRIMINATOR, HookConfig::SPACE);
+    account(&mut out, "ExemptRegistry", &ExemptRegistry::DISCRIMINATOR, ExemptRegistry::space(16));
+    account(&mut out, "HolderState", &HolderState::DISCRIMINATOR, HolderState::SPACE);
+    account(&mut out, "GovernanceApproval", &GovernanceApproval::DISCRIMINATOR, GovernanceApproval::SPACE);
+    account(&mut out, "UsedNonces", &UsedNonces::DISCRIMINATOR, UsedNonces::SPACE);
+    account(&mut out, "GovernanceLog", &GovernanceLog::DISCRIMINATOR, GovernanceLog::SPACE);
+    account(&mut out, "Snapshot", &Snapshot::DISCRIMINATOR, Snapshot::SPACE);
+    account(&mut out, "Lockup", &Lockup::DISCRIMINATOR, Lockup::SPACE);
+    account(&mut out, "GlobalConfig", &GlobalConfig::DISCRIMINATOR, GlobalConfig::SPACE);
+    account(&mut out, "MintRegistry", &MintRegistry::DISCRIMINATOR, MintRegistry::SPACE);
+    account(&mut out, "ConfigExtension", &ConfigExtension::DISCRIMINATOR, ConfigExtension::SPACE);
+    account(&mut out, "SniperLog", &SniperLog::DISCRIMINATOR, SniperLog::SPACE);
+    account(&mut out, "TrustedOwner", &TrustedOwner::DISCRIMINATOR, TrustedOwner::SPACE);
+
+    writeln!(out, "event_schema_version {}", EVENT_SCHEMA_VERSION).unwrap();
+    event(&mut out, "WalletCapUpdateProposed", &WalletCapUpdateProposed::DISCRIMINATOR);
+    event(&mut out, "WalletCapUpdated", &WalletCapUpdated::DISCRIMINATOR);
+    event(&mut out, "WalletCapUpdateCanceled", &WalletCapUpdateCanceled::DISCRIMINATOR);
+    event(&mut out, "WalletCapUpdateVetoed", &WalletCapUpdateVetoed::DISCRIMINATOR);
+    event(&mut out, "WalletCapUpdateExpired", &WalletCapUpdateExpired::DISCRIMINATOR);
+    event(&mut out, "ProposalExpiryUpdated", &ProposalExpiryUpdated::DISCRIMINATOR);
+    event(&mut out, "MinCapFloorRaised", &MinCapFloorRaised::DISCRIMINATOR);
+    event(&mut out, "VetoAuthorityUpdateProposed", &VetoAuthorityUpdateProposed::DISCRIMINATOR);
+    event(&mut out, "VetoAuthorityUpdated", &VetoAuthorityUpdated::DISCRIMINATOR);
+    event(&mut out, "VetoAuthorityUpdateCanceled", &VetoAuthorityUpdateCanceled::DISCRIMINATOR);
+    event(&mut out, "CapUpdateRoleUpdated", &CapUpdateRoleUpdated::DISCRIMINATOR);
+    event(&mut out, "GovernanceTransferProposed", &GovernanceTransferProposed::DISCRIMINATOR);
+    event(&mut out, "GovernanceAuthorityUpdated", &GovernanceAuthorityUpdated::DISCRIMINATOR);
+    event(&mut out, "GovernanceTransferCanceled", &GovernanceTransferCanceled::DISCRIMINATOR);
+    event(&mut out, "ExemptEntryAdded", &ExemptEntryAdded::DISCRIMINATOR);
+    event(&mut out, "ExemptEntryRemoved", &ExemptEntryRemoved::DISCRIMINATOR);
+    event(&mut out, "ExemptRegistryPruned", &ExemptRegistryPruned::DISCRIMINATOR);
+    event(&mut out, "TrustedProgramUpdated", &TrustedProgramUpdated::DISCRIMINATOR);
+    event(&mut out, "DevWalletUpdateProposed", &DevWalletUpdateProposed::DISCRIMINATOR);
+    event(&mut out, "DevWalletUpdated", &DevWalletUpdated::DISCRIMINATOR);
+    event(&mut out, "TimelockUpdateProposed", &TimelockUpdateProposed::DISCRIMINATOR);
+    event(&mut out, "TimelockUpdated", &TimelockUpdated::DISCRIMINATOR);
+    event(&mut out, "TimelockUpdateCanceled", &TimelockUpdateCanceled::DISCRIMINATOR);
+    event(&mut out, "DevWalletUpdateCanceled", &DevWalletUpdateCanceled::DISCRIMINATOR);
+    event(&mut out, "DevExemptionExpiryUpdated", &DevExemptionExpiryUpdated::DISCRIMINATOR);
+    event(&mut out, "DevExemptionExpired", &DevExemptionExpired::DISCRIMINATOR);
+    event(&mut out, "AirdropAllowanceUpdated", &AirdropAllowanceUpdated::DISCRIMINATOR);
+    event(&mut out, "PermitSignerUpdated", &PermitSignerUpdated::DISCRIMINATOR);
+    event(&mut out, "PermitRedeemed", &PermitRedeemed::DISCRIMINATOR);
+    event(&mut out, "CircuitBreakerUpdated", &CircuitBreakerUpdated::DISCRIMINATOR);
+    event(&mut out, "CircuitBreakerTriggered", &CircuitBreakerTriggered::DISCRIMINATOR);
+    event(&mut out, "CircuitBreakerCleared", &CircuitBreakerCleared::DISCRIMINATOR);
+    event(&mut out, "MaxHoldersUpdated", &MaxHoldersUpdated::DISCRIMINATOR);
+    event(&mut out, "GovernanceMembersUpdated", &GovernanceMembersUpdated::DISCRIMINATOR);
+    event(&mut out, "GovernanceActionProposed", &GovernanceActionProposed::DISCRIMINATOR);
+    event(&mut out, "GovernanceActionApproved", &GovernanceActionApproved::DISCRIMINATOR);
+    event(&mut out, "GuardianUpdated", &GuardianUpdated::DISCRIMINATOR);
+    event(&mut out, "PauseUpdated", &PauseUpdated::DISCRIMINATOR);
+    event(&mut out, "ConfigFrozen", &ConfigFrozen::DISCRIMINATOR);
+    event(&mut out, "GovernanceRenounced", &GovernanceRenounced::DISCRIMINATOR);
+    event(&mut out, "ConfigMigrated", &ConfigMigrated::DISCRIMINATOR);
+    event(&mut out, "ExtraAccountMetasUpdated", &ExtraAccountMetasUpdated::DISCRIMINATOR);
+    event(&mut out, "ExtraAccountMetaListClosed", &ExtraAccountMetaListClosed::DISCRIMINATOR);
+    event(&mut out, "ConfigClosed", &ConfigClosed::DISCRIMINATOR);
+    event(&mut out, "DevWalletIsGovernanceAuthority", &DevWalletIsGovernanceAuthority::DISCRIMINATOR);
+    event(&mut out, "HookTransferChecked", &HookTransferChecked::DISCRIMINATOR);
+    event(&mut out, "TransferEventsUpdated", &TransferEventsUpdated::DISCRIMINATOR);
+    event(&mut out, "SnapshotTaken", &SnapshotTaken::DISCRIMINATOR);
+    event(&mut out, "SnapshotClosed", &SnapshotClosed::DISCRIMINATOR);
+    event(&mut out, "HookDisableProposed", &HookDisableProposed::DISCRIMINATOR);
+    event(&mut out, "HookDisableCanceled", &HookDisableCanceled::DISCRIMINATOR);
+    event(&mut out, "HookPermanentlyDisabled", &HookPermanentlyDisabled::DISCRIMINATOR);
+    event(&mut out, "LockupCreated", &LockupCreated::DISCRIMINATOR);
+    event(&mut out, "LockupShortened", &LockupShortened::DISCRIMINATOR);
+    event(&mut out, "MaxCapChangeTightened", &MaxCapChangeTightened::DISCRIMINATOR);
+    event(&mut out, "StakingProgramUpdated", &StakingProgramUpdated::DISCRIMINATOR);
+    event(&mut out, "StakingVaultRegistered", &StakingVaultRegistered::DISCRIMINATOR);
+    event(&mut out, "BridgeCustodyRegistered", &BridgeCustodyRegistered::DISCRIMINATOR);
+    event(&mut out, "LaunchDetected", &LaunchDetected::DISCRIMINATOR);
+    event(&mut out, "LaunchDetectionReset", &LaunchDetectionReset::DISCRIMINATOR);
+    event(&mut out, "SniperFlagged", &SniperFlagged::DISCRIMINATOR);
+    event(&mut out, "SniperFlagCleared", &SniperFlagCleared::DISCRIMINATOR);
+    event(&mut out, "SnipeWindowUpdated", &SnipeWindowUpdated::DISCRIMINATOR);
+    event(&mut out, "WalletOwnersRequiredUpdated", &WalletOwnersRequiredUpdated::DISCRIMINATOR);
+    event(&mut out, "MemoThresholdUpdated", &MemoThresholdUpdated::DISCRIMINATOR);
+    event(&mut out, "DevOutflowLimitLowered", &DevOutflowLimitLowered::DISCRIMINATOR);
+    event(&mut out, "SetupFinalized", &SetupFinalized::DISCRIMINATOR);
+    event(&mut out, "SurplusLamportsWithdrawn", &SurplusLamportsWithdrawn::DISCRIMINATOR);
+    event(&mut out, "TrustedOwnerRegistered", &TrustedOwnerRegistered::DISCRIMINATOR);
+
+    error(&mut out, "WalletCapExceeded", HookError::WalletCapExceeded);
+    error(&mut out, "InsufficientAccountSpace", HookError::InsufficientAccountSpace);
+    error(&mut out, "UnauthorizedGovernance", HookError::UnauthorizedGovernance);
+    error(&mut out, "InvalidWalletCap", HookError::InvalidWalletCap);
+    error(&mut out, "NoPendingUpdate", HookError::NoPendingUpdate);
+    error(&mut out, "TimelockNotExpired", HookError::TimelockNotExpired);
+    error(&mut out, "InvalidAccountOwner", HookError::InvalidAccountOwner);
+    error(&mut out, "InvalidMigrationVersion", HookError::InvalidMigrationVersion);
+    error(&mut out, "UnsupportedVersion", HookError::UnsupportedVersion);
+    error(&mut out, "UnsupportedMigration", HookError::UnsupportedMigration);
+    error(&mut out, "ExemptRegistryFull", HookError::ExemptRegistryFull);
+    error(&mut out, "InvalidTrustedProgramSlot", HookError::InvalidTrustedProgramSlot);
+    error(&mut out, "InvalidDevWalletSlot", HookError::InvalidDevWalletSlot);
+    error(&mut out, "DevExemptionExtension", HookError::DevExemptionExtension);
+    error(&mut out, "DevExemptionNotExpired", HookError::DevExemptionNotExpired);
+    error(&mut out, "DevExemptionAlreadyFinalized", HookError::DevExemptionAlreadyFinalized);
+    error(&mut out, "ExemptEntryNotFound", HookError::ExemptEntryNotFound);
+    error(&mut out, "InvalidRentRecipient", HookError::InvalidRentRecipient);
+    error(&mut out, "PermitExpired", HookError::PermitExpired);
+    error(&mut out, "PermitWrongDestination", HookError::PermitWrongDestination);
+    error(&mut out, "PermitNonceReplayed", HookError::PermitNonceReplayed);
+    error(&mut out, "PermitWrongMint", HookError::PermitWrongMint);
+    error(&mut out, "PermitAmountExceeded", HookError::PermitAmountExceeded);
+    error(&mut out, "PermitNonceOutOfRange", HookError::PermitNonceOutOfRange);
+    error(&mut out, "UsedNoncesNotInitialized", HookError::UsedNoncesNotInitialized);
+    error(&mut out, "SellLimitExceeded", HookError::SellLimitExceeded);
+    error(&mut out, "SellLimitBelowFloor", HookError::SellLimitBelowFloor);
+    error(&mut out, "CircuitBreakerTripped", HookError::CircuitBreakerTripped);
+    error(&mut out, "InvalidCircuitBreakerConfig", HookError::InvalidCircuitBreakerConfig);
+    error(&mut out, "MaxHoldersReached", HookError::MaxHoldersReached);
+    error(&mut out, "HolderStateNotInitialized", HookError::HolderStateNotInitialized);
+    error(&mut out, "InvalidHolderState", HookError::InvalidHolderState);
+    error(&mut out, "UiCapUnsupported", HookError::UiCapUnsupported);
+    error(&mut out, "NoPendingGovernanceTransfer", HookError::NoPendingGovernanceTransfer);
+    error(&mut out, "NotPendingGovernanceAuthority", HookError::NotPendingGovernanceAuthority);
+    error(&mut out, "GovernanceRenounced", HookError::GovernanceRenounced);
+    error(&mut out, "MultisigGovernanceDisabled", HookError::MultisigGovernanceDisabled);
+    error(&mut out, "NotGovernanceMember", HookError::NotGovernanceMember);
+    error(&mut out, "GovernanceActionAlreadyApproved", HookError::GovernanceActionAlreadyApproved);
+    error(&mut out, "GovernanceApprovalRequired", HookError::GovernanceApprovalRequired);
+    error(&mut out, "StaleGovernanceApproval", HookError::StaleGovernanceApproval);
+    error(&mut out, "GovernanceActionMismatch", HookError::GovernanceActionMismatch);
+    error(&mut out, "GovernanceThresholdNotMet", HookError::GovernanceThresholdNotMet);
+    error(&mut out, "GovernanceApprovalStillActive", HookError::GovernanceApprovalStillActive);
+    error(&mut out, "InvalidGovernanceMembers", HookError::InvalidGovernanceMembers);
+    error(&mut out, "GuardianCannotUnpause", HookError::GuardianCannotUnpause);
+    error(&mut out, "InvalidTimelock", HookError::InvalidTimelock);
+    error(&mut out, "ProposalExpired", HookError::ProposalExpired);
+    error(&mut out, "ProposalNotExpired", HookError::ProposalNotExpired);
+    error(&mut out, "InvalidProposalExpiry", HookError::InvalidProposalExpiry);
+    error(&mut out, "ProposalAlreadyPending", HookError::ProposalAlreadyPending);
+    error(&mut out, "UnauthorizedExecutor", HookError::UnauthorizedExecutor);
+    error(&mut out, "TimelockRequired", HookError::TimelockRequired);
+    error(&mut out, "CapBelowFloor", HookError::CapBelowFloor);
+    error(&mut out, "CapFloorLowered", HookError::CapFloorLowered);
+    error(&mut out, "CapChangeTooLarge", HookError::CapChangeTooLarge);
+    error(&mut out, "ConfigFrozen", HookError::ConfigFrozen);
+    error(&mut out, "FreezeWhilePaused", HookError::FreezeWhilePaused);
+    error(&mut out, "MintNotConfiguredForHook", HookError::MintNotConfiguredForHook);
+    error(&mut out, "UnauthorizedInitializer", HookError::UnauthorizedInitializer);
+    error(&mut out, "NotInTransfer", HookError::NotInTransfer);
+    error(&mut out, "SourceMintMismatch", HookError::SourceMintMismatch);
+    error(&mut out, "DestinationMintMismatch", HookError::DestinationMintMismatch);
+    error(&mut out, "OwnerMismatch", HookError::OwnerMismatch);
+    error(&mut out, "ExtraAccountMetasMismatch", HookError::ExtraAccountMetasMismatch);
+    error(&mut out, "ArithmeticOverflow", HookError::ArithmeticOverflow);
+    error(&mut out, "MintDetachUnconfirmed", HookError::MintDetachUnconfirmed);
+    error(&mut out, "PendingCapUpdateExists", HookError::PendingCapUpdateExists);
+    error(&mut out, "InvalidAuthority", HookError::InvalidAuthority);
+    error(&mut out, "HolderBalanceNotZero", HookError::HolderBalanceNotZero);
+    error(&mut out, "SnapshotTooSoon", HookError::SnapshotTooSoon);
+    error(&mut out, "HookDisabled", HookError::HookDisabled);
+    error(&mut out, "HookDisableAlreadyPending", HookError::HookDisableAlreadyPending);
+    error(&mut out, "TokensLocked", HookError::TokensLocked);
+    error(&mut out, "InvalidLockup", HookError::InvalidLockup);
+    error(&mut out, "InvalidLockupSchedule", HookError::InvalidLockupSchedule);
+    error(&mut out, "LockupExtension", HookError::LockupExtension);
+    error(&mut out, "DevWalletAccountMismatch", HookError::DevWalletAccountMismatch);
+    error(&mut out, "NotUpgradeAuthority", HookError::NotUpgradeAuthority);
+    error(&mut out, "InvalidGlobalDefaultsFields", HookError::InvalidGlobalDefaultsFields);
+    error(&mut out, "CapChangeLimitLoosened", HookError::CapChangeLimitLoosened);
+    error(&mut out, "UnstakeWouldExceedCap", HookError::UnstakeWouldExceedCap);
+    error(&mut out, "NotStakingVault", HookError::NotStakingVault);
+    error(&mut out, "NotBridgeCustody", HookError::NotBridgeCustody);
+    error(&mut out, "BridgeCustodyNotVerified", HookError::BridgeCustodyNotVerified);
+    error(&mut out, "UnsupportedMintExtension", HookError::UnsupportedMintExtension);
+    error(&mut out, "ConfidentialTransferUnsupported", HookError::ConfidentialTransferUnsupported);
+    error(&mut out, "InvalidConfigExtension", HookError::InvalidConfigExtension);
+    error(&mut out, "LaunchNotDetected", HookError::LaunchNotDetected);
+    error(&mut out, "LaunchResetUnavailable", HookError::LaunchResetUnavailable);
+    error(&mut out, "InvalidSniperLog", HookError::InvalidSniperLog);
+    error(&mut out, "SniperNotFlagged", HookError::SniperNotFlagged);
+    error(&mut out, "InvalidSnipeWindow", HookError::InvalidSnipeWindow);
+    error(&mut out, "ProgramOwnedDestination", HookError::ProgramOwnedDestination);
+    error(&mut out, "MemoRequired", HookError::MemoRequired);
+    error(&mut out, "DevOutflowLimitExceeded", HookError::DevOutflowLimitExceeded);
+    error(&mut out, "DevOutflowLimitRaised", HookError::DevOutflowLimitRaised);
+    error(&mut out, "SetupNotFinalized", HookError::SetupNotFinalized);
+    error(&mut out, "SetupAlreadyFinalized", HookError::SetupAlreadyFinalized);
+    error(&mut out, "ExtraAccountMetaListMissing", HookError::ExtraAccountMetaListMissing);
+    error(&mut out, "NotTrustedProgramOwner", HookError::NotTrustedProgramOwner);
+
+    out
+}
+
+#[test]
+fn interface_matches_snapshot() {
+    let rendered = render();
+    if std::env::var_os("UPDATE_INTERFACE_SNAPSHOT").is_some() {
+        std::fs::write(SNAPSHOT, &rendered).unwrap();
+        return;
+    }
+
+    let snapshot = std::fs::read_to_string(SNAPSHOT).unwrap();
+    for (line, (rendered, expected)) in rendered.lines().zip(snapshot.lines()).enumerate() {
+        assert_eq!(rendered, expected, "interface changed at snapshot line {}", line + 1);
+    }
+    assert_eq!(rendered.lines().count(), snapshot.lines().count(), "interface gained or lost entries");
+}
@@ -0,0 +1,143 @@
+// Launch detection: the first buy out of a registered pool vault records when trading began in the mint's
+// config extension, and nothing after it moves that time. Governance can reset it once, to recover from a
+// premature first buy, until a snipe window has been measured from it.
+
+mod test_utils;
+
+use anchor_lang::AccountDeserialize;
+use one_kx_hook::{
+    accounts, instruction, ConfigExtension, ExemptKind, HookError, LaunchDetected, LaunchDetectionReset,
+};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{decode_event, event_authority, hook_error, pda, program_instruction, Harness, HookedMint};
+
+/// Register a pool vault holding `amount`, owned by the returned pool keypair
+async fn pool_vault(harness: &mut Harness, mint: &HookedMint, amount: u64) -> (Pubkey, Keypair) {
+    let payer = harness.context.payer.pubkey();
+    let exempt_registry = pda(&[b"exempt-registry", mint.address.as_ref()]);
+    let pool = Keypair::new();
+    let vault = harness.create_token_account(mint, &pool.pubkey(), amount).await;
+    let init_exempt_registry = program_instruction(
+        accounts::InitExemptRegistry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+        },
+        instruction::InitExemptRegistry { rent_recipient: payer },
+    );
+    let add = program_instruction(
+        accounts::AddExemptEntry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::AddExemptEntry { token_account: vault, kind: ExemptKind::PoolVault, expires_at: None },
+    );
+    let governance = mint.governance.insecure_clone();
+    harness.process(&[init_exempt_registry, add], &[&governance]).await.0.unwrap();
+    (vault, pool)
+}
+
+async fn config_extension(harness: &mut Harness, mint: &HookedMint) -> ConfigExtension {
+    let account = harness.context.banks_client.get_account(mint.config_extension()).await.unwrap().unwrap();
+    ConfigExtension::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Buy `amount` out of the pool vault and return the launches detected on the way
+async fn buy(
+    harness: &mut Harness,
+    mint: &HookedMint,
+    (vault, pool): &(Pubkey, Keypair),
+    destination: &Pubkey,
+    amount: u64,
+) -> Vec<LaunchDetected> {
+    let transfer = harness.transfer_instruction(mint, vault, destination, &pool.pubkey(), amount).await;
+    let events = harness.process_for_events(&[transfer], &[pool]).await;
+    events.iter().filter_map(|event| decode_event::<LaunchDetected>(event)).collect()
+}
+
+fn reset_launch_detection(mint: &HookedMint) -> Instruction {
+    program_instruction(
+        accounts::ResetLaunchDetection {
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            config_extension: mint.config_extension(),
+            mint: mint.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::ResetLaunchDetection {},
+    )
+}
+
+#[tokio::test]
+async fn the_first_buy_marks_the_launch_once() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let pool = pool_vault(&mut harness, &mint, 10_000).await;
+    let buyer = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    // Sending into the pool is a sell, not the start of trading
+    let seller = Keypair::new();
+    let seller_wallet = harness.create_token_account(&mint, &seller.pubkey(), 100).await;
+    harness.transfer(&mint, &seller_wallet, &pool.0, &seller, 100).await.0.unwrap();
+    assert_eq!(config_extension(&mut harness, &mint).await.launch_detected_at, None);
+
+    let launched_at = harness.unix_timestamp().await;
+    let detected = buy(&mut harness, &mint, &pool, &buyer, 1_000).await;
+    assert_eq!(detected.len(), 1);
+    assert_eq!((detected[0].pool_vault, detected[0].amount, detected[0].detected_at), (pool.0, 1_000, launched_at));
+    let extension = config_extension(&mut harness, &mint).await;
+    assert_eq!(extension.launch_detected_at, Some(launched_at));
+    assert_eq!(extension.launch_detected_slot, Some(detected[0].slot));
+
+    // Later buys leave it where it is
+    harness.warp_to_timestamp(launched_at + 600).await;
+    assert!(buy(&mut harness, &mint, &pool, &buyer, 2_000).await.is_empty());
+    let later = config_extension(&mut harness, &mint).await;
+    assert_eq!((later.launch_detected_at, later.launch_detected_slot), (Some(launched_at), Some(detected[0].slot)));
+}
+
+#[tokio::test]
+async fn governance_resets_the_launch_once() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let pool = pool_vault(&mut harness, &mint, 10_000).await;
+    let buyer = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    let governance = mint.governance.insecure_clone();
+
+    // Nothing to reset before the launch
+    let (result, _) = harness.process(&[reset_launch_detection(&mint)], &[&governance]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::LaunchNotDetected));
+
+    let premature = buy(&mut harness, &mint, &pool, &buyer, 1_000).await;
+    let events = harness.process_for_events(&[reset_launch_detection(&mint)], &[&governance]).await;
+    let reset = events.iter().find_map(|event| decode_event::<LaunchDetectionReset>(event)).expect("reset event");
+    assert_eq!((reset.old_detected_at, reset.old_detected_slot), (premature[0].detected_at, premature[0].slot));
+    assert_eq!(config_extension(&mut harness, &mint).await.launch_detected_at, None);
+
+    // The next buy marks the launch afresh, and that one stays
+    let launched_at = premature[0].detected_at + 3_600;
+    harness.warp_to_timestamp(launched_at).await;
+    let detected = buy(&mut harness, &mint, &pool, &buyer, 2_000).await;
+    assert_eq!(detected[0].detected_at, launched_at);
+    let (result, _) = harness.process(&[reset_launch_detection(&mint)], &[&governance]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::LaunchResetUnavailable));
+    assert_eq!(config_extension(&mut harness, &mint).await.launch_detected_at, Some(launched_at));
+}
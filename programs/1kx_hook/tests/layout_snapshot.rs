@@ -0,0 +1,774 @@
+// Pins the Borsh layout of HookConfig, PendingCapUpdate and every event. A reordered field or a changed
+// type would misread every deployed config after an upgrade, and every event an indexer has stored, while
+// the interface snapshot only covers discriminators and sizes. Every field gets its own value and every
+// option is Some, so a field that moves, grows or shrinks shifts bytes in the snapshot.
+//
+// Layouts only change with a config version migration or an EVENT_SCHEMA_VERSION bump. Rerun with
+// UPDATE_LAYOUT_SNAPSHOT=1 in the same change to rewrite the snapshot, so the review shows both.
+
+use std::fmt::Write;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, AnchorSerialize, Event};
+use one_kx_hook::{
+    AirdropAllowanceUpdated, BridgeCustodyRegistered, CapKind, CapUnit, CapUpdateRole, CapUpdateRoleUpdated,
+    CircuitBreakerCleared, CircuitBreakerTriggered, CircuitBreakerUpdated, ConfigClosed, ConfigFrozen, ConfigMigrated,
+    DevExemptionExpired, DevExemptionExpiryUpdated, DevOutflowLimitLowered, DevWalletIsGovernanceAuthority,
+    DevWalletUpdateCanceled, DevWalletUpdateProposed, DevWalletUpdated, ExemptEntryAdded, ExemptEntryRemoved,
+    ExemptKind, ExemptRegistryPruned, ExtraAccountMetaListClosed, ExtraAccountMetasUpdated, GovernanceAction,
+    GovernanceActionApproved, GovernanceActionProposed, GovernanceAuthorityUpdated, GovernanceMembersUpdated,
+    GovernanceRenounced, GovernanceTransferCanceled, GovernanceTransferProposed, GuardianUpdated, HookConfig,
+    HookDisableCanceled, HookDisableProposed, HookPermanentlyDisabled, HookTransferChecked, LaunchDetected,
+    LaunchDetectionReset, LockupCreated, LockupShortened, MaxCapChangeTightened, MaxHoldersUpdated,
+    MemoThresholdUpdated, MinCapFloorRaised, PauseUpdated, PendingCapUpdate, PendingDevWalletUpdate,
+    PendingTimelockUpdate, PendingVetoAuthorityUpdate, PermitRedeemed, PermitSignerUpdated, ProposalExpiryUpdated,
+    SetupFinalized, SnapshotClosed, SnapshotTaken, SnipeWindowUpdated, SniperFlagCleared, SniperFlagged,
+    StakingProgramUpdated, StakingVaultRegistered, SurplusLamportsWithdrawn, TimelockUpdateCanceled,
+    TimelockUpdateProposed, TimelockUpdated, TransferEventsUpdated, TrustedOwnerRegistered, TrustedProgramUpdated,
+    VetoAuthorityUpdateCanceled, VetoAuthorityUpdateProposed, VetoAuthorityUpdated, WalletCapUpdateCanceled,
+    WalletCapUpdateExpired, WalletCapUpdateProposed, WalletCapUpdateVetoed, WalletCapUpdated,
+    WalletOwnersRequiredUpdated,
+};
+
+const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/layouts.txt");
+
+fn key(byte: u8) -> Pubkey {
+    Pubkey::new_from_array([byte; 32])
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn unhex(hex: &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap()).collect()
+}
+
+// Accounts as stored: the discriminator, then the Borsh encoded fields
+fn account(out: &mut String, name: &str, account: &impl AccountSerialize) {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    let (discriminator, fields) = data.split_at(8);
+    writeln!(out, "account {} {} {}", name, hex(discriminator), hex(fields)).unwrap();
+}
+
+// Types stored inside an account
+fn layout(out: &mut String, name: &str, value: &impl AnchorSerialize) {
+    writeln!(out, "layout {} {}", name, hex(&value.try_to_vec().unwrap())).unwrap();
+}
+
+// Events as logged or carried by the self-CPI: the discriminator, then the Borsh encoded fields
+fn event(out: &mut String, name: &str, event: &impl Event) {
+    let data = event.data();
+    let (discriminator, fields) = data.split_at(8);
+    writeln!(out, "event {} {} {}", name, hex(discriminator), hex(fields)).unwrap();
+}
+
+fn render() -> String {
+    let mut out = String::new();
+
+    account(&mut out, "HookConfig", &HookConfig {
+        version: 1,
+        dev_wallets: [key(2); 4],
+        wallet_cap_raw: 3,
+        governance_authority: key(4),
+        pending_cap_update: Some(PendingCapUpdate {
+            kind: CapKind::Buy,
+            new_cap: 6,
+            proposed_at: -7,
+            execution_time: -8,
+            expires_at: -9,
+            nonce: 10,
+            proposed_by: key(11),
+            memo_hash: [12; 32],
+        }),
+        trusted_programs: [key(13); 4],
+        pending_dev_wallet_update: Some(PendingDevWalletUpdate {
+            index: 14,
+            new_wallet: key(15),
+            proposed_at: -16,
+            execution_time: -17,
+        }),
+        dev_exemption_expires_at: Some(-18),
+        dev_exemption_finalized: true,
+        airdrop_allowance: 20,
+        permit_signer: key(21),
+        buy_cap_raw: 22,
+        max_sell_raw: 23,
+        circuit_breaker_threshold_raw: 24,
+        circuit_breaker_window_slots: 25,
+        circuit_breaker_cooldown: -26,
+        window_start_slot: 27,
+        window_volume: 28,
+        paused_until: -29,
+        holder_count: 30,
+        max_holders: 31,
+        cap_unit: CapUnit::Ui,
+        pending_governance_authority: Some(key(33)),
+        governance_renounced: true,
+        governance_members: [key(35); 5],
+        governance_threshold: 36,
+        governance_epoch: 37,
+        guardian: Some(key(38)),
+        timelock_seconds: 39,
+        pending_timelock_update: Some(PendingTimelockUpdate {
+            timelock_seconds: 40,
+            proposed_at: -41,
+            execution_time: -42,
+        }),
+        proposal_expiry_seconds: 43,
+        proposal_nonce: 44,
+        proposer_authority: key(45),
+        executor_authority: key(46),
+        veto_authority: Some(key(47)),
+        pending_veto_authority_update: Some(PendingVetoAuthorityUpdate {
+            veto_authority: Some(key(48)),
+            proposed_at: -49,
+            execution_time: -50,
+        }),
+        min_cap_floor: 51,
+        max_cap_change_bps: 52,
+        frozen: true,
+        bump: 54,
+        emit_transfer_events: true,
+        total_transfers: 56,
+        total_volume: 57,
+        snapshot_count: 58,
+        last_snapshot_slot: 59,
+        pending_hook_disable: Some(-60),
+        hook_disabled: true,
+        dev_wallet_ata: key(62),
+        staking_program_slot: 63,
+        allow_confidential: true,
+        require_wallet_owners: true,
+        finalized: true,
+        exempt_registry_bump: 68,
+        used_nonces_bump: 69,
+        reserved: [70; 15],
+    });
+
+    layout(&mut out, "PendingCapUpdate", &PendingCapUpdate {
+        kind: CapKind::Buy,
+        new_cap: 2,
+        proposed_at: -3,
+        execution_time: -4,
+        expires_at: -5,
+        nonce: 6,
+        proposed_by: key(7),
+        memo_hash: [8; 32],
+    });
+
+    event(&mut out, "WalletCapUpdateProposed", &WalletCapUpdateProposed {
+        mint: key(1),
+        slot: 2,
+        kind: CapKind::Buy,
+        new_cap: 4,
+        current_cap: 5,
+        proposed_at: -6,
+        execution_time: -7,
+        governance_authority: key(8),
+        proposal_nonce: 9,
+        proposed_by: key(10),
+        memo_hash: [11; 32],
+    });
+    event(&mut out, "WalletCapUpdated", &WalletCapUpdated {
+        mint: key(1),
+        slot: 2,
+        kind: CapKind::Buy,
+        old_cap: 4,
+        new_cap: 5,
+        updated_at: -6,
+        executed_by: key(7),
+        proposal_nonce: 8,
+        immediate: true,
+        memo_hash: [10; 32],
+    });
+    event(&mut out, "WalletCapUpdateCanceled", &WalletCapUpdateCanceled {
+        mint: key(1),
+        slot: 2,
+        kind: CapKind::Buy,
+        canceled_cap: 4,
+        current_cap: 5,
+        canceled_at: -6,
+        governance_authority: key(7),
+        proposal_nonce: 8,
+        memo_hash: [9; 32],
+    });
+    event(&mut out, "WalletCapUpdateVetoed", &WalletCapUpdateVetoed {
+        mint: key(1),
+        slot: 2,
+        kind: CapKind::Buy,
+        vetoed_cap: 4,
+        current_cap: 5,
+        vetoed_at: -6,
+        veto_authority: key(7),
+        proposal_nonce: 8,
+        memo_hash: [9; 32],
+    });
+    event(&mut out, "WalletCapUpdateExpired", &WalletCapUpdateExpired {
+        mint: key(1),
+        slot: 2,
+        kind: CapKind::Buy,
+        expired_cap: 4,
+        current_cap: 5,
+        expires_at: -6,
+        swept_at: -7,
+        proposal_nonce: 8,
+        memo_hash: [9; 32],
+    });
+    event(&mut out, "ProposalExpiryUpdated", &ProposalExpiryUpdated {
+        mint: key(1),
+        slot: 2,
+        old_expiry_seconds: 3,
+        new_expiry_seconds: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "MinCapFloorRaised", &MinCapFloorRaised {
+        mint: key(1),
+        slot: 2,
+        old_floor: 3,
+        new_floor: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "VetoAuthorityUpdateProposed", &VetoAuthorityUpdateProposed {
+        mint: key(1),
+        slot: 2,
+        old_veto_authority: Some(key(3)),
+        new_veto_authority: Some(key(4)),
+        proposed_at: -5,
+        execution_time: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "VetoAuthorityUpdated", &VetoAuthorityUpdated {
+        mint: key(1),
+        slot: 2,
+        old_veto_authority: Some(key(3)),
+        new_veto_authority: Some(key(4)),
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "VetoAuthorityUpdateCanceled", &VetoAuthorityUpdateCanceled {
+        mint: key(1),
+        slot: 2,
+        veto_authority: Some(key(3)),
+        canceled_veto_authority: Some(key(4)),
+        canceled_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "CapUpdateRoleUpdated", &CapUpdateRoleUpdated {
+        mint: key(1),
+        slot: 2,
+        role: CapUpdateRole::Executor,
+        old_authority: key(4),
+        new_authority: key(5),
+        updated_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "GovernanceTransferProposed", &GovernanceTransferProposed {
+        mint: key(1),
+        slot: 2,
+        old_authority: key(3),
+        new_authority: key(4),
+        proposed_at: -5,
+    });
+    event(&mut out, "GovernanceAuthorityUpdated", &GovernanceAuthorityUpdated {
+        mint: key(1),
+        slot: 2,
+        old_authority: key(3),
+        new_authority: key(4),
+        updated_at: -5,
+    });
+    event(&mut out, "GovernanceTransferCanceled", &GovernanceTransferCanceled {
+        mint: key(1),
+        slot: 2,
+        old_authority: key(3),
+        new_authority: key(4),
+        canceled_at: -5,
+    });
+    event(&mut out, "ExemptEntryAdded", &ExemptEntryAdded {
+        mint: key(1),
+        slot: 2,
+        token_account: key(3),
+        kind: ExemptKind::PoolVault,
+        expires_at: Some(-5),
+        added_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "ExemptEntryRemoved", &ExemptEntryRemoved {
+        mint: key(1),
+        slot: 2,
+        token_account: key(3),
+        removed_at: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "ExemptRegistryPruned", &ExemptRegistryPruned {
+        mint: key(1),
+        slot: 2,
+        removed: 3,
+        remaining: 4,
+        refunded_lamports: 5,
+        pruned_at: -6,
+    });
+    event(&mut out, "TrustedProgramUpdated", &TrustedProgramUpdated {
+        mint: key(1),
+        slot: 2,
+        index: 3,
+        old_program: key(4),
+        new_program: key(5),
+        updated_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "DevWalletUpdateProposed", &DevWalletUpdateProposed {
+        mint: key(1),
+        slot: 2,
+        index: 3,
+        old_wallet: key(4),
+        new_wallet: key(5),
+        proposed_at: -6,
+        execution_time: -7,
+        governance_authority: key(8),
+    });
+    event(&mut out, "DevWalletUpdated", &DevWalletUpdated {
+        mint: key(1),
+        slot: 2,
+        index: 3,
+        old_wallet: key(4),
+        new_wallet: key(5),
+        updated_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "TimelockUpdateProposed", &TimelockUpdateProposed {
+        mint: key(1),
+        slot: 2,
+        old_timelock_seconds: 3,
+        new_timelock_seconds: 4,
+        proposed_at: -5,
+        execution_time: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "TimelockUpdated", &TimelockUpdated {
+        mint: key(1),
+        slot: 2,
+        old_timelock_seconds: 3,
+        new_timelock_seconds: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "TimelockUpdateCanceled", &TimelockUpdateCanceled {
+        mint: key(1),
+        slot: 2,
+        timelock_seconds: 3,
+        canceled_timelock_seconds: 4,
+        canceled_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "DevWalletUpdateCanceled", &DevWalletUpdateCanceled {
+        mint: key(1),
+        slot: 2,
+        index: 3,
+        old_wallet: key(4),
+        canceled_wallet: key(5),
+        canceled_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "DevExemptionExpiryUpdated", &DevExemptionExpiryUpdated {
+        mint: key(1),
+        slot: 2,
+        old_expires_at: Some(-3),
+        new_expires_at: -4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "DevExemptionExpired", &DevExemptionExpired {
+        mint: key(1),
+        slot: 2,
+        expired_at: -3,
+        finalized_at: -4,
+    });
+    event(&mut out, "AirdropAllowanceUpdated", &AirdropAllowanceUpdated {
+        mint: key(1),
+        slot: 2,
+        old_allowance: 3,
+        new_allowance: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "PermitSignerUpdated", &PermitSignerUpdated {
+        mint: key(1),
+        slot: 2,
+        old_signer: key(3),
+        new_signer: key(4),
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "PermitRedeemed", &PermitRedeemed {
+        mint: key(1),
+        slot: 2,
+        destination_owner: key(3),
+        amount: 4,
+        nonce: 5,
+        redeemed_at: -6,
+    });
+    event(&mut out, "CircuitBreakerUpdated", &CircuitBreakerUpdated {
+        mint: key(1),
+        slot: 2,
+        threshold_raw: 3,
+        window_slots: 4,
+        cooldown: -5,
+        updated_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "CircuitBreakerTriggered", &CircuitBreakerTriggered {
+        mint: key(1),
+        slot: 2,
+        window_start_slot: 3,
+        paused_until: -4,
+        triggered_at: -5,
+    });
+    event(&mut out, "CircuitBreakerCleared", &CircuitBreakerCleared {
+        mint: key(1),
+        slot: 2,
+        paused_until: -3,
+        cleared_at: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "MaxHoldersUpdated", &MaxHoldersUpdated {
+        mint: key(1),
+        slot: 2,
+        old_max_holders: 3,
+        new_max_holders: 4,
+        holder_count: 5,
+        updated_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "GovernanceMembersUpdated", &GovernanceMembersUpdated {
+        mint: key(1),
+        slot: 2,
+        members: [key(3); 5],
+        threshold: 4,
+        epoch: 5,
+        updated_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "GovernanceActionProposed", &GovernanceActionProposed {
+        mint: key(1),
+        slot: 2,
+        action_hash: [3; 32],
+        action: GovernanceAction::SetAirdropAllowance { allowance: 4 },
+        epoch: 5,
+        proposer: key(6),
+        proposed_at: -7,
+    });
+    event(&mut out, "GovernanceActionApproved", &GovernanceActionApproved {
+        mint: key(1),
+        slot: 2,
+        action_hash: [3; 32],
+        member: key(4),
+        approvals: 5,
+        threshold: 6,
+        approved_at: -7,
+    });
+    event(&mut out, "GuardianUpdated", &GuardianUpdated {
+        mint: key(1),
+        slot: 2,
+        old_guardian: Some(key(3)),
+        new_guardian: Some(key(4)),
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "PauseUpdated", &PauseUpdated {
+        mint: key(1),
+        slot: 2,
+        paused: true,
+        updated_at: -4,
+        authority: key(5),
+    });
+    event(&mut out, "ConfigFrozen", &ConfigFrozen {
+        mint: key(1),
+        slot: 2,
+        wallet_cap_raw: 3,
+        dev_wallets: [key(4); 4],
+        frozen_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "GovernanceRenounced", &GovernanceRenounced {
+        mint: key(1),
+        slot: 2,
+        old_authority: key(3),
+        wallet_cap_raw: 4,
+        renounced_at: -5,
+    });
+    event(&mut out, "ConfigMigrated", &ConfigMigrated {
+        mint: key(1),
+        slot: 2,
+        old_version: 3,
+        new_version: 4,
+        migrated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "ExtraAccountMetasUpdated", &ExtraAccountMetasUpdated {
+        mint: key(1),
+        slot: 2,
+        old_count: 3,
+        new_count: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "ExtraAccountMetaListClosed", &ExtraAccountMetaListClosed {
+        mint: key(1),
+        slot: 2,
+        recipient: key(3),
+        lamports: 4,
+        closed_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "ConfigClosed", &ConfigClosed {
+        mint: key(1),
+        slot: 2,
+        recipient: key(3),
+        lamports: 4,
+        closed_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "DevWalletIsGovernanceAuthority", &DevWalletIsGovernanceAuthority {
+        mint: key(1),
+        slot: 2,
+        authority: key(3),
+    });
+    event(&mut out, "HookTransferChecked", &HookTransferChecked {
+        mint: key(1),
+        slot: 2,
+        destination_owner: key(3),
+        amount: 4,
+        post_balance: 5,
+        cap: 6,
+        exempt: true,
+    });
+    event(&mut out, "TransferEventsUpdated", &TransferEventsUpdated {
+        mint: key(1),
+        slot: 2,
+        enabled: true,
+        updated_at: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "SnapshotTaken", &SnapshotTaken {
+        mint: key(1),
+        slot: 2,
+        index: 3,
+        supply: 4,
+        wallet_cap_raw: 5,
+        paused: true,
+        taken_at: -7,
+        payer: key(8),
+    });
+    event(&mut out, "SnapshotClosed", &SnapshotClosed {
+        mint: key(1),
+        slot: 2,
+        index: 3,
+        recipient: key(4),
+        lamports: 5,
+        closed_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "HookDisableProposed", &HookDisableProposed {
+        mint: key(1),
+        slot: 2,
+        proposed_at: -3,
+        execution_time: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "HookDisableCanceled", &HookDisableCanceled {
+        mint: key(1),
+        slot: 2,
+        execution_time: -3,
+        canceled_at: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "HookPermanentlyDisabled", &HookPermanentlyDisabled {
+        mint: key(1),
+        slot: 2,
+        disabled_at: -3,
+        governance_authority: key(4),
+    });
+    event(&mut out, "LockupCreated", &LockupCreated {
+        mint: key(1),
+        slot: 2,
+        owner: key(3),
+        locked_amount: 4,
+        locked_until: -5,
+        linear_release_end: Some(-6),
+        created_at: -7,
+        governance_authority: key(8),
+    });
+    event(&mut out, "LockupShortened", &LockupShortened {
+        mint: key(1),
+        slot: 2,
+        owner: key(3),
+        old_locked_until: -4,
+        locked_until: -5,
+        old_linear_release_end: Some(-6),
+        linear_release_end: Some(-7),
+        shortened_at: -8,
+        governance_authority: key(9),
+    });
+    event(&mut out, "MaxCapChangeTightened", &MaxCapChangeTightened {
+        mint: key(1),
+        slot: 2,
+        old_bps: 3,
+        new_bps: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "StakingProgramUpdated", &StakingProgramUpdated {
+        mint: key(1),
+        slot: 2,
+        index: 3,
+        old_program: key(4),
+        new_program: key(5),
+        updated_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "StakingVaultRegistered", &StakingVaultRegistered {
+        mint: key(1),
+        slot: 2,
+        token_account: key(3),
+        vault_owner: key(4),
+        registered_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "BridgeCustodyRegistered", &BridgeCustodyRegistered {
+        mint: key(1),
+        slot: 2,
+        token_account: key(3),
+        custody_owner: key(4),
+        bridge_program: key(5),
+        registered_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "LaunchDetected", &LaunchDetected {
+        mint: key(1),
+        slot: 2,
+        pool_vault: key(3),
+        amount: 4,
+        detected_at: -5,
+    });
+    event(&mut out, "LaunchDetectionReset", &LaunchDetectionReset {
+        mint: key(1),
+        slot: 2,
+        old_detected_at: -3,
+        old_detected_slot: 4,
+        reset_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "SniperFlagged", &SniperFlagged {
+        mint: key(1),
+        slot: 2,
+        owner: key(3),
+        amount: 4,
+        seconds_after_launch: -5,
+        logged: true,
+    });
+    event(&mut out, "SniperFlagCleared", &SniperFlagCleared {
+        mint: key(1),
+        slot: 2,
+        owner: key(3),
+        cleared_at: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "SnipeWindowUpdated", &SnipeWindowUpdated {
+        mint: key(1),
+        slot: 2,
+        old_window_seconds: 3,
+        new_window_seconds: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "WalletOwnersRequiredUpdated", &WalletOwnersRequiredUpdated {
+        mint: key(1),
+        slot: 2,
+        enabled: true,
+        updated_at: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "MemoThresholdUpdated", &MemoThresholdUpdated {
+        mint: key(1),
+        slot: 2,
+        old_threshold: None,
+        new_threshold: Some(3),
+        updated_at: -4,
+        governance_authority: key(5),
+    });
+    event(&mut out, "DevOutflowLimitLowered", &DevOutflowLimitLowered {
+        mint: key(1),
+        slot: 2,
+        old_limit: Some(3),
+        new_limit: 4,
+        updated_at: -5,
+        governance_authority: key(6),
+    });
+    event(&mut out, "SetupFinalized", &SetupFinalized { mint: key(1), slot: 2, meta_count: 3, finalized_at: -4 });
+    event(&mut out, "SurplusLamportsWithdrawn", &SurplusLamportsWithdrawn {
+        mint: key(1),
+        slot: 2,
+        recipient: key(3),
+        config_lamports: 4,
+        extra_account_meta_list_lamports: 5,
+        withdrawn_at: -6,
+        governance_authority: key(7),
+    });
+    event(&mut out, "TrustedOwnerRegistered", &TrustedOwnerRegistered {
+        mint: key(1),
+        slot: 2,
+        owner: key(3),
+        program: key(4),
+        registered_at: -5,
+    });
+
+    out
+}
+
+/// The byte offsets at which `actual` differs from `expected`, for the failure message
+fn diff_offsets(expected: &[u8], actual: &[u8]) -> String {
+    let mut offsets =
+        (0..expected.len().max(actual.len())).filter(|&offset| expected.get(offset) != actual.get(offset));
+    let first: Vec<String> = offsets.by_ref().take(16).map(|offset| offset.to_string()).collect();
+    let more = match offsets.count() {
+        0 => String::new(),
+        more => format!(" and {} more", more),
+    };
+    let lengths = format!("{} bytes expected, {} rendered", expected.len(), actual.len());
+    format!("{}, differing at offsets {}{}", lengths, first.join(", "), more)
+}
+
+/// Fail on the first part of `rendered` that differs from the snapshot's `expected` line
+fn assert_line_matches(rendered: &str, expected: &str) {
+    let rendered: Vec<&str> = rendered.split(' ').collect();
+    let expected: Vec<&str> = expected.split(' ').collect();
+    assert_eq!(rendered[..2], expected[..2], "layout snapshot entries out of order");
+    assert_eq!(rendered.len(), expected.len(), "{} gained or lost its discriminator", expected[1]);
+
+    let parts = if expected.len() == 4 { &["discriminator", "fields"][..] } else { &["fields"][..] };
+    for (part, (rendered_hex, expected_hex)) in parts.iter().zip(rendered[2..].iter().zip(&expected[2..])) {
+        if rendered_hex != expected_hex {
+            let offsets = diff_offsets(&unhex(expected_hex), &unhex(rendered_hex));
+            panic!("{} {} changed: {}\nrendered: {}", expected[1], part, offsets, rendered_hex);
+        }
+    }
+}
+
+#[test]
+fn layouts_match_snapshot() {
+    let rendered = render();
+    if std::env::var_os("UPDATE_LAYOUT_SNAPSHOT").is_some() {
+        std::fs::write(SNAPSHOT, &rendered).unwrap();
+        return;
+    }
+
+    let snapshot = std::fs::read_to_string(SNAPSHOT).unwrap();
+    for (rendered, expected) in rendered.lines().zip(snapshot.lines()) {
+        assert_line_matches(rendered, expected);
+    }
+    assert_eq!(rendered.lines().count(), snapshot.lines().count(), "layouts gained or lost entries");
+}
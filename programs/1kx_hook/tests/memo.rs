@@ -0,0 +1,84 @@
+// Memo-required transfers: once governance sets a threshold in the config extension, a transfer of more than
+// that between accounts that aren't exempt needs an SPL Memo instruction in the same transaction. Without a
+// threshold nothing is asked of any transfer.
+
+mod test_utils;
+
+use one_kx_hook::{accounts, instruction, HookError, MemoThresholdUpdated};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{decode_event, event_authority, hook_error, program_instruction, Harness, HookedMint};
+
+const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+fn memo(text: &str) -> Instruction {
+    Instruction::new_with_bytes(MEMO_PROGRAM_ID, text.as_bytes(), vec![])
+}
+
+async fn set_memo_required_above(
+    harness: &mut Harness,
+    mint: &HookedMint,
+    threshold: Option<u64>,
+) -> MemoThresholdUpdated {
+    let governance = mint.governance.insecure_clone();
+    let set = program_instruction(
+        accounts::SetMemoRequiredAbove {
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            config_extension: mint.config_extension(),
+            mint: mint.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::SetMemoRequiredAbove { threshold },
+    );
+    let events = harness.process_for_events(&[set], &[&governance]).await;
+    events.iter().find_map(|event| decode_event::<MemoThresholdUpdated>(event)).expect("update event")
+}
+
+#[tokio::test]
+async fn transfers_above_the_threshold_need_a_memo() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let holder = Keypair::new();
+    let source = harness.create_token_account(&mint, &holder.pubkey(), 10_000).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    // No threshold: any amount goes without a memo
+    harness.transfer(&mint, &source, &destination, &holder, 2_000).await.0.unwrap();
+
+    let updated = set_memo_required_above(&mut harness, &mint, Some(1_000)).await;
+    assert_eq!((updated.old_threshold, updated.new_threshold), (None, Some(1_000)));
+
+    // The threshold itself goes through, one more needs the memo
+    harness.transfer(&mint, &source, &destination, &holder, 1_000).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &source, &destination, &holder, 1_001).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::MemoRequired));
+
+    let transfer = harness.transfer_instruction(&mint, &source, &destination, &holder.pubkey(), 1_001).await;
+    harness.process(&[memo("invoice 42"), transfer], &[&holder]).await.0.unwrap();
+    assert_eq!(harness.balance(&destination).await, 4_001);
+}
+
+#[tokio::test]
+async fn exempt_parties_and_lifted_thresholds_need_no_memo() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let holder = Keypair::new();
+    let source = harness.create_token_account(&mint, &holder.pubkey(), 10_000).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    let dev_wallet = harness.create_token_account(&mint, &mint.dev_wallet, 0).await;
+    set_memo_required_above(&mut harness, &mint, Some(1_000)).await;
+
+    // Sends into the dev wallet are exempt, so no memo is asked for
+    harness.transfer(&mint, &source, &dev_wallet, &holder, 2_000).await.0.unwrap();
+
+    let updated = set_memo_required_above(&mut harness, &mint, None).await;
+    assert_eq!((updated.old_threshold, updated.new_threshold), (Some(1_000), None));
+    harness.transfer(&mint, &source, &destination, &holder, 2_000).await.0.unwrap();
+}
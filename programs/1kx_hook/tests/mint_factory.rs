@@ -0,0 +1,364 @@
+// The factory path: a `GlobalConfig` created once by the upgrade authority names a factory admin, who
+// may initialize the config of any hooked mint through `initialize_for_mint`, as may the mint authority.
+// Either way the mint is recorded on a `MintRegistry` page for tooling to enumerate. The global config's
+// parameter defaults fill in what `initialize_for_mint` leaves out and can later be adopted field by field,
+// but never reach the transfer path.
+
+mod test_utils;
+
+use anchor_lang::AccountDeserialize;
+use one_kx_hook::{
+    accounts, instruction, CapUnit, GlobalConfig, HookError, MintRegistry, MintRegistryEntry, PendingTimelockUpdate,
+};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{
+    event_authority, hook_error, pda, program_instruction, Harness, HookedMint, TIMELOCK_SECONDS, WALLET_CAP_RAW,
+};
+
+fn global_config() -> Pubkey {
+    pda(&[b"global"])
+}
+
+fn registry_page(page: u32) -> Pubkey {
+    pda(&[b"mint-registry", &page.to_le_bytes()])
+}
+
+fn init_global_config(payer: Pubkey, upgrade_authority: &Pubkey, factory_admin: &Pubkey) -> Instruction {
+    let program_data =
+        Pubkey::find_program_address(&[one_kx_hook::ID.as_ref()], &solana_sdk::bpf_loader_upgradeable::id()).0;
+    let accounts = accounts::InitGlobalConfig {
+        payer,
+        global_config: global_config(),
+        upgrade_authority: *upgrade_authority,
+        program_data,
+        system_program: system_program::ID,
+    };
+    program_instruction(accounts, instruction::InitGlobalConfig { factory_admin: *factory_admin })
+}
+
+/// `initialize_for_mint` with the harness defaults, signed by `initializer`, onto the first registry page
+fn initialize_for_mint(payer: Pubkey, mint: &HookedMint, initializer: &Pubkey) -> Instruction {
+    initialize_with_overrides(payer, mint, initializer, Some(TIMELOCK_SECONDS), Some(0), Some(0))
+}
+
+/// `initialize_for_mint` taking the global default wherever a parameter is `None`
+fn initialize_with_overrides(
+    payer: Pubkey,
+    mint: &HookedMint,
+    initializer: &Pubkey,
+    timelock_seconds: Option<u32>,
+    min_cap_floor: Option<u64>,
+    max_cap_change_bps: Option<u16>,
+) -> Instruction {
+    let accounts = accounts::InitializeForMint {
+        payer,
+        config: mint.config(),
+        mint: mint.address,
+        initializer: *initializer,
+        global_config: global_config(),
+        mint_registry: registry_page(0),
+        system_program: system_program::ID,
+        event_authority: event_authority(),
+        program: one_kx_hook::ID,
+    };
+    let args = instruction::InitializeForMint {
+        dev_wallet: mint.dev_wallet,
+        governance_authority: mint.governance.pubkey(),
+        cap_unit: CapUnit::Raw,
+        timelock_seconds,
+        min_cap_floor,
+        max_cap_change_bps,
+        strict_authorities: false,
+        allow_confidential: false,
+    };
+    program_instruction(accounts, args)
+}
+
+fn set_global_defaults(factory_admin: &Pubkey, timelock_seconds: u32, min_cap_floor: u64, bps: u16) -> Instruction {
+    program_instruction(
+        accounts::SetGlobalDefaults { global_config: global_config(), factory_admin: *factory_admin },
+        instruction::SetGlobalDefaults { timelock_seconds, min_cap_floor, max_cap_change_bps: bps },
+    )
+}
+
+/// `adopt_global_defaults` of `fields`, to be signed by the mint's governance
+fn adopt_global_defaults(mint: &HookedMint, fields: u8) -> Instruction {
+    let accounts = accounts::AdoptGlobalDefaults {
+        config: mint.config(),
+        governance_authority: mint.governance.pubkey(),
+        global_config: global_config(),
+        mint: mint.address,
+        approval: None,
+        event_authority: event_authority(),
+        program: one_kx_hook::ID,
+    };
+    program_instruction(accounts, instruction::AdoptGlobalDefaults { fields })
+}
+
+/// Create the global config and its first registry page, returning the factory admin
+async fn factory(harness: &mut Harness) -> Keypair {
+    let upgrade_authority = Keypair::new();
+    harness.set_upgrade_authority(&upgrade_authority.pubkey());
+    let admin = Keypair::new();
+    let payer = harness.context.payer.pubkey();
+    let init_page = program_instruction(
+        accounts::InitMintRegistryPage {
+            payer,
+            global_config: global_config(),
+            mint_registry: registry_page(0),
+            system_program: system_program::ID,
+        },
+        instruction::InitMintRegistryPage {},
+    );
+    let setup = [init_global_config(payer, &upgrade_authority.pubkey(), &admin.pubkey()), init_page];
+    harness.process(&setup, &[&upgrade_authority]).await.0.unwrap();
+    admin
+}
+
+async fn account<T: AccountDeserialize>(harness: &mut Harness, address: Pubkey) -> T {
+    let account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+#[tokio::test]
+async fn global_config_needs_the_upgrade_authority() {
+    let mut harness = Harness::new().await;
+    let upgrade_authority = Keypair::new();
+    harness.set_upgrade_authority(&upgrade_authority.pubkey());
+    let payer = harness.context.payer.pubkey();
+    let admin = Pubkey::new_unique();
+
+    let stranger = Keypair::new();
+    let init = init_global_config(payer, &stranger.pubkey(), &admin);
+    assert_eq!(harness.process(&[init], &[&stranger]).await.0.unwrap_err(), hook_error(HookError::NotUpgradeAuthority));
+
+    let init = init_global_config(payer, &upgrade_authority.pubkey(), &admin);
+    harness.process(&[init], &[&upgrade_authority]).await.0.unwrap();
+    let global: GlobalConfig = account(&mut harness, global_config()).await;
+    assert_eq!((global.factory_admin, global.mint_count), (admin, 0));
+
+    // A singleton: not even the upgrade authority gets to create it twice
+    let init = init_global_config(payer, &upgrade_authority.pubkey(), &Pubkey::new_unique());
+    assert!(harness.process(&[init], &[&upgrade_authority]).await.0.is_err());
+}
+
+#[tokio::test]
+async fn mint_authority_or_factory_admin_registers_mints() {
+    let mut harness = Harness::new().await;
+    let admin = factory(&mut harness).await;
+    let payer = harness.context.payer.pubkey();
+    let own = harness.create_mint().await;
+    let managed = harness.create_mint().await;
+
+    let authority = own.mint_authority.insecure_clone();
+    harness.process(&[initialize_for_mint(payer, &own, &authority.pubkey())], &[&authority]).await.0.unwrap();
+    harness.context.warp_to_slot(1_000).unwrap();
+    harness.process(&[initialize_for_mint(payer, &managed, &admin.pubkey())], &[&admin]).await.0.unwrap();
+
+    // Both configs are the ones `initialize` would have written
+    for mint in [&own, &managed] {
+        let config = harness.config(mint).await;
+        assert_eq!(config.governance_authority, mint.governance.pubkey());
+        assert_eq!(config.dev_wallets[0], mint.dev_wallet);
+    }
+    let global: GlobalConfig = account(&mut harness, global_config()).await;
+    assert_eq!(global.mint_count, 2);
+    let registry: MintRegistry = account(&mut harness, registry_page(0)).await;
+    assert_eq!(registry.page, 0);
+    let [first, second] = &registry.entries[..] else { panic!("{:?}", registry.entries) };
+    assert_eq!((first.mint, first.creator), (own.address, authority.pubkey()));
+    assert!(first.created_slot < 1_000);
+    assert_eq!(
+        second,
+        &MintRegistryEntry { mint: managed.address, creator: admin.pubkey(), created_slot: second.created_slot }
+    );
+    assert!(second.created_slot >= 1_000);
+}
+
+#[tokio::test]
+async fn other_signers_cannot_initialize() {
+    let mut harness = Harness::new().await;
+    let admin = factory(&mut harness).await;
+    let payer = harness.context.payer.pubkey();
+    let mint = harness.create_mint().await;
+
+    let stranger = Keypair::new();
+    let initialize = initialize_for_mint(payer, &mint, &stranger.pubkey());
+    assert_eq!(
+        harness.process(&[initialize], &[&stranger]).await.0.unwrap_err(),
+        hook_error(HookError::UnauthorizedInitializer)
+    );
+
+    // The factory admin only counts through `initialize_for_mint`; plain `initialize` still wants the
+    // mint authority
+    let initialize = program_instruction(
+        accounts::Initialize {
+            payer,
+            config: mint.config(),
+            mint: mint.address,
+            mint_authority: admin.pubkey(),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::Initialize {
+            dev_wallet: mint.dev_wallet,
+            governance_authority: mint.governance.pubkey(),
+            cap_unit: CapUnit::Raw,
+            timelock_seconds: TIMELOCK_SECONDS,
+            min_cap_floor: 0,
+            max_cap_change_bps: 0,
+            strict_authorities: false,
+            allow_confidential: false,
+        },
+    );
+    assert_eq!(
+        harness.process(&[initialize], &[&admin]).await.0.unwrap_err(),
+        hook_error(HookError::UnauthorizedInitializer)
+    );
+    let global: GlobalConfig = account(&mut harness, global_config()).await;
+    assert_eq!(global.mint_count, 0);
+}
+
+#[tokio::test]
+async fn global_defaults_fill_in_what_initialize_for_mint_leaves_out() {
+    let mut harness = Harness::new().await;
+    let admin = factory(&mut harness).await;
+    let payer = harness.context.payer.pubkey();
+
+    let stranger = Keypair::new();
+    let set = set_global_defaults(&stranger.pubkey(), 2 * TIMELOCK_SECONDS, 1_000_000_000, 2_000);
+    assert_eq!(
+        harness.process(&[set], &[&stranger]).await.0.unwrap_err(),
+        hook_error(HookError::UnauthorizedInitializer)
+    );
+    // Only defaults `initialize` itself would accept
+    let set = set_global_defaults(&admin.pubkey(), 2 * TIMELOCK_SECONDS, WALLET_CAP_RAW + 1, 2_000);
+    assert_eq!(harness.process(&[set], &[&admin]).await.0.unwrap_err(), hook_error(HookError::CapBelowFloor));
+    let set = set_global_defaults(&admin.pubkey(), 2 * TIMELOCK_SECONDS, 1_000_000_000, 2_000);
+    harness.process(&[set], &[&admin]).await.0.unwrap();
+
+    let defaulted = harness.create_mint().await;
+    let overridden = harness.create_mint().await;
+    let initialize = [
+        initialize_with_overrides(payer, &defaulted, &admin.pubkey(), None, None, None),
+        initialize_with_overrides(payer, &overridden, &admin.pubkey(), Some(TIMELOCK_SECONDS), None, Some(500)),
+    ];
+    harness.process(&initialize, &[&admin]).await.0.unwrap();
+
+    let config = harness.config(&defaulted).await;
+    assert_eq!(
+        (config.timelock_seconds, config.min_cap_floor, config.max_cap_change_bps),
+        (2 * TIMELOCK_SECONDS, 1_000_000_000, 2_000)
+    );
+    let config = harness.config(&overridden).await;
+    assert_eq!(
+        (config.timelock_seconds, config.min_cap_floor, config.max_cap_change_bps),
+        (TIMELOCK_SECONDS, 1_000_000_000, 500)
+    );
+}
+
+#[tokio::test]
+async fn adopt_global_defaults_resyncs_selected_fields() {
+    let mut harness = Harness::new().await;
+    let admin = factory(&mut harness).await;
+    let payer = harness.context.payer.pubkey();
+    let mint = harness.create_mint().await;
+    harness.process(&[initialize_for_mint(payer, &mint, &admin.pubkey())], &[&admin]).await.0.unwrap();
+    let set = set_global_defaults(&admin.pubkey(), 2 * TIMELOCK_SECONDS, 1_000_000_000, 2_000);
+    harness.process(&[set], &[&admin]).await.0.unwrap();
+    let governance = mint.governance.insecure_clone();
+
+    for fields in [0, GlobalConfig::ADOPT_ALL + 1] {
+        let adopt = adopt_global_defaults(&mint, fields);
+        assert_eq!(
+            harness.process(&[adopt], &[&governance]).await.0.unwrap_err(),
+            hook_error(HookError::InvalidGlobalDefaultsFields)
+        );
+    }
+    // A stranger's signature doesn't adopt anything
+    let impostor = HookedMint {
+        address: mint.address,
+        mint_authority: Keypair::new(),
+        dev_wallet: mint.dev_wallet,
+        governance: Keypair::new(),
+    };
+    let adopt = adopt_global_defaults(&impostor, GlobalConfig::ADOPT_MIN_CAP_FLOOR);
+    assert_eq!(
+        harness.process(&[adopt], &[&impostor.governance]).await.0.unwrap_err(),
+        hook_error(HookError::UnauthorizedGovernance)
+    );
+
+    // The floor and swing limit apply at once, the timelock is left alone
+    let adopt =
+        adopt_global_defaults(&mint, GlobalConfig::ADOPT_MIN_CAP_FLOOR | GlobalConfig::ADOPT_MAX_CAP_CHANGE_BPS);
+    harness.process(&[adopt], &[&governance]).await.0.unwrap();
+    let config = harness.config(&mint).await;
+    assert_eq!((config.min_cap_floor, config.max_cap_change_bps), (1_000_000_000, 2_000));
+    assert_eq!(config.timelock_seconds, TIMELOCK_SECONDS);
+    assert!(config.pending_timelock_update.is_none());
+
+    // The timelock is only proposed, to run out under the current one
+    let now = harness.unix_timestamp().await;
+    harness.process(&[adopt_global_defaults(&mint, GlobalConfig::ADOPT_TIMELOCK)], &[&governance]).await.0.unwrap();
+    let config = harness.config(&mint).await;
+    assert_eq!(config.timelock_seconds, TIMELOCK_SECONDS);
+    let Some(PendingTimelockUpdate { timelock_seconds, execution_time, .. }) = config.pending_timelock_update else {
+        panic!("no timelock update pending")
+    };
+    assert_eq!((timelock_seconds, execution_time), (2 * TIMELOCK_SECONDS, now + TIMELOCK_SECONDS as i64));
+
+    // A looser default is no reason to loosen a mint that adopted the tighter one
+    let set = set_global_defaults(&admin.pubkey(), 2 * TIMELOCK_SECONDS, 1_000_000_000, 5_000);
+    harness.process(&[set], &[&admin]).await.0.unwrap();
+    let adopt = adopt_global_defaults(&mint, GlobalConfig::ADOPT_MAX_CAP_CHANGE_BPS);
+    assert_eq!(
+        harness.process(&[adopt], &[&governance]).await.0.unwrap_err(),
+        hook_error(HookError::CapChangeLimitLoosened)
+    );
+    assert_eq!(harness.config(&mint).await.max_cap_change_bps, 2_000);
+}
+
+#[tokio::test]
+async fn transfers_never_read_the_global_config() {
+    let mut harness = Harness::new().await;
+    let admin = factory(&mut harness).await;
+    let payer = harness.context.payer.pubkey();
+    let plain = harness.create_hooked_mint().await;
+    let mint = harness.create_mint().await;
+    let init_extra_account_meta_list = program_instruction(
+        accounts::InitExtraAccountMetaList {
+            payer,
+            extra_account_meta_list: mint.extra_account_meta_list(),
+            mint: mint.address,
+            system_program: system_program::ID,
+        },
+        instruction::InitExtraAccountMetaList {},
+    );
+    let setup =
+        [initialize_for_mint(payer, &mint, &admin.pubkey()), init_extra_account_meta_list, mint.finalize_setup()];
+    harness.process(&setup, &[&admin]).await.0.unwrap();
+
+    // A factory mint resolves the same extra accounts as any other
+    let banks_client = &mut harness.context.banks_client;
+    let factory_metas = banks_client.get_account(mint.extra_account_meta_list()).await.unwrap().unwrap();
+    let plain_metas = banks_client.get_account(plain.extra_account_meta_list()).await.unwrap().unwrap();
+    assert_eq!(factory_metas.data, plain_metas.data);
+
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    let transfer = harness.transfer_instruction(&mint, &source, &destination, &owner.pubkey(), 1).await;
+    assert!(transfer.accounts.iter().all(|meta| meta.pubkey != global_config()));
+
+    // Changing the defaults afterwards doesn't touch the mint's transfers either
+    let set = set_global_defaults(&admin.pubkey(), 2 * TIMELOCK_SECONDS, WALLET_CAP_RAW, 1);
+    harness.process(&[set], &[&admin]).await.0.unwrap();
+    harness.process(&[transfer], &[&owner]).await.0.unwrap();
+    assert_eq!(harness.balance(&destination).await, 1);
+}
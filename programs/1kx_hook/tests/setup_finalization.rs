@@ -0,0 +1,91 @@
+// Setup finalization: a config starts out not finalized and the hook refuses every transfer until
+// `finalize_setup` has checked that the mint routes its transfers here and that the extra account meta
+// list holds what `execute` resolves. Anyone can send it, and it only succeeds once.
+
+mod test_utils;
+
+use one_kx_hook::{HookError, SetupFinalized};
+use solana_sdk::{
+    account::Account,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use test_utils::{decode_event, hook_error, Harness};
+
+#[tokio::test]
+async fn nothing_moves_before_the_setup_is_finalized() {
+    let mut harness = Harness::new().await;
+    let dev = Keypair::new();
+    let mint = harness.create_unfinalized_hooked_mint(dev.pubkey()).await;
+    let holder = Keypair::new();
+    let source = harness.create_token_account(&mint, &holder.pubkey(), 1_000).await;
+    let dev_wallet = harness.create_token_account(&mint, &dev.pubkey(), 1_000).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    assert!(!harness.config(&mint).await.finalized);
+
+    // Not even the exempt dev wallet can send yet
+    let (result, _) = harness.transfer(&mint, &source, &destination, &holder, 1).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::SetupNotFinalized));
+    let (result, _) = harness.transfer(&mint, &dev_wallet, &destination, &dev, 1).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::SetupNotFinalized));
+
+    // No signer beyond the fee payer
+    let events = harness.process_for_events(&[mint.finalize_setup()], &[]).await;
+    let finalized = events.iter().find_map(|event| decode_event::<SetupFinalized>(event)).expect("event");
+    assert_eq!(finalized.mint, mint.address);
+    let list = harness.context.banks_client.get_account(mint.extra_account_meta_list()).await.unwrap().unwrap();
+    assert_eq!(ExtraAccountMetaList::size_of(finalized.meta_count as usize).unwrap(), list.data.len());
+    assert!(harness.config(&mint).await.finalized);
+
+    harness.transfer(&mint, &source, &destination, &holder, 1).await.0.unwrap();
+    harness.transfer(&mint, &dev_wallet, &destination, &dev, 1).await.0.unwrap();
+    assert_eq!(harness.balance(&destination).await, 2);
+
+    let (result, _) = harness.process(&[mint.finalize_setup()], &[]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::SetupAlreadyFinalized));
+}
+
+#[tokio::test]
+async fn finalize_setup_checks_the_meta_list_and_the_mint() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_unfinalized_hooked_mint(Pubkey::new_unique()).await;
+    let address = mint.extra_account_meta_list();
+    let list = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+
+    // No meta list at its address
+    harness.context.set_account(&address, &Account::new(list.lamports, 0, &system_program::ID).into());
+    let (result, _) = harness.process(&[mint.finalize_setup()], &[]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::ExtraAccountMetaListMissing));
+
+    // A list, but not the accounts this version resolves
+    let other = [ExtraAccountMeta::new_with_pubkey(&Pubkey::new_unique(), false, false).unwrap()];
+    let mut data = vec![0u8; ExtraAccountMetaList::size_of(other.len()).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &other).unwrap();
+    harness.context.set_account(&address, &Account { data, ..list.clone() }.into());
+    let (result, _) = harness.process(&[mint.finalize_setup()], &[]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::ExtraAccountMetasMismatch));
+    harness.context.set_account(&address, &list.into());
+
+    // The right list, on a mint whose hook now points at another program
+    let authority = mint.mint_authority.insecure_clone();
+    let repoint = |program_id| {
+        spl_token_2022::extension::transfer_hook::instruction::update(
+            &spl_token_2022::id(),
+            &mint.address,
+            &authority.pubkey(),
+            &[],
+            Some(program_id),
+        )
+        .unwrap()
+    };
+    harness.process(&[repoint(Pubkey::new_unique())], &[&authority]).await.0.unwrap();
+    let (result, _) = harness.process(&[mint.finalize_setup()], &[]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::MintNotConfiguredForHook));
+
+    harness.process(&[repoint(one_kx_hook::ID)], &[&authority]).await.0.unwrap();
+    harness.process(&[mint.finalize_setup()], &[]).await.0.unwrap();
+    assert!(harness.config(&mint).await.finalized);
+}
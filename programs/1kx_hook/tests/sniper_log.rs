@@ -0,0 +1,174 @@
+// Sniper flagging: once governance sets a snipe window, every buy out of a registered pool vault landing
+// within it of the detected launch flags the destination owner, in a SniperFlagged event and in the mint's
+// pre-allocated sniper log. The log saturates instead of pushing early snipers out; governance clears
+// flags one owner at a time.
+
+mod test_utils;
+
+use anchor_lang::{AccountDeserialize, AccountSerialize};
+use one_kx_hook::{
+    accounts, instruction, ExemptKind, HookError, SnipeWindowUpdated, SniperFlagCleared, SniperFlagged, SniperLog,
+};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{decode_event, event_authority, hook_error, pda, program_instruction, Harness, HookedMint};
+
+/// Register a pool vault holding `amount`, owned by the returned pool keypair
+async fn pool_vault(harness: &mut Harness, mint: &HookedMint, amount: u64) -> (Pubkey, Keypair) {
+    let payer = harness.context.payer.pubkey();
+    let exempt_registry = pda(&[b"exempt-registry", mint.address.as_ref()]);
+    let pool = Keypair::new();
+    let vault = harness.create_token_account(mint, &pool.pubkey(), amount).await;
+    let init_exempt_registry = program_instruction(
+        accounts::InitExemptRegistry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+        },
+        instruction::InitExemptRegistry { rent_recipient: payer },
+    );
+    let add = program_instruction(
+        accounts::AddExemptEntry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::AddExemptEntry { token_account: vault, kind: ExemptKind::PoolVault, expires_at: None },
+    );
+    let governance = mint.governance.insecure_clone();
+    harness.process(&[init_exempt_registry, add], &[&governance]).await.0.unwrap();
+    (vault, pool)
+}
+
+fn set_snipe_window(mint: &HookedMint, window_seconds: u32) -> Instruction {
+    program_instruction(
+        accounts::SetSnipeWindow {
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            config_extension: mint.config_extension(),
+            mint: mint.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::SetSnipeWindow { window_seconds },
+    )
+}
+
+fn clear_sniper_flag(mint: &HookedMint, owner: Pubkey) -> Instruction {
+    program_instruction(
+        accounts::ClearSniperFlag {
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            sniper_log: mint.sniper_log(),
+            mint: mint.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::ClearSniperFlag { owner },
+    )
+}
+
+async fn sniper_log(harness: &mut Harness, mint: &HookedMint) -> SniperLog {
+    let account = harness.context.banks_client.get_account(mint.sniper_log()).await.unwrap().unwrap();
+    SniperLog::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Buy `amount` out of the pool vault into a fresh account of `buyer` and return the flags it raised
+async fn buy(
+    harness: &mut Harness,
+    mint: &HookedMint,
+    (vault, pool): &(Pubkey, Keypair),
+    buyer: &Pubkey,
+    amount: u64,
+) -> Vec<SniperFlagged> {
+    let destination = harness.create_token_account(mint, buyer, 0).await;
+    let transfer = harness.transfer_instruction(mint, vault, &destination, &pool.pubkey(), amount).await;
+    let events = harness.process_for_events(&[transfer], &[pool]).await;
+    events.iter().filter_map(|event| decode_event::<SniperFlagged>(event)).collect()
+}
+
+#[tokio::test]
+async fn buys_in_the_snipe_window_flag_their_buyer() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let pool = pool_vault(&mut harness, &mint, 10_000).await;
+    let governance = mint.governance.insecure_clone();
+
+    // Without a window nobody is flagged
+    let launched_at = harness.unix_timestamp().await;
+    assert!(buy(&mut harness, &mint, &pool, &Pubkey::new_unique(), 100).await.is_empty());
+
+    let events = harness.process_for_events(&[set_snipe_window(&mint, 600)], &[&governance]).await;
+    let updated = events.iter().find_map(|event| decode_event::<SnipeWindowUpdated>(event)).expect("window event");
+    assert_eq!((updated.old_window_seconds, updated.new_window_seconds), (0, 600));
+    let (result, _) = harness.process(&[set_snipe_window(&mint, 86_401)], &[&governance]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::InvalidSnipeWindow));
+
+    // Within the window, measured from the launch rather than from setting the window
+    let sniper = Pubkey::new_unique();
+    harness.warp_to_timestamp(launched_at + 300).await;
+    let flagged = buy(&mut harness, &mint, &pool, &sniper, 1_000).await;
+    assert_eq!(flagged.len(), 1);
+    assert_eq!((flagged[0].owner, flagged[0].amount, flagged[0].logged), (sniper, 1_000, true));
+    assert_eq!(flagged[0].seconds_after_launch, 300);
+    // Buying again flags the same owner without listing it twice
+    assert!(buy(&mut harness, &mint, &pool, &sniper, 1_000).await[0].logged);
+
+    harness.warp_to_timestamp(launched_at + 600).await;
+    assert!(buy(&mut harness, &mint, &pool, &Pubkey::new_unique(), 1_000).await.is_empty());
+    let log = sniper_log(&mut harness, &mint).await;
+    assert_eq!((log.sniper_list, log.dropped), (vec![sniper], 0));
+}
+
+#[tokio::test]
+async fn the_sniper_log_saturates_and_governance_clears_it() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let pool = pool_vault(&mut harness, &mint, 10_000).await;
+    let governance = mint.governance.insecure_clone();
+    harness.process(&[set_snipe_window(&mint, 600)], &[&governance]).await.0.unwrap();
+
+    // Fill the log: everything after the mint, the drop count and the list's length prefix is list
+    let capacity = (SniperLog::SPACE - 8 - 32 - 4 - 4) / 32;
+    let snipers: Vec<_> = (0..capacity).map(|_| Pubkey::new_unique()).collect();
+    let full = SniperLog { mint: mint.address, dropped: 0, sniper_list: snipers.clone() };
+    let mut data = Vec::new();
+    full.try_serialize(&mut data).unwrap();
+    let account = harness.context.banks_client.get_account(mint.sniper_log()).await.unwrap().unwrap();
+    harness.context.set_account(&mint.sniper_log(), &Account { data, ..account }.into());
+
+    // A sniper finding the log full is still reported, but the owners listed first stay listed
+    let latecomer = Pubkey::new_unique();
+    let flagged = buy(&mut harness, &mint, &pool, &latecomer, 1_000).await;
+    assert_eq!((flagged[0].owner, flagged[0].logged), (latecomer, false));
+    let log = sniper_log(&mut harness, &mint).await;
+    assert_eq!((&log.sniper_list, log.dropped), (&snipers, 1));
+
+    // Clearing a flag makes room for the next one
+    let events = harness.process_for_events(&[clear_sniper_flag(&mint, snipers[0])], &[&governance]).await;
+    let cleared = events.iter().find_map(|event| decode_event::<SniperFlagCleared>(event)).expect("clear event");
+    assert_eq!(cleared.owner, snipers[0]);
+    let (result, _) = harness.process(&[clear_sniper_flag(&mint, snipers[0])], &[&governance]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::SniperNotFlagged));
+    assert!(buy(&mut harness, &mint, &pool, &latecomer, 1_000).await[0].logged);
+    let log = sniper_log(&mut harness, &mint).await;
+    assert_eq!(log.sniper_list[..capacity - 1], snipers[1..]);
+    assert_eq!(log.sniper_list[capacity - 1], latecomer);
+}
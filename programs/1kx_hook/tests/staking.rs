@@ -0,0 +1,174 @@
+// Staking through a designated staking program: the mock staking program in the harness owns a vault
+// through its pool PDA. Once governance designates the program and the vault is registered, staking into
+// the vault takes any amount, while unstaking back out is held to the wallet cap with an error of its own.
+
+mod test_utils;
+
+use one_kx_hook::{accounts, instruction, HookError};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{
+    event_authority, hook_error, mock_staking, pda, program_instruction, Harness, HookedMint, WALLET_CAP_RAW,
+};
+
+fn exempt_registry(mint: &HookedMint) -> Pubkey {
+    pda(&[b"exempt-registry", mint.address.as_ref()])
+}
+
+/// Designate the mock staking program in trusted program slot 0, open its pool and create the exempt
+/// registry, returning the pool's vault
+async fn staking(harness: &mut Harness, mint: &HookedMint) -> Pubkey {
+    let payer = harness.context.payer.pubkey();
+    let init_exempt_registry = program_instruction(
+        accounts::InitExemptRegistry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry: exempt_registry(mint),
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+        },
+        instruction::InitExemptRegistry { rent_recipient: payer },
+    );
+    let setup = [
+        mint.governance_instruction(instruction::SetStakingProgram { index: 0, program_id: mock_staking::ID }),
+        init_exempt_registry,
+        mock_staking::open_pool(&payer, &mint.address),
+    ];
+    let governance = mint.governance.insecure_clone();
+    harness.process(&setup, &[&governance]).await.0.unwrap();
+    harness.create_token_account(mint, &mock_staking::pool(&mint.address), 0).await
+}
+
+fn register_staking_vault(
+    payer: Pubkey,
+    mint: &HookedMint,
+    vault: &Pubkey,
+    vault_owner: &Pubkey,
+    seeds: Vec<Vec<u8>>,
+) -> Instruction {
+    program_instruction(
+        accounts::RegisterStakingVault {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry: exempt_registry(mint),
+            vault: *vault,
+            vault_owner: *vault_owner,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::RegisterStakingVault { seeds },
+    )
+}
+
+/// Have the staking program release `amount` from `vault` into `destination`
+async fn unstake(
+    harness: &mut Harness,
+    mint: &HookedMint,
+    vault: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let pool = mock_staking::pool(&mint.address);
+    let transfer = harness.transfer_instruction(mint, vault, destination, &pool, amount).await;
+    mock_staking::unstake(&transfer, amount)
+}
+
+#[tokio::test]
+async fn staking_vaults_take_any_amount_and_unstakes_are_capped() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let vault = staking(&mut harness, &mint).await;
+    let pool = mock_staking::pool(&mint.address);
+    let payer = harness.context.payer.pubkey();
+    let governance = mint.governance.insecure_clone();
+    let register = register_staking_vault(payer, &mint, &vault, &pool, mock_staking::pool_seeds(&mint.address));
+    harness.process(&[register], &[&governance]).await.0.unwrap();
+
+    // Two holders at the cap both stake everything; the vault ends up holding twice the cap
+    let (alice, bob) = (Keypair::new(), Keypair::new());
+    let alice_wallet = harness.create_token_account(&mint, &alice.pubkey(), WALLET_CAP_RAW).await;
+    let bob_wallet = harness.create_token_account(&mint, &bob.pubkey(), WALLET_CAP_RAW).await;
+    harness.transfer(&mint, &alice_wallet, &vault, &alice, WALLET_CAP_RAW).await.0.unwrap();
+    harness.transfer(&mint, &bob_wallet, &vault, &bob, WALLET_CAP_RAW).await.0.unwrap();
+    assert_eq!(harness.balance(&vault).await, 2 * WALLET_CAP_RAW);
+
+    // Unstaking is held to the wallet cap like any receive, and says so
+    let back = unstake(&mut harness, &mint, &vault, &alice_wallet, WALLET_CAP_RAW).await;
+    harness.process(&[back], &[]).await.0.unwrap();
+    let over = unstake(&mut harness, &mint, &vault, &alice_wallet, 1).await;
+    assert_eq!(harness.process(&[over], &[]).await.0.unwrap_err(), hook_error(HookError::UnstakeWouldExceedCap));
+    assert_eq!(harness.balance(&alice_wallet).await, WALLET_CAP_RAW);
+
+    // The same receive from anything but the vault is an ordinary over-cap transfer
+    let carol = Keypair::new();
+    let carol_wallet = harness.create_token_account(&mint, &carol.pubkey(), 1).await;
+    let (result, _) = harness.transfer(&mint, &carol_wallet, &alice_wallet, &carol, 1).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::WalletCapExceeded));
+}
+
+#[tokio::test]
+async fn only_vaults_of_the_staking_program_register() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let vault = staking(&mut harness, &mint).await;
+    let pool = mock_staking::pool(&mint.address);
+    let seeds = mock_staking::pool_seeds(&mint.address);
+    let payer = harness.context.payer.pubkey();
+    let governance = mint.governance.insecure_clone();
+    let not_staking_vault = hook_error(HookError::NotStakingVault);
+
+    // Only governance registers vaults, so nobody else can fill the exempt registry
+    let stranger = Keypair::new();
+    let mut register = register_staking_vault(payer, &mint, &vault, &pool, seeds.clone());
+    let authority = register.accounts.iter_mut().find(|meta| meta.pubkey == governance.pubkey()).unwrap();
+    authority.pubkey = stranger.pubkey();
+    assert_eq!(
+        harness.process(&[register], &[&stranger]).await.0.unwrap_err(),
+        hook_error(HookError::UnauthorizedGovernance)
+    );
+
+    // A wallet's account, one owned by a PDA with no account of the staking program behind it, the vault
+    // passed with the wrong owner, and the vault with seeds that derive some other address
+    let wallet = Keypair::new().pubkey();
+    let wallet_owned = harness.create_token_account(&mint, &wallet, 0).await;
+    let (stray_pda, stray_bump) = Pubkey::find_program_address(&[b"pool"], &mock_staking::ID);
+    let stray_owned = harness.create_token_account(&mint, &stray_pda, 0).await;
+    let stray_seeds = vec![b"pool".to_vec(), vec![stray_bump]];
+    let other_seeds = mock_staking::pool_seeds(&Pubkey::new_unique());
+    for (vault, owner, seeds) in [
+        (wallet_owned, wallet, vec![]),
+        (stray_owned, stray_pda, stray_seeds),
+        (vault, Pubkey::new_unique(), seeds.clone()),
+        (vault, pool, other_seeds),
+    ] {
+        let register = register_staking_vault(payer, &mint, &vault, &owner, seeds);
+        assert_eq!(harness.process(&[register], &[&governance]).await.0.unwrap_err(), not_staking_vault);
+    }
+
+    // Handing the staking program's slot to a plain trusted program ends the designation
+    let replace =
+        mint.governance_instruction(instruction::SetTrustedProgram { index: 0, program_id: mock_staking::ID });
+    harness.process(&[replace], &[&governance]).await.0.unwrap();
+    assert_eq!(harness.config(&mint).await.staking_program(), None);
+    let register = register_staking_vault(payer, &mint, &vault, &pool, seeds.clone());
+    assert_eq!(harness.process(&[register], &[&governance]).await.0.unwrap_err(), not_staking_vault);
+
+    let designate =
+        mint.governance_instruction(instruction::SetStakingProgram { index: 1, program_id: mock_staking::ID });
+    harness.process(&[designate], &[&governance]).await.0.unwrap();
+    let config = harness.config(&mint).await;
+    assert_eq!(config.staking_program(), Some(mock_staking::ID));
+    assert_eq!(config.trusted_programs[..2], [mock_staking::ID, mock_staking::ID]);
+    let register = register_staking_vault(payer, &mint, &vault, &pool, seeds);
+    harness.process(&[register], &[&governance]).await.0.unwrap();
+}
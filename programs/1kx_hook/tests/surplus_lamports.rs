@@ -0,0 +1,90 @@
+// Surplus lamports: SOL sent to the config or the extra account meta list by mistake can be withdrawn by
+// governance. Only what either account holds above its rent exemption moves, so both stay open.
+
+mod test_utils;
+
+use one_kx_hook::{accounts, instruction, HookError, SurplusLamportsWithdrawn};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use test_utils::{decode_event, event_authority, hook_error, program_instruction, Harness, HookedMint};
+
+fn withdraw_surplus_lamports(mint: &HookedMint, governance_authority: &Pubkey, recipient: &Pubkey) -> Instruction {
+    program_instruction(
+        accounts::WithdrawSurplusLamports {
+            config: mint.config(),
+            governance_authority: *governance_authority,
+            extra_account_meta_list: mint.extra_account_meta_list(),
+            recipient: *recipient,
+            mint: mint.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::WithdrawSurplusLamports {},
+    )
+}
+
+async fn lamports(harness: &mut Harness, address: Pubkey) -> u64 {
+    harness.context.banks_client.get_balance(address).await.unwrap()
+}
+
+#[tokio::test]
+async fn governance_withdraws_exactly_the_surplus() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let governance = mint.governance.insecure_clone();
+    let recipient = Pubkey::new_unique();
+    let (config, list) = (mint.config(), mint.extra_account_meta_list());
+    let rent_exempt = (lamports(&mut harness, config).await, lamports(&mut harness, list).await);
+
+    // SOL sent to both addresses, as wallets do when pasted the wrong one
+    let payer = harness.context.payer.pubkey();
+    let gifts = [
+        system_instruction::transfer(&payer, &config, 2_000_000),
+        system_instruction::transfer(&payer, &list, 1_000_000),
+    ];
+    harness.process(&gifts, &[]).await.0.unwrap();
+
+    let stranger = Keypair::new();
+    let (result, _) =
+        harness.process(&[withdraw_surplus_lamports(&mint, &stranger.pubkey(), &recipient)], &[&stranger]).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::UnauthorizedGovernance));
+
+    let withdraw = withdraw_surplus_lamports(&mint, &governance.pubkey(), &recipient);
+    let events = harness.process_for_events(&[withdraw], &[&governance]).await;
+    let withdrawn = events.iter().find_map(|event| decode_event::<SurplusLamportsWithdrawn>(event)).expect("event");
+    assert_eq!((withdrawn.config_lamports, withdrawn.extra_account_meta_list_lamports), (2_000_000, 1_000_000));
+    assert_eq!(withdrawn.recipient, recipient);
+    assert_eq!(lamports(&mut harness, recipient).await, 3_000_000);
+
+    // Both are left at exactly their rent exemption and keep working
+    assert_eq!((lamports(&mut harness, config).await, lamports(&mut harness, list).await), rent_exempt);
+    let rent = harness.context.banks_client.get_rent().await.unwrap();
+    for address in [config, list] {
+        let account = harness.context.banks_client.get_account(address).await.unwrap().unwrap();
+        assert!(rent.is_exempt(account.lamports, account.data.len()));
+    }
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), 1).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    harness.transfer(&mint, &source, &destination, &owner, 1).await.0.unwrap();
+}
+
+#[tokio::test]
+async fn nothing_to_withdraw_is_a_no_op() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let governance = mint.governance.insecure_clone();
+    let recipient = Pubkey::new_unique();
+    let config_lamports = lamports(&mut harness, mint.config()).await;
+
+    let withdraw = withdraw_surplus_lamports(&mint, &governance.pubkey(), &recipient);
+    let events = harness.process_for_events(&[withdraw], &[&governance]).await;
+    assert!(events.iter().all(|event| decode_event::<SurplusLamportsWithdrawn>(event).is_none()));
+    assert_eq!(lamports(&mut harness, mint.config()).await, config_lamports);
+    assert_eq!(lamports(&mut harness, recipient).await, 0);
+}
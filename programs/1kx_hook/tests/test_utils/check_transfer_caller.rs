@@ -0,0 +1,63 @@
+// A stand-in for a staking or escrow program that asks the hook, before releasing tokens to a user,
+// whether the release would bounce off the cap: it CPIs into `check_transfer` and branches on the
+// verdict it reads back with `get_return_data`.
+//
+// The instruction data is the amount as a little-endian u64, the accounts are the hook program and then
+// `check_transfer`'s own. Where a real caller would transfer or keep the tokens, it logs
+// `release <amount>` or `hold <amount>: reason <code>`.
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::{get_return_data, invoke},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use anchor_lang::{AnchorDeserialize, InstructionData};
+use one_kx_hook::{instruction, CheckTransferResult};
+
+pub const ID: Pubkey = Pubkey::new_from_array([0xca; 32]);
+
+pub fn process_instruction(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let amount = <[u8; 8]>::try_from(data).map(u64::from_le_bytes).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let [hook, check_accounts @ ..] = accounts else { return Err(ProgramError::NotEnoughAccountKeys) };
+    let check = Instruction {
+        program_id: *hook.key,
+        accounts: check_accounts.iter().map(|account| AccountMeta::new_readonly(*account.key, false)).collect(),
+        data: instruction::CheckTransfer { amount }.data(),
+    };
+    // A malformed account fails the hook, and with it this call
+    invoke(&check, accounts)?;
+
+    // Return data is left by whichever program set it last, so only the hook's is taken
+    let verdict = match get_return_data() {
+        Some((program_id, data)) if program_id == *hook.key => {
+            CheckTransferResult::try_from_slice(&data).map_err(|_| ProgramError::InvalidAccountData)?
+        }
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+    if verdict.allowed {
+        msg!("release {}", amount);
+    } else {
+        msg!("hold {}: reason {}", amount, verdict.reason_code);
+    }
+    Ok(())
+}
+
+/// Ask the caller to release `amount` into `destination`, a token account of `mint`
+pub fn release(mint: &Pubkey, destination: &Pubkey, amount: u64) -> Instruction {
+    let accounts = [
+        one_kx_hook::ID,
+        super::pda(&[b"config", mint.as_ref()]),
+        *mint,
+        *destination,
+        super::pda(&[b"exempt-registry", mint.as_ref()]),
+    ];
+    Instruction {
+        program_id: ID,
+        accounts: accounts.iter().map(|account| AccountMeta::new_readonly(*account, false)).collect(),
+        data: amount.to_le_bytes().to_vec(),
+    }
+}
@@ -0,0 +1,95 @@
+// A stand-in for a staking program: a pool account at its PDA `["pool", mint]` owns the vault that users
+// stake into with plain transfers, and `unstake` releases tokens from the vault by having Token-2022
+// transfer them with the pool's signature, hook and all.
+//
+// Instruction data is a tag byte: 0 opens the pool (accounts: payer, pool, mint, system program), 1 and
+// a little-endian u64 amount unstakes it (accounts: Token-2022, vault, mint, destination, pool, then the
+// hook's extra accounts as the transfer resolves them).
+
+use anchor_lang::solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+
+pub const ID: Pubkey = Pubkey::new_from_array([0x57; 32]);
+
+pub fn process_instruction(_: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    match data {
+        [0] => {
+            let [payer, pool, mint, _system_program] = accounts else { return Err(ProgramError::NotEnoughAccountKeys) };
+            let bump = pool_bump(mint.key, pool.key)?;
+            let create =
+                system_instruction::create_account(payer.key, pool.key, Rent::get()?.minimum_balance(0), 0, &ID);
+            invoke_signed(&create, accounts, &[&[b"pool", mint.key.as_ref(), &[bump]]])
+        }
+        [1, amount @ ..] => {
+            let amount = <[u8; 8]>::try_from(amount)
+                .map(u64::from_le_bytes)
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
+            let [token_program, vault, mint, destination, pool, hook_accounts @ ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            let bump = pool_bump(mint.key, pool.key)?;
+            spl_token_2022::onchain::invoke_transfer_checked(
+                token_program.key,
+                vault.clone(),
+                mint.clone(),
+                destination.clone(),
+                pool.clone(),
+                hook_accounts,
+                amount,
+                super::DECIMALS,
+                &[&[b"pool", mint.key.as_ref(), &[bump]]],
+            )
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn pool_bump(mint: &Pubkey, pool: &Pubkey) -> Result<u8, ProgramError> {
+    let (address, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &ID);
+    if address != *pool {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(bump)
+}
+
+/// The pool PDA of `mint`, which owns its vault
+pub fn pool(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool", mint.as_ref()], &ID).0
+}
+
+/// The seeds, bump included, that derive the pool PDA of `mint`
+pub fn pool_seeds(mint: &Pubkey) -> Vec<Vec<u8>> {
+    let (_, bump) = Pubkey::find_program_address(&[b"pool", mint.as_ref()], &ID);
+    vec![b"pool".to_vec(), mint.to_bytes().to_vec(), vec![bump]]
+}
+
+/// Create the pool account of `mint`, so the pool is a PDA account the staking program owns
+pub fn open_pool(payer: &Pubkey, mint: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(pool(mint), false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    Instruction { program_id: ID, accounts, data: vec![0] }
+}
+
+/// Unstake through `transfer`, a `transfer_checked` out of the vault with the pool as its authority and
+/// the hook's extra accounts resolved
+pub fn unstake(transfer: &Instruction, amount: u64) -> Instruction {
+    let accounts = [AccountMeta::new_readonly(transfer.program_id, false)]
+        .into_iter()
+        .chain(transfer.accounts.iter().map(|meta| AccountMeta { is_signer: false, ..meta.clone() }))
+        .collect();
+    let data = [&[1][..], &amount.to_le_bytes()].concat();
+    Instruction { program_id: ID, accounts, data }
+}
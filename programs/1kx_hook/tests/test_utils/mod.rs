@@ -0,0 +1,523 @@
+// Shared harness for the suites that drive the hook through Token-2022 itself.
+//
+// Nothing is injected: mints and token accounts are created with Token-2022's own instructions, the hook
+// is set up through `initialize` and `init_extra_account_meta_list`, and transfers are `transfer_checked`
+// instructions whose extra accounts are resolved from the meta list the way wallets resolve them. Under
+// `cargo test-sbf` the compiled program is loaded; plain `cargo test` runs the native entrypoint.
+
+// Each suite that declares this module uses a different part of it
+#![allow(dead_code)]
+
+pub mod check_transfer_caller;
+pub mod mock_staking;
+
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, log::sol_log_data};
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use one_kx_hook::{accounts, instruction, CapUnit, HookConfig, HookError};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::AccountSharedData,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use spl_tlv_account_resolution::state::AccountFetchError;
+use spl_token_2022::extension::{ExtensionType, StateWithExtensions};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+
+/// The config's default cap, with `CapUnit::Raw`
+pub const WALLET_CAP_RAW: u64 = 5_000_000_000;
+
+pub const DECIMALS: u8 = 9;
+
+/// The 48 hour default every harness mint is initialized with
+pub const TIMELOCK_SECONDS: u32 = 48 * 60 * 60;
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // BanksClient returns no inner instructions, so log the data of the program's event self-CPIs where
+    // `process_for_events` finds it, as an indexer would read it from the transaction's inner instructions
+    if data.starts_with(&EVENT_IX_TAG_LE) {
+        sol_log_data(&[data]);
+    }
+    // Anchor's entrypoint ties the account slice lifetime to the account data; leaking the
+    // slice satisfies that for the lifetime of the test process
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    one_kx_hook::entry(program_id, accounts, data)
+}
+
+pub fn compiled_program_available() -> bool {
+    std::env::var("BPF_OUT_DIR").is_ok()
+}
+
+fn program_test() -> ProgramTest {
+    let mut test = if compiled_program_available() {
+        let mut test = ProgramTest::new("one_kx_hook", one_kx_hook::ID, None);
+        test.prefer_bpf(true);
+        test
+    } else {
+        ProgramTest::new("one_kx_hook", one_kx_hook::ID, processor!(process_instruction))
+    };
+    // Native under `cargo test-sbf` too, as there are no compiled mock programs to load
+    let prefer_bpf = compiled_program_available();
+    test.prefer_bpf(false);
+    test.add_program(
+        "check_transfer_caller",
+        check_transfer_caller::ID,
+        processor!(check_transfer_caller::process_instruction),
+    );
+    test.add_program("mock_staking", mock_staking::ID, processor!(mock_staking::process_instruction));
+    test.prefer_bpf(prefer_bpf);
+    test
+}
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &one_kx_hook::ID).0
+}
+
+/// The PDA that signs the program's event self-CPIs, passed to every instruction that emits through `emit_cpi!`
+pub fn event_authority() -> Pubkey {
+    pda(&[b"__event_authority"])
+}
+
+pub fn program_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction { program_id: one_kx_hook::ID, accounts: accounts.to_account_metas(None), data: data.data() }
+}
+
+/// Decode an event payload as `T`, or None if it is a different event
+pub fn decode_event<T: Discriminator + AnchorDeserialize>(payload: &[u8]) -> Option<T> {
+    let (discriminator, data) = payload.split_at(8);
+    (discriminator == T::DISCRIMINATOR).then(|| T::deserialize(&mut &data[..]).unwrap())
+}
+
+/// The error a transaction fails with when the hook rejects its first instruction
+pub fn hook_error(error: HookError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+}
+
+/// A Token-2022 mint whose TransferHook extension points at the program, with its config and meta list
+pub struct HookedMint {
+    pub address: Pubkey,
+    pub mint_authority: Keypair,
+    pub dev_wallet: Pubkey,
+    pub governance: Keypair,
+}
+
+impl HookedMint {
+    pub fn config(&self) -> Pubkey {
+        pda(&[b"config", self.address.as_ref()])
+    }
+
+    pub fn extra_account_meta_list(&self) -> Pubkey {
+        pda(&[b"extra-account-metas", self.address.as_ref()])
+    }
+
+    pub fn governance_log(&self) -> Pubkey {
+        pda(&[b"gov-log", self.address.as_ref()])
+    }
+
+    pub fn config_extension(&self) -> Pubkey {
+        pda(&[b"config-ext", self.address.as_ref()])
+    }
+
+    pub fn sniper_log(&self) -> Pubkey {
+        pda(&[b"sniper-log", self.address.as_ref()])
+    }
+
+    /// `data` over the config / authority / mint layout most governance instructions share, to be signed
+    /// by `governance`
+    pub fn governance_instruction(&self, data: impl InstructionData) -> Instruction {
+        let accounts = accounts::SetCircuitBreaker {
+            config: self.config(),
+            governance_authority: self.governance.pubkey(),
+            mint: self.address,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        };
+        program_instruction(accounts, data)
+    }
+
+    /// `finalize_setup`, which anyone can send
+    pub fn finalize_setup(&self) -> Instruction {
+        let accounts = accounts::FinalizeSetup {
+            config: self.config(),
+            extra_account_meta_list: self.extra_account_meta_list(),
+            mint: self.address,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        };
+        program_instruction(accounts, instruction::FinalizeSetup {})
+    }
+
+    /// Accounts for proposing or cancelling a cap update as the mint's governance authority
+    fn cap_update_accounts(&self) -> accounts::ProposeWalletCapUpdate {
+        accounts::ProposeWalletCapUpdate {
+            config: self.config(),
+            governance_authority: self.governance.pubkey(),
+            mint: self.address,
+            governance_log: self.governance_log(),
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        }
+    }
+
+    /// `propose_wallet_cap_update`, to be signed by `governance`
+    pub fn propose_wallet_cap(&self, new_cap: u64, overwrite: bool) -> Instruction {
+        program_instruction(
+            self.cap_update_accounts(),
+            instruction::ProposeWalletCapUpdate { new_cap, overwrite, memo_hash: [0; 32] },
+        )
+    }
+
+    /// `cancel_wallet_cap_update`, to be signed by `governance`
+    pub fn cancel_wallet_cap_update(&self) -> Instruction {
+        program_instruction(self.cap_update_accounts(), instruction::CancelWalletCapUpdate {})
+    }
+
+    /// `execute_wallet_cap_update` cranked by `executor`
+    pub fn execute_wallet_cap_update(&self, executor: &Pubkey) -> Instruction {
+        let accounts = accounts::ExecuteWalletCapUpdate {
+            config: self.config(),
+            executor: *executor,
+            mint: self.address,
+            governance_log: self.governance_log(),
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        };
+        program_instruction(accounts, instruction::ExecuteWalletCapUpdate {})
+    }
+}
+
+/// A started test validator; every mint it creates gets a config of its own
+pub struct Harness {
+    pub context: ProgramTestContext,
+}
+
+impl Harness {
+    pub async fn new() -> Self {
+        Self { context: program_test().start_with_context().await }
+    }
+
+    /// A new Token-2022 mint whose TransferHook extension points at the program, with nothing of the
+    /// hook set up for it yet
+    pub async fn create_mint(&mut self) -> HookedMint {
+        let mint = Keypair::new();
+        let hooked = HookedMint {
+            address: mint.pubkey(),
+            mint_authority: Keypair::new(),
+            dev_wallet: Pubkey::new_unique(),
+            governance: Keypair::new(),
+        };
+        let authority = hooked.mint_authority.pubkey();
+
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+        let create_mint = [
+            self.create_account(&hooked.address, space).await,
+            spl_token_2022::extension::transfer_hook::instruction::initialize(
+                &spl_token_2022::id(),
+                &hooked.address,
+                Some(authority),
+                Some(one_kx_hook::ID),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::id(),
+                &hooked.address,
+                &authority,
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+        ];
+        self.process(&create_mint, &[&mint]).await.0.unwrap();
+        hooked
+    }
+
+    /// A new hooked mint, initialized the way a deployment sets one up: the mint, then the config,
+    /// then the meta list Token-2022 resolves the hook's accounts from, then `finalize_setup`
+    pub async fn create_hooked_mint(&mut self) -> HookedMint {
+        self.create_hooked_mint_with_dev_wallet(Pubkey::new_unique()).await
+    }
+
+    /// `create_hooked_mint` with `dev_wallet` in dev wallet slot 0, for suites that sign as the dev wallet
+    pub async fn create_hooked_mint_with_dev_wallet(&mut self, dev_wallet: Pubkey) -> HookedMint {
+        let hooked = self.create_unfinalized_hooked_mint(dev_wallet).await;
+        self.process(&[hooked.finalize_setup()], &[]).await.0.unwrap();
+        hooked
+    }
+
+    /// `create_hooked_mint_with_dev_wallet` stopping short of `finalize_setup`, so the hook still refuses
+    /// every transfer
+    pub async fn create_unfinalized_hooked_mint(&mut self, dev_wallet: Pubkey) -> HookedMint {
+        let mut hooked = self.create_mint().await;
+        hooked.dev_wallet = dev_wallet;
+        let payer = self.context.payer.pubkey();
+        let authority = hooked.mint_authority.pubkey();
+
+        let initialize = program_instruction(
+            accounts::Initialize {
+                payer,
+                config: hooked.config(),
+                mint: hooked.address,
+                mint_authority: authority,
+                system_program: system_program::ID,
+                event_authority: event_authority(),
+                program: one_kx_hook::ID,
+            },
+            instruction::Initialize {
+                dev_wallet: hooked.dev_wallet,
+                governance_authority: hooked.governance.pubkey(),
+                cap_unit: CapUnit::Raw,
+                timelock_seconds: TIMELOCK_SECONDS,
+                min_cap_floor: 0,
+                max_cap_change_bps: 0,
+                strict_authorities: false,
+                allow_confidential: false,
+            },
+        );
+        let init_governance_log = program_instruction(
+            accounts::InitGovernanceLog {
+                payer,
+                config: hooked.config(),
+                governance_log: hooked.governance_log(),
+                mint: hooked.address,
+                system_program: system_program::ID,
+            },
+            instruction::InitGovernanceLog {},
+        );
+        let init_config_extension = program_instruction(
+            accounts::InitConfigExtension {
+                payer,
+                config: hooked.config(),
+                config_extension: hooked.config_extension(),
+                mint: hooked.address,
+                system_program: system_program::ID,
+            },
+            instruction::InitConfigExtension {},
+        );
+        let init_sniper_log = program_instruction(
+            accounts::InitSniperLog {
+                payer,
+                config: hooked.config(),
+                sniper_log: hooked.sniper_log(),
+                mint: hooked.address,
+                system_program: system_program::ID,
+            },
+            instruction::InitSniperLog {},
+        );
+        let init_extra_account_meta_list = program_instruction(
+            accounts::InitExtraAccountMetaList {
+                payer,
+                extra_account_meta_list: hooked.extra_account_meta_list(),
+                mint: hooked.address,
+                system_program: system_program::ID,
+            },
+            instruction::InitExtraAccountMetaList {},
+        );
+        let mint_authority = hooked.mint_authority.insecure_clone();
+        let setup =
+            [initialize, init_governance_log, init_config_extension, init_sniper_log, init_extra_account_meta_list];
+        self.process(&setup, &[&mint_authority]).await.0.unwrap();
+        hooked
+    }
+
+    /// A token account of `mint` owned by `owner`, with `amount` minted into it
+    pub async fn create_token_account(&mut self, mint: &HookedMint, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = Keypair::new();
+        // Token-2022 requires the TransferHookAccount extension on every account of a hooked mint
+        let extensions = [ExtensionType::TransferHookAccount];
+        let space = ExtensionType::try_calculate_account_len::<TokenAccount>(&extensions).unwrap();
+        let mut instructions = vec![
+            self.create_account(&account.pubkey(), space).await,
+            spl_token_2022::instruction::initialize_account3(
+                &spl_token_2022::id(),
+                &account.pubkey(),
+                &mint.address,
+                owner,
+            )
+            .unwrap(),
+        ];
+        if amount > 0 {
+            instructions.push(
+                spl_token_2022::instruction::mint_to_checked(
+                    &spl_token_2022::id(),
+                    &mint.address,
+                    &account.pubkey(),
+                    &mint.mint_authority.pubkey(),
+                    &[],
+                    amount,
+                    DECIMALS,
+                )
+                .unwrap(),
+            );
+        }
+        self.process(&instructions, &[&account, &mint.mint_authority]).await.0.unwrap();
+        account.pubkey()
+    }
+
+    async fn create_account(&mut self, address: &Pubkey, space: usize) -> Instruction {
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        system_instruction::create_account(
+            &self.context.payer.pubkey(),
+            address,
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::id(),
+        )
+    }
+
+    /// `transfer_checked` of `amount` from `source` to `destination`, with the hook's extra accounts
+    /// resolved from the on-chain meta list by SPL's offchain helper
+    pub async fn transfer_instruction(
+        &mut self,
+        mint: &HookedMint,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let banks_client = self.context.banks_client.clone();
+        let fetch_account_data = |address: Pubkey| {
+            let mut banks_client = banks_client.clone();
+            async move {
+                let account = banks_client.get_account(address).await;
+                let account = account.map_err(|error| Box::new(error) as AccountFetchError)?;
+                Ok(account.map(|account| account.data))
+            }
+        };
+        spl_token_2022::offchain::create_transfer_checked_instruction_with_extra_metas(
+            &spl_token_2022::id(),
+            source,
+            &mint.address,
+            destination,
+            owner,
+            &[],
+            amount,
+            DECIMALS,
+            fetch_account_data,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// The hook's own `execute`, called directly over the accounts Token-2022 would pass it for the
+    /// same transfer
+    pub async fn execute_instruction(
+        &mut self,
+        mint: &HookedMint,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let transfer = self.transfer_instruction(mint, source, destination, owner, amount).await;
+        // Token-2022 passes the transfer's accounts without the owner's signature, the meta list, then the
+        // accounts resolved from it
+        let meta_list = mint.extra_account_meta_list();
+        let (transfer_accounts, rest) = transfer.accounts.split_at(4);
+        let accounts = transfer_accounts
+            .iter()
+            .map(|meta| AccountMeta { is_signer: false, ..meta.clone() })
+            .chain([AccountMeta::new_readonly(meta_list, false)])
+            .chain(rest.iter().filter(|meta| meta.pubkey != meta_list && meta.pubkey != one_kx_hook::ID).cloned())
+            .collect();
+        Instruction { program_id: one_kx_hook::ID, accounts, data: instruction::Execute { amount }.data() }
+    }
+
+    /// Transfer `amount` from `source`, signed by its owner, and return the result with the compute
+    /// units the transaction consumed
+    pub async fn transfer(
+        &mut self,
+        mint: &HookedMint,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> (Result<(), TransactionError>, u64) {
+        let transfer = self.transfer_instruction(mint, source, destination, &owner.pubkey(), amount).await;
+        self.process(&[transfer], &[owner]).await
+    }
+
+    /// Make `authority` the program's upgrade authority where `init_global_config` reads it. The harness
+    /// doesn't load the program through the upgradeable loader, so its ProgramData account is written here
+    pub fn set_upgrade_authority(&mut self, authority: &Pubkey) {
+        let program_data = Pubkey::find_program_address(&[one_kx_hook::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
+        let state = UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: Some(*authority) };
+        let account = AccountSharedData::new_data(LAMPORTS_PER_SOL, &state, &bpf_loader_upgradeable::id()).unwrap();
+        self.context.set_account(&program_data, &account);
+    }
+
+    pub async fn config(&mut self, mint: &HookedMint) -> HookConfig {
+        let account = self.context.banks_client.get_account(mint.config()).await.unwrap().unwrap();
+        HookConfig::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn unix_timestamp(&mut self) -> i64 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// Set the bank clock's unix timestamp, which is all the program's timelocks read
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = self.context.banks_client.get_account(*token_account).await.unwrap().unwrap();
+        StateWithExtensions::<TokenAccount>::unpack(&account.data).unwrap().base.amount
+    }
+
+    async fn transaction(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let payer = self.context.payer.pubkey();
+        Transaction::new_signed_with_payer(instructions, Some(&payer), &all_signers, blockhash)
+    }
+
+    /// Send `instructions` and return the result with the compute units the transaction consumed
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> (Result<(), TransactionError>, u64) {
+        let transaction = self.transaction(instructions, signers).await;
+        let outcome = self.context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+        (outcome.result, outcome.metadata.unwrap().compute_units_consumed)
+    }
+
+    /// Send `instructions`, which must succeed, and return the events they emitted in order: `emit!`
+    /// payloads from the log and `emit_cpi!` payloads from the event self-CPIs' instruction data, minus
+    /// its tag. Self-CPIs are only seen by the native entrypoint, so under `cargo test-sbf` this has the
+    /// log alone.
+    pub async fn process_for_events(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Vec<Vec<u8>> {
+        self.process_for_logs(instructions, signers)
+            .await
+            .iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .map(|data| BASE64.decode(data).unwrap())
+            .map(|data| match data.strip_prefix(&EVENT_IX_TAG_LE) {
+                Some(event) => event.to_vec(),
+                None => data,
+            })
+            .collect()
+    }
+
+    /// Send `instructions`, which must succeed, and return the transaction's log
+    pub async fn process_for_logs(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Vec<String> {
+        let transaction = self.transaction(instructions, signers).await;
+        let outcome = self.context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+        outcome.result.unwrap();
+        outcome.metadata.unwrap().log_messages
+    }
+}
@@ -0,0 +1,115 @@
+// The wallet cap timelock, driven by warping the bank clock: a proposal matures at exactly its
+// `execution_time`, and the cap it sets is what Token-2022 transfers are held to from then on.
+
+mod test_utils;
+
+use one_kx_hook::{HookError, WalletCapUpdateCanceled, WalletCapUpdateProposed, WalletCapUpdated};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use test_utils::{decode_event, hook_error, Harness, HookedMint, TIMELOCK_SECONDS, WALLET_CAP_RAW};
+
+const NEW_CAP: u64 = 2_000_000_000;
+
+/// Propose `new_cap` as the mint's governance and return the proposal event
+async fn propose(harness: &mut Harness, mint: &HookedMint, new_cap: u64, overwrite: bool) -> Vec<Vec<u8>> {
+    let governance = mint.governance.insecure_clone();
+    harness.process_for_events(&[mint.propose_wallet_cap(new_cap, overwrite)], &[&governance]).await
+}
+
+#[tokio::test]
+async fn a_cap_update_executes_at_exactly_its_execution_time() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let cranker = Keypair::new();
+
+    let events = propose(&mut harness, &mint, NEW_CAP, false).await;
+    let proposed = decode_event::<WalletCapUpdateProposed>(&events[0]).expect("proposal event");
+    assert_eq!(proposed.execution_time, proposed.proposed_at + i64::from(TIMELOCK_SECONDS));
+
+    // Right away, then one second short of the timelock
+    for now in [None, Some(proposed.execution_time - 1)] {
+        if let Some(now) = now {
+            harness.warp_to_timestamp(now).await;
+        }
+        let (result, _) = harness.process(&[mint.execute_wallet_cap_update(&cranker.pubkey())], &[&cranker]).await;
+        assert_eq!(result, Err(hook_error(HookError::TimelockNotExpired)));
+        assert_eq!(harness.config(&mint).await.wallet_cap_raw, WALLET_CAP_RAW);
+    }
+
+    harness.warp_to_timestamp(proposed.execution_time).await;
+    let execute = mint.execute_wallet_cap_update(&cranker.pubkey());
+    let events = harness.process_for_events(&[execute], &[&cranker]).await;
+    let updated = decode_event::<WalletCapUpdated>(&events[0]).expect("update event");
+    assert_eq!((updated.old_cap, updated.new_cap), (WALLET_CAP_RAW, NEW_CAP));
+    assert_eq!((updated.updated_at, updated.proposal_nonce), (proposed.execution_time, proposed.proposal_nonce));
+    assert_eq!(updated.executed_by, cranker.pubkey());
+    let config = harness.config(&mint).await;
+    assert_eq!(config.wallet_cap_raw, NEW_CAP);
+    assert!(config.pending_cap_update.is_none());
+
+    // Transfers are held to the new cap
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+    harness.transfer(&mint, &source, &destination, &owner, NEW_CAP).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &source, &destination, &owner, 1).await;
+    assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+}
+
+#[tokio::test]
+async fn a_canceled_cap_update_cannot_be_executed() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let governance = mint.governance.insecure_clone();
+    let cranker = Keypair::new();
+
+    let events = propose(&mut harness, &mint, NEW_CAP, false).await;
+    let proposed = decode_event::<WalletCapUpdateProposed>(&events[0]).expect("proposal event");
+    let events = harness.process_for_events(&[mint.cancel_wallet_cap_update()], &[&governance]).await;
+    let canceled = decode_event::<WalletCapUpdateCanceled>(&events[0]).expect("cancel event");
+    assert_eq!((canceled.canceled_cap, canceled.proposal_nonce), (NEW_CAP, proposed.proposal_nonce));
+
+    // Maturing doesn't bring it back
+    harness.warp_to_timestamp(proposed.execution_time).await;
+    let (result, _) = harness.process(&[mint.execute_wallet_cap_update(&cranker.pubkey())], &[&cranker]).await;
+    assert_eq!(result, Err(hook_error(HookError::NoPendingUpdate)));
+    assert_eq!(harness.config(&mint).await.wallet_cap_raw, WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn overwriting_a_proposal_restarts_its_timelock() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let governance = mint.governance.insecure_clone();
+    let cranker = Keypair::new();
+
+    let events = propose(&mut harness, &mint, NEW_CAP, false).await;
+    let first = decode_event::<WalletCapUpdateProposed>(&events[0]).expect("proposal event");
+
+    // A pending proposal is only replaced on purpose
+    let (result, _) = harness.process(&[mint.propose_wallet_cap(3_000_000_000, false)], &[&governance]).await;
+    assert_eq!(result, Err(hook_error(HookError::ProposalAlreadyPending)));
+
+    // Halfway through the first timelock, the overwrite cancels it and starts the clock over
+    let halfway = first.proposed_at + i64::from(TIMELOCK_SECONDS) / 2;
+    harness.warp_to_timestamp(halfway).await;
+    let events = propose(&mut harness, &mint, 3_000_000_000, true).await;
+    let canceled = decode_event::<WalletCapUpdateCanceled>(&events[0]).expect("cancel event");
+    assert_eq!((canceled.canceled_cap, canceled.proposal_nonce), (NEW_CAP, first.proposal_nonce));
+    let second = decode_event::<WalletCapUpdateProposed>(&events[1]).expect("proposal event");
+    assert_eq!(second.execution_time, halfway + i64::from(TIMELOCK_SECONDS));
+    assert_ne!(second.proposal_nonce, first.proposal_nonce);
+
+    // The first proposal's maturity no longer executes anything
+    harness.warp_to_timestamp(first.execution_time).await;
+    let (result, _) = harness.process(&[mint.execute_wallet_cap_update(&cranker.pubkey())], &[&cranker]).await;
+    assert_eq!(result, Err(hook_error(HookError::TimelockNotExpired)));
+
+    harness.warp_to_timestamp(second.execution_time).await;
+    let execute = mint.execute_wallet_cap_update(&cranker.pubkey());
+    let events = harness.process_for_events(&[execute], &[&cranker]).await;
+    let updated = decode_event::<WalletCapUpdated>(&events[0]).expect("update event");
+    assert_eq!((updated.new_cap, updated.proposal_nonce), (3_000_000_000, second.proposal_nonce));
+}
@@ -0,0 +1,87 @@
+// End-to-end transfers: Token-2022 resolves the hook's accounts from the meta list and invokes
+// `execute` on every `transfer_checked` of a hooked mint.
+
+mod test_utils;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::instruction::InstructionError;
+use one_kx_hook::HookError;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+use test_utils::{hook_error, Harness, WALLET_CAP_RAW};
+
+#[tokio::test]
+async fn transfers_under_the_cap_go_through() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), 0).await;
+
+    // The resolved instruction carries the meta list, the accounts it derives and the hook program
+    let transfer = harness.transfer_instruction(&mint, &source, &destination, &owner.pubkey(), 1).await;
+    assert!(transfer.accounts.iter().any(|meta| meta.pubkey == mint.extra_account_meta_list()));
+    assert!(transfer.accounts.iter().any(|meta| meta.pubkey == mint.config()));
+    assert!(transfer.accounts.iter().any(|meta| meta.pubkey == one_kx_hook::ID));
+
+    harness.transfer(&mint, &source, &destination, &owner, WALLET_CAP_RAW).await.0.unwrap();
+    assert_eq!(harness.balance(&source).await, 0);
+    assert_eq!(harness.balance(&destination).await, WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn transfers_over_the_cap_fail_with_the_hook_error() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+    let destination = harness.create_token_account(&mint, &Pubkey::new_unique(), WALLET_CAP_RAW - 1).await;
+
+    harness.transfer(&mint, &source, &destination, &owner, 1).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &source, &destination, &owner, 1).await;
+    // Token-2022 passes the hook's custom code through unchanged
+    let code = ERROR_CODE_OFFSET + HookError::WalletCapExceeded as u32;
+    assert_eq!(result, Err(TransactionError::InstructionError(0, InstructionError::Custom(code))));
+    assert_eq!(harness.balance(&destination).await, WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn the_dev_wallet_receives_past_the_cap() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let owner = Keypair::new();
+    let source = harness.create_token_account(&mint, &owner.pubkey(), WALLET_CAP_RAW).await;
+    let dev_wallet_account = harness.create_token_account(&mint, &mint.dev_wallet, WALLET_CAP_RAW).await;
+
+    harness.transfer(&mint, &source, &dev_wallet_account, &owner, WALLET_CAP_RAW).await.0.unwrap();
+    assert_eq!(harness.balance(&dev_wallet_account).await, 2 * WALLET_CAP_RAW);
+}
+
+#[tokio::test]
+async fn each_mint_is_checked_against_its_own_config() {
+    let mut harness = Harness::new().await;
+    let first = harness.create_hooked_mint().await;
+    let second = harness.create_hooked_mint().await;
+    assert_ne!(first.config(), second.config());
+    assert_eq!(harness.config(&first).await.dev_wallets[0], first.dev_wallet);
+    assert_eq!(harness.config(&second).await.dev_wallets[0], second.dev_wallet);
+
+    // A holder at the cap of one mint can still receive the other
+    let holder = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let first_source = harness.create_token_account(&first, &owner.pubkey(), 1).await;
+    let second_source = harness.create_token_account(&second, &owner.pubkey(), 2).await;
+    let first_destination = harness.create_token_account(&first, &holder, WALLET_CAP_RAW).await;
+    let second_destination = harness.create_token_account(&second, &holder, 0).await;
+    let (result, _) = harness.transfer(&first, &first_source, &first_destination, &owner, 1).await;
+    assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+    harness.transfer(&second, &second_source, &second_destination, &owner, 1).await.0.unwrap();
+
+    // And one mint's dev wallet has no exemption on the other
+    let first_dev_wallet = harness.create_token_account(&second, &first.dev_wallet, WALLET_CAP_RAW).await;
+    let (result, _) = harness.transfer(&second, &second_source, &first_dev_wallet, &owner, 1).await;
+    assert_eq!(result, Err(hook_error(HookError::WalletCapExceeded)));
+}
@@ -0,0 +1,119 @@
+// The trusted program exemption through Token-2022: the mock staking program stands in for an AMM whose
+// pool PDA owns a vault. Token-2022 can't pass the hook the pool's account, so the exemption only applies
+// once `register_trusted_owner` has recorded the pool, whose record the meta list resolves from the
+// destination's owner.
+
+mod test_utils;
+
+use one_kx_hook::{accounts, instruction, HookError, TrustedOwner};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{
+    event_authority, hook_error, mock_staking, pda, program_instruction, Harness, HookedMint, WALLET_CAP_RAW,
+};
+
+fn trusted_owner(mint: &HookedMint, owner: &Pubkey) -> Pubkey {
+    pda(&[b"trusted-owner", mint.address.as_ref(), owner.as_ref()])
+}
+
+fn register_trusted_owner(payer: Pubkey, mint: &HookedMint, owner: &Pubkey, seeds: Vec<Vec<u8>>) -> Instruction {
+    program_instruction(
+        accounts::RegisterTrustedOwner {
+            payer,
+            config: mint.config(),
+            trusted_owner: trusted_owner(mint, owner),
+            owner: *owner,
+            mint: mint.address,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::RegisterTrustedOwner { seeds },
+    )
+}
+
+/// Trust the mock program in slot 0 and open its pool, returning a vault the pool owns
+async fn pool_vault(harness: &mut Harness, mint: &HookedMint) -> Pubkey {
+    let payer = harness.context.payer.pubkey();
+    let setup = [
+        mint.governance_instruction(instruction::SetTrustedProgram { index: 0, program_id: mock_staking::ID }),
+        mock_staking::open_pool(&payer, &mint.address),
+    ];
+    let governance = mint.governance.insecure_clone();
+    harness.process(&setup, &[&governance]).await.0.unwrap();
+    harness.create_token_account(mint, &mock_staking::pool(&mint.address), 0).await
+}
+
+#[tokio::test]
+async fn registered_pool_authorities_receive_past_the_cap() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let vault = pool_vault(&mut harness, &mint).await;
+    let pool = mock_staking::pool(&mint.address);
+    let (alice, bob) = (Keypair::new(), Keypair::new());
+    let alice_wallet = harness.create_token_account(&mint, &alice.pubkey(), WALLET_CAP_RAW).await;
+    let bob_wallet = harness.create_token_account(&mint, &bob.pubkey(), WALLET_CAP_RAW).await;
+
+    // Unregistered, the vault is held to the cap like any other account
+    harness.transfer(&mint, &alice_wallet, &vault, &alice, WALLET_CAP_RAW).await.0.unwrap();
+    let (result, _) = harness.transfer(&mint, &bob_wallet, &vault, &bob, 1).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::WalletCapExceeded));
+
+    let payer = harness.context.payer.pubkey();
+    let register = register_trusted_owner(payer, &mint, &pool, mock_staking::pool_seeds(&mint.address));
+    harness.process(&[register], &[]).await.0.unwrap();
+    let record = harness.context.banks_client.get_account(trusted_owner(&mint, &pool)).await.unwrap().unwrap();
+    let record: TrustedOwner = anchor_lang::AccountDeserialize::try_deserialize(&mut record.data.as_slice()).unwrap();
+    assert_eq!((record.mint, record.owner, record.program), (mint.address, pool, mock_staking::ID));
+
+    // Token-2022 now resolves the pool's record for the hook, which exempts the vault
+    harness.transfer(&mint, &bob_wallet, &vault, &bob, WALLET_CAP_RAW).await.0.unwrap();
+    assert_eq!(harness.balance(&vault).await, 2 * WALLET_CAP_RAW);
+
+    // Once governance stops trusting the program, the record no longer counts
+    let governance = mint.governance.insecure_clone();
+    let untrust =
+        mint.governance_instruction(instruction::SetTrustedProgram { index: 0, program_id: Pubkey::default() });
+    harness.process(&[untrust], &[&governance]).await.0.unwrap();
+    let carol = Keypair::new();
+    let carol_wallet = harness.create_token_account(&mint, &carol.pubkey(), 1).await;
+    let (result, _) = harness.transfer(&mint, &carol_wallet, &vault, &carol, 1).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::WalletCapExceeded));
+}
+
+#[tokio::test]
+async fn only_pdas_of_trusted_programs_register() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let payer = harness.context.payer.pubkey();
+    let not_trusted = hook_error(HookError::NotTrustedProgramOwner);
+
+    // The pool's account exists, but its program isn't trusted yet
+    let pool = mock_staking::pool(&mint.address);
+    harness.process(&[mock_staking::open_pool(&payer, &mint.address)], &[]).await.0.unwrap();
+    let seeds = mock_staking::pool_seeds(&mint.address);
+    let register = register_trusted_owner(payer, &mint, &pool, seeds.clone());
+    assert_eq!(harness.process(&[register], &[]).await.0.unwrap_err(), not_trusted);
+
+    // Once it is, neither a wallet nor a PDA of the program with no account behind it qualifies
+    let governance = mint.governance.insecure_clone();
+    let trust = mint.governance_instruction(instruction::SetTrustedProgram { index: 0, program_id: mock_staking::ID });
+    harness.process(&[trust], &[&governance]).await.0.unwrap();
+    let wallet = Keypair::new().pubkey();
+    let (stray_pda, stray_bump) = Pubkey::find_program_address(&[b"pool"], &mock_staking::ID);
+    let stray_seeds = vec![b"pool".to_vec(), vec![stray_bump]];
+    for (owner, seeds) in [(wallet, vec![]), (stray_pda, stray_seeds)] {
+        let register = register_trusted_owner(payer, &mint, &owner, seeds);
+        assert_eq!(harness.process(&[register], &[]).await.0.unwrap_err(), not_trusted);
+    }
+
+    // Nor does the pool itself with seeds that derive some other address
+    let other_seeds = mock_staking::pool_seeds(&Pubkey::new_unique());
+    let register = register_trusted_owner(payer, &mint, &pool, other_seeds);
+    assert_eq!(harness.process(&[register], &[]).await.0.unwrap_err(), not_trusted);
+    harness.process(&[register_trusted_owner(payer, &mint, &pool, seeds)], &[]).await.0.unwrap();
+}
@@ -0,0 +1,84 @@
+// Strict wallet owners: with `require_wallet_owners` on, tokens only go to accounts whose owner is a wallet,
+// or to exempt ones. A token account owned by a program-derived address is refused until governance registers
+// it in the exempt registry, the way pool vaults are.
+
+mod test_utils;
+
+use one_kx_hook::{accounts, instruction, ExemptKind, HookError, WalletOwnersRequiredUpdated};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+use test_utils::{decode_event, event_authority, hook_error, pda, program_instruction, Harness, HookedMint};
+
+async fn require_wallet_owners(harness: &mut Harness, mint: &HookedMint) -> WalletOwnersRequiredUpdated {
+    let governance = mint.governance.insecure_clone();
+    let enable = mint.governance_instruction(instruction::SetRequireWalletOwners { enabled: true });
+    let events = harness.process_for_events(&[enable], &[&governance]).await;
+    events.iter().find_map(|event| decode_event::<WalletOwnersRequiredUpdated>(event)).expect("update event")
+}
+
+/// Create the exempt registry and register `token_account` in it as a pool vault
+async fn exempt(harness: &mut Harness, mint: &HookedMint, token_account: Pubkey) {
+    let payer = harness.context.payer.pubkey();
+    let exempt_registry = pda(&[b"exempt-registry", mint.address.as_ref()]);
+    let init_exempt_registry = program_instruction(
+        accounts::InitExemptRegistry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+        },
+        instruction::InitExemptRegistry { rent_recipient: payer },
+    );
+    let add = program_instruction(
+        accounts::AddExemptEntry {
+            payer,
+            config: mint.config(),
+            governance_authority: mint.governance.pubkey(),
+            exempt_registry,
+            mint: mint.address,
+            system_program: system_program::ID,
+            approval: None,
+            event_authority: event_authority(),
+            program: one_kx_hook::ID,
+        },
+        instruction::AddExemptEntry { token_account, kind: ExemptKind::PoolVault, expires_at: None },
+    );
+    let governance = mint.governance.insecure_clone();
+    harness.process(&[init_exempt_registry, add], &[&governance]).await.0.unwrap();
+}
+
+#[tokio::test]
+async fn strict_mode_refuses_program_owned_destinations() {
+    let mut harness = Harness::new().await;
+    let mint = harness.create_hooked_mint().await;
+    let holder = Keypair::new();
+    let source = harness.create_token_account(&mint, &holder.pubkey(), 1_000).await;
+    let pool_authority = Pubkey::find_program_address(&[b"pool-authority"], &Pubkey::new_unique()).0;
+    let program_owned = harness.create_token_account(&mint, &pool_authority, 0).await;
+    let wallet_owned = harness.create_token_account(&mint, &Keypair::new().pubkey(), 0).await;
+
+    // Off by default: a PDA-owned account receives like any other
+    assert!(!harness.config(&mint).await.require_wallet_owners);
+    harness.transfer(&mint, &source, &program_owned, &holder, 100).await.0.unwrap();
+
+    let updated = require_wallet_owners(&mut harness, &mint).await;
+    assert!(updated.enabled);
+    assert_eq!(updated.governance_authority, mint.governance.pubkey());
+    assert!(harness.config(&mint).await.require_wallet_owners);
+
+    let (result, _) = harness.transfer(&mint, &source, &program_owned, &holder, 100).await;
+    assert_eq!(result.unwrap_err(), hook_error(HookError::ProgramOwnedDestination));
+    // Wallets are unaffected
+    harness.transfer(&mint, &source, &wallet_owned, &holder, 100).await.0.unwrap();
+
+    // Once exempt, the account receives again
+    exempt(&mut harness, &mint, program_owned).await;
+    harness.transfer(&mint, &source, &program_owned, &holder, 100).await.0.unwrap();
+    assert_eq!(harness.balance(&program_owned).await, 200);
+}
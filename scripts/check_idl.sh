@@ -0,0 +1,84 @@
+#!/bin/bash
+
+# IDL Snapshot Check
+# Builds the program's IDL (through the `idl-build` feature) and compares it against the committed
+# idl/one_kx_hook.json, which the TypeScript client and frontends are generated from. Renaming an
+# account in a `#[derive(Accounts)]` struct or changing an argument, event or error breaks them
+# silently, so any difference fails the check.
+#
+# Usage:
+#   ./scripts/check_idl.sh            # fail with a diff if the IDL changed
+#   ./scripts/check_idl.sh --update   # rewrite the snapshot after an intentional interface change
+#
+# An intentional change commits the regenerated snapshot together with the code that caused it.
+
+set -euo pipefail
+
+# Colors for output
+RED='\033[0;31m'
+GREEN='\033[0;32m'
+BLUE='\033[0;34m'
+NC='\033[0m' # No Color
+
+# Configuration
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+PROJECT_DIR="$(dirname "$SCRIPT_DIR")"
+PROGRAM_DIR="$PROJECT_DIR/programs/1kx_hook"
+SNAPSHOT="$PROJECT_DIR/idl/one_kx_hook.json"
+
+# Status functions
+status_info() {
+    echo -e "${BLUE}ℹ${NC} $1"
+}
+
+status_ok() {
+    echo -e "${GREEN}✓${NC} $1"
+}
+
+status_error() {
+    echo -e "${RED}✗${NC} $1"
+}
+
+update=false
+case "${1:-}" in
+    "") ;;
+    --update) update=true ;;
+    *)
+        status_error "Unknown argument: $1 (expected --update)"
+        exit 2
+        ;;
+esac
+
+if ! command -v anchor >/dev/null 2>&1; then
+    status_error "anchor CLI not found; install anchor-cli 0.29.0"
+    exit 2
+fi
+
+generated="$(mktemp)"
+trap 'rm -f "$generated"' EXIT
+
+status_info "Building the IDL..."
+(cd "$PROGRAM_DIR" && anchor idl build -o "$generated")
+
+if [ "$update" = true ]; then
+    mkdir -p "$(dirname "$SNAPSHOT")"
+    cp "$generated" "$SNAPSHOT"
+    status_ok "Snapshot updated: idl/one_kx_hook.json"
+    exit 0
+fi
+
+if [ ! -f "$SNAPSHOT" ]; then
+    status_error "No IDL snapshot at idl/one_kx_hook.json"
+    echo "Generate it with ./scripts/check_idl.sh --update and commit it."
+    exit 1
+fi
+
+if diff -u --label "idl/one_kx_hook.json (committed)" --label "idl/one_kx_hook.json (built)" \
+    "$SNAPSHOT" "$generated"; then
+    status_ok "IDL matches the snapshot"
+else
+    echo ""
+    status_error "The IDL changed: instructions, accounts, arguments, events or errors differ from the snapshot"
+    echo "If the change is intentional, run ./scripts/check_idl.sh --update and commit the snapshot with it."
+    exit 1
+fi
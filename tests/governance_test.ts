@@ -6,6 +6,7 @@ import { Program } from "@coral-xyz/anchor";
 import { OneKxHook } from "../target/types/one_kx_hook";
 import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
 
 describe("Governance", () => {
   const provider = anchor.AnchorProvider.env();
@@ -15,6 +16,7 @@ describe("Governance", () => {
   
   let mint: PublicKey;
   let config: PublicKey;
+  let governanceLog: PublicKey;
   let governanceAuthority: Keypair;
   let devWallet: Keypair;
 
@@ -35,14 +37,31 @@ describe("Governance", () => {
       [Buffer.from("config"), mint.toBuffer()],
       program.programId
     );
+    [governanceLog] = PublicKey.findProgramAddressSync(
+      [Buffer.from("gov-log"), mint.toBuffer()],
+      program.programId
+    );
   });
 
   it("Initializes with governance authority", async () => {
     await program.methods
-      .initialize(devWallet.publicKey, governanceAuthority.publicKey)
+      .initialize(devWallet.publicKey, governanceAuthority.publicKey, { raw: {} }, 48 * 60 * 60, new anchor.BN(0), 0, false)
+      .accounts({
+        payer: governanceAuthority.publicKey,
+        config,
+        mint,
+        mintAuthority: governanceAuthority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([governanceAuthority])
+      .rpc();
+
+    await program.methods
+      .initGovernanceLog()
       .accounts({
         payer: governanceAuthority.publicKey,
         config,
+        governanceLog,
         mint,
         systemProgram: SystemProgram.programId,
       })
@@ -54,17 +73,20 @@ describe("Governance", () => {
       governanceAuthority.publicKey.toString()
     );
     expect(configAccount.walletCapRaw.toString()).to.equal("5000000000");
+    expect(configAccount.timelockSeconds).to.equal(48 * 60 * 60);
   });
 
   it("Proposes wallet cap update", async () => {
     const newCap = new anchor.BN(10_000_000_000); // 10 tokens
+    const memoHash = Array.from(createHash("sha256").update("https://forum.example/proposals/1").digest());
 
     const tx = await program.methods
-      .proposeWalletCapUpdate(newCap)
+      .proposeWalletCapUpdate(newCap, false, memoHash)
       .accounts({
         config,
         governanceAuthority: governanceAuthority.publicKey,
         mint,
+        governanceLog,
       })
       .signers([governanceAuthority])
       .rpc();
@@ -72,6 +94,10 @@ describe("Governance", () => {
     const configAccount = await program.account.hookConfig.fetch(config);
     expect(configAccount.pendingCapUpdate).to.not.be.null;
     expect(configAccount.pendingCapUpdate.newCap.toString()).to.equal("10000000000");
+    expect(configAccount.pendingCapUpdate.proposedBy.toString()).to.equal(
+      governanceAuthority.publicKey.toString()
+    );
+    expect(configAccount.pendingCapUpdate.memoHash).to.deep.equal(memoHash);
   });
 
   it("Fails to execute before timelock expires", async () => {
@@ -80,10 +106,10 @@ describe("Governance", () => {
         .executeWalletCapUpdate()
         .accounts({
           config,
-          governanceAuthority: governanceAuthority.publicKey,
+          executor: provider.wallet.publicKey,
           mint,
+          governanceLog,
         })
-        .signers([governanceAuthority])
         .rpc();
       
       expect.fail("Should have failed due to timelock");
@@ -99,6 +125,7 @@ describe("Governance", () => {
         config,
         governanceAuthority: governanceAuthority.publicKey,
         mint,
+        governanceLog,
       })
       .signers([governanceAuthority])
       .rpc();
@@ -118,11 +145,12 @@ describe("Governance", () => {
 
     try {
       await program.methods
-        .proposeWalletCapUpdate(new anchor.BN(8_000_000_000))
+        .proposeWalletCapUpdate(new anchor.BN(8_000_000_000), false, new Array(32).fill(0))
         .accounts({
           config,
           governanceAuthority: unauthorizedSigner.publicKey,
           mint,
+          governanceLog,
         })
         .signers([unauthorizedSigner])
         .rpc();
@@ -133,11 +161,11 @@ describe("Governance", () => {
     }
   });
 
-  it("Updates governance authority", async () => {
+  it("Transfers governance authority in two steps", async () => {
     const newGovernanceAuthority = Keypair.generate();
 
     await program.methods
-      .updateGovernanceAuthority(newGovernanceAuthority.publicKey)
+      .proposeGovernanceTransfer(newGovernanceAuthority.publicKey)
       .accounts({
         config,
         governanceAuthority: governanceAuthority.publicKey,
@@ -146,21 +174,38 @@ describe("Governance", () => {
       .signers([governanceAuthority])
       .rpc();
 
-    const configAccount = await program.account.hookConfig.fetch(config);
+    let configAccount = await program.account.hookConfig.fetch(config);
+    expect(configAccount.governanceAuthority.toString()).to.equal(
+      governanceAuthority.publicKey.toString()
+    );
+
+    await program.methods
+      .acceptGovernanceAuthority()
+      .accounts({
+        config,
+        newAuthority: newGovernanceAuthority.publicKey,
+        mint,
+      })
+      .signers([newGovernanceAuthority])
+      .rpc();
+
+    configAccount = await program.account.hookConfig.fetch(config);
     expect(configAccount.governanceAuthority.toString()).to.equal(
       newGovernanceAuthority.publicKey.toString()
     );
+    expect(configAccount.pendingGovernanceAuthority).to.be.null;
   });
 
   it("Validates cap ranges", async () => {
     // Test with cap too high
     try {
       await program.methods
-        .proposeWalletCapUpdate(new anchor.BN(200_000_000_000)) // 200 tokens - over limit
+        .proposeWalletCapUpdate(new anchor.BN(200_000_000_000), false, new Array(32).fill(0)) // 200 tokens - over limit
         .accounts({
           config,
           governanceAuthority: governanceAuthority.publicKey,
           mint,
+          governanceLog,
         })
         .signers([governanceAuthority])
         .rpc();
@@ -173,11 +218,12 @@ describe("Governance", () => {
     // Test with zero cap
     try {
       await program.methods
-        .proposeWalletCapUpdate(new anchor.BN(0))
+        .proposeWalletCapUpdate(new anchor.BN(0), false, new Array(32).fill(0))
         .accounts({
           config,
           governanceAuthority: governanceAuthority.publicKey,
           mint,
+          governanceLog,
         })
         .signers([governanceAuthority])
         .rpc();